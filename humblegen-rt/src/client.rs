@@ -0,0 +1,239 @@
+//! `GEN` Generic parts of the humblegen HTTP service client implementation, based on [`reqwest`](https://docs.rs/reqwest).
+
+pub use crate::handler::RawResponse;
+use crate::service_protocol::{ErrorResponse, ErrorResponseKind, RuntimeError, ServiceError};
+
+use core::fmt::Display;
+use futures::StreamExt;
+use reqwest::Url;
+
+/// The response of a client method for an endpoint returning `stream[T]`.
+///
+/// Items are yielded as soon as they are received. After a transport error, the stream ends.
+pub type StreamResponse<T> = futures::stream::BoxStream<'static, Result<T, ClientError>>;
+
+/// An error returned by a generated client.
+///
+/// Errors reported by the service are mapped back to the types of the service protocol,
+/// see `humblespec/service_protocol.md`.
+#[derive(Debug)]
+pub enum ClientError {
+    /// The service could not fulfill the request, see `service_protocol::ServiceError`.
+    Service(ServiceError),
+    /// The service runtime rejected the request, e.g. because a route param was invalid.
+    Runtime(RuntimeError),
+    /// The base URL passed to the client cannot be used to build request URLs.
+    InvalidBaseUrl(String),
+    /// The request could not be serialized.
+    Serialize(String),
+    /// The request could not be sent or the response could not be received.
+    Transport(reqwest::Error),
+    /// The response of a successful request could not be deserialized.
+    Deserialize(serde_json::Error),
+    /// The service responded with an error status, but without an "Error Response" body.
+    UnexpectedResponse { status: u16, body: String },
+}
+
+impl Display for ClientError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ClientError::Service(e) => write!(f, "service error: {:?}", e),
+            ClientError::Runtime(e) => write!(f, "service runtime error: {:?}", e),
+            ClientError::InvalidBaseUrl(e) => write!(f, "invalid base url: {}", e),
+            ClientError::Serialize(e) => write!(f, "cannot serialize request: {}", e),
+            ClientError::Transport(e) => write!(f, "transport error: {}", e),
+            ClientError::Deserialize(e) => write!(f, "cannot deserialize response: {}", e),
+            ClientError::UnexpectedResponse { status, body } => {
+                write!(f, "unexpected response with status {}: {}", status, body)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ClientError {}
+
+/// Parse the URL at which a service is mounted, e.g. `http://localhost:3000/api`.
+pub fn parse_base_url(base_url: &str) -> Result<Url, ClientError> {
+    let url = Url::parse(base_url).map_err(|e| ClientError::InvalidBaseUrl(e.to_string()))?;
+    if url.cannot_be_a_base() {
+        return Err(ClientError::InvalidBaseUrl(format!(
+            "{} cannot be a base",
+            base_url
+        )));
+    }
+    Ok(url)
+}
+
+/// Build the URL of a route by appending the route's path `segments` to `base_url`.
+pub fn build_url(base_url: &Url, segments: &[String], query: Option<String>) -> Url {
+    let mut url = base_url.clone();
+    url.path_segments_mut()
+        .expect("base url is checked by parse_base_url")
+        .pop_if_empty()
+        .extend(segments);
+    url.set_query(query.as_deref());
+    url
+}
+
+/// Helper function used by generated code to serialize a user defined type to the URL query.
+pub fn ser_query_serde_urlencoded<T: serde::Serialize>(query: &T) -> Result<String, ClientError> {
+    serde_urlencoded::to_string(query).map_err(|e| ClientError::Serialize(e.to_string()))
+}
+
+/// Helper function used by generated code to serialize a primitive type to the URL query.
+pub fn ser_query_primitive<T: Display>(query: &T) -> Result<String, ClientError> {
+    Ok(query.to_string())
+}
+
+/// Helper function used by generated code to serialize query parameters to the URL query.
+/// Parameters without a value are left out, `None` is returned if no parameter has a value.
+pub fn ser_query_params(params: &[(&str, Option<String>)]) -> Result<Option<String>, ClientError> {
+    let params: Vec<_> = params
+        .iter()
+        .filter_map(|(name, value)| value.as_ref().map(|value| (name, value)))
+        .collect();
+    if params.is_empty() {
+        return Ok(None);
+    }
+    serde_urlencoded::to_string(params)
+        .map(Some)
+        .map_err(|e| ClientError::Serialize(e.to_string()))
+}
+
+/// Send `request` and deserialize the response into `T`.
+///
+/// Responses with an error status are decoded as "Error Response"s.
+pub async fn send_request<T: serde::de::DeserializeOwned>(
+    request: reqwest::RequestBuilder,
+) -> Result<T, ClientError> {
+    let response = request.send().await.map_err(ClientError::Transport)?;
+    let status = response.status();
+    let body = response.bytes().await.map_err(ClientError::Transport)?;
+
+    if status.is_success() {
+        serde_json::from_slice(&body).map_err(ClientError::Deserialize)
+    } else {
+        Err(error_response_to_client_error(status, &body))
+    }
+}
+
+/// Send `request` to an endpoint returning `result[T][E]` and declared with
+/// `@error_status(...)`, deserializing the response into the endpoint's result.
+///
+/// Responses with `error_status` contain the `Err` of the result, unless they cannot be
+/// deserialized into it. Those and responses with other error statuses are decoded as
+/// "Error Response"s.
+pub async fn send_result_request<T, E>(
+    error_status: u16,
+    request: reqwest::RequestBuilder,
+) -> Result<Result<T, E>, ClientError>
+where
+    T: serde::de::DeserializeOwned,
+    E: serde::de::DeserializeOwned,
+{
+    let response = request.send().await.map_err(ClientError::Transport)?;
+    let status = response.status();
+    let body = response.bytes().await.map_err(ClientError::Transport)?;
+
+    if status.is_success() {
+        serde_json::from_slice(&body).map_err(ClientError::Deserialize)
+    } else if status.as_u16() == error_status {
+        // runtime errors like an unknown route may use the same status
+        serde_json::from_slice::<Result<T, E>>(&body)
+            .ok()
+            .filter(Result::is_err)
+            .ok_or_else(|| error_response_to_client_error(status, &body))
+    } else {
+        Err(error_response_to_client_error(status, &body))
+    }
+}
+
+/// Send `request` to an endpoint returning `raw bytes`.
+///
+/// Responses with an error status are decoded as "Error Response"s.
+pub async fn send_raw_request(
+    request: reqwest::RequestBuilder,
+) -> Result<RawResponse, ClientError> {
+    let response = request.send().await.map_err(ClientError::Transport)?;
+    let status = response.status();
+    let content_type = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or("application/octet-stream")
+        .to_owned();
+    let body = response.bytes().await.map_err(ClientError::Transport)?;
+
+    if status.is_success() {
+        Ok(RawResponse {
+            content_type,
+            body: body.to_vec(),
+        })
+    } else {
+        Err(error_response_to_client_error(status, &body))
+    }
+}
+
+/// Send `request` to an endpoint returning `stream[T]`, deserializing the items of the
+/// newline-delimited JSON response while they are received.
+///
+/// Responses with an error status are decoded as "Error Response"s.
+pub async fn send_stream_request<T>(
+    request: reqwest::RequestBuilder,
+) -> Result<StreamResponse<T>, ClientError>
+where
+    T: serde::de::DeserializeOwned + Send + 'static,
+{
+    let response = request.send().await.map_err(ClientError::Transport)?;
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.bytes().await.map_err(ClientError::Transport)?;
+        return Err(error_response_to_client_error(status, &body));
+    }
+
+    // `None` once the response has been read completely or reading it failed
+    let state: (Option<reqwest::Response>, Vec<u8>) = (Some(response), Vec::new());
+    let items = futures::stream::unfold(state, |(mut response, mut buffer)| async move {
+        loop {
+            if let Some(end) = buffer.iter().position(|b| *b == b'\n') {
+                let line: Vec<u8> = buffer.drain(..=end).collect();
+                if line.iter().all(u8::is_ascii_whitespace) {
+                    continue;
+                }
+                let item = serde_json::from_slice(&line).map_err(ClientError::Deserialize);
+                return Some((item, (response, buffer)));
+            }
+            match response.as_mut()?.chunk().await {
+                Ok(Some(chunk)) => buffer.extend_from_slice(&chunk),
+                Ok(None) => {
+                    response = None;
+                    if buffer.iter().all(u8::is_ascii_whitespace) {
+                        return None;
+                    }
+                    // the last item is not terminated by a newline
+                    let item = serde_json::from_slice(&buffer).map_err(ClientError::Deserialize);
+                    return Some((item, (response, Vec::new())));
+                }
+                Err(e) => return Some((Err(ClientError::Transport(e)), (None, Vec::new()))),
+            }
+        }
+    });
+    Ok(items.boxed())
+}
+
+fn error_response_to_client_error(status: reqwest::StatusCode, body: &[u8]) -> ClientError {
+    match serde_json::from_slice::<ErrorResponse>(body) {
+        Ok(ErrorResponse {
+            kind: ErrorResponseKind::Service(e),
+            ..
+        }) => ClientError::Service(e),
+        Ok(ErrorResponse {
+            kind: ErrorResponseKind::Runtime(e),
+            ..
+        }) => ClientError::Runtime(e),
+        Err(_) => ClientError::UnexpectedResponse {
+            status: status.as_u16(),
+            body: String::from_utf8_lossy(body).into_owned(),
+        },
+    }
+}
@@ -5,6 +5,74 @@ use core::fmt::Display;
 /// The response type returned by implementors of a humblegen service trait function.
 pub type HandlerResponse<T> = Result<T, ServiceError>;
 
+/// The response of a handler trait function for an endpoint returning `raw bytes`.
+///
+/// The `body` is sent verbatim with the given `content_type`, e.g. `image/png`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RawResponse {
+    pub content_type: String,
+    pub body: Vec<u8>,
+}
+
+/// The response of a handler trait function for an endpoint returning `stream[T]`.
+///
+/// The items are sent as newline-delimited JSON while the stream produces them.
+/// Any `Stream` can be turned into a `StreamResponse` using `futures::StreamExt::boxed`.
+pub type StreamResponse<T> = futures::stream::BoxStream<'static, T>;
+
+/// The response of a handler trait function for a `WS` endpoint, a pair of the messages
+/// pushed to the client and a sink for the messages the client sends.
+///
+/// Each `outgoing` message is sent as a JSON text message, the connection is closed once the
+/// stream ends. Only available with the `websocket` feature.
+#[cfg(feature = "websocket")]
+pub struct WebSocketResponse<T> {
+    pub outgoing: StreamResponse<T>,
+    pub incoming: WebSocketSink,
+}
+
+/// A sink for the text messages that the client of a `WS` endpoint sends.
+#[cfg(feature = "websocket")]
+pub type WebSocketSink = std::pin::Pin<
+    Box<dyn futures::Sink<String, Error = Box<dyn std::error::Error + Send + Sync>> + Send>,
+>;
+
+#[cfg(feature = "websocket")]
+impl<T> WebSocketResponse<T> {
+    /// Push the messages produced by `outgoing` to the client, discarding the messages the
+    /// client sends.
+    pub fn new(outgoing: impl futures::Stream<Item = T> + Send + 'static) -> Self {
+        use futures::{SinkExt, StreamExt};
+
+        Self {
+            outgoing: outgoing.boxed(),
+            incoming: Box::pin(futures::sink::drain().sink_map_err(|never| match never {})),
+        }
+    }
+
+    /// Feed the text messages the client sends into `incoming`.
+    pub fn with_incoming<S>(mut self, incoming: S) -> Self
+    where
+        S: futures::Sink<String> + Send + 'static,
+        S::Error: std::error::Error + Send + Sync + 'static,
+    {
+        use futures::SinkExt;
+
+        self.incoming = Box::pin(incoming.sink_map_err(|e| e.into()));
+        self
+    }
+}
+
+/// The result of the `intercept_handler_pre` function of a humblegen service trait.
+#[derive(Debug)]
+pub enum InterceptOutcome<C> {
+    /// Deserialize the request and invoke the handler trait function with the context `C`.
+    Continue(C),
+    /// Send this response without invoking the handler trait function, e.g. a redirect or a
+    /// cached `304 Not Modified`.
+    Respond(hyper::Response<hyper::Body>),
+}
+
 /// A service-level error.
 ///
 /// This type is returned by implementors of a humblegen service trait function
@@ -16,6 +84,15 @@ pub enum ServiceError {
     Authentication,
     Authorization,
     Internal(Box<dyn std::error::Error + Send + Sync>),
+    /// An error with a status code and a machine-readable `code` chosen by the handler,
+    /// e.g. `429` and `"rate_limited"`.
+    ///
+    /// `status` must be a 4XX or 5XX status code, otherwise 500 is sent.
+    Custom {
+        status: u16,
+        code: String,
+        message: String,
+    },
 }
 
 impl Display for ServiceError {
@@ -24,6 +101,11 @@ impl Display for ServiceError {
             ServiceError::Authentication => write!(f, "authentication error"),
             ServiceError::Authorization => write!(f, "not authorized"),
             ServiceError::Internal(e) => write!(f, "internal server error: {:?}", e),
+            ServiceError::Custom {
+                status,
+                code,
+                message,
+            } => write!(f, "{} ({}): {}", code, status, message),
         }
     }
 }
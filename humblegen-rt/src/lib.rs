@@ -6,6 +6,8 @@
 //! - `SERVER` = within the `server` module
 //! - `HANDLER` = from the handler implementation
 
+#[cfg(feature = "client")]
+pub mod client;
 pub mod serialization_helpers;
 pub use serialization_helpers as deser_helpers; // compat
 pub mod handler;
@@ -14,13 +16,27 @@ pub mod server;
 pub mod service_protocol;
 
 pub extern crate anyhow;
+#[cfg(feature = "axum-router")]
+pub extern crate axum;
 pub extern crate chrono;
 pub extern crate downcast_rs;
+pub extern crate futures;
 pub extern crate hyper;
+#[cfg(feature = "metrics")]
+pub extern crate prometheus;
 pub extern crate regex;
+#[cfg(feature = "client")]
+pub extern crate reqwest;
+pub extern crate rust_decimal;
+pub extern crate serde_json;
 pub extern crate tokio;
+#[cfg(feature = "websocket")]
+pub extern crate tokio_tungstenite;
+#[cfg(feature = "tls")]
+pub use tokio_rustls::rustls;
 pub extern crate tracing;
 pub extern crate tracing_futures;
+pub extern crate url;
 pub extern crate uuid;
 
 pub use async_trait_with_sync::async_trait;
@@ -6,8 +6,16 @@
 //! - `.matches_input(i(` must return true
 //!
 //! The `GetResult` contains a reference to the matching entry.
+//!
+//! `RegexSetMap::new` rejects entries that could both be candidates for the same `s` and `i`,
+//! so that lookups are only ambiguous if that check has to give up.
 
 use core::fmt;
+use regex_automata::dfa::{dense, Automaton, StartKind};
+use regex_automata::util::primitives::StateID;
+use regex_automata::util::start;
+use regex_automata::{Anchored, MatchKind};
+use std::collections::{HashSet, VecDeque};
 
 /// Refer to module-level docs.
 pub struct RegexSetMap<I, T: Entry<I>> {
@@ -20,6 +28,16 @@ pub struct RegexSetMap<I, T: Entry<I>> {
 pub trait Entry<I> {
     fn regex(&self) -> &regex::Regex;
     fn matches_input(&self, i: &I) -> bool;
+
+    /// Whether some input `i` is matched by both `self` and `other`.
+    fn overlaps_input(&self, _other: &Self) -> bool {
+        true
+    }
+
+    /// Describes the entry in errors.
+    fn describe(&self) -> String {
+        self.regex().as_str().to_owned()
+    }
 }
 
 impl<I, T: Entry<I>> Entry<I> for (regex::Regex, T) {
@@ -29,6 +47,38 @@ impl<I, T: Entry<I>> Entry<I> for (regex::Regex, T) {
     fn matches_input(&self, i: &I) -> bool {
         self.1.matches_input(i)
     }
+    fn overlaps_input(&self, other: &Self) -> bool {
+        self.1.overlaps_input(&other.1)
+    }
+}
+
+/// Error of `RegexSetMap::new`.
+#[derive(Debug)]
+pub enum Error {
+    /// The regex of an entry is invalid.
+    Regex(regex::Error),
+    /// Two entries, given by their descriptions, are both candidates for some `s` and `i`.
+    Ambiguous { first: String, second: String },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Regex(e) => write!(f, "invalid regex: {}", e),
+            Error::Ambiguous { first, second } => {
+                write!(f, "`{}` and `{}` match the same requests", first, second)
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Regex(e) => Some(e),
+            Error::Ambiguous { .. } => None,
+        }
+    }
 }
 
 impl<I, T: Entry<I>> fmt::Debug for RegexSetMap<I, T> {
@@ -51,8 +101,30 @@ pub enum GetResult<'a, T> {
 
 impl<I, T: Entry<I>> RegexSetMap<I, T> {
     /// Refer to module-level docs.
-    pub fn new(entries: Vec<T>) -> Result<Self, regex::Error> {
-        let set = regex::RegexSet::new(entries.iter().map(|r| r.regex().as_str())).unwrap();
+    pub fn new(entries: Vec<T>) -> Result<Self, Error> {
+        let set = regex::RegexSet::new(entries.iter().map(|r| r.regex().as_str()))
+            .map_err(Error::Regex)?;
+
+        let dfas: Vec<_> = entries
+            .iter()
+            .map(|e| Dfa::new(e.regex().as_str()))
+            .collect();
+        for (i, first) in entries.iter().enumerate() {
+            for (j, second) in entries.iter().enumerate().skip(i + 1) {
+                let overlapping = match (&dfas[i], &dfas[j]) {
+                    (Some(a), Some(b)) => first.overlaps_input(second) && a.overlaps(b),
+                    // too complex to check, lookups will report the ambiguity if there is one
+                    _ => false,
+                };
+                if overlapping {
+                    return Err(Error::Ambiguous {
+                        first: first.describe(),
+                        second: second.describe(),
+                    });
+                }
+            }
+        }
+
         Ok(Self {
             set,
             entries,
@@ -88,3 +160,79 @@ impl<I, T: Entry<I>> RegexSetMap<I, T> {
         GetResult::One(&self.entries[matching_idx])
     }
 }
+
+/// A DFA for the anchored search of a regex, used to check whether two regexes match a common
+/// string.
+struct Dfa {
+    dfa: dense::DFA<Vec<u32>>,
+    start: StateID,
+}
+
+impl Dfa {
+    /// Builds the DFA, or returns `None` if the regex uses features the DFA does not support,
+    /// e.g. Unicode word boundaries.
+    fn new(pattern: &str) -> Option<Self> {
+        let dfa = dense::Builder::new()
+            .configure(
+                dense::Config::new()
+                    .match_kind(MatchKind::All)
+                    .start_kind(StartKind::Anchored),
+            )
+            .build(pattern)
+            .ok()?;
+        let start = dfa
+            .start_state(&start::Config::new().anchored(Anchored::Yes))
+            .ok()?;
+        Some(Self { dfa, start })
+    }
+
+    /// Advances `state` by `byte`, returning `None` once a match has been found.
+    ///
+    /// Since the regexes are not anchored at the end, a string is matched as soon as one of its
+    /// prefixes is matched. Matches are reported with a delay of one byte.
+    fn next(&self, state: Option<StateID>, byte: u8) -> Option<Option<StateID>> {
+        let state = match state {
+            Some(state) => state,
+            None => return Some(None),
+        };
+        let next = self.dfa.next_state(state, byte);
+        if self.dfa.is_match_state(next) {
+            Some(None)
+        } else if self.dfa.is_dead_state(next) {
+            None
+        } else {
+            Some(Some(next))
+        }
+    }
+
+    /// Whether the string leading to `state` is matched.
+    fn is_match(&self, state: Option<StateID>) -> bool {
+        match state {
+            Some(state) => self.dfa.is_match_state(self.dfa.next_eoi_state(state)),
+            None => true,
+        }
+    }
+
+    /// Whether some string is matched by both `self` and `other`, checked by exploring the
+    /// states of both DFAs in lockstep.
+    fn overlaps(&self, other: &Dfa) -> bool {
+        let start = (Some(self.start), Some(other.start));
+        let mut seen = HashSet::new();
+        seen.insert(start);
+        let mut queue = VecDeque::new();
+        queue.push_back(start);
+        while let Some((a, b)) = queue.pop_front() {
+            if self.is_match(a) && other.is_match(b) {
+                return true;
+            }
+            for byte in 0..=u8::MAX {
+                if let (Some(a), Some(b)) = (self.next(a, byte), other.next(b, byte)) {
+                    if seen.insert((a, b)) {
+                        queue.push_back((a, b));
+                    }
+                }
+            }
+        }
+        false
+    }
+}
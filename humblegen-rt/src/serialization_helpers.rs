@@ -1,26 +1,202 @@
 //! `GEN` - deserialization helpers used by dispatcher.
 
 use crate::service_protocol::ErrorResponse;
+use crate::service_protocol::FieldError;
 use crate::service_protocol::RuntimeError;
 use crate::service_protocol::ToErrorResponse;
 
+use percent_encoding::{utf8_percent_encode, AsciiSet, CONTROLS};
 use serde::{Deserializer, Serializer};
 
 pub fn deser_param<T, E>(name: &str, value: &str) -> Result<T, ErrorResponse>
 where
-    E: std::fmt::Display,
+    E: std::fmt::Display + 'static,
     T: std::str::FromStr<Err = E>,
 {
     // TODO: Use std::primitive::str here, once Rust 1.43.0 has been out longer.
     str::parse(value).map_err(|e| {
         RuntimeError::RouteParamInvalid {
             param_name: name.to_owned(),
-            parse_error: format!("{}", e),
+            parse_error: describe_parse_error(&e),
         }
         .to_error_response()
     })
 }
 
+/// Describe why a route parameter, query parameter or header could not be parsed.
+///
+/// The errors of `Uuid::from_str` only name the offending character or length, so the expected
+/// format is added to them.
+fn describe_parse_error<E: std::fmt::Display + 'static>(e: &E) -> String {
+    match (e as &dyn std::any::Any).downcast_ref::<uuid::Error>() {
+        Some(e) => format!(
+            "invalid UUID ({}), expected a value like `67e55044-10b1-426f-9247-bb680e5fe0c8`",
+            e
+        ),
+        None => format!("{}", e),
+    }
+}
+
+/// Error of the `FromStr` impl generated for enums with simple variants only, returned for
+/// strings that do not name a variant.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseEnumError {
+    /// Name of the enum.
+    pub enum_name: &'static str,
+    /// The string that was parsed.
+    pub value: String,
+}
+
+impl std::fmt::Display for ParseEnumError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "unknown variant `{}` of enum `{}`",
+            self.value, self.enum_name
+        )
+    }
+}
+
+impl std::error::Error for ParseEnumError {}
+
+/// Error of a field whose value is not in the range declared in the spec, e.g. `age: i32 in 0..=150`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutOfRange {
+    /// Name of the field.
+    pub field: &'static str,
+    /// The offending value.
+    pub value: i128,
+    /// The smallest allowed value, if any.
+    pub min: Option<i64>,
+    /// The largest allowed value, if any.
+    pub max: Option<i64>,
+}
+
+impl std::fmt::Display for OutOfRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "`{}` must be in the range ", self.field)?;
+        if let Some(min) = self.min {
+            write!(f, "{}", min)?;
+        }
+        match self.max {
+            Some(max) => write!(f, "..={}", max)?,
+            None => write!(f, "..")?,
+        }
+        write!(f, ", but is {}", self.value)
+    }
+}
+
+impl std::error::Error for OutOfRange {}
+
+/// Helper function used by generated code to check that the value of `field` is within the
+/// inclusive range `min..=max`.
+pub fn check_range<T: Copy + Into<i128>>(
+    field: &'static str,
+    value: T,
+    min: Option<i64>,
+    max: Option<i64>,
+) -> Result<(), OutOfRange> {
+    let value = value.into();
+    if min.is_some_and(|min| value < min.into()) || max.is_some_and(|max| value > max.into()) {
+        Err(OutOfRange {
+            field,
+            value,
+            min,
+            max,
+        })
+    } else {
+        Ok(())
+    }
+}
+
+/// Helper function used by generated code to deserialize an integer field with a range
+/// constraint, rejecting values outside of the range.
+pub fn deser_in_range<'de, D, T>(
+    input: D,
+    field: &'static str,
+    min: Option<i64>,
+    max: Option<i64>,
+) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: serde::Deserialize<'de> + Copy + Into<i128>,
+{
+    let value = T::deserialize(input)?;
+    check_range(field, value, min, max).map_err(serde::de::Error::custom)?;
+    Ok(value)
+}
+
+/// Like [`deser_in_range`], for optional integer fields. `null` is always accepted.
+pub fn deser_optional_in_range<'de, D, T>(
+    input: D,
+    field: &'static str,
+    min: Option<i64>,
+    max: Option<i64>,
+) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: serde::Deserialize<'de> + Copy + Into<i128>,
+{
+    let value = <Option<T> as serde::Deserialize>::deserialize(input)?;
+    if let Some(value) = value {
+        check_range(field, value, min, max).map_err(serde::de::Error::custom)?;
+    }
+    Ok(value)
+}
+
+/// Helper function used by generated code to deserialize a humblegen `nullable[T]` field.
+///
+/// It deserializes like `Option<T>`, but since serde only falls back to `None` for missing
+/// `Option` fields that are not deserialized using a function, the field is required.
+pub fn deser_nullable<'de, D, T>(input: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: serde::Deserialize<'de>,
+{
+    <Option<T> as serde::Deserialize>::deserialize(input)
+}
+
+/// Helper function used by generated code to deserialize a required request header.
+pub fn deser_header<T, E>(headers: &hyper::HeaderMap, name: &str) -> Result<T, ErrorResponse>
+where
+    E: std::fmt::Display + 'static,
+    T: std::str::FromStr<Err = E>,
+{
+    deser_optional_header(headers, name)?.ok_or_else(|| {
+        RuntimeError::HeaderMissing {
+            header_name: name.to_owned(),
+        }
+        .to_error_response()
+    })
+}
+
+/// Helper function used by generated code to deserialize an optional request header.
+pub fn deser_optional_header<T, E>(
+    headers: &hyper::HeaderMap,
+    name: &str,
+) -> Result<Option<T>, ErrorResponse>
+where
+    E: std::fmt::Display + 'static,
+    T: std::str::FromStr<Err = E>,
+{
+    let invalid = |parse_error: String| {
+        RuntimeError::HeaderInvalid {
+            header_name: name.to_owned(),
+            parse_error,
+        }
+        .to_error_response()
+    };
+    match headers.get(name) {
+        None => Ok(None),
+        Some(value) => {
+            let value = value.to_str().map_err(|e| invalid(format!("{}", e)))?;
+            str::parse(value)
+                .map(Some)
+                .map_err(|e| invalid(describe_parse_error(&e)))
+        }
+    }
+}
+
 /// Helper function used by generated code to deserialize POST body data.
 pub async fn deser_post_data<T: serde::de::DeserializeOwned>(
     req_body: &mut hyper::Body,
@@ -29,10 +205,75 @@ pub async fn deser_post_data<T: serde::de::DeserializeOwned>(
         .await
         .map_err(|e| RuntimeError::PostBodyReadError(format!("{}", e)).to_error_response())?
         .to_vec();
-    match serde_json::from_slice::<T>(&bytes[..]) {
-        Ok(b) => Ok(b),
-        Err(e) => Err(RuntimeError::PostBodyReadError(format!("{}", e)).to_error_response()),
+    deser_json_body(&bytes[..])
+}
+
+/// Helper function used by generated code to deserialize POST body data of at most
+/// `max_body_size` bytes.
+///
+/// The body is read chunk by chunk and reading is aborted as soon as the limit is exceeded.
+pub async fn deser_post_data_limited<T: serde::de::DeserializeOwned>(
+    req_body: &mut hyper::Body,
+    max_body_size: usize,
+) -> Result<T, ErrorResponse> {
+    use hyper::body::HttpBody;
+
+    let too_large = || RuntimeError::PayloadTooLarge { max_body_size }.to_error_response();
+
+    // reject early if the client announced a larger body via `Content-Length`
+    if req_body.size_hint().lower() > max_body_size as u64 {
+        return Err(too_large());
     }
+
+    let mut bytes = Vec::new();
+    while let Some(chunk) = req_body.data().await {
+        let chunk = chunk
+            .map_err(|e| RuntimeError::PostBodyReadError(format!("{}", e)).to_error_response())?;
+        if bytes.len() + chunk.len() > max_body_size {
+            return Err(too_large());
+        }
+        bytes.extend_from_slice(&chunk);
+    }
+    deser_json_body(&bytes[..])
+}
+
+/// Deserialize the JSON request body `bytes`, reporting the field that could not be deserialized
+/// (if any) in a `RuntimeError::PostBodyInvalid`.
+fn deser_json_body<T: serde::de::DeserializeOwned>(bytes: &[u8]) -> Result<T, ErrorResponse> {
+    let mut deserializer = serde_json::Deserializer::from_slice(bytes);
+    let body = serde_path_to_error::deserialize(&mut deserializer).map_err(|e| {
+        // only data errors concern a field, syntax errors concern the body as a whole
+        let is_field_error = e.inner().classify() == serde_json::error::Category::Data
+            && e.path().iter().next().is_some();
+        let field = if is_field_error {
+            Some(e.path().to_string())
+        } else {
+            None
+        };
+        post_body_invalid(e.into_inner(), field)
+    })?;
+    // reject trailing characters, like `serde_json::from_slice`
+    deserializer.end().map_err(|e| post_body_invalid(e, None))?;
+    Ok(body)
+}
+
+fn post_body_invalid(error: serde_json::Error, field: Option<String>) -> ErrorResponse {
+    let message = error.to_string();
+    let fields = field
+        .map(|field| {
+            // the position is only meaningful for the body as a whole
+            let position = format!(" at line {} column {}", error.line(), error.column());
+            FieldError {
+                field,
+                message: message
+                    .strip_suffix(&position)
+                    .unwrap_or(&message)
+                    .to_owned(),
+            }
+        })
+        .into_iter()
+        .collect();
+    RuntimeError::PostBodyInvalid { message, fields }.to_error_response()
 }
 
 /// Helper function used by generated code to deserialize the URL query from application/x-www-form-urlencoded into a type T.
@@ -45,11 +286,107 @@ pub fn deser_query_serde_urlencoded<'a, T: serde::de::Deserialize<'a>>(
     }
 }
 
+/// Helper function used by generated code to deserialize the URL query like
+/// `deser_query_serde_urlencoded`, but rejecting keys not contained in `expected_keys`.
+pub fn deser_query_strict<'a, T: serde::de::Deserialize<'a>>(
+    query: &'a str,
+    expected_keys: &[&str],
+) -> Result<T, ErrorResponse> {
+    check_query_keys(Some(query), expected_keys)?;
+    deser_query_serde_urlencoded(query)
+}
+
+/// Helper function used by generated code to reject URL queries containing keys not
+/// contained in `expected_keys`.
+pub fn check_query_keys(query: Option<&str>, expected_keys: &[&str]) -> Result<(), ErrorResponse> {
+    let pairs: Vec<(String, String)> = match query {
+        None => return Ok(()),
+        Some(query) => serde_urlencoded::from_str(query)
+            .map_err(|e| RuntimeError::QueryInvalid(format!("{}", e)).to_error_response())?,
+    };
+    match pairs
+        .iter()
+        .find(|(key, _)| !expected_keys.contains(&key.as_str()))
+    {
+        None => Ok(()),
+        Some((key, _)) => Err(RuntimeError::QueryInvalid(format!(
+            "unknown query parameter `{}`",
+            key
+        ))
+        .to_error_response()),
+    }
+}
+
 /// Helper function used by generated code to deserialize the URL query into a primitive type.
-pub fn deser_query_primitive<E: std::fmt::Display, T: std::str::FromStr<Err = E>>(
+pub fn deser_query_primitive<E: std::fmt::Display + 'static, T: std::str::FromStr<Err = E>>(
     query: &str,
 ) -> Result<T, ErrorResponse> {
-    str::parse(query).map_err(|e| RuntimeError::QueryInvalid(format!("{}", e)).to_error_response())
+    str::parse(query)
+        .map_err(|e| RuntimeError::QueryInvalid(describe_parse_error(&e)).to_error_response())
+}
+
+/// Helper function used by generated code to deserialize a required query parameter
+/// from the URL query.
+pub fn deser_query_param<T, E>(query: Option<&str>, name: &str) -> Result<T, ErrorResponse>
+where
+    E: std::fmt::Display + 'static,
+    T: std::str::FromStr<Err = E>,
+{
+    deser_optional_query_param(query, name)?.ok_or_else(|| {
+        RuntimeError::QueryInvalid(format!("missing query parameter `{}`", name))
+            .to_error_response()
+    })
+}
+
+/// Helper function used by generated code to deserialize an optional query parameter
+/// from the URL query.
+pub fn deser_optional_query_param<T, E>(
+    query: Option<&str>,
+    name: &str,
+) -> Result<Option<T>, ErrorResponse>
+where
+    E: std::fmt::Display + 'static,
+    T: std::str::FromStr<Err = E>,
+{
+    let invalid = |e: &dyn std::fmt::Display| {
+        RuntimeError::QueryInvalid(format!("query parameter `{}`: {}", name, e)).to_error_response()
+    };
+    let pairs: Vec<(String, String)> = match query {
+        None => Vec::new(),
+        Some(query) => serde_urlencoded::from_str(query).map_err(|e| invalid(&e))?,
+    };
+    match pairs.into_iter().find(|(key, _)| key == name) {
+        None => Ok(None),
+        Some((_, value)) => str::parse(&value)
+            .map(Some)
+            .map_err(|e| invalid(&describe_parse_error(&e))),
+    }
+}
+
+/// Characters that are percent-encoded in a path segment, in addition to controls: those that
+/// would end the segment or the path, or are not allowed in URLs at all.
+const PATH_SEGMENT: &AsciiSet = &CONTROLS
+    .add(b' ')
+    .add(b'"')
+    .add(b'#')
+    .add(b'%')
+    .add(b'/')
+    .add(b'<')
+    .add(b'>')
+    .add(b'?')
+    .add(b'`')
+    .add(b'{')
+    .add(b'}');
+
+/// Helper function used by generated code to build the path of a route from its (unescaped)
+/// `segments`, e.g. `/monsters/42` from `["monsters", "42"]`.
+pub fn build_path(segments: &[String]) -> String {
+    let mut path = String::new();
+    for segment in segments {
+        path.push('/');
+        path.extend(utf8_percent_encode(segment, PATH_SEGMENT));
+    }
+    path
 }
 
 /// Helper function used by generate code to deserialize a humblegen `bytes` field.
@@ -86,3 +423,158 @@ where
 {
     serializer.serialize_str(&base64::encode(v))
 }
+
+/// Helper function used by generate code to deserialize a humblegen `bytes` field
+/// annotated with `@encoding("hex")`.
+pub fn deser_bytes_hex<'de, D>(input: D) -> Result<Vec<u8>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    struct HexBytesSerdeVisitor;
+
+    impl<'de> serde::de::Visitor<'de> for HexBytesSerdeVisitor {
+        type Value = Vec<u8>;
+        fn expecting(
+            &self,
+            formatter: &mut std::fmt::Formatter<'_>,
+        ) -> std::result::Result<(), std::fmt::Error> {
+            write!(formatter, "a hex-encoded byte array")
+        }
+
+        fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+        where
+            E: serde::de::Error,
+        {
+            let digits = v
+                .chars()
+                .map(|c| {
+                    c.to_digit(16)
+                        .ok_or_else(|| E::custom(format!("invalid hex digit `{}`", c)))
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            if digits.len() % 2 != 0 {
+                return Err(E::custom("odd number of hex digits"));
+            }
+            Ok(digits
+                .chunks(2)
+                .map(|pair| (pair[0] << 4 | pair[1]) as u8)
+                .collect())
+        }
+    }
+
+    input.deserialize_str(HexBytesSerdeVisitor)
+}
+
+/// Helper function used by generate code to serialize a humblegen `bytes` field
+/// annotated with `@encoding("hex")`, using lower case digits.
+pub fn ser_bytes_hex<S>(v: &[u8], serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    let hex: String = v.iter().map(|byte| format!("{:02x}", byte)).collect();
+    serializer.serialize_str(&hex)
+}
+
+/// Helper module used by generated code to (de)serialize a humblegen `u128` or `i128` field as a
+/// decimal string, since JSON numbers cannot represent them exactly in most implementations.
+pub mod int_string {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: std::fmt::Display,
+        S: Serializer,
+    {
+        serializer.collect_str(value)
+    }
+
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+    where
+        T: std::str::FromStr,
+        T::Err: std::fmt::Display,
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(|e| {
+            serde::de::Error::custom(format!("invalid integer string \"{}\": {}", s, e))
+        })
+    }
+}
+
+/// Like `int_string`, but for optional fields.
+pub mod optional_int_string {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<T, S>(value: &Option<T>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: std::fmt::Display,
+        S: Serializer,
+    {
+        match value {
+            Some(value) => serializer.collect_str(value),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<Option<T>, D::Error>
+    where
+        T: std::str::FromStr,
+        T::Err: std::fmt::Display,
+        D: Deserializer<'de>,
+    {
+        match Option::<String>::deserialize(deserializer)? {
+            Some(s) => s.parse().map(Some).map_err(|e| {
+                serde::de::Error::custom(format!("invalid integer string \"{}\": {}", s, e))
+            }),
+            None => Ok(None),
+        }
+    }
+}
+
+/// Helper module used by generated code to (de)serialize a humblegen `duration` field as integer
+/// milliseconds. Fractions of a millisecond are truncated when serializing.
+pub mod duration_millis {
+    use serde::{Deserialize, Deserializer, Serializer};
+    use std::convert::TryFrom;
+    use std::time::Duration;
+
+    pub fn serialize<S>(value: &Duration, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let millis = u64::try_from(value.as_millis()).map_err(|_| {
+            serde::ser::Error::custom(format!("duration {:?} exceeds u64 milliseconds", value))
+        })?;
+        serializer.serialize_u64(millis)
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Duration, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        u64::deserialize(deserializer).map(Duration::from_millis)
+    }
+}
+
+/// Like `duration_millis`, but for optional fields.
+pub mod optional_duration_millis {
+    use serde::{Deserialize, Deserializer, Serializer};
+    use std::time::Duration;
+
+    pub fn serialize<S>(value: &Option<Duration>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match value {
+            Some(value) => super::duration_millis::serialize(value, serializer),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<Duration>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Option::<u64>::deserialize(deserializer).map(|millis| millis.map(Duration::from_millis))
+    }
+}
@@ -1,6 +1,6 @@
 //! `GEN` Generic parts of the humblegen HTTP service server implementation, based on [`hyper`](https://hyper.rs).
 
-use crate::handler::HandlerResponse;
+use crate::handler::{HandlerResponse, RawResponse, StreamResponse};
 use crate::regexset_map;
 use crate::regexset_map::RegexSetMap;
 use crate::service_protocol::{self, RuntimeError, ToErrorResponse};
@@ -13,68 +13,389 @@ use hyper::Request;
 use hyper::Response;
 
 use std::convert::Infallible;
+use std::future::Future;
 use std::net::SocketAddr;
+use std::pin::Pin;
 use std::sync::Arc;
+use std::task::{Context as TaskContext, Poll};
+use std::time::Duration;
 
 use rand::Rng;
 
-/// Serve `services` via HTTP, binding to the given `addr`.
-/// Invokes `handle_request`.
+mod cors;
+pub use cors::{AllowedOrigins, CorsConfig};
+
+mod health;
+pub use health::HealthChecks;
+
+mod static_files;
+pub use static_files::StaticFiles;
+
+mod proxy;
+pub use proxy::proxy_route;
+
+#[cfg(feature = "compression")]
+mod compression;
+
+#[cfg(feature = "metrics")]
+mod metrics;
+
+#[cfg(feature = "websocket")]
+mod websocket;
+#[cfg(feature = "websocket")]
+pub use websocket::{
+    accept_websocket, websocket_handler_response_to_hyper_response, WebSocketUpgrade,
+};
+
+#[cfg(feature = "axum-router")]
+mod axum_router;
+#[cfg(feature = "axum-router")]
+pub use axum_router::{check_axum_options, into_axum_response, into_hyper_request};
+
+#[cfg(feature = "tls")]
+mod tls;
+#[cfg(feature = "tls")]
+pub use tls::{listen_and_run_forever_tls, TlsConfig};
+
+/// Expands to nothing, see the `tls` feature.
+#[cfg(not(feature = "tls"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __builder_tls_methods {
+    () => {};
+}
+
+#[cfg(not(feature = "metrics"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __builder_metrics_methods {
+    () => {};
+}
+
+/// Serve `service` via HTTP, binding to the given `addr`.
 ///
 /// Invoked by generated code.
-pub async fn listen_and_run_forever(
-    services: RegexSetMap<Request<Body>, Service>,
+pub async fn listen_and_run_forever(service: HttpService, addr: &SocketAddr) -> anyhow::Result<()> {
+    listen_with_shutdown(service, addr, futures::future::pending()).await
+}
+
+/// Serve `service` via HTTP, binding to the given `addr`, until `shutdown` completes.
+/// Once it does, the server stops accepting connections and returns after all
+/// in-flight requests have been answered.
+///
+/// Invoked by generated code.
+pub async fn listen_with_shutdown(
+    service: HttpService,
     addr: &SocketAddr,
+    shutdown: impl Future<Output = ()>,
 ) -> anyhow::Result<()> {
-    // Note: this is the standard (noisy) dance for handling hyper requests.
-    let services = Arc::new(services);
-    let server = hyper::Server::bind(addr).serve(hyper::service::make_service_fn(
-        move |_sock: &hyper::server::conn::AddrStream| {
-            let services = Arc::clone(&services);
-            async move {
-                Ok::<_, Infallible>(hyper::service::service_fn(
-                    move |req: hyper::Request<hyper::Body>| {
-                        let services = Arc::clone(&services);
-                        async move {
-                            let resp = handle_request(services, req).await;
-                            Ok::<Response<hyper::Body>, Infallible>(resp)
-                        }
-                    },
-                ))
-            }
-        },
-    ));
+    let server = hyper::Server::bind(addr)
+        .serve(hyper::service::make_service_fn(
+            move |_sock: &hyper::server::conn::AddrStream| {
+                futures::future::ok::<_, Infallible>(service.clone())
+            },
+        ))
+        .with_graceful_shutdown(shutdown);
 
     server.await.context("server error")?;
     Ok(())
 }
 
-const REQUEST_ID_HEADER_NAME: &'static str = "Request-ID";
+/// The services of a server along with its options, answering requests using `handle_request`.
+///
+/// Implements `hyper::service::Service`, which is a re-export of
+/// [`tower::Service`](https://docs.rs/tower-service/0.3), so that it can be wrapped in tower
+/// middleware or served by a `hyper::Server` configured by the user, cloning it for each
+/// connection (which is cheap):
+///
+/// ```ignore
+/// let service = Builder::new().add("/api", handler).into_service()?;
+/// let make_service = hyper::service::make_service_fn(move |_conn| {
+///     futures::future::ok::<_, std::convert::Infallible>(service.clone())
+/// });
+/// hyper::Server::bind(&addr).serve(make_service).await?;
+/// ```
+///
+/// Created using the generated `Builder::into_service`.
+#[derive(Debug, Clone)]
+pub struct HttpService {
+    services: Arc<RegexSetMap<Request<Body>, Service>>,
+    options: Arc<ServerOptions>,
+}
+
+impl HttpService {
+    /// Invoked by generated code.
+    pub fn new(services: RegexSetMap<Request<Body>, Service>, options: ServerOptions) -> Self {
+        Self {
+            services: Arc::new(services),
+            options: Arc::new(options),
+        }
+    }
+}
+
+impl hyper::service::Service<Request<Body>> for HttpService {
+    type Response = Response<Body>;
+    type Error = Infallible;
+    type Future = Pin<Box<dyn Future<Output = Result<Response<Body>, Infallible>> + Send>>;
+
+    fn poll_ready(&mut self, _cx: &mut TaskContext<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: Request<Body>) -> Self::Future {
+        let services = Arc::clone(&self.services);
+        let options = Arc::clone(&self.options);
+        Box::pin(async move { Ok(handle_request(services, options, req).await) })
+    }
+}
+
+/// Options of the server that apply to all services.
+#[derive(Debug, Clone)]
+pub struct ServerOptions {
+    /// How request IDs are assigned.
+    pub request_ids: RequestIdConfig,
+    /// The maximum size of a request body in bytes (2 MiB by default).
+    /// Larger bodies are rejected with `RuntimeError::PayloadTooLarge`.
+    pub max_body_size: usize,
+    /// Whether URL query keys that the endpoint does not declare are rejected with
+    /// `RuntimeError::QueryInvalid` (disabled by default).
+    pub strict_query: bool,
+    /// Whether a path with a trailing slash is matched like the path without it if no route
+    /// matches it exactly, e.g. `/monsters/` like `/monsters` (disabled by default).
+    pub ignore_trailing_slash: bool,
+    /// Cross-Origin Resource Sharing, disabled if `None` (the default).
+    pub cors: Option<CorsConfig>,
+    /// The `/healthz` and `/readyz` endpoints, disabled if `None` (the default).
+    pub health_checks: Option<HealthChecks>,
+    /// How long the interceptors and handler may take to produce a response before the request
+    /// fails with `RuntimeError::HandlerTimeout`, unlimited if `None` (the default).
+    pub request_timeout: Option<Duration>,
+    /// Directories whose files are served before the services are matched, in the order they
+    /// are looked up (none by default).
+    pub static_files: Vec<StaticFiles>,
+    /// How long the upstream server of a `PROXY` route may take to send the head of its
+    /// response before the request fails with `RuntimeError::UpstreamTimeout` (30 seconds by
+    /// default).
+    pub proxy_timeout: Duration,
+    /// The path the request metrics are served at, disabled if `None` (the default).
+    #[cfg(feature = "metrics")]
+    pub metrics_path: Option<String>,
+}
+
+impl Default for ServerOptions {
+    fn default() -> Self {
+        Self {
+            request_ids: RequestIdConfig::default(),
+            max_body_size: 2 * 1024 * 1024,
+            strict_query: false,
+            ignore_trailing_slash: false,
+            cors: None,
+            health_checks: None,
+            request_timeout: None,
+            static_files: Vec::new(),
+            proxy_timeout: Duration::from_secs(30),
+            #[cfg(feature = "metrics")]
+            metrics_path: None,
+        }
+    }
+}
+
+/// Inbound request IDs longer than this are replaced by a generated one.
+const MAX_INBOUND_REQUEST_ID_LEN: usize = 128;
+
+/// How a server assigns request IDs to incoming requests.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RequestIdStrategy {
+    /// Always generate a random request ID.
+    Generate,
+    /// Reuse the request ID that the client (or a reverse proxy) sent in the request ID header,
+    /// generate a random one if it is absent or invalid.
+    ReuseInbound,
+}
+
+/// Configuration of the request IDs returned to the client in every response.
+#[derive(Debug, Clone)]
+pub struct RequestIdConfig {
+    /// The name of the request ID header (`Request-ID` by default).
+    pub header_name: hyper::header::HeaderName,
+    /// Whether inbound request IDs are reused (`Generate` by default).
+    pub strategy: RequestIdStrategy,
+}
+
+impl Default for RequestIdConfig {
+    fn default() -> Self {
+        Self {
+            header_name: hyper::header::HeaderName::from_static("request-id"),
+            strategy: RequestIdStrategy::Generate,
+        }
+    }
+}
+
+impl RequestIdConfig {
+    /// Returns the request ID for `req`.
+    fn request_id(&self, req: &Request<Body>) -> String {
+        let inbound = match self.strategy {
+            RequestIdStrategy::Generate => None,
+            RequestIdStrategy::ReuseInbound => req
+                .headers()
+                .get(&self.header_name)
+                .and_then(|value| value.to_str().ok())
+                .filter(|id| !id.is_empty() && id.len() <= MAX_INBOUND_REQUEST_ID_LEN),
+        };
+        match inbound {
+            Some(id) => id.to_owned(),
+            None => rand::thread_rng()
+                .sample_iter(&rand::distributions::Alphanumeric)
+                .take(30)
+                .collect(),
+        }
+    }
+}
 
 /// The routine that maps an incoming hyper request to a service in `services`,
 /// and invokes the service's dispatcher.
 pub async fn handle_request(
     services: Arc<RegexSetMap<Request<Body>, Service>>,
+    options: Arc<ServerOptions>,
     req: Request<Body>,
 ) -> Response<Body> {
-    let request_id: String = rand::thread_rng()
-        .sample_iter(&rand::distributions::Alphanumeric)
-        .take(30)
-        .collect();
+    let request_id = options.request_ids.request_id(&req);
     let span = tracing::error_span!("handle_request", request_id = ?request_id);
-    handle_request_impl(services, req, request_id)
+    handle_request_impl(services, options, req, request_id)
         .instrument(span)
         .await
 }
 
 pub async fn handle_request_impl(
     services: Arc<RegexSetMap<Request<Body>, Service>>,
+    options: Arc<ServerOptions>,
     req: Request<Body>,
     request_id: String,
 ) -> Response<Body> {
+    // computed before `req` is moved into the dispatcher
+    let cors_headers = options
+        .cors
+        .as_ref()
+        .map(|cors| cors.response_headers(&req, &options.request_ids.header_name));
+    #[cfg(feature = "compression")]
+    let encoding = compression::Encoding::negotiate(&req);
+    #[cfg(feature = "metrics")]
+    let (method, started) = (req.method().clone(), std::time::Instant::now());
+    let is_head = req.method() == hyper::Method::HEAD;
+
+    let (mut response, matched_route) = match (&options.health_checks, &options.cors) {
+        #[cfg(feature = "metrics")]
+        _ if metrics::is_scrape(&req, options.metrics_path.as_deref()) => {
+            (metrics::scrape_response(), None)
+        }
+        // health checks take precedence over the services, whatever they are mounted at
+        (Some(health_checks), _) if HealthChecks::is_probe(&req) => {
+            tracing::debug!("answering health check");
+            (health_checks.probe_response(&req).await, None)
+        }
+        (_, Some(cors)) if CorsConfig::is_preflight(&req) => {
+            tracing::debug!("answering CORS preflight request");
+            (cors.preflight_response(&req), None)
+        }
+        // requests below the path prefix of static files never reach the services
+        _ => match static_files::static_file_response(&options.static_files, &req).await {
+            Some(response) => {
+                tracing::debug!(
+                    status = response.status().as_u16(),
+                    "answering static file request"
+                );
+                (response, None)
+            }
+            None => dispatch(services, Arc::clone(&options), req).await,
+        },
+    };
+
+    #[cfg(feature = "metrics")]
+    metrics::record(
+        &method,
+        response.status(),
+        matched_route.as_ref(),
+        started.elapsed(),
+    );
+
+    response.headers_mut().insert(
+        options.request_ids.header_name.clone(),
+        hyper::header::HeaderValue::from_str(&request_id)
+            .expect("request ID is expected to be valid header value"),
+    );
+    response
+        .headers_mut()
+        .extend(cors_headers.into_iter().flatten());
+
+    // handlers of raw responses choose their own content type, WebSocket upgrades have no body
+    // and upstream servers of proxy routes are not second-guessed
+    let proxied = matches!(&matched_route, Some(route) if route.kind == RouteKind::Proxy);
+    if response.status() != hyper::StatusCode::NO_CONTENT
+        && response.status() != hyper::StatusCode::SWITCHING_PROTOCOLS
+        && !proxied
+    {
+        response
+            .headers_mut()
+            .entry(hyper::header::CONTENT_TYPE)
+            .or_insert_with(|| hyper::header::HeaderValue::from_static("application/json"));
+    }
+
+    #[cfg(feature = "compression")]
+    let response = compression::compress_response(response, encoding).await;
+
+    match &matched_route {
+        Some(MatchedRoute { service, route, .. }) => tracing::debug!(
+            http_status = ?response.status(),
+            service = service.as_str(),
+            route = route.as_str(),
+            "finished request"
+        ),
+        None => tracing::debug!(http_status = ?response.status(), "finished request"),
+    }
+
+    if is_head {
+        strip_body(response)
+    } else {
+        response
+    }
+}
+
+/// Drops the body of the response to a `HEAD` request, which is otherwise answered like a `GET`
+/// request. The length of the body is kept in the `Content-Length` header if it is known and
+/// the header is not set yet, e.g. by the upstream server of a proxy route.
+fn strip_body(response: Response<Body>) -> Response<Body> {
+    use hyper::body::HttpBody;
+
+    let (mut parts, body) = response.into_parts();
+    if let Some(len) = body.size_hint().exact() {
+        parts
+            .headers
+            .entry(hyper::header::CONTENT_LENGTH)
+            .or_insert_with(|| hyper::header::HeaderValue::from(len));
+    }
+    Response::from_parts(parts, Body::empty())
+}
+
+/// The route a request was dispatched to.
+struct MatchedRoute {
+    /// The root the service is mounted at.
+    service: String,
+    /// The regex of the route within the service.
+    route: String,
+    /// The kind of the route.
+    kind: RouteKind,
+}
+
+/// Dispatch `req` to the route of the service it is addressed to, returning the response and
+/// the matched route (if any).
+async fn dispatch(
+    services: Arc<RegexSetMap<Request<Body>, Service>>,
+    options: Arc<ServerOptions>,
+    req: Request<Body>,
+) -> (Response<Body>, Option<MatchedRoute>) {
     let path = req.uri().path().to_string(); // necessary because we need to move req into dispatcher, but also need to move captures into dispatcher
 
-    let mut response = match services.get(&path, &req) {
+    let response = match services.get(&path, &req) {
         regexset_map::GetResult::None => RuntimeError::NoServiceMounted
             .to_error_response()
             .to_hyper_response(),
@@ -86,8 +407,13 @@ pub async fn handle_request_impl(
             let tuple = &service.0;
             let service_regex_captures = tuple.0.captures(&path).unwrap();
             let service = service_regex_captures["root"].to_string();
-            let suffix = &service_regex_captures["suffix"];
-            match tuple.1.get(&suffix, &req) {
+            let (route, suffix) = match_route(
+                &tuple.1,
+                &service_regex_captures["suffix"],
+                &req,
+                options.ignore_trailing_slash,
+            );
+            match route {
                 regexset_map::GetResult::None => RuntimeError::NoRouteMountedInService { service }
                     .to_error_response()
                     .to_hyper_response(),
@@ -97,15 +423,37 @@ pub async fn handle_request_impl(
                         .to_hyper_response()
                 }
                 regexset_map::GetResult::One(route) => {
-                    tracing::debug!(route_regex = route.regex.as_str(), "route matched");
+                    tracing::debug!(route = route.template, "route matched");
                     let captures = route.regex.captures(suffix).unwrap();
                     let dispatcher = &route.dispatcher;
+                    let matched_route = MatchedRoute {
+                        service,
+                        route: route.regex.as_str().to_owned(),
+                        kind: route.kind,
+                    };
 
+                    let request_timeout = options.request_timeout;
                     let dispatcher_result = {
-                        let dispatcher_span = tracing::error_span!("invoke_dispatcher");
-                        dispatcher(req, captures).instrument(dispatcher_span).await
+                        let dispatcher_span = tracing::error_span!(
+                            "invoke_dispatcher",
+                            service = route.service,
+                            route = route.template
+                        );
+                        let dispatched =
+                            dispatcher(req, captures, options).instrument(dispatcher_span);
+                        match request_timeout {
+                            None => dispatched.await,
+                            Some(timeout) => tokio::time::timeout(timeout, dispatched)
+                                .await
+                                .unwrap_or_else(|_| {
+                                    Err(RuntimeError::HandlerTimeout {
+                                        timeout_ms: timeout.as_millis() as u64,
+                                    }
+                                    .to_error_response())
+                                }),
+                        }
                     };
-                    match dispatcher_result {
+                    let response = match dispatcher_result {
                         Ok(r) => {
                             tracing::debug!("handler returned Ok");
                             r
@@ -114,26 +462,30 @@ pub async fn handle_request_impl(
                             tracing::error!(err = ?e, "handler returned error");
                             e.to_hyper_response()
                         }
-                    }
+                    };
+                    return (response, Some(matched_route));
                 }
             }
         }
     };
+    (response, None)
+}
 
-    response.headers_mut().insert(
-        REQUEST_ID_HEADER_NAME,
-        hyper::header::HeaderValue::from_str(&request_id)
-            .expect("request ID is expected to be valid header value"),
-    );
-
-    response.headers_mut().insert(
-        hyper::header::CONTENT_TYPE,
-        hyper::header::HeaderValue::from_static("application/json"),
-    );
-
-    tracing::debug!(http_status = ?response.status(), "finished request");
-
-    response
+/// Looks up the route matching the path `suffix` below the root of a service, retrying without
+/// a trailing slash if `ignore_trailing_slash` is set. Returns the path that was matched.
+fn match_route<'a, 'b>(
+    routes: &'a RegexSetMap<Request<Body>, Route>,
+    suffix: &'b str,
+    req: &Request<Body>,
+    ignore_trailing_slash: bool,
+) -> (regexset_map::GetResult<'a, Route>, &'b str) {
+    let route = routes.get(suffix, req);
+    if let (regexset_map::GetResult::None, true) = (&route, ignore_trailing_slash) {
+        if let Some(trimmed) = suffix.strip_suffix('/').filter(|t| !t.is_empty()) {
+            return (routes.get(trimmed, req), trimmed);
+        }
+    }
+    (route, suffix)
 }
 
 /// A service is a collection of Routes that share a common `prefix`.
@@ -142,6 +494,92 @@ pub async fn handle_request_impl(
 #[derive(Debug)]
 pub struct Service(pub (regex::Regex, RegexSetMap<Request<Body>, Route>));
 
+/// Error of `Builder::try_add` in generated code, returned if a handler cannot be mounted at
+/// the given root, of `Builder::into_service` if the options conflict with the routes, or of
+/// `Handler::into_router` if the options do not apply to the axum runtime.
+#[derive(Debug)]
+pub enum BuilderError {
+    /// The root does not start with a `/`.
+    RootWithoutLeadingSlash(String),
+    /// The root ends with a `/`.
+    RootWithTrailingSlash(String),
+    /// The root is not a valid regular expression.
+    InvalidRoot(String, regex::Error),
+    /// The routes of the handler are invalid, e.g. because some of them match the same requests.
+    InvalidRoutes(regexset_map::Error),
+    /// The path the metrics are served at is matched by a route of the service mounted at the
+    /// given root, too.
+    MetricsPathConflict { path: String, root: String },
+    /// The option of the `ServerOptions` passed to `Handler::into_router` does not apply to the
+    /// axum runtime.
+    UnsupportedByAxum(&'static str),
+}
+
+impl std::fmt::Display for BuilderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BuilderError::RootWithoutLeadingSlash(root) => {
+                write!(f, "root must start with \"/\", but is {:?}", root)
+            }
+            BuilderError::RootWithTrailingSlash(root) => {
+                write!(f, "root must not end with \"/\", but is {:?}", root)
+            }
+            BuilderError::InvalidRoot(root, e) => write!(f, "invalid root {:?}: {}", root, e),
+            BuilderError::InvalidRoutes(e) => write!(f, "invalid routes: {}", e),
+            BuilderError::MetricsPathConflict { path, root } => write!(
+                f,
+                "metrics path {:?} is matched by a route of the service mounted at {:?}",
+                path, root
+            ),
+            BuilderError::UnsupportedByAxum(option) => write!(
+                f,
+                "option `{}` is not supported by the axum runtime, use axum or tower middleware instead",
+                option
+            ),
+        }
+    }
+}
+
+impl std::error::Error for BuilderError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            BuilderError::InvalidRoot(_, e) => Some(e),
+            BuilderError::InvalidRoutes(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+/// Check that no route of `services` matches the requests for the metrics, which would never be
+/// dispatched to it.
+///
+/// Invoked by generated code.
+pub fn check_metrics_path(
+    services: &RegexSetMap<Request<Body>, Service>,
+    options: &ServerOptions,
+) -> Result<(), BuilderError> {
+    #[cfg(feature = "metrics")]
+    metrics::check_path(services, options)?;
+    #[cfg(not(feature = "metrics"))]
+    let _ = (services, options);
+    Ok(())
+}
+
+/// Check that `root` can be used as the URL path prefix of a service and return the regex
+/// matching the paths below it.
+///
+/// Invoked by generated code.
+pub fn root_regex(root: &str) -> Result<regex::Regex, BuilderError> {
+    if !root.starts_with('/') {
+        return Err(BuilderError::RootWithoutLeadingSlash(root.to_owned()));
+    }
+    if root.ends_with('/') {
+        return Err(BuilderError::RootWithTrailingSlash(root.to_owned()));
+    }
+    regex::Regex::new(&format!(r"^(?P<root>{})(?P<suffix>/.*)", root))
+        .map_err(|e| BuilderError::InvalidRoot(root.to_owned(), e))
+}
+
 // helper type that avoids bloating the type signature of `DispatcherClosure`.
 type BoxSyncFuture<Output> =
     std::pin::Pin<Box<dyn Send + Sync + std::future::Future<Output = Output>>>;
@@ -151,6 +589,7 @@ type BoxSyncFuture<Output> =
 type DispatcherClosure = dyn Fn(
         Request<Body>,
         regex::Captures,
+        Arc<ServerOptions>,
     ) -> BoxSyncFuture<Result<Response<Body>, service_protocol::ErrorResponse>>
     + Send
     + Sync;
@@ -164,16 +603,78 @@ type DispatcherClosure = dyn Fn(
 pub struct Route {
     pub method: hyper::Method,
     pub regex: regex::Regex,
+    /// Name of the service the route belongs to.
+    pub service: &'static str,
+    /// The path of the route relative to the root of the service, with placeholders for the
+    /// route params, e.g. `/monsters/{id}`.
+    pub template: &'static str,
+    pub kind: RouteKind,
     #[derivative(Debug = "ignore")]
     pub dispatcher: Box<DispatcherClosure>,
 }
 
+/// The kind of requests a `Route` matches, which lets e.g. a `GET` and a `WS` endpoint share
+/// their path.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RouteKind {
+    /// Plain HTTP requests.
+    Plain,
+    /// WebSocket upgrade requests (`WS` endpoints).
+    WebSocket,
+    /// Requests accepting `text/event-stream` (`SSE` endpoints).
+    EventStream,
+    /// Requests of any method and kind, forwarded to an upstream server (`PROXY` routes).
+    Proxy,
+}
+
+impl RouteKind {
+    /// The kind of route that `req` is meant for.
+    fn of_request(req: &Request<Body>) -> Self {
+        let header_values = |name| {
+            req.headers()
+                .get_all(name)
+                .iter()
+                .filter_map(|value| value.to_str().ok())
+        };
+        if header_values(hyper::header::UPGRADE)
+            .any(|value| value.eq_ignore_ascii_case("websocket"))
+        {
+            RouteKind::WebSocket
+        } else if header_values(hyper::header::ACCEPT)
+            .flat_map(|value| value.split(','))
+            .any(|media_type| {
+                let essence = media_type.split(';').next().unwrap_or("").trim();
+                essence.eq_ignore_ascii_case("text/event-stream")
+            })
+        {
+            RouteKind::EventStream
+        } else {
+            RouteKind::Plain
+        }
+    }
+}
+
 impl<'a> regexset_map::Entry<Request<Body>> for Route {
     fn regex(&self) -> &regex::Regex {
         &self.regex
     }
     fn matches_input(&self, req: &Request<Body>) -> bool {
-        self.method == req.method()
+        if self.kind == RouteKind::Proxy {
+            return true;
+        }
+        // `HEAD` requests are answered by `GET` routes, see `strip_body`
+        let method_matches = self.method == req.method()
+            || (self.method == hyper::Method::GET && req.method() == hyper::Method::HEAD);
+        method_matches && self.kind == RouteKind::of_request(req)
+    }
+    fn overlaps_input(&self, other: &Self) -> bool {
+        // proxy routes match all requests to their paths, so they must not share them
+        (self.method == other.method && self.kind == other.kind)
+            || self.kind == RouteKind::Proxy
+            || other.kind == RouteKind::Proxy
+    }
+    fn describe(&self) -> String {
+        format!("{} {}", self.method, self.regex.as_str())
     }
 }
 
@@ -189,13 +690,23 @@ impl<'a> regexset_map::Entry<Request<Body>> for Service {
 
 /// Conversion of a `HandlerResponse` to a hyper response.
 /// Invoked from generated code within a `DispatcherClosure`.
-pub fn handler_response_to_hyper_response<T>(handler_response: HandlerResponse<T>) -> Response<Body>
+///
+/// Successful responses are sent with the endpoint's `status`, e.g. `201` for an endpoint
+/// declared with `status 201`.
+pub fn handler_response_to_hyper_response<T>(
+    status: u16,
+    handler_response: HandlerResponse<T>,
+) -> Response<Body>
 where
     T: serde::Serialize,
 {
     match handler_response {
         Ok(x) => serde_json::to_string(&x)
-            .map(|s| Response::new(Body::from(s)))
+            .map(|s| {
+                let mut response = Response::new(Body::from(s));
+                *response.status_mut() = success_status(status);
+                response
+            })
             .unwrap_or_else(|e| {
                 tracing::error!(error = ?e, "cannot serialize handler response");
                 RuntimeError::SerializeHandlerResponse(e.to_string())
@@ -210,3 +721,165 @@ where
         }
     }
 }
+
+/// Conversion of a `HandlerResponse` for an endpoint returning `result[T][E]` and declared with
+/// `@error_status(...)` to a hyper response.
+/// Invoked from generated code within a `DispatcherClosure`.
+///
+/// The result is serialized like by `handler_response_to_hyper_response`, but an `Err` is sent
+/// with `error_status` instead of the endpoint's `status`, e.g. `404` for a domain error like
+/// `MonsterError::NotFound`.
+pub fn result_handler_response_to_hyper_response<T, E>(
+    status: u16,
+    error_status: u16,
+    handler_response: HandlerResponse<Result<T, E>>,
+) -> Response<Body>
+where
+    T: serde::Serialize,
+    E: serde::Serialize,
+{
+    let is_err = matches!(handler_response, Ok(Err(_)));
+    let mut response = handler_response_to_hyper_response(status, handler_response);
+    if is_err && response.status().is_success() {
+        *response.status_mut() =
+            hyper::StatusCode::from_u16(error_status).expect("invalid error status code");
+    }
+    response
+}
+
+/// Conversion of a `HandlerResponse` for an endpoint returning `stream[T]` to a hyper response.
+/// Invoked from generated code within a `DispatcherClosure`.
+///
+/// The items are sent as newline-delimited JSON (`application/x-ndjson`) while the handler's
+/// stream produces them. If an item cannot be serialized, the response is aborted.
+pub fn stream_handler_response_to_hyper_response<T>(
+    status: u16,
+    handler_response: HandlerResponse<StreamResponse<T>>,
+) -> Response<Body>
+where
+    T: serde::Serialize + 'static,
+{
+    use futures::StreamExt;
+
+    match handler_response {
+        Ok(items) => {
+            let lines = items.map(|item| {
+                serde_json::to_vec(&item)
+                    .map(|mut line| {
+                        line.push(b'\n');
+                        line
+                    })
+                    .map_err(|e| {
+                        tracing::error!(error = ?e, "cannot serialize streamed handler response item");
+                        e
+                    })
+            });
+            Response::builder()
+                .status(success_status(status))
+                .header(hyper::header::CONTENT_TYPE, "application/x-ndjson")
+                .body(Body::wrap_stream(lines))
+                .unwrap_or_else(|e| {
+                    tracing::error!(error = ?e, "cannot build streamed handler response");
+                    RuntimeError::SerializeHandlerResponse(e.to_string())
+                        .to_error_response()
+                        .to_hyper_response()
+                })
+        }
+        Err(e) => {
+            tracing::error!(error = ?e, "handler returned error");
+            service_protocol::ServiceError::from(e)
+                .to_error_response()
+                .to_hyper_response()
+        }
+    }
+}
+
+/// Idle event streams send a comment at this interval, so that proxies and browsers do not
+/// close the connection. Timed using `futures_timer`, as the stream may be polled by the tokio
+/// runtime of axum rather than the one of this crate.
+const EVENT_STREAM_HEARTBEAT: Duration = Duration::from_secs(15);
+
+/// Conversion of a `HandlerResponse` for an `SSE` endpoint to a hyper response.
+/// Invoked from generated code within a `DispatcherClosure`.
+///
+/// Each item is sent as a Server-Sent Event (`text/event-stream`) whose `data` is the item's
+/// JSON representation and whose `id` counts up from 1. If an item cannot be serialized, the
+/// response is aborted.
+pub fn event_stream_handler_response_to_hyper_response<T>(
+    handler_response: HandlerResponse<StreamResponse<T>>,
+) -> Response<Body>
+where
+    T: serde::Serialize + 'static,
+{
+    use futures::future::{self, Either};
+    use futures::StreamExt;
+
+    match handler_response {
+        Ok(items) => {
+            let events = futures::stream::unfold((items, 1u64), |(mut items, id)| async move {
+                let heartbeat = futures_timer::Delay::new(EVENT_STREAM_HEARTBEAT);
+                match future::select(items.next(), heartbeat).await {
+                    Either::Left((Some(item), _)) => {
+                        let event = serde_json::to_string(&item)
+                            .map(|data| format!("id: {}\ndata: {}\n\n", id, data))
+                            .map_err(|e| {
+                                tracing::error!(error = ?e, "cannot serialize event stream item");
+                                e
+                            });
+                        Some((event, (items, id + 1)))
+                    }
+                    Either::Left((None, _)) => None,
+                    // comments are ignored by clients
+                    Either::Right(_) => Some((Ok(": heartbeat\n\n".to_owned()), (items, id))),
+                }
+            });
+            Response::builder()
+                .header(hyper::header::CONTENT_TYPE, "text/event-stream")
+                .header(hyper::header::CACHE_CONTROL, "no-cache")
+                .body(Body::wrap_stream(events))
+                .unwrap_or_else(|e| {
+                    tracing::error!(error = ?e, "cannot build event stream handler response");
+                    RuntimeError::SerializeHandlerResponse(e.to_string())
+                        .to_error_response()
+                        .to_hyper_response()
+                })
+        }
+        Err(e) => {
+            tracing::error!(error = ?e, "handler returned error");
+            service_protocol::ServiceError::from(e)
+                .to_error_response()
+                .to_hyper_response()
+        }
+    }
+}
+
+/// Conversion of a `HandlerResponse` for an endpoint returning `raw bytes` to a hyper response.
+/// Invoked from generated code within a `DispatcherClosure`.
+pub fn raw_handler_response_to_hyper_response(
+    status: u16,
+    handler_response: HandlerResponse<RawResponse>,
+) -> Response<Body> {
+    match handler_response {
+        Ok(RawResponse { content_type, body }) => Response::builder()
+            .status(success_status(status))
+            .header(hyper::header::CONTENT_TYPE, content_type)
+            .body(Body::from(body))
+            .unwrap_or_else(|e| {
+                tracing::error!(error = ?e, "cannot build raw handler response");
+                RuntimeError::SerializeHandlerResponse(e.to_string())
+                    .to_error_response()
+                    .to_hyper_response()
+            }),
+        Err(e) => {
+            tracing::error!(error = ?e, "handler returned error");
+            service_protocol::ServiceError::from(e)
+                .to_error_response()
+                .to_hyper_response()
+        }
+    }
+}
+
+/// The status code of successful responses; the parser only accepts valid ones.
+fn success_status(status: u16) -> hyper::StatusCode {
+    hyper::StatusCode::from_u16(status).expect("invalid success status code")
+}
@@ -0,0 +1,117 @@
+//! `GEN` Glue between [`axum`](https://docs.rs/axum/0.7) and the dispatch code of generated
+//! services, used by servers generated for the axum runtime.
+//!
+//! The generated dispatch code and the handler traits work with the `hyper` 0.13 types used by
+//! the rest of this crate, while axum is built on `http` 1.0, thus requests and responses are
+//! converted at the boundary. Bodies are streamed through rather than buffered.
+//!
+//! axum runs on tokio 1 rather than the tokio 0.2 runtime of this crate, thus timers must be
+//! runtime-agnostic.
+
+use super::{BuilderError, ServerOptions};
+use crate::service_protocol::{ErrorResponse, RuntimeError, ToErrorResponse};
+
+use futures::future::{self, Either};
+use futures::stream::TryStreamExt;
+use hyper::Body;
+
+use std::future::Future;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Check that `options` only sets options that apply to the axum runtime, since the others are
+/// left to axum and tower middleware.
+///
+/// Invoked by generated code.
+pub fn check_axum_options(options: &ServerOptions) -> Result<(), BuilderError> {
+    let default_request_ids = super::RequestIdConfig::default();
+    let unsupported = [
+        (
+            "request_ids",
+            options.request_ids.header_name != default_request_ids.header_name
+                || options.request_ids.strategy != default_request_ids.strategy,
+        ),
+        ("ignore_trailing_slash", options.ignore_trailing_slash),
+        ("cors", options.cors.is_some()),
+        ("health_checks", options.health_checks.is_some()),
+        ("static_files", !options.static_files.is_empty()),
+        #[cfg(feature = "metrics")]
+        ("metrics_path", options.metrics_path.is_some()),
+    ];
+    match unsupported.iter().find(|(_, set)| *set) {
+        Some((option, _)) => Err(BuilderError::UnsupportedByAxum(option)),
+        None => Ok(()),
+    }
+}
+
+/// Converts a request received by an axum handler into the request type of the dispatch code.
+///
+/// Invoked by generated code.
+pub fn into_hyper_request(req: axum::extract::Request) -> hyper::Request<Body> {
+    let (parts, body) = req.into_parts();
+
+    let mut builder = hyper::Request::builder()
+        .method(parts.method.as_str())
+        .uri(parts.uri.to_string());
+    for (name, value) in &parts.headers {
+        builder = builder.header(name.as_str(), value.as_bytes());
+    }
+
+    // the stream of an axum body is not `Sync`, which the body of a hyper request has to be
+    let data = Mutex::new(body.into_data_stream());
+    let body = Body::wrap_stream(futures::stream::poll_fn(move |cx| {
+        let mut data = data.lock().expect("body stream lock is not poisoned");
+        futures::Stream::poll_next(std::pin::Pin::new(&mut *data), cx)
+            .map(|chunk| chunk.map(|chunk| chunk.map(|bytes| bytes.to_vec())))
+    }));
+
+    builder
+        .body(body)
+        .expect("parts of a valid request form a valid request")
+}
+
+/// Awaits the response of the dispatch code and converts it into an axum response, adding the
+/// `Content-Type` of JSON responses like the hyper server does. If the response takes longer than
+/// `request_timeout`, the request fails with `RuntimeError::HandlerTimeout`.
+///
+/// Invoked by generated code.
+pub async fn into_axum_response(
+    request_timeout: Option<Duration>,
+    response: impl Future<Output = Result<hyper::Response<Body>, ErrorResponse>>,
+) -> axum::response::Response {
+    let response = match request_timeout {
+        None => response.await,
+        Some(timeout) => {
+            futures::pin_mut!(response);
+            match future::select(response, futures_timer::Delay::new(timeout)).await {
+                Either::Left((response, _)) => response,
+                Either::Right(_) => Err(RuntimeError::HandlerTimeout {
+                    timeout_ms: timeout.as_millis() as u64,
+                }
+                .to_error_response()),
+            }
+        }
+    };
+    let (parts, body) = match response {
+        Ok(response) => response,
+        Err(error_response) => error_response.to_hyper_response(),
+    }
+    .into_parts();
+
+    let mut builder = axum::http::Response::builder().status(parts.status.as_u16());
+    for (name, value) in &parts.headers {
+        builder = builder.header(name.as_str(), value.as_bytes());
+    }
+    // handlers of raw responses choose their own content type
+    if parts.status != hyper::StatusCode::NO_CONTENT
+        && !parts.headers.contains_key(hyper::header::CONTENT_TYPE)
+    {
+        builder = builder.header(axum::http::header::CONTENT_TYPE, "application/json");
+    }
+
+    builder
+        .body(axum::body::Body::from_stream(
+            body.map_ok(|bytes| bytes.to_vec()),
+        ))
+        .expect("parts of a valid response form a valid response")
+}
@@ -0,0 +1,126 @@
+//! `SERVER` Response compression, negotiated using the request's `Accept-Encoding` header.
+//!
+//! Only available with the `compression` feature.
+
+use flate2::write::{GzEncoder, ZlibEncoder};
+use flate2::Compression;
+use hyper::body::HttpBody;
+use hyper::header::{self, HeaderValue};
+use hyper::{Body, Request, Response};
+
+use std::io::Write;
+
+/// Bodies smaller than this are sent uncompressed, compressing them does not pay off.
+const MIN_COMPRESSED_BODY_SIZE: u64 = 1024;
+
+/// A content coding supported for responses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Encoding {
+    Gzip,
+    Deflate,
+}
+
+impl Encoding {
+    fn name(self) -> &'static str {
+        match self {
+            Encoding::Gzip => "gzip",
+            Encoding::Deflate => "deflate",
+        }
+    }
+
+    /// The encoding preferred by the client that sent `req`, if it accepts any.
+    pub(super) fn negotiate(req: &Request<Body>) -> Option<Encoding> {
+        let accept_encoding = req
+            .headers()
+            .get_all(header::ACCEPT_ENCODING)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','));
+
+        let mut gzip = None;
+        let mut deflate = None;
+        let mut any = None;
+        for coding in accept_encoding {
+            let mut params = coding.split(';');
+            let name = params.next().unwrap_or("").trim().to_ascii_lowercase();
+            let quality = params
+                .filter_map(|param| param.trim().strip_prefix("q="))
+                .find_map(|q| q.trim().parse::<f32>().ok())
+                .unwrap_or(1.0);
+            match name.as_str() {
+                "gzip" | "x-gzip" => gzip = Some(quality),
+                "deflate" => deflate = Some(quality),
+                "*" => any = Some(quality),
+                _ => {}
+            }
+        }
+
+        // codings that are not listed explicitly are accepted with the quality of `*`
+        let gzip = gzip.or(any).unwrap_or(0.0);
+        let deflate = deflate.or(any).unwrap_or(0.0);
+        if gzip > 0.0 && gzip >= deflate {
+            Some(Encoding::Gzip)
+        } else if deflate > 0.0 {
+            Some(Encoding::Deflate)
+        } else {
+            None
+        }
+    }
+}
+
+/// Compress the body of `response` using `encoding` (if any), unless it is too small,
+/// streamed or already encoded.
+pub(super) async fn compress_response(
+    response: Response<Body>,
+    encoding: Option<Encoding>,
+) -> Response<Body> {
+    let (mut parts, body) = response.into_parts();
+    // the response varies with `Accept-Encoding` whether it is compressed or not
+    parts
+        .headers
+        .append(header::VARY, HeaderValue::from_static("Accept-Encoding"));
+
+    let encoding = match encoding {
+        Some(encoding) => encoding,
+        None => return Response::from_parts(parts, body),
+    };
+    // only bodies produced completely have an exact size, streams are sent as they are
+    let size = body.size_hint().exact();
+    if parts.headers.contains_key(header::CONTENT_ENCODING)
+        || !matches!(size, Some(size) if size >= MIN_COMPRESSED_BODY_SIZE)
+    {
+        return Response::from_parts(parts, body);
+    }
+
+    let bytes = match hyper::body::to_bytes(body).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            tracing::error!(error = ?e, "cannot read response body for compression");
+            return Response::from_parts(parts, Body::empty());
+        }
+    };
+    let compressed = match encoding {
+        Encoding::Gzip => {
+            let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+            encoder.write_all(&bytes).and_then(|()| encoder.finish())
+        }
+        Encoding::Deflate => {
+            let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+            encoder.write_all(&bytes).and_then(|()| encoder.finish())
+        }
+    };
+    match compressed {
+        Ok(compressed) => {
+            parts.headers.insert(
+                header::CONTENT_ENCODING,
+                HeaderValue::from_static(encoding.name()),
+            );
+            parts.headers.remove(header::CONTENT_LENGTH);
+            Response::from_parts(parts, Body::from(compressed))
+        }
+        Err(e) => {
+            tracing::error!(error = ?e, "cannot compress response body");
+            Response::from_parts(parts, Body::from(bytes))
+        }
+    }
+}
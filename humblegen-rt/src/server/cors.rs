@@ -0,0 +1,149 @@
+//! `SERVER` Cross-Origin Resource Sharing (CORS) for the humblegen HTTP service server.
+//!
+//! Browsers only let a web app access a service served from another origin if the service
+//! allows it using `Access-Control-Allow-*` response headers. For requests other than simple
+//! `GET`s, browsers ask for permission first using a "preflight" `OPTIONS` request.
+
+use hyper::header::{self, HeaderMap, HeaderName, HeaderValue};
+use hyper::{Body, Method, Request, Response, StatusCode};
+
+use std::time::Duration;
+
+/// The origins allowed to access the services.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AllowedOrigins {
+    /// Any origin.
+    Any,
+    /// Only the listed origins, e.g. `https://app.example.com`.
+    List(Vec<String>),
+}
+
+/// Configuration of Cross-Origin Resource Sharing, see `Builder::cors`.
+///
+/// The default allows any origin to use the `GET`, `POST`, `PUT`, `PATCH` and `DELETE` methods
+/// with the `Content-Type` and `Authorization` headers, without credentials.
+#[derive(Debug, Clone)]
+pub struct CorsConfig {
+    /// The origins allowed to access the services.
+    pub allowed_origins: AllowedOrigins,
+    /// The methods that cross-origin requests may use.
+    pub allowed_methods: Vec<Method>,
+    /// The request headers that cross-origin requests may send.
+    pub allowed_headers: Vec<HeaderName>,
+    /// Whether cross-origin requests may include credentials (cookies, HTTP authentication).
+    pub allow_credentials: bool,
+    /// How long browsers may cache the result of a preflight request.
+    pub max_age: Option<Duration>,
+}
+
+impl Default for CorsConfig {
+    fn default() -> Self {
+        Self {
+            allowed_origins: AllowedOrigins::Any,
+            allowed_methods: vec![
+                Method::GET,
+                Method::POST,
+                Method::PUT,
+                Method::PATCH,
+                Method::DELETE,
+            ],
+            allowed_headers: vec![header::CONTENT_TYPE, header::AUTHORIZATION],
+            allow_credentials: false,
+            max_age: None,
+        }
+    }
+}
+
+impl CorsConfig {
+    /// Whether `req` is a preflight request, which is answered without dispatching it.
+    pub(super) fn is_preflight(req: &Request<Body>) -> bool {
+        req.method() == Method::OPTIONS
+            && req.headers().contains_key(header::ORIGIN)
+            && req
+                .headers()
+                .contains_key(header::ACCESS_CONTROL_REQUEST_METHOD)
+    }
+
+    /// The response to the preflight request `req`.
+    ///
+    /// Requests from origins that are not allowed are answered without `Access-Control-Allow-*`
+    /// headers, which makes the browser fail the actual request.
+    pub(super) fn preflight_response(&self, req: &Request<Body>) -> Response<Body> {
+        let mut response = Response::new(Body::empty());
+        *response.status_mut() = StatusCode::NO_CONTENT;
+
+        let headers = response.headers_mut();
+        if !self.allow_origin(req, headers) {
+            return response;
+        }
+        headers.insert(
+            header::ACCESS_CONTROL_ALLOW_METHODS,
+            join_header_values(self.allowed_methods.iter().map(Method::as_str)),
+        );
+        if !self.allowed_headers.is_empty() {
+            headers.insert(
+                header::ACCESS_CONTROL_ALLOW_HEADERS,
+                join_header_values(self.allowed_headers.iter().map(HeaderName::as_str)),
+            );
+        }
+        if let Some(max_age) = self.max_age {
+            headers.insert(header::ACCESS_CONTROL_MAX_AGE, max_age.as_secs().into());
+        }
+        response
+    }
+
+    /// The CORS headers of the response to the (non-preflight) request `req`.
+    ///
+    /// `exposed_header` is made readable for the web app, i.e. the request ID header.
+    pub(super) fn response_headers(
+        &self,
+        req: &Request<Body>,
+        exposed_header: &HeaderName,
+    ) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        if self.allow_origin(req, &mut headers) {
+            headers.insert(
+                header::ACCESS_CONTROL_EXPOSE_HEADERS,
+                HeaderValue::from_str(exposed_header.as_str())
+                    .expect("header names are valid header values"),
+            );
+        }
+        headers
+    }
+
+    /// Insert the `Access-Control-Allow-Origin` (and `-Credentials`) header into `headers`
+    /// if the origin of `req` is allowed, returning whether it is.
+    fn allow_origin(&self, req: &Request<Body>, headers: &mut HeaderMap) -> bool {
+        // the response depends on the origin unless any origin gets the same `*`
+        if self.allowed_origins != AllowedOrigins::Any || self.allow_credentials {
+            headers.insert(header::VARY, HeaderValue::from_static("Origin"));
+        }
+
+        let origin = match req.headers().get(header::ORIGIN) {
+            Some(origin) => origin,
+            None => return false,
+        };
+        let allow_origin = match &self.allowed_origins {
+            // browsers reject the wildcard for requests with credentials
+            AllowedOrigins::Any if !self.allow_credentials => HeaderValue::from_static("*"),
+            AllowedOrigins::Any => origin.clone(),
+            AllowedOrigins::List(origins) if origins.iter().any(|o| origin == o.as_str()) => {
+                origin.clone()
+            }
+            AllowedOrigins::List(_) => return false,
+        };
+        headers.insert(header::ACCESS_CONTROL_ALLOW_ORIGIN, allow_origin);
+        if self.allow_credentials {
+            headers.insert(
+                header::ACCESS_CONTROL_ALLOW_CREDENTIALS,
+                HeaderValue::from_static("true"),
+            );
+        }
+        true
+    }
+}
+
+fn join_header_values<'a>(values: impl Iterator<Item = &'a str>) -> HeaderValue {
+    HeaderValue::from_str(&values.collect::<Vec<_>>().join(", "))
+        .expect("methods and header names are valid header values")
+}
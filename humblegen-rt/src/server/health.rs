@@ -0,0 +1,68 @@
+//! `SERVER` Health checks for orchestrators like Kubernetes, served independently of the
+//! services.
+//!
+//! * `GET /healthz` (liveness) always succeeds while the server is running.
+//! * `GET /readyz` (readiness) succeeds if the readiness closure reports the server as ready.
+
+use futures::future::BoxFuture;
+use futures::FutureExt;
+use hyper::{Body, Method, Request, Response, StatusCode};
+
+use std::fmt;
+use std::future::Future;
+use std::sync::Arc;
+
+const LIVENESS_PATH: &str = "/healthz";
+const READINESS_PATH: &str = "/readyz";
+
+/// Configuration of the health check endpoints, see `Builder::with_health_checks`.
+#[derive(Clone)]
+pub struct HealthChecks {
+    readiness: Arc<dyn Fn() -> BoxFuture<'static, bool> + Send + Sync>,
+}
+
+impl fmt::Debug for HealthChecks {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("HealthChecks")
+            .field("readiness", &"<closure>")
+            .finish()
+    }
+}
+
+impl HealthChecks {
+    /// Health checks whose readiness is determined by `readiness`, which is invoked on every
+    /// readiness probe.
+    pub fn new<F, Fut>(readiness: F) -> Self
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = bool> + Send + 'static,
+    {
+        Self {
+            readiness: Arc::new(move || readiness().boxed()),
+        }
+    }
+
+    /// Whether `req` is a health check, which is answered without dispatching it.
+    pub(super) fn is_probe(req: &Request<Body>) -> bool {
+        (req.method() == Method::GET || req.method() == Method::HEAD)
+            && (req.uri().path() == LIVENESS_PATH || req.uri().path() == READINESS_PATH)
+    }
+
+    /// The response to the health check `req`: 200 if the server is alive (ready),
+    /// 503 if it is not ready.
+    pub(super) async fn probe_response(&self, req: &Request<Body>) -> Response<Body> {
+        let ready = req.uri().path() == LIVENESS_PATH || (self.readiness)().await;
+        let (status, body) = if ready {
+            (StatusCode::OK, r#"{"status":"ok"}"#)
+        } else {
+            (
+                StatusCode::SERVICE_UNAVAILABLE,
+                r#"{"status":"unavailable"}"#,
+            )
+        };
+        let mut response = Response::new(Body::from(body));
+        *response.status_mut() = status;
+        response
+    }
+}
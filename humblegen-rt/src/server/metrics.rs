@@ -0,0 +1,127 @@
+//! `SERVER` Request metrics in the Prometheus text format, served at the path set using
+//! `Builder::serve_metrics` in generated code.
+//!
+//! The metrics are registered in the default registry of the `prometheus` crate (re-exported as
+//! `humblegen_rt::prometheus`), so that metrics registered by the application are served, too.
+//!
+//! Only available with the `metrics` feature.
+
+use hyper::header::{self, HeaderValue};
+use hyper::{Body, Method, Request, Response, StatusCode};
+use prometheus::{Encoder, HistogramVec, IntCounterVec, TextEncoder};
+
+use crate::regexset_map::{GetResult, RegexSetMap};
+
+use std::time::Duration;
+
+lazy_static::lazy_static! {
+    static ref REQUESTS: IntCounterVec = prometheus::register_int_counter_vec!(
+        "humblegen_http_requests_total",
+        "Number of HTTP requests answered, by route and status code.",
+        &["service", "route", "method", "status"]
+    )
+    .expect("metric is registered once");
+    static ref REQUEST_DURATION: HistogramVec = prometheus::register_histogram_vec!(
+        "humblegen_http_request_duration_seconds",
+        "Time until the response to an HTTP request was ready to be sent, by route.",
+        &["service", "route", "method"]
+    )
+    .expect("metric is registered once");
+}
+
+/// Whether `req` is a scrape of the metrics served at `metrics_path`, which is answered without
+/// dispatching it. Never true if the metrics are not served, i.e. `metrics_path` is `None`.
+pub(super) fn is_scrape(req: &Request<Body>, metrics_path: Option<&str>) -> bool {
+    req.method() == Method::GET && Some(req.uri().path()) == metrics_path
+}
+
+/// Check that no route of `services` matches `GET` requests for `options.metrics_path`.
+pub(super) fn check_path(
+    services: &RegexSetMap<Request<Body>, super::Service>,
+    options: &super::ServerOptions,
+) -> Result<(), super::BuilderError> {
+    let path = match &options.metrics_path {
+        Some(path) => path,
+        None => return Ok(()),
+    };
+    let req = Request::get(path.as_str())
+        .body(Body::empty())
+        .expect("metrics path is a valid URI");
+    if let GetResult::One(service) = services.get(path, &req) {
+        let (root, routes) = &service.0;
+        let captures = root.captures(path).expect("service regex matches");
+        let (route, _) = super::match_route(
+            routes,
+            &captures["suffix"],
+            &req,
+            options.ignore_trailing_slash,
+        );
+        if !matches!(route, GetResult::None) {
+            return Err(super::BuilderError::MetricsPathConflict {
+                path: path.clone(),
+                root: captures["root"].to_owned(),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// The response to a scrape, all metrics of the default registry.
+pub(super) fn scrape_response() -> Response<Body> {
+    let encoder = TextEncoder::new();
+    let mut body = Vec::new();
+    if let Err(e) = encoder.encode(&prometheus::gather(), &mut body) {
+        tracing::error!(error = ?e, "cannot encode metrics");
+        let mut response = Response::new(Body::empty());
+        *response.status_mut() = StatusCode::INTERNAL_SERVER_ERROR;
+        return response;
+    }
+    let mut response = Response::new(Body::from(body));
+    response.headers_mut().insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_str(encoder.format_type()).expect("format is a valid header value"),
+    );
+    response
+}
+
+/// Record an answered request. Requests that were not dispatched to a route are recorded with
+/// an empty `service` and `route`.
+pub(super) fn record(
+    method: &Method,
+    status: StatusCode,
+    matched_route: Option<&super::MatchedRoute>,
+    duration: Duration,
+) {
+    let (service, route) = matched_route
+        .map(|matched| (matched.service.as_str(), matched.route.as_str()))
+        .unwrap_or(("", ""));
+    REQUESTS
+        .with_label_values(&[service, route, method.as_str(), status.as_str()])
+        .inc();
+    REQUEST_DURATION
+        .with_label_values(&[service, route, method.as_str()])
+        .observe(duration.as_secs_f64());
+}
+
+/// The `serve_metrics` method of the `Builder` in generated code, which cannot refer to the
+/// `metrics` feature of `humblegen_rt`. Expands to nothing without the feature.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __builder_metrics_methods {
+    () => {
+        /// Serves the recorded metrics in the Prometheus text format at `GET path`, e.g.
+        /// `/metrics` (disabled by default). They are matched before any service, building the
+        /// server fails with `BuilderError::MetricsPathConflict` if a route matches them, too.
+        ///
+        /// Panics if `path` does not start with a `/`.
+        pub fn serve_metrics(mut self, path: &str) -> Self {
+            assert!(
+                path.starts_with('/'),
+                "metrics path must start with \"/\", but is {:?}",
+                path
+            );
+            self.options.metrics_path = Some(path.to_owned());
+            self
+        }
+    };
+}
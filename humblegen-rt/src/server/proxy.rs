@@ -0,0 +1,130 @@
+//! Forwarding of the requests to `PROXY` routes to their upstream server.
+
+use super::{Route, RouteKind, ServerOptions};
+use crate::service_protocol::{ErrorResponse, RuntimeError, ToErrorResponse};
+
+use hyper::client::HttpConnector;
+use hyper::header::{self, HeaderMap, HeaderName, HeaderValue};
+use hyper::{Body, Request, Response};
+use std::sync::Arc;
+
+/// Headers that only apply to a single connection and are thus not forwarded, see
+/// [RFC 7230, section 6.1](https://tools.ietf.org/html/rfc7230#section-6.1).
+const HOP_BY_HOP_HEADERS: &[&str] = &[
+    "connection",
+    "keep-alive",
+    "proxy-authenticate",
+    "proxy-authorization",
+    "proxy-connection",
+    "te",
+    "trailer",
+    "transfer-encoding",
+    "upgrade",
+];
+
+/// The method of proxy routes, which match requests of any method.
+pub(super) fn proxy_method() -> hyper::Method {
+    hyper::Method::from_bytes(b"PROXY").unwrap()
+}
+
+/// Creates the route of `PROXY /prefix/* -> "upstream"` with the given `template`, e.g.
+/// `/legacy/*`. It matches `/legacy` and all paths below it, forwarding them to `upstream`
+/// with the part after `/legacy` appended to its path.
+///
+/// Invoked by generated code.
+pub fn proxy_route(service: &'static str, template: &'static str, upstream: &'static str) -> Route {
+    let prefix = template.strip_suffix("/*").unwrap_or(template);
+    let regex = regex::Regex::new(&format!("^{}(?P<rest>/.*)?$", regex::escape(prefix)))
+        .expect("proxy route regex is valid");
+    let client = hyper::Client::new();
+    Route {
+        method: proxy_method(),
+        regex,
+        service,
+        template,
+        kind: RouteKind::Proxy,
+        dispatcher: Box::new(move |req, captures, options| {
+            let rest = captures.name("rest").map_or("", |m| m.as_str()).to_owned();
+            // the response future of the client is not `Sync`, thus it is polled by its own task
+            let forwarded = tokio::spawn(forward(client.clone(), upstream, rest, req, options));
+            Box::pin(async move {
+                forwarded.await.unwrap_or_else(|e| {
+                    Err(RuntimeError::UpstreamUnavailable {
+                        upstream: upstream.to_owned(),
+                        error: e.to_string(),
+                    }
+                    .to_error_response())
+                })
+            })
+        }),
+    }
+}
+
+/// Forward `req` to `upstream`, appending `rest` to its path, and stream back the response.
+async fn forward(
+    client: hyper::Client<HttpConnector>,
+    upstream: &'static str,
+    rest: String,
+    req: Request<Body>,
+    options: Arc<ServerOptions>,
+) -> Result<Response<Body>, ErrorResponse> {
+    let unavailable = |error: String| {
+        tracing::error!(upstream, error = error.as_str(), "upstream unavailable");
+        RuntimeError::UpstreamUnavailable {
+            upstream: upstream.to_owned(),
+            error,
+        }
+        .to_error_response()
+    };
+
+    let (mut parts, body) = req.into_parts();
+    let uri = match parts.uri.query() {
+        Some(query) => format!("{}{}?{}", upstream, rest, query),
+        None => format!("{}{}", upstream, rest),
+    };
+    parts.uri = uri
+        .parse()
+        .map_err(|e: hyper::http::uri::InvalidUri| unavailable(e.to_string()))?;
+    let original_host = parts.headers.remove(header::HOST);
+    remove_hop_by_hop_headers(&mut parts.headers);
+    if let Some(host) = original_host {
+        parts
+            .headers
+            .insert(HeaderName::from_static("x-forwarded-host"), host);
+    }
+    tracing::debug!(uri = uri.as_str(), "forwarding request to upstream");
+
+    let timeout = options.proxy_timeout;
+    let response = tokio::time::timeout(timeout, client.request(Request::from_parts(parts, body)))
+        .await
+        .map_err(|_| {
+            tracing::error!(upstream, "upstream timed out");
+            RuntimeError::UpstreamTimeout {
+                upstream: upstream.to_owned(),
+                timeout_ms: timeout.as_millis() as u64,
+            }
+            .to_error_response()
+        })?
+        .map_err(|e| unavailable(e.to_string()))?;
+
+    let (mut parts, body) = response.into_parts();
+    remove_hop_by_hop_headers(&mut parts.headers);
+    Ok(Response::from_parts(parts, body))
+}
+
+/// Removes the hop-by-hop headers, including those listed in the `Connection` header.
+fn remove_hop_by_hop_headers(headers: &mut HeaderMap<HeaderValue>) {
+    let listed: Vec<HeaderName> = headers
+        .get_all(header::CONNECTION)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .filter_map(|name| HeaderName::from_bytes(name.trim().as_bytes()).ok())
+        .collect();
+    for name in listed {
+        headers.remove(name);
+    }
+    for name in HOP_BY_HOP_HEADERS {
+        headers.remove(*name);
+    }
+}
@@ -0,0 +1,106 @@
+//! `SERVER` Static files served from a directory, e.g. the HTML and JavaScript of a single-page
+//! application, looked up before the services are matched.
+
+use hyper::{Body, Method, Request, Response, StatusCode};
+use percent_encoding::percent_decode_str;
+
+use std::path::{Component, Path, PathBuf};
+
+/// A directory whose files are served below a URL path prefix, see `Builder::serve_static`.
+#[derive(Debug, Clone)]
+pub struct StaticFiles {
+    /// The path prefix without trailing slash, empty for `/`.
+    prefix: String,
+    dir: PathBuf,
+}
+
+impl StaticFiles {
+    /// Serve the files in `dir` below the URL path `prefix`.
+    ///
+    /// Panics if `prefix` does not start with a `/`.
+    pub fn new(prefix: &str, dir: impl Into<PathBuf>) -> Self {
+        assert!(
+            prefix.starts_with('/'),
+            "static file path prefix {:?} must start with a `/`",
+            prefix
+        );
+        Self {
+            prefix: prefix.trim_end_matches('/').to_owned(),
+            dir: dir.into(),
+        }
+    }
+
+    /// Whether `path` is the prefix or below it.
+    fn contains(&self, path: &str) -> bool {
+        match path.strip_prefix(&self.prefix) {
+            Some(rest) => rest.is_empty() || rest.starts_with('/'),
+            None => false,
+        }
+    }
+
+    /// The file `path` refers to, `None` if it is not below the prefix or refers to a file
+    /// outside of the directory. Paths of directories refer to their `index.html`.
+    fn file_path(&self, path: &str) -> Option<PathBuf> {
+        let relative = match path.strip_prefix(&self.prefix)? {
+            "" => "",
+            rest => rest.strip_prefix('/')?,
+        };
+        let mut file_path = self.dir.clone();
+        for segment in relative.split('/').filter(|segment| !segment.is_empty()) {
+            let segment = percent_decode_str(segment).decode_utf8().ok()?;
+            // guard against path traversal: each segment must be a plain file name,
+            // not `..`, a root or a path of several components
+            let mut components = Path::new(segment.as_ref()).components();
+            match (components.next(), components.next()) {
+                (Some(Component::Normal(name)), None) => file_path.push(name),
+                _ => return None,
+            }
+        }
+        if relative.is_empty() || relative.ends_with('/') {
+            file_path.push("index.html");
+        }
+        Some(file_path)
+    }
+
+    /// The response serving the file requested by `req`, `None` if there is no such file.
+    async fn response(&self, req: &Request<Body>) -> Option<Response<Body>> {
+        let file_path = self.file_path(req.uri().path())?;
+        // fails for missing files and directories alike
+        let contents = tokio::fs::read(&file_path).await.ok()?;
+        let content_type = mime_guess::from_path(&file_path).first_or_octet_stream();
+        Some(
+            Response::builder()
+                .header(hyper::header::CONTENT_TYPE, content_type.as_ref())
+                .body(Body::from(contents))
+                .expect("static file responses must always be buildable"),
+        )
+    }
+}
+
+/// The response to a `GET` (or `HEAD`) request `req` below the path prefix of any of
+/// `static_files`: the file from the first of them that contains it, or status code 404 if none
+/// does. `None` for other requests, which are dispatched to the services.
+pub(super) async fn static_file_response(
+    static_files: &[StaticFiles],
+    req: &Request<Body>,
+) -> Option<Response<Body>> {
+    if req.method() != Method::GET && req.method() != Method::HEAD {
+        return None;
+    }
+    let mut below_prefix = false;
+    for files in static_files {
+        if !files.contains(req.uri().path()) {
+            continue;
+        }
+        below_prefix = true;
+        if let Some(response) = files.response(req).await {
+            return Some(response);
+        }
+    }
+    if !below_prefix {
+        return None;
+    }
+    let mut response = Response::new(Body::empty());
+    *response.status_mut() = StatusCode::NOT_FOUND;
+    Some(response)
+}
@@ -0,0 +1,150 @@
+//! `GEN` TLS termination for the humblegen HTTP service server, based on
+//! [`tokio-rustls`](https://docs.rs/tokio-rustls).
+//!
+//! Only available with the `tls` feature.
+
+use super::HttpService;
+
+use anyhow::Context;
+use futures::StreamExt;
+use tokio_rustls::rustls;
+
+use std::convert::Infallible;
+use std::io::BufReader;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// The TLS configuration of a server started with `listen_and_run_forever_tls`.
+#[derive(Clone)]
+pub enum TlsConfig {
+    /// A ready-made rustls server configuration.
+    ServerConfig(Arc<rustls::ServerConfig>),
+    /// A PEM encoded certificate chain and a PEM encoded private key (PKCS#8 or RSA).
+    PemFiles {
+        cert_path: PathBuf,
+        key_path: PathBuf,
+    },
+}
+
+impl TlsConfig {
+    pub fn from_pem_files<C: Into<PathBuf>, K: Into<PathBuf>>(cert_path: C, key_path: K) -> Self {
+        TlsConfig::PemFiles {
+            cert_path: cert_path.into(),
+            key_path: key_path.into(),
+        }
+    }
+
+    fn into_server_config(self) -> anyhow::Result<Arc<rustls::ServerConfig>> {
+        let (cert_path, key_path) = match self {
+            TlsConfig::ServerConfig(config) => return Ok(config),
+            TlsConfig::PemFiles {
+                cert_path,
+                key_path,
+            } => (cert_path, key_path),
+        };
+
+        let certs = {
+            let file = std::fs::File::open(&cert_path)
+                .with_context(|| format!("cannot open certificate file {:?}", cert_path))?;
+            rustls::internal::pemfile::certs(&mut BufReader::new(file))
+                .ok()
+                .filter(|certs| !certs.is_empty())
+                .with_context(|| format!("no PEM certificates in {:?}", cert_path))?
+        };
+
+        let key = {
+            let pem = std::fs::read(&key_path)
+                .with_context(|| format!("cannot read private key file {:?}", key_path))?;
+            let pkcs8 = rustls::internal::pemfile::pkcs8_private_keys(&mut &pem[..]);
+            let rsa = rustls::internal::pemfile::rsa_private_keys(&mut &pem[..]);
+            pkcs8
+                .into_iter()
+                .chain(rsa)
+                .flatten()
+                .next()
+                .with_context(|| format!("no PEM private key in {:?}", key_path))?
+        };
+
+        let mut config = rustls::ServerConfig::new(rustls::NoClientAuth::new());
+        config
+            .set_single_cert(certs, key)
+            .context("invalid certificate or private key")?;
+        Ok(Arc::new(config))
+    }
+}
+
+impl From<rustls::ServerConfig> for TlsConfig {
+    fn from(config: rustls::ServerConfig) -> Self {
+        TlsConfig::ServerConfig(Arc::new(config))
+    }
+}
+
+impl From<Arc<rustls::ServerConfig>> for TlsConfig {
+    fn from(config: Arc<rustls::ServerConfig>) -> Self {
+        TlsConfig::ServerConfig(config)
+    }
+}
+
+/// Serve `service` via HTTPS, binding to the given `addr`.
+/// Connections that fail the TLS handshake are logged and dropped.
+///
+/// Invoked by generated code.
+pub async fn listen_and_run_forever_tls(
+    service: HttpService,
+    addr: &SocketAddr,
+    tls_config: TlsConfig,
+) -> anyhow::Result<()> {
+    let acceptor = tokio_rustls::TlsAcceptor::from(tls_config.into_server_config()?);
+    let mut listener = tokio::net::TcpListener::bind(addr)
+        .await
+        .with_context(|| format!("cannot bind to {}", addr))?;
+
+    let connections = listener.incoming().filter_map(move |tcp| {
+        let acceptor = acceptor.clone();
+        async move {
+            let tcp = tcp
+                .map_err(|e| tracing::error!(error = ?e, "cannot accept connection"))
+                .ok()?;
+            acceptor
+                .accept(tcp)
+                .await
+                .map_err(|e| tracing::debug!(error = ?e, "TLS handshake failed"))
+                .ok()
+                .map(Ok::<_, Infallible>)
+        }
+    });
+
+    let server = hyper::Server::builder(hyper::server::accept::from_stream(connections)).serve(
+        hyper::service::make_service_fn(move |_conn| {
+            futures::future::ok::<_, Infallible>(service.clone())
+        }),
+    );
+
+    server.await.context("server error")?;
+    Ok(())
+}
+
+/// Expands to the generated `Builder::listen_and_run_forever_tls`.
+/// Generated code cannot check the features of `humblegen_rt`, thus the
+/// method is defined here and expands to nothing without the `tls` feature.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __builder_tls_methods {
+    () => {
+        /// Like `listen_and_run_forever`, but terminates TLS using `tls_config`, i.e. either a
+        /// rustls `ServerConfig` or `TlsConfig::from_pem_files(cert_path, key_path)`.
+        pub async fn listen_and_run_forever_tls(
+            self,
+            addr: &::std::net::SocketAddr,
+            tls_config: impl Into<$crate::server::TlsConfig>,
+        ) -> $crate::anyhow::Result<()> {
+            $crate::server::listen_and_run_forever_tls(
+                self.into_service()?,
+                addr,
+                tls_config.into(),
+            )
+            .await
+        }
+    };
+}
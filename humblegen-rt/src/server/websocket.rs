@@ -0,0 +1,157 @@
+//! `SERVER` WebSocket endpoints (`WS`), which push messages to the client over a WebSocket.
+//!
+//! Only available with the `websocket` feature.
+
+use crate::handler::{HandlerResponse, WebSocketResponse};
+use crate::service_protocol::{self, ErrorResponse, RuntimeError, ToErrorResponse};
+
+use futures::{SinkExt, StreamExt};
+use hyper::header::{self, HeaderValue};
+use hyper::{Body, Request, Response, StatusCode};
+use sha1::{Digest, Sha1};
+use tokio_tungstenite::tungstenite::protocol::{Message, Role};
+use tokio_tungstenite::WebSocketStream;
+use tracing_futures::Instrument;
+
+/// Appended to the client's key to compute the `Sec-WebSocket-Accept` header (RFC 6455).
+const WEBSOCKET_GUID: &[u8] = b"258EAFA5-E914-47DA-95CA-C5AB0DC85B11";
+
+/// A valid WebSocket handshake whose connection is upgraded once the handler accepts it.
+///
+/// Created by `accept_websocket`.
+pub struct WebSocketUpgrade {
+    accept_key: String,
+    on_upgrade: hyper::upgrade::OnUpgrade,
+}
+
+/// Check the WebSocket handshake of `req`, taking its body to upgrade the connection later.
+/// Invoked from generated code within a `DispatcherClosure`.
+pub fn accept_websocket(req: &mut Request<Body>) -> Result<WebSocketUpgrade, ErrorResponse> {
+    let invalid = |reason: &str| {
+        RuntimeError::WebSocketHandshakeInvalid(reason.to_owned()).to_error_response()
+    };
+
+    let version = req.headers().get(header::SEC_WEBSOCKET_VERSION);
+    if version.map(HeaderValue::as_bytes) != Some(b"13") {
+        return Err(invalid(
+            "unsupported or missing Sec-WebSocket-Version, expected 13",
+        ));
+    }
+    let key = req
+        .headers()
+        .get(header::SEC_WEBSOCKET_KEY)
+        .ok_or_else(|| invalid("missing Sec-WebSocket-Key"))?;
+
+    let mut sha1 = Sha1::default();
+    sha1.update(key.as_bytes());
+    sha1.update(WEBSOCKET_GUID);
+    let accept_key = base64::encode(sha1.finalize());
+
+    let body = std::mem::replace(req.body_mut(), Body::empty());
+    Ok(WebSocketUpgrade {
+        accept_key,
+        on_upgrade: body.on_upgrade(),
+    })
+}
+
+/// Conversion of a `HandlerResponse` for a `WS` endpoint to a hyper response.
+/// Invoked from generated code within a `DispatcherClosure`.
+///
+/// If the handler accepts the connection, the response switches protocols and the WebSocket
+/// is served by a new task.
+pub fn websocket_handler_response_to_hyper_response<T>(
+    upgrade: WebSocketUpgrade,
+    handler_response: HandlerResponse<WebSocketResponse<T>>,
+) -> Response<Body>
+where
+    T: serde::Serialize + Send + 'static,
+{
+    match handler_response {
+        Ok(websocket) => {
+            let WebSocketUpgrade {
+                accept_key,
+                on_upgrade,
+            } = upgrade;
+            tokio::spawn(
+                serve_websocket(on_upgrade, websocket)
+                    .instrument(tracing::error_span!("websocket")),
+            );
+            Response::builder()
+                .status(StatusCode::SWITCHING_PROTOCOLS)
+                .header(header::CONNECTION, "upgrade")
+                .header(header::UPGRADE, "websocket")
+                .header(header::SEC_WEBSOCKET_ACCEPT, accept_key)
+                .body(Body::empty())
+                .expect("WebSocket upgrade responses must always be buildable")
+        }
+        Err(e) => {
+            tracing::error!(error = ?e, "handler returned error");
+            service_protocol::ServiceError::from(e)
+                .to_error_response()
+                .to_hyper_response()
+        }
+    }
+}
+
+/// Push the handler's messages to the client and feed the client's messages into the
+/// handler's sink, until either side is done.
+async fn serve_websocket<T>(on_upgrade: hyper::upgrade::OnUpgrade, websocket: WebSocketResponse<T>)
+where
+    T: serde::Serialize + Send + 'static,
+{
+    let upgraded = match on_upgrade.await {
+        Ok(upgraded) => upgraded,
+        Err(e) => {
+            tracing::error!(error = ?e, "cannot upgrade connection to WebSocket");
+            return;
+        }
+    };
+    let socket = WebSocketStream::from_raw_socket(upgraded, Role::Server, None).await;
+    let (mut sender, mut receiver) = socket.split();
+    let WebSocketResponse {
+        mut outgoing,
+        mut incoming,
+    } = websocket;
+
+    let send = async move {
+        while let Some(message) = outgoing.next().await {
+            let text = match serde_json::to_string(&message) {
+                Ok(text) => text,
+                Err(e) => {
+                    tracing::error!(error = ?e, "cannot serialize WebSocket message");
+                    break;
+                }
+            };
+            if let Err(e) = sender.send(Message::Text(text)).await {
+                tracing::debug!(error = ?e, "cannot send WebSocket message");
+                return;
+            }
+        }
+        // the handler has no more messages, close the connection
+        if let Err(e) = sender.close().await {
+            tracing::debug!(error = ?e, "cannot close WebSocket");
+        }
+    };
+    let receive = async move {
+        while let Some(message) = receiver.next().await {
+            match message {
+                Ok(Message::Text(text)) => {
+                    if let Err(e) = incoming.send(text).await {
+                        tracing::error!(error = ?e, "handler rejected WebSocket message");
+                        break;
+                    }
+                }
+                // pings are answered by tungstenite, binary messages are not supported
+                Ok(Message::Close(_)) => break,
+                Ok(_) => {}
+                Err(e) => {
+                    tracing::debug!(error = ?e, "cannot receive WebSocket message");
+                    break;
+                }
+            }
+        }
+    };
+
+    futures::future::select(Box::pin(send), Box::pin(receive)).await;
+    tracing::debug!("WebSocket closed");
+}
@@ -42,6 +42,15 @@ pub enum ServiceError {
     /// Maps to HTTP status code 500.
     /// Examples: no database connection
     Internal(String),
+    /// The request cannot be fulfilled for a reason specific to the service that
+    /// is signalled through the HTTP status code, e.g. rate limiting.
+    /// Maps to HTTP status code `status` if it is a 4XX or 5XX status code, 500 otherwise.
+    /// `code` is a machine-readable identifier, `message` is meant for humans.
+    Custom {
+        status: u16,
+        code: String,
+        message: String,
+    },
 }
 
 /// Responses generated by humblegen-rt for conditions that are outside
@@ -61,10 +70,48 @@ pub enum RuntimeError {
         parse_error: String,
     },
     QueryInvalid(String),
+    HeaderMissing {
+        header_name: String,
+    },
+    HeaderInvalid {
+        header_name: String,
+        parse_error: String,
+    },
     PostBodyReadError(String),
-    PostBodyInvalid(String),
+    /// The request body is not valid JSON or does not match the type of the body.
+    /// `fields` lists the fields that could not be deserialized.
+    PostBodyInvalid {
+        message: String,
+        fields: Vec<FieldError>,
+    },
+    PayloadTooLarge {
+        max_body_size: usize,
+    },
     SerializeHandlerResponse(String),
     SerializeErrorResponse(String),
+    WebSocketHandshakeInvalid(String),
+    HandlerTimeout {
+        timeout_ms: u64,
+    },
+    /// The upstream server of a `PROXY` route cannot be reached or sent an invalid response.
+    UpstreamUnavailable {
+        upstream: String,
+        error: String,
+    },
+    /// The upstream server of a `PROXY` route did not respond within the proxy timeout.
+    UpstreamTimeout {
+        upstream: String,
+        timeout_ms: u64,
+    },
+}
+
+/// A field of a request body that could not be deserialized.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FieldError {
+    /// The path of the field within the body, e.g. `home.city` or `monsters[0].hp`.
+    pub field: String,
+    /// Why the field is invalid.
+    pub message: String,
 }
 
 impl ErrorResponse {
@@ -107,10 +154,17 @@ impl RuntimeError {
             RuntimeError::ServiceMountsAmbiguous => 500,
             RuntimeError::RouteParamInvalid { .. } => 400,
             RuntimeError::QueryInvalid(_) => 400,
+            RuntimeError::HeaderMissing { .. } => 400,
+            RuntimeError::HeaderInvalid { .. } => 400,
             RuntimeError::PostBodyReadError(_) => 400,
-            RuntimeError::PostBodyInvalid(_) => 400,
+            RuntimeError::PostBodyInvalid { .. } => 400,
+            RuntimeError::PayloadTooLarge { .. } => 413,
             RuntimeError::SerializeHandlerResponse(_) => 500,
             RuntimeError::SerializeErrorResponse(_) => 500,
+            RuntimeError::WebSocketHandshakeInvalid(_) => 400,
+            RuntimeError::HandlerTimeout { .. } => 504,
+            RuntimeError::UpstreamUnavailable { .. } => 502,
+            RuntimeError::UpstreamTimeout { .. } => 504,
         }
     }
 }
@@ -121,6 +175,8 @@ impl ServiceError {
             ServiceError::Authentication => 401,
             ServiceError::Authorization => 403,
             ServiceError::Internal(_) => 500,
+            ServiceError::Custom { status, .. } if (400..600).contains(status) => *status,
+            ServiceError::Custom { .. } => 500,
         }
     }
 }
@@ -131,6 +187,15 @@ impl From<super::handler::ServiceError> for ServiceError {
             super::handler::ServiceError::Authentication => ServiceError::Authentication,
             super::handler::ServiceError::Authorization => ServiceError::Authorization,
             super::handler::ServiceError::Internal(e) => ServiceError::Internal(format!("{}", e)),
+            super::handler::ServiceError::Custom {
+                status,
+                code,
+                message,
+            } => ServiceError::Custom {
+                status,
+                code,
+                message,
+            },
         }
     }
 }
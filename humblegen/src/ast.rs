@@ -1,25 +1,44 @@
 //! Humble language abstract syntax tree
 
+pub mod visit;
+
 /// A spec node.
 ///
 /// A spec is the top-level item in humble.
-#[derive(Debug)]
-pub struct Spec(pub Vec<SpecItem>);
+#[derive(Debug, Clone)]
+pub struct Spec {
+    /// The items of the spec, including those of imported files.
+    pub items: Vec<SpecItem>,
+    /// The title of the spec, set using `@title "..."` at the top of the (importing) file.
+    pub title: Option<String>,
+    /// The version of the API, set using `@version "..."` at the top of the (importing) file,
+    /// after the title.
+    pub version: Option<String>,
+}
 
 impl Spec {
     /// Iterate over items in spec.
     pub fn iter(&self) -> impl Iterator<Item = &SpecItem> {
-        self.0.iter()
+        self.items.iter()
     }
 
     /// Mutable iterator over items in spec.
     pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut SpecItem> {
-        self.0.iter_mut()
+        self.items.iter_mut()
+    }
+
+    /// Whether any endpoint is declared using `@paginated`, which requires the `Page` envelope
+    /// type to be generated.
+    pub fn has_paginated_endpoints(&self) -> bool {
+        self.iter()
+            .filter_map(SpecItem::service_def)
+            .flat_map(|service| &service.endpoints)
+            .any(|endpoint| endpoint.route.is_paginated())
     }
 }
 
 /// A Spec item node.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum SpecItem {
     /// `struct` definition.
     StructDef(StructDef),
@@ -30,6 +49,22 @@ pub enum SpecItem {
 }
 
 impl SpecItem {
+    /// The struct definition if `self` is a `StructDef`.
+    pub fn struct_def(&self) -> Option<&StructDef> {
+        match self {
+            SpecItem::StructDef(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// The enum definition if `self` is an `EnumDef`.
+    pub fn enum_def(&self) -> Option<&EnumDef> {
+        match self {
+            SpecItem::EnumDef(e) => Some(e),
+            _ => None,
+        }
+    }
+
     /// The service definition if `self` is a `ServiceDef`.
     pub fn service_def(&self) -> Option<&ServiceDef> {
         match self {
@@ -40,7 +75,7 @@ impl SpecItem {
 }
 
 /// A struct definition.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct StructDef {
     /// Name of the struct.
     pub name: String,
@@ -48,10 +83,178 @@ pub struct StructDef {
     pub fields: StructFields,
     /// Documentation comment.
     pub doc_comment: Option<String>,
+    /// Naming convention applied to field names on the wire, set using `@rename_all("...")`.
+    pub rename_all: Option<RenameRule>,
+    /// Additional traits to derive, set using `@derive(...)`.
+    pub derives: Vec<Derive>,
+    /// Whether embedded structs are kept as flattened fields instead of being inlined,
+    /// set using `@flatten_embeds`. Only affects backends that support it (Rust).
+    pub flatten_embeds: bool,
+    /// The struct whose fields are added to this one as optional fields, set using
+    /// `@patch_of(...)`. Already resolved by the parser, see `parser::patches`.
+    pub patch_of: Option<String>,
+    /// Note explaining what to use instead, if the struct is deprecated using
+    /// `@deprecated("...")`.
+    pub deprecated: Option<String>,
+    /// The location of the definition, including its doc comment and annotations.
+    pub span: Option<Span>,
+}
+
+/// A trait that can be derived for a user defined type in addition to the default ones.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Derive {
+    /// `PartialEq`
+    PartialEq,
+    /// `Eq`
+    Eq,
+    /// `PartialOrd`
+    PartialOrd,
+    /// `Ord`
+    Ord,
+    /// `Hash`
+    Hash,
+    /// `Default`
+    Default,
+}
+
+impl Derive {
+    /// All supported derives.
+    pub const ALL: [Derive; 6] = [
+        Derive::PartialEq,
+        Derive::Eq,
+        Derive::PartialOrd,
+        Derive::Ord,
+        Derive::Hash,
+        Derive::Default,
+    ];
+
+    /// The name of the trait.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Derive::PartialEq => "PartialEq",
+            Derive::Eq => "Eq",
+            Derive::PartialOrd => "PartialOrd",
+            Derive::Ord => "Ord",
+            Derive::Hash => "Hash",
+            Derive::Default => "Default",
+        }
+    }
+
+    /// Parse a derive from its trait name.
+    pub fn from_name(name: &str) -> Option<Derive> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|derive| derive.as_str() == name)
+    }
+
+    /// Supertraits that must be derived as well.
+    pub fn requires(self) -> &'static [Derive] {
+        match self {
+            Derive::PartialEq | Derive::Hash | Derive::Default => &[],
+            Derive::Eq | Derive::PartialOrd => &[Derive::PartialEq],
+            Derive::Ord => &[Derive::Eq, Derive::PartialOrd],
+        }
+    }
+}
+
+/// The text encoding of a `bytes` field, selected using `@encoding("...")`.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Default)]
+pub enum BytesEncoding {
+    /// Standard base64 with padding, the default.
+    #[default]
+    Base64,
+    /// Lower case hexadecimal digits, two per byte.
+    Hex,
+}
+
+impl BytesEncoding {
+    /// All supported encodings.
+    pub const ALL: [BytesEncoding; 2] = [BytesEncoding::Base64, BytesEncoding::Hex];
+
+    /// The name of the encoding, as used in humblespec.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            BytesEncoding::Base64 => "base64",
+            BytesEncoding::Hex => "hex",
+        }
+    }
+
+    /// Parse an encoding from its name.
+    pub fn from_name(name: &str) -> Option<BytesEncoding> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|encoding| encoding.as_str() == name)
+    }
+}
+
+/// A naming convention for the serialized representation of field names.
+///
+/// Mirrors the field renaming rules of serde's `rename_all` container attribute.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum RenameRule {
+    /// `camelCase`
+    CamelCase,
+    /// `snake_case`
+    SnakeCase,
+    /// `SCREAMING_SNAKE_CASE`
+    ScreamingSnakeCase,
+    /// `kebab-case`
+    KebabCase,
+}
+
+impl RenameRule {
+    /// All supported rules.
+    pub const ALL: [RenameRule; 4] = [
+        RenameRule::CamelCase,
+        RenameRule::SnakeCase,
+        RenameRule::ScreamingSnakeCase,
+        RenameRule::KebabCase,
+    ];
+
+    /// The name of the rule, as used in humblespec and serde.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            RenameRule::CamelCase => "camelCase",
+            RenameRule::SnakeCase => "snake_case",
+            RenameRule::ScreamingSnakeCase => "SCREAMING_SNAKE_CASE",
+            RenameRule::KebabCase => "kebab-case",
+        }
+    }
+
+    /// Parse a rule from its name.
+    pub fn from_name(name: &str) -> Option<RenameRule> {
+        Self::ALL.iter().copied().find(|rule| rule.as_str() == name)
+    }
+
+    /// Apply the rule to a (snake_case) field name.
+    pub fn apply_to_field(self, field: &str) -> String {
+        match self {
+            RenameRule::SnakeCase => field.to_owned(),
+            RenameRule::ScreamingSnakeCase => field.to_ascii_uppercase(),
+            RenameRule::KebabCase => field.replace('_', "-"),
+            RenameRule::CamelCase => {
+                let mut camel = String::new();
+                let mut capitalize = false;
+                for ch in field.chars() {
+                    if ch == '_' {
+                        capitalize = !camel.is_empty();
+                    } else if capitalize {
+                        camel.push(ch.to_ascii_uppercase());
+                        capitalize = false;
+                    } else {
+                        camel.push(ch);
+                    }
+                }
+                camel
+            }
+        }
+    }
 }
 
 /// Container of struct fields.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct StructFields(pub Vec<FieldNode>);
 
 impl StructFields {
@@ -62,7 +265,7 @@ impl StructFields {
 }
 
 /// Enum definition.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct EnumDef {
     /// Name of the `enum`.
     pub name: String,
@@ -70,6 +273,34 @@ pub struct EnumDef {
     pub variants: Vec<VariantDef>,
     /// Documentation comment.
     pub doc_comment: Option<String>,
+    /// Whether more variants may be added in the future, set using `@non_exhaustive`.
+    pub non_exhaustive: bool,
+    /// Additional traits to derive, set using `@derive(...)`.
+    pub derives: Vec<Derive>,
+    /// How variants are represented on the wire, set using `@tag(...)`. If unset, the default
+    /// of the code generator is used, see `EnumDef::tagging`.
+    pub tagging: Option<EnumTagging>,
+    /// Note explaining what to use instead, if the enum is deprecated using
+    /// `@deprecated("...")`.
+    pub deprecated: Option<String>,
+    /// The location of the definition, including its doc comment and annotations.
+    pub span: Option<Span>,
+}
+
+/// The representation of enum variants on the wire, following serde's enum representations.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum EnumTagging {
+    /// `{"Variant": content}`, or just `"Variant"` for simple variants. The default, unless the
+    /// code generator is configured otherwise.
+    #[default]
+    External,
+    /// `{"<tag>": "Variant", ...fields}`, set using `@tag("<tag>")`.
+    ///
+    /// Only simple and struct variants can be tagged internally.
+    Internal { tag: String },
+    /// `{"<tag>": "Variant", "<content>": content}`, set using
+    /// `@tag("<tag>", content = "<content>")`.
+    Adjacent { tag: String, content: String },
 }
 
 impl EnumDef {
@@ -86,10 +317,16 @@ impl EnumDef {
     pub fn simple_variants(&self) -> impl Iterator<Item = &VariantDef> {
         self.variants.iter().filter(|v| v.is_simple())
     }
+
+    /// How variants are represented on the wire, external tagging if unset.
+    pub fn tagging(&self) -> &EnumTagging {
+        static EXTERNAL: EnumTagging = EnumTagging::External;
+        self.tagging.as_ref().unwrap_or(&EXTERNAL)
+    }
 }
 
 /// A variant definition.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct VariantDef {
     /// Name of the variant.
     pub name: String,
@@ -97,10 +334,14 @@ pub struct VariantDef {
     pub variant_type: VariantType,
     /// Documentation comment.
     pub doc_comment: Option<String>,
+    /// Representation of a simple variant on the wire, if overridden using `@value("...")`.
+    pub value: Option<String>,
+    /// The location of the variant, including its doc comment and annotations.
+    pub span: Option<Span>,
 }
 
 /// An (enum-)variant type.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum VariantType {
     /// Simple C-style variant.
     Simple,
@@ -129,6 +370,11 @@ impl VariantType {
 }
 
 impl VariantDef {
+    /// The name used for the variant in its serialized representation.
+    pub fn wire_name(&self) -> &str {
+        self.value.as_deref().unwrap_or(&self.name)
+    }
+
     /// Returns whether or not a variant is simple.
     fn is_simple(&self) -> bool {
         if let VariantType::Simple = self.variant_type {
@@ -148,14 +394,64 @@ impl VariantDef {
 ///    POST /monsters -> MonsterData -> result[Monster][MonsterError]
 /// }
 /// ```
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct ServiceDef {
     /// The service name. (example: `MonsterApi`)
     pub name: String,
     /// The doc comment of the service. (example: `Monster management service.`)
     pub doc_comment: Option<String>,
+    /// The path prepended to the routes of all endpoints, set using `base "..."`.
+    /// (example: `/v1`)
+    pub base: Option<String>,
     /// The service endpoints. (example: see struct `ServiceEndpoint`)
     pub endpoints: Vec<ServiceEndpoint>,
+    /// The routes forwarded to other HTTP servers. (example: see struct `ProxyDef`)
+    pub proxies: Vec<ProxyDef>,
+    /// The names of the namespaces the service is defined in, outermost first, empty for
+    /// services defined at the top level. (example: `["Admin"]` for a service defined in
+    /// `namespace Admin { ... }`)
+    pub namespace: Vec<String>,
+    /// The location of the definition, including its doc comment.
+    pub span: Option<Span>,
+}
+
+impl ServiceDef {
+    /// The name of the service prefixed by the names of its namespaces, separated by `sep`.
+    /// (example: `Admin.MonsterApi` for `sep = "."`)
+    pub fn qualified_name(&self, sep: &str) -> String {
+        self.namespace
+            .iter()
+            .chain(std::iter::once(&self.name))
+            .map(String::as_str)
+            .collect::<Vec<_>>()
+            .join(sep)
+    }
+
+    /// The path segments of the `base` path, empty if there is none. (example: `["v1"]`)
+    pub fn base_segments(&self) -> Vec<&str> {
+        match &self.base {
+            Some(base) => base.split('/').skip(1).collect(),
+            None => Vec::new(),
+        }
+    }
+}
+
+/// A route prefix within a service definition whose requests are forwarded to another
+/// HTTP server, instead of being handled by an endpoint.
+/// Example:
+/// ```text
+/// /// The monster management of the old system.
+/// PROXY /legacy/* -> "http://legacy:8080"
+/// ```
+#[derive(Debug, Clone)]
+pub struct ProxyDef {
+    /// The doc comment of the proxy. (example: `The monster management of the old system.`)
+    pub doc_comment: Option<String>,
+    /// The literal path segments in front of the `/*`. (example: `["legacy"]`)
+    pub prefix: Vec<String>,
+    /// The URL the rest of the path and the query are appended to. (example:
+    /// `http://legacy:8080`)
+    pub upstream: String,
 }
 
 /// An endpoint within a service definition.
@@ -164,21 +460,61 @@ pub struct ServiceDef {
 /// /// Retrieve all monsters.
 /// GET /monsters -> vec[Monster],
 /// ```
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct ServiceEndpoint {
     /// The doc comment of the endpoint. (example: `Retrieve all monsters.`)
     pub doc_comment: Option<String>,
     /// The route of the endpoint. (example: see struct `ServiceRoute`)
     pub route: ServiceRoute,
+    /// Note explaining what to use instead, if the endpoint is deprecated using
+    /// `@deprecated("...")`.
+    pub deprecated: Option<String>,
+    /// Whether the default of the query type is used if the request has no query, instead of
+    /// passing the query as an option, set using `@default_query`.
+    pub default_query: bool,
+    /// The status code of responses with an `Err`, if the endpoint returns a
+    /// `result[T][E]` and declares it using `@error_status(404)`. Otherwise both arms are sent
+    /// with the status of successful responses.
+    pub error_status: Option<u16>,
+    /// The maximum size of the request body in bytes, if the endpoint declares it using
+    /// `@max_body(1mb)`. It replaces the `max_body_size` option of the server for this endpoint.
+    pub max_body_size: Option<usize>,
+    /// The location of the endpoint, including its doc comment and annotations.
+    pub span: Option<Span>,
+}
+
+/// How the value returned by an endpoint is sent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResponseKind {
+    /// The returned value is JSON encoded.
+    Json,
+    /// The returned `bytes` are sent verbatim (`raw bytes`).
+    Raw,
+    /// The endpoint returns a stream of values which are sent as newline-delimited JSON
+    /// while they are produced (`stream[T]`).
+    Stream,
+    /// The connection is upgraded to a WebSocket, over which values are pushed as JSON text
+    /// messages (`WS` endpoints).
+    WebSocket,
+    /// The endpoint returns a stream of values which are pushed as Server-Sent Events
+    /// (`SSE` endpoints).
+    EventStream,
+    /// The endpoint returns a page of a list of values, wrapped in a `Page` envelope
+    /// (`@paginated` endpoints returning `list[T]`). The endpoint takes the optional query
+    /// fields `limit` and `offset`.
+    Paginated,
 }
 
 /// And endpoint's route.
 /// Example:
 /// ```text
 /// GET  /monsters?{GetMonstersQuery} -> vec[Monster],
+/// GET  /monsters/search?name: option[str]&min_hp: i32 -> vec[Monster],
 /// POST /monsters -> MonsterData -> result[Monster][MonsterError]
+/// WS   /events -> Event,
+/// SSE  /notifications -> Notification,
 /// ```
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum ServiceRoute {
     /// A GET endpoint.
     Get {
@@ -186,8 +522,18 @@ pub enum ServiceRoute {
         components: Vec<ServiceRouteComponent>,
         /// The query type, if specified. (example: `GetMonstersQuery`)
         query: Option<TypeIdent>,
-        /// The route return type.
+        /// Query parameters bound to arguments of the endpoint, if the query is specified
+        /// as a list of fields instead of a type. (example: `?name: option[str]&min_hp: i32`)
+        query_fields: Vec<FieldDefPair>,
+        /// Request headers bound to arguments of the endpoint. See struct `HeaderBinding`.
+        headers: Vec<HeaderBinding>,
+        /// The route return type, the item type for streamed and paginated responses.
         ret: TypeIdent,
+        /// How the returned value is sent. See enum `ResponseKind`.
+        response_kind: ResponseKind,
+        /// The status code of successful responses, `200` unless declared using
+        /// `status 201`.
+        status: u16,
     },
     /// A POST endpoint.
     Post {
@@ -195,10 +541,20 @@ pub enum ServiceRoute {
         components: Vec<ServiceRouteComponent>,
         /// The query type, if specified. (example: `GetMonstersQuery`)
         query: Option<TypeIdent>,
+        /// Query parameters bound to arguments of the endpoint, if the query is specified
+        /// as a list of fields instead of a type. (example: `?name: option[str]&min_hp: i32`)
+        query_fields: Vec<FieldDefPair>,
+        /// Request headers bound to arguments of the endpoint. See struct `HeaderBinding`.
+        headers: Vec<HeaderBinding>,
         /// The POST body type. (example: `MonsterData`)
         body: TypeIdent,
-        /// The route return type.
+        /// The route return type, the item type for streamed and paginated responses.
         ret: TypeIdent,
+        /// How the returned value is sent. See enum `ResponseKind`.
+        response_kind: ResponseKind,
+        /// The status code of successful responses, `200` unless declared using
+        /// `status 201`.
+        status: u16,
     },
     /// A DELETE endpoint
     Delete {
@@ -206,8 +562,18 @@ pub enum ServiceRoute {
         components: Vec<ServiceRouteComponent>,
         /// The query type, if specified. (example: `GetMonstersQuery`)
         query: Option<TypeIdent>,
-        /// The route return type.
+        /// Query parameters bound to arguments of the endpoint, if the query is specified
+        /// as a list of fields instead of a type. (example: `?name: option[str]&min_hp: i32`)
+        query_fields: Vec<FieldDefPair>,
+        /// Request headers bound to arguments of the endpoint. See struct `HeaderBinding`.
+        headers: Vec<HeaderBinding>,
+        /// The route return type, the item type for streamed and paginated responses.
         ret: TypeIdent,
+        /// How the returned value is sent. See enum `ResponseKind`.
+        response_kind: ResponseKind,
+        /// The status code of successful responses, `200` unless declared using
+        /// `status 201`.
+        status: u16,
     },
     /// A PUT endpoint.
     Put {
@@ -215,10 +581,20 @@ pub enum ServiceRoute {
         components: Vec<ServiceRouteComponent>,
         /// The query type, if specified. (example: `GetMonstersQuery`)
         query: Option<TypeIdent>,
+        /// Query parameters bound to arguments of the endpoint, if the query is specified
+        /// as a list of fields instead of a type. (example: `?name: option[str]&min_hp: i32`)
+        query_fields: Vec<FieldDefPair>,
+        /// Request headers bound to arguments of the endpoint. See struct `HeaderBinding`.
+        headers: Vec<HeaderBinding>,
         /// The POST body type. (example: `MonsterData`)
         body: TypeIdent,
-        /// The route return type.
+        /// The route return type, the item type for streamed and paginated responses.
         ret: TypeIdent,
+        /// How the returned value is sent. See enum `ResponseKind`.
+        response_kind: ResponseKind,
+        /// The status code of successful responses, `200` unless declared using
+        /// `status 201`.
+        status: u16,
     },
     /// A PATCH endpoint.
     Patch {
@@ -226,9 +602,93 @@ pub enum ServiceRoute {
         components: Vec<ServiceRouteComponent>,
         /// The query type, if specified. (example: `GetMonstersQuery`)
         query: Option<TypeIdent>,
+        /// Query parameters bound to arguments of the endpoint, if the query is specified
+        /// as a list of fields instead of a type. (example: `?name: option[str]&min_hp: i32`)
+        query_fields: Vec<FieldDefPair>,
+        /// Request headers bound to arguments of the endpoint. See struct `HeaderBinding`.
+        headers: Vec<HeaderBinding>,
         /// The POST body type. (example: `MonsterData`)
         body: TypeIdent,
-        /// The route return type.
+        /// The route return type, the item type for streamed and paginated responses.
+        ret: TypeIdent,
+        /// How the returned value is sent. See enum `ResponseKind`.
+        response_kind: ResponseKind,
+        /// The status code of successful responses, `200` unless declared using
+        /// `status 201`.
+        status: u16,
+    },
+    /// An OPTIONS endpoint. If CORS is enabled, the server answers preflight requests itself
+    /// instead.
+    Options {
+        /// The route components. See struct `ServiceRouteComponent`.
+        components: Vec<ServiceRouteComponent>,
+        /// The query type, if specified. (example: `GetMonstersQuery`)
+        query: Option<TypeIdent>,
+        /// Query parameters bound to arguments of the endpoint, if the query is specified
+        /// as a list of fields instead of a type. (example: `?name: option[str]&min_hp: i32`)
+        query_fields: Vec<FieldDefPair>,
+        /// Request headers bound to arguments of the endpoint. See struct `HeaderBinding`.
+        headers: Vec<HeaderBinding>,
+        /// The route return type, the item type for streamed and paginated responses.
+        ret: TypeIdent,
+        /// How the returned value is sent. See enum `ResponseKind`.
+        response_kind: ResponseKind,
+        /// The status code of successful responses, `200` unless declared using
+        /// `status 201`.
+        status: u16,
+    },
+    /// An endpoint using a method not covered by the other variants, declared using its name
+    /// in quotes. (example: `"PROPFIND" /files -> list[str]`)
+    Custom {
+        /// The name of the method. (example: `PROPFIND`)
+        method: String,
+        /// The route components. See struct `ServiceRouteComponent`.
+        components: Vec<ServiceRouteComponent>,
+        /// The query type, if specified. (example: `GetMonstersQuery`)
+        query: Option<TypeIdent>,
+        /// Query parameters bound to arguments of the endpoint, if the query is specified
+        /// as a list of fields instead of a type. (example: `?name: option[str]&min_hp: i32`)
+        query_fields: Vec<FieldDefPair>,
+        /// Request headers bound to arguments of the endpoint. See struct `HeaderBinding`.
+        headers: Vec<HeaderBinding>,
+        /// The request body type, if the endpoint has one. (example: `MonsterData`)
+        body: Option<TypeIdent>,
+        /// The route return type, the item type for streamed and paginated responses.
+        ret: TypeIdent,
+        /// How the returned value is sent. See enum `ResponseKind`.
+        response_kind: ResponseKind,
+        /// The status code of successful responses, `200` unless declared using
+        /// `status 201`.
+        status: u16,
+    },
+    /// A WebSocket endpoint, which upgrades a GET request to a WebSocket and pushes messages
+    /// to the client.
+    Ws {
+        /// The route components. See struct `ServiceRouteComponent`.
+        components: Vec<ServiceRouteComponent>,
+        /// The query type, if specified. (example: `GetMonstersQuery`)
+        query: Option<TypeIdent>,
+        /// Query parameters bound to arguments of the endpoint, if the query is specified
+        /// as a list of fields instead of a type. (example: `?name: option[str]&min_hp: i32`)
+        query_fields: Vec<FieldDefPair>,
+        /// Request headers bound to arguments of the endpoint. See struct `HeaderBinding`.
+        headers: Vec<HeaderBinding>,
+        /// The type of the messages pushed to the client. (example: `Event`)
+        ret: TypeIdent,
+    },
+    /// A Server-Sent Events endpoint, which answers a GET request with an event stream
+    /// pushing messages to the client.
+    Sse {
+        /// The route components. See struct `ServiceRouteComponent`.
+        components: Vec<ServiceRouteComponent>,
+        /// The query type, if specified. (example: `GetMonstersQuery`)
+        query: Option<TypeIdent>,
+        /// Query parameters bound to arguments of the endpoint, if the query is specified
+        /// as a list of fields instead of a type. (example: `?name: option[str]&min_hp: i32`)
+        query_fields: Vec<FieldDefPair>,
+        /// Request headers bound to arguments of the endpoint. See struct `HeaderBinding`.
+        headers: Vec<HeaderBinding>,
+        /// The type of the messages pushed to the client. (example: `Notification`)
         ret: TypeIdent,
     },
 }
@@ -242,6 +702,10 @@ impl ServiceRoute {
             ServiceRoute::Post { components, .. } => components,
             ServiceRoute::Put { components, .. } => components,
             ServiceRoute::Patch { components, .. } => components,
+            ServiceRoute::Options { components, .. } => components,
+            ServiceRoute::Custom { components, .. } => components,
+            ServiceRoute::Ws { components, .. } => components,
+            ServiceRoute::Sse { components, .. } => components,
         }
     }
 
@@ -253,6 +717,41 @@ impl ServiceRoute {
             ServiceRoute::Post { query, .. } => query,
             ServiceRoute::Put { query, .. } => query,
             ServiceRoute::Patch { query, .. } => query,
+            ServiceRoute::Options { query, .. } => query,
+            ServiceRoute::Custom { query, .. } => query,
+            ServiceRoute::Ws { query, .. } => query,
+            ServiceRoute::Sse { query, .. } => query,
+        }
+    }
+
+    /// Query parameters bound to arguments of the endpoint. A parameter is optional
+    /// if its type is an `option[T]`.
+    pub fn query_fields(&self) -> &Vec<FieldDefPair> {
+        match self {
+            ServiceRoute::Get { query_fields, .. } => query_fields,
+            ServiceRoute::Delete { query_fields, .. } => query_fields,
+            ServiceRoute::Post { query_fields, .. } => query_fields,
+            ServiceRoute::Put { query_fields, .. } => query_fields,
+            ServiceRoute::Patch { query_fields, .. } => query_fields,
+            ServiceRoute::Options { query_fields, .. } => query_fields,
+            ServiceRoute::Custom { query_fields, .. } => query_fields,
+            ServiceRoute::Ws { query_fields, .. } => query_fields,
+            ServiceRoute::Sse { query_fields, .. } => query_fields,
+        }
+    }
+
+    /// Request headers bound to arguments of the endpoint. See struct `HeaderBinding`.
+    pub fn headers(&self) -> &Vec<HeaderBinding> {
+        match self {
+            ServiceRoute::Get { headers, .. } => headers,
+            ServiceRoute::Delete { headers, .. } => headers,
+            ServiceRoute::Post { headers, .. } => headers,
+            ServiceRoute::Put { headers, .. } => headers,
+            ServiceRoute::Patch { headers, .. } => headers,
+            ServiceRoute::Options { headers, .. } => headers,
+            ServiceRoute::Custom { headers, .. } => headers,
+            ServiceRoute::Ws { headers, .. } => headers,
+            ServiceRoute::Sse { headers, .. } => headers,
         }
     }
 
@@ -264,9 +763,72 @@ impl ServiceRoute {
             ServiceRoute::Post { ret, .. } => ret,
             ServiceRoute::Put { ret, .. } => ret,
             ServiceRoute::Patch { ret, .. } => ret,
+            ServiceRoute::Options { ret, .. } => ret,
+            ServiceRoute::Custom { ret, .. } => ret,
+            ServiceRoute::Ws { ret, .. } => ret,
+            ServiceRoute::Sse { ret, .. } => ret,
+        }
+    }
+
+    /// How the returned value is sent. See enum `ResponseKind`.
+    pub fn response_kind(&self) -> ResponseKind {
+        match self {
+            ServiceRoute::Get { response_kind, .. } => *response_kind,
+            ServiceRoute::Delete { response_kind, .. } => *response_kind,
+            ServiceRoute::Post { response_kind, .. } => *response_kind,
+            ServiceRoute::Put { response_kind, .. } => *response_kind,
+            ServiceRoute::Patch { response_kind, .. } => *response_kind,
+            ServiceRoute::Options { response_kind, .. } => *response_kind,
+            ServiceRoute::Custom { response_kind, .. } => *response_kind,
+            ServiceRoute::Ws { .. } => ResponseKind::WebSocket,
+            ServiceRoute::Sse { .. } => ResponseKind::EventStream,
+        }
+    }
+
+    /// The status code of successful responses. WebSocket endpoints always answer with
+    /// `101 Switching Protocols`, Server-Sent Events endpoints with `200 OK`.
+    pub fn status(&self) -> u16 {
+        match self {
+            ServiceRoute::Get { status, .. } => *status,
+            ServiceRoute::Delete { status, .. } => *status,
+            ServiceRoute::Post { status, .. } => *status,
+            ServiceRoute::Put { status, .. } => *status,
+            ServiceRoute::Patch { status, .. } => *status,
+            ServiceRoute::Options { status, .. } => *status,
+            ServiceRoute::Custom { status, .. } => *status,
+            ServiceRoute::Ws { .. } => 101,
+            ServiceRoute::Sse { .. } => 200,
         }
     }
 
+    /// Whether the response is sent verbatim with a content type chosen by the handler,
+    /// declared using `raw bytes` as return type.
+    pub fn is_raw_response(&self) -> bool {
+        self.response_kind() == ResponseKind::Raw
+    }
+
+    /// Whether the response is a stream of values of the return type, declared using
+    /// `stream[T]` as return type.
+    pub fn is_stream_response(&self) -> bool {
+        self.response_kind() == ResponseKind::Stream
+    }
+
+    /// Whether the endpoint returns a page of a list of values of the return type, declared
+    /// using `@paginated`.
+    pub fn is_paginated(&self) -> bool {
+        self.response_kind() == ResponseKind::Paginated
+    }
+
+    /// Whether the endpoint is a WebSocket endpoint, declared using `WS`.
+    pub fn is_websocket(&self) -> bool {
+        self.response_kind() == ResponseKind::WebSocket
+    }
+
+    /// Whether the endpoint is a Server-Sent Events endpoint, declared using `SSE`.
+    pub fn is_event_stream(&self) -> bool {
+        self.response_kind() == ResponseKind::EventStream
+    }
+
     pub fn request_body(&self) -> Option<&TypeIdent> {
         match self {
             ServiceRoute::Get { .. } => None,
@@ -274,20 +836,41 @@ impl ServiceRoute {
             ServiceRoute::Post { body, .. } => Some(body),
             ServiceRoute::Put { body, .. } => Some(body),
             ServiceRoute::Patch { body, .. } => Some(body),
+            ServiceRoute::Options { .. } => None,
+            ServiceRoute::Custom { body, .. } => body.as_ref(),
+            ServiceRoute::Ws { .. } => None,
+            ServiceRoute::Sse { .. } => None,
         }
     }
 
-    pub fn http_method_as_str(&self) -> &'static str {
+    pub fn http_method_as_str(&self) -> &str {
         match self {
             ServiceRoute::Get { .. } => "GET",
             ServiceRoute::Delete { .. } => "DELETE",
             ServiceRoute::Post { .. } => "POST",
             ServiceRoute::Put { .. } => "PUT",
             ServiceRoute::Patch { .. } => "PATCH",
+            ServiceRoute::Options { .. } => "OPTIONS",
+            ServiceRoute::Custom { method, .. } => method,
+            ServiceRoute::Ws { .. } => "WS",
+            ServiceRoute::Sse { .. } => "SSE",
         }
     }
 }
 
+/// A request header bound to an argument of an endpoint.
+/// Example:
+/// ```text
+/// GET /monsters header "X-Tenant-Id": str -> vec[Monster]
+/// ```
+#[derive(Debug, Clone)]
+pub struct HeaderBinding {
+    /// The name of the header. (example: `X-Tenant-Id`)
+    pub name: String,
+    /// The type of the header's value. Headers of type `option[T]` may be omitted.
+    pub type_ident: TypeIdent,
+}
+
 /// A component of a `ServiceRoute`.
 /// Example:
 /// ```text
@@ -295,12 +878,52 @@ impl ServiceRoute {
 /// ```
 /// results in
 /// - `Literal("monsters")
-/// - `Variable(FieldDefPair{ name: "id", type_ident: TypeIdent::BuiltIn(AtomType::Str) })`
+/// - `Variable(FieldDefPair{ name: "id", type_ident: TypeIdent::BuiltIn(AtomType::Str) }, RouteParamPattern::Segment)`
 ///
 #[derive(Debug, Clone)]
 pub enum ServiceRouteComponent {
     Literal(String),
-    Variable(FieldDefPair),
+    Variable(FieldDefPair, RouteParamPattern),
+}
+
+/// The part of the request path that a route parameter matches, set using
+/// `{name: type as "class"}` or `{name: type as /regex/}`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RouteParamPattern {
+    /// A single path segment, the default (`"segment"`).
+    Segment,
+    /// One or more path segments, including the slashes between them (`"rest"`).
+    Rest,
+    /// A custom regular expression, e.g. `/\d+/`.
+    Regex(String),
+}
+
+impl RouteParamPattern {
+    /// The names of all predefined classes.
+    pub const CLASSES: &'static [&'static str] = &["segment", "rest"];
+
+    /// Looks up a predefined class by its name.
+    pub fn from_class_name(name: &str) -> Option<Self> {
+        match name {
+            "segment" => Some(RouteParamPattern::Segment),
+            "rest" => Some(RouteParamPattern::Rest),
+            _ => None,
+        }
+    }
+
+    /// The regular expression matching the parameter, without anchors or capture group.
+    pub fn to_regex(&self) -> &str {
+        match self {
+            RouteParamPattern::Segment => "[^/]+",
+            RouteParamPattern::Rest => ".+",
+            RouteParamPattern::Regex(regex) => regex,
+        }
+    }
+
+    /// Whether the parameter may span several path segments, i.e. contain slashes.
+    pub fn is_multi_segment(&self) -> bool {
+        *self != RouteParamPattern::Segment
+    }
 }
 
 /// A field node (field definition inside struct).
@@ -309,6 +932,94 @@ pub struct FieldNode {
     pub pair: FieldDefPair,
     /// Documentation comment.
     pub doc_comment: Option<String>,
+    /// Name of the field on the wire, if overridden using `@rename("...")`.
+    pub rename: Option<String>,
+    /// Name of the struct embedded by the containing struct that this field was inlined from,
+    /// `None` for fields declared by the containing struct itself.
+    pub embedded_from: Option<String>,
+    /// Value of the field if it is missing from a received message, set using `= value`.
+    pub default: Option<DefaultValue>,
+    /// Encoding of the field if it is a `bytes` field, set using `@encoding("...")`.
+    pub bytes_encoding: BytesEncoding,
+    /// Note explaining what to use instead, if the field is deprecated using
+    /// `@deprecated("...")`.
+    pub deprecated: Option<String>,
+    /// The location of the field, including its doc comment and annotations. Fields inlined
+    /// from embedded structs and added to patches keep the location of the original field.
+    pub span: Option<Span>,
+}
+
+impl FieldNode {
+    /// The name used for the field in its serialized representation, given the `rename_all`
+    /// rule of the containing struct.
+    pub fn wire_name(&self, rename_all: Option<RenameRule>) -> String {
+        match (&self.rename, rename_all) {
+            (Some(rename), _) => rename.clone(),
+            (None, Some(rule)) => rule.apply_to_field(&self.pair.name),
+            (None, None) => self.pair.name.clone(),
+        }
+    }
+}
+
+/// The location of a node in the humble file it was parsed from, for tools and diagnostics.
+/// Nodes that are not written in a humble file, e.g. the ones added by code generators, have
+/// none.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    /// Byte offset of the start of the node.
+    pub start: usize,
+    /// Byte offset just past the end of the node.
+    pub end: usize,
+    /// Line of the start of the node, starting at 1.
+    pub line: usize,
+    /// Column of the start of the node, starting at 1.
+    pub col: usize,
+}
+
+/// Formats the span compactly, e.g. `Span(12..40 @ 2:5)`, to keep debug output of the AST
+/// readable.
+impl std::fmt::Debug for Span {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Span({}..{} @ {}:{})",
+            self.start, self.end, self.line, self.col
+        )
+    }
+}
+
+/// The default value of a field, a literal matching the type of the field.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DefaultValue {
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    Str(String),
+}
+
+impl DefaultValue {
+    /// Whether the value is the default value of its type, i.e. `false`, zero or the empty string.
+    pub fn is_zero(&self) -> bool {
+        match self {
+            DefaultValue::Bool(b) => !b,
+            DefaultValue::Int(i) => *i == 0,
+            DefaultValue::Float(f) => *f == 0.0,
+            DefaultValue::Str(s) => s.is_empty(),
+        }
+    }
+}
+
+/// Formats the value as it is written in a spec.
+impl std::fmt::Display for DefaultValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DefaultValue::Bool(b) => write!(f, "{}", b),
+            DefaultValue::Int(i) => write!(f, "{}", i),
+            // debug formatting keeps the decimal point of whole numbers
+            DefaultValue::Float(x) => write!(f, "{:?}", x),
+            DefaultValue::Str(s) => write!(f, "\"{}\"", s),
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -317,6 +1028,37 @@ pub struct FieldDefPair {
     pub name: String,
     /// Type of the field.
     pub type_ident: TypeIdent,
+    /// Range the value of an integer struct field must be in, set using `in min..=max`.
+    pub range: Option<RangeConstraint>,
+}
+
+/// An inclusive range of integers, either bound may be open.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RangeConstraint {
+    /// The smallest allowed value.
+    pub min: Option<i64>,
+    /// The largest allowed value.
+    pub max: Option<i64>,
+}
+
+impl RangeConstraint {
+    /// Whether `value` is within the range.
+    pub fn contains(&self, value: i64) -> bool {
+        self.min.is_none_or(|min| min <= value) && self.max.is_none_or(|max| value <= max)
+    }
+}
+
+/// Formats the range as it is written in a spec, e.g. `0..=150` or `1..`.
+impl std::fmt::Display for RangeConstraint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(min) = self.min {
+            write!(f, "{}", min)?;
+        }
+        match self.max {
+            Some(max) => write!(f, "..={}", max),
+            None => write!(f, ".."),
+        }
+    }
 }
 
 impl FieldDefPair {
@@ -337,12 +1079,18 @@ pub enum TypeIdent {
     BuiltIn(AtomType),
     /// `list[T]`
     List(Box<TypeIdent>),
-    /// `option[T]`
+    /// `set[T]`
+    Set(Box<TypeIdent>),
+    /// `option[T]`, as a field: the key may be absent, the value may be `null`
     Option(Box<TypeIdent>),
+    /// `nullable[T]`, as a field: the key has to be present, but the value may be `null`
+    Nullable(Box<TypeIdent>),
     /// `result[T]`
     Result(Box<TypeIdent>, Box<TypeIdent>),
     /// `map[t][u]`
     Map(Box<TypeIdent>, Box<TypeIdent>),
+    /// `omap[t][u]`, a map whose entries are ordered by key
+    OrderedMap(Box<TypeIdent>, Box<TypeIdent>),
     /// Tuple type.
     Tuple(TupleDef),
     /// Type defined in humble file.
@@ -358,6 +1106,24 @@ impl TypeIdent {
     }
 }
 
+/// Formats the type as it is written in a spec, e.g. `map[str][list[Monster]]`.
+impl std::fmt::Display for TypeIdent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TypeIdent::BuiltIn(atom) => write!(f, "{}", atom),
+            TypeIdent::List(ty) => write!(f, "list[{}]", ty),
+            TypeIdent::Set(ty) => write!(f, "set[{}]", ty),
+            TypeIdent::Option(ty) => write!(f, "option[{}]", ty),
+            TypeIdent::Nullable(ty) => write!(f, "nullable[{}]", ty),
+            TypeIdent::Result(ok, err) => write!(f, "result[{}][{}]", ok, err),
+            TypeIdent::Map(key, value) => write!(f, "map[{}][{}]", key, value),
+            TypeIdent::OrderedMap(key, value) => write!(f, "omap[{}][{}]", key, value),
+            TypeIdent::Tuple(tuple) => write!(f, "{}", tuple),
+            TypeIdent::UserDefined(name) => write!(f, "{}", name),
+        }
+    }
+}
+
 /// An atomic type.
 #[derive(Debug, Clone, Copy)]
 pub enum AtomType {
@@ -371,6 +1137,22 @@ pub enum AtomType {
     U32,
     /// Unsigned 8-bit integer.
     U8,
+    /// Signed 64-bit integer.
+    I64,
+    /// Unsigned 64-bit integer.
+    U64,
+    /// Signed 16-bit integer.
+    I16,
+    /// Unsigned 16-bit integer.
+    U16,
+    /// Signed 8-bit integer.
+    I8,
+    /// Unsigned 128-bit integer, encoded as a decimal string.
+    U128,
+    /// Signed 128-bit integer, encoded as a decimal string.
+    I128,
+    /// 32-bit IEEE floating-point number.
+    F32,
     /// 64-bit IEEE floating-point number.
     F64,
     /// Boolean value.
@@ -381,8 +1163,51 @@ pub enum AtomType {
     Date,
     /// A UUID value
     Uuid,
+    /// An absolute URL
+    Url,
+    /// An IPv4 or IPv6 address
+    IpAddr,
+    /// A span of time, encoded as integer milliseconds
+    Duration,
     /// A raw byte array
     Bytes,
+    /// An exact decimal number
+    Decimal,
+    /// Arbitrary JSON, passed through untouched
+    Json,
+}
+
+/// Formats the type using its keyword in a spec, e.g. `str` or `()`.
+impl std::fmt::Display for AtomType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let keyword = match self {
+            AtomType::Empty => "()",
+            AtomType::Str => "str",
+            AtomType::I32 => "i32",
+            AtomType::U32 => "u32",
+            AtomType::U8 => "u8",
+            AtomType::I64 => "i64",
+            AtomType::U64 => "u64",
+            AtomType::I16 => "i16",
+            AtomType::U16 => "u16",
+            AtomType::I8 => "i8",
+            AtomType::U128 => "u128",
+            AtomType::I128 => "i128",
+            AtomType::F32 => "f32",
+            AtomType::F64 => "f64",
+            AtomType::Bool => "bool",
+            AtomType::DateTime => "datetime",
+            AtomType::Date => "date",
+            AtomType::Uuid => "uuid",
+            AtomType::Url => "url",
+            AtomType::IpAddr => "ip",
+            AtomType::Duration => "duration",
+            AtomType::Bytes => "bytes",
+            AtomType::Decimal => "decimal",
+            AtomType::Json => "json",
+        };
+        f.write_str(keyword)
+    }
 }
 
 /// A tuple definition.
@@ -395,3 +1220,22 @@ impl TupleDef {
         &self.0
     }
 }
+
+/// Formats the tuple as it is written in a spec, e.g. `(str, i32)` or `(str,)`.
+impl std::fmt::Display for TupleDef {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.elements().as_slice() {
+            [single] => write!(f, "({},)", single),
+            elements => {
+                f.write_str("(")?;
+                for (i, element) in elements.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(", ")?;
+                    }
+                    write!(f, "{}", element)?;
+                }
+                f.write_str(")")
+            }
+        }
+    }
+}
@@ -0,0 +1,494 @@
+//! Traversal of the AST, for tools built on top of humblegen such as linters or custom code
+//! generators.
+//!
+//! A [`Visitor`] walks a spec by reference, a [`VisitorMut`] walks it by mutable reference and
+//! can thus transform it in place. Each `visit_*` method defaults to calling the `walk_*`
+//! function of the same name, which visits the children of the node. An implementation
+//! overrides the methods of the nodes it is interested in, calling the `walk_*` function
+//! itself if the children should be visited as well.
+//!
+//! # Example
+//!
+//! ```
+//! use humblegen::ast::{visit::{self, Visitor}, ServiceEndpoint};
+//!
+//! #[derive(Default)]
+//! struct EndpointCounter(usize);
+//!
+//! impl<'ast> Visitor<'ast> for EndpointCounter {
+//!     fn visit_service_endpoint(&mut self, endpoint: &'ast ServiceEndpoint) {
+//!         self.0 += 1;
+//!         visit::walk_service_endpoint(self, endpoint);
+//!     }
+//! }
+//!
+//! let spec = humblegen::parse("service Monsters { GET /monsters -> list[str] }".as_bytes())?;
+//! let mut counter = EndpointCounter::default();
+//! counter.visit_spec(&spec);
+//! assert_eq!(counter.0, 1);
+//! # Ok::<(), humblegen::LibError>(())
+//! ```
+//!
+//! Embeds and patches are already resolved by the parser, so their fields are visited as
+//! part of the struct they end up in.
+
+use super::*;
+
+/// Visits the nodes of a spec by reference. See the [module documentation](self).
+pub trait Visitor<'ast> {
+    fn visit_spec(&mut self, spec: &'ast Spec) {
+        walk_spec(self, spec)
+    }
+
+    fn visit_spec_item(&mut self, item: &'ast SpecItem) {
+        walk_spec_item(self, item)
+    }
+
+    fn visit_struct_def(&mut self, sdef: &'ast StructDef) {
+        walk_struct_def(self, sdef)
+    }
+
+    fn visit_enum_def(&mut self, edef: &'ast EnumDef) {
+        walk_enum_def(self, edef)
+    }
+
+    fn visit_variant_def(&mut self, variant: &'ast VariantDef) {
+        walk_variant_def(self, variant)
+    }
+
+    fn visit_field_node(&mut self, field: &'ast FieldNode) {
+        walk_field_node(self, field)
+    }
+
+    fn visit_field_def_pair(&mut self, pair: &'ast FieldDefPair) {
+        walk_field_def_pair(self, pair)
+    }
+
+    fn visit_service_def(&mut self, service: &'ast ServiceDef) {
+        walk_service_def(self, service)
+    }
+
+    fn visit_service_endpoint(&mut self, endpoint: &'ast ServiceEndpoint) {
+        walk_service_endpoint(self, endpoint)
+    }
+
+    fn visit_proxy_def(&mut self, _proxy: &'ast ProxyDef) {}
+
+    fn visit_service_route(&mut self, route: &'ast ServiceRoute) {
+        walk_service_route(self, route)
+    }
+
+    fn visit_route_component(&mut self, component: &'ast ServiceRouteComponent) {
+        walk_route_component(self, component)
+    }
+
+    fn visit_header_binding(&mut self, header: &'ast HeaderBinding) {
+        walk_header_binding(self, header)
+    }
+
+    fn visit_type_ident(&mut self, type_ident: &'ast TypeIdent) {
+        walk_type_ident(self, type_ident)
+    }
+}
+
+pub fn walk_spec<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, spec: &'ast Spec) {
+    for item in spec.iter() {
+        visitor.visit_spec_item(item);
+    }
+}
+
+pub fn walk_spec_item<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, item: &'ast SpecItem) {
+    match item {
+        SpecItem::StructDef(sdef) => visitor.visit_struct_def(sdef),
+        SpecItem::EnumDef(edef) => visitor.visit_enum_def(edef),
+        SpecItem::ServiceDef(service) => visitor.visit_service_def(service),
+    }
+}
+
+pub fn walk_struct_def<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, sdef: &'ast StructDef) {
+    for field in sdef.fields.iter() {
+        visitor.visit_field_node(field);
+    }
+}
+
+pub fn walk_enum_def<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, edef: &'ast EnumDef) {
+    for variant in &edef.variants {
+        visitor.visit_variant_def(variant);
+    }
+}
+
+pub fn walk_variant_def<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    variant: &'ast VariantDef,
+) {
+    match &variant.variant_type {
+        VariantType::Simple => {}
+        VariantType::Tuple(tdef) => {
+            for element in tdef.elements() {
+                visitor.visit_type_ident(element);
+            }
+        }
+        VariantType::Struct(fields) => {
+            for field in fields.iter() {
+                visitor.visit_field_node(field);
+            }
+        }
+        VariantType::Newtype(type_ident) => visitor.visit_type_ident(type_ident),
+    }
+}
+
+pub fn walk_field_node<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, field: &'ast FieldNode) {
+    visitor.visit_field_def_pair(&field.pair);
+}
+
+pub fn walk_field_def_pair<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    pair: &'ast FieldDefPair,
+) {
+    visitor.visit_type_ident(&pair.type_ident);
+}
+
+pub fn walk_service_def<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    service: &'ast ServiceDef,
+) {
+    for endpoint in &service.endpoints {
+        visitor.visit_service_endpoint(endpoint);
+    }
+    for proxy in &service.proxies {
+        visitor.visit_proxy_def(proxy);
+    }
+}
+
+pub fn walk_service_endpoint<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    endpoint: &'ast ServiceEndpoint,
+) {
+    visitor.visit_service_route(&endpoint.route);
+}
+
+pub fn walk_service_route<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    route: &'ast ServiceRoute,
+) {
+    for component in route.components() {
+        visitor.visit_route_component(component);
+    }
+    if let Some(query) = route.query() {
+        visitor.visit_type_ident(query);
+    }
+    for field in route.query_fields() {
+        visitor.visit_field_def_pair(field);
+    }
+    for header in route.headers() {
+        visitor.visit_header_binding(header);
+    }
+    if let Some(body) = route.request_body() {
+        visitor.visit_type_ident(body);
+    }
+    visitor.visit_type_ident(route.return_type());
+}
+
+pub fn walk_route_component<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    component: &'ast ServiceRouteComponent,
+) {
+    match component {
+        ServiceRouteComponent::Literal(_) => {}
+        ServiceRouteComponent::Variable(pair, _) => visitor.visit_field_def_pair(pair),
+    }
+}
+
+pub fn walk_header_binding<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    header: &'ast HeaderBinding,
+) {
+    visitor.visit_type_ident(&header.type_ident);
+}
+
+pub fn walk_type_ident<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    type_ident: &'ast TypeIdent,
+) {
+    match type_ident {
+        TypeIdent::BuiltIn(_) | TypeIdent::UserDefined(_) => {}
+        TypeIdent::List(inner)
+        | TypeIdent::Set(inner)
+        | TypeIdent::Option(inner)
+        | TypeIdent::Nullable(inner) => visitor.visit_type_ident(inner),
+        TypeIdent::Result(ok, err) => {
+            visitor.visit_type_ident(ok);
+            visitor.visit_type_ident(err);
+        }
+        TypeIdent::Map(key, value) | TypeIdent::OrderedMap(key, value) => {
+            visitor.visit_type_ident(key);
+            visitor.visit_type_ident(value);
+        }
+        TypeIdent::Tuple(tdef) => {
+            for element in tdef.elements() {
+                visitor.visit_type_ident(element);
+            }
+        }
+    }
+}
+
+/// Visits the nodes of a spec by mutable reference, e.g. to rename types or add fields.
+/// See the [module documentation](self).
+pub trait VisitorMut {
+    fn visit_spec_mut(&mut self, spec: &mut Spec) {
+        walk_spec_mut(self, spec)
+    }
+
+    fn visit_spec_item_mut(&mut self, item: &mut SpecItem) {
+        walk_spec_item_mut(self, item)
+    }
+
+    fn visit_struct_def_mut(&mut self, sdef: &mut StructDef) {
+        walk_struct_def_mut(self, sdef)
+    }
+
+    fn visit_enum_def_mut(&mut self, edef: &mut EnumDef) {
+        walk_enum_def_mut(self, edef)
+    }
+
+    fn visit_variant_def_mut(&mut self, variant: &mut VariantDef) {
+        walk_variant_def_mut(self, variant)
+    }
+
+    fn visit_field_node_mut(&mut self, field: &mut FieldNode) {
+        walk_field_node_mut(self, field)
+    }
+
+    fn visit_field_def_pair_mut(&mut self, pair: &mut FieldDefPair) {
+        walk_field_def_pair_mut(self, pair)
+    }
+
+    fn visit_service_def_mut(&mut self, service: &mut ServiceDef) {
+        walk_service_def_mut(self, service)
+    }
+
+    fn visit_service_endpoint_mut(&mut self, endpoint: &mut ServiceEndpoint) {
+        walk_service_endpoint_mut(self, endpoint)
+    }
+
+    fn visit_proxy_def_mut(&mut self, _proxy: &mut ProxyDef) {}
+
+    fn visit_service_route_mut(&mut self, route: &mut ServiceRoute) {
+        walk_service_route_mut(self, route)
+    }
+
+    fn visit_route_component_mut(&mut self, component: &mut ServiceRouteComponent) {
+        walk_route_component_mut(self, component)
+    }
+
+    fn visit_header_binding_mut(&mut self, header: &mut HeaderBinding) {
+        walk_header_binding_mut(self, header)
+    }
+
+    fn visit_type_ident_mut(&mut self, type_ident: &mut TypeIdent) {
+        walk_type_ident_mut(self, type_ident)
+    }
+}
+
+pub fn walk_spec_mut<V: VisitorMut + ?Sized>(visitor: &mut V, spec: &mut Spec) {
+    for item in spec.iter_mut() {
+        visitor.visit_spec_item_mut(item);
+    }
+}
+
+pub fn walk_spec_item_mut<V: VisitorMut + ?Sized>(visitor: &mut V, item: &mut SpecItem) {
+    match item {
+        SpecItem::StructDef(sdef) => visitor.visit_struct_def_mut(sdef),
+        SpecItem::EnumDef(edef) => visitor.visit_enum_def_mut(edef),
+        SpecItem::ServiceDef(service) => visitor.visit_service_def_mut(service),
+    }
+}
+
+pub fn walk_struct_def_mut<V: VisitorMut + ?Sized>(visitor: &mut V, sdef: &mut StructDef) {
+    for field in sdef.fields.0.iter_mut() {
+        visitor.visit_field_node_mut(field);
+    }
+}
+
+pub fn walk_enum_def_mut<V: VisitorMut + ?Sized>(visitor: &mut V, edef: &mut EnumDef) {
+    for variant in &mut edef.variants {
+        visitor.visit_variant_def_mut(variant);
+    }
+}
+
+pub fn walk_variant_def_mut<V: VisitorMut + ?Sized>(visitor: &mut V, variant: &mut VariantDef) {
+    match &mut variant.variant_type {
+        VariantType::Simple => {}
+        VariantType::Tuple(tdef) => {
+            for element in &mut tdef.0 {
+                visitor.visit_type_ident_mut(element);
+            }
+        }
+        VariantType::Struct(fields) => {
+            for field in fields.0.iter_mut() {
+                visitor.visit_field_node_mut(field);
+            }
+        }
+        VariantType::Newtype(type_ident) => visitor.visit_type_ident_mut(type_ident),
+    }
+}
+
+pub fn walk_field_node_mut<V: VisitorMut + ?Sized>(visitor: &mut V, field: &mut FieldNode) {
+    visitor.visit_field_def_pair_mut(&mut field.pair);
+}
+
+pub fn walk_field_def_pair_mut<V: VisitorMut + ?Sized>(visitor: &mut V, pair: &mut FieldDefPair) {
+    visitor.visit_type_ident_mut(&mut pair.type_ident);
+}
+
+pub fn walk_service_def_mut<V: VisitorMut + ?Sized>(visitor: &mut V, service: &mut ServiceDef) {
+    for endpoint in &mut service.endpoints {
+        visitor.visit_service_endpoint_mut(endpoint);
+    }
+    for proxy in &mut service.proxies {
+        visitor.visit_proxy_def_mut(proxy);
+    }
+}
+
+pub fn walk_service_endpoint_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    endpoint: &mut ServiceEndpoint,
+) {
+    visitor.visit_service_route_mut(&mut endpoint.route);
+}
+
+pub fn walk_service_route_mut<V: VisitorMut + ?Sized>(visitor: &mut V, route: &mut ServiceRoute) {
+    let (components, query, query_fields, headers, body, ret) = match route {
+        ServiceRoute::Get {
+            components,
+            query,
+            query_fields,
+            headers,
+            ret,
+            ..
+        }
+        | ServiceRoute::Delete {
+            components,
+            query,
+            query_fields,
+            headers,
+            ret,
+            ..
+        }
+        | ServiceRoute::Options {
+            components,
+            query,
+            query_fields,
+            headers,
+            ret,
+            ..
+        } => (components, query, query_fields, headers, None, ret),
+        ServiceRoute::Post {
+            components,
+            query,
+            query_fields,
+            headers,
+            body,
+            ret,
+            ..
+        }
+        | ServiceRoute::Put {
+            components,
+            query,
+            query_fields,
+            headers,
+            body,
+            ret,
+            ..
+        }
+        | ServiceRoute::Patch {
+            components,
+            query,
+            query_fields,
+            headers,
+            body,
+            ret,
+            ..
+        } => (components, query, query_fields, headers, Some(body), ret),
+        ServiceRoute::Custom {
+            components,
+            query,
+            query_fields,
+            headers,
+            body,
+            ret,
+            ..
+        } => (components, query, query_fields, headers, body.as_mut(), ret),
+        ServiceRoute::Ws {
+            components,
+            query,
+            query_fields,
+            headers,
+            ret,
+        }
+        | ServiceRoute::Sse {
+            components,
+            query,
+            query_fields,
+            headers,
+            ret,
+        } => (components, query, query_fields, headers, None, ret),
+    };
+
+    for component in components {
+        visitor.visit_route_component_mut(component);
+    }
+    if let Some(query) = query {
+        visitor.visit_type_ident_mut(query);
+    }
+    for field in query_fields {
+        visitor.visit_field_def_pair_mut(field);
+    }
+    for header in headers {
+        visitor.visit_header_binding_mut(header);
+    }
+    if let Some(body) = body {
+        visitor.visit_type_ident_mut(body);
+    }
+    visitor.visit_type_ident_mut(ret);
+}
+
+pub fn walk_route_component_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    component: &mut ServiceRouteComponent,
+) {
+    match component {
+        ServiceRouteComponent::Literal(_) => {}
+        ServiceRouteComponent::Variable(pair, _) => visitor.visit_field_def_pair_mut(pair),
+    }
+}
+
+pub fn walk_header_binding_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    header: &mut HeaderBinding,
+) {
+    visitor.visit_type_ident_mut(&mut header.type_ident);
+}
+
+pub fn walk_type_ident_mut<V: VisitorMut + ?Sized>(visitor: &mut V, type_ident: &mut TypeIdent) {
+    match type_ident {
+        TypeIdent::BuiltIn(_) | TypeIdent::UserDefined(_) => {}
+        TypeIdent::List(inner)
+        | TypeIdent::Set(inner)
+        | TypeIdent::Option(inner)
+        | TypeIdent::Nullable(inner) => visitor.visit_type_ident_mut(inner),
+        TypeIdent::Result(ok, err) => {
+            visitor.visit_type_ident_mut(ok);
+            visitor.visit_type_ident_mut(err);
+        }
+        TypeIdent::Map(key, value) | TypeIdent::OrderedMap(key, value) => {
+            visitor.visit_type_ident_mut(key);
+            visitor.visit_type_ident_mut(value);
+        }
+        TypeIdent::Tuple(tdef) => {
+            for element in &mut tdef.0 {
+                visitor.visit_type_ident_mut(element);
+            }
+        }
+    }
+}
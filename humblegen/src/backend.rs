@@ -1,3 +1,33 @@
 pub mod docs;
 pub mod elm;
+pub mod jsonschema;
+pub mod openapi;
 pub mod rust;
+pub mod typescript;
+
+use crate::{ast, parser, LibError};
+
+/// Returns a copy of `spec` in which the enums without a `@tag(...)` annotation are represented
+/// using `tagging`.
+///
+/// Fails if the variants of such an enum cannot be represented using `tagging`, e.g. tuple
+/// variants with internal tagging.
+pub(crate) fn with_default_enum_tagging(
+    spec: &ast::Spec,
+    tagging: &ast::EnumTagging,
+) -> Result<ast::Spec, LibError> {
+    let mut spec = spec.clone();
+    for edef in spec.iter_mut().filter_map(|item| match item {
+        ast::SpecItem::EnumDef(edef) if edef.tagging.is_none() => Some(edef),
+        _ => None,
+    }) {
+        parser::check_enum_tagging(tagging, &edef.variants).map_err(|reason| {
+            LibError::InvalidDefaultEnumTagging {
+                enum_name: edef.name.clone(),
+                reason,
+            }
+        })?;
+        edef.tagging = Some(tagging.clone());
+    }
+    Ok(spec)
+}
@@ -17,6 +17,10 @@ use ast::Spec;
 #[derive(Default)]
 struct Context {
     body: String,
+    /// The title of the page, see `Generator::title`.
+    title: String,
+    /// The `@version` of the spec, shown below the title.
+    version: Option<String>,
 }
 
 /// Wrapper struct which will emit the HTML-escaped version of the contained
@@ -57,7 +61,17 @@ impl<'a> fmt::Display for Escape<'a> {
 }
 
 impl Context {
+    fn new(title: String, version: Option<String>) -> Self {
+        Self {
+            title,
+            version,
+            ..Self::default()
+        }
+    }
+
     fn add_spec(&mut self, spec: &ast::Spec) -> &mut Self {
+        self.body.push_str(&Self::table_of_contents_to_html(spec));
+
         let spec_html = spec
             .iter()
             .map(|item| item.service_def())
@@ -65,12 +79,13 @@ impl Context {
             .map(|service| {
                 format!(
                     include_str!("docs/service.html"),
-                    serviceName = Escape(service.name.as_str()),
+                    serviceName = Escape(service.qualified_name(".").as_str()),
+                    serviceLink = Self::link_to_service(&service.qualified_name(".")),
                     serviceDescription = markdown_to_html(
                         service.doc_comment.as_deref().unwrap_or(""),
                         &basic_options()
                     ),
-                    serviceEndpoints = self.endpoints_to_html(&service.endpoints),
+                    serviceEndpoints = self.endpoints_to_html(spec, service),
                 )
             })
             .join("\n");
@@ -87,6 +102,44 @@ impl Context {
         self
     }
 
+    /// A table of contents linking to all services, their endpoints and all user defined types.
+    fn table_of_contents_to_html(spec: &ast::Spec) -> String {
+        format!(
+            include_str!("docs/toc.html"),
+            services = spec
+                .iter()
+                .filter_map(|item| item.service_def())
+                .map(|service| format!(
+                    r##"<li><a href="#{}">{}</a><ul>{}</ul></li>"##,
+                    Self::link_to_service(&service.qualified_name(".")),
+                    Escape(&service.qualified_name(".")),
+                    service
+                        .endpoints
+                        .iter()
+                        .map(|endpoint| format!(
+                            r##"<li><a href="#{}"><code>{}</code></a></li>"##,
+                            Self::components_to_link(service, &endpoint.route),
+                            Escape(&Self::route_to_text(service, &endpoint.route))
+                        ))
+                        .join("")
+                ))
+                .join(""),
+            userDefinedTypes = spec
+                .iter()
+                .filter_map(|item| match item {
+                    ast::SpecItem::StructDef(ast::StructDef { name, .. })
+                    | ast::SpecItem::EnumDef(ast::EnumDef { name, .. }) => Some(name),
+                    ast::SpecItem::ServiceDef(_) => None,
+                })
+                .map(|name| format!(
+                    r##"<li><a href="#{}">{}</a></li>"##,
+                    Self::link_to_user_defined_type(name),
+                    Escape(name)
+                ))
+                .join(""),
+        )
+    }
+
     fn user_defined_types_to_html(&mut self, spec: &ast::Spec) -> String {
         spec.iter()
             .filter_map(|item| match item {
@@ -98,7 +151,8 @@ impl Context {
                         struct_def.doc_comment.as_deref().unwrap_or(""),
                         &basic_options()
                     ),
-                    codeSamples = Self::struct_definition_to_html(struct_def),
+                    deprecation = Self::deprecation_to_html(&struct_def.deprecated),
+                    codeSamples = Self::struct_definition_to_html(spec, item, struct_def),
                     id = Self::link_to_user_defined_type(&struct_def.name)
                 )),
                 ast::SpecItem::EnumDef(enum_def) => Some(format!(
@@ -109,7 +163,8 @@ impl Context {
                         enum_def.doc_comment.as_deref().unwrap_or(""),
                         &basic_options()
                     ),
-                    codeSamples = Self::enum_definition_to_html(enum_def),
+                    deprecation = Self::deprecation_to_html(&enum_def.deprecated),
+                    codeSamples = Self::enum_definition_to_html(spec, item, enum_def),
                     id = Self::link_to_user_defined_type(&enum_def.name)
                 )),
                 _ => None,
@@ -135,6 +190,33 @@ impl Context {
         )
     }
 
+    /// A "Deprecated" badge followed by the note of a deprecated item, empty otherwise.
+    fn deprecation_to_html(deprecated: &Option<String>) -> String {
+        match deprecated {
+            Some(note) => format!(
+                r#"<div class="deprecated"><span class="deprecated--badge">Deprecated</span>{}</div>"#,
+                Escape(note)
+            ),
+            None => String::new(),
+        }
+    }
+
+    /// The type of a struct field as shown in its property table, along with the encoding of
+    /// `bytes` fields, the range of integer fields and the default value, if any.
+    fn field_type_to_html(field: &ast::FieldNode) -> String {
+        let mut html = Self::type_ident_to_html(&field.pair.type_ident);
+        if field.bytes_encoding != ast::BytesEncoding::default() {
+            html = format!("{} ({})", html, field.bytes_encoding.as_str());
+        }
+        if let Some(range) = field.pair.range {
+            html = format!("{} in {}", html, range);
+        }
+        if let Some(ref default) = field.default {
+            html = format!("{} = {}", html, Escape(&default.to_string()));
+        }
+        html
+    }
+
     fn generate_struct_property_table(struct_def: &ast::StructDef) -> String {
         format!(
             include_str!("docs/typedef_table_struct.html"),
@@ -145,10 +227,14 @@ impl Context {
                     format!(
                         include_str!("docs/typedef_table_struct_field.html"),
                         fieldName = Escape(&field_node.pair.name),
-                        fieldType = Self::type_ident_to_html(&field_node.pair.type_ident),
-                        fieldComment = markdown_to_html(
-                            &field_node.doc_comment.as_deref().unwrap_or(""),
-                            &basic_options()
+                        fieldType = Self::field_type_to_html(field_node),
+                        fieldComment = format!(
+                            "{}{}",
+                            Self::deprecation_to_html(&field_node.deprecated),
+                            markdown_to_html(
+                                &field_node.doc_comment.as_deref().unwrap_or(""),
+                                &basic_options()
+                            )
                         )
                     )
                 })
@@ -156,12 +242,47 @@ impl Context {
         )
     }
 
-    fn struct_definition_to_html(struct_def: &ast::StructDef) -> String {
-        // TODO: make a common interface/trait for all languages?! why does this not exist in the first place
-        let tabs = vec![(
+    /// Tabs showing an example JSON value of the user defined type `item` and its definition as
+    /// generated for each language.
+    fn code_sample_tabs(spec: &ast::Spec, item: &ast::SpecItem) -> Vec<(&'static str, String)> {
+        vec![
+            (
+                "Example JSON",
+                format!(
+                    include_str!("docs/typedef_for_language.html"),
+                    langId = "json",
+                    code = Escape(&example_json(spec, item)),
+                ),
+            ),
+            (
+                "Rust",
+                format!(
+                    include_str!("docs/typedef_for_language.html"),
+                    langId = "rust",
+                    code = Escape(&super::rust::render_type_def(item)),
+                ),
+            ),
+            (
+                "Elm",
+                format!(
+                    include_str!("docs/typedef_for_language.html"),
+                    langId = "elm",
+                    code = Escape(&super::elm::render_type_def(item)),
+                ),
+            ),
+        ]
+    }
+
+    fn struct_definition_to_html(
+        spec: &ast::Spec,
+        item: &ast::SpecItem,
+        struct_def: &ast::StructDef,
+    ) -> String {
+        let mut tabs = vec![(
             "Language Agnostic",
             Self::generate_struct_property_table(struct_def),
         )];
+        tabs.extend(Self::code_sample_tabs(spec, item));
 
         Self::tabbed_navigation_to_html(tabs)
     }
@@ -179,7 +300,10 @@ impl Context {
                             variantNestingDepth = 0,
                             variantNestingParent = "",
                             variantName = Escape(&variant.name),
-                            variantValue = "<i>empty</i>",
+                            variantValue = match &variant.value {
+                                Some(value) => format!("<code>\"{}\"</code>", Escape(value)),
+                                None => "<i>empty</i>".to_owned(),
+                            },
                             variantComment = markdown_to_html(
                                 &variant.doc_comment.as_deref().unwrap_or(""),
                                 &basic_options()
@@ -228,9 +352,13 @@ impl Context {
                                     variantNestingParent = struct_def.name,
                                     variantName = Escape(&field.pair.name),
                                     variantValue = Self::type_ident_to_html(&field.pair.type_ident),
-                                    variantComment = markdown_to_html(
-                                        &field.doc_comment.as_deref().unwrap_or(""),
-                                        &basic_options(),
+                                    variantComment = format!(
+                                        "{}{}",
+                                        Self::deprecation_to_html(&field.deprecated),
+                                        markdown_to_html(
+                                            &field.doc_comment.as_deref().unwrap_or(""),
+                                            &basic_options(),
+                                        )
                                     ),
                                 ));
                             }
@@ -242,25 +370,40 @@ impl Context {
         )
     }
 
-    fn enum_definition_to_html(enum_def: &ast::EnumDef) -> String {
-        // TODO: make a common interface/trait for all languages?! why does this not exist in the first place
-        let tabs = vec![(
+    fn enum_definition_to_html(
+        spec: &ast::Spec,
+        item: &ast::SpecItem,
+        enum_def: &ast::EnumDef,
+    ) -> String {
+        let mut tabs = vec![(
             "Language Agnostic",
             Self::generate_enum_variant_table(enum_def),
         )];
+        tabs.extend(Self::code_sample_tabs(spec, item));
 
         Self::tabbed_navigation_to_html(tabs)
     }
 
-    fn endpoints_to_html(&mut self, endpoints: &[ast::ServiceEndpoint]) -> String {
-        endpoints
+    fn endpoints_to_html(&mut self, spec: &ast::Spec, service: &ast::ServiceDef) -> String {
+        let base_html = service
+            .base_segments()
+            .iter()
+            .map(|segment| format!("/<span>{}</span>", Escape(segment)))
+            .join("");
+        service
+            .endpoints
             .iter()
             .map(|endpoint| {
                 format!(
                     include_str!("docs/endpoint.html"),
                     httpMethod = endpoint.route.http_method_as_str(),
-                    endpointRoute = Self::components_to_html(endpoint.route.components()),
-                    endpointLink = Self::components_to_link(&endpoint.route),
+                    endpointRoute = format!(
+                        "{}{}",
+                        base_html,
+                        Self::components_to_html(endpoint.route.components())
+                    ),
+                    endpointLink = Self::components_to_link(service, &endpoint.route),
+                    deprecation = Self::deprecation_to_html(&endpoint.deprecated),
                     endpointDescription = markdown_to_html(
                         endpoint.doc_comment.as_deref().unwrap_or(""),
                         &basic_options()
@@ -271,19 +414,103 @@ impl Context {
                         ),
                         &basic_options()
                     ),
-                    endpointReturn = Self::type_ident_to_html(endpoint.route.return_type()),
-                    endpointRouteQuery = endpoint
-                        .route
-                        .query()
-                        .as_ref()
-                        .map(|q| { format!("?{}", Self::type_ident_to_html(q)) })
-                        .unwrap_or_default(),
-                    endpointProperties = Self::properties_to_html(&endpoint.route),
+                    endpointReturn = Self::status_to_html(
+                        endpoint,
+                        match endpoint.route.response_kind() {
+                            ast::ResponseKind::Json => {
+                                Self::type_ident_to_html(endpoint.route.return_type())
+                            }
+                            ast::ResponseKind::Paginated => format!(
+                                "page of {}",
+                                Self::type_ident_to_html(endpoint.route.return_type())
+                            ),
+                            ast::ResponseKind::Raw => "raw bytes".to_owned(),
+                            ast::ResponseKind::Stream => format!(
+                                "stream[{}]",
+                                Self::type_ident_to_html(endpoint.route.return_type())
+                            ),
+                            ast::ResponseKind::WebSocket => format!(
+                                "messages of {}",
+                                Self::type_ident_to_html(endpoint.route.return_type())
+                            ),
+                            ast::ResponseKind::EventStream => format!(
+                                "events of {}",
+                                Self::type_ident_to_html(endpoint.route.return_type())
+                            ),
+                        }
+                    ),
+                    endpointRouteQuery = Self::query_to_html(&endpoint.route),
+                    endpointProperties = Self::endpoint_tabs_to_html(spec, service, endpoint),
                 )
             })
             .join("\n")
     }
 
+    /// Append the status code of successful responses to the return type of an endpoint,
+    /// unless it is the default, and the status code of errors declared using `@error_status`.
+    fn status_to_html(endpoint: &ast::ServiceEndpoint, return_html: String) -> String {
+        let route = &endpoint.route;
+        let return_html = match route.response_kind() {
+            ast::ResponseKind::WebSocket | ast::ResponseKind::EventStream => return_html,
+            _ if route.status() == 200 => return_html,
+            _ => format!(
+                r#"{} <span class="endpoint--status">status {}</span>"#,
+                return_html,
+                route.status()
+            ),
+        };
+        match endpoint.error_status {
+            Some(error_status) => format!(
+                r#"{} <span class="endpoint--status">error status {}</span>"#,
+                return_html, error_status
+            ),
+            None => return_html,
+        }
+    }
+
+    fn endpoint_tabs_to_html(
+        spec: &ast::Spec,
+        service: &ast::ServiceDef,
+        endpoint: &ast::ServiceEndpoint,
+    ) -> String {
+        let route = &endpoint.route;
+        let mut tabs = vec![("Language Agnostic", Self::properties_to_html(endpoint))];
+        // curl cannot talk to WebSockets
+        if !route.is_websocket() {
+            tabs.push((
+                "curl",
+                format!(
+                    include_str!("docs/typedef_for_language.html"),
+                    langId = "shell",
+                    code = Escape(&curl_example(spec, service, route)),
+                ),
+            ));
+        }
+
+        Self::tabbed_navigation_to_html(tabs)
+    }
+
+    fn query_to_html(route: &ast::ServiceRoute) -> String {
+        if let Some(query) = route.query() {
+            format!("?{}", Self::type_ident_to_html(query))
+        } else if !route.query_fields().is_empty() {
+            format!(
+                "?{}",
+                route
+                    .query_fields()
+                    .iter()
+                    .map(|field| format!(
+                        "{}: {}",
+                        field.name,
+                        Self::type_ident_to_html(&field.type_ident)
+                    ))
+                    .join("&amp;")
+            )
+        } else {
+            String::new()
+        }
+    }
+
     pub fn atom_to_html(t: ast::AtomType) -> &'static str {
         match t {
             ast::AtomType::Empty => "empty",
@@ -291,12 +518,25 @@ impl Context {
             ast::AtomType::I32 => "int",
             ast::AtomType::U32 => "uint",
             ast::AtomType::U8 => "uint",
+            ast::AtomType::I64 => "int",
+            ast::AtomType::U64 => "uint",
+            ast::AtomType::U128 => "u128",
+            ast::AtomType::I128 => "i128",
+            ast::AtomType::I16 => "int",
+            ast::AtomType::U16 => "uint",
+            ast::AtomType::I8 => "int",
+            ast::AtomType::F32 => "float",
             ast::AtomType::F64 => "float",
             ast::AtomType::Bool => "bool",
             ast::AtomType::DateTime => "datetime",
             ast::AtomType::Date => "date",
             ast::AtomType::Uuid => "uuid",
+            ast::AtomType::Url => "url",
+            ast::AtomType::IpAddr => "ip",
+            ast::AtomType::Duration => "duration",
             ast::AtomType::Bytes => "bytes",
+            ast::AtomType::Decimal => "decimal",
+            ast::AtomType::Json => "json",
         }
     }
 
@@ -315,7 +555,11 @@ impl Context {
         match type_ident {
             ast::TypeIdent::BuiltIn(atom) => Self::atom_to_html(*atom).to_string(),
             ast::TypeIdent::List(ty) => format!("list[{}]", Self::type_ident_to_html(&*ty)),
+            ast::TypeIdent::Set(ty) => format!("set[{}]", Self::type_ident_to_html(ty)),
             ast::TypeIdent::Option(ty) => format!("option[{}]", Self::type_ident_to_html(&*ty)),
+            ast::TypeIdent::Nullable(ty) => {
+                format!("nullable[{}]", Self::type_ident_to_html(ty))
+            }
             ast::TypeIdent::Result(ty1, ty2) => format!(
                 "result[{},{}]",
                 Self::type_ident_to_html(&*ty1),
@@ -326,6 +570,11 @@ impl Context {
                 Self::type_ident_to_html(&*ty1),
                 Self::type_ident_to_html(&*ty2)
             ),
+            ast::TypeIdent::OrderedMap(ty1, ty2) => format!(
+                "omap[{},{}]",
+                Self::type_ident_to_html(ty1),
+                Self::type_ident_to_html(ty2)
+            ),
             ast::TypeIdent::Tuple(tuple) => Self::tuple_def_to_html(tuple),
             ast::TypeIdent::UserDefined(name) => format!(
                 r##"<a href="#{}">{}</a>"##,
@@ -339,6 +588,34 @@ impl Context {
         format!("type-{}", name)
     }
 
+    pub fn link_to_service(name: &str) -> String {
+        format!("service-{}", name)
+    }
+
+    /// The method and path of an endpoint as plain text, e.g. `GET /v1/monsters/{id}`.
+    pub fn route_to_text(service: &ast::ServiceDef, route: &ast::ServiceRoute) -> String {
+        let path = route
+            .components()
+            .iter()
+            .map(|c| match c {
+                ast::ServiceRouteComponent::Literal(lit) => format!("/{}", lit),
+                ast::ServiceRouteComponent::Variable(ast::FieldDefPair { name, .. }, _) => {
+                    format!("/{{{}}}", name)
+                }
+            })
+            .join("");
+
+        match (service.base.as_deref(), path.as_str()) {
+            (None, "") => format!("{} /", route.http_method_as_str()),
+            (base, path) => format!(
+                "{} {}{}",
+                route.http_method_as_str(),
+                base.unwrap_or(""),
+                path
+            ),
+        }
+    }
+
     pub fn components_to_html(components: &[ast::ServiceRouteComponent]) -> String {
         components
             .iter()
@@ -346,24 +623,40 @@ impl Context {
                 ast::ServiceRouteComponent::Literal(lit) => {
                     format!("/<span>{}</span>", Escape(&lit))
                 }
-                ast::ServiceRouteComponent::Variable(ast::FieldDefPair { name, type_ident }) => {
+                ast::ServiceRouteComponent::Variable(
+                    ast::FieldDefPair {
+                        name, type_ident, ..
+                    },
+                    pattern,
+                ) => {
+                    let pattern = match pattern {
+                        ast::RouteParamPattern::Segment => String::new(),
+                        ast::RouteParamPattern::Rest => " as \"rest\"".to_owned(),
+                        ast::RouteParamPattern::Regex(regex) => format!(" as /{}/", regex),
+                    };
                     format!(
-                        "/<var><span class=\"var-bracket\">{{</span><span class=\"var-name\">{}</span><span class=\"var-ty-name-sep\">:</span><span class=\"var-ty\">{}</span><span class=\"var-bracket\">}}</span></var>",
+                        "/<var><span class=\"var-bracket\">{{</span><span class=\"var-name\">{}</span><span class=\"var-ty-name-sep\">:</span><span class=\"var-ty\">{}</span>{}<span class=\"var-bracket\">}}</span></var>",
                         Escape(&name),
-                        Escape(&Self::type_ident_to_html(&type_ident))
+                        Escape(&Self::type_ident_to_html(&type_ident)),
+                        Escape(&pattern)
                     )
                 }
             })
             .join("")
     }
 
-    pub fn components_to_link(route: &ast::ServiceRoute) -> String {
+    pub fn components_to_link(service: &ast::ServiceDef, route: &ast::ServiceRoute) -> String {
         let component_str = route
             .components()
             .iter()
             .map(|c| match c {
                 ast::ServiceRouteComponent::Literal(lit) => format!("/{}", Escape(&lit)),
-                ast::ServiceRouteComponent::Variable(ast::FieldDefPair { name, type_ident }) => {
+                ast::ServiceRouteComponent::Variable(
+                    ast::FieldDefPair {
+                        name, type_ident, ..
+                    },
+                    _,
+                ) => {
                     format!(
                         "/{}:{}",
                         Escape(&name),
@@ -373,22 +666,65 @@ impl Context {
             })
             .join("");
 
-        format!("{}{}", route.http_method_as_str(), component_str)
+        format!(
+            "{}{}{}",
+            route.http_method_as_str(),
+            Escape(service.base.as_deref().unwrap_or("")),
+            component_str
+        )
     }
 
-    pub fn properties_to_html(route: &ast::ServiceRoute) -> String {
-        match route.request_body() {
+    /// The body type of an endpoint, along with its maximum size if declared using `@max_body`,
+    /// and its headers.
+    pub fn properties_to_html(endpoint: &ast::ServiceEndpoint) -> String {
+        let route = &endpoint.route;
+        let body = match route.request_body() {
             Some(type_ident) => format!(
                 include_str!("docs/endpoint-properties.html"),
-                endpointBody = Self::type_ident_to_html(type_ident),
+                endpointBody = match endpoint.max_body_size {
+                    Some(max_body_size) => format!(
+                        r#"{} <span class="endpoint--body-limit">at most {}</span>"#,
+                        Self::type_ident_to_html(type_ident),
+                        byte_size_to_string(max_body_size)
+                    ),
+                    None => Self::type_ident_to_html(type_ident),
+                },
             ),
             None => "".to_owned(),
+        };
+        format!("{}{}", body, Self::headers_to_html(route.headers()))
+    }
+
+    pub fn headers_to_html(headers: &[ast::HeaderBinding]) -> String {
+        if headers.is_empty() {
+            return "".to_owned();
         }
+        format!(
+            include_str!("docs/endpoint-headers.html"),
+            endpointHeaders = headers
+                .iter()
+                .map(|header| format!(
+                    "<tr><td><code>{}</code><td><code>{}</code>",
+                    Escape(&header.name),
+                    Self::type_ident_to_html(&header.type_ident)
+                ))
+                .join("\n")
+        )
     }
 
     // FIXME: Consider renaming this
     #[allow(clippy::wrong_self_convention)]
-    fn to_html(&mut self) -> String {
+    fn to_html(&mut self, assets: Assets) -> String {
+        let (style, script) = match assets {
+            Assets::Inline => (
+                format!("<style>\n{}\n</style>", stylesheet()),
+                format!("<script>\n{}\n</script>", include_str!("docs/script.js")),
+            ),
+            Assets::Linked => (
+                format!(r#"<link href="{}" rel="stylesheet" />"#, STYLESHEET_FILE),
+                format!(r#"<script src="{}"></script>"#, SCRIPT_FILE),
+            ),
+        };
         vec![
             "<!doctype html>",
             r#"<meta charset="utf-8">"#,
@@ -397,34 +733,61 @@ impl Context {
             "</title>",
             r#"<meta name="viewport" content="width=device-width, initial-scale=1">"#,
             include_str!("docs/external_head.html"),
-            "<style>",
-            //include_str!("docs/prism.css"),
-            include_str!("docs/main.css"),
-            &inline_svg_icon("link", include_str!("docs/unicode-symbol-1f517.svg")),
-            &inline_svg_icon(
-                "chevron-contract",
-                include_str!("docs/bootstrap-icons/chevron-contract.svg"),
-            ),
-            &inline_svg_icon(
-                "chevron-expand",
-                include_str!("docs/bootstrap-icons/chevron-expand.svg"),
-            ),
-            &inline_svg_icon("search", include_str!("docs/bootstrap-icons/search.svg")),
-            "</style>",
+            &style,
             "<body>",
             include_str!("docs/page_head.html"),
+            &format!(r#"<h1 class="page-title">{}</h1>"#, self.spec_name()),
+            &self.version_html(),
             &self.body,
-            "<script>",
-            include_str!("docs/script.js"),
-            "</script>",
+            &script,
             include_str!("docs/external_body.html"),
         ]
         .join("\n")
     }
 
     fn spec_name(&self) -> String {
-        String::new()
+        Escape(&self.title).to_string()
     }
+
+    fn version_html(&self) -> String {
+        match self.version {
+            Some(ref version) => {
+                format!(r#"<p class="page-version">Version {}</p>"#, Escape(version))
+            }
+            None => String::new(),
+        }
+    }
+}
+
+/// How the stylesheet and script are included in the generated HTML.
+enum Assets {
+    /// Inlined into the HTML, producing a single self-contained file.
+    Inline,
+    /// Referenced from the HTML, expecting them as `STYLESHEET_FILE` and `SCRIPT_FILE` next to it.
+    Linked,
+}
+
+const INDEX_FILE: &str = "index.html";
+const STYLESHEET_FILE: &str = "main.css";
+const SCRIPT_FILE: &str = "script.js";
+
+/// The stylesheet of the documentation, including the icons.
+fn stylesheet() -> String {
+    [
+        //include_str!("docs/prism.css"),
+        include_str!("docs/main.css"),
+        &inline_svg_icon("link", include_str!("docs/unicode-symbol-1f517.svg")),
+        &inline_svg_icon(
+            "chevron-contract",
+            include_str!("docs/bootstrap-icons/chevron-contract.svg"),
+        ),
+        &inline_svg_icon(
+            "chevron-expand",
+            include_str!("docs/bootstrap-icons/chevron-expand.svg"),
+        ),
+        &inline_svg_icon("search", include_str!("docs/bootstrap-icons/search.svg")),
+    ]
+    .join("\n")
 }
 
 fn inline_svg_icon(class_name: &str, svg: &str) -> String {
@@ -435,28 +798,369 @@ fn inline_svg_icon(class_name: &str, svg: &str) -> String {
     )
 }
 
-fn markdown_get_first_line_as_summary(markdown: &str) -> String {
-    let first_sentence = markdown.split("\n\n").next().unwrap_or("");
-    if first_sentence.len() > 100 {
-        format!("{}...", &first_sentence[0..97])
+/// A `curl` command calling the endpoint `route` of `service`, using example values for path
+/// and query parameters, headers and the request body. The service is expected at `$BASE_URL`.
+fn curl_example(spec: &ast::Spec, service: &ast::ServiceDef, route: &ast::ServiceRoute) -> String {
+    let method = match route {
+        ast::ServiceRoute::Sse { .. } => "GET",
+        route => route.http_method_as_str(),
+    };
+
+    let path = route
+        .components()
+        .iter()
+        .map(|c| match c {
+            ast::ServiceRouteComponent::Literal(lit) => format!("/{}", lit),
+            ast::ServiceRouteComponent::Variable(pair, _) => {
+                format!("/{}", example_param(spec, &pair.type_ident))
+            }
+        })
+        .join("");
+    let query_fields = match route.query() {
+        Some(ast::TypeIdent::UserDefined(name)) => find_struct(spec, name)
+            .map(|sdef| {
+                sdef.fields
+                    .iter()
+                    .map(|field| (field.wire_name(sdef.rename_all), &field.pair.type_ident))
+                    .collect()
+            })
+            .unwrap_or_default(),
+        _ => route
+            .query_fields()
+            .iter()
+            .map(|pair| (pair.name.clone(), &pair.type_ident))
+            .collect::<Vec<_>>(),
+    };
+    let query = if query_fields.is_empty() {
+        String::new()
     } else {
-        first_sentence.to_string()
+        format!(
+            "?{}",
+            query_fields
+                .iter()
+                .map(|(name, ty)| format!("{}={}", name, example_param(spec, ty)))
+                .join("&")
+        )
+    };
+
+    let mut lines = vec![format!(
+        "curl -X {} \"${{BASE_URL}}{}{}{}\"",
+        method,
+        service.base.as_deref().unwrap_or(""),
+        path,
+        query
+    )];
+    if route.is_event_stream() {
+        lines.push("-N".to_owned());
+        lines.push("-H 'Accept: text/event-stream'".to_owned());
+    }
+    for header in route.headers() {
+        lines.push(format!(
+            "-H '{}: {}'",
+            header.name,
+            example_param(spec, &header.type_ident)
+        ));
+    }
+    if let Some(body) = route.request_body() {
+        let body = serde_json::to_string_pretty(&example_value(spec, body, &mut Vec::new()))
+            .expect("example values are valid JSON");
+        lines.push("-H 'Content-Type: application/json'".to_owned());
+        // end the single-quoted string to insert a quote
+        lines.push(format!("-d '{}'", body.replace('\'', "'\\''")));
+    }
+
+    lines.join(" \\\n  ")
+}
+
+/// An example value of a path or query parameter or header of type `ty`.
+fn example_param(spec: &ast::Spec, ty: &ast::TypeIdent) -> String {
+    match example_value(spec, ty, &mut Vec::new()) {
+        serde_json::Value::String(s) => s,
+        value => value.to_string(),
+    }
+}
+
+/// A pretty-printed example JSON value of the struct or enum `item`.
+fn example_json(spec: &ast::Spec, item: &ast::SpecItem) -> String {
+    let value = match item {
+        ast::SpecItem::StructDef(sdef) => example_fields(
+            spec,
+            &sdef.fields,
+            sdef.rename_all,
+            &mut vec![sdef.name.as_str()],
+        ),
+        ast::SpecItem::EnumDef(edef) => example_enum(spec, edef, &mut vec![edef.name.as_str()]),
+        ast::SpecItem::ServiceDef(_) => serde_json::Value::Null,
+    };
+    serde_json::to_string_pretty(&value).expect("serialize example value")
+}
+
+/// An example JSON value of type `ty`. `seen` contains the user defined types currently being
+/// expanded, recursive occurrences are represented as `null`.
+fn example_value<'a>(
+    spec: &'a ast::Spec,
+    ty: &'a ast::TypeIdent,
+    seen: &mut Vec<&'a str>,
+) -> serde_json::Value {
+    use serde_json::{json, Map, Value};
+
+    match ty {
+        ast::TypeIdent::BuiltIn(atom) => match atom {
+            ast::AtomType::Empty => Value::Null,
+            ast::AtomType::Str => json!("string"),
+            ast::AtomType::I32
+            | ast::AtomType::U32
+            | ast::AtomType::U8
+            | ast::AtomType::I64
+            | ast::AtomType::U64
+            | ast::AtomType::I16
+            | ast::AtomType::U16
+            | ast::AtomType::I8 => json!(0),
+            ast::AtomType::U128 | ast::AtomType::I128 => json!("0"),
+            ast::AtomType::F32 | ast::AtomType::F64 => json!(0.0),
+            ast::AtomType::Bool => json!(false),
+            ast::AtomType::DateTime => json!("2020-01-01T00:00:00Z"),
+            ast::AtomType::Date => json!("2020-01-01"),
+            ast::AtomType::Uuid => json!("00000000-0000-0000-0000-000000000000"),
+            ast::AtomType::Url => json!("https://example.com/"),
+            ast::AtomType::IpAddr => json!("192.0.2.1"),
+            ast::AtomType::Duration => json!(1500),
+            ast::AtomType::Bytes => json!(""),
+            ast::AtomType::Decimal => json!("0.0"),
+            ast::AtomType::Json => json!({}),
+        },
+        ast::TypeIdent::List(inner) | ast::TypeIdent::Set(inner) => {
+            json!([example_value(spec, inner, seen)])
+        }
+        ast::TypeIdent::Option(inner) | ast::TypeIdent::Nullable(inner) => {
+            example_value(spec, inner, seen)
+        }
+        ast::TypeIdent::Result(ok, _) => json!({ "Ok": example_value(spec, ok, seen) }),
+        ast::TypeIdent::Map(key, value) | ast::TypeIdent::OrderedMap(key, value) => {
+            let mut map = Map::new();
+            map.insert(example_param(spec, key), example_value(spec, value, seen));
+            Value::Object(map)
+        }
+        ast::TypeIdent::Tuple(tuple) => example_tuple(spec, tuple, seen),
+        ast::TypeIdent::UserDefined(name) if seen.contains(&name.as_str()) => Value::Null,
+        ast::TypeIdent::UserDefined(name) => {
+            seen.push(name);
+            let value = if let Some(sdef) = find_struct(spec, name) {
+                example_fields(spec, &sdef.fields, sdef.rename_all, seen)
+            } else if let Some(edef) = spec
+                .iter()
+                .filter_map(|item| item.enum_def())
+                .find(|edef| &edef.name == name)
+            {
+                example_enum(spec, edef, seen)
+            } else {
+                Value::Null
+            };
+            seen.pop();
+            value
+        }
+    }
+}
+
+/// An example JSON array of a tuple's elements.
+fn example_tuple<'a>(
+    spec: &'a ast::Spec,
+    tuple: &'a ast::TupleDef,
+    seen: &mut Vec<&'a str>,
+) -> serde_json::Value {
+    serde_json::Value::Array(
+        tuple
+            .elements()
+            .iter()
+            .map(|element| example_value(spec, element, seen))
+            .collect(),
+    )
+}
+
+/// An example JSON object with the given fields. Fields show their default value, if any, and
+/// integer fields a value within their range, so that the server accepts the example.
+fn example_fields<'a>(
+    spec: &'a ast::Spec,
+    fields: &'a ast::StructFields,
+    rename_all: Option<ast::RenameRule>,
+    seen: &mut Vec<&'a str>,
+) -> serde_json::Value {
+    use serde_json::{json, Value};
+
+    Value::Object(
+        fields
+            .iter()
+            .map(|field| {
+                let value = match (&field.default, field.pair.range) {
+                    (Some(ast::DefaultValue::Bool(b)), _) => json!(b),
+                    (Some(ast::DefaultValue::Int(i)), _) => json!(i),
+                    (Some(ast::DefaultValue::Float(f)), _) => json!(f),
+                    (Some(ast::DefaultValue::Str(s)), _) => json!(s),
+                    // the value closest to zero
+                    (None, Some(range)) => {
+                        let value = range.min.map_or(0, |min| min.max(0));
+                        json!(range.max.map_or(value, |max| max.min(value)))
+                    }
+                    (None, None) => example_value(spec, &field.pair.type_ident, seen),
+                };
+                (field.wire_name(rename_all), value)
+            })
+            .collect(),
+    )
+}
+
+/// An example JSON value of the first variant of an enum, following its serde representation.
+fn example_enum<'a>(
+    spec: &'a ast::Spec,
+    edef: &'a ast::EnumDef,
+    seen: &mut Vec<&'a str>,
+) -> serde_json::Value {
+    use serde_json::{json, Value};
+
+    let variant = match edef.variants.first() {
+        Some(variant) => variant,
+        None => return Value::Null,
+    };
+    let content = match &variant.variant_type {
+        ast::VariantType::Simple => None,
+        ast::VariantType::Tuple(tuple) => Some(example_tuple(spec, tuple, seen)),
+        ast::VariantType::Struct(fields) => Some(example_fields(spec, fields, None, seen)),
+        ast::VariantType::Newtype(ty) => Some(example_value(spec, ty, seen)),
+    };
+    match (edef.tagging(), content) {
+        (ast::EnumTagging::External, None) => json!(variant.wire_name()),
+        (ast::EnumTagging::External, Some(content)) => json!({ &variant.name: content }),
+        (ast::EnumTagging::Internal { tag }, content) => {
+            let mut value = match content {
+                Some(Value::Object(fields)) => fields,
+                _ => Default::default(),
+            };
+            value.insert(tag.clone(), json!(variant.wire_name()));
+            Value::Object(value)
+        }
+        (ast::EnumTagging::Adjacent { tag, .. }, None) => json!({ tag: variant.wire_name() }),
+        (ast::EnumTagging::Adjacent { tag, content: key }, Some(content)) => {
+            json!({ tag: variant.name, key: content })
+        }
+    }
+}
+
+fn find_struct<'a>(spec: &'a ast::Spec, name: &str) -> Option<&'a ast::StructDef> {
+    spec.iter()
+        .filter_map(|item| item.struct_def())
+        .find(|sdef| sdef.name == name)
+}
+
+/// A size in bytes in the largest binary unit that divides it, e.g. `1 MiB` or `1500 bytes`.
+fn byte_size_to_string(bytes: usize) -> String {
+    let units = [(1 << 30, "GiB"), (1 << 20, "MiB"), (1 << 10, "KiB")];
+    if bytes == 0 {
+        return "0 bytes".to_owned();
+    }
+    match units.iter().find(|(size, _)| bytes.is_multiple_of(*size)) {
+        Some((size, unit)) => format!("{} {}", bytes / size, unit),
+        None => format!("{} bytes", bytes),
+    }
+}
+
+/// The maximum length of the summary of an endpoint, in characters.
+const SUMMARY_MAX_CHARS: usize = 100;
+
+/// The summary of an endpoint shown next to its route: the first paragraph of its doc comment.
+///
+/// Paragraphs longer than `SUMMARY_MAX_CHARS` are shortened to their first sentence if it is
+/// short enough, or else to as many words as fit, followed by `...`.
+fn markdown_get_first_line_as_summary(markdown: &str) -> String {
+    let first_paragraph = markdown
+        .lines()
+        .map(str::trim)
+        .skip_while(|line| line.is_empty())
+        .take_while(|line| !line.is_empty())
+        .join(" ");
+    if first_paragraph.chars().count() <= SUMMARY_MAX_CHARS {
+        return first_paragraph;
+    }
+
+    let chars: Vec<(usize, char)> = first_paragraph.char_indices().collect();
+    let mut sentence_end = None;
+    let mut last_word_end = None;
+    for (n, &(i, c)) in chars.iter().enumerate().take(SUMMARY_MAX_CHARS) {
+        let next = chars.get(n + 1).map(|&(_, next)| next);
+        let at_end_of_word = !matches!(next, Some(next) if !next.is_whitespace());
+        if matches!(c, '.' | '!' | '?') && at_end_of_word {
+            sentence_end = Some(i + c.len_utf8());
+            break;
+        }
+        // the shortened summary has to leave room for the ellipsis
+        if c.is_whitespace() && n <= SUMMARY_MAX_CHARS - 3 {
+            last_word_end = Some(i);
+        }
+    }
+
+    match (sentence_end, last_word_end) {
+        (Some(end), _) => first_paragraph[..end].to_owned(),
+        (None, Some(end)) => format!(
+            "{}...",
+            first_paragraph[..end].trim_end_matches(|c: char| c.is_whitespace() || c == ',')
+        ),
+        // a single word that is too long
+        (None, None) => format!(
+            "{}...",
+            first_paragraph
+                .chars()
+                .take(SUMMARY_MAX_CHARS - 3)
+                .collect::<String>()
+        ),
     }
 }
 
 #[derive(Default)]
 pub struct Generator {}
 
+impl Generator {
+    /// The title of the documentation, set using `@title` in the spec, or else the name of the
+    /// output file (or directory) like in the OpenAPI backend.
+    fn title(spec: &Spec, output: &Path) -> String {
+        spec.title.clone().unwrap_or_else(|| {
+            output
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+                .unwrap_or_default()
+        })
+    }
+
+    /// Write the documentation to `INDEX_FILE`, with the stylesheet and script as separate
+    /// files next to it, all placed in the directory `output`.
+    fn generate_dir(&self, spec: &Spec, output: &Path) -> Result<(), LibError> {
+        let docs = Context::new(Self::title(spec, output), spec.version.clone())
+            .add_spec(spec)
+            .to_html(Assets::Linked);
+
+        write_file(&output.join(INDEX_FILE), &docs)?;
+        write_file(&output.join(STYLESHEET_FILE), &stylesheet())?;
+        write_file(&output.join(SCRIPT_FILE), include_str!("docs/script.js"))
+    }
+}
+
+fn write_file(path: &Path, contents: &str) -> Result<(), LibError> {
+    let mut outfile = File::create(path).map_err(LibError::IoError)?;
+    outfile
+        .write_all(contents.as_bytes())
+        .map_err(LibError::IoError)
+}
+
 impl crate::CodeGenerator for Generator {
+    /// Writes the documentation as a single HTML file `output`. If `output` is an existing
+    /// directory, the stylesheet and script are written as separate files instead.
     fn generate(&self, spec: &Spec, output: &Path) -> Result<(), LibError> {
-        let docs = Context::default().add_spec(spec).to_html();
+        if output.is_dir() {
+            return self.generate_dir(spec, output);
+        }
+        let docs = Context::new(Self::title(spec, output), spec.version.clone())
+            .add_spec(spec)
+            .to_html(Assets::Inline);
 
-        // TODO: support folder as output path
-        let mut outfile = File::create(&output).map_err(LibError::IoError)?;
-        outfile
-            .write_all(docs.as_bytes())
-            .map_err(LibError::IoError)?;
-        Ok(())
+        write_file(output, &docs)
     }
 }
 
@@ -3,14 +3,16 @@
 // TODO: Fix lints and remove this.
 #![allow(clippy::write_literal)]
 
-use crate::{ast, Artifact, LibError, Spec};
+use crate::{ast, backend, Artifact, LibError, Spec};
 use anyhow::Result;
 use inflector::cases::camelcase::to_camel_case;
 use std::io::{self, BufWriter};
 use std::{
+    cell::RefCell,
     fs::{self, File},
     io::Write,
     path::{Path, PathBuf},
+    rc::Rc,
 };
 
 const BACKEND_NAME: &str = "elm";
@@ -18,6 +20,7 @@ const BACKEND_NAME: &str = "elm";
 pub mod decoder_generation;
 pub mod encoder_generation;
 pub mod endpoint_generation;
+mod map_keys;
 pub mod type_generation;
 
 pub(crate) struct IndentWriter {
@@ -42,6 +45,13 @@ impl IndentWriter {
         })
     }
 
+    fn for_buffer(buffer: SharedBuffer) -> Self {
+        Self {
+            outstream: Box::new(buffer),
+            indent: 0,
+        }
+    }
+
     fn kill_indent(&mut self) {
         self.indent = 0;
     }
@@ -79,13 +89,95 @@ impl IndentWriter {
     }
 }
 
-fn generate_doc_comment(_doc_comment: &Option<String>) -> String {
-    // TODO: figure out escaping rules
-    // match doc_comment {
-    //     Some(ref ds) => format!("{{-| {ds}\n-}}", ds = ds),
-    //     None => "".to_owned(),
-    // }
-    "".to_owned()
+/// An in-memory output of an `IndentWriter` that can still be read after writing.
+#[derive(Clone, Default)]
+struct SharedBuffer(Rc<RefCell<Vec<u8>>>);
+
+impl io::Write for SharedBuffer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.borrow_mut().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Render the Elm definition of a user defined type as generated into `Data.elm`.
+/// Used for code samples in the documentation.
+pub(crate) fn render_type_def(item: &ast::SpecItem) -> String {
+    let buffer = SharedBuffer::default();
+    let mut file = IndentWriter::for_buffer(buffer.clone());
+    match item {
+        ast::SpecItem::StructDef(sdef) => type_generation::generate_struct_def(sdef, &mut file),
+        ast::SpecItem::EnumDef(edef) => type_generation::generate_enum_def(edef, &mut file),
+        ast::SpecItem::ServiceDef(_) => Ok(()),
+    }
+    .expect("writing to memory cannot fail");
+
+    let code = buffer.0.borrow();
+    String::from_utf8_lossy(&code).trim().to_owned()
+}
+
+/// Generate an Elm doc comment (`{-| ... -}`), which must directly precede a declaration.
+fn generate_doc_comment(doc_comment: &Option<String>) -> String {
+    match doc_comment {
+        Some(ref ds) => format!("{{-| {}\n-}}", escape_comment(ds)),
+        None => "".to_owned(),
+    }
+}
+
+/// Generate an Elm doc comment for a type, listing its documented fields or variants as a
+/// markdown bullet list, since Elm does not allow documenting them inline.
+fn generate_type_doc_comment(
+    doc_comment: &Option<String>,
+    members: impl Iterator<Item = (String, Option<String>)>,
+) -> String {
+    let members: Vec<_> = members
+        .filter_map(|(name, doc)| {
+            let doc = doc?;
+            // continuation lines are indented to stay within the list item
+            let doc = doc
+                .lines()
+                .map(|line| match line {
+                    "" => "".to_owned(),
+                    line => format!("    {}", line),
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            Some(format!("  - `{}`: {}", name, doc.trim_start()))
+        })
+        .collect();
+    let doc_comment = match (doc_comment, members.is_empty()) {
+        (None, true) => None,
+        (Some(doc), true) => Some(doc.clone()),
+        (None, false) => Some(members.join("\n")),
+        (Some(doc), false) => Some(format!("{}\n\n{}", doc, members.join("\n"))),
+    };
+    generate_doc_comment(&doc_comment)
+}
+
+/// Append the note of a deprecated item to its doc comment, since Elm has no deprecation
+/// attribute.
+fn with_deprecation(doc_comment: &Option<String>, deprecated: &Option<String>) -> Option<String> {
+    match (doc_comment, deprecated) {
+        (_, None) => doc_comment.clone(),
+        (None, Some(note)) => Some(format!("**Deprecated:** {}", note)),
+        (Some(doc), Some(note)) => Some(format!("{}\n\n**Deprecated:** {}", doc, note)),
+    }
+}
+
+/// Generate a regular Elm comment, e.g. for documentation not attached to a declaration.
+fn generate_comment(comment: &Option<String>) -> String {
+    match comment {
+        Some(ref c) => format!("{{- {}\n-}}", escape_comment(c)),
+        None => "".to_owned(),
+    }
+}
+
+/// Escape a comment's text so that it neither ends the comment nor opens a nested one.
+fn escape_comment(s: &str) -> String {
+    s.replace("{-", "{ -").replace("-}", "- }")
 }
 
 fn to_atom(s: String) -> String {
@@ -103,6 +195,7 @@ fn field_name(ident: &str) -> String {
 pub struct Generator {
     module_prefix: String,
     _artifact: Artifact,
+    default_enum_tagging: ast::EnumTagging,
 }
 
 impl Generator {
@@ -111,6 +204,7 @@ impl Generator {
             Artifact::TypesOnly | Artifact::ClientEndpoints => Ok(Self {
                 module_prefix,
                 _artifact: artifact,
+                default_enum_tagging: ast::EnumTagging::default(),
             }),
             Artifact::ServerEndpoints => Err(LibError::UnsupportedArtifact {
                 artifact,
@@ -119,6 +213,13 @@ impl Generator {
         }
     }
 
+    /// Represent enums without a `@tag(...)` annotation using `tagging` instead of external
+    /// tagging, like `rust::Generator::with_default_enum_tagging` does.
+    pub fn with_default_enum_tagging(mut self, tagging: ast::EnumTagging) -> Self {
+        self.default_enum_tagging = tagging;
+        self
+    }
+
     fn make_file(&self, _spec: &Spec, outdir: &Path, name: &str) -> Result<IndentWriter, LibError> {
         // TODO: populate mem filesystem or temp folder first, then make everything visible at once
         // to avoid partial write out on error
@@ -162,6 +263,15 @@ impl Generator {
             )?;
         }
 
+        {
+            let mut file = self.make_file(spec, outdir, "BuiltIn/Decimal")?;
+            write!(
+                file.handle(),
+                "{}",
+                include_str!("./elm/builtin_type_decimal.elm"),
+            )?;
+        }
+
         let mut file = self.make_file(spec, outdir, "Data")?;
         write!(
             file.start_line()?,
@@ -170,6 +280,9 @@ impl Generator {
         )?;
         file.empty_lines(2)?;
 
+        if let Some(ref version) = spec.version {
+            type_generation::generate_api_version(version, &mut file)?;
+        }
         for spec_item in spec.iter() {
             match spec_item {
                 ast::SpecItem::StructDef(sdef) => {
@@ -181,6 +294,9 @@ impl Generator {
                 ast::SpecItem::ServiceDef(_) => {}
             };
         }
+        if spec.has_paginated_endpoints() {
+            type_generation::generate_page_def(&mut file)?;
+        }
 
         Ok(())
     }
@@ -247,8 +363,15 @@ impl Generator {
             match spec_item {
                 ast::SpecItem::StructDef(..) | ast::SpecItem::EnumDef(..) => {}
                 ast::SpecItem::ServiceDef(service) => {
-                    let mut file =
-                        self.make_file(spec, outdir, &format!("Service/{}", service.name))?;
+                    // services of namespaces are placed in nested modules
+                    let mut namespace_dir = outdir.join("Service");
+                    namespace_dir.extend(&service.namespace);
+                    fs::create_dir_all(namespace_dir)?;
+                    let mut file = self.make_file(
+                        spec,
+                        outdir,
+                        &format!("Service/{}", service.qualified_name("/")),
+                    )?;
                     write!(
                         file.start_line()?,
                         "import {}.Data as Ty",
@@ -310,6 +433,12 @@ impl Generator {
 impl crate::CodeGenerator for Generator {
     fn generate(&self, spec: &Spec, output: &Path) -> Result<(), LibError> {
         Self::validate_output_dir(&output)?;
+        let spec = &backend::with_default_enum_tagging(spec, &self.default_enum_tagging)?;
+        map_keys::check_map_keys(spec)?;
+        map_keys::check_set_elements(spec)?;
+        for service in spec.iter().filter_map(ast::SpecItem::service_def) {
+            endpoint_generation::endpoint_names(service)?;
+        }
 
         self.generate_user_defined_types(&spec, &output)?;
         self.generate_decoders(&spec, &output)?;
@@ -1,3 +1,6 @@
+import Bytes
+import Bytes.Decode
+import Dict
 import Http
 import Json.Decode as D
 import Json.Encode as E
@@ -13,34 +16,43 @@ type alias Request q t =
     , urlComponents : List String
     , query: Maybe q
     , queryEncoder: QueryEncoder q
+    , queryParams : List Url.Builder.QueryParameter
     , body : Http.Body
-    , resolver : Http.Resolver Error t
+    , resolver : Http.Resolver ApiError t
     , timeout : Maybe Float
     , base : String
     }
 
 
-type ResponseBody
-    = StringResponse String
+{-| Why a request failed.
+-}
+type ApiError
+    = BadUrl String -- the URL of the request is invalid
+    | Timeout -- the server did not respond in time
+    | NetworkError -- the server could not be reached
+    | BadStatus Int -- the server responded with an error status, but not with an "Error Response" of the humble service protocol
+    | DecodeError String -- the response of the server could not be decoded
+    | ServiceError ServiceError -- the handler of the request rejected it
+    | RuntimeError Int String -- the server rejected the request before it reached the handler, with the status and name of the runtime error (e.g. `RouteParamInvalid`)
 
 
-type Error
-    = Bug String
-    | HttpBug Http.Metadata ResponseBody
-    | InvalidResponse Http.Metadata ResponseBody D.Error
-    | TransportError String
-    | AuthorizationError -- humble service protocol level authorization error (e.g. the server-side request handler indicates that the client is unauthorized to access the resource. The client's access token is valid, though.
-    | AuthenticationError -- humble service protocol level authentication error (e.g. the server-side request handler indicates that the client did not provide a valid access token)
-    | ServerError
+{-| A service-level error of the humble service protocol, returned by the handler of a request.
+-}
+type ServiceError
+    = Authentication -- the client did not provide valid credentials (status 401)
+    | Authorization -- the client is not allowed to access the resource (status 403)
+    | Internal String -- an error internal to the service, e.g. no database connection (status 500)
+    | Custom { status : Int, code : String, message : String } -- an error chosen by the handler, e.g. for rate limiting
 
 
-makeRequest : String -> List String -> QueryEncoder q -> Http.Resolver Error t -> Request q t
+makeRequest : String -> List String -> QueryEncoder q -> Http.Resolver ApiError t -> Request q t
 makeRequest method urlComponents queryEncoder resolver =
     { method = method
     , headers = []
     , base = ""
     , query = Nothing
     , queryEncoder = queryEncoder
+    , queryParams = []
     , urlComponents = urlComponents
     , body = Http.emptyBody
     , resolver = resolver
@@ -52,42 +64,202 @@ type alias NoQuery = Never
 noQueryEncoder : QueryEncoder Never
 noQueryEncoder _ = []
 
-jsonResolver : D.Decoder t -> Http.Resolver Error t
+jsonResolver : D.Decoder t -> Http.Resolver ApiError t
 jsonResolver =
     let
         resolve decoder response =
             case response of
                 Http.BadUrl_ badUrl ->
-                    Err <| Bug <| "bad url: " ++ badUrl
+                    Err <| BadUrl badUrl
 
                 Http.Timeout_ ->
-                    Err <| TransportError "Http.Timeout_"
+                    Err Timeout
 
                 Http.NetworkError_ ->
-                    Err <| TransportError "Http.NetworkError_"
+                    Err NetworkError
 
                 Http.BadStatus_ metadata body ->
-                    Err <|
-                        case metadata.statusCode of
-                            401 ->
-                                AuthorizationError
+                    Err <| badStatusError metadata body
+
+                Http.GoodStatus_ metadata body ->
+                    D.decodeString decoder body
+                        |> Result.mapError (DecodeError << D.errorToString)
+    in
+    Http.stringResolver << resolve
 
-                            403 ->
-                                AuthenticationError
 
-                            500 ->
-                                ServerError
+{-| Resolves the response of an endpoint returning `result[T][E]` and declared with
+`@error_status(...)`, whose `Err` is sent with the status `errorStatus`.
+-}
+resultResolver : Int -> D.Decoder t -> Http.Resolver ApiError t
+resultResolver errorStatus =
+    let
+        resolve decoder response =
+            case response of
+                Http.BadUrl_ badUrl ->
+                    Err <| BadUrl badUrl
+
+                Http.Timeout_ ->
+                    Err Timeout
+
+                Http.NetworkError_ ->
+                    Err NetworkError
+
+                Http.BadStatus_ metadata body ->
+                    if metadata.statusCode == errorStatus then
+                        -- runtime errors like an unknown route may use the same status
+                        D.decodeString decoder body
+                            |> Result.mapError (\_ -> badStatusError metadata body)
 
-                            _ ->
-                                HttpBug metadata (StringResponse body)
+                    else
+                        Err <| badStatusError metadata body
 
                 Http.GoodStatus_ metadata body ->
                     D.decodeString decoder body
-                        |> Result.mapError (InvalidResponse metadata (StringResponse body))
+                        |> Result.mapError (DecodeError << D.errorToString)
     in
     Http.stringResolver << resolve
 
 
+{-| Resolves the newline-delimited JSON response of an endpoint returning `stream[T]`
+into the list of its items.
+-}
+ndjsonResolver : D.Decoder t -> Http.Resolver ApiError (List t)
+ndjsonResolver =
+    let
+        decodeLines decoder metadata body =
+            String.lines body
+                |> List.filter (not << String.isEmpty << String.trim)
+                |> List.map (D.decodeString decoder)
+                |> List.foldr (Result.map2 (::)) (Ok [])
+                |> Result.mapError (DecodeError << D.errorToString)
+
+        resolve decoder response =
+            case response of
+                Http.BadUrl_ badUrl ->
+                    Err <| BadUrl badUrl
+
+                Http.Timeout_ ->
+                    Err Timeout
+
+                Http.NetworkError_ ->
+                    Err NetworkError
+
+                Http.BadStatus_ metadata body ->
+                    Err <| badStatusError metadata body
+
+                Http.GoodStatus_ metadata body ->
+                    decodeLines decoder metadata body
+    in
+    Http.stringResolver << resolve
+
+
+{-| The response of an endpoint returning `raw bytes`.
+-}
+type alias RawResponse =
+    { contentType : String
+    , body : Bytes.Bytes
+    }
+
+
+rawResolver : Http.Resolver ApiError RawResponse
+rawResolver =
+    let
+        resolve response =
+            case response of
+                Http.BadUrl_ badUrl ->
+                    Err <| BadUrl badUrl
+
+                Http.Timeout_ ->
+                    Err Timeout
+
+                Http.NetworkError_ ->
+                    Err NetworkError
+
+                Http.BadStatus_ metadata body ->
+                    Err <|
+                        badStatusError metadata <|
+                            Maybe.withDefault "" <|
+                                Bytes.Decode.decode (Bytes.Decode.string (Bytes.width body)) body
+
+                Http.GoodStatus_ metadata body ->
+                    Ok
+                        { contentType =
+                            Dict.get "content-type" metadata.headers
+                                |> Maybe.withDefault "application/octet-stream"
+                        , body = body
+                        }
+    in
+    Http.bytesResolver resolve
+
+
+badStatusError : Http.Metadata -> String -> ApiError
+badStatusError metadata body =
+    D.decodeString errorResponseDecoder body
+        |> Result.withDefault (BadStatus metadata.statusCode)
+
+
+{-| Decodes the "Error Response" of the humble service protocol.
+-}
+errorResponseDecoder : D.Decoder ApiError
+errorResponseDecoder =
+    D.field "code" D.int
+        |> D.andThen
+            (\code ->
+                D.field "kind" <|
+                    D.oneOf
+                        [ D.field "Service" (D.map ServiceError serviceErrorDecoder)
+                        , D.field "Runtime" (D.map (RuntimeError code) variantNameDecoder)
+                        ]
+            )
+
+
+serviceErrorDecoder : D.Decoder ServiceError
+serviceErrorDecoder =
+    D.oneOf
+        [ D.string
+            |> D.andThen
+                (\name ->
+                    case name of
+                        "Authentication" ->
+                            D.succeed Authentication
+
+                        "Authorization" ->
+                            D.succeed Authorization
+
+                        _ ->
+                            D.fail <| "unknown service error " ++ name
+                )
+        , D.field "Internal" (D.map Internal D.string)
+        , D.field "Custom"
+            (D.map3 (\status code message -> Custom { status = status, code = code, message = message })
+                (D.field "status" D.int)
+                (D.field "code" D.string)
+                (D.field "message" D.string)
+            )
+        ]
+
+
+{-| Decodes the name of the variant of an externally tagged enum, like `"NoServiceMounted"` or
+`{ "QueryInvalid": "..." }`.
+-}
+variantNameDecoder : D.Decoder String
+variantNameDecoder =
+    D.oneOf
+        [ D.string
+        , D.keyValuePairs D.value
+            |> D.andThen
+                (\pairs ->
+                    case pairs of
+                        [ ( name, _ ) ] ->
+                            D.succeed name
+
+                        _ ->
+                            D.fail "expected an object with a single field"
+                )
+        ]
+
+
 withBase : String -> Request q t -> Request q t
 withBase base req =
     { req | base = base }
@@ -113,6 +285,31 @@ withHeader name value req =
     { req | headers = Http.header name value :: req.headers }
 
 
+withOptionalHeader : String -> Maybe String -> Request q t -> Request q t
+withOptionalHeader name value req =
+    case value of
+        Just v ->
+            withHeader name v req
+
+        Nothing ->
+            req
+
+
+withQueryParam : String -> String -> Request q t -> Request q t
+withQueryParam name value req =
+    { req | queryParams = req.queryParams ++ [ Url.Builder.string name value ] }
+
+
+withOptionalQueryParam : String -> Maybe String -> Request q t -> Request q t
+withOptionalQueryParam name value req =
+    case value of
+        Just v ->
+            withQueryParam name v req
+
+        Nothing ->
+            req
+
+
 withJsonBody : (body -> E.Value) -> body -> Request q t -> Request q t
 withJsonBody encoder value req =
     { req | body = Http.stringBody "application/json" <| E.encode 2 (encoder value) }
@@ -123,10 +320,10 @@ makeUrl req =
     Url.Builder.crossOrigin
          req.base
             req.urlComponents
-            (Maybe.withDefault [] <| Maybe.map req.queryEncoder req.query)
+            (req.queryParams ++ (Maybe.withDefault [] <| Maybe.map req.queryEncoder req.query))
 
 
-toTask : Request q t -> Task Error t
+toTask : Request q t -> Task ApiError t
 toTask req =
     Http.task
         { method = req.method
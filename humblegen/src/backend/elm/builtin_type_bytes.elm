@@ -1,6 +1,8 @@
 import Json.Decode as D
 import Json.Encode as E
 import Bytes as PkgBytes
+import Bytes.Decode as PkgBytesDecode
+import Bytes.Encode as PkgBytesEncode
 import Base64 as PkgBase64
 
 type Bytes = Bytes PkgBytes.Bytes
@@ -23,3 +25,59 @@ encodeQuery (Bytes bytes) = PkgBase64.fromBytes bytes |> Maybe.withDefault "" --
 
 encodeUrlcomponent : Bytes -> String
 encodeUrlcomponent (Bytes bytes) = PkgBase64.fromBytes bytes |> Maybe.withDefault "" -- base64 _en_coding never fails
+
+-- Hexadecimal encoding, used for fields annotated with `@encoding("hex")`.
+
+hexDigits : String
+hexDigits = "0123456789abcdef"
+
+toHex : Bytes -> String
+toHex (Bytes bytes) =
+    let
+        hexDigit d = String.slice d (d + 1) hexDigits
+
+        step ( remaining, digits ) =
+            if remaining <= 0 then
+                PkgBytesDecode.succeed (PkgBytesDecode.Done (String.concat (List.reverse digits)))
+            else
+                PkgBytesDecode.unsignedInt8
+                    |> PkgBytesDecode.map (\byte -> PkgBytesDecode.Loop ( remaining - 1, (hexDigit (byte // 16) ++ hexDigit (modBy 16 byte)) :: digits ))
+    in
+    PkgBytesDecode.decode (PkgBytesDecode.loop ( PkgBytes.width bytes, [] ) step) bytes
+        |> Maybe.withDefault "" -- reading `width` bytes never fails
+
+fromHex : String -> Maybe Bytes
+fromHex str =
+    let
+        digitValue c = List.head (String.indexes (String.fromChar (Char.toLower c)) hexDigits)
+
+        pairs chars encoders = case chars of
+            high :: low :: rest ->
+                case ( digitValue high, digitValue low ) of
+                    ( Just h, Just l ) ->
+                        pairs rest (PkgBytesEncode.unsignedInt8 (h * 16 + l) :: encoders)
+                    _ ->
+                        Nothing
+            [] ->
+                Just (List.reverse encoders)
+            _ ->
+                Nothing
+    in
+    pairs (String.toList str) []
+        |> Maybe.map (PkgBytesEncode.sequence >> PkgBytesEncode.encode >> Bytes)
+
+encodeHex : Bytes -> E.Value
+encodeHex bytes = E.string (toHex bytes)
+
+hexDecodeHelper : String -> D.Decoder Bytes
+hexDecodeHelper hexStr = case fromHex hexStr of
+    Just bytes ->
+        D.succeed bytes
+    Nothing ->
+        D.fail "invalid hex"
+
+decodeHex : D.Decoder Bytes
+decodeHex = D.andThen hexDecodeHelper D.string
+
+encodeQueryHex : Bytes -> String
+encodeQueryHex = toHex
@@ -0,0 +1,42 @@
+import Json.Decode as D
+import Json.Encode as E
+
+-- Decimals are transmitted as strings (e.g. "-1234.56") to avoid floating-point rounding.
+type Decimal = Decimal String
+
+
+encode : Decimal -> E.Value
+encode (Decimal str) = E.string str
+
+decode : D.Decoder Decimal
+decode = D.andThen decodeHelper D.string
+
+decodeHelper : String -> D.Decoder Decimal
+decodeHelper str = case fromString str of
+    Just decimal ->
+        D.succeed decimal
+    Nothing ->
+        D.fail <| "invalid decimal: " ++ str
+
+encodeQuery : Decimal -> String
+encodeQuery (Decimal str) = str
+
+encodeUrlcomponent : Decimal -> String
+encodeUrlcomponent (Decimal str) = str
+
+toString : Decimal -> String
+toString (Decimal str) = str
+
+fromString : String -> Maybe Decimal
+fromString str =
+    let
+        isDigits s = not (String.isEmpty s) && String.all Char.isDigit s
+        unsigned = if String.startsWith "-" str then String.dropLeft 1 str else str
+    in
+    case String.split "." unsigned of
+        [ integral ] ->
+            if isDigits integral then Just (Decimal str) else Nothing
+        [ integral, fractional ] ->
+            if isDigits integral && isDigits fractional then Just (Decimal str) else Nothing
+        _ ->
+            Nothing
@@ -6,15 +6,34 @@ use itertools::Itertools; // directly call join(.) on iterators
 
 /// Generate elm code for decoders for a spec.
 pub fn generate_type_decoders(spec: &ast::Spec) -> String {
+    let page_decoder = if spec.has_paginated_endpoints() {
+        Some(generate_page_decoder())
+    } else {
+        None
+    };
     spec.iter()
         .filter_map(|spec_item| match spec_item {
             ast::SpecItem::StructDef(sdef) => Some(generate_struct_decoder(sdef)),
             ast::SpecItem::EnumDef(edef) => Some(generate_enum_decoder(edef)),
             ast::SpecItem::ServiceDef(_) => None,
         })
+        .chain(page_decoder)
         .join("\n\n\n")
 }
 
+/// Generate the decoder of the envelope returned by `@paginated` endpoints, which takes the
+/// decoder of the items.
+fn generate_page_decoder() -> String {
+    "decodePage : D.Decoder a -> D.Decoder (Page a)\n\
+     decodePage decodeItem =\n   \
+     D.succeed Page\n        \
+     |> required \"items\" (D.list decodeItem)\n        \
+     |> required \"total\" builtinDecodeInt64\n        \
+     |> required \"limit\" D.int\n        \
+     |> required \"offset\" D.int"
+        .to_owned()
+}
+
 fn generate_struct_decoder(sdef: &ast::StructDef) -> String {
     let ns = "";
     format!(
@@ -25,32 +44,44 @@ fn generate_struct_decoder(sdef: &ast::StructDef) -> String {
         field_decoders = sdef
             .fields
             .iter()
-            .map(|f| generate_field_decoder(f, ns))
+            .map(|f| generate_field_decoder(f, sdef.rename_all, ns))
             .join("\n        ")
     )
 }
 
 fn generate_enum_decoder(edef: &ast::EnumDef) -> String {
+    match edef.tagging() {
+        ast::EnumTagging::External => generate_externally_tagged_enum_decoder(edef),
+        ast::EnumTagging::Internal { tag } => generate_tagged_enum_decoder(edef, tag, None),
+        ast::EnumTagging::Adjacent { tag, content } => {
+            generate_tagged_enum_decoder(edef, tag, Some(content))
+        }
+    }
+}
+
+fn generate_externally_tagged_enum_decoder(edef: &ast::EnumDef) -> String {
     let ns = "";
 
     let mut fields = edef.variants.iter().map(|variant| {
         match variant.variant_type {
             ast::VariantType::Simple => {
                 format!(
-                    "D.string |> D.andThen (\\s -> if s == \"{name}\" then D.succeed {name} else D.fail \"\")",
+                    "D.string |> D.andThen (\\s -> if s == \"{value}\" then D.succeed {name} else D.fail \"\")",
+                    value = variant.wire_name(),
                     name = variant.name
                 )
             }
             ast::VariantType::Tuple(ref components) => format!(
-                "D.succeed {name} {components}",
+                "D.field \"{variantName}\" (D.succeed {name} {components})",
                 name = variant.name,
+                variantName = variant.name,
                 components = generate_components_by_index_pipeline(components, ns)
             ),
             ast::VariantType::Struct(ref fields) => format!(
                 "D.field \"{variantName}\" (D.succeed {name} {field_decoders} |> D.map {variantName})",
                 name = type_generation::enum_anonymous_struct_constructor_name(&edef.name, &variant.name),
                 variantName = variant.name,
-                field_decoders = fields.iter().map(|f| generate_field_decoder(f, ns)).join(" "),
+                field_decoders = fields.iter().map(|f| generate_field_decoder(f, None, ns)).join(" "),
             ),
             ast::VariantType::Newtype(ref ty) => format!(
                 "D.field \"{variantName}\" (D.map {name} {ty})",
@@ -69,21 +100,152 @@ fn generate_enum_decoder(edef: &ast::EnumDef) -> String {
     )
 }
 
-fn generate_field_decoder(field: &ast::FieldNode, ns: &str) -> String {
+/// Generate a decoder for an internally (`content` is `None`) or adjacently tagged enum.
+///
+/// The tag is decoded first to select the decoder of the variant it names.
+fn generate_tagged_enum_decoder(edef: &ast::EnumDef, tag: &str, content: Option<&str>) -> String {
+    let ns = "";
+
+    let mut branches = edef.variants.iter().map(|variant| {
+        let decoder = match variant.variant_type {
+            ast::VariantType::Simple => format!("D.succeed {}", variant.name),
+            ast::VariantType::Tuple(ref components) => format!(
+                "D.succeed {name} {components}",
+                name = variant.name,
+                components = generate_components_by_index_pipeline(components, ns)
+            ),
+            ast::VariantType::Struct(ref fields) => format!(
+                "D.succeed {name} {field_decoders} |> D.map {variantName}",
+                name = type_generation::enum_anonymous_struct_constructor_name(
+                    &edef.name,
+                    &variant.name
+                ),
+                variantName = variant.name,
+                field_decoders = fields
+                    .iter()
+                    .map(|f| generate_field_decoder(f, None, ns))
+                    .join(" "),
+            ),
+            ast::VariantType::Newtype(ref ty) => format!(
+                "D.map {name} {ty}",
+                name = variant.name,
+                ty = to_atom(generate_type_decoder(ty, ns)),
+            ),
+        };
+        // internally tagged enums only have simple and struct variants, whose fields are
+        // siblings of the tag
+        let decoder = match (content, &variant.variant_type) {
+            (Some(content), ast::VariantType::Tuple(_))
+            | (Some(content), ast::VariantType::Struct(_))
+            | (Some(content), ast::VariantType::Newtype(_)) => {
+                format!("D.field \"{}\" ({})", content, decoder)
+            }
+            _ => decoder,
+        };
+        format!(
+            "                    \"{name}\" ->\n                        {decoder}\n\n",
+            name = variant.wire_name(),
+            decoder = decoder
+        )
+    });
+
     format!(
-        "|> required \"{name}\" {decoder}",
-        name = field.pair.name,
-        decoder = to_atom(generate_type_decoder(&field.pair.type_ident, ns)),
+        "{dec_name} : D.Decoder {name}\n{dec_name} =\n    D.field \"{tag}\" D.string\n        |> D.andThen\n            (\\variant ->\n                case variant of\n{branches}                    _ ->\n                        D.fail (\"unknown variant \" ++ variant)\n            )",
+        dec_name = decoder_name(&edef.name, ns),
+        name = edef.name,
+        tag = tag,
+        branches = branches.join(""),
     )
 }
 
+fn generate_field_decoder(
+    field: &ast::FieldNode,
+    rename_all: Option<ast::RenameRule>,
+    ns: &str,
+) -> String {
+    let decoder = match (field.bytes_encoding, field.pair.range) {
+        (ast::BytesEncoding::Hex, _) => "BuiltinBytes.decodeHex".to_owned(),
+        (_, Some(range)) => to_atom(generate_range_decoder(&field.pair.type_ident, range, ns)),
+        _ => to_atom(generate_type_decoder(&field.pair.type_ident, ns)),
+    };
+    match field.default {
+        Some(ref default) => format!(
+            "|> optional \"{name}\" {decoder} {default}",
+            name = field.wire_name(rename_all),
+            decoder = decoder,
+            default = generate_default_value(default),
+        ),
+        // the key of `option[T]` fields may be absent, unlike the one of `nullable[T]` fields
+        None if matches!(field.pair.type_ident, ast::TypeIdent::Option(_)) => format!(
+            "|> optional \"{name}\" {decoder} Nothing",
+            name = field.wire_name(rename_all),
+            decoder = decoder,
+        ),
+        None => format!(
+            "|> required \"{name}\" {decoder}",
+            name = field.wire_name(rename_all),
+            decoder = decoder,
+        ),
+    }
+}
+
+/// Generate the decoder of an integer field of type `type_ident`, which may be optional, that
+/// fails for values outside of `range`.
+fn generate_range_decoder(
+    type_ident: &ast::TypeIdent,
+    range: ast::RangeConstraint,
+    ns: &str,
+) -> String {
+    let bound = |bound: Option<i64>| match bound {
+        Some(b) if b < 0 => format!("(Just ({}))", b),
+        Some(b) => format!("(Just {})", b),
+        None => "Nothing".to_owned(),
+    };
+    match type_ident {
+        ast::TypeIdent::Option(inner) | ast::TypeIdent::Nullable(inner) => format!(
+            "{}builtinDecodeOption {}",
+            ns,
+            to_atom(generate_range_decoder(inner, range, ns))
+        ),
+        _ => format!(
+            "{}builtinDecodeInRange {} {} {}",
+            ns,
+            bound(range.min),
+            bound(range.max),
+            to_atom(generate_type_decoder(type_ident, ns))
+        ),
+    }
+}
+
+/// Generate the elm literal of a default value.
+fn generate_default_value(default: &ast::DefaultValue) -> String {
+    let literal = match default {
+        ast::DefaultValue::Bool(true) => "True".to_owned(),
+        ast::DefaultValue::Bool(false) => "False".to_owned(),
+        ast::DefaultValue::Int(i) => i.to_string(),
+        // debug formatting keeps the decimal point of whole numbers
+        ast::DefaultValue::Float(f) => format!("{:?}", f),
+        ast::DefaultValue::Str(s) => format!("\"{}\"", s),
+    };
+    if literal.starts_with('-') {
+        format!("({})", literal)
+    } else {
+        literal
+    }
+}
+
 pub(crate) fn generate_type_decoder(type_ident: &ast::TypeIdent, ns: &str) -> String {
     match type_ident {
         ast::TypeIdent::BuiltIn(atom) => generate_atom_decoder(atom, ns),
         ast::TypeIdent::List(inner) => {
             format!("D.list {}", to_atom(generate_type_decoder(inner, ns)))
         }
-        ast::TypeIdent::Option(inner) => format!(
+        ast::TypeIdent::Set(inner) => format!(
+            "{}builtinDecodeSet {}",
+            ns,
+            to_atom(generate_type_decoder(inner, ns))
+        ),
+        ast::TypeIdent::Option(inner) | ast::TypeIdent::Nullable(inner) => format!(
             "{}builtinDecodeOption {}",
             ns,
             to_atom(generate_type_decoder(inner, ns))
@@ -94,13 +256,10 @@ pub(crate) fn generate_type_decoder(type_ident: &ast::TypeIdent, ns: &str) -> St
             to_atom(generate_type_decoder(err, ns)),
             to_atom(generate_type_decoder(ok, ns))
         ),
-        ast::TypeIdent::Map(key, value) => {
+        ast::TypeIdent::Map(key, value) | ast::TypeIdent::OrderedMap(key, value) => {
             // TODO: elm supports more than D.string, every comparable type
-            assert_eq!(
-                generate_type_decoder(key, ns),
-                "D.string",
-                "elm only supports dict keys"
-            );
+            // keys are checked to be strings by `map_keys::check_map_keys`
+            debug_assert_eq!(generate_type_decoder(key, ns), "D.string");
             format!("D.dict {}", to_atom(generate_type_decoder(value, ns)))
         }
         ast::TypeIdent::Tuple(tdef) => generate_tuple_decoder(tdef, ns),
@@ -139,12 +298,26 @@ fn generate_atom_decoder(atom: &ast::AtomType, ns: &str) -> String {
         ast::AtomType::I32 => "D.int".to_string(),
         ast::AtomType::U32 => "D.int".to_string(),
         ast::AtomType::U8 => "D.int".to_string(),
+        // JavaScript numbers cannot represent all 64-bit integers, reject those that would be truncated
+        ast::AtomType::I64 | ast::AtomType::U64 => format!("{}builtinDecodeInt64", ns),
+        // 128-bit integers are kept as the decimal strings they are encoded as
+        ast::AtomType::U128 => format!("({}builtinDecodeIntString False)", ns),
+        ast::AtomType::I128 => format!("({}builtinDecodeIntString True)", ns),
+        ast::AtomType::I16 => "D.int".to_string(),
+        ast::AtomType::U16 => "D.int".to_string(),
+        ast::AtomType::I8 => "D.int".to_string(),
+        ast::AtomType::F32 => "D.float".to_string(),
         ast::AtomType::F64 => "D.float".to_string(),
         ast::AtomType::Bool => "D.bool".to_string(),
         ast::AtomType::DateTime => format!("{}builtinDecodeIso8601", ns),
         ast::AtomType::Date => format!("{}builtinDecodeDate", ns),
         ast::AtomType::Uuid => "BuiltinUuid.decode".to_string(),
+        ast::AtomType::Url => format!("{}builtinDecodeUrl", ns),
+        ast::AtomType::IpAddr => format!("{}builtinDecodeIp", ns),
+        ast::AtomType::Duration => format!("{}builtinDecodeDuration", ns),
         ast::AtomType::Bytes => "BuiltinBytes.decode".to_string(),
+        ast::AtomType::Decimal => "BuiltinDecimal.decode".to_string(),
+        ast::AtomType::Json => "D.value".to_string(),
     }
 }
 
@@ -25,7 +25,7 @@ fn generate_struct_json_encoder(sdef: &ast::StructDef) -> String {
         "{encoder_name} : {type_name} -> E.Value\n{encoder_name} obj =\n    E.object\n        [ {fields}\n        ]",
         encoder_name = struct_or_enum_encoder_name(&sdef.name, ns),
         type_name = sdef.name,
-        fields = sdef.fields.iter().map(|f| generate_field_json_encoder(f, ns)).join("\n        , "),
+        fields = sdef.fields.iter().map(|f| generate_field_json_encoder(f, sdef.rename_all, ns)).join("\n        , "),
     )
 }
 
@@ -35,7 +35,7 @@ fn generate_struct_query_encoder(sdef: &ast::StructDef) -> String {
         "{encoder_name} : {type_name} -> List Url.Builder.QueryParameter\n{encoder_name} obj =\n    [ {fields}\n    ]",
         encoder_name = query_struct_encoder_name(&sdef.name, ns),
         type_name = sdef.name,
-        fields = sdef.fields.iter().map(|f| generate_field_query_encoder(f, ns)).join("\n    , "),
+        fields = sdef.fields.iter().map(|f| generate_field_query_encoder(f, sdef.rename_all, ns)).join("\n    , "),
     )
 }
 
@@ -49,50 +49,81 @@ fn generate_enum_encoder(edef: &ast::EnumDef) -> String {
         variants = edef
             .variants
             .iter()
-            .map(|v| generate_variant_encoder_branch(v, ns))
+            .map(|v| generate_variant_encoder_branch(v, edef.tagging(), ns))
             .join("\n        "),
     )
 }
 
-fn generate_field_json_encoder(field: &ast::FieldNode, ns: &str) -> String {
+fn generate_field_json_encoder(
+    field: &ast::FieldNode,
+    rename_all: Option<ast::RenameRule>,
+    ns: &str,
+) -> String {
     format!(
         "(\"{name}\", {value_encoder} obj.{field_name})",
-        name = field.pair.name,
+        name = field.wire_name(rename_all),
         field_name = field_name(&field.pair.name),
-        value_encoder = generate_type_json_encoder(&field.pair.type_ident, ns)
+        value_encoder = match field.bytes_encoding {
+            ast::BytesEncoding::Base64 => generate_type_json_encoder(&field.pair.type_ident, ns),
+            ast::BytesEncoding::Hex => "BuiltinBytes.encodeHex".to_owned(),
+        }
     )
 }
 
-fn generate_field_query_encoder(field: &ast::FieldNode, ns: &str) -> String {
+fn generate_field_query_encoder(
+    field: &ast::FieldNode,
+    rename_all: Option<ast::RenameRule>,
+    ns: &str,
+) -> String {
+    let name = field.wire_name(rename_all);
     // TODO: escape strings (but we could fix this in the whole codebase)
     match field.pair.type_ident {
-        ast::TypeIdent::BuiltIn(ast::AtomType::Str) => format!(
+        ast::TypeIdent::BuiltIn(ast::AtomType::Str)
+        | ast::TypeIdent::BuiltIn(ast::AtomType::Url)
+        | ast::TypeIdent::BuiltIn(ast::AtomType::IpAddr)
+        | ast::TypeIdent::BuiltIn(ast::AtomType::U128)
+        | ast::TypeIdent::BuiltIn(ast::AtomType::I128) => format!(
             "Url.Builder.string \"{name}\" obj.{field_name}",
-            name = field.pair.name,
+            name = name,
             field_name = field_name(&field.pair.name)
         ),
         ast::TypeIdent::BuiltIn(ast::AtomType::Uuid) => format!(
             "Url.Builder.string \"{name}\" (BuiltinUuid.encodeQuery obj.{field_name})",
-            name = field.pair.name,
+            name = name,
             field_name = field_name(&field.pair.name)
         ),
         ast::TypeIdent::BuiltIn(ast::AtomType::Bytes) => format!(
-            "Url.Builder.string \"{name}\" (BuiltinBytes.encodeQuery obj.{field_name})",
-            name = field.pair.name,
+            "Url.Builder.string \"{name}\" (BuiltinBytes.{encoder} obj.{field_name})",
+            name = name,
+            encoder = match field.bytes_encoding {
+                ast::BytesEncoding::Base64 => "encodeQuery",
+                ast::BytesEncoding::Hex => "encodeQueryHex",
+            },
+            field_name = field_name(&field.pair.name)
+        ),
+        ast::TypeIdent::BuiltIn(ast::AtomType::Decimal) => format!(
+            "Url.Builder.string \"{name}\" (BuiltinDecimal.encodeQuery obj.{field_name})",
+            name = name,
             field_name = field_name(&field.pair.name)
         ),
         ast::TypeIdent::BuiltIn(ast::AtomType::I32)
         | ast::TypeIdent::BuiltIn(ast::AtomType::U32)
-        | ast::TypeIdent::BuiltIn(ast::AtomType::U8) => format!(
+        | ast::TypeIdent::BuiltIn(ast::AtomType::U8)
+        | ast::TypeIdent::BuiltIn(ast::AtomType::I64)
+        | ast::TypeIdent::BuiltIn(ast::AtomType::U64)
+        | ast::TypeIdent::BuiltIn(ast::AtomType::Duration)
+        | ast::TypeIdent::BuiltIn(ast::AtomType::I16)
+        | ast::TypeIdent::BuiltIn(ast::AtomType::U16)
+        | ast::TypeIdent::BuiltIn(ast::AtomType::I8) => format!(
             "Url.Builder.int \"{name}\" obj.{field_name}",
-            name = field.pair.name,
+            name = name,
             field_name = field_name(&field.pair.name),
         ),
         _ => {
             // encode other types as json encoded strings
             format!(
                 "obj.{field_name} |> {value_encoder} |> E.encode 4 |> Url.Builder.string \"{name}\"",
-                name = field.pair.name,
+                name = name,
                 field_name = field_name(&field.pair.name),
                 value_encoder = generate_complex_type_query_encoder(&field.pair.type_ident, ns)
             )
@@ -100,40 +131,82 @@ fn generate_field_query_encoder(field: &ast::FieldNode, ns: &str) -> String {
     }
 }
 
-fn generate_variant_encoder_branch(variant: &ast::VariantDef, ns: &str) -> String {
-    match variant.variant_type {
-        ast::VariantType::Simple => format!("{name} -> E.string \"{name}\"", name = variant.name),
-        ast::VariantType::Tuple(ref tdef) => format!(
-            "{name} {field_names} -> E.object [ (\"{name}\", E.list identity [{field_encoders}]) ]",
-            name = variant.name,
-            field_names = (0..tdef.elements().len())
-                .map(|i| format!("x{}", i))
-                .join(" "),
-            field_encoders = tdef
-                .elements()
-                .iter()
-                .enumerate()
-                .map(|(idx, component)| format!(
-                    "{} x{}",
-                    generate_type_json_encoder(component, ns),
-                    idx
-                ))
-                .join(", "),
+fn generate_variant_encoder_branch(
+    variant: &ast::VariantDef,
+    tagging: &ast::EnumTagging,
+    ns: &str,
+) -> String {
+    let name = &variant.name;
+    let (pattern, content) = match variant.variant_type {
+        ast::VariantType::Simple => (name.clone(), None),
+        ast::VariantType::Tuple(ref tdef) => (
+            format!(
+                "{name} {field_names}",
+                name = name,
+                field_names = (0..tdef.elements().len())
+                    .map(|i| format!("x{}", i))
+                    .join(" "),
+            ),
+            Some(format!(
+                "E.list identity [{field_encoders}]",
+                field_encoders = tdef
+                    .elements()
+                    .iter()
+                    .enumerate()
+                    .map(|(idx, component)| format!(
+                        "{} x{}",
+                        generate_type_json_encoder(component, ns),
+                        idx
+                    ))
+                    .join(", "),
+            )),
         ),
-        ast::VariantType::Struct(ref fields) => format!(
-            "{name} obj -> E.object [ (\"{name}\", E.object [{fields}]) ]",
-            name = variant.name,
-            fields = fields
-                .iter()
-                .map(|f| generate_field_json_encoder(f, ns))
-                .join(", "),
+        ast::VariantType::Struct(ref fields) => (
+            format!("{} obj", name),
+            Some(format!(
+                "E.object [{fields}]",
+                fields = fields
+                    .iter()
+                    .map(|f| generate_field_json_encoder(f, None, ns))
+                    .join(", "),
+            )),
         ),
-        ast::VariantType::Newtype(ref ty) => format!(
-            "{name} obj -> E.object [ (\"{name}\", {enc} obj) ]",
-            name = variant.name,
-            enc = generate_type_json_encoder(ty, ns),
+        ast::VariantType::Newtype(ref ty) => (
+            format!("{} obj", name),
+            Some(format!("{} obj", generate_type_json_encoder(ty, ns))),
         ),
-    }
+    };
+
+    let wire_name = variant.wire_name();
+    let value = match (tagging, content) {
+        (ast::EnumTagging::External, None) => format!("E.string \"{}\"", wire_name),
+        (ast::EnumTagging::External, Some(content)) => {
+            format!("E.object [ (\"{}\", {}) ]", name, content)
+        }
+        (ast::EnumTagging::Internal { tag }, _) => {
+            // the fields of struct variants are siblings of the tag
+            let fields = match variant.variant_type {
+                ast::VariantType::Struct(ref fields) => fields
+                    .iter()
+                    .map(|f| format!(", {}", generate_field_json_encoder(f, None, ns)))
+                    .join(""),
+                _ => String::new(),
+            };
+            format!(
+                "E.object [ (\"{}\", E.string \"{}\"){} ]",
+                tag, wire_name, fields
+            )
+        }
+        (ast::EnumTagging::Adjacent { tag, content: key }, content) => format!(
+            "E.object [ (\"{}\", E.string \"{}\"){} ]",
+            tag,
+            wire_name,
+            content
+                .map(|content| format!(", (\"{}\", {})", key, content))
+                .unwrap_or_default(),
+        ),
+    };
+    format!("{} -> {}", pattern, value)
 }
 
 /// Generate elm code for a type encoder.
@@ -147,7 +220,11 @@ fn generate_type_encoder(
         ast::TypeIdent::List(inner) => {
             format!("E.list {}", to_atom(generate_type_json_encoder(inner, ns)))
         }
-        ast::TypeIdent::Option(inner) => format!(
+        ast::TypeIdent::Set(inner) => format!(
+            "builtinEncodeSet {}",
+            to_atom(generate_type_json_encoder(inner, ns))
+        ),
+        ast::TypeIdent::Option(inner) | ast::TypeIdent::Nullable(inner) => format!(
             "builtinEncodeMaybe {}",
             to_atom(generate_type_json_encoder(inner, ns))
         ),
@@ -156,12 +233,9 @@ fn generate_type_encoder(
             to_atom(generate_type_json_encoder(err, ns)),
             to_atom(generate_type_json_encoder(ok, ns))
         ),
-        ast::TypeIdent::Map(key, value) => {
-            assert_eq!(
-                generate_type_json_encoder(key, ns),
-                "E.string",
-                "can only encode string keys in maps"
-            );
+        ast::TypeIdent::Map(key, value) | ast::TypeIdent::OrderedMap(key, value) => {
+            // keys are checked to be strings by `map_keys::check_map_keys`
+            debug_assert_eq!(generate_type_json_encoder(key, ns), "E.string");
             format!(
                 "E.dict identity {}",
                 to_atom(generate_type_json_encoder(value, ns))
@@ -186,28 +260,53 @@ pub(crate) fn generate_type_urlcomponent_encoder(type_ident: &ast::TypeIdent, ns
 
 fn generate_atom_json_encoder(atom: &ast::AtomType, ns: &str) -> String {
     match atom {
-        ast::AtomType::Empty => "(_ -> E.null)".to_owned(),
+        ast::AtomType::Empty => "(\\_ -> E.null)".to_owned(),
         ast::AtomType::Str => "E.string".to_owned(),
         ast::AtomType::I32 => "E.int".to_owned(),
         ast::AtomType::U32 => "E.int".to_owned(),
         ast::AtomType::U8 => "E.int".to_owned(),
+        ast::AtomType::I64 => "E.int".to_owned(),
+        ast::AtomType::U64 => "E.int".to_owned(),
+        ast::AtomType::Duration => "E.int".to_owned(),
+        ast::AtomType::U128 | ast::AtomType::I128 => "E.string".to_owned(),
+        ast::AtomType::I16 => "E.int".to_owned(),
+        ast::AtomType::U16 => "E.int".to_owned(),
+        ast::AtomType::I8 => "E.int".to_owned(),
+        ast::AtomType::F32 => "E.float".to_owned(),
         ast::AtomType::F64 => "E.float".to_owned(),
         ast::AtomType::Bool => "E.bool".to_owned(),
         ast::AtomType::DateTime => format!("{}builtinEncodeIso8601", ns),
         ast::AtomType::Date => format!("{}builtinEncodeDate", ns),
         ast::AtomType::Uuid => "BuiltinUuid.encode".to_owned(),
+        ast::AtomType::Url | ast::AtomType::IpAddr => "E.string".to_owned(),
         ast::AtomType::Bytes => "BuiltinBytes.encode".to_owned(),
+        ast::AtomType::Decimal => "BuiltinDecimal.encode".to_owned(),
+        ast::AtomType::Json => "identity".to_owned(),
     }
 }
 
 fn generate_atom_query_encoder(atom: &ast::AtomType, ns: &str) -> String {
     match atom {
-        ast::AtomType::Empty => "E.null".to_owned(),
-        ast::AtomType::Str => "Url.Builder.string".to_owned(),
+        ast::AtomType::Empty => "(\\_ -> E.null)".to_owned(),
+        ast::AtomType::Str
+        | ast::AtomType::Url
+        | ast::AtomType::IpAddr
+        | ast::AtomType::U128
+        | ast::AtomType::I128 => "Url.Builder.string".to_owned(),
         ast::AtomType::Uuid => "Url.Builder.uuid".to_owned(),
         ast::AtomType::Bytes => "Url.Builder.bytes".to_owned(),
-        ast::AtomType::I32 | ast::AtomType::U32 | ast::AtomType::U8 => "Url.Builder.int".to_owned(),
-        ast::AtomType::F64 => "E.float".to_owned(),
+        ast::AtomType::Decimal => "BuiltinDecimal.encode".to_owned(),
+        ast::AtomType::Json => "identity".to_owned(),
+        ast::AtomType::I32
+        | ast::AtomType::U32
+        | ast::AtomType::U8
+        | ast::AtomType::I64
+        | ast::AtomType::U64
+        | ast::AtomType::Duration
+        | ast::AtomType::I16
+        | ast::AtomType::U16
+        | ast::AtomType::I8 => "Url.Builder.int".to_owned(),
+        ast::AtomType::F32 | ast::AtomType::F64 => "E.float".to_owned(),
         ast::AtomType::Bool => "E.bool".to_owned(),
         ast::AtomType::DateTime => format!("{}builtinEncodeIso8601", ns),
         ast::AtomType::Date => format!("{}builtinEncodeDate", ns),
@@ -217,20 +316,36 @@ fn generate_atom_query_encoder(atom: &ast::AtomType, ns: &str) -> String {
 fn generate_atom_urlcomponent_encoder(atom: &ast::AtomType, ns: &str) -> String {
     match atom {
         ast::AtomType::Empty => unimplemented!(),
-        ast::AtomType::Str => "identity".to_owned(),
-        ast::AtomType::I32 | ast::AtomType::U32 | ast::AtomType::U8 => "String.fromInt".to_owned(),
-        ast::AtomType::F64 => "String.fromFloat".to_owned(),
+        ast::AtomType::Str
+        | ast::AtomType::Url
+        | ast::AtomType::IpAddr
+        | ast::AtomType::U128
+        | ast::AtomType::I128 => "identity".to_owned(),
+        ast::AtomType::I32
+        | ast::AtomType::U32
+        | ast::AtomType::U8
+        | ast::AtomType::I64
+        | ast::AtomType::U64
+        | ast::AtomType::Duration
+        | ast::AtomType::I16
+        | ast::AtomType::U16
+        | ast::AtomType::I8 => "String.fromInt".to_owned(),
+        ast::AtomType::F32 | ast::AtomType::F64 => "String.fromFloat".to_owned(),
         ast::AtomType::Bool => "String.fromBool".to_owned(),
         ast::AtomType::DateTime => format!("{}builtinEncodeIso8601", ns),
         ast::AtomType::Date => format!("{}builtinEncodeDate", ns),
         ast::AtomType::Uuid => "BuiltinUuid.encodeUrlcomponent".to_owned(),
         ast::AtomType::Bytes => "BuiltinBytes.encodeUrlcomponent".to_owned(),
+        ast::AtomType::Decimal => "BuiltinDecimal.encodeUrlcomponent".to_owned(),
+        ast::AtomType::Json => "(E.encode 0)".to_owned(),
     }
 }
 
+/// Generate an encoder for a tuple, a lambda in parentheses so that it can be applied like the
+/// encoders of other types.
 fn generate_tuple_encoder(tdef: &ast::TupleDef, ns: &str) -> String {
     format!(
-        "\\({field_names}) -> E.list identity [ {encode_values} ]",
+        "(\\({field_names}) -> E.list identity [ {encode_values} ])",
         field_names = (0..tdef.elements().len())
             .map(|i| format!("x{}", i))
             .join(", "),
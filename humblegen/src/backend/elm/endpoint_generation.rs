@@ -2,8 +2,8 @@
 #![allow(clippy::write_literal)]
 
 use super::{
-    decoder_generation, encoder_generation, generate_doc_comment, to_atom, type_generation,
-    IndentWriter,
+    decoder_generation, encoder_generation, generate_comment, generate_doc_comment, to_atom,
+    type_generation, with_deprecation, IndentWriter,
 };
 use crate::{ast, LibError};
 use inflector::Inflector;
@@ -15,12 +15,14 @@ pub(crate) fn generate(service: &ast::ServiceDef, file: &mut IndentWriter) -> Re
     write!(
         file.start_line()?,
         "{}",
-        generate_doc_comment(&service.doc_comment)
+        // not attached to a declaration, thus not a doc comment
+        generate_comment(&service.doc_comment)
     )?;
 
     file.empty_lines(2)?;
 
-    for endpoint in &service.endpoints {
+    let endpoint_names = endpoint_names(service)?;
+    for (endpoint, endpoint_name) in http_endpoints(service).zip(endpoint_names) {
         // Note: we currently generate a single flat function for each endpoint. This is what
         // OpenApi does. A worthfile, alternative api would generate an enum of endpoints
         // enum Endpoints = GetPet | PostMonster | etc first that is consumed by a generic
@@ -28,19 +30,21 @@ pub(crate) fn generate(service: &ast::ServiceDef, file: &mut IndentWriter) -> Re
         write!(
             file.start_line()?,
             "{}",
-            generate_doc_comment(&endpoint.doc_comment)
+            generate_doc_comment(&with_deprecation(
+                &endpoint.doc_comment,
+                &endpoint.deprecated
+            ))
         )?;
 
         {
             let mut line_type_signature = Vec::new();
             let mut line_arguments = Vec::new();
 
-            let endpoint_name = synthesize_endpoint_name(&endpoint.route);
             write!(line_type_signature, "{} : ", endpoint_name)?;
             write!(line_arguments, "{}", endpoint_name)?;
 
             for (idx, component) in endpoint.route.components().iter().enumerate() {
-                if let ast::ServiceRouteComponent::Variable(arg) = component {
+                if let ast::ServiceRouteComponent::Variable(arg, _) = component {
                     write!(
                         line_type_signature,
                         "{} -> ",
@@ -64,6 +68,30 @@ pub(crate) fn generate(service: &ast::ServiceDef, file: &mut IndentWriter) -> Re
                 write!(line_arguments, " body")?;
             }
 
+            for field in endpoint.route.query_fields() {
+                write!(
+                    line_type_signature,
+                    "{} -> ",
+                    to_atom(type_generation::generate_type_ident(
+                        &field.type_ident,
+                        "Ty."
+                    ))
+                )?;
+                write!(line_arguments, " {}", query_argument_name(field))?;
+            }
+
+            for header in endpoint.route.headers() {
+                write!(
+                    line_type_signature,
+                    "{} -> ",
+                    to_atom(type_generation::generate_type_ident(
+                        &header.type_ident,
+                        "Ty."
+                    ))
+                )?;
+                write!(line_arguments, " {}", header_argument_name(header))?;
+            }
+
             // return type
             write!(
                 line_type_signature,
@@ -74,10 +102,31 @@ pub(crate) fn generate(service: &ast::ServiceDef, file: &mut IndentWriter) -> Re
                     .as_ref()
                     .map(|q| type_generation::generate_type_ident(q, "Ty."))
                     .unwrap_or_else(|| "NoQuery".to_owned()),
-                to_atom(type_generation::generate_type_ident(
-                    endpoint.route.return_type(),
-                    "Ty."
-                ))
+                match endpoint.route.response_kind() {
+                    ast::ResponseKind::Json => to_atom(type_generation::generate_type_ident(
+                        endpoint.route.return_type(),
+                        "Ty.",
+                    )),
+                    ast::ResponseKind::Paginated => format!(
+                        "(Ty.Page {})",
+                        to_atom(type_generation::generate_type_ident(
+                            endpoint.route.return_type(),
+                            "Ty.",
+                        ))
+                    ),
+                    ast::ResponseKind::Raw => "RawResponse".to_owned(),
+                    // the items of a stream are collected into a list
+                    ast::ResponseKind::Stream => format!(
+                        "(List {})",
+                        to_atom(type_generation::generate_type_ident(
+                            endpoint.route.return_type(),
+                            "Ty.",
+                        ))
+                    ),
+                    ast::ResponseKind::WebSocket | ast::ResponseKind::EventStream => {
+                        unreachable!("WebSocket and Server-Sent Events endpoints are skipped")
+                    }
+                }
             )?;
 
             file.start_line()?.write_all(&line_type_signature)?;
@@ -99,26 +148,54 @@ pub(crate) fn generate(service: &ast::ServiceDef, file: &mut IndentWriter) -> Re
 
         // urlComponents
         {
+            // params spanning several path segments contribute one url component per segment
+            let multi_segment = endpoint.route.components().iter().any(|c| match c {
+                ast::ServiceRouteComponent::Literal(_) => false,
+                ast::ServiceRouteComponent::Variable(_, pattern) => pattern.is_multi_segment(),
+            });
+
             file.increase_indent();
+            if multi_segment {
+                write!(file.start_line()?, "(List.concat")?;
+            }
+            // the service's base path comes first
+            let base_segments = service.base_segments();
+            for (idx, segment) in base_segments.iter().enumerate() {
+                let literal = format!("\"{}\"", segment.escape_default());
+                write!(
+                    file.start_line()?,
+                    "{delimiter} {}",
+                    if multi_segment {
+                        format!("[ {} ]", literal)
+                    } else {
+                        literal
+                    },
+                    delimiter = if idx == 0 { "[" } else { "," }
+                )?;
+            }
             for (idx, component) in endpoint.route.components().iter().enumerate() {
-                let is_first = idx == 0;
+                let is_first = idx == 0 && base_segments.is_empty();
                 let delimiter = if is_first { "[" } else { "," };
 
                 match component {
                     ast::ServiceRouteComponent::Literal(literal) => {
                         // TODO: is this escape sufficient and correct for elm?
+                        let literal = format!("\"{}\"", literal.escape_default());
                         write!(
                             file.start_line()?,
-                            "{delimiter} \"{}\"",
-                            literal.escape_default(),
+                            "{delimiter} {}",
+                            if multi_segment {
+                                format!("[ {} ]", literal)
+                            } else {
+                                literal
+                            },
                             delimiter = delimiter
                         )?;
                     }
 
-                    ast::ServiceRouteComponent::Variable(arg) => {
-                        write!(
-                            file.start_line()?,
-                            "{delimiter} component{idx}_{name} |> {encoder}",
+                    ast::ServiceRouteComponent::Variable(arg, pattern) => {
+                        let encoded = format!(
+                            "component{idx}_{name} |> {encoder}",
                             encoder =
                                 to_atom(encoder_generation::generate_type_urlcomponent_encoder(
                                     &arg.type_ident,
@@ -126,13 +203,28 @@ pub(crate) fn generate(service: &ast::ServiceDef, file: &mut IndentWriter) -> Re
                                 )),
                             name = arg.name,
                             idx = idx,
+                        );
+                        write!(
+                            file.start_line()?,
+                            "{delimiter} {}",
+                            if pattern.is_multi_segment() {
+                                format!("String.split \"/\" ({})", encoded)
+                            } else if multi_segment {
+                                format!("[ {} ]", encoded)
+                            } else {
+                                encoded
+                            },
                             delimiter = delimiter
                         )?;
                     }
                 }
             }
 
-            write!(file.start_line()?, "]")?;
+            write!(
+                file.start_line()?,
+                "{}",
+                if multi_segment { "])" } else { "]" }
+            )?;
         }
 
         // queryEncoder
@@ -149,14 +241,45 @@ pub(crate) fn generate(service: &ast::ServiceDef, file: &mut IndentWriter) -> Re
         }
 
         // resolver
-        write!(
-            file.start_line()?,
-            "(jsonResolver ({}))",
-            to_atom(decoder_generation::generate_type_decoder(
-                &endpoint.route.return_type(),
-                "AD."
-            ))
-        )?;
+        match endpoint.route.response_kind() {
+            ast::ResponseKind::Json if endpoint.error_status.is_some() => write!(
+                file.start_line()?,
+                "(resultResolver {} ({}))",
+                endpoint.error_status.unwrap(),
+                to_atom(decoder_generation::generate_type_decoder(
+                    endpoint.route.return_type(),
+                    "AD."
+                ))
+            )?,
+            ast::ResponseKind::Json => write!(
+                file.start_line()?,
+                "(jsonResolver ({}))",
+                to_atom(decoder_generation::generate_type_decoder(
+                    &endpoint.route.return_type(),
+                    "AD."
+                ))
+            )?,
+            ast::ResponseKind::Paginated => write!(
+                file.start_line()?,
+                "(jsonResolver (AD.decodePage {}))",
+                to_atom(decoder_generation::generate_type_decoder(
+                    endpoint.route.return_type(),
+                    "AD."
+                ))
+            )?,
+            ast::ResponseKind::Raw => write!(file.start_line()?, "rawResolver")?,
+            ast::ResponseKind::Stream => write!(
+                file.start_line()?,
+                "(ndjsonResolver ({}))",
+                to_atom(decoder_generation::generate_type_decoder(
+                    endpoint.route.return_type(),
+                    "AD."
+                ))
+            )?,
+            ast::ResponseKind::WebSocket | ast::ResponseKind::EventStream => {
+                unreachable!("WebSocket and Server-Sent Events endpoints are skipped")
+            }
+        }
 
         // |> withBody if we send a body
         if let Some(body) = endpoint.route.request_body() {
@@ -167,6 +290,54 @@ pub(crate) fn generate(service: &ast::ServiceDef, file: &mut IndentWriter) -> Re
             )?;
         }
 
+        // |> withQueryParam for each query field
+        for field in endpoint.route.query_fields() {
+            match &field.type_ident {
+                ast::TypeIdent::Option(inner) | ast::TypeIdent::Nullable(inner) => write!(
+                    file.start_line()?,
+                    "|> withOptionalQueryParam \"{name}\" (Maybe.map {encoder} {arg})",
+                    name = field.name,
+                    encoder = to_atom(encoder_generation::generate_type_urlcomponent_encoder(
+                        inner, "AE."
+                    )),
+                    arg = query_argument_name(field),
+                )?,
+                ty => write!(
+                    file.start_line()?,
+                    "|> withQueryParam \"{name}\" ({arg} |> {encoder})",
+                    name = field.name,
+                    encoder = to_atom(encoder_generation::generate_type_urlcomponent_encoder(
+                        ty, "AE."
+                    )),
+                    arg = query_argument_name(field),
+                )?,
+            }
+        }
+
+        // |> withHeader for each header binding
+        for header in endpoint.route.headers() {
+            match &header.type_ident {
+                ast::TypeIdent::Option(inner) | ast::TypeIdent::Nullable(inner) => write!(
+                    file.start_line()?,
+                    "|> withOptionalHeader \"{name}\" (Maybe.map {encoder} {arg})",
+                    name = header.name,
+                    encoder = to_atom(encoder_generation::generate_type_urlcomponent_encoder(
+                        inner, "AE."
+                    )),
+                    arg = header_argument_name(header),
+                )?,
+                ty => write!(
+                    file.start_line()?,
+                    "|> withHeader \"{name}\" ({arg} |> {encoder})",
+                    name = header.name,
+                    encoder = to_atom(encoder_generation::generate_type_urlcomponent_encoder(
+                        ty, "AE."
+                    )),
+                    arg = header_argument_name(header),
+                )?,
+            }
+        }
+
         file.decrease_indent();
 
         file.kill_indent();
@@ -177,8 +348,55 @@ pub(crate) fn generate(service: &ast::ServiceDef, file: &mut IndentWriter) -> Re
     Ok(())
 }
 
+fn query_argument_name(field: &ast::FieldDefPair) -> String {
+    format!("query_{}", field.name)
+}
+
+fn header_argument_name(header: &ast::HeaderBinding) -> String {
+    format!("header_{}", header.name.to_snake_case())
+}
+
+/// The endpoints of `service` for which functions are generated.
+///
+/// `Http` cannot open WebSockets or event streams, use ports for `WS` and `SSE` endpoints.
+fn http_endpoints(service: &ast::ServiceDef) -> impl Iterator<Item = &ast::ServiceEndpoint> {
+    service
+        .endpoints
+        .iter()
+        .filter(|e| !e.route.is_websocket() && !e.route.is_event_stream())
+}
+
+/// The names of the functions generated for the endpoints of `service`, in order.
+///
+/// Endpoints whose synthesized names are equal, e.g. because they differ only by their query,
+/// are told apart by a numeric suffix in the order they are declared: `getMonsters`,
+/// `getMonsters2` and so on. Fails if a name is still used twice, e.g. if the suffixed name is
+/// the name of another endpoint.
+pub(crate) fn endpoint_names(service: &ast::ServiceDef) -> Result<Vec<String>, LibError> {
+    let synthesized: Vec<_> = http_endpoints(service)
+        .map(|endpoint| synthesize_endpoint_name(&endpoint.route))
+        .collect();
+
+    let mut names: Vec<String> = Vec::with_capacity(synthesized.len());
+    for (idx, name) in synthesized.iter().enumerate() {
+        let previous = synthesized[..idx].iter().filter(|n| *n == name).count();
+        let name = if previous == 0 {
+            name.clone()
+        } else {
+            format!("{}{}", name, previous + 1)
+        };
+        if names.contains(&name) || (previous > 0 && synthesized.contains(&name)) {
+            return Err(LibError::ElmEndpointNameCollision {
+                service: service.qualified_name("."),
+                name,
+            });
+        }
+        names.push(name);
+    }
+    Ok(names)
+}
+
 fn synthesize_endpoint_name(route: &ast::ServiceRoute) -> String {
-    // TODO: not guranteed to be collision free
     // TODO: let user specify names in humble spec file
     let mut out = vec![];
 
@@ -189,7 +407,7 @@ fn synthesize_endpoint_name(route: &ast::ServiceRoute) -> String {
             ast::ServiceRouteComponent::Literal(lit) => {
                 out.push(lit.clone().to_pascal_case());
             }
-            ast::ServiceRouteComponent::Variable(var) => {
+            ast::ServiceRouteComponent::Variable(var, _) => {
                 out.push(format!("By{}Of", var.name.clone().to_pascal_case()));
             }
         }
@@ -203,6 +421,13 @@ fn synthesize_endpoint_name(route: &ast::ServiceRoute) -> String {
         ast::ServiceRoute::Delete { .. } => "delete",
         ast::ServiceRoute::Put { .. } => "replace",
         ast::ServiceRoute::Patch { .. } => "modify",
+        ast::ServiceRoute::Ws { .. } => "listen",
+        ast::ServiceRoute::Sse { .. } => "subscribe",
+        ast::ServiceRoute::Options { .. } => "options",
+        // e.g. `propfind` for `"PROPFIND"`
+        ast::ServiceRoute::Custom { method, .. } => {
+            return format!("{}{}", method.to_lowercase().to_camel_case(), action)
+        }
     };
 
     format!("{}{}", verb, action)
@@ -0,0 +1,184 @@
+//! Validation of map key and set element types.
+//!
+//! Elm's `Dict` is encoded as and decoded from a JSON object, so only string keys are supported.
+//! Elm's `Set` requires its elements to be `comparable`.
+
+use super::type_generation;
+use crate::{ast, LibError};
+
+/// Check that all maps used in the spec have string keys.
+pub(crate) fn check_map_keys(spec: &ast::Spec) -> Result<(), LibError> {
+    for (location, type_ident) in spec_members(spec) {
+        if let Some(key) = unsupported_map_key(type_ident) {
+            return Err(LibError::ElmUnsupportedMapKey {
+                key_type: type_generation::generate_local_type_ident(key),
+                location,
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Check that all sets used in the spec have `comparable` elements.
+pub(crate) fn check_set_elements(spec: &ast::Spec) -> Result<(), LibError> {
+    for (location, type_ident) in spec_members(spec) {
+        if let Some(element) = unsupported_set_element(type_ident) {
+            return Err(LibError::ElmUnsupportedSetElement {
+                element_type: type_generation::generate_local_type_ident(element),
+                location,
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Describe all places in the spec that refer to a type along with that type.
+fn spec_members(spec: &ast::Spec) -> Vec<(String, &ast::TypeIdent)> {
+    let mut members = Vec::new();
+    for spec_item in spec.iter() {
+        match spec_item {
+            ast::SpecItem::StructDef(sdef) => members.extend(sdef.fields.iter().map(|field| {
+                (
+                    format!("field `{}` of `{}`", field.pair.name, sdef.name),
+                    &field.pair.type_ident,
+                )
+            })),
+            ast::SpecItem::EnumDef(edef) => {
+                for variant in &edef.variants {
+                    let location = format!("variant `{}` of `{}`", variant.name, edef.name);
+                    match variant.variant_type {
+                        ast::VariantType::Simple => {}
+                        ast::VariantType::Tuple(ref tdef) => {
+                            members.extend(tdef.elements().iter().map(|ty| (location.clone(), ty)))
+                        }
+                        ast::VariantType::Newtype(ref ty) => members.push((location, ty)),
+                        ast::VariantType::Struct(ref fields) => {
+                            members.extend(fields.iter().map(|field| {
+                                (
+                                    format!("field `{}` of {}", field.pair.name, location),
+                                    &field.pair.type_ident,
+                                )
+                            }))
+                        }
+                    }
+                }
+            }
+            ast::SpecItem::ServiceDef(service) => {
+                for endpoint in &service.endpoints {
+                    let route = &endpoint.route;
+                    let location = format!(
+                        "endpoint `{} {}` of `{}`",
+                        route.http_method_as_str(),
+                        route_to_string(route),
+                        service.qualified_name(".")
+                    );
+                    members.extend(route.components().iter().filter_map(|c| match c {
+                        ast::ServiceRouteComponent::Literal(_) => None,
+                        ast::ServiceRouteComponent::Variable(pair, _) => {
+                            Some((location.clone(), &pair.type_ident))
+                        }
+                    }));
+                    members.extend(route.query().iter().map(|ty| (location.clone(), ty)));
+                    members.extend(
+                        route
+                            .query_fields()
+                            .iter()
+                            .map(|field| (location.clone(), &field.type_ident)),
+                    );
+                    members.extend(
+                        route
+                            .headers()
+                            .iter()
+                            .map(|header| (location.clone(), &header.type_ident)),
+                    );
+                    members.extend(route.request_body().map(|ty| (location.clone(), ty)));
+                    members.push((location, route.return_type()));
+                }
+            }
+        }
+    }
+    members
+}
+
+fn route_to_string(route: &ast::ServiceRoute) -> String {
+    route
+        .components()
+        .iter()
+        .map(|c| match c {
+            ast::ServiceRouteComponent::Literal(literal) => format!("/{}", literal),
+            ast::ServiceRouteComponent::Variable(pair, _) => format!("/{{{}}}", pair.name),
+        })
+        .collect()
+}
+
+/// Returns the key type of the first map within `type_ident` whose keys are not strings.
+fn unsupported_map_key(type_ident: &ast::TypeIdent) -> Option<&ast::TypeIdent> {
+    match type_ident {
+        ast::TypeIdent::BuiltIn(_) | ast::TypeIdent::UserDefined(_) => None,
+        ast::TypeIdent::List(inner)
+        | ast::TypeIdent::Set(inner)
+        | ast::TypeIdent::Option(inner)
+        | ast::TypeIdent::Nullable(inner) => unsupported_map_key(inner),
+        ast::TypeIdent::Result(ok, err) => {
+            unsupported_map_key(ok).or_else(|| unsupported_map_key(err))
+        }
+        ast::TypeIdent::Map(key, value) | ast::TypeIdent::OrderedMap(key, value) => {
+            match key.as_ref() {
+                ast::TypeIdent::BuiltIn(ast::AtomType::Str) => unsupported_map_key(value),
+                key => Some(key),
+            }
+        }
+        ast::TypeIdent::Tuple(tdef) => tdef.elements().iter().find_map(unsupported_map_key),
+    }
+}
+
+/// Returns the element type of the first set within `type_ident` whose elements are not `comparable`.
+fn unsupported_set_element(type_ident: &ast::TypeIdent) -> Option<&ast::TypeIdent> {
+    match type_ident {
+        ast::TypeIdent::BuiltIn(_) | ast::TypeIdent::UserDefined(_) => None,
+        ast::TypeIdent::List(inner)
+        | ast::TypeIdent::Option(inner)
+        | ast::TypeIdent::Nullable(inner) => unsupported_set_element(inner),
+        ast::TypeIdent::Set(element) if !is_comparable(element) => Some(element),
+        ast::TypeIdent::Set(element) => unsupported_set_element(element),
+        ast::TypeIdent::Result(ok, err) => {
+            unsupported_set_element(ok).or_else(|| unsupported_set_element(err))
+        }
+        ast::TypeIdent::Map(key, value) | ast::TypeIdent::OrderedMap(key, value) => {
+            unsupported_set_element(key).or_else(|| unsupported_set_element(value))
+        }
+        ast::TypeIdent::Tuple(tdef) => tdef.elements().iter().find_map(unsupported_set_element),
+    }
+}
+
+/// Whether the Elm representation of `type_ident` is `comparable`, i.e. a number, a string,
+/// or a list or tuple of those.
+fn is_comparable(type_ident: &ast::TypeIdent) -> bool {
+    match type_ident {
+        ast::TypeIdent::BuiltIn(atom) => matches!(
+            atom,
+            ast::AtomType::Str
+                | ast::AtomType::Url
+                | ast::AtomType::IpAddr
+                | ast::AtomType::U128
+                | ast::AtomType::I128
+                | ast::AtomType::I32
+                | ast::AtomType::U32
+                | ast::AtomType::U8
+                | ast::AtomType::I64
+                | ast::AtomType::U64
+                | ast::AtomType::Duration
+                | ast::AtomType::I16
+                | ast::AtomType::U16
+                | ast::AtomType::I8
+                | ast::AtomType::F32
+                | ast::AtomType::F64
+        ),
+        ast::TypeIdent::List(inner) => is_comparable(inner),
+        // Elm only compares tuples of up to three elements
+        ast::TypeIdent::Tuple(tdef) => {
+            tdef.elements().len() <= 3 && tdef.elements().iter().all(is_comparable)
+        }
+        _ => false,
+    }
+}
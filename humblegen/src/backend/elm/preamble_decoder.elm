@@ -1,10 +1,13 @@
 import Date -- justinmimbs/date
 import Dict exposing (Dict)
 import Iso8601  -- rtfeldman/elm-iso8601-date-strings
+import Set exposing (Set)
 import Json.Decode as D
 import Time  -- elm/time
+import Url  -- elm/url
 import {module_prefix}.BuiltIn.Bytes as BuiltinBytes
 import {module_prefix}.BuiltIn.Uuid as BuiltinUuid
+import {module_prefix}.BuiltIn.Decimal as BuiltinDecimal
 
 -- TODO: move into its own module to avoid name collision
 
@@ -16,6 +19,23 @@ required : String -> D.Decoder a -> D.Decoder (a -> b) -> D.Decoder b
 required key valDecoder decoder =
     custom (D.field key valDecoder) decoder
 
+-- A helper function for a field that decodes to `default` if it is missing from the JSON object.
+optional : String -> D.Decoder a -> a -> D.Decoder (a -> b) -> D.Decoder b
+optional key valDecoder default decoder =
+    custom
+        (D.maybe (D.field key D.value)
+            |> D.andThen
+                (\value ->
+                    case value of
+                        Just _ ->
+                            D.field key valDecoder
+
+                        Nothing ->
+                            D.succeed default
+                )
+        )
+        decoder
+
 -- A helper function for a required index in a JSON list.
 requiredIdx : Int -> D.Decoder a -> D.Decoder (a -> b) -> D.Decoder b
 requiredIdx idx itemDecoder decoder =
@@ -52,6 +72,108 @@ builtinDecodeIso8601 : D.Decoder Time.Posix
 builtinDecodeIso8601 =
     Iso8601.decoder
 
+-- URLs are kept as strings, but only accepted if they are valid. Note that `Url.fromString` only
+-- accepts `http` and `https` URLs.
+builtinDecodeUrl : D.Decoder String
+builtinDecodeUrl =
+    D.string
+    |> D.andThen
+        (\s ->
+            case Url.fromString s of
+                Just _ ->
+                    D.succeed s
+
+                Nothing ->
+                    D.fail <| "not a valid URL: " ++ s
+        )
+
+-- IP addresses are kept as strings, but only accepted if they are valid IPv4 or IPv6 addresses.
+builtinDecodeIp : D.Decoder String
+builtinDecodeIp =
+    D.string
+    |> D.andThen
+        (\s ->
+            if builtinIsIpv4 s || builtinIsIpv6 s then
+                D.succeed s
+
+            else
+                D.fail <| "not a valid IP address: " ++ s
+        )
+
+builtinIsIpv4 : String -> Bool
+builtinIsIpv4 s =
+    let
+        isOctet octet =
+            String.length octet >= 1
+                && String.length octet <= 3
+                && String.all Char.isDigit octet
+                && Maybe.withDefault 256 (String.toInt octet) <= 255
+
+        octets =
+            String.split "." s
+    in
+    List.length octets == 4 && List.all isOctet octets
+
+builtinIsIpv6 : String -> Bool
+builtinIsIpv6 s =
+    let
+        isGroup group =
+            String.length group >= 1 && String.length group <= 4 && String.all Char.isHexDigit group
+
+        groups part =
+            if part == "" then
+                []
+
+            else
+                String.split ":" part
+
+        -- the number of groups in `part`, an embedded IPv4 address at its end counts as two
+        groupCount part =
+            case List.reverse (groups part) of
+                last :: rest ->
+                    if builtinIsIpv4 last && List.all isGroup rest then
+                        Just (List.length rest + 2)
+
+                    else if isGroup last && List.all isGroup rest then
+                        Just (List.length rest + 1)
+
+                    else
+                        Nothing
+
+                [] ->
+                    Just 0
+    in
+    case String.split "::" s of
+        [ full ] ->
+            groupCount full == Just 8
+
+        [ before, after ] ->
+            -- `::` abbreviates at least one group of zeros
+            List.all isGroup (groups before)
+                && (case groupCount after of
+                        Just count ->
+                            List.length (groups before) + count <= 7
+
+                        Nothing ->
+                            False
+                   )
+
+        _ ->
+            False
+
+-- Integers with a range constraint are only accepted within the inclusive range `lower..upper`,
+-- either bound may be missing.
+builtinDecodeInRange : Maybe Int -> Maybe Int -> D.Decoder Int -> D.Decoder Int
+builtinDecodeInRange lower upper =
+    D.andThen
+        (\i ->
+            if Maybe.withDefault i lower <= i && i <= Maybe.withDefault i upper then
+                D.succeed i
+
+            else
+                D.fail <| "integer " ++ String.fromInt i ++ " is out of range"
+        )
+
 
 builtinDecodeResult : D.Decoder error -> D.Decoder value -> D.Decoder (Result error value)
 builtinDecodeResult error value =
@@ -62,4 +184,57 @@ builtinDecodeResult error value =
 
 builtinDecodeOption : D.Decoder value -> D.Decoder (Maybe value)
 builtinDecodeOption =
-    D.nullable
\ No newline at end of file
+    D.nullable
+
+builtinDecodeSet : D.Decoder comparable -> D.Decoder (Set comparable)
+builtinDecodeSet =
+    D.list >> D.map Set.fromList
+
+-- Elm's `Int` is a JavaScript number, which only represents integers up to 2^53 - 1 exactly.
+-- Larger 64-bit values would be silently truncated, so they are rejected instead.
+builtinDecodeInt64 : D.Decoder Int
+builtinDecodeInt64 =
+    D.int
+    |> D.andThen
+        (\i ->
+            if abs i <= 9007199254740991 then
+                D.succeed i
+
+            else
+                D.fail <| "integer cannot be represented without loss of precision: " ++ String.fromInt i
+        )
+
+-- durations are encoded as non-negative integer milliseconds
+builtinDecodeDuration : D.Decoder Int
+builtinDecodeDuration =
+    builtinDecodeInt64
+    |> D.andThen
+        (\i ->
+            if i >= 0 then
+                D.succeed i
+
+            else
+                D.fail <| "duration cannot be negative: " ++ String.fromInt i
+        )
+
+-- 128-bit integers are encoded as decimal strings and kept as such, since Elm's `Int` cannot
+-- represent them. Only strings of digits are accepted, with a leading `-` if `signed`.
+builtinDecodeIntString : Bool -> D.Decoder String
+builtinDecodeIntString signed =
+    D.string
+    |> D.andThen
+        (\s ->
+            let
+                digits =
+                    if signed && String.startsWith "-" s then
+                        String.dropLeft 1 s
+
+                    else
+                        s
+            in
+            if not (String.isEmpty digits) && String.all Char.isDigit digits then
+                D.succeed s
+
+            else
+                D.fail <| "not a valid integer: " ++ s
+        )
@@ -1,11 +1,13 @@
 import Date -- justinmimbs/date
 import Dict exposing (Dict)
 import Iso8601  -- rtfeldman/elm-iso8601-date-strings
+import Set exposing (Set)
 import Json.Encode as E
 import Time  -- elm/time
 import Url.Builder
 import {module_prefix}.BuiltIn.Bytes as BuiltinBytes
 import {module_prefix}.BuiltIn.Uuid as BuiltinUuid
+import {module_prefix}.BuiltIn.Decimal as BuiltinDecimal
 
 
 
@@ -24,6 +26,11 @@ builtinEncodeMaybe encoder =
     Maybe.map encoder >> Maybe.withDefault E.null
 
 
+builtinEncodeSet : (comparable -> E.Value) -> Set comparable -> E.Value
+builtinEncodeSet encoder =
+    Set.toList >> E.list encoder
+
+
 builtinEncodeResult : (err -> E.Value) -> (ok -> E.Value) -> Result err ok -> E.Value
 builtinEncodeResult errEncoder okEncoder res =
     case res of
@@ -1,6 +1,8 @@
 import {module_prefix}.BuiltIn.Bytes as BuiltinBytes
 import {module_prefix}.BuiltIn.Uuid as BuiltinUuid
+import {module_prefix}.BuiltIn.Decimal as BuiltinDecimal
 import {module_prefix}.ServiceBuiltIn
+import Set exposing (Set)
 type alias Error = {module_prefix}.ServiceBuiltIn.Error
 type alias Request q t  = {module_prefix}.ServiceBuiltIn.Request q t
 withHeader = {module_prefix}.ServiceBuiltIn.withHeader
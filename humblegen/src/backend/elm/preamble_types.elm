@@ -1,6 +1,9 @@
 import Date -- justinmimbs/date
 import Dict exposing (Dict)
 import Iso8601  -- rtfeldman/elm-iso8601-date-strings
+import Json.Encode
+import Set exposing (Set)
 import Time  -- elm/time
 import {module_prefix}.BuiltIn.Bytes as BuiltinBytes
 import {module_prefix}.BuiltIn.Uuid as BuiltinUuid
+import {module_prefix}.BuiltIn.Decimal as BuiltinDecimal
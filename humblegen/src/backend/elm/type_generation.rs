@@ -1,15 +1,46 @@
-use super::{field_name, generate_doc_comment, to_atom, IndentWriter};
+use super::{
+    field_name, generate_doc_comment, generate_type_doc_comment, to_atom, with_deprecation,
+    IndentWriter,
+};
 use crate::{ast, LibError};
 use itertools::Itertools;
 
-// TODO: Elm does not allow documentation on members, so the docs need to be converted to markdown
-//       lists instead. This is true for `type alias` struct fields as well as enum variants.
-
 pub(crate) fn generate_struct_def(
     def: &ast::StructDef,
     file: &mut IndentWriter,
 ) -> Result<(), LibError> {
-    generate_struct_def_from_parts(&def.name, &def.doc_comment, &def.fields, file)
+    let doc_comment = with_deprecation(&def.doc_comment, &def.deprecated);
+    generate_struct_def_from_parts(&def.name, &doc_comment, &def.fields, file)
+}
+
+/// Generate the envelope returned by `@paginated` endpoints, mirroring the `Page<T>` of the
+/// Rust backend.
+pub(crate) fn generate_page_def(file: &mut IndentWriter) -> Result<(), LibError> {
+    file.kill_indent();
+    write!(
+        file.start_line()?,
+        "{{-| A page of the items returned by a paginated endpoint.\n-}}\n\
+         type alias Page a =\n    \
+         {{ items: List a\n    \
+         , total: Int\n    \
+         , limit: Int\n    \
+         , offset: Int\n    \
+         }}"
+    )?;
+    file.empty_lines(2)?;
+    Ok(())
+}
+
+/// Generate the `apiVersion` constant, the `@version` of the spec.
+pub(crate) fn generate_api_version(version: &str, file: &mut IndentWriter) -> Result<(), LibError> {
+    file.kill_indent();
+    write!(
+        file.start_line()?,
+        "{{-| The version of the API.\n-}}\napiVersion : String\napiVersion =\n    {:?}",
+        version
+    )?;
+    file.empty_lines(2)?;
+    Ok(())
 }
 
 pub(crate) fn generate_struct_def_from_parts(
@@ -23,7 +54,13 @@ pub(crate) fn generate_struct_def_from_parts(
     write!(
         file.start_line()?,
         "{doc_comment}\ntype alias {name} =",
-        doc_comment = generate_doc_comment(def_doc_comment),
+        doc_comment = generate_type_doc_comment(
+            def_doc_comment,
+            def_fields.iter().map(|field| (
+                field_name(&field.pair.name),
+                with_deprecation(&field.doc_comment, &field.deprecated)
+            ))
+        ),
         name = def_name
     )?;
 
@@ -80,7 +117,12 @@ pub(crate) fn generate_enum_def(
     write!(
         file.start_line()?,
         "{doc_comment}\ntype {name}",
-        doc_comment = generate_doc_comment(&def.doc_comment),
+        doc_comment = generate_type_doc_comment(
+            &with_deprecation(&def.doc_comment, &def.deprecated),
+            def.variants
+                .iter()
+                .map(|variant| (variant.name.clone(), variant.doc_comment.clone()))
+        ),
         name = def.name,
     )?;
 
@@ -93,6 +135,8 @@ pub(crate) fn generate_enum_def(
 
     file.empty_lines(2)?;
 
+    generate_enum_variant_constructors(def, file)?;
+
     Ok(())
 }
 
@@ -110,6 +154,61 @@ fn generate_enum_variant_anonymous_constructors(
     Ok(())
 }
 
+/// Generate a function for each struct variant of `def` that takes the fields of the variant and
+/// returns the enum value, so that the anonymous struct need not be built separately.
+fn generate_enum_variant_constructors(
+    def: &ast::EnumDef,
+    file: &mut IndentWriter,
+) -> Result<(), LibError> {
+    file.kill_indent();
+
+    for variant in def.variants.iter() {
+        let fields = match variant.variant_type {
+            ast::VariantType::Struct(ref fields) => fields,
+            _ => continue,
+        };
+        let name = enum_variant_constructor_name(&def.name, &variant.name);
+        let field_names: Vec<_> = fields
+            .iter()
+            .map(|field| field_name(&field.pair.name))
+            .collect();
+        let signature = fields
+            .iter()
+            .map(|field| to_atom(generate_local_type_ident(&field.pair.type_ident)))
+            .chain(std::iter::once(def.name.clone()))
+            .join(" -> ");
+
+        write!(
+            file.start_line()?,
+            "{doc_comment}\n{name} : {signature}\n{name}{args} =",
+            doc_comment = generate_doc_comment(&Some(format!(
+                "Construct the `{}` variant of `{}` from its fields.",
+                variant.name, def.name
+            ))),
+            name = name,
+            signature = signature,
+            args = field_names.iter().map(|arg| format!(" {}", arg)).join(""),
+        )?;
+        let record = if field_names.is_empty() {
+            "{}".to_owned()
+        } else {
+            format!(
+                "{{ {} }}",
+                field_names
+                    .iter()
+                    .map(|field| format!("{0} = {0}", field))
+                    .join(", ")
+            )
+        };
+        file.increase_indent();
+        write!(file.start_line()?, "{} {}", variant.name, record)?;
+        file.decrease_indent();
+        file.empty_lines(2)?;
+    }
+
+    Ok(())
+}
+
 /// Generate elm code for a variant definition.
 fn generate_enum_variant_def(
     edef: &ast::EnumDef,
@@ -171,12 +270,23 @@ pub(crate) fn enum_anonymous_struct_constructor_name(
     format!("{}__{}__Internal__", enum_name, variant_name)
 }
 
+/// Name of the function constructing the struct variant `variant_name` of the enum `enum_name`.
+///
+/// The underscore separating the names cannot occur in type names or in (camel cased) field
+/// names, so the function neither collides with another constructor nor with its arguments.
+fn enum_variant_constructor_name(enum_name: &str, variant_name: &str) -> String {
+    let mut enum_name = enum_name.to_owned();
+    enum_name[..1].make_ascii_lowercase();
+    format!("{}_{}", enum_name, variant_name)
+}
+
 /// Generate elm code for a type identifier.
 pub(crate) fn generate_type_ident(type_ident: &ast::TypeIdent, ns: &str) -> String {
     match type_ident {
         ast::TypeIdent::BuiltIn(atom) => generate_atom(atom),
         ast::TypeIdent::List(inner) => format!("List {}", to_atom(generate_type_ident(inner, ns))),
-        ast::TypeIdent::Option(inner) => {
+        ast::TypeIdent::Set(inner) => format!("Set {}", to_atom(generate_type_ident(inner, ns))),
+        ast::TypeIdent::Option(inner) | ast::TypeIdent::Nullable(inner) => {
             format!("Maybe {}", to_atom(generate_type_ident(inner, ns)))
         }
         ast::TypeIdent::Result(ok, err) => format!(
@@ -184,7 +294,8 @@ pub(crate) fn generate_type_ident(type_ident: &ast::TypeIdent, ns: &str) -> Stri
             to_atom(generate_type_ident(err, ns)),
             to_atom(generate_type_ident(ok, ns)),
         ),
-        ast::TypeIdent::Map(key, value) => format!(
+        // `Dict` is ordered by key anyway
+        ast::TypeIdent::Map(key, value) | ast::TypeIdent::OrderedMap(key, value) => format!(
             "Dict {} {}",
             to_atom(generate_type_ident(key, ns)),
             to_atom(generate_type_ident(value, ns)),
@@ -217,12 +328,24 @@ fn generate_atom(atom: &ast::AtomType) -> String {
         ast::AtomType::I32 => "Int",
         ast::AtomType::U32 => "Int",
         ast::AtomType::U8 => "Int",
+        ast::AtomType::I64 => "Int",
+        ast::AtomType::U64 => "Int",
+        // milliseconds
+        ast::AtomType::Duration => "Int",
+        ast::AtomType::I16 => "Int",
+        ast::AtomType::U16 => "Int",
+        ast::AtomType::I8 => "Int",
+        ast::AtomType::F32 => "Float",
         ast::AtomType::F64 => "Float",
         ast::AtomType::Bool => "Bool",
         ast::AtomType::DateTime => "Time.Posix",
         ast::AtomType::Date => "Date.Date",
         ast::AtomType::Uuid => "BuiltinUuid.Uuid",
+        ast::AtomType::Url | ast::AtomType::IpAddr => "String",
+        ast::AtomType::U128 | ast::AtomType::I128 => "String",
         ast::AtomType::Bytes => "BuiltinBytes.Bytes",
+        ast::AtomType::Decimal => "BuiltinDecimal.Decimal",
+        ast::AtomType::Json => "Json.Encode.Value",
     }
     .to_owned()
 }
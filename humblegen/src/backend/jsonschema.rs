@@ -0,0 +1,355 @@
+//! Generates a JSON Schema (draft 2020-12) document for each user defined type of a humble
+//! specification file
+//!
+//! Each document is self-contained: the types it refers to are included as `$defs`, so that
+//! messages can be validated without resolving other files. Schemas describe the JSON
+//! representation documented in `docs/humblespec/data_types_json_representation.md`.
+
+use crate::{ast, LibError};
+
+use serde_json::{json, Map, Value};
+
+use std::{collections::BTreeMap, fs, path::Path};
+
+/// The JSON Schema dialect of the generated documents.
+const SCHEMA_DIALECT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+/// Generate the schemas of all user defined types of `spec`, by type name.
+fn generate_schemas(spec: &ast::Spec) -> BTreeMap<&str, Value> {
+    spec.iter()
+        .filter_map(|spec_item| match spec_item {
+            ast::SpecItem::StructDef(sdef) => Some((sdef.name.as_str(), struct_schema(sdef))),
+            ast::SpecItem::EnumDef(edef) => Some((edef.name.as_str(), enum_schema(edef))),
+            ast::SpecItem::ServiceDef(_) => None,
+        })
+        .collect()
+}
+
+/// Generate the document of the type `name`, containing the schemas of all types it refers to.
+fn generate_document(schemas: &BTreeMap<&str, Value>, name: &str) -> Value {
+    let mut defs = Map::new();
+    let mut pending = vec![name.to_owned()];
+    while let Some(name) = pending.pop() {
+        if defs.contains_key(&name) {
+            continue;
+        }
+        let schema = &schemas[name.as_str()];
+        collect_references(schema, &mut pending);
+        defs.insert(name, schema.clone());
+    }
+
+    json!({
+        "$schema": SCHEMA_DIALECT,
+        "title": name,
+        "$ref": format!("#/$defs/{}", name),
+        "$defs": defs,
+    })
+}
+
+/// Add the names of all types referred to by `schema` to `names`.
+fn collect_references(schema: &Value, names: &mut Vec<String>) {
+    match schema {
+        Value::Object(object) => {
+            if let Some(name) = object
+                .get("$ref")
+                .and_then(Value::as_str)
+                .and_then(|r| r.strip_prefix("#/$defs/"))
+            {
+                names.push(name.to_owned());
+            }
+            object.values().for_each(|v| collect_references(v, names));
+        }
+        Value::Array(array) => array.iter().for_each(|v| collect_references(v, names)),
+        _ => {}
+    }
+}
+
+/// Generate the schema of a struct.
+fn struct_schema(sdef: &ast::StructDef) -> Value {
+    with_deprecation(
+        with_description(
+            fields_schema(&sdef.fields, sdef.rename_all),
+            &sdef.doc_comment,
+        ),
+        &sdef.deprecated,
+    )
+}
+
+/// Generate the schema of an object with the given fields. Optional fields and fields with a
+/// default value may be omitted, nullable fields have to be present but may be `null`.
+fn fields_schema(fields: &ast::StructFields, rename_all: Option<ast::RenameRule>) -> Value {
+    let mut properties = Map::new();
+    let mut required = Vec::new();
+    for field in fields.iter() {
+        let wire_name = field.wire_name(rename_all);
+        if field.default.is_none() && !matches!(field.pair.type_ident, ast::TypeIdent::Option(_)) {
+            required.push(json!(wire_name));
+        }
+        let mut schema = match field.bytes_encoding {
+            ast::BytesEncoding::Base64 => type_schema(&field.pair.type_ident),
+            ast::BytesEncoding::Hex => json!({ "type": "string", "contentEncoding": "base16" }),
+        };
+        if let Some(range) = field.pair.range {
+            // the integer schema of an optional field is the first alternative
+            let integer = match field.pair.type_ident {
+                ast::TypeIdent::Option(_) | ast::TypeIdent::Nullable(_) => &mut schema["anyOf"][0],
+                _ => &mut schema,
+            };
+            with_range(integer, range);
+        }
+        if let Some(ref default) = field.default {
+            schema["default"] = default_value(default);
+        }
+        let schema = with_description(schema, &field.doc_comment);
+        properties.insert(wire_name, with_deprecation(schema, &field.deprecated));
+    }
+
+    json!({
+        "type": "object",
+        "properties": properties,
+        "required": required,
+    })
+}
+
+/// Generate the schema of an enum, following its serde representation.
+fn enum_schema(edef: &ast::EnumDef) -> Value {
+    let schema = match edef.tagging() {
+        ast::EnumTagging::External => externally_tagged_enum_schema(edef),
+        ast::EnumTagging::Internal { tag } => tagged_enum_schema(edef, tag, None),
+        ast::EnumTagging::Adjacent { tag, content } => tagged_enum_schema(edef, tag, Some(content)),
+    };
+    with_deprecation(
+        with_description(schema, &edef.doc_comment),
+        &edef.deprecated,
+    )
+}
+
+/// Generate the schema of an externally tagged enum.
+fn externally_tagged_enum_schema(edef: &ast::EnumDef) -> Value {
+    let simple_variants: Vec<_> = edef
+        .simple_variants()
+        .map(|v| json!(v.wire_name()))
+        .collect();
+    let simple = json!({ "enum": simple_variants });
+
+    let complex: Vec<_> = edef
+        .complex_variants()
+        .map(|variant| {
+            let payload = match &variant.variant_type {
+                ast::VariantType::Simple => unreachable!("variant is complex"),
+                ast::VariantType::Tuple(tdef) => tuple_schema(tdef),
+                ast::VariantType::Struct(fields) => fields_schema(fields, None),
+                ast::VariantType::Newtype(ty) => type_schema(ty),
+            };
+            json!({
+                "type": "object",
+                "properties": {
+                    variant.name.clone(): with_description(payload, &variant.doc_comment),
+                },
+                "required": [variant.name],
+                "additionalProperties": false,
+            })
+        })
+        .collect();
+
+    if complex.is_empty() {
+        simple
+    } else if simple_variants.is_empty() {
+        json!({ "oneOf": complex })
+    } else {
+        json!({ "oneOf": std::iter::once(simple).chain(complex).collect::<Vec<_>>() })
+    }
+}
+
+/// Generate the schema of an internally (`content` is `None`) or adjacently tagged enum.
+fn tagged_enum_schema(edef: &ast::EnumDef, tag: &str, content: Option<&str>) -> Value {
+    let variants: Vec<_> = edef
+        .variants
+        .iter()
+        .map(|variant| {
+            let mut schema = match (content, &variant.variant_type) {
+                // internally tagged enums only have simple and struct variants
+                (None, ast::VariantType::Struct(fields)) => fields_schema(fields, None),
+                (Some(content), ast::VariantType::Tuple(tdef)) => {
+                    json!({ "properties": { content: tuple_schema(tdef) }, "required": [content] })
+                }
+                (Some(content), ast::VariantType::Struct(fields)) => json!({
+                    "properties": { content: fields_schema(fields, None) },
+                    "required": [content],
+                }),
+                (Some(content), ast::VariantType::Newtype(ty)) => json!({
+                    "properties": { content: type_schema(ty) },
+                    "required": [content],
+                }),
+                _ => json!({ "properties": {}, "required": [] }),
+            };
+            schema["type"] = json!("object");
+            schema["properties"][tag] = json!({ "const": variant.wire_name() });
+            schema["required"]
+                .as_array_mut()
+                .expect("schemas of variants have a required list")
+                .insert(0, json!(tag));
+            with_description(schema, &variant.doc_comment)
+        })
+        .collect();
+
+    json!({ "oneOf": variants })
+}
+
+/// Generate the schema of a type.
+fn type_schema(type_ident: &ast::TypeIdent) -> Value {
+    match type_ident {
+        ast::TypeIdent::BuiltIn(atom) => atom_schema(*atom),
+        ast::TypeIdent::List(inner) => json!({ "type": "array", "items": type_schema(inner) }),
+        ast::TypeIdent::Set(inner) => json!({
+            "type": "array",
+            "items": type_schema(inner),
+            "uniqueItems": true,
+        }),
+        ast::TypeIdent::Option(inner) | ast::TypeIdent::Nullable(inner) => {
+            json!({ "anyOf": [type_schema(inner), { "type": "null" }] })
+        }
+        ast::TypeIdent::Result(ok, err) => json!({
+            "oneOf": [
+                {
+                    "type": "object",
+                    "properties": { "Ok": type_schema(ok) },
+                    "required": ["Ok"],
+                    "additionalProperties": false,
+                },
+                {
+                    "type": "object",
+                    "properties": { "Err": type_schema(err) },
+                    "required": ["Err"],
+                    "additionalProperties": false,
+                },
+            ],
+        }),
+        // keys are always represented as strings in JSON
+        ast::TypeIdent::Map(_, value) | ast::TypeIdent::OrderedMap(_, value) => json!({
+            "type": "object",
+            "additionalProperties": type_schema(value),
+        }),
+        ast::TypeIdent::Tuple(tdef) => tuple_schema(tdef),
+        ast::TypeIdent::UserDefined(name) => def_ref(name),
+    }
+}
+
+/// Generate the schema of a tuple.
+fn tuple_schema(tdef: &ast::TupleDef) -> Value {
+    json!({
+        "type": "array",
+        "prefixItems": tdef.elements().iter().map(type_schema).collect::<Vec<_>>(),
+        "items": false,
+        "minItems": tdef.elements().len(),
+    })
+}
+
+/// Generate the schema of a built-in type.
+fn atom_schema(atom: ast::AtomType) -> Value {
+    fn integer(min: i64, max: i64) -> Value {
+        json!({ "type": "integer", "minimum": min, "maximum": max })
+    }
+
+    match atom {
+        ast::AtomType::Empty => json!({ "type": "null" }),
+        ast::AtomType::Str => json!({ "type": "string" }),
+        ast::AtomType::I8 => integer(i8::MIN.into(), i8::MAX.into()),
+        ast::AtomType::I16 => integer(i16::MIN.into(), i16::MAX.into()),
+        ast::AtomType::I32 => integer(i32::MIN.into(), i32::MAX.into()),
+        ast::AtomType::U8 => integer(0, u8::MAX.into()),
+        ast::AtomType::U16 => integer(0, u16::MAX.into()),
+        ast::AtomType::U32 => integer(0, u32::MAX.into()),
+        ast::AtomType::I64 => json!({ "type": "integer" }),
+        ast::AtomType::U64 => json!({ "type": "integer", "minimum": 0 }),
+        ast::AtomType::Duration => json!({
+            "type": "integer",
+            "minimum": 0,
+            "description": "duration in milliseconds",
+        }),
+        ast::AtomType::U128 => json!({ "type": "string", "pattern": "^[0-9]+$" }),
+        ast::AtomType::I128 => json!({ "type": "string", "pattern": "^-?[0-9]+$" }),
+        ast::AtomType::F32 | ast::AtomType::F64 => json!({ "type": "number" }),
+        ast::AtomType::Bool => json!({ "type": "boolean" }),
+        ast::AtomType::DateTime => json!({ "type": "string", "format": "date-time" }),
+        ast::AtomType::Date => json!({ "type": "string", "format": "date" }),
+        ast::AtomType::Uuid => json!({ "type": "string", "format": "uuid" }),
+        ast::AtomType::Url => json!({ "type": "string", "format": "uri" }),
+        ast::AtomType::IpAddr => json!({
+            "anyOf": [
+                { "type": "string", "format": "ipv4" },
+                { "type": "string", "format": "ipv6" },
+            ],
+        }),
+        ast::AtomType::Bytes => json!({ "type": "string", "contentEncoding": "base64" }),
+        ast::AtomType::Decimal => json!({ "type": "string", "pattern": "^-?[0-9]+(\\.[0-9]+)?$" }),
+        // any value
+        ast::AtomType::Json => json!({}),
+    }
+}
+
+fn def_ref(name: &str) -> Value {
+    json!({ "$ref": format!("#/$defs/{}", name) })
+}
+
+/// Narrow the bounds of the integer `schema` to `range`.
+fn with_range(schema: &mut Value, range: ast::RangeConstraint) {
+    if let Some(min) = range.min {
+        schema["minimum"] = json!(min);
+    }
+    if let Some(max) = range.max {
+        schema["maximum"] = json!(max);
+    }
+}
+
+/// The JSON representation of a default value.
+fn default_value(default: &ast::DefaultValue) -> Value {
+    match default {
+        ast::DefaultValue::Bool(b) => json!(b),
+        ast::DefaultValue::Int(i) => json!(i),
+        ast::DefaultValue::Float(f) => json!(f),
+        ast::DefaultValue::Str(s) => json!(s),
+    }
+}
+
+/// Add the doc comment, if any, as description to `object`.
+fn with_description(mut object: Value, doc_comment: &Option<String>) -> Value {
+    if let Some(doc_comment) = doc_comment {
+        object["description"] = json!(doc_comment);
+    }
+    object
+}
+
+/// Mark `object` as deprecated if `deprecated` holds a note, which is added to the description.
+fn with_deprecation(mut object: Value, deprecated: &Option<String>) -> Value {
+    if let Some(note) = deprecated {
+        let description = match object["description"].as_str() {
+            Some(description) => format!("{}\n\nDeprecated: {}", description, note),
+            None => format!("Deprecated: {}", note),
+        };
+        object["description"] = json!(description);
+        object["deprecated"] = json!(true);
+    }
+    object
+}
+
+/// JSON Schema backend. Writes a document `<Type>.schema.json` for each user defined type
+/// into the output directory, which is created if it does not exist.
+#[derive(Default)]
+pub struct Generator {}
+
+impl crate::CodeGenerator for Generator {
+    fn generate(&self, spec: &ast::Spec, output: &Path) -> Result<(), LibError> {
+        fs::create_dir_all(output)?;
+
+        let schemas = generate_schemas(spec);
+        for name in schemas.keys() {
+            let document = generate_document(&schemas, name);
+            let mut json =
+                serde_json::to_string_pretty(&document).map_err(|e| LibError::IoError(e.into()))?;
+            json.push('\n');
+            fs::write(output.join(format!("{}.schema.json", name)), json)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,584 @@
+//! Generates an OpenAPI 3.0 document for a humble specification file
+//!
+//! Structs and enums become entries of `components/schemas`, each service endpoint becomes an
+//! operation. Schemas describe the JSON representation documented in
+//! `docs/humblespec/data_types_json_representation.md`.
+//!
+//! Services are mounted at a prefix chosen at runtime (see `Builder::add` in `humblegen-rt`),
+//! so paths are relative to that prefix. Operations are tagged with the name of their service.
+
+use crate::{ast, LibError};
+
+use inflector::Inflector;
+use serde_json::{json, Map, Value};
+
+use std::{fs::File, io::Write, path::Path};
+
+/// The OpenAPI version of the generated document.
+const OPENAPI_VERSION: &str = "3.0.3";
+
+/// Name of the schema of service-level and runtime error responses (see `service_protocol.md`).
+/// Contains a `.` so that it cannot collide with a user defined type.
+const ERROR_RESPONSE_SCHEMA: &str = "humblegen.ErrorResponse";
+
+/// Generate the OpenAPI document for `spec`.
+fn generate_document(spec: &ast::Spec, title: &str) -> Value {
+    let mut schemas = Map::new();
+    let mut paths = Map::new();
+    let mut tags = Vec::new();
+
+    for spec_item in spec.iter() {
+        match spec_item {
+            ast::SpecItem::StructDef(sdef) => {
+                schemas.insert(sdef.name.clone(), struct_schema(sdef));
+            }
+            ast::SpecItem::EnumDef(edef) => {
+                schemas.insert(edef.name.clone(), enum_schema(edef));
+            }
+            ast::SpecItem::ServiceDef(service) => {
+                tags.push(with_description(
+                    json!({ "name": service.qualified_name(".") }),
+                    &service.doc_comment,
+                ));
+                // OpenAPI cannot describe WebSockets and custom methods, and `SSE` endpoints may
+                // share their path with a `GET` endpoint
+                for endpoint in service.endpoints.iter().filter(|e| {
+                    !e.route.is_websocket()
+                        && !e.route.is_event_stream()
+                        && !matches!(e.route, ast::ServiceRoute::Custom { .. })
+                }) {
+                    let path_item = paths
+                        .entry(path(service, &endpoint.route))
+                        .or_insert_with(|| json!({}));
+                    path_item[endpoint.route.http_method_as_str().to_lowercase()] =
+                        operation(service, endpoint);
+                }
+            }
+        }
+    }
+    schemas.insert(ERROR_RESPONSE_SCHEMA.to_owned(), error_response_schema());
+
+    json!({
+        "openapi": OPENAPI_VERSION,
+        "info": {
+            "title": title,
+            "version": spec.version.as_deref().unwrap_or("1"),
+        },
+        "tags": tags,
+        "paths": paths,
+        "components": {
+            "schemas": schemas,
+        },
+    })
+}
+
+/// The OpenAPI path of a route including the service's base path, e.g. `/v1/monsters/{id}`.
+fn path(service: &ast::ServiceDef, route: &ast::ServiceRoute) -> String {
+    let route_path: String = route
+        .components()
+        .iter()
+        .map(|c| match c {
+            ast::ServiceRouteComponent::Literal(literal) => format!("/{}", literal),
+            ast::ServiceRouteComponent::Variable(pair, _) => format!("/{{{}}}", pair.name),
+        })
+        .collect();
+    format!("{}{}", service.base.as_deref().unwrap_or(""), route_path)
+}
+
+/// Generate the operation object of an endpoint.
+fn operation(service: &ast::ServiceDef, endpoint: &ast::ServiceEndpoint) -> Value {
+    let route = &endpoint.route;
+    let mut parameters = Vec::new();
+
+    for component in route.components() {
+        if let ast::ServiceRouteComponent::Variable(pair, pattern) = component {
+            let mut schema = type_schema(&pair.type_ident);
+            match pattern {
+                ast::RouteParamPattern::Segment => {}
+                ast::RouteParamPattern::Rest => {
+                    schema["description"] = json!("may span several path segments");
+                }
+                ast::RouteParamPattern::Regex(regex) => {
+                    schema["pattern"] = json!(format!("^(?:{})$", regex));
+                }
+            }
+            parameters.push(json!({
+                "name": pair.name,
+                "in": "path",
+                "required": true,
+                "schema": schema,
+            }));
+        }
+    }
+
+    if let Some(query) = route.query() {
+        // the fields of the query struct are sent as individual query parameters
+        parameters.push(json!({
+            "name": "query",
+            "in": "query",
+            "required": false,
+            "style": "form",
+            "explode": true,
+            "schema": type_schema(query),
+        }));
+    }
+    parameters.extend(route.query_fields().iter().map(|field| {
+        let (required, schema) = optional_param_schema(&field.type_ident);
+        json!({
+            "name": field.name,
+            "in": "query",
+            "required": required,
+            "schema": schema,
+        })
+    }));
+    parameters.extend(route.headers().iter().map(|header| {
+        let (required, schema) = optional_param_schema(&header.type_ident);
+        json!({
+            "name": header.name,
+            "in": "header",
+            "required": required,
+            "schema": schema,
+        })
+    }));
+
+    let success = match route.response_kind() {
+        ast::ResponseKind::Json => json!({
+            "description": "Successful response.",
+            "content": {
+                "application/json": { "schema": type_schema(route.return_type()) },
+            },
+        }),
+        ast::ResponseKind::Paginated => json!({
+            "description": "A page of the items, along with the total number of items.",
+            "content": {
+                "application/json": { "schema": page_schema(route.return_type()) },
+            },
+        }),
+        ast::ResponseKind::Raw => json!({
+            "description": "The raw response body, with a content type chosen by the handler.",
+            "content": {
+                "*/*": { "schema": { "type": "string", "format": "binary" } },
+            },
+        }),
+        ast::ResponseKind::Stream => json!({
+            "description": "Newline-delimited JSON, one line per item of the given schema.",
+            "content": {
+                "application/x-ndjson": { "schema": type_schema(route.return_type()) },
+            },
+        }),
+        ast::ResponseKind::WebSocket | ast::ResponseKind::EventStream => {
+            unreachable!("WebSocket and Server-Sent Events endpoints are skipped")
+        }
+    };
+
+    let mut operation = json!({
+        "tags": [service.qualified_name(".")],
+        "operationId": operation_id(service, route),
+        "parameters": parameters,
+        "responses": {
+            "default": {
+                "description": "Service-level or runtime error.",
+                "content": {
+                    "application/json": { "schema": schema_ref(ERROR_RESPONSE_SCHEMA) },
+                },
+            },
+        },
+    });
+    operation["responses"][route.status().to_string()] = success;
+    if let (Some(error_status), ast::TypeIdent::Result(_, err)) =
+        (endpoint.error_status, route.return_type())
+    {
+        operation["responses"][error_status.to_string()] = json!({
+            "description": "Domain error of the endpoint, declared using `@error_status`.",
+            "content": {
+                "application/json": {
+                    "schema": {
+                        "type": "object",
+                        "properties": { "Err": type_schema(err) },
+                        "required": ["Err"],
+                        "additionalProperties": false,
+                    },
+                },
+            },
+        });
+    }
+    if let Some(body) = route.request_body() {
+        operation["requestBody"] = json!({
+            "required": true,
+            "content": {
+                "application/json": { "schema": type_schema(body) },
+            },
+        });
+    }
+    with_deprecation(
+        with_description(operation, &endpoint.doc_comment),
+        &endpoint.deprecated,
+    )
+}
+
+/// A unique name for an endpoint, e.g. `monsters_get_monsters_id`.
+fn operation_id(service: &ast::ServiceDef, route: &ast::ServiceRoute) -> String {
+    let stem = route
+        .components()
+        .iter()
+        .map(|c| match c {
+            ast::ServiceRouteComponent::Literal(literal) => literal.as_str(),
+            ast::ServiceRouteComponent::Variable(pair, _) => pair.name.as_str(),
+        })
+        .collect::<Vec<_>>()
+        .join("_");
+    format!(
+        "{}_{}_{}",
+        service.qualified_name("_").to_snake_case(),
+        route.http_method_as_str().to_lowercase(),
+        stem.to_snake_case()
+    )
+}
+
+/// Whether a query parameter or header of the given type is required, along with the schema
+/// of its value. Parameters of type `option[T]` may be omitted.
+fn optional_param_schema(type_ident: &ast::TypeIdent) -> (bool, Value) {
+    match type_ident {
+        ast::TypeIdent::Option(inner) | ast::TypeIdent::Nullable(inner) => {
+            (false, type_schema(inner))
+        }
+        ty => (true, type_schema(ty)),
+    }
+}
+
+/// Generate the schema of a struct.
+fn struct_schema(sdef: &ast::StructDef) -> Value {
+    with_deprecation(
+        with_description(
+            fields_schema(&sdef.fields, sdef.rename_all),
+            &sdef.doc_comment,
+        ),
+        &sdef.deprecated,
+    )
+}
+
+/// Generate the schema of an object with the given fields. Optional fields and fields with a
+/// default value may be omitted, nullable fields have to be present but may be `null`.
+fn fields_schema(fields: &ast::StructFields, rename_all: Option<ast::RenameRule>) -> Value {
+    let mut properties = Map::new();
+    let mut required = Vec::new();
+    for field in fields.iter() {
+        let wire_name = field.wire_name(rename_all);
+        if field.default.is_none() && !matches!(field.pair.type_ident, ast::TypeIdent::Option(_)) {
+            required.push(json!(wire_name));
+        }
+        let mut schema = match field.bytes_encoding {
+            ast::BytesEncoding::Base64 => type_schema(&field.pair.type_ident),
+            ast::BytesEncoding::Hex => {
+                json!({ "type": "string", "pattern": "^([0-9a-fA-F]{2})*$" })
+            }
+        };
+        // optional integers are nullable integer schemas, so the bounds apply to both
+        if let Some(range) = field.pair.range {
+            if let Some(min) = range.min {
+                schema["minimum"] = json!(min);
+            }
+            if let Some(max) = range.max {
+                schema["maximum"] = json!(max);
+            }
+        }
+        if let Some(ref default) = field.default {
+            schema["default"] = default_value(default);
+        }
+        let schema = with_description(schema, &field.doc_comment);
+        properties.insert(wire_name, with_deprecation(schema, &field.deprecated));
+    }
+
+    let mut schema = json!({
+        "type": "object",
+        "properties": properties,
+    });
+    // OpenAPI 3.0 requires `required` to be non-empty if present
+    if !required.is_empty() {
+        schema["required"] = Value::Array(required);
+    }
+    schema
+}
+
+/// Generate the schema of an enum, following its serde representation.
+fn enum_schema(edef: &ast::EnumDef) -> Value {
+    let schema = match edef.tagging() {
+        ast::EnumTagging::External => externally_tagged_enum_schema(edef),
+        ast::EnumTagging::Internal { tag } => tagged_enum_schema(edef, tag, None),
+        ast::EnumTagging::Adjacent { tag, content } => tagged_enum_schema(edef, tag, Some(content)),
+    };
+    with_deprecation(
+        with_description(schema, &edef.doc_comment),
+        &edef.deprecated,
+    )
+}
+
+/// Generate the schema of an externally tagged enum.
+fn externally_tagged_enum_schema(edef: &ast::EnumDef) -> Value {
+    let simple_variants: Vec<_> = edef
+        .simple_variants()
+        .map(|v| json!(v.wire_name()))
+        .collect();
+    let simple = json!({ "type": "string", "enum": simple_variants });
+
+    let complex: Vec<_> = edef
+        .complex_variants()
+        .map(|variant| {
+            let payload = match &variant.variant_type {
+                ast::VariantType::Simple => unreachable!("variant is complex"),
+                ast::VariantType::Tuple(tdef) => tuple_schema(tdef),
+                ast::VariantType::Struct(fields) => fields_schema(fields, None),
+                ast::VariantType::Newtype(ty) => type_schema(ty),
+            };
+            json!({
+                "type": "object",
+                "properties": {
+                    variant.name.clone(): with_description(payload, &variant.doc_comment),
+                },
+                "required": [variant.name],
+                "additionalProperties": false,
+            })
+        })
+        .collect();
+
+    if complex.is_empty() {
+        simple
+    } else if simple_variants.is_empty() {
+        json!({ "oneOf": complex })
+    } else {
+        json!({ "oneOf": std::iter::once(simple).chain(complex).collect::<Vec<_>>() })
+    }
+}
+
+/// Generate the schema of an internally (`content` is `None`) or adjacently tagged enum.
+fn tagged_enum_schema(edef: &ast::EnumDef, tag: &str, content: Option<&str>) -> Value {
+    let variants: Vec<_> = edef
+        .variants
+        .iter()
+        .map(|variant| {
+            let tag_schema = json!({ "type": "string", "enum": [variant.wire_name()] });
+            let mut schema = match (content, &variant.variant_type) {
+                // internally tagged enums only have simple and struct variants
+                (None, ast::VariantType::Struct(fields)) => fields_schema(fields, None),
+                (Some(content), ast::VariantType::Tuple(tdef)) => {
+                    json!({ "properties": { content: tuple_schema(tdef) }, "required": [content] })
+                }
+                (Some(content), ast::VariantType::Struct(fields)) => json!({
+                    "properties": { content: fields_schema(fields, None) },
+                    "required": [content],
+                }),
+                (Some(content), ast::VariantType::Newtype(ty)) => json!({
+                    "properties": { content: type_schema(ty) },
+                    "required": [content],
+                }),
+                _ => json!({ "properties": {} }),
+            };
+            schema["type"] = json!("object");
+            schema["properties"][tag] = tag_schema;
+            match schema["required"].as_array_mut() {
+                Some(required) => required.insert(0, json!(tag)),
+                None => schema["required"] = json!([tag]),
+            }
+            with_description(schema, &variant.doc_comment)
+        })
+        .collect();
+
+    json!({ "oneOf": variants })
+}
+
+/// Generate the schema of a type.
+fn type_schema(type_ident: &ast::TypeIdent) -> Value {
+    match type_ident {
+        ast::TypeIdent::BuiltIn(atom) => atom_schema(*atom),
+        ast::TypeIdent::List(inner) => json!({ "type": "array", "items": type_schema(inner) }),
+        ast::TypeIdent::Set(inner) => json!({
+            "type": "array",
+            "items": type_schema(inner),
+            "uniqueItems": true,
+        }),
+        ast::TypeIdent::Option(inner) | ast::TypeIdent::Nullable(inner) => {
+            let mut schema = type_schema(inner);
+            // siblings of a `$ref` are ignored
+            if schema.get("$ref").is_some() {
+                schema = json!({ "allOf": [schema] });
+            }
+            schema["nullable"] = json!(true);
+            schema
+        }
+        ast::TypeIdent::Result(ok, err) => json!({
+            "oneOf": [
+                {
+                    "type": "object",
+                    "properties": { "Ok": type_schema(ok) },
+                    "required": ["Ok"],
+                    "additionalProperties": false,
+                },
+                {
+                    "type": "object",
+                    "properties": { "Err": type_schema(err) },
+                    "required": ["Err"],
+                    "additionalProperties": false,
+                },
+            ],
+        }),
+        // keys are always represented as strings in JSON
+        ast::TypeIdent::Map(_, value) | ast::TypeIdent::OrderedMap(_, value) => json!({
+            "type": "object",
+            "additionalProperties": type_schema(value),
+        }),
+        ast::TypeIdent::Tuple(tdef) => tuple_schema(tdef),
+        ast::TypeIdent::UserDefined(name) => schema_ref(name),
+    }
+}
+
+/// Generate the schema of the `Page` envelope returned by paginated endpoints.
+fn page_schema(item: &ast::TypeIdent) -> Value {
+    let count = |atom| type_schema(&ast::TypeIdent::BuiltIn(atom));
+    json!({
+        "type": "object",
+        "required": ["items", "total", "limit", "offset"],
+        "properties": {
+            "items": type_schema(&ast::TypeIdent::List(Box::new(item.clone()))),
+            "total": count(ast::AtomType::U64),
+            "limit": count(ast::AtomType::U32),
+            "offset": count(ast::AtomType::U32),
+        },
+    })
+}
+
+/// Generate the schema of a tuple. OpenAPI 3.0 cannot describe the type of each element,
+/// so every element may have any of the element types.
+fn tuple_schema(tdef: &ast::TupleDef) -> Value {
+    let len = tdef.elements().len();
+    json!({
+        "type": "array",
+        "items": {
+            "oneOf": tdef.elements().iter().map(type_schema).collect::<Vec<_>>(),
+        },
+        "minItems": len,
+        "maxItems": len,
+    })
+}
+
+/// Generate the schema of a built-in type.
+fn atom_schema(atom: ast::AtomType) -> Value {
+    match atom {
+        ast::AtomType::Empty => json!({ "nullable": true, "enum": [null] }),
+        ast::AtomType::Str => json!({ "type": "string" }),
+        ast::AtomType::I32 | ast::AtomType::I16 | ast::AtomType::I8 => {
+            json!({ "type": "integer", "format": "int32" })
+        }
+        ast::AtomType::U16 | ast::AtomType::U8 => {
+            json!({ "type": "integer", "format": "int32", "minimum": 0 })
+        }
+        ast::AtomType::I64 => json!({ "type": "integer", "format": "int64" }),
+        ast::AtomType::U32 | ast::AtomType::U64 => {
+            json!({ "type": "integer", "format": "int64", "minimum": 0 })
+        }
+        ast::AtomType::Duration => json!({
+            "type": "integer",
+            "format": "int64",
+            "minimum": 0,
+            "description": "duration in milliseconds",
+        }),
+        ast::AtomType::U128 => json!({ "type": "string", "pattern": "^[0-9]+$" }),
+        ast::AtomType::I128 => json!({ "type": "string", "pattern": "^-?[0-9]+$" }),
+        ast::AtomType::F32 => json!({ "type": "number", "format": "float" }),
+        ast::AtomType::F64 => json!({ "type": "number", "format": "double" }),
+        ast::AtomType::Bool => json!({ "type": "boolean" }),
+        ast::AtomType::DateTime => json!({ "type": "string", "format": "date-time" }),
+        ast::AtomType::Date => json!({ "type": "string", "format": "date" }),
+        ast::AtomType::Uuid => json!({ "type": "string", "format": "uuid" }),
+        ast::AtomType::Url => json!({ "type": "string", "format": "uri" }),
+        ast::AtomType::IpAddr => json!({
+            "anyOf": [
+                { "type": "string", "format": "ipv4" },
+                { "type": "string", "format": "ipv6" },
+            ],
+        }),
+        ast::AtomType::Bytes => json!({ "type": "string", "format": "byte" }),
+        ast::AtomType::Decimal => json!({ "type": "string", "format": "decimal" }),
+        // any value
+        ast::AtomType::Json => json!({}),
+    }
+}
+
+/// The schema of error responses, see `service_protocol.md`.
+fn error_response_schema() -> Value {
+    json!({
+        "type": "object",
+        "properties": {
+            "code": { "type": "integer", "format": "int32" },
+            "kind": {
+                "description": "Either `{\"Service\": ...}` or `{\"Runtime\": ...}`.",
+                "type": "object",
+            },
+        },
+        "required": ["code", "kind"],
+    })
+}
+
+fn schema_ref(name: &str) -> Value {
+    json!({ "$ref": format!("#/components/schemas/{}", name) })
+}
+
+/// The JSON representation of a default value.
+fn default_value(default: &ast::DefaultValue) -> Value {
+    match default {
+        ast::DefaultValue::Bool(b) => json!(b),
+        ast::DefaultValue::Int(i) => json!(i),
+        ast::DefaultValue::Float(f) => json!(f),
+        ast::DefaultValue::Str(s) => json!(s),
+    }
+}
+
+/// Add the doc comment, if any, as description to `object`.
+fn with_description(mut object: Value, doc_comment: &Option<String>) -> Value {
+    if let Some(doc_comment) = doc_comment {
+        // siblings of a `$ref` are ignored
+        if object.get("$ref").is_some() {
+            object = json!({ "allOf": [object] });
+        }
+        object["description"] = json!(doc_comment);
+    }
+    object
+}
+
+/// Mark `object` as deprecated if `deprecated` holds a note, which is added to the description.
+fn with_deprecation(mut object: Value, deprecated: &Option<String>) -> Value {
+    if let Some(note) = deprecated {
+        // siblings of a `$ref` are ignored
+        if object.get("$ref").is_some() {
+            object = json!({ "allOf": [object] });
+        }
+        let description = match object["description"].as_str() {
+            Some(description) => format!("{}\n\nDeprecated: {}", description, note),
+            None => format!("Deprecated: {}", note),
+        };
+        object["description"] = json!(description);
+        object["deprecated"] = json!(true);
+    }
+    object
+}
+
+/// OpenAPI 3.0 backend. Writes a single JSON document to the output path.
+#[derive(Default)]
+pub struct Generator {}
+
+impl crate::CodeGenerator for Generator {
+    fn generate(&self, spec: &ast::Spec, output: &Path) -> Result<(), LibError> {
+        let title = spec.title.clone().unwrap_or_else(|| {
+            output
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+                .unwrap_or_default()
+        });
+        let document = generate_document(spec, &title);
+
+        let mut outfile = File::create(output)?;
+        serde_json::to_writer_pretty(&mut outfile, &document)
+            .map_err(|e| LibError::IoError(e.into()))?;
+        outfile.write_all(b"\n")?;
+        Ok(())
+    }
+}
@@ -1,17 +1,26 @@
 //! Rust code generator.
 
+mod axum_router;
+mod derives;
+mod field_encodings;
+mod paths;
+mod pretty_print;
+mod query_types;
+mod round_trip_tests;
 pub(crate) mod rustfmt;
+mod service_client;
 mod service_server;
+mod unknown_fields;
 
-use crate::{ast, Artifact, LibError, Spec};
+use crate::{ast, backend, Artifact, LibError, Spec};
 use anyhow::Result;
+use inflector::Inflector;
 use proc_macro2::TokenStream;
 use quote::quote;
+use std::collections::HashSet;
 use std::path::Path;
 use std::{fs::File, io::Write};
 
-const BACKEND_NAME: &str = "rust";
-
 /// Helper function to format an ident.
 ///
 /// Turns a string into an ident, eases the use inside `quote!`.
@@ -24,34 +33,387 @@ fn fmt_opt_string(s: &Option<String>) -> &str {
     s.as_ref().map(|s| s.as_str()).unwrap_or("")
 }
 
-/// Generate rust code for a struct definition.
-pub(crate) fn generate_struct_def(sdef: &ast::StructDef) -> TokenStream {
+/// Names of the user defined types of `spec` that are deprecated.
+fn deprecated_type_names(spec: &ast::Spec) -> HashSet<&str> {
+    spec.iter()
+        .filter_map(|spec_item| match spec_item {
+            ast::SpecItem::StructDef(sdef) if sdef.deprecated.is_some() => Some(sdef.name.as_str()),
+            ast::SpecItem::EnumDef(edef) if edef.deprecated.is_some() => Some(edef.name.as_str()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Whether `type_ident` refers to one of the `deprecated` user defined types.
+fn refers_to_deprecated(type_ident: &ast::TypeIdent, deprecated: &HashSet<&str>) -> bool {
+    match type_ident {
+        ast::TypeIdent::BuiltIn(_) => false,
+        ast::TypeIdent::List(inner)
+        | ast::TypeIdent::Set(inner)
+        | ast::TypeIdent::Option(inner)
+        | ast::TypeIdent::Nullable(inner) => refers_to_deprecated(inner, deprecated),
+        ast::TypeIdent::Result(a, b)
+        | ast::TypeIdent::Map(a, b)
+        | ast::TypeIdent::OrderedMap(a, b) => {
+            refers_to_deprecated(a, deprecated) || refers_to_deprecated(b, deprecated)
+        }
+        ast::TypeIdent::Tuple(tdef) => tdef
+            .elements()
+            .iter()
+            .any(|element| refers_to_deprecated(element, deprecated)),
+        ast::TypeIdent::UserDefined(name) => deprecated.contains(name.as_str()),
+    }
+}
+
+/// Whether the definition of `fields` uses deprecated fields or types.
+fn fields_use_deprecated(fields: &ast::StructFields, deprecated: &HashSet<&str>) -> bool {
+    fields.iter().any(|field| {
+        field.deprecated.is_some()
+            || refers_to_deprecated(&field.pair.type_ident, deprecated)
+            || field
+                .embedded_from
+                .as_ref()
+                .is_some_and(|embed| deprecated.contains(embed.as_str()))
+    })
+}
+
+/// Generate a `#[deprecated]` attribute carrying the note of a deprecated item.
+fn generate_deprecated(deprecated: &Option<String>) -> TokenStream {
+    match deprecated {
+        Some(note) => quote!(#[deprecated(note = #note)]),
+        None => quote!(),
+    }
+}
+
+/// Generate `#[allow(deprecated)]` for generated items that use deprecated items, so that
+/// only code written by users of the deprecated items is warned about.
+fn generate_allow_deprecated(uses_deprecated: bool) -> TokenStream {
+    if uses_deprecated {
+        quote!(#[allow(deprecated)])
+    } else {
+        quote!()
+    }
+}
+
+/// Generate rust code for a struct definition. `deprecated_types` are the names of the
+/// deprecated types of the spec.
+pub(crate) fn generate_struct_def(
+    sdef: &ast::StructDef,
+    deprecated_types: &HashSet<&str>,
+    deny_unknown_fields: bool,
+) -> TokenStream {
     let ident = fmt_ident(&sdef.name);
+    let deprecated = generate_deprecated(&sdef.deprecated);
+    let allow_deprecated = generate_allow_deprecated(
+        sdef.deprecated.is_some() || fields_use_deprecated(&sdef.fields, deprecated_types),
+    );
     let doc_comment = fmt_opt_string(&sdef.doc_comment);
-    let fields: Vec<_> = sdef.fields.iter().map(generate_pub_field_node).collect();
+    let fields: Vec<_> = if sdef.flatten_embeds {
+        generate_pub_fields_flattening_embeds(&sdef.name, &sdef.fields)
+    } else {
+        sdef.fields
+            .iter()
+            .map(|field| generate_pub_field_node(&sdef.name, field))
+            .collect()
+    };
+    let field_fns = generate_field_fns(
+        &ident,
+        sdef.fields
+            .iter()
+            // fields inlined from a flattened embed are deserialized by the embedded struct
+            .filter(|field| !sdef.flatten_embeds || field.embedded_from.is_none())
+            .map(|field| (None, field)),
+        &allow_deprecated,
+    );
+    let validate_fn = generate_struct_validate_fn(sdef, &allow_deprecated);
+    let rename_all: Vec<_> = sdef
+        .rename_all
+        .iter()
+        .map(|rule| {
+            let rule = rule.as_str();
+            quote!(#[serde(rename_all = #rule)])
+        })
+        .collect();
+    let derives = generate_extra_derives(&sdef.derives);
+    let deny_unknown_fields = if deny_unknown_fields {
+        quote!(#[serde(deny_unknown_fields)])
+    } else {
+        quote!()
+    };
 
     quote!(
-        #[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+        #[derive(Debug, Clone, serde::Deserialize, serde::Serialize #(, #derives)*)]
         #[doc = #doc_comment]
+        #deprecated
+        #allow_deprecated
+        #(#rename_all)*
+        #deny_unknown_fields
         pub struct #ident {
             #(#fields),*
         }
+
+        #field_fns
+
+        #validate_fn
+    )
+}
+
+/// Generate a `validate` method for a struct with range constraints, which checks that the
+/// values of its fields are within their ranges. Values are checked when deserialized, the method
+/// is meant for values constructed in code.
+fn generate_struct_validate_fn(
+    sdef: &ast::StructDef,
+    allow_deprecated: &TokenStream,
+) -> TokenStream {
+    let mut embeds = HashSet::new();
+    let checks: Vec<_> = sdef
+        .fields
+        .iter()
+        .filter(|field| field.pair.range.is_some())
+        .filter_map(|field| match field.embedded_from {
+            // flattened embeds are validated by the embedded struct
+            Some(ref embedded_from) if sdef.flatten_embeds => {
+                if embeds.insert(embedded_from) {
+                    let embed = fmt_ident(&embedded_from.to_snake_case());
+                    Some(quote!(self.#embed.validate()?;))
+                } else {
+                    None
+                }
+            }
+            _ => {
+                let ident = fmt_ident(&field.pair.name);
+                Some(generate_range_check(&field.pair, quote!(self.#ident)))
+            }
+        })
+        .collect();
+    if checks.is_empty() {
+        return quote!();
+    }
+
+    let ident = fmt_ident(&sdef.name);
+    quote!(
+        #allow_deprecated
+        impl #ident {
+            /// Check that all fields are within the ranges declared in the spec.
+            pub fn validate(&self) -> Result<(), ::humblegen_rt::serialization_helpers::OutOfRange> {
+                #(#checks)*
+                Ok(())
+            }
+        }
+    )
+}
+
+/// Generate a `validate` method for an enum with range constraints on fields of its struct
+/// variants, see `generate_struct_validate_fn`.
+fn generate_enum_validate_fn(edef: &ast::EnumDef, allow_deprecated: &TokenStream) -> TokenStream {
+    let ident = fmt_ident(&edef.name);
+    let mut unchecked_variants = false;
+    let arms: Vec<_> = edef
+        .variants
+        .iter()
+        .filter_map(|variant| {
+            let fields: Vec<_> = match variant.variant_type {
+                ast::VariantType::Struct(ref fields) => fields
+                    .iter()
+                    .filter(|field| field.pair.range.is_some())
+                    .collect(),
+                _ => vec![],
+            };
+            if fields.is_empty() {
+                unchecked_variants = true;
+                return None;
+            }
+            let variant_ident = fmt_ident(&variant.name);
+            let field_idents: Vec<_> = fields.iter().map(|f| fmt_ident(&f.pair.name)).collect();
+            let checks: Vec<_> = fields
+                .iter()
+                .zip(&field_idents)
+                .map(|(field, ident)| generate_range_check(&field.pair, quote!(#ident)))
+                .collect();
+            Some(quote!(#ident::#variant_ident { #(#field_idents,)* .. } => { #(#checks)* }))
+        })
+        .collect();
+    if arms.is_empty() {
+        return quote!();
+    }
+    let rest = if unchecked_variants {
+        quote!(_ => {})
+    } else {
+        quote!()
+    };
+
+    quote!(
+        #allow_deprecated
+        impl #ident {
+            /// Check that all fields are within the ranges declared in the spec.
+            pub fn validate(&self) -> Result<(), ::humblegen_rt::serialization_helpers::OutOfRange> {
+                match *self {
+                    #(#arms)*
+                    #rest
+                }
+                Ok(())
+            }
+        }
     )
 }
 
-/// Generate rust code for an enum definition.
-pub(crate) fn generate_enum_def(edef: &ast::EnumDef) -> TokenStream {
+/// Generate a statement returning an `OutOfRange` error if `value`, the value of the field
+/// `pair`, is not within its range.
+fn generate_range_check(pair: &ast::FieldDefPair, value: TokenStream) -> TokenStream {
+    let range = pair.range.expect("field has a range");
+    let name = &pair.name;
+    let (min, max) = (generate_bound(range.min), generate_bound(range.max));
+    let check = quote!(::humblegen_rt::serialization_helpers::check_range);
+    match pair.type_ident {
+        ast::TypeIdent::Option(_) | ast::TypeIdent::Nullable(_) => quote!(
+            if let Some(value) = #value {
+                #check(#name, value, #min, #max)?;
+            }
+        ),
+        _ => quote!(#check(#name, #value, #min, #max)?;),
+    }
+}
+
+/// Generate rust code for a bound of a range constraint, an `Option<i64>`.
+fn generate_bound(bound: Option<i64>) -> TokenStream {
+    match bound {
+        Some(bound) => {
+            let literal = proc_macro2::Literal::i64_unsuffixed(bound);
+            quote!(Some(#literal))
+        }
+        None => quote!(None),
+    }
+}
+
+/// Generate rust code for an enum definition. `deprecated_types` are the names of the
+/// deprecated types of the spec.
+pub(crate) fn generate_enum_def(
+    edef: &ast::EnumDef,
+    deprecated_types: &HashSet<&str>,
+) -> TokenStream {
     let ident = fmt_ident(&edef.name);
     let doc_comment = fmt_opt_string(&edef.doc_comment);
+    let deprecated = generate_deprecated(&edef.deprecated);
+    let allow_deprecated = generate_allow_deprecated(
+        edef.deprecated.is_some()
+            || edef
+                .variants
+                .iter()
+                .any(|variant| match variant.variant_type {
+                    ast::VariantType::Simple => false,
+                    ast::VariantType::Tuple(ref tdef) => tdef
+                        .elements()
+                        .iter()
+                        .any(|element| refers_to_deprecated(element, deprecated_types)),
+                    ast::VariantType::Struct(ref fields) => {
+                        fields_use_deprecated(fields, deprecated_types)
+                    }
+                    ast::VariantType::Newtype(ref ty) => refers_to_deprecated(ty, deprecated_types),
+                }),
+    );
 
-    let variants: Vec<_> = edef.variants.iter().map(generate_variant).collect();
+    let variants: Vec<_> = edef
+        .variants
+        .iter()
+        .map(|variant| generate_variant(&edef.name, variant))
+        .collect();
+    let field_fns = generate_field_fns(
+        &ident,
+        edef.variants.iter().flat_map(|variant| {
+            let fields = match variant.variant_type {
+                ast::VariantType::Struct(ref fields) => fields.0.as_slice(),
+                _ => &[],
+            };
+            fields.iter().map(move |field| (Some(variant), field))
+        }),
+        &allow_deprecated,
+    );
+    let validate_fn = generate_enum_validate_fn(edef, &allow_deprecated);
+    let non_exhaustive = if edef.non_exhaustive {
+        quote!(#[non_exhaustive])
+    } else {
+        quote!()
+    };
+    let derives = generate_extra_derives(&edef.derives);
+    let tagging = match edef.tagging() {
+        ast::EnumTagging::External => quote!(),
+        ast::EnumTagging::Internal { tag } => quote!(#[serde(tag = #tag)]),
+        ast::EnumTagging::Adjacent { tag, content } => {
+            quote!(#[serde(tag = #tag, content = #content)])
+        }
+    };
+    let string_conversions = if edef.complex_variants().count() == 0 {
+        generate_simple_enum_string_conversions(edef, &allow_deprecated)
+    } else {
+        quote!()
+    };
 
     quote!(
-        #[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+        #[derive(Debug, Clone, serde::Deserialize, serde::Serialize #(, #derives)*)]
         #[doc = #doc_comment]
+        #deprecated
+        #allow_deprecated
+        #tagging
+        #non_exhaustive
         pub enum #ident {
             #(#variants),*
-    })
+        }
+
+        #field_fns
+
+        #validate_fn
+
+        #string_conversions
+    )
+}
+
+/// Generate `Display` and `FromStr` impls for an enum with simple variants only.
+///
+/// Variants are displayed as, and parsed from, their serialized representation.
+fn generate_simple_enum_string_conversions(
+    edef: &ast::EnumDef,
+    allow_deprecated: &TokenStream,
+) -> TokenStream {
+    let ident = fmt_ident(&edef.name);
+    let enum_name = &edef.name;
+    let (variant_idents, variant_names): (Vec<_>, Vec<_>) = edef
+        .simple_variants()
+        .map(|variant| (fmt_ident(&variant.name), variant.wire_name()))
+        .unzip();
+
+    quote!(
+        #allow_deprecated
+        impl ::std::fmt::Display for #ident {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                f.write_str(match *self {
+                    #(#ident::#variant_idents => #variant_names),*
+                })
+            }
+        }
+
+        #allow_deprecated
+        impl ::std::str::FromStr for #ident {
+            type Err = ::humblegen_rt::serialization_helpers::ParseEnumError;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                match s {
+                    #(#variant_names => Ok(#ident::#variant_idents),)*
+                    _ => Err(::humblegen_rt::serialization_helpers::ParseEnumError {
+                        enum_name: #enum_name,
+                        value: s.to_owned(),
+                    }),
+                }
+            }
+        }
+    )
+}
+
+/// Generate the idents of traits derived in addition to the default ones.
+fn generate_extra_derives(derives: &[ast::Derive]) -> Vec<proc_macro2::Ident> {
+    derives
+        .iter()
+        .map(|derive| fmt_ident(derive.as_str()))
+        .collect()
 }
 
 /// Generate rust code for a field node.
@@ -61,13 +423,13 @@ fn generate_field_def_pair(pair: &ast::FieldDefPair) -> TokenStream {
     quote!(#ident: #ty)
 }
 
-/// Generate rust code for a public field node.
+/// Generate rust code for a public field node of the struct `owner`.
 ///
 /// Even though all fields are pub in generated code, fields in a `pub enum` cannot carry an
 /// additional `pub` qualifier.
-fn generate_pub_field_node(field: &ast::FieldNode) -> TokenStream {
+fn generate_pub_field_node(owner: &str, field: &ast::FieldNode) -> TokenStream {
     let doc_comment = fmt_opt_string(&field.doc_comment);
-    let attributes = generate_field_attributes(&field.pair.type_ident);
+    let attributes = generate_field_node_attributes(owner, None, field);
     let field = generate_field_def_pair(&field.pair);
     quote! {
         #[doc = #doc_comment]
@@ -76,13 +438,47 @@ fn generate_pub_field_node(field: &ast::FieldNode) -> TokenStream {
     }
 }
 
-/// Generate rust code for an enum variant.
-fn generate_variant(variant: &ast::VariantDef) -> TokenStream {
+/// Generate rust code for the public fields of a struct annotated with `@flatten_embeds`.
+///
+/// The fields inlined from an embedded struct are replaced by a single `#[serde(flatten)]`
+/// field of the embedded struct's type, named after it, at the position of the embed.
+fn generate_pub_fields_flattening_embeds(
+    owner: &str,
+    fields: &ast::StructFields,
+) -> Vec<TokenStream> {
+    let mut flattened = HashSet::new();
+    fields
+        .iter()
+        .filter_map(|field| match field.embedded_from {
+            None => Some(generate_pub_field_node(owner, field)),
+            Some(ref embedded_from) if flattened.insert(embedded_from) => {
+                let ident = fmt_ident(&embedded_from.to_snake_case());
+                let ty = fmt_ident(embedded_from);
+                Some(quote! {
+                    #[serde(flatten)]
+                    pub #ident: #ty
+                })
+            }
+            Some(_) => None,
+        })
+        .collect()
+}
+
+/// Generate rust code for a variant of the enum `owner`.
+fn generate_variant(owner: &str, variant: &ast::VariantDef) -> TokenStream {
     let doc_comment = fmt_opt_string(&variant.doc_comment);
     let ident = fmt_ident(&variant.name);
 
     match variant.variant_type {
-        ast::VariantType::Simple => quote!(#[doc = #doc_comment] #ident),
+        ast::VariantType::Simple => match variant.value {
+            Some(ref value) => quote!(#[doc = #doc_comment] #[serde(rename = #value)] #ident),
+            None => quote!(#[doc = #doc_comment] #ident),
+        },
+        // `Variant(T,)` would be a newtype variant, serialized as its element instead of an array
+        ast::VariantType::Tuple(ref inner) if inner.elements().len() == 1 => {
+            let tuple = generate_tuple_def(inner);
+            quote!(#[doc = #doc_comment] #ident(#tuple))
+        }
         ast::VariantType::Tuple(ref inner) => {
             let tuple = generate_tuple_def(inner);
             quote!(#[doc = #doc_comment] #ident #tuple)
@@ -92,8 +488,9 @@ fn generate_variant(variant: &ast::VariantDef) -> TokenStream {
                 .iter()
                 .map(|field| {
                     let doc_comment = fmt_opt_string(&field.doc_comment);
+                    let attributes = generate_field_node_attributes(owner, Some(variant), field);
                     let fld = generate_field_def_pair(&field.pair);
-                    quote!(#[doc = #doc_comment] #fld)
+                    quote!(#[doc = #doc_comment] #(#[#attributes])* #fld)
                 })
                 .collect();
 
@@ -115,7 +512,12 @@ fn generate_type_ident(type_ident: &ast::TypeIdent) -> TokenStream {
             let inner_ty = generate_type_ident(inner);
             quote!(Vec<#inner_ty>)
         }
-        ast::TypeIdent::Option(inner) => {
+        // ordered, so that sets are serialized deterministically
+        ast::TypeIdent::Set(inner) => {
+            let inner_ty = generate_type_ident(inner);
+            quote!(::std::collections::BTreeSet<#inner_ty>)
+        }
+        ast::TypeIdent::Option(inner) | ast::TypeIdent::Nullable(inner) => {
             let inner_ty = generate_type_ident(inner);
             quote!(Option<#inner_ty>)
         }
@@ -129,6 +531,11 @@ fn generate_type_ident(type_ident: &ast::TypeIdent) -> TokenStream {
             let value_ty = generate_type_ident(value);
             quote!(::std::collections::HashMap<#key_ty, #value_ty>)
         }
+        ast::TypeIdent::OrderedMap(key, value) => {
+            let key_ty = generate_type_ident(key);
+            let value_ty = generate_type_ident(value);
+            quote!(::std::collections::BTreeMap<#key_ty, #value_ty>)
+        }
         ast::TypeIdent::Tuple(tdef) => generate_tuple_def(tdef),
         ast::TypeIdent::UserDefined(ident) => {
             let id = fmt_ident(&ident);
@@ -141,8 +548,139 @@ fn generate_type_ident(type_ident: &ast::TypeIdent) -> TokenStream {
 /// Without the surrounding `#[` and `]`
 type FieldAttributes = Vec<TokenStream>;
 
-/// Render the list of field attributes for the given type_ident
-fn generate_field_attributes(type_ident: &ast::TypeIdent) -> FieldAttributes {
+/// Render the list of field attributes for the given field node of the struct or enum `owner`
+/// (and its variant `variant`), including those required by its type.
+fn generate_field_node_attributes(
+    owner: &str,
+    variant: Option<&ast::VariantDef>,
+    field: &ast::FieldNode,
+) -> FieldAttributes {
+    let mut attributes = generate_field_attributes(&field.pair.type_ident, field.bytes_encoding);
+    if let Some(ref rename) = field.rename {
+        attributes.push(quote! { serde(rename = #rename) });
+    }
+    if let Some(ref note) = field.deprecated {
+        attributes.push(quote! { deprecated(note = #note) });
+    }
+    match field.default {
+        Some(ref default) if default.is_zero() => attributes.push(quote! { serde(default) }),
+        Some(_) => {
+            let default_fn = format!("{}::{}", owner, field_fn_name("default", variant, field));
+            attributes.push(quote! { serde(default = #default_fn) });
+        }
+        None => {}
+    }
+    if field.pair.range.is_some() {
+        let deserialize_fn = format!(
+            "{}::{}",
+            owner,
+            field_fn_name("deserialize", variant, field)
+        );
+        attributes.push(quote! { serde(deserialize_with = #deserialize_fn) });
+        // `deserialize_with` makes serde require the field even if it is optional
+        if let ast::TypeIdent::Option(_) = field.pair.type_ident {
+            attributes.push(quote! { serde(default) });
+        }
+    } else if let ast::TypeIdent::Nullable(ref inner) = field.pair.type_ident {
+        // serde only requires `Option` fields that are deserialized using a function, which
+        // the custom encodings of `u128`, `i128` and `duration` already are
+        match **inner {
+            ast::TypeIdent::BuiltIn(ast::AtomType::U128)
+            | ast::TypeIdent::BuiltIn(ast::AtomType::I128)
+            | ast::TypeIdent::BuiltIn(ast::AtomType::Duration) => {}
+            _ => attributes.push(quote! {
+                serde(deserialize_with = "::humblegen_rt::serialization_helpers::deser_nullable")
+            }),
+        }
+    }
+    attributes
+}
+
+/// Name of an associated function for `field` starting with `prefix`, the field belongs to a
+/// struct or, if `variant` is given, to an enum variant.
+fn field_fn_name(
+    prefix: &str,
+    variant: Option<&ast::VariantDef>,
+    field: &ast::FieldNode,
+) -> String {
+    match variant {
+        Some(variant) => format!(
+            "{}_{}_{}",
+            prefix,
+            variant.name.to_snake_case(),
+            field.pair.name
+        ),
+        None => format!("{}_{}", prefix, field.pair.name),
+    }
+}
+
+/// Generate an impl block for `ident` with the associated functions used to deserialize
+/// `fields`, given along with the variant they belong to, if any: a `default_*` function
+/// returning the default value unless it is the zero value of the field's type, for which
+/// `Default::default` is used, and a `deserialize_*` function checking range constraints.
+fn generate_field_fns<'a>(
+    ident: &proc_macro2::Ident,
+    fields: impl Iterator<Item = (Option<&'a ast::VariantDef>, &'a ast::FieldNode)>,
+    allow_deprecated: &TokenStream,
+) -> TokenStream {
+    let mut field_fns = Vec::new();
+    for (variant, field) in fields {
+        let ty = generate_type_ident(&field.pair.type_ident);
+        match field.default {
+            Some(ref default) if !default.is_zero() => {
+                let name = fmt_ident(&field_fn_name("default", variant, field));
+                let value = generate_default_value(default);
+                field_fns.push(quote!(fn #name() -> #ty { #value }));
+            }
+            _ => {}
+        }
+        if let Some(range) = field.pair.range {
+            let name = fmt_ident(&field_fn_name("deserialize", variant, field));
+            let field_name = &field.pair.name;
+            let (min, max) = (generate_bound(range.min), generate_bound(range.max));
+            let helper = match field.pair.type_ident {
+                ast::TypeIdent::Option(_) | ast::TypeIdent::Nullable(_) => {
+                    quote!(deser_optional_in_range)
+                }
+                _ => quote!(deser_in_range),
+            };
+            field_fns.push(quote!(
+                fn #name<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<#ty, D::Error> {
+                    ::humblegen_rt::serialization_helpers::#helper(deserializer, #field_name, #min, #max)
+                }
+            ));
+        }
+    }
+
+    if field_fns.is_empty() {
+        quote!()
+    } else {
+        quote!(#allow_deprecated impl #ident { #(#field_fns)* })
+    }
+}
+
+/// Generate rust code for a default value.
+fn generate_default_value(default: &ast::DefaultValue) -> TokenStream {
+    match default {
+        ast::DefaultValue::Bool(b) => quote!(#b),
+        ast::DefaultValue::Int(i) => {
+            let literal = proc_macro2::Literal::i64_unsuffixed(*i);
+            quote!(#literal)
+        }
+        ast::DefaultValue::Float(f) => {
+            let literal = proc_macro2::Literal::f64_unsuffixed(*f);
+            quote!(#literal)
+        }
+        ast::DefaultValue::Str(s) => quote!(#s.to_owned()),
+    }
+}
+
+/// Render the list of field attributes for the given type_ident, `bytes` fields are encoded
+/// using `bytes_encoding`.
+fn generate_field_attributes(
+    type_ident: &ast::TypeIdent,
+    bytes_encoding: ast::BytesEncoding,
+) -> FieldAttributes {
     match type_ident {
         ast::TypeIdent::BuiltIn(atom) => match atom {
             ast::AtomType::Empty => vec![],
@@ -150,20 +688,69 @@ fn generate_field_attributes(type_ident: &ast::TypeIdent) -> FieldAttributes {
             ast::AtomType::I32 => vec![],
             ast::AtomType::U32 => vec![],
             ast::AtomType::U8 => vec![],
+            ast::AtomType::I64 => vec![],
+            ast::AtomType::U64 => vec![],
+            ast::AtomType::U128 | ast::AtomType::I128 => {
+                vec![quote! { serde(with = "::humblegen_rt::serialization_helpers::int_string") }]
+            }
+            ast::AtomType::I16 => vec![],
+            ast::AtomType::U16 => vec![],
+            ast::AtomType::I8 => vec![],
+            ast::AtomType::F32 => vec![],
             ast::AtomType::F64 => vec![],
             ast::AtomType::Bool => vec![],
             ast::AtomType::DateTime => vec![],
             ast::AtomType::Date => vec![],
             ast::AtomType::Uuid => vec![],
-            ast::AtomType::Bytes => vec![
-                quote! { serde(deserialize_with = "::humblegen_rt::serialization_helpers::deser_bytes") },
-                quote! { serde(serialize_with = "::humblegen_rt::serialization_helpers::ser_bytes") },
-            ],
+            ast::AtomType::Url => vec![],
+            ast::AtomType::IpAddr => vec![],
+            ast::AtomType::Duration => {
+                vec![
+                    quote! { serde(with = "::humblegen_rt::serialization_helpers::duration_millis") },
+                ]
+            }
+            ast::AtomType::Bytes => match bytes_encoding {
+                ast::BytesEncoding::Base64 => vec![
+                    quote! { serde(deserialize_with = "::humblegen_rt::serialization_helpers::deser_bytes") },
+                    quote! { serde(serialize_with = "::humblegen_rt::serialization_helpers::ser_bytes") },
+                ],
+                ast::BytesEncoding::Hex => vec![
+                    quote! { serde(deserialize_with = "::humblegen_rt::serialization_helpers::deser_bytes_hex") },
+                    quote! { serde(serialize_with = "::humblegen_rt::serialization_helpers::ser_bytes_hex") },
+                ],
+            },
+            ast::AtomType::Decimal => vec![],
+            ast::AtomType::Json => vec![],
         },
         ast::TypeIdent::List(_) => vec![],
-        ast::TypeIdent::Option(_) => vec![],
+        ast::TypeIdent::Set(_) => vec![],
+        ast::TypeIdent::Option(inner) => match **inner {
+            ast::TypeIdent::BuiltIn(ast::AtomType::U128)
+            | ast::TypeIdent::BuiltIn(ast::AtomType::I128) => vec![
+                quote! { serde(with = "::humblegen_rt::serialization_helpers::optional_int_string") },
+                // `with` makes serde require the field even if it is optional
+                quote! { serde(default) },
+            ],
+            ast::TypeIdent::BuiltIn(ast::AtomType::Duration) => vec![
+                quote! { serde(with = "::humblegen_rt::serialization_helpers::optional_duration_millis") },
+                quote! { serde(default) },
+            ],
+            _ => vec![],
+        },
+        // like for `option[...]`, but without `default`, since the key is required
+        ast::TypeIdent::Nullable(inner) => match **inner {
+            ast::TypeIdent::BuiltIn(ast::AtomType::U128)
+            | ast::TypeIdent::BuiltIn(ast::AtomType::I128) => vec![
+                quote! { serde(with = "::humblegen_rt::serialization_helpers::optional_int_string") },
+            ],
+            ast::TypeIdent::BuiltIn(ast::AtomType::Duration) => vec![
+                quote! { serde(with = "::humblegen_rt::serialization_helpers::optional_duration_millis") },
+            ],
+            _ => vec![],
+        },
         ast::TypeIdent::Result(_, _) => vec![],
         ast::TypeIdent::Map(_, _) => vec![],
+        ast::TypeIdent::OrderedMap(_, _) => vec![],
         ast::TypeIdent::Tuple(_) => vec![],
         ast::TypeIdent::UserDefined(_) => vec![],
     }
@@ -188,6 +775,14 @@ fn generate_atom(atom: &ast::AtomType) -> TokenStream {
         ast::AtomType::I32 => quote!(i32),
         ast::AtomType::U32 => quote!(u32),
         ast::AtomType::U8 => quote!(u8),
+        ast::AtomType::I64 => quote!(i64),
+        ast::AtomType::U64 => quote!(u64),
+        ast::AtomType::U128 => quote!(u128),
+        ast::AtomType::I128 => quote!(i128),
+        ast::AtomType::I16 => quote!(i16),
+        ast::AtomType::U16 => quote!(u16),
+        ast::AtomType::I8 => quote!(i8),
+        ast::AtomType::F32 => quote!(f32),
         ast::AtomType::F64 => quote!(f64),
         ast::AtomType::Bool => quote!(bool),
         ast::AtomType::DateTime => {
@@ -197,58 +792,304 @@ fn generate_atom(atom: &ast::AtomType) -> TokenStream {
         // https://github.com/chronotope/chrono/issues/182#issuecomment-332382103
         ast::AtomType::Date => quote!(::humblegen_rt::chrono::NaiveDate),
         ast::AtomType::Uuid => quote! {::humblegen_rt::uuid::Uuid},
+        ast::AtomType::Url => quote! {::humblegen_rt::url::Url},
+        ast::AtomType::IpAddr => quote! {::std::net::IpAddr},
+        ast::AtomType::Duration => quote! {::std::time::Duration},
         ast::AtomType::Bytes => quote!(Vec<u8>),
+        ast::AtomType::Decimal => quote!(::humblegen_rt::rust_decimal::Decimal),
+        ast::AtomType::Json => quote!(::humblegen_rt::serde_json::Value),
+    }
+}
+
+/// Render the rust definition of a user defined type as generated, formatted using rustfmt if it
+/// is available. Used for code samples in the documentation.
+pub(crate) fn render_type_def(item: &ast::SpecItem) -> String {
+    let code = match item {
+        ast::SpecItem::StructDef(sdef) => generate_struct_def(sdef, &HashSet::new(), false),
+        ast::SpecItem::EnumDef(edef) => generate_enum_def(edef, &HashSet::new()),
+        ast::SpecItem::ServiceDef(_) => quote! {},
+    }
+    .to_string();
+    rustfmt::format_generated(&code, true)
+}
+
+/// Generate rust code for the user defined types of a spec.
+fn render_types(spec: &ast::Spec, deny_unknown_fields: DenyUnknownFields) -> TokenStream {
+    let deprecated_types = deprecated_type_names(spec);
+    let denying_unknown_fields =
+        unknown_fields::structs_denying_unknown_fields(spec, deny_unknown_fields);
+    let page_type = if spec.has_paginated_endpoints() {
+        generate_page_type()
+    } else {
+        quote! {}
+    };
+    let api_version = match spec.version {
+        Some(ref version) => quote! {
+            /// The version of the API, set using `@version` in the spec.
+            pub const API_VERSION: &str = #version;
+        },
+        None => quote! {},
+    };
+    api_version
+        .into_iter()
+        .chain(spec.iter().flat_map(|spec_item| match spec_item {
+            ast::SpecItem::StructDef(sdef) => generate_struct_def(
+                sdef,
+                &deprecated_types,
+                denying_unknown_fields.contains(sdef.name.as_str()),
+            ),
+            ast::SpecItem::EnumDef(edef) => generate_enum_def(edef, &deprecated_types),
+            ast::SpecItem::ServiceDef(_) => quote! {},
+        }))
+        .chain(page_type)
+        .collect()
+}
+
+/// Generate the envelope returned by `@paginated` endpoints, generated once per spec.
+fn generate_page_type() -> TokenStream {
+    quote! {
+        /// A page of the items returned by a paginated endpoint.
+        #[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+        pub struct Page<T> {
+            /// The items on this page.
+            pub items: Vec<T>,
+            /// The number of items on all pages.
+            pub total: u64,
+            /// The maximum number of items on a page.
+            pub limit: u32,
+            /// The number of items on preceding pages.
+            pub offset: u32,
+        }
     }
 }
 
 /// Generate rust code for a spec definition.
-pub fn render_spec(spec: &ast::Spec) -> TokenStream {
-    let mut out = TokenStream::new();
+pub fn render_spec(
+    spec: &ast::Spec,
+    deny_unknown_fields: DenyUnknownFields,
+    server_runtime: ServerRuntime,
+) -> TokenStream {
+    let mut out = render_types(spec, deny_unknown_fields);
 
-    out.extend(spec.iter().flat_map(|spec_item| match spec_item {
-        ast::SpecItem::StructDef(sdef) => generate_struct_def(sdef),
-        ast::SpecItem::EnumDef(edef) => generate_enum_def(edef),
-        ast::SpecItem::ServiceDef(_) => quote! {}, // done below
-    }));
+    out.extend(service_server::generate_services(spec, server_runtime));
 
-    out.extend(service_server::generate_services(
-        spec.iter().filter_map(|si| si.service_def()),
-    ));
+    out
+}
+
+/// Generate rust code for a spec definition, with clients instead of servers for its services.
+pub fn render_client_spec(spec: &ast::Spec, deny_unknown_fields: DenyUnknownFields) -> TokenStream {
+    let mut out = render_types(spec, deny_unknown_fields);
+
+    out.extend(service_client::generate_clients(spec));
 
     out
 }
 
+/// Which structs reject JSON objects with unknown fields when deserialized, using
+/// `#[serde(deny_unknown_fields)]`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DenyUnknownFields {
+    /// No struct does, unknown fields are ignored. The default.
+    #[default]
+    Never,
+    /// Structs used as request body of a `POST`, `PUT` or `PATCH` endpoint and the structs
+    /// nested in them.
+    RequestBodies,
+    /// All structs.
+    All,
+}
+
+/// How the generated server answers HTTP requests.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ServerRuntime {
+    /// The built-in server of `humblegen_rt`, configured using the generated `Builder`. The
+    /// default.
+    #[default]
+    Hyper,
+    /// An `axum::Router` built by `Handler::into_router`, which requires the `axum-router`
+    /// feature of `humblegen_rt`. `WS` endpoints, `PROXY` routes and route params matched by a
+    /// regular expression are not supported.
+    Axum,
+}
+
 pub struct Generator {
-    _artifact: Artifact,
+    artifact: Artifact,
+    default_enum_tagging: ast::EnumTagging,
+    deny_unknown_fields: DenyUnknownFields,
+    server_runtime: ServerRuntime,
+    use_rustfmt: bool,
+    preamble: Option<String>,
+    suffix: Option<String>,
+    round_trip_tests: bool,
 }
 
 impl Generator {
     pub fn new(artifact: Artifact) -> Result<Self, LibError> {
-        match artifact {
-            Artifact::TypesOnly | Artifact::ServerEndpoints => Ok(Self {
-                _artifact: artifact,
-            }),
-            Artifact::ClientEndpoints => Err(LibError::UnsupportedArtifact {
-                artifact,
-                backend: BACKEND_NAME,
-            }),
+        Ok(Self {
+            artifact,
+            default_enum_tagging: ast::EnumTagging::default(),
+            deny_unknown_fields: DenyUnknownFields::default(),
+            server_runtime: ServerRuntime::default(),
+            use_rustfmt: true,
+            preamble: None,
+            suffix: None,
+            round_trip_tests: false,
+        })
+    }
+
+    /// Represent enums without a `@tag(...)` annotation using `tagging` instead of external
+    /// tagging.
+    pub fn with_default_enum_tagging(mut self, tagging: ast::EnumTagging) -> Self {
+        self.default_enum_tagging = tagging;
+        self
+    }
+
+    /// Reject unknown fields when deserializing the selected structs, e.g. to catch typos in
+    /// the requests of clients instead of silently ignoring them.
+    pub fn with_deny_unknown_fields(mut self, deny_unknown_fields: DenyUnknownFields) -> Self {
+        self.deny_unknown_fields = deny_unknown_fields;
+        self
+    }
+
+    /// Generate a server for `server_runtime` instead of the built-in server, e.g. to mount the
+    /// services in an existing axum application.
+    pub fn with_server_runtime(mut self, server_runtime: ServerRuntime) -> Self {
+        self.server_runtime = server_runtime;
+        self
+    }
+
+    /// Format the generated code using the built-in pretty-printer instead of rustfmt, e.g. to
+    /// get the same output regardless of the installed rustfmt version. The pretty-printer is
+    /// also used if rustfmt is not available.
+    pub fn with_rustfmt(mut self, use_rustfmt: bool) -> Self {
+        self.use_rustfmt = use_rustfmt;
+        self
+    }
+
+    /// Insert `preamble` verbatim at the top of the generated code, e.g. inner attributes like
+    /// `#![allow(clippy::all)]` or additional `use` declarations. If the code is split into
+    /// several files, it is inserted into each of them.
+    pub fn with_preamble(mut self, preamble: impl Into<String>) -> Self {
+        self.preamble = Some(preamble.into());
+        self
+    }
+
+    /// Append `suffix` verbatim to the generated code, like `with_preamble`.
+    pub fn with_suffix(mut self, suffix: impl Into<String>) -> Self {
+        self.suffix = Some(suffix.into());
+        self
+    }
+
+    /// Append a `#[cfg(test)]` module to the generated types with a test per struct and enum,
+    /// checking that an example value is unchanged after serializing it to JSON and back.
+    /// `PartialEq` is derived for all types to compare the values. Disabled by default.
+    pub fn with_round_trip_tests(mut self, round_trip_tests: bool) -> Self {
+        self.round_trip_tests = round_trip_tests;
+        self
+    }
+}
+
+impl Generator {
+    /// Split the generated code into `types.rs`, `server.rs` or `client.rs` (depending on the
+    /// artifact) and a `mod.rs` re-exporting both, all placed in the directory `output`.
+    fn generate_dir(&self, spec: &Spec, output: &Path) -> Result<(), LibError> {
+        let (services_module, services) = match self.artifact {
+            Artifact::TypesOnly | Artifact::ServerEndpoints => (
+                fmt_ident("server"),
+                service_server::generate_services(spec, self.server_runtime),
+            ),
+            Artifact::ClientEndpoints => {
+                (fmt_ident("client"), service_client::generate_clients(spec))
+            }
+        };
+
+        let mut types = render_types(spec, self.deny_unknown_fields);
+        if self.round_trip_tests {
+            types.extend(round_trip_tests::generate_round_trip_tests(spec));
+        }
+        self.write_formatted(&output.join("types.rs"), types)?;
+        self.write_formatted(
+            &output.join(format!("{}.rs", services_module)),
+            quote! {
+                #[allow(unused_imports)]
+                use super::types::*;
+                #services
+            },
+        )?;
+        self.write_formatted(
+            &output.join("mod.rs"),
+            quote! {
+                pub mod types;
+                pub mod #services_module;
+                pub use types::*;
+                pub use #services_module::*;
+            },
+        )
+    }
+
+    /// Format `code` using rustfmt, if enabled and available, or the built-in pretty-printer and
+    /// write it to `output`, surrounded by the preamble and suffix. An `output` of `-` denotes
+    /// stdout.
+    fn write_formatted(&self, output: &Path, code: TokenStream) -> Result<(), LibError> {
+        let mut code = rustfmt::format_generated(&code.to_string(), self.use_rustfmt);
+        if let Some(preamble) = &self.preamble {
+            code = format!("{}\n{}", preamble.trim_end(), code);
+        }
+        if let Some(suffix) = &self.suffix {
+            code = format!("{}\n{}\n", code, suffix.trim_end());
         }
+
+        if output == Path::new("-") {
+            std::io::stdout().write_all(code.as_bytes())?;
+        } else {
+            File::create(output)?.write_all(code.as_bytes())?;
+        }
+        Ok(())
+    }
+}
+
+/// Check that the preamble or suffix of the generated code consists of valid rust tokens.
+fn check_injected_code(position: &'static str, code: &Option<String>) -> Result<(), LibError> {
+    match code.as_deref().map(str::parse::<TokenStream>) {
+        Some(Err(e)) => Err(LibError::InvalidInjectedCode {
+            position,
+            reason: e.to_string(),
+        }),
+        _ => Ok(()),
     }
 }
 
 impl crate::CodeGenerator for Generator {
+    /// Writes all code to the file `output`, or to stdout if `output` is `-`.
+    /// If `output` is an existing directory, the code is split into several files instead.
     fn generate(&self, spec: &Spec, output: &Path) -> Result<(), LibError> {
-        // TODO: honor artifact field
-        let generated_code_unformatted = render_spec(spec).to_string();
-        let generated_code = rustfmt::rustfmt_2018_generated_string(&generated_code_unformatted)
-            .map(std::borrow::Cow::into_owned)
-            .unwrap_or(generated_code_unformatted);
-
-        // TODO: support folder as output path
-        let mut outfile = File::create(&output).map_err(LibError::IoError)?;
-        outfile
-            .write_all(generated_code.as_bytes())
-            .map_err(LibError::IoError)?;
-        Ok(())
+        // TODO: honor artifact field for `TypesOnly`
+        let mut spec = backend::with_default_enum_tagging(spec, &self.default_enum_tagging)?;
+        if self.round_trip_tests {
+            round_trip_tests::derive_partial_eq(&mut spec);
+        }
+        let spec = &spec;
+        derives::check_derives(spec)?;
+        field_encodings::check_field_encodings(spec)?;
+        query_types::check_query_types(spec)?;
+        check_injected_code("preamble", &self.preamble)?;
+        check_injected_code("suffix", &self.suffix)?;
+        if self.server_runtime == ServerRuntime::Axum && self.artifact != Artifact::ClientEndpoints
+        {
+            axum_router::check_axum_routes(spec)?;
+        }
+        if output.is_dir() {
+            return self.generate_dir(spec, output);
+        }
+        let mut generated_code = match self.artifact {
+            Artifact::TypesOnly | Artifact::ServerEndpoints => {
+                render_spec(spec, self.deny_unknown_fields, self.server_runtime)
+            }
+            Artifact::ClientEndpoints => render_client_spec(spec, self.deny_unknown_fields),
+        };
+        if self.round_trip_tests {
+            generated_code.extend(round_trip_tests::generate_round_trip_tests(spec));
+        }
+        self.write_formatted(output, generated_code)
     }
 }
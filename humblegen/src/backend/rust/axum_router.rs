@@ -0,0 +1,190 @@
+//! Code generation of an [`axum`](https://docs.rs/axum/0.7) router for humblespec `service`s,
+//! the alternative to the built-in server selected by `ServerRuntime::Axum`.
+//!
+//! For each service `$ServiceName`, `generate_router_fn` generates a `fn router_$ServiceName`
+//! that adds one axum handler per endpoint to an `axum::Router`. The handlers convert the axum
+//! request and dispatch it to the handler trait like the routes of the built-in server do, thus
+//! implementations of the handler traits work with both runtimes.
+//!
+//! Route params become axum path params (`/:id`), `rest` params a wildcard (`/*path`). Routes
+//! the axum router cannot express are rejected by `check_axum_routes` before generating code.
+
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+
+use super::service_server::{self, Service, ServiceRouteComponent};
+use crate::{ast, LibError};
+
+/// Check that all endpoints of the services of `spec` can be served by an axum router.
+pub(crate) fn check_axum_routes(spec: &ast::Spec) -> Result<(), LibError> {
+    for service in spec.iter().filter_map(|spec_item| spec_item.service_def()) {
+        let base = service.base_segments();
+        let unsupported = |location: String, reason| LibError::AxumUnsupportedRoute {
+            location: format!("{} of `{}`", location, service.qualified_name(".")),
+            reason,
+        };
+
+        if let Some(proxy) = service.proxies.first() {
+            return Err(unsupported(
+                format!("route `PROXY /{}/*`", proxy.prefix.join("/")),
+                "`PROXY` routes are only served by the built-in server",
+            ));
+        }
+
+        // paths of the `GET` and `SSE` endpoints, along with whether they are `SSE` endpoints
+        let mut get_routes: Vec<(Vec<String>, bool)> = Vec::new();
+        for endpoint in &service.endpoints {
+            let route = &endpoint.route;
+            let components = route.components();
+            let location = || {
+                let path = components
+                    .iter()
+                    .map(|c| match c {
+                        ast::ServiceRouteComponent::Literal(literal) => format!("/{}", literal),
+                        ast::ServiceRouteComponent::Variable(pair, _) => {
+                            format!("/{{{}}}", pair.name)
+                        }
+                    })
+                    .collect::<String>();
+                format!("endpoint `{} {}`", route.http_method_as_str(), path)
+            };
+
+            match route {
+                ast::ServiceRoute::Ws { .. } => {
+                    return Err(unsupported(
+                        location(),
+                        "`WS` endpoints are only served by the built-in server",
+                    ));
+                }
+                ast::ServiceRoute::Custom { .. } => {
+                    return Err(unsupported(
+                        location(),
+                        "custom methods are only served by the built-in server",
+                    ));
+                }
+                _ => {}
+            }
+            for (i, component) in components.iter().enumerate() {
+                match component {
+                    ast::ServiceRouteComponent::Variable(_, ast::RouteParamPattern::Regex(_)) => {
+                        return Err(unsupported(
+                            location(),
+                            "route params cannot be matched by a regular expression",
+                        ));
+                    }
+                    ast::ServiceRouteComponent::Variable(_, ast::RouteParamPattern::Rest)
+                        if i + 1 != components.len() =>
+                    {
+                        return Err(unsupported(
+                            location(),
+                            "`rest` params have to be the last segment of the route",
+                        ));
+                    }
+                    _ => {}
+                }
+            }
+
+            // axum routes `SSE` endpoints by their `GET` method, just like `GET` endpoints
+            let path: Vec<String> = base
+                .iter()
+                .map(|segment| (*segment).to_owned())
+                .chain(components.iter().map(|c| match c {
+                    ast::ServiceRouteComponent::Literal(literal) => literal.clone(),
+                    ast::ServiceRouteComponent::Variable(..) => String::new(),
+                }))
+                .collect();
+            let is_sse = match route {
+                ast::ServiceRoute::Get { .. } => false,
+                ast::ServiceRoute::Sse { .. } => true,
+                _ => continue,
+            };
+            if get_routes
+                .iter()
+                .any(|(other_path, other_is_sse)| *other_path == path && *other_is_sse != is_sse)
+            {
+                return Err(unsupported(
+                    location(),
+                    "`SSE` and `GET` endpoints cannot share their path",
+                ));
+            }
+            get_routes.push((path, is_sse));
+        }
+    }
+    Ok(())
+}
+
+/// The name of the router function of the service with handler trait `trait_name`.
+pub(super) fn router_fn_name(trait_name: &proc_macro2::Ident) -> proc_macro2::Ident {
+    format_ident!("router_{}", trait_name)
+}
+
+/// Generates the router function of a single service (called by `Handler::into_router`).
+pub(super) fn generate_router_fn(service: &Service) -> TokenStream {
+    let trait_name = &service.trait_name;
+    let router_fn_name = router_fn_name(trait_name);
+
+    let routes = service.service_routes.iter().map(|r| {
+        let path = r
+            .components
+            .iter()
+            .map(|c| match c {
+                ServiceRouteComponent::Literal { spec } => format!("/{}", spec),
+                ServiceRouteComponent::Param {
+                    spec_arg_name,
+                    multi_segment: true,
+                    ..
+                } => format!("/*{}", spec_arg_name),
+                ServiceRouteComponent::Param { spec_arg_name, .. } => {
+                    format!("/:{}", spec_arg_name)
+                }
+            })
+            .collect::<String>();
+        let method_fn = &r.axum_method_fn;
+        let traitfn_ident = &r.traitfn_ident;
+        // axum rejects requests to routes without params that extract them
+        let has_params = r
+            .components
+            .iter()
+            .any(|c| matches!(c, ServiceRouteComponent::Param { .. }));
+        let captures_arg = if has_params {
+            Some(quote! {
+                Path(captures): Path<HashMap<String, String>>,
+            })
+        } else {
+            None
+        };
+        let dispatch = service_server::generate_dispatch(r);
+
+        quote! {
+            let #traitfn_ident = {
+                let handler = Arc::clone(&handler);
+                let options = Arc::clone(&options);
+                move |#captures_arg req: Request| {
+                    let mut req = server::into_hyper_request(req);
+                    let request_timeout = options.request_timeout;
+                    server::into_axum_response(request_timeout, #dispatch)
+                }
+            };
+            let router = router.route(#path, routing::#method_fn(#traitfn_ident));
+        }
+    });
+
+    quote! {
+        #[allow(unused_variables)]
+        #[allow(unused_mut)]
+        #[allow(non_snake_case)]
+        #[allow(deprecated)]
+        fn #router_fn_name<Context: Default + Sized + Send + Sync + 'static>(
+            handler: Arc<dyn #trait_name<Context=Context> + Send + Sync>,
+            options: Arc<server::ServerOptions>,
+        ) -> ::humblegen_rt::axum::Router {
+            use ::humblegen_rt::axum::extract::{Path, Request};
+            use ::humblegen_rt::axum::routing;
+            use ::std::collections::HashMap;
+
+            let router = ::humblegen_rt::axum::Router::new();
+            #(#routes)*
+            router
+        }
+    }
+}
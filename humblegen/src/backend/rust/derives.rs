@@ -0,0 +1,174 @@
+//! Validation of user requested derives.
+
+use crate::{ast, LibError};
+
+/// Check that all derives requested using `@derive(...)` can be implemented for their types.
+pub(crate) fn check_derives(spec: &ast::Spec) -> Result<(), LibError> {
+    for spec_item in spec.iter() {
+        let (type_name, derives, members) = match spec_item {
+            ast::SpecItem::StructDef(sdef) => (&sdef.name, &sdef.derives, struct_members(sdef)),
+            ast::SpecItem::EnumDef(edef) => (&edef.name, &edef.derives, enum_members(edef)),
+            ast::SpecItem::ServiceDef(_) => continue,
+        };
+
+        for &derive in derives {
+            let error = |reason| LibError::UnsupportedDerive {
+                type_name: type_name.clone(),
+                derive: derive.as_str(),
+                reason,
+            };
+
+            if derive == ast::Derive::Default {
+                if let ast::SpecItem::EnumDef(_) = spec_item {
+                    return Err(error("enums have no default variant".to_owned()));
+                }
+            }
+
+            if let Some(missing) = derive.requires().iter().find(|r| !derives.contains(r)) {
+                return Err(error(format!(
+                    "requires `{}` to be derived as well",
+                    missing.as_str()
+                )));
+            }
+
+            for (member, type_ident) in &members {
+                if let Some(offender) = unsupported_type(spec, derive, type_ident) {
+                    return Err(error(format!(
+                        "{} contains `{}`, which does not implement `{}`",
+                        member,
+                        offender,
+                        derive.as_str()
+                    )));
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Describe all members of a struct along with their types.
+fn struct_members(sdef: &ast::StructDef) -> Vec<(String, &ast::TypeIdent)> {
+    sdef.fields
+        .iter()
+        .map(|field| {
+            (
+                format!("field `{}`", field.pair.name),
+                &field.pair.type_ident,
+            )
+        })
+        .collect()
+}
+
+/// Describe all members of an enum's variants along with their types.
+fn enum_members(edef: &ast::EnumDef) -> Vec<(String, &ast::TypeIdent)> {
+    let mut members = Vec::new();
+    for variant in &edef.variants {
+        match variant.variant_type {
+            ast::VariantType::Simple => {}
+            ast::VariantType::Tuple(ref tdef) => members.extend(
+                tdef.elements()
+                    .iter()
+                    .map(|ty| (format!("variant `{}`", variant.name), ty)),
+            ),
+            ast::VariantType::Newtype(ref ty) => {
+                members.push((format!("variant `{}`", variant.name), ty))
+            }
+            ast::VariantType::Struct(ref fields) => members.extend(fields.iter().map(|field| {
+                (
+                    format!("field `{}` of variant `{}`", field.pair.name, variant.name),
+                    &field.pair.type_ident,
+                )
+            })),
+        }
+    }
+    members
+}
+
+/// Find a type within `type_ident` that does not implement `derive`, returning its name.
+fn unsupported_type(
+    spec: &ast::Spec,
+    derive: ast::Derive,
+    type_ident: &ast::TypeIdent,
+) -> Option<String> {
+    if derive == ast::Derive::Default {
+        return undefaultable_type(spec, type_ident);
+    }
+
+    // floats and hash maps only support a subset of the derivable traits
+    let total = match derive {
+        ast::Derive::PartialEq | ast::Derive::PartialOrd => false,
+        ast::Derive::Eq | ast::Derive::Ord | ast::Derive::Hash | ast::Derive::Default => true,
+    };
+    let unordered = match derive {
+        ast::Derive::PartialEq | ast::Derive::Eq => false,
+        ast::Derive::PartialOrd | ast::Derive::Ord | ast::Derive::Hash | ast::Derive::Default => {
+            true
+        }
+    };
+
+    match type_ident {
+        ast::TypeIdent::BuiltIn(ast::AtomType::F32) if total => Some("f32".to_owned()),
+        ast::TypeIdent::BuiltIn(ast::AtomType::F64) if total => Some("f64".to_owned()),
+        // `serde_json::Value` is neither ordered nor hashable
+        ast::TypeIdent::BuiltIn(ast::AtomType::Json) if unordered => Some("json".to_owned()),
+        ast::TypeIdent::BuiltIn(_) => None,
+        ast::TypeIdent::List(inner)
+        | ast::TypeIdent::Set(inner)
+        | ast::TypeIdent::Option(inner)
+        | ast::TypeIdent::Nullable(inner) => unsupported_type(spec, derive, inner),
+        ast::TypeIdent::Result(ok, err) => {
+            unsupported_type(spec, derive, ok).or_else(|| unsupported_type(spec, derive, err))
+        }
+        ast::TypeIdent::Map(..) if unordered => Some("map".to_owned()),
+        ast::TypeIdent::Map(key, value) | ast::TypeIdent::OrderedMap(key, value) => {
+            unsupported_type(spec, derive, key).or_else(|| unsupported_type(spec, derive, value))
+        }
+        ast::TypeIdent::Tuple(tdef) => tdef
+            .elements()
+            .iter()
+            .find_map(|element| unsupported_type(spec, derive, element)),
+        ast::TypeIdent::UserDefined(name) => unsupported_user_defined(spec, derive, name),
+    }
+}
+
+/// Find a type within `type_ident` that has no natural default value, returning its name.
+fn undefaultable_type(spec: &ast::Spec, type_ident: &ast::TypeIdent) -> Option<String> {
+    match type_ident {
+        ast::TypeIdent::BuiltIn(ast::AtomType::DateTime) => Some("datetime".to_owned()),
+        ast::TypeIdent::BuiltIn(ast::AtomType::Date) => Some("date".to_owned()),
+        ast::TypeIdent::BuiltIn(ast::AtomType::Uuid) => Some("uuid".to_owned()),
+        ast::TypeIdent::BuiltIn(ast::AtomType::Url) => Some("url".to_owned()),
+        ast::TypeIdent::BuiltIn(ast::AtomType::IpAddr) => Some("ip".to_owned()),
+        ast::TypeIdent::BuiltIn(_) => None,
+        // empty collections, regardless of the contained type
+        ast::TypeIdent::List(_)
+        | ast::TypeIdent::Set(_)
+        | ast::TypeIdent::Option(_)
+        | ast::TypeIdent::Nullable(_)
+        | ast::TypeIdent::Map(..)
+        | ast::TypeIdent::OrderedMap(..) => None,
+        ast::TypeIdent::Result(..) => Some("result".to_owned()),
+        ast::TypeIdent::Tuple(tdef) => tdef
+            .elements()
+            .iter()
+            .find_map(|element| undefaultable_type(spec, element)),
+        ast::TypeIdent::UserDefined(name) => {
+            unsupported_user_defined(spec, ast::Derive::Default, name)
+        }
+    }
+}
+
+/// Returns the name of a user defined type if it does not derive `derive`.
+fn unsupported_user_defined(spec: &ast::Spec, derive: ast::Derive, name: &str) -> Option<String> {
+    // unknown types are reported by the compiler
+    let derives = spec.iter().find_map(|spec_item| match spec_item {
+        ast::SpecItem::StructDef(sdef) if sdef.name == name => Some(&sdef.derives),
+        ast::SpecItem::EnumDef(edef) if edef.name == name => Some(&edef.derives),
+        _ => None,
+    })?;
+    if derives.contains(&derive) {
+        None
+    } else {
+        Some(name.to_owned())
+    }
+}
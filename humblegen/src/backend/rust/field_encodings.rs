@@ -0,0 +1,164 @@
+//! Validation of the places atoms with a custom encoding are used in.
+//!
+//! `u128` and `i128` values are encoded as decimal strings and `duration`s as integer
+//! milliseconds using `#[serde(with = "...")]` attributes, which can only be attached to fields,
+//! but not to the elements of a list or the return type of an endpoint, for example. Route
+//! parameters, query fields and headers are strings, which 128-bit integers are parsed from
+//! anyway, but durations are not.
+
+use crate::{ast, LibError};
+
+/// Check that `u128` and `i128` are only used as the type of fields, route parameters, query
+/// fields and headers, and `duration` only as the type of fields, optionally wrapped in
+/// `option[...]`.
+pub(crate) fn check_field_encodings(spec: &ast::Spec) -> Result<(), LibError> {
+    for spec_item in spec.iter() {
+        match spec_item {
+            ast::SpecItem::StructDef(sdef) => {
+                for field in sdef.fields.iter() {
+                    check_field(&field.pair.type_ident, &|| {
+                        format!("field `{}` of `{}`", field.pair.name, sdef.name)
+                    })?;
+                }
+            }
+            ast::SpecItem::EnumDef(edef) => {
+                for variant in &edef.variants {
+                    let location = || format!("variant `{}` of `{}`", variant.name, edef.name);
+                    match &variant.variant_type {
+                        ast::VariantType::Simple => {}
+                        ast::VariantType::Tuple(tdef) => {
+                            for element in tdef.elements() {
+                                check_nested(element, &location)?;
+                            }
+                        }
+                        ast::VariantType::Newtype(ty) => check_nested(ty, &location)?,
+                        ast::VariantType::Struct(fields) => {
+                            for field in fields.iter() {
+                                check_field(&field.pair.type_ident, &|| {
+                                    format!("field `{}` of {}", field.pair.name, location())
+                                })?;
+                            }
+                        }
+                    }
+                }
+            }
+            ast::SpecItem::ServiceDef(service) => {
+                for endpoint in &service.endpoints {
+                    let route = &endpoint.route;
+                    let location = || {
+                        format!(
+                            "endpoint `{} {}` of `{}`",
+                            route.http_method_as_str(),
+                            route
+                                .components()
+                                .iter()
+                                .map(|c| match c {
+                                    ast::ServiceRouteComponent::Literal(literal) => {
+                                        format!("/{}", literal)
+                                    }
+                                    ast::ServiceRouteComponent::Variable(pair, _) => {
+                                        format!("/{{{}}}", pair.name)
+                                    }
+                                })
+                                .collect::<String>(),
+                            service.qualified_name(".")
+                        )
+                    };
+                    for component in route.components() {
+                        if let ast::ServiceRouteComponent::Variable(pair, _) = component {
+                            check_string(&pair.type_ident, &location)?;
+                        }
+                    }
+                    for field in route.query_fields() {
+                        check_string(&field.type_ident, &location)?;
+                    }
+                    for header in route.headers() {
+                        check_string(&header.type_ident, &location)?;
+                    }
+                    if let Some(query) = route.query() {
+                        check_nested(query, &location)?;
+                    }
+                    if let Some(body) = route.request_body() {
+                        check_nested(body, &location)?;
+                    }
+                    check_nested(route.return_type(), &location)?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Fail if `type_ident`, the type of a field, contains an atom with a custom encoding anywhere but
+/// at its top level, optionally wrapped in `option[...]`.
+fn check_field(type_ident: &ast::TypeIdent, location: &dyn Fn() -> String) -> Result<(), LibError> {
+    match top_level_atom(type_ident) {
+        Some(atom) if has_custom_encoding(atom) => Ok(()),
+        _ => check_nested(type_ident, location),
+    }
+}
+
+/// Fail if `type_ident`, the type of a string encoded value, contains an atom with a custom
+/// encoding other than a 128-bit integer at its top level, optionally wrapped in `option[...]`.
+fn check_string(
+    type_ident: &ast::TypeIdent,
+    location: &dyn Fn() -> String,
+) -> Result<(), LibError> {
+    match top_level_atom(type_ident) {
+        Some(atom) if is_wide_integer(atom) => Ok(()),
+        _ => check_nested(type_ident, location),
+    }
+}
+
+/// The atom `type_ident` consists of, optionally wrapped in `option[...]` or `nullable[...]`.
+fn top_level_atom(type_ident: &ast::TypeIdent) -> Option<ast::AtomType> {
+    match type_ident {
+        ast::TypeIdent::BuiltIn(atom) => Some(*atom),
+        ast::TypeIdent::Option(inner) | ast::TypeIdent::Nullable(inner) => match **inner {
+            ast::TypeIdent::BuiltIn(atom) => Some(atom),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn is_wide_integer(atom: ast::AtomType) -> bool {
+    matches!(atom, ast::AtomType::U128 | ast::AtomType::I128)
+}
+
+fn has_custom_encoding(atom: ast::AtomType) -> bool {
+    is_wide_integer(atom) || matches!(atom, ast::AtomType::Duration)
+}
+
+/// Fail if `type_ident` contains an atom with a custom encoding anywhere. Fields of user defined
+/// types are checked along with their definition.
+fn check_nested(
+    type_ident: &ast::TypeIdent,
+    location: &dyn Fn() -> String,
+) -> Result<(), LibError> {
+    match type_ident {
+        ast::TypeIdent::BuiltIn(atom) if is_wide_integer(*atom) => {
+            Err(LibError::UnsupportedWideInteger {
+                location: location(),
+            })
+        }
+        ast::TypeIdent::BuiltIn(ast::AtomType::Duration) => Err(LibError::UnsupportedDuration {
+            location: location(),
+        }),
+        ast::TypeIdent::BuiltIn(_) | ast::TypeIdent::UserDefined(_) => Ok(()),
+        ast::TypeIdent::List(inner)
+        | ast::TypeIdent::Set(inner)
+        | ast::TypeIdent::Option(inner)
+        | ast::TypeIdent::Nullable(inner) => check_nested(inner, location),
+        ast::TypeIdent::Result(a, b)
+        | ast::TypeIdent::Map(a, b)
+        | ast::TypeIdent::OrderedMap(a, b) => {
+            check_nested(a, location)?;
+            check_nested(b, location)
+        }
+        ast::TypeIdent::Tuple(tdef) => tdef
+            .elements()
+            .iter()
+            .try_for_each(|element| check_nested(element, location)),
+    }
+}
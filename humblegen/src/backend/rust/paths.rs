@@ -0,0 +1,125 @@
+//! Code generation of path-construction helpers for the routes of humblespec `service`s.
+//!
+//! For each service `$ServiceName`, `generate_paths` generates a `pub mod ${service_name}_paths`
+//! with one function per distinct route, e.g. `fn monsters_id_path(id: &i32) -> String` for
+//! `GET /monsters/{id: i32}`. The functions take the route params like the client methods and
+//! return the path relative to the root the service is mounted at, including its `base`.
+//! The module is generated along with both the server and the client.
+
+use inflector::Inflector;
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+
+use super::service_server::{Service, ServiceRoute, ServiceRouteComponent};
+
+/// Generates the path helpers of a single service.
+pub(super) fn generate_paths(service: &Service) -> TokenStream {
+    let module_name = format_ident!("{}_paths", service.trait_name.to_string().to_snake_case());
+    let module_comment = format!(
+        "Paths of the routes of the `{}` service, relative to the root it is mounted at.",
+        service.service_name
+    );
+
+    // endpoints with different methods may share their route
+    let mut path_fn_idents = Vec::new();
+    let path_fns: Vec<_> = service
+        .service_routes
+        .iter()
+        .filter(|r| {
+            if path_fn_idents.contains(&r.path_fn_ident) {
+                return false;
+            }
+            path_fn_idents.push(r.path_fn_ident.clone());
+            true
+        })
+        .map(generate_path_fn)
+        .collect();
+
+    quote! {
+        #[doc = #module_comment]
+        #[allow(dead_code)]
+        pub mod #module_name {
+            #[allow(unused_imports)]
+            use super::*;
+
+            #(#path_fns)*
+        }
+    }
+}
+
+/// Generates the path helper of a single route.
+fn generate_path_fn(route: &ServiceRoute) -> TokenStream {
+    let ServiceRoute {
+        path_fn_ident,
+        components,
+        ..
+    } = route;
+
+    let template = components
+        .iter()
+        .map(|c| match c {
+            ServiceRouteComponent::Literal { spec } => format!("/{}", spec),
+            ServiceRouteComponent::Param { spec_arg_name, .. } => format!("/{{{}}}", spec_arg_name),
+        })
+        .collect::<String>();
+    let doc_comment = format!("The path `{}`.", template);
+    let param_list = components.iter().filter_map(|c| match c {
+        ServiceRouteComponent::Literal { .. } => None,
+        ServiceRouteComponent::Param {
+            rust_var_ident,
+            rust_var_type,
+            ..
+        } => Some(quote! { #rust_var_ident: &#rust_var_type }),
+    });
+    let segments = generate_segments(components);
+
+    quote! {
+        #[doc = #doc_comment]
+        pub fn #path_fn_ident(#(#param_list),*) -> String {
+            ::humblegen_rt::serialization_helpers::build_path(&#segments)
+        }
+    }
+}
+
+/// Generates an expression evaluating to the (unescaped) path segments of a route, given its
+/// params as variables of the same name.
+///
+/// Shared with the `service_client` module.
+pub(super) fn generate_segments(components: &[ServiceRouteComponent]) -> TokenStream {
+    let multi_segment = components.iter().any(|c| match c {
+        ServiceRouteComponent::Literal { .. } => false,
+        ServiceRouteComponent::Param { multi_segment, .. } => *multi_segment,
+    });
+
+    // params spanning several path segments are split at `/` to keep the slashes unescaped
+    if multi_segment {
+        let pushes = components.iter().map(|c| match c {
+            ServiceRouteComponent::Literal { spec } => quote! { segments.push(#spec.to_owned()); },
+            ServiceRouteComponent::Param {
+                rust_var_ident,
+                multi_segment: true,
+                ..
+            } => quote! {
+                segments.extend(#rust_var_ident.to_string().split('/').map(str::to_owned));
+            },
+            ServiceRouteComponent::Param { rust_var_ident, .. } => {
+                quote! { segments.push(#rust_var_ident.to_string()); }
+            }
+        });
+        quote! {
+            {
+                let mut segments = Vec::new();
+                #(#pushes)*
+                segments
+            }
+        }
+    } else {
+        let segments = components.iter().map(|c| match c {
+            ServiceRouteComponent::Literal { spec } => quote! { #spec.to_owned() },
+            ServiceRouteComponent::Param { rust_var_ident, .. } => {
+                quote! { #rust_var_ident.to_string() }
+            }
+        });
+        quote! { [#(#segments),*] }
+    }
+}
@@ -0,0 +1,311 @@
+//! A simple pretty-printer for generated code, used instead of rustfmt if it is unavailable.
+//!
+//! Lines are broken after attributes, after `;` and after the `,` separating the items of a
+//! block, e.g. struct fields or match arms. Blocks are indented by four spaces per level. The
+//! result is not as tidy as the output of rustfmt, but it is readable and consists of the same
+//! tokens as the input.
+
+use proc_macro2::{Delimiter, Spacing, TokenStream, TokenTree};
+
+const INDENT: &str = "    ";
+
+/// Keywords that are separated from a following `(` or `[` by a space, unlike function names.
+const KEYWORDS: &[&str] = &[
+    "as", "async", "await", "break", "const", "continue", "dyn", "else", "enum", "for", "if",
+    "impl", "in", "let", "loop", "match", "mod", "move", "mut", "ref", "return", "static",
+    "struct", "trait", "type", "unsafe", "use", "where", "while",
+];
+
+/// Pretty-print the rust code `source`. Returns `source` unchanged if it cannot be tokenized.
+pub(crate) fn pretty_print(source: &str) -> String {
+    let tokens: TokenStream = match source.parse() {
+        Ok(tokens) => tokens,
+        Err(_) => return source.to_owned(),
+    };
+    let mut printer = Printer::default();
+    printer.print_stream(tokens, true);
+    let mut out = printer.out.trim_end().to_owned();
+    out.push('\n');
+    out
+}
+
+/// The previously printed token, which determines the whitespace in front of the next one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Prev {
+    /// Nothing on the current line yet.
+    LineStart,
+    /// An opening `(` or `[`.
+    Open,
+    /// An identifier or keyword.
+    Word(String),
+    /// A literal, a closing delimiter or the `>` closing generic arguments.
+    Value,
+    /// An operator followed by a space, e.g. `=` or `,`.
+    Op,
+    /// An operator glued to the following token, e.g. `::`, `&` or `'`.
+    Prefix,
+}
+
+#[derive(Debug)]
+struct Printer {
+    out: String,
+    depth: usize,
+    prev: Prev,
+    /// The number of unclosed `<` opening generic arguments.
+    generics: usize,
+    /// Whether the parameters of a closure are being printed.
+    closure_params: bool,
+}
+
+impl Default for Printer {
+    fn default() -> Self {
+        Self {
+            out: String::new(),
+            depth: 0,
+            prev: Prev::LineStart,
+            generics: 0,
+            closure_params: false,
+        }
+    }
+}
+
+impl Printer {
+    /// Print `tokens`. In a `block`, i.e. at the top level or in braces, `,` and `;` end lines.
+    fn print_stream(&mut self, tokens: TokenStream, block: bool) {
+        let tokens: Vec<TokenTree> = tokens.into_iter().collect();
+        let mut i = 0;
+        while i < tokens.len() {
+            i = match &tokens[i] {
+                TokenTree::Punct(punct) if punct.as_char() == '#' => {
+                    self.print_attribute_or_op(&tokens, i, block)
+                }
+                TokenTree::Punct(_) => self.print_op(&tokens, i, block),
+                TokenTree::Ident(ident) => {
+                    let word = ident.to_string();
+                    let space = !matches!(self.prev, Prev::Open | Prev::Prefix);
+                    self.write(&word, space);
+                    self.prev = Prev::Word(word);
+                    i + 1
+                }
+                TokenTree::Literal(literal) => {
+                    let space = !matches!(self.prev, Prev::Open | Prev::Prefix);
+                    self.write(&literal.to_string(), space);
+                    self.prev = Prev::Value;
+                    i + 1
+                }
+                TokenTree::Group(group) => {
+                    match group.delimiter() {
+                        Delimiter::Brace => self.print_block(group.stream(), tokens.get(i + 1)),
+                        Delimiter::Parenthesis => {
+                            self.print_group("(", group.stream(), ")", !self.is_callee())
+                        }
+                        Delimiter::Bracket => {
+                            self.print_group("[", group.stream(), "]", !self.is_callee())
+                        }
+                        Delimiter::None => self.print_stream(group.stream(), block),
+                    }
+                    i + 1
+                }
+            };
+        }
+    }
+
+    /// Write `s`, indented if it starts a line and separated from the previous token by a space
+    /// if `space` is set.
+    fn write(&mut self, s: &str, space: bool) {
+        if self.prev == Prev::LineStart {
+            for _ in 0..self.depth {
+                self.out.push_str(INDENT);
+            }
+        } else if space {
+            self.out.push(' ');
+        }
+        self.out.push_str(s);
+    }
+
+    fn newline(&mut self) {
+        if self.prev != Prev::LineStart {
+            self.out.push('\n');
+            self.prev = Prev::LineStart;
+        }
+    }
+
+    /// Whether a following `(` or `[` is a call or index, i.e. is not preceded by a space.
+    fn is_callee(&self) -> bool {
+        match &self.prev {
+            Prev::Word(word) => !KEYWORDS.contains(&word.as_str()),
+            Prev::Value | Prev::Prefix | Prev::Open => true,
+            Prev::LineStart | Prev::Op => false,
+        }
+    }
+
+    /// Whether the previous token ends an operand, so that a following `&`, `*`, `-` or `!` is
+    /// a binary operator.
+    fn follows_operand(&self) -> bool {
+        match &self.prev {
+            Prev::Word(word) => !KEYWORDS.contains(&word.as_str()),
+            Prev::Value => true,
+            Prev::LineStart | Prev::Open | Prev::Op | Prev::Prefix => false,
+        }
+    }
+
+    fn print_group(&mut self, open: &str, tokens: TokenStream, close: &str, space: bool) {
+        self.write(open, space);
+        self.prev = Prev::Open;
+        self.print_stream(tokens, false);
+        self.write(close, false);
+        self.prev = Prev::Value;
+    }
+
+    /// Print a block in braces, spanning several lines unless it is empty or part of a path,
+    /// e.g. `use std::{fmt, io};`.
+    fn print_block(&mut self, tokens: TokenStream, next: Option<&TokenTree>) {
+        let space = !matches!(self.prev, Prev::Open | Prev::Prefix);
+        if tokens.is_empty() {
+            self.write("{}", space);
+            self.prev = Prev::Value;
+        } else if self.out.ends_with("::") {
+            self.print_group("{", tokens, "}", false);
+        } else {
+            self.write("{", space);
+            self.depth += 1;
+            self.prev = Prev::Op;
+            self.newline();
+            self.print_stream(tokens, true);
+            self.newline();
+            self.depth -= 1;
+            self.write("}", false);
+            self.prev = Prev::Value;
+        }
+
+        let continues_line = match next {
+            Some(TokenTree::Punct(punct)) => ",;.?".contains(punct.as_char()),
+            Some(TokenTree::Ident(ident)) => ident == "else",
+            Some(_) => false,
+            None => true,
+        };
+        if !continues_line {
+            self.newline();
+            if self.depth == 0 {
+                // separate top level items by an empty line
+                self.out.push('\n');
+            }
+        }
+    }
+
+    /// Print the attribute starting with the `#` at `tokens[i]` on a line of its own, or the `#`
+    /// alone if it does not start an attribute. Returns the index of the next token.
+    fn print_attribute_or_op(&mut self, tokens: &[TokenTree], i: usize, block: bool) -> usize {
+        let inner = matches!(tokens.get(i + 1), Some(TokenTree::Punct(p)) if p.as_char() == '!');
+        let group_index = if inner { i + 2 } else { i + 1 };
+        let group = match tokens.get(group_index) {
+            Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Bracket => group,
+            _ => return self.print_op(tokens, i, block),
+        };
+
+        if block {
+            self.newline();
+        }
+        let space = !matches!(self.prev, Prev::Open | Prev::Prefix);
+        self.write(if inner { "#!" } else { "#" }, space);
+        self.prev = Prev::Prefix;
+        self.print_group("[", group.stream(), "]", false);
+        if block {
+            self.newline();
+        }
+        group_index + 1
+    }
+
+    /// Print the operator starting at `tokens[i]`, i.e. all punctuation joined to it, e.g. `::`.
+    /// Returns the index of the next token.
+    fn print_op(&mut self, tokens: &[TokenTree], i: usize, block: bool) -> usize {
+        let mut op = String::new();
+        let mut next = i;
+        while let Some(TokenTree::Punct(punct)) = tokens.get(next) {
+            // the `'` of a lifetime is joined to the following identifier
+            if !op.is_empty() && punct.as_char() == '\'' {
+                break;
+            }
+            op.push(punct.as_char());
+            next += 1;
+            if punct.spacing() == Spacing::Alone || punct.as_char() == '\'' {
+                break;
+            }
+        }
+
+        let (space_before, prev) = match op.as_str() {
+            "," | ";" => {
+                self.write(&op, false);
+                // the arguments of generics and the parameters of closures stay on one line
+                if block && self.generics == 0 && !self.closure_params {
+                    self.newline();
+                } else {
+                    self.prev = Prev::Op;
+                }
+                return next;
+            }
+            ":" => (false, Prev::Op),
+            "?" if self.follows_operand() => (false, Prev::Op),
+            // a leading `::`, e.g. in `impl ::std::fmt::Display`, is not glued to a keyword
+            "::" => (!self.follows_operand(), Prev::Prefix),
+            "." | ".." | "..=" => (false, Prev::Prefix),
+            "'" => (true, Prev::Prefix),
+            "!" if self.follows_operand() => (false, Prev::Prefix),
+            "&" | "&&" | "*" | "-" | "!" | "?" if !self.follows_operand() => (true, Prev::Prefix),
+            "<" if self.opens_generics(tokens, i) => {
+                self.generics += 1;
+                (self.prev == Prev::Op, Prev::Prefix)
+            }
+            _ if op.chars().all(|c| c == '>') && self.generics >= op.len() => {
+                self.generics -= op.len();
+                (false, Prev::Value)
+            }
+            "|" | "||" if self.closure_params => {
+                self.closure_params = false;
+                (false, Prev::Op)
+            }
+            "|" if !self.follows_operand() => {
+                self.closure_params = true;
+                (true, Prev::Prefix)
+            }
+            _ => (true, Prev::Op),
+        };
+        let space_before = space_before && !matches!(self.prev, Prev::Open | Prev::Prefix);
+        self.write(&op, space_before);
+        self.prev = prev;
+        next
+    }
+
+    /// Whether the `<` at `tokens[i]` opens generic arguments rather than comparing two values,
+    /// i.e. is closed by a `>` before the end of the statement.
+    fn opens_generics(&self, tokens: &[TokenTree], i: usize) -> bool {
+        let mut depth = 0;
+        let mut prev_joint = None;
+        for token in &tokens[i..] {
+            match token {
+                TokenTree::Punct(punct) => {
+                    match (prev_joint, punct.as_char()) {
+                        (_, ';') => return false,
+                        // `->`, `=>` and `>=` do not close generic arguments
+                        (Some('-'), '>') | (Some('='), '>') => {}
+                        (_, '<') => depth += 1,
+                        (_, '>') => {
+                            depth -= 1;
+                            if depth == 0 {
+                                return true;
+                            }
+                        }
+                        _ => {}
+                    }
+                    prev_joint = match punct.spacing() {
+                        Spacing::Joint => Some(punct.as_char()),
+                        Spacing::Alone => None,
+                    };
+                }
+                TokenTree::Group(group) if group.delimiter() == Delimiter::Brace => return false,
+                _ => prev_joint = None,
+            }
+        }
+        false
+    }
+}
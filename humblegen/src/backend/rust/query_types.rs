@@ -0,0 +1,33 @@
+//! Validation of the structs used as query types.
+//!
+//! Query types may embed other structs, e.g. to share `Pagination { limit, offset }` between
+//! the queries of several endpoints. The embedded fields are inlined by the parser, so the
+//! query is deserialized from the URL like any other struct. A struct annotated using
+//! `@flatten_embeds` keeps its embeds as `#[serde(flatten)]` fields instead, which serde cannot
+//! deserialize from a URL query unless all of their fields are strings.
+
+use crate::{ast, LibError};
+
+/// Check that no struct used as the query type of an endpoint is annotated using
+/// `@flatten_embeds`.
+pub(crate) fn check_query_types(spec: &ast::Spec) -> Result<(), LibError> {
+    let query_types = spec
+        .iter()
+        .filter_map(ast::SpecItem::service_def)
+        .flat_map(|service| &service.endpoints)
+        .filter_map(|endpoint| endpoint.route.query().as_ref())
+        .filter_map(ast::TypeIdent::user_defined);
+
+    for name in query_types {
+        let flattens_embeds = spec
+            .iter()
+            .filter_map(ast::SpecItem::struct_def)
+            .any(|sdef| &sdef.name == name && sdef.flatten_embeds);
+        if flattens_embeds {
+            return Err(LibError::FlattenedQueryEmbeds {
+                type_name: name.clone(),
+            });
+        }
+    }
+    Ok(())
+}
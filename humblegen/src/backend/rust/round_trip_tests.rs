@@ -0,0 +1,273 @@
+//! Generation of round-trip tests for the user defined types, enabled using
+//! `Generator::with_round_trip_tests`.
+//!
+//! A `#[cfg(test)]` module is appended to the generated types, containing a `#[test]` per struct
+//! and enum that constructs an example value, serializes it to JSON, deserializes it again and
+//! asserts that the result is equal to the example. To compare the values, `PartialEq` is
+//! derived for all types.
+//!
+//! Example values are constructed from the spec: numbers are `1` (or a bound of their range),
+//! strings are `"string"`, collections contain a single element and enums use their first
+//! variant that can be constructed. Recursive types are cut short using empty collections or
+//! `None`.
+
+use super::fmt_ident;
+use crate::ast;
+use inflector::Inflector;
+use proc_macro2::{Literal, TokenStream};
+use quote::quote;
+use std::collections::HashSet;
+
+/// Derive `PartialEq` for all structs and enums of `spec`, which the round-trip tests use to
+/// compare values.
+pub(crate) fn derive_partial_eq(spec: &mut ast::Spec) {
+    for derives in spec.iter_mut().filter_map(|item| match item {
+        ast::SpecItem::StructDef(sdef) => Some(&mut sdef.derives),
+        ast::SpecItem::EnumDef(edef) => Some(&mut edef.derives),
+        ast::SpecItem::ServiceDef(_) => None,
+    }) {
+        if !derives.contains(&ast::Derive::PartialEq) {
+            derives.push(ast::Derive::PartialEq);
+        }
+    }
+}
+
+/// Generate the `round_trip_tests` module, empty if the spec defines no types.
+pub(crate) fn generate_round_trip_tests(spec: &ast::Spec) -> TokenStream {
+    let tests: Vec<_> = spec
+        .iter()
+        .filter_map(|spec_item| {
+            let name = match spec_item {
+                ast::SpecItem::StructDef(sdef) => &sdef.name,
+                ast::SpecItem::EnumDef(edef) => &edef.name,
+                ast::SpecItem::ServiceDef(_) => return None,
+            };
+            let example = example_type(spec, name, &mut Vec::new())?;
+            let test_fn = fmt_ident(&format!("{}_round_trips", name.to_snake_case()));
+            Some(quote!(
+                #[test]
+                fn #test_fn() {
+                    assert_round_trip(#example);
+                }
+            ))
+        })
+        .collect();
+    if tests.is_empty() {
+        return quote!();
+    }
+
+    quote!(
+        #[cfg(test)]
+        #[allow(deprecated)]
+        mod round_trip_tests {
+            use super::*;
+
+            /// Assert that `value` is unchanged after serializing it to JSON and back.
+            fn assert_round_trip<T>(value: T)
+            where
+                T: serde::Serialize + serde::de::DeserializeOwned + PartialEq + ::std::fmt::Debug,
+            {
+                let json = ::humblegen_rt::serde_json::to_string(&value).expect("serialize example");
+                let decoded: T = ::humblegen_rt::serde_json::from_str(&json).expect("deserialize example");
+                assert_eq!(decoded, value, "round trip of {}", json);
+            }
+
+            #(#tests)*
+        }
+    )
+}
+
+/// Generate an expression constructing an example value of the user defined type `name`.
+/// `seen` are the user defined types currently being constructed, to detect recursion.
+fn example_type(spec: &ast::Spec, name: &str, seen: &mut Vec<String>) -> Option<TokenStream> {
+    if seen.iter().any(|seen| seen == name) {
+        return None;
+    }
+    seen.push(name.to_owned());
+    let example = spec.iter().find_map(|spec_item| match spec_item {
+        ast::SpecItem::StructDef(sdef) if sdef.name == name => {
+            Some(example_struct(spec, sdef, seen))
+        }
+        ast::SpecItem::EnumDef(edef) if edef.name == name => Some(example_enum(spec, edef, seen)),
+        _ => None,
+    });
+    seen.pop();
+    example.flatten()
+}
+
+fn example_struct(
+    spec: &ast::Spec,
+    sdef: &ast::StructDef,
+    seen: &mut Vec<String>,
+) -> Option<TokenStream> {
+    let ident = fmt_ident(&sdef.name);
+    let mut embeds = HashSet::new();
+    let mut fields = Vec::new();
+    for field in sdef.fields.iter() {
+        match field.embedded_from {
+            // flattened embeds are a single field of the embedded struct's type
+            Some(ref embedded_from) if sdef.flatten_embeds => {
+                if embeds.insert(embedded_from) {
+                    let field_ident = fmt_ident(&embedded_from.to_snake_case());
+                    let value = example_type(spec, embedded_from, seen)?;
+                    fields.push(quote!(#field_ident: #value));
+                }
+            }
+            _ => {
+                let field_ident = fmt_ident(&field.pair.name);
+                let value = example_field(spec, &field.pair, seen)?;
+                fields.push(quote!(#field_ident: #value));
+            }
+        }
+    }
+    Some(quote!(#ident { #(#fields),* }))
+}
+
+/// The first variant of `edef` that can be constructed.
+fn example_enum(
+    spec: &ast::Spec,
+    edef: &ast::EnumDef,
+    seen: &mut Vec<String>,
+) -> Option<TokenStream> {
+    let ident = fmt_ident(&edef.name);
+    edef.variants.iter().find_map(|variant| {
+        let variant_ident = fmt_ident(&variant.name);
+        match variant.variant_type {
+            ast::VariantType::Simple => Some(quote!(#ident::#variant_ident)),
+            ast::VariantType::Tuple(ref tdef) => {
+                let tuple = example_tuple(spec, tdef, seen)?;
+                // single element tuple variants wrap a tuple, see `generate_variant`
+                if tdef.elements().len() == 1 {
+                    Some(quote!(#ident::#variant_ident(#tuple)))
+                } else {
+                    Some(quote!(#ident::#variant_ident #tuple))
+                }
+            }
+            ast::VariantType::Struct(ref fields) => {
+                let fields = fields
+                    .iter()
+                    .map(|field| {
+                        let field_ident = fmt_ident(&field.pair.name);
+                        let value = example_field(spec, &field.pair, seen)?;
+                        Some(quote!(#field_ident: #value))
+                    })
+                    .collect::<Option<Vec<_>>>()?;
+                Some(quote!(#ident::#variant_ident { #(#fields),* }))
+            }
+            ast::VariantType::Newtype(ref ty) => {
+                let value = example_value(spec, ty, seen)?;
+                Some(quote!(#ident::#variant_ident(#value)))
+            }
+        }
+    })
+}
+
+/// An example value of a field, within the range of the field if it has one.
+fn example_field(
+    spec: &ast::Spec,
+    pair: &ast::FieldDefPair,
+    seen: &mut Vec<String>,
+) -> Option<TokenStream> {
+    let range = match pair.range {
+        Some(range) => range,
+        None => return example_value(spec, &pair.type_ident, seen),
+    };
+    let value = if range.contains(1) {
+        1
+    } else {
+        range.min.or(range.max).expect("range with a bound")
+    };
+    let literal = Literal::i64_unsuffixed(value);
+    match pair.type_ident {
+        ast::TypeIdent::Option(_) | ast::TypeIdent::Nullable(_) => Some(quote!(Some(#literal))),
+        _ => Some(quote!(#literal)),
+    }
+}
+
+/// An example value of `type_ident`, `None` if it cannot be constructed without recursion.
+fn example_value(
+    spec: &ast::Spec,
+    type_ident: &ast::TypeIdent,
+    seen: &mut Vec<String>,
+) -> Option<TokenStream> {
+    match type_ident {
+        ast::TypeIdent::BuiltIn(atom) => Some(example_atom(atom)),
+        ast::TypeIdent::List(inner) => Some(match example_value(spec, inner, seen) {
+            Some(value) => quote!(vec![#value]),
+            None => quote!(Vec::new()),
+        }),
+        ast::TypeIdent::Set(inner) => Some(match example_value(spec, inner, seen) {
+            Some(value) => quote!(vec![#value].into_iter().collect()),
+            None => quote!(Default::default()),
+        }),
+        ast::TypeIdent::Map(key, value) | ast::TypeIdent::OrderedMap(key, value) => Some(
+            match (
+                example_value(spec, key, seen),
+                example_value(spec, value, seen),
+            ) {
+                (Some(key), Some(value)) => quote!(vec![(#key, #value)].into_iter().collect()),
+                _ => quote!(Default::default()),
+            },
+        ),
+        // `Some(())` is serialized as `null`, which is deserialized as `None`
+        ast::TypeIdent::Option(inner) | ast::TypeIdent::Nullable(inner)
+            if matches!(**inner, ast::TypeIdent::BuiltIn(ast::AtomType::Empty)) =>
+        {
+            Some(quote!(None))
+        }
+        ast::TypeIdent::Option(inner) | ast::TypeIdent::Nullable(inner) => {
+            Some(match example_value(spec, inner, seen) {
+                Some(value) => quote!(Some(#value)),
+                None => quote!(None),
+            })
+        }
+        ast::TypeIdent::Result(ok, err) => match example_value(spec, ok, seen) {
+            Some(value) => Some(quote!(Ok(#value))),
+            None => example_value(spec, err, seen).map(|value| quote!(Err(#value))),
+        },
+        ast::TypeIdent::Tuple(tdef) => example_tuple(spec, tdef, seen),
+        ast::TypeIdent::UserDefined(name) => example_type(spec, name, seen),
+    }
+}
+
+fn example_tuple(
+    spec: &ast::Spec,
+    tdef: &ast::TupleDef,
+    seen: &mut Vec<String>,
+) -> Option<TokenStream> {
+    let elements = tdef
+        .elements()
+        .iter()
+        .map(|element| example_value(spec, element, seen))
+        .collect::<Option<Vec<_>>>()?;
+    Some(quote!((#(#elements,)*)))
+}
+
+fn example_atom(atom: &ast::AtomType) -> TokenStream {
+    let parsed = |example: &str| quote!(#example.parse().expect("valid example value"));
+    match atom {
+        ast::AtomType::Empty => quote!(()),
+        ast::AtomType::Str => quote!("string".to_owned()),
+        ast::AtomType::I32
+        | ast::AtomType::U32
+        | ast::AtomType::U8
+        | ast::AtomType::I64
+        | ast::AtomType::U64
+        | ast::AtomType::I16
+        | ast::AtomType::U16
+        | ast::AtomType::I8
+        | ast::AtomType::U128
+        | ast::AtomType::I128 => quote!(1),
+        ast::AtomType::F32 | ast::AtomType::F64 => quote!(1.5),
+        ast::AtomType::Bool => quote!(true),
+        ast::AtomType::DateTime => parsed("2020-01-01T12:30:00Z"),
+        ast::AtomType::Date => parsed("2020-01-01"),
+        ast::AtomType::Uuid => parsed("67e55044-10b1-426f-9247-bb680e5fe0c8"),
+        ast::AtomType::Url => parsed("https://example.com/"),
+        ast::AtomType::IpAddr => parsed("127.0.0.1"),
+        ast::AtomType::Duration => quote!(::std::time::Duration::from_millis(1500)),
+        ast::AtomType::Bytes => quote!(vec![1, 2, 3]),
+        ast::AtomType::Decimal => parsed("12.34"),
+        ast::AtomType::Json => quote!(::humblegen_rt::serde_json::json!({ "key": [1, "value"] })),
+    }
+}
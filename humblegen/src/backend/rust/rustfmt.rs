@@ -77,9 +77,18 @@ pub(crate) fn rustfmt_2018_generated_string<'a>(source: &'a str) -> io::Result<C
     }
 }
 
+/// Format `source` using rustfmt if `use_rustfmt` is set and rustfmt is available, using the
+/// built-in pretty-printer otherwise.
+pub(crate) fn format_generated(source: &str, use_rustfmt: bool) -> String {
+    if use_rustfmt {
+        match rustfmt_2018_generated_string(source) {
+            Ok(formatted) => return formatted.into_owned(),
+            Err(e) => log::debug!("falling back to the built-in pretty-printer: {}", e),
+        }
+    }
+    super::pretty_print::pretty_print(source)
+}
+
 pub(crate) fn try_rustfmt_2018_token_stream(ts: &TokenStream) -> String {
-    let s = format!("{}", ts);
-    rustfmt_2018_generated_string(&s)
-        .map(|f| f.into_owned())
-        .unwrap_or(s)
+    format_generated(&ts.to_string(), true)
 }
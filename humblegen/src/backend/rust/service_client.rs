@@ -0,0 +1,266 @@
+//! Code generation for a [`reqwest`](https://docs.rs/reqwest)-based client of humblespec `service`s.
+//!
+//! The entrypoint to this module is the `generate_clients` function.
+//! For each service `$ServiceName`, it generates a `pub struct ${ServiceName}Client` with one
+//! async method per endpoint. The methods are named like the functions of the handler trait
+//! generated by the `service_server` module and take the same arguments (minus the context),
+//! by reference. Clients of services defined in a namespace are placed in a module named like the
+//! namespace in snake case, like the handler traits.
+//!
+//! The lowered service representation is shared with the `service_server` module.
+
+use crate::ast;
+use inflector::Inflector;
+use itertools::Itertools;
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+
+use super::paths::{generate_paths, generate_segments};
+use super::service_server::{
+    lower_all_services, Service, ServiceRoute, ServiceRouteComponent, ServiceRouteHeader,
+    ServiceRouteQueryParam,
+};
+
+/// Entrypoint for generating clients for *all* services of a humblespec.
+pub fn generate_clients(spec: &ast::Spec) -> TokenStream {
+    let all_services = lower_all_services(spec);
+
+    if all_services.is_empty() {
+        return quote! {};
+    }
+
+    let mut out = TokenStream::new();
+
+    out.extend(quote! {
+        #[allow(unused_imports)]
+        use ::humblegen_rt::client::{
+            build_url, parse_base_url, send_raw_request, send_request, send_result_request,
+            send_stream_request,
+            ser_query_params, ser_query_primitive, ser_query_serde_urlencoded,
+        };
+        #[allow(unused_imports)]
+        pub use ::humblegen_rt::client::{ClientError, RawResponse, StreamResponse};
+        #[allow(unused_imports)]
+        use ::humblegen_rt::reqwest;
+    });
+
+    out.extend(generate_namespace(&all_services, &[]));
+
+    out
+}
+
+/// Generates the clients of the services in the namespace with names `namespace` and, as nested
+/// modules, those of its child namespaces.
+fn generate_namespace(all_services: &[Service], namespace: &[String]) -> TokenStream {
+    let mut out: TokenStream = all_services
+        .iter()
+        .filter(|s| s.namespace == namespace)
+        .flat_map(|s| {
+            let mut out = generate_client(s);
+            out.extend(generate_paths(s));
+            out
+        })
+        .collect();
+
+    let child_namespaces = all_services
+        .iter()
+        .filter(|s| s.namespace.len() > namespace.len() && s.namespace.starts_with(namespace))
+        .map(|s| &s.namespace[namespace.len()])
+        .unique();
+    for child_namespace in child_namespaces {
+        let mut child_path = namespace.to_vec();
+        child_path.push(child_namespace.clone());
+        let module_name = format_ident!("{}", child_namespace.to_snake_case());
+        let module_doc_comment = format!("Clients of namespace `{}`.", child_path.join("."));
+        let child = generate_namespace(all_services, &child_path);
+        out.extend(quote! {
+            #[doc = #module_doc_comment]
+            pub mod #module_name {
+                #[allow(unused_imports)]
+                use super::*;
+                #child
+            }
+        });
+    }
+
+    out
+}
+
+/// Generates rust code for the client of a single service.
+fn generate_client(service: &Service) -> TokenStream {
+    let client_name = format_ident!("{}Client", service.trait_name);
+    let client_comment = format!(
+        "Client for the `{}` service. Requires the `client` feature of `humblegen_rt`.",
+        service.service_name
+    );
+    let trait_comment = &service.trait_comment;
+    // WebSocket and Server-Sent Events endpoints need a dedicated client
+    let methods = service
+        .service_routes
+        .iter()
+        .filter(|r| {
+            r.response_kind != ast::ResponseKind::WebSocket
+                && r.response_kind != ast::ResponseKind::EventStream
+        })
+        .map(generate_client_method);
+
+    quote! {
+        #[doc = #client_comment]
+        #[doc = ""]
+        #[doc = #trait_comment]
+        #[derive(Debug, Clone)]
+        pub struct #client_name {
+            base_url: reqwest::Url,
+            http_client: reqwest::Client,
+        }
+
+        #[allow(deprecated)]
+        impl #client_name {
+            /// Creates a client for the service mounted at `base_url`, e.g. `http://localhost:3000/api`.
+            pub fn new(base_url: &str) -> Result<Self, ClientError> {
+                Self::with_http_client(base_url, reqwest::Client::new())
+            }
+
+            /// Creates a client that sends its requests using the given `http_client`.
+            pub fn with_http_client(base_url: &str, http_client: reqwest::Client) -> Result<Self, ClientError> {
+                Ok(Self {
+                    base_url: parse_base_url(base_url)?,
+                    http_client,
+                })
+            }
+
+            #(#methods)*
+        }
+    }
+}
+
+/// Generates the client method for a single route.
+fn generate_client_method(route: &ServiceRoute) -> TokenStream {
+    let ServiceRoute {
+        doc_comment,
+        deprecated,
+        traitfn_ident,
+        hyper_method,
+        components,
+        query_type,
+        query_ser_fn,
+        query_params,
+        post_body_type,
+        headers,
+        ret_type,
+        response_kind,
+        error_status,
+        ..
+    } = route;
+
+    let mut param_list = vec![quote! { &self }];
+    param_list.extend(post_body_type.iter().map(|t| quote! { post_body: &#t }));
+    param_list.extend(query_type.iter().map(|t| quote! { query: Option<&#t> }));
+    param_list.extend(query_params.iter().map(|q| {
+        let ServiceRouteQueryParam {
+            rust_var_ident,
+            rust_value_type,
+            required,
+            ..
+        } = q;
+        if *required {
+            quote! { #rust_var_ident: &#rust_value_type }
+        } else {
+            quote! { #rust_var_ident: Option<&#rust_value_type> }
+        }
+    }));
+    param_list.extend(components.iter().filter_map(|c| match c {
+        ServiceRouteComponent::Literal { .. } => None,
+        ServiceRouteComponent::Param {
+            rust_var_ident,
+            rust_var_type,
+            ..
+        } => Some(quote! { #rust_var_ident: &#rust_var_type }),
+    }));
+    param_list.extend(headers.iter().map(|h| {
+        let ServiceRouteHeader {
+            rust_var_ident,
+            rust_value_type,
+            required,
+            ..
+        } = h;
+        if *required {
+            quote! { #rust_var_ident: &#rust_value_type }
+        } else {
+            quote! { #rust_var_ident: Option<&#rust_value_type> }
+        }
+    }));
+
+    let segments = generate_segments(components);
+
+    let query = if query_type.is_some() {
+        quote! { query.map(#query_ser_fn).transpose()? }
+    } else if !query_params.is_empty() {
+        let params = query_params.iter().map(|q| {
+            let ServiceRouteQueryParam {
+                param_name,
+                rust_var_ident,
+                required,
+                ..
+            } = q;
+            if *required {
+                quote! { (#param_name, Some(#rust_var_ident.to_string())) }
+            } else {
+                quote! { (#param_name, #rust_var_ident.map(ToString::to_string)) }
+            }
+        });
+        quote! { ser_query_params(&[#(#params),*])? }
+    } else {
+        quote! { None }
+    };
+
+    let post_body = post_body_type
+        .as_ref()
+        .map(|_| quote! { let request = request.json(post_body); });
+
+    let header_stmts = headers.iter().map(|h| {
+        let ServiceRouteHeader {
+            header_name,
+            rust_var_ident,
+            required,
+            ..
+        } = h;
+        if *required {
+            quote! { let request = request.header(#header_name, #rust_var_ident.to_string()); }
+        } else {
+            quote! {
+                let request = match #rust_var_ident {
+                    Some(value) => request.header(#header_name, value.to_string()),
+                    None => request,
+                };
+            }
+        }
+    });
+
+    let send_call = match (response_kind, error_status) {
+        (ast::ResponseKind::Json, Some(error_status)) => {
+            let error_status = proc_macro2::Literal::u16_unsuffixed(*error_status);
+            quote! { send_result_request(#error_status, request) }
+        }
+        (ast::ResponseKind::Json, None) | (ast::ResponseKind::Paginated, _) => {
+            quote! { send_request(request) }
+        }
+        (ast::ResponseKind::Raw, _) => quote! { send_raw_request(request) },
+        (ast::ResponseKind::Stream, _) => quote! { send_stream_request(request) },
+        (ast::ResponseKind::WebSocket, _) | (ast::ResponseKind::EventStream, _) => {
+            unreachable!("clients skip WebSocket and Server-Sent Events endpoints")
+        }
+    };
+
+    quote! {
+        #doc_comment
+        #deprecated
+        pub async fn #traitfn_ident(#(#param_list),*) -> Result<#ret_type, ClientError> {
+            let url = build_url(&self.base_url, &#segments, #query);
+            let request = self.http_client.request(#hyper_method, url);
+            #(#header_stmts)*
+            #post_body
+            #send_call.await
+        }
+    }
+}
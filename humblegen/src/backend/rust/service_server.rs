@@ -13,6 +13,14 @@
 //! ```text
 //! Handler::$ServiceName(Arc::new(h))
 //! ```
+//! Services defined in a `namespace $Namespace { ... }` are generated into a `pub mod $namespace`
+//! with its own handler enum, which is wrapped in a variant of the enclosing one:
+//! ```text
+//! Handler::$Namespace($namespace::Handler::$ServiceName(Arc::new(h)))
+//! ```
+//! With `ServerRuntime::Axum`, no `Builder` is generated. Instead, `Handler::into_router` builds
+//! an `axum::Router` using the router functions generated by the `axum_router` module.
+//!
 //! See generated example code's docs for details.
 //!
 //! # Implementation Notes
@@ -28,34 +36,99 @@
 //!
 
 use crate::ast;
+use inflector::Inflector;
+use itertools::Itertools;
 use proc_macro2::TokenStream;
 use quote::{format_ident, quote};
 
 use super::fmt_opt_string;
 use super::generate_type_ident;
+use super::ServerRuntime;
 
 /// Lowered representation of an `ast::ServiceDef`.
-struct Service {
-    trait_name: proc_macro2::Ident,
-    trait_comment: String,
-    routes_factory_name: proc_macro2::Ident,
-    service_routes: Vec<ServiceRoute>,
+///
+/// Shared with the `service_client` module.
+pub(super) struct Service {
+    pub(super) trait_name: proc_macro2::Ident,
+    /// The names of the namespaces of the service, outermost first.
+    pub(super) namespace: Vec<String>,
+    /// The name of the service prefixed by its namespaces, for logs.
+    pub(super) service_name: String,
+    pub(super) trait_comment: String,
+    pub(super) routes_factory_name: proc_macro2::Ident,
+    pub(super) service_routes: Vec<ServiceRoute>,
+    pub(super) proxies: Vec<ServiceProxy>,
+}
+
+/// Lowered representation of an `ast::ProxyDef`.
+pub(super) struct ServiceProxy {
+    /// The path of the proxy relative to the root of the service, e.g. `/v1/legacy/*`.
+    pub(super) template: String,
+    pub(super) upstream: String,
 }
 
 /// Lowered representation of an `ast::ServiceRoute`.
-struct ServiceRoute {
-    doc_comment: TokenStream,
-    traitfn_ident: proc_macro2::Ident,
-    hyper_method: TokenStream,
-    components: Vec<ServiceRouteComponent>,
-    query_type: Option<TokenStream>,
-    query_deser_fn: TokenStream,
-    post_body_type: Option<TokenStream>,
-    ret_type: TokenStream,
+pub(super) struct ServiceRoute {
+    pub(super) doc_comment: TokenStream,
+    /// The `#[deprecated]` attribute of a deprecated endpoint, empty otherwise.
+    pub(super) deprecated: TokenStream,
+    pub(super) traitfn_ident: proc_macro2::Ident,
+    /// name of the generated path helper, see the `paths` module
+    pub(super) path_fn_ident: proc_macro2::Ident,
+    pub(super) hyper_method: TokenStream,
+    /// The `axum::routing` function for the method of the route, e.g. `get`.
+    pub(super) axum_method_fn: proc_macro2::Ident,
+    pub(super) components: Vec<ServiceRouteComponent>,
+    pub(super) query_type: Option<TokenStream>,
+    /// Whether the handler gets the default of `query_type` if the request has no query,
+    /// instead of an `Option`.
+    pub(super) default_query: bool,
+    pub(super) query_deser_fn: TokenStream,
+    pub(super) query_ser_fn: TokenStream,
+    pub(super) query_params: Vec<ServiceRouteQueryParam>,
+    /// The keys a query may contain, `None` if they are not known at code-gen time
+    /// (e.g. for primitive or enum query types).
+    pub(super) query_keys: Option<Vec<String>>,
+    pub(super) post_body_type: Option<TokenStream>,
+    pub(super) headers: Vec<ServiceRouteHeader>,
+    pub(super) ret_type: TokenStream,
+    /// How the response is sent, `ret_type` is `RawResponse` for `raw bytes`,
+    /// `StreamResponse<_>` for `stream[T]` and `SSE` endpoints and `WebSocketResponse<_>` for
+    /// `WS` endpoints.
+    pub(super) response_kind: ast::ResponseKind,
+    /// The status code of successful responses.
+    pub(super) status: u16,
+    /// status code of responses with an `Err`, see `ast::ServiceEndpoint::error_status`
+    pub(super) error_status: Option<u16>,
+    /// The maximum size of the request body, see `ast::ServiceEndpoint::max_body_size`. `None`
+    /// if the `max_body_size` option of the server applies.
+    pub(super) max_body_size: Option<usize>,
+}
+
+/// Lowered representation of a query field of an `ast::ServiceRoute`.
+pub(super) struct ServiceRouteQueryParam {
+    pub(super) param_name: String,
+    pub(super) rust_var_ident: proc_macro2::Ident,
+    /// The type of the handler argument, `Option<_>` if the parameter is optional.
+    pub(super) rust_var_type: TokenStream,
+    /// The type of the parameter value.
+    pub(super) rust_value_type: TokenStream,
+    pub(super) required: bool,
+}
+
+/// Lowered representation of an `ast::HeaderBinding`.
+pub(super) struct ServiceRouteHeader {
+    pub(super) header_name: String,
+    pub(super) rust_var_ident: proc_macro2::Ident,
+    /// The type of the handler argument, `Option<_>` if the header is optional.
+    pub(super) rust_var_type: TokenStream,
+    /// The type of the header value.
+    pub(super) rust_value_type: TokenStream,
+    pub(super) required: bool,
 }
 
 /// Lowered representation of an `ast::ServiceRouteComponent`.
-enum ServiceRouteComponent {
+pub(super) enum ServiceRouteComponent {
     Literal {
         spec: String,
     },
@@ -64,14 +137,14 @@ enum ServiceRouteComponent {
         rust_var_ident: proc_macro2::Ident,
         rust_var_type: TokenStream,
         url_regex_str: String,
+        /// Whether the param may contain slashes, i.e. span several path segments.
+        multi_segment: bool,
     },
 }
 
 /// Entrypoint for generate *all* services of a humblespec.
-pub fn generate_services<'a, I: Iterator<Item = &'a ast::ServiceDef>>(
-    all_services: I,
-) -> TokenStream {
-    let all_services = lower_all_services(all_services);
+pub fn generate_services(spec: &ast::Spec, runtime: ServerRuntime) -> TokenStream {
+    let all_services = lower_all_services(spec);
 
     if all_services.is_empty() {
         return quote! {};
@@ -79,126 +152,349 @@ pub fn generate_services<'a, I: Iterator<Item = &'a ast::ServiceDef>>(
 
     let mut out = TokenStream::new();
 
+    // WebSocket support is an optional feature of humblegen_rt, only refer to it if necessary
+    let has_websocket_routes = all_services
+        .iter()
+        .flat_map(|s| &s.service_routes)
+        .any(|r| r.response_kind == ast::ResponseKind::WebSocket);
+    if has_websocket_routes {
+        out.extend(quote! {
+            pub use ::humblegen_rt::handler::WebSocketResponse;
+            use ::humblegen_rt::server::{accept_websocket, websocket_handler_response_to_hyper_response};
+        });
+    }
+
     // generate imports and server builder
     out.extend(quote! {
         #[allow(unused_imports)]
         use ::humblegen_rt::deser_helpers::{
-            deser_post_data, deser_query_primitive, deser_query_serde_urlencoded, deser_param,
+            deser_header, deser_optional_header, deser_optional_query_param, deser_post_data_limited,
+            deser_query_param, deser_query_primitive, deser_query_serde_urlencoded, deser_query_strict,
+            deser_param, check_query_keys,
         };
         #[allow(unused_imports)]
         use ::humblegen_rt::service_protocol::ErrorResponse;
         #[allow(unused_imports)]
-        pub use ::humblegen_rt::handler::{self, HandlerResponse as Response, ServiceError};
+        pub use ::humblegen_rt::handler::{
+            self, HandlerResponse as Response, InterceptOutcome, RawResponse, ServiceError,
+            StreamResponse,
+        };
         #[allow(unused_imports)]
         use ::humblegen_rt::regexset_map::RegexSetMap;
         #[allow(unused_imports)]
-        use ::humblegen_rt::server::{self, handler_response_to_hyper_response, Route, Service};
+        use ::humblegen_rt::server::{
+            self, handler_response_to_hyper_response, raw_handler_response_to_hyper_response,
+            result_handler_response_to_hyper_response,
+            stream_handler_response_to_hyper_response, event_stream_handler_response_to_hyper_response,
+            Route, Service,
+        };
         #[allow(unused_imports)]
         use ::std::sync::Arc;
-        use std::net::SocketAddr;
         #[allow(unused_imports)]
         use ::humblegen_rt::{hyper, tracing};
         use ::humblegen_rt::tracing_futures::Instrument;
+    });
 
-        /// Builds an HTTP server that exposes services implemented by handler trait objects.
-        #[derive(Debug)]
-        pub struct Builder {
-            services: Vec<Service>,
-        }
+    // the axum runtime mounts the routes of a `Handler` using `Handler::into_router` instead
+    if runtime == ServerRuntime::Hyper {
+        out.extend(quote! {
+            use std::net::SocketAddr;
 
-        impl Builder {
-            pub fn new() -> Self {
-                Self { services: vec![] }
+            /// Builds an HTTP server that exposes services implemented by handler trait objects.
+            #[derive(Debug)]
+            pub struct Builder {
+                services: Vec<Service>,
+                options: server::ServerOptions,
             }
 
-            /// Mounts `handler` at URL path prefix `root`.
-            /// This means that a `handler` implementing humble service
-            /// ```
-            /// service S {
-            ///     GET /bar -> i32,
-            ///     GET /baz -> str,
-            /// }
-            /// ```
-            /// and `root="/api"` will expose
-            /// * handler method `fn bar() -> i32` at `/api/bar` and
-            /// * handler method `fn baz() -> String` at `/api/baz`
-            pub fn add<Context: Default + Sized + Send + Sync>(mut self, root: &str, handler: Handler<Context>) -> Self {
-                if !root.starts_with('/') {
-                    panic!("root must start with \"/\"")
-                } else  if root.ends_with('/') {
-                    panic!("root must not end with \"/\"")
+            impl Builder {
+                pub fn new() -> Self {
+                    Self {
+                        services: vec![],
+                        options: server::ServerOptions::default(),
+                    }
                 }
 
-                let routes: Vec<Route> = handler.into_routes();
-                let routes = RegexSetMap::new(routes).unwrap();
-                self.services.push(Service((
-                    humblegen_rt::regex::Regex::new(&format!(r"^(?P<root>{})(?P<suffix>/.*)", root))
-                        .unwrap(),
-                    routes,
-                )));
-                self
-            }
+                /// Configures the request ID header name and whether inbound request IDs are reused.
+                pub fn request_ids(mut self, request_ids: server::RequestIdConfig) -> Self {
+                    self.options.request_ids = request_ids;
+                    self
+                }
+
+                /// Sets the maximum size of request bodies in bytes (2 MiB by default).
+                /// Requests with larger bodies are rejected with status code 413.
+                pub fn max_body_size(mut self, max_body_size: usize) -> Self {
+                    self.options.max_body_size = max_body_size;
+                    self
+                }
+
+                /// Enables strict query parsing (disabled by default).
+                /// In strict mode, requests whose URL query contains keys that the endpoint does not
+                /// declare are rejected with status code 400.
+                pub fn strict_query(mut self, strict_query: bool) -> Self {
+                    self.options.strict_query = strict_query;
+                    self
+                }
+
+                /// Makes paths with a trailing slash match the route of the path without it (disabled by
+                /// default), e.g. `GET /monsters/` is then handled like `GET /monsters`.
+                pub fn ignore_trailing_slash(mut self, ignore_trailing_slash: bool) -> Self {
+                    self.options.ignore_trailing_slash = ignore_trailing_slash;
+                    self
+                }
+
+                /// Sets how long the interceptors and handler may take to produce a response (no limit by
+                /// default). Slower requests are rejected with status code 504. For streamed responses,
+                /// only the time until the handler returns the stream counts.
+                pub fn request_timeout(mut self, request_timeout: ::std::time::Duration) -> Self {
+                    self.options.request_timeout = Some(request_timeout);
+                    self
+                }
+
+                /// Sets how long the upstream server of a `PROXY` route may take to send the head of its
+                /// response (30 seconds by default). Slower requests are rejected with status code 504.
+                pub fn proxy_timeout(mut self, proxy_timeout: ::std::time::Duration) -> Self {
+                    self.options.proxy_timeout = proxy_timeout;
+                    self
+                }
+
+                /// Enables Cross-Origin Resource Sharing (disabled by default), i.e. answers
+                /// preflight requests and adds `Access-Control-Allow-*` headers to responses.
+                pub fn cors(mut self, cors: server::CorsConfig) -> Self {
+                    self.options.cors = Some(cors);
+                    self
+                }
+
+                /// Serves the health check endpoints `GET /healthz`, which always succeeds, and
+                /// `GET /readyz`, which succeeds if `readiness` resolves to `true` and fails with
+                /// status code 503 otherwise (disabled by default).
+                /// They are matched before any service.
+                pub fn with_health_checks<F, Fut>(mut self, readiness: F) -> Self
+                where
+                    F: Fn() -> Fut + Send + Sync + 'static,
+                    Fut: ::std::future::Future<Output = bool> + Send + 'static,
+                {
+                    self.options.health_checks = Some(server::HealthChecks::new(readiness));
+                    self
+                }
+
+                /// Serves the files in directory `dir` below URL path prefix `path_prefix`, e.g.
+                /// `dir/app.js` at `/static/app.js` for `path_prefix="/static"`, with a content type
+                /// guessed from the file extension. Paths of directories are answered with their
+                /// `index.html`, e.g. `dir/index.html` at `/` for `path_prefix="/"`.
+                /// Files are looked up before any service is matched, `GET` requests below
+                /// `path_prefix` for files that do not exist are answered with status code 404.
+                /// Thus, mount the services outside of `path_prefix`.
+                ///
+                /// Panics if `path_prefix` does not start with a `/`.
+                pub fn serve_static(mut self, path_prefix: &str, dir: impl Into<::std::path::PathBuf>) -> Self {
+                    self.options.static_files.push(server::StaticFiles::new(path_prefix, dir));
+                    self
+                }
+
+                /// Mounts `handler` at URL path prefix `root`.
+                /// This means that a `handler` implementing humble service
+                /// ```
+                /// service S {
+                ///     GET /bar -> i32,
+                ///     GET /baz -> str,
+                /// }
+                /// ```
+                /// and `root="/api"` will expose
+                /// * handler method `fn bar() -> i32` at `/api/bar` and
+                /// * handler method `fn baz() -> String` at `/api/baz`
+                ///
+                /// Panics if `root` is invalid, see `try_add`.
+                pub fn add<Context: Default + Sized + Send + Sync>(self, root: &str, handler: Handler<Context>) -> Self {
+                    self.try_add(root, handler).unwrap_or_else(|e| panic!("{}", e))
+                }
+
+                /// Like `add`, but returns an error instead of panicking if `root` does not start
+                /// with a `/`, ends with a `/` or is not a valid regular expression, or if routes of
+                /// `handler` match the same requests.
+                pub fn try_add<Context: Default + Sized + Send + Sync>(
+                    mut self,
+                    root: &str,
+                    handler: Handler<Context>,
+                ) -> Result<Self, server::BuilderError> {
+                    let root = server::root_regex(root)?;
+                    let routes: Vec<Route> = handler.into_routes();
+                    let routes = RegexSetMap::new(routes).map_err(server::BuilderError::InvalidRoutes)?;
+                    self.services.push(Service((root, routes)));
+                    Ok(self)
+                }
+
+                /// Turns the previously `add`ed handlers into a `tower::Service` answering requests,
+                /// to compose it with middleware or serve it using a custom `hyper::Server`.
+                pub fn into_service(self) -> humblegen_rt::anyhow::Result<server::HttpService> {
+                    use humblegen_rt::anyhow::Context;
+                    let services = RegexSetMap::new(self.services).context("invalid service configuration")?;
+                    server::check_metrics_path(&services, &self.options)?;
+                    Ok(server::HttpService::new(services, self.options))
+                }
+
+                /// Starts an HTTP server bound to address `addr` and serves incoming requests using
+                /// the previously `add`ed handlers.
+                pub async fn listen_and_run_forever(self, addr: &SocketAddr) -> humblegen_rt::anyhow::Result<()> {
+                    server::listen_and_run_forever(self.into_service()?, addr).await
+                }
 
-            /// Starts an HTTP server bound to address `addr` and serves incoming requests using
-            /// the previously `add`ed handlers.
-            pub async fn listen_and_run_forever(self, addr: &SocketAddr) -> humblegen_rt::anyhow::Result<()> {
-                use humblegen_rt::anyhow::Context;
-                let services = RegexSetMap::new(self.services).context("invalid service configuration")?;
-                server::listen_and_run_forever(services, addr).await
+                /// Like `listen_and_run_forever`, but stops accepting connections once `shutdown`
+                /// completes and returns after all in-flight requests have been answered.
+                pub async fn listen_with_shutdown(
+                    self,
+                    addr: &SocketAddr,
+                    shutdown: impl ::std::future::Future<Output = ()>,
+                ) -> humblegen_rt::anyhow::Result<()> {
+                    server::listen_with_shutdown(self.into_service()?, addr, shutdown).await
+                }
+
+                // `listen_and_run_forever_tls`, if humblegen_rt is built with the `tls` feature
+                ::humblegen_rt::__builder_tls_methods!();
+
+                ::humblegen_rt::__builder_metrics_methods!();
             }
-        }
+        });
+    }
 
-    });
+    out.extend(generate_namespace(&all_services, &[], runtime));
 
-    // generate code for the `Handler` enum
-    let handler_enum_variants: Vec<_> = all_services
+    out
+}
+
+/// Generates the `Handler` enum and the services of the namespace with names `namespace`,
+/// which is empty for the top level. Nested namespaces become modules of the same name in
+/// snake case, with their own `Handler` enum that is wrapped in a variant of the parent's one.
+fn generate_namespace(
+    all_services: &[Service],
+    namespace: &[String],
+    runtime: ServerRuntime,
+) -> TokenStream {
+    let services: Vec<&Service> = all_services
         .iter()
-        .map(|s| {
-            let Service { trait_name, .. } = s;
-            quote! {
-                #trait_name(Arc<dyn #trait_name<Context=Context> + Send + Sync>)
-            }
-        })
+        .filter(|s| s.namespace == namespace)
         .collect();
-    let handler_into_routes_match_arms: Vec<_> = all_services
+    // names of the nested namespaces along with their `Handler` variant and module names
+    let child_namespaces: Vec<(&String, proc_macro2::Ident, proc_macro2::Ident)> = all_services
         .iter()
-        .map(|s| {
-            let Service {
-                trait_name,
-                routes_factory_name,
-                ..
-            } = s;
-            quote! {
-                Handler::#trait_name(h) => #routes_factory_name(h)
-            }
+        .filter(|s| s.namespace.len() > namespace.len() && s.namespace.starts_with(namespace))
+        .map(|s| &s.namespace[namespace.len()])
+        .unique()
+        .map(|name| {
+            (
+                name,
+                format_ident!("{}", name),
+                format_ident!("{}", name.to_snake_case()),
+            )
         })
         .collect();
 
-    let handler_debug_arms: Vec<_> = all_services
-        .iter()
-        .map(|s| {
-            let Service { trait_name, .. } = s;
-            let trait_name_str = format!("{}", trait_name);
+    // generate code for the `Handler` enum
+    let mut handler_enum_variants = Vec::new();
+    let mut handler_into_routes_match_arms = Vec::new();
+    let mut handler_debug_arms = Vec::new();
+    for Service {
+        trait_name,
+        routes_factory_name,
+        ..
+    } in &services
+    {
+        let trait_name_str = format!("{}", trait_name);
+        handler_enum_variants.push(quote! {
+            #trait_name(Arc<dyn #trait_name<Context=Context> + Send + Sync>)
+        });
+        handler_into_routes_match_arms.push(match runtime {
+            ServerRuntime::Hyper => quote! { Handler::#trait_name(h) => #routes_factory_name(h) },
+            ServerRuntime::Axum => {
+                let router_fn_name = super::axum_router::router_fn_name(trait_name);
+                quote! { Handler::#trait_name(h) => Ok(#router_fn_name(h, options)) }
+            }
+        });
+        handler_debug_arms.push(quote! {
+            Handler::#trait_name(_) => write!(formatter, "{}", #trait_name_str)?
+        });
+    }
+    for (_, variant_name, module_name) in &child_namespaces {
+        let variant_name_str = format!("{}", variant_name);
+        handler_enum_variants.push(quote! {
+            #variant_name(#module_name::Handler<Context>)
+        });
+        handler_into_routes_match_arms.push(match runtime {
+            ServerRuntime::Hyper => quote! { Handler::#variant_name(h) => h.into_routes() },
+            ServerRuntime::Axum => quote! { Handler::#variant_name(h) => h.into_router(options) },
+        });
+        handler_debug_arms.push(quote! {
+            Handler::#variant_name(h) => write!(formatter, "{}.{:?}", #variant_name_str, h)?
+        });
+    }
+    let handler_usage = match runtime {
+        ServerRuntime::Hyper => "Used to pass instantiated handler trait objects to `Builder::add`",
+        ServerRuntime::Axum => {
+            "Used to turn instantiated handler trait objects into an `axum::Router` using \
+             `Handler::into_router`"
+        }
+    };
+    let handler_doc_comment = if namespace.is_empty() {
+        format!(
+            "Wrapper enum with one variant for each service defined in the humble spec.\n{}.",
+            handler_usage
+        )
+    } else {
+        format!(
+            "Wrapper enum with one variant for each service defined in namespace `{}` of the humble spec.\n\
+             {}, wrapped in the `Handler` of the enclosing namespace.",
+            namespace.join("."),
+            handler_usage
+        )
+    };
+    // the parent namespace's `Handler` calls `into_routes` (`into_router`) of nested ones
+    let into_routes_fn = match runtime {
+        ServerRuntime::Hyper => {
+            let visibility = if namespace.is_empty() {
+                quote! {}
+            } else {
+                quote! { pub(super) }
+            };
             quote! {
-                Handler::#trait_name(_) => write!(formatter, "{}", #trait_name_str)?
+                #visibility fn into_routes(self) -> Vec<Route> {
+                    match self {
+                        #(#handler_into_routes_match_arms,)*
+                    }
+                }
             }
-        })
-        .collect();
-    out.extend(quote! {
+        }
+        ServerRuntime::Axum => {
+            let visibility = if namespace.is_empty() {
+                quote! { pub }
+            } else {
+                quote! { pub(super) }
+            };
+            quote! {
+                /// Builds an `axum::Router` serving the routes of the handler. Of the `options`,
+                /// only `max_body_size`, `strict_query` and `request_timeout` apply, setting any
+                /// other one fails with `BuilderError::UnsupportedByAxum`.
+                #visibility fn into_router(
+                    self,
+                    options: Arc<server::ServerOptions>,
+                ) -> Result<::humblegen_rt::axum::Router, server::BuilderError> {
+                    server::check_axum_options(&options)?;
+                    match self {
+                        #(#handler_into_routes_match_arms,)*
+                    }
+                }
+            }
+        }
+    };
+    let mut out = quote! {
 
-        /// Wrapper enum with one variant for each service defined in the humble spec.
-        /// Used to pass instantiated handler trait objects to `Builder::add`.
+        #[doc = #handler_doc_comment]
         #[allow(dead_code)]
         pub enum Handler<Context: Default + Sized + Send + Sync + 'static> {
             #(#handler_enum_variants,)*
         }
 
         impl<Context: Default + Sized + Send + Sync + 'static> Handler<Context> {
-            fn into_routes(self) -> Vec<Route> {
-                match self {
-                    #(#handler_into_routes_match_arms,)*
-                }
-            }
+            #into_routes_fn
         }
 
         impl<Context: Default + Sized + Send + Sync + 'static> std::fmt::Debug for Handler<Context> {
@@ -210,10 +506,28 @@ pub fn generate_services<'a, I: Iterator<Item = &'a ast::ServiceDef>>(
             }
         }
 
-    });
+    };
 
     // generate code for the service definitions
-    out.extend(all_services.iter().map(generate_service).flatten());
+    for service in services {
+        out.extend(generate_service(service, runtime));
+        out.extend(super::paths::generate_paths(service));
+    }
+
+    for (child_namespace, _, module_name) in child_namespaces {
+        let mut child_path = namespace.to_vec();
+        child_path.push(child_namespace.clone());
+        let module_doc_comment = format!("Services of namespace `{}`.", child_path.join("."));
+        let child = generate_namespace(all_services, &child_path, runtime);
+        out.extend(quote! {
+            #[doc = #module_doc_comment]
+            pub mod #module_name {
+                #[allow(unused_imports)]
+                use super::*;
+                #child
+            }
+        });
+    }
 
     out
 }
@@ -221,8 +535,9 @@ pub fn generate_services<'a, I: Iterator<Item = &'a ast::ServiceDef>>(
 /// generates rust code for a single a single service, which includes:
 ///
 /// - a handler trait definition
-/// - a routes factory function (called by Handler::into_routes)
-fn generate_service(service: &Service) -> TokenStream {
+/// - a routes factory function (called by Handler::into_routes), or a router function (called
+///   by Handler::into_router) for the axum runtime
+fn generate_service(service: &Service, runtime: ServerRuntime) -> TokenStream {
     let service_routes = &service.service_routes;
     let trait_comment = &service.trait_comment;
 
@@ -233,16 +548,34 @@ fn generate_service(service: &Service) -> TokenStream {
                 traitfn_ident,
                 post_body_type,
                 query_type,
+                default_query,
+                query_params,
                 components,
+                headers,
                 ret_type,
                 doc_comment,
+                deprecated,
                 ..
             } = r;
             let mut param_list = vec![];
             param_list.push(quote! {&self});
             param_list.push(quote! {ctx: Self::Context});
             param_list.extend(post_body_type.iter().map(|t| quote! { post_body: #t }));
-            param_list.extend(query_type.iter().map(|t| quote! { query: Option<#t> }));
+            param_list.extend(query_type.iter().map(|t| {
+                if *default_query {
+                    quote! { query: #t }
+                } else {
+                    quote! { query: Option<#t> }
+                }
+            }));
+            param_list.extend(query_params.iter().map(|q| {
+                let ServiceRouteQueryParam {
+                    rust_var_ident,
+                    rust_var_type,
+                    ..
+                } = q;
+                quote! { #rust_var_ident : #rust_var_type }
+            }));
             param_list.extend(components.iter().filter_map(|c| match c {
                 ServiceRouteComponent::Literal { .. } => None,
                 ServiceRouteComponent::Param {
@@ -251,6 +584,14 @@ fn generate_service(service: &Service) -> TokenStream {
                     ..
                 } => Some(quote! { #rust_var_ident : #rust_var_type }),
             }));
+            param_list.extend(headers.iter().map(|h| {
+                let ServiceRouteHeader {
+                    rust_var_ident,
+                    rust_var_type,
+                    ..
+                } = h;
+                quote! { #rust_var_ident : #rust_var_type }
+            }));
             let param_list = quote! { #(#param_list),* };
 
             let decl_without_comment = quote! {
@@ -263,18 +604,25 @@ fn generate_service(service: &Service) -> TokenStream {
             let decl_with_comment = quote! {
                 #[doc = #decl_as_doc_comment ]
                 #doc_comment
+                #deprecated
                 #decl_without_comment
             };
             (decl_with_comment, decl_without_comment)
         })
         .unzip();
     let trait_name = &service.trait_name;
+    let service_name = &service.service_name;
     let trait_def_interceptor_fn = quote! {
         type Context: Default + Sized + Send + Sync;
         async fn intercept_handler_pre(&self,
             _req: &hyper::Request<hyper::Body>,
-        ) -> Result<Self::Context, ServiceError> {
-            Ok(Self::Context::default())
+        ) -> Result<InterceptOutcome<Self::Context>, ServiceError> {
+            Ok(InterceptOutcome::Continue(Self::Context::default()))
+        }
+        async fn intercept_handler_post(&self,
+            resp: hyper::Response<hyper::Body>,
+        ) -> hyper::Response<hyper::Body> {
+            resp
         }
     };
     let trait_def_as_doc_comment = {
@@ -290,6 +638,7 @@ fn generate_service(service: &Service) -> TokenStream {
     let trait_def = quote! {
         #[doc = #trait_comment]
         #[doc = #trait_def_as_doc_comment ]
+        #[allow(deprecated)]
         #[humblegen_rt::async_trait(Sync)]
         pub trait #trait_name {
             #trait_def_interceptor_fn
@@ -297,13 +646,27 @@ fn generate_service(service: &Service) -> TokenStream {
         }
     };
 
+    if runtime == ServerRuntime::Axum {
+        let router_fn = super::axum_router::generate_router_fn(service);
+        return quote! {
+            #trait_def
+            #router_fn
+        };
+    }
+
     let routes = service_routes.iter().map(|r| {
         let ServiceRoute {
-            traitfn_ident,
             hyper_method,
+            response_kind,
             ..
         } = r;
 
+        let route_kind = match response_kind {
+            ast::ResponseKind::WebSocket => quote! { server::RouteKind::WebSocket },
+            ast::ResponseKind::EventStream => quote! { server::RouteKind::EventStream },
+            _ => quote! { server::RouteKind::Plain },
+        };
+
         let regex_str = r
             .components
             .iter()
@@ -318,101 +681,46 @@ fn generate_service(service: &Service) -> TokenStream {
             .collect::<Vec<_>>()
             .join("");
         let regex_str = format!("^{}$", regex_str);
+        // stable name of the route for logs, e.g. `/monsters/{id}`
+        let template = r
+            .components
+            .iter()
+            .map(|c| match c {
+                ServiceRouteComponent::Literal { spec } => format!("/{}", spec),
+                ServiceRouteComponent::Param { spec_arg_name, .. } => {
+                    format!("/{{{}}}", spec_arg_name)
+                }
+            })
+            .collect::<String>();
 
-        // post body
-        let post_body_var = r.post_body_type.iter().map(|_| {
-                quote! { post_body }
-        }).collect::<Vec<_>>();
-        let post_body_def = r.post_body_type.as_ref().map(|pbt| quote!{
-            let post_body: #pbt =
-            deser_post_data(req.body_mut()).await?;
-        });
-
-        // query
-        let query_var = r.query_type.iter().map(|_| {
-                quote!{ query }
-        }).collect::<Vec<_>>();
-        let query_deser_fn = &r.query_deser_fn;
-        let query_def = r.query_type.as_ref().map(|qt| quote!{
-            let query: Option<#qt> = match req.uri().query() {
-                None => None,
-                Some(q) => Some(#query_deser_fn(q)?),
-            };
-        });
+        let dispatch = generate_dispatch(r);
 
-        // route params
-        let (route_param_vars, route_param_parse_stmts): (Vec<TokenStream>, Vec<TokenStream>) = r.components.iter().filter_map(|c| match c {
-            ServiceRouteComponent::Literal { .. } => None,
-            ServiceRouteComponent::Param {
-                spec_arg_name,
-                rust_var_ident,
-                rust_var_type,
-                ..
-            } => Some((
-                quote! { #rust_var_ident },
-                quote! { let #rust_var_ident: Result<#rust_var_type, ErrorResponse> = deser_param( #spec_arg_name,  &captures[ #spec_arg_name ]); },
-            )),
-        }).unzip();
-
-        let mut arg_list = Vec::new();
-        arg_list.extend(&post_body_var);
-        arg_list.extend(&query_var);
-        arg_list.extend(&route_param_vars);
-
-
-        let route_param_parse_stmts = route_param_parse_stmts.into_iter();
-        let route_param_vars2 = route_param_vars.iter();
-        let route_param_vars = route_param_vars.iter();
-        let arg_list = arg_list.into_iter();
         quote! {
             {
                 let handler = Arc::clone(&handler);
                 Route{
                     method: #hyper_method,
                     regex: ::humblegen_rt::regex::Regex::new(#regex_str).unwrap(),
+                    service: #service_name,
+                    template: #template,
+                    kind: #route_kind,
                     dispatcher: Box::new(
                         move |mut req: ::humblegen_rt::hyper::Request<::humblegen_rt::hyper::Body>,
-                        captures| {
-                            let handler = Arc::clone(&handler);
-                            // We cannot move the regex captures into the async closure, thus do the parsing
-                            // of route params outside of the closure and move the parsing results into it.
-                            // Inside the closure, `?` the results and return the param deserialization error.
-                            #(#route_param_parse_stmts);*
-                            Box::pin(async move {
-                                // Invoke the interceptor
-                                use ::humblegen_rt::service_protocol::ToErrorResponse;
-                                let ctx = {
-                                    let span = tracing::error_span!("interceptor");
-                                    handler.intercept_handler_pre(&req).instrument(span).await
-                                        .map_err(::humblegen_rt::service_protocol::ServiceError::from)
-                                        .map_err(|e| {
-                                            tracing::debug!(service_error = ?format!("{:?}", e), "interceptor rejected request");
-                                            e
-                                        })
-                                        .map_err(|e| e.to_error_response())?
-                                };
-
-                                // deserialize only after we have invoked the interceptor
-                                // => interceptor can implement some DoS protection
-                                #(let #route_param_vars = #route_param_vars2?;)*
-                                #query_def
-                                #post_body_def
-
-                                drop(req); // free some memory
-
-                                // Invoke handler if interceptor doesn't return a ServiceError
-                                {
-                                    let span = tracing::error_span!("handler");
-                                    Ok(handler_response_to_hyper_response(handler.#traitfn_ident( ctx, #(#arg_list),* ).instrument(span).await))
-                                }
-                            })
-                        }
+                        captures,
+                        options: Arc<server::ServerOptions>| #dispatch
                     ),
                 }
             }
         }
     });
 
+    // requests to the paths of proxies are forwarded as they are
+    let proxy_routes = service.proxies.iter().map(|p| {
+        let ServiceProxy { template, upstream } = p;
+        quote! { server::proxy_route(#service_name, #template, #upstream) }
+    });
+    let routes = routes.chain(proxy_routes);
+
     let routes_factory_name = &service.routes_factory_name;
     quote! {
         #trait_def
@@ -422,6 +730,7 @@ fn generate_service(service: &Service) -> TokenStream {
         #[allow(non_snake_case)]
         #[allow(clippy::trivial_regex)]
         #[allow(clippy::single_char_pattern)]
+        #[allow(deprecated)]
         fn #routes_factory_name<Context: Default + Sized + Send + Sync + 'static>(handler: Arc<dyn #trait_name<Context=Context> + Send + Sync>) -> Vec<Route> {
             vec![#(#routes),*]
         }
@@ -429,70 +738,431 @@ fn generate_service(service: &Service) -> TokenStream {
     }
 }
 
-/// lower the `ast::ServiceDefs` into `struct Service`
-fn lower_all_services<'a, I: Iterator<Item = &'a ast::ServiceDef>>(
-    all_services: I,
-) -> Vec<Service> {
-    all_services
+/// Generates the block dispatching a request to the handler method of `r`, evaluating to a
+/// boxed future of the response.
+///
+/// The block expects `handler`, the request `req`, the captured route params `captures` (indexed
+/// by their name) and the `options` of the server in scope. Shared with the `axum_router` module.
+pub(super) fn generate_dispatch(r: &ServiceRoute) -> TokenStream {
+    let ServiceRoute {
+        traitfn_ident,
+        response_kind,
+        status,
+        error_status,
+        ..
+    } = r;
+
+    let response_conversion_fn = match response_kind {
+        ast::ResponseKind::Json if error_status.is_some() => {
+            quote! { result_handler_response_to_hyper_response }
+        }
+        ast::ResponseKind::Json | ast::ResponseKind::Paginated => {
+            quote! { handler_response_to_hyper_response }
+        }
+        ast::ResponseKind::Raw => quote! { raw_handler_response_to_hyper_response },
+        ast::ResponseKind::Stream => quote! { stream_handler_response_to_hyper_response },
+        ast::ResponseKind::WebSocket => quote! { websocket_handler_response_to_hyper_response },
+        ast::ResponseKind::EventStream => {
+            quote! { event_stream_handler_response_to_hyper_response }
+        }
+    };
+    // the handshake is checked last, it takes the body of the request to upgrade it
+    let (websocket_upgrade_def, response_conversion_arg) = match response_kind {
+        ast::ResponseKind::WebSocket => (
+            Some(quote! { let websocket_upgrade = accept_websocket(&mut req)?; }),
+            Some(quote! { websocket_upgrade, }),
+        ),
+        ast::ResponseKind::EventStream => (None, None),
+        _ => {
+            let status = proc_macro2::Literal::u16_unsuffixed(*status);
+            let error_status = error_status.map(|error_status| {
+                let error_status = proc_macro2::Literal::u16_unsuffixed(error_status);
+                quote! { #error_status, }
+            });
+            (None, Some(quote! { #status, #error_status }))
+        }
+    };
+
+    // post body
+    let post_body_var = r
+        .post_body_type
+        .iter()
+        .map(|_| {
+            quote! { post_body }
+        })
+        .collect::<Vec<_>>();
+    let max_body_size = match r.max_body_size {
+        Some(max_body_size) => {
+            let max_body_size = proc_macro2::Literal::usize_unsuffixed(max_body_size);
+            quote! { #max_body_size }
+        }
+        None => quote! { options.max_body_size },
+    };
+    let post_body_def = r.post_body_type.as_ref().map(|pbt| {
+        quote! {
+            let post_body: #pbt =
+            deser_post_data_limited(req.body_mut(), #max_body_size).await?;
+        }
+    });
+
+    // query
+    let query_var = r
+        .query_type
+        .iter()
+        .map(|_| {
+            quote! { query }
+        })
+        .collect::<Vec<_>>();
+    let query_deser_fn = &r.query_deser_fn;
+    let query_def = r.query_type.as_ref().map(|qt| {
+        let deser_query = match &r.query_keys {
+            Some(keys) => quote! {
+                if options.strict_query {
+                    deser_query_strict(q, &[#(#keys),*])?
+                } else {
+                    #query_deser_fn(q)?
+                }
+            },
+            None => quote! { #query_deser_fn(q)? },
+        };
+        if r.default_query {
+            quote! {
+                let query: #qt = match req.uri().query() {
+                    None => Default::default(),
+                    Some(q) => #deser_query,
+                };
+            }
+        } else {
+            quote! {
+                let query: Option<#qt> = match req.uri().query() {
+                    None => None,
+                    Some(q) => Some(#deser_query),
+                };
+            }
+        }
+    });
+    // for query params, check the keys once up front
+    let query_keys_check = match &r.query_keys {
+        Some(keys) if r.query_type.is_none() => Some(quote! {
+            if options.strict_query {
+                check_query_keys(req.uri().query(), &[#(#keys),*])?;
+            }
+        }),
+        _ => None,
+    };
+
+    // query params
+    let (query_param_vars, query_param_defs): (Vec<TokenStream>, Vec<TokenStream>) = r.query_params.iter().map(|q| {
+        let ServiceRouteQueryParam {
+            param_name,
+            rust_var_ident,
+            rust_var_type,
+            required,
+            ..
+        } = q;
+        let deser_fn = if *required {
+            quote! { deser_query_param }
+        } else {
+            quote! { deser_optional_query_param }
+        };
+        (
+            quote! { #rust_var_ident },
+            quote! { let #rust_var_ident: #rust_var_type = #deser_fn(req.uri().query(), #param_name)?; },
+        )
+    }).unzip();
+
+    // route params
+    let (route_param_vars, route_param_parse_stmts): (Vec<TokenStream>, Vec<TokenStream>) = r.components.iter().filter_map(|c| match c {
+        ServiceRouteComponent::Literal { .. } => None,
+        ServiceRouteComponent::Param {
+            spec_arg_name,
+            rust_var_ident,
+            rust_var_type,
+            ..
+        } => Some((
+            quote! { #rust_var_ident },
+            quote! { let #rust_var_ident: Result<#rust_var_type, ErrorResponse> = deser_param( #spec_arg_name,  &captures[ #spec_arg_name ]); },
+        )),
+    }).unzip();
+
+    // headers
+    let (header_vars, header_defs): (Vec<TokenStream>, Vec<TokenStream>) = r.headers.iter().map(|h| {
+        let ServiceRouteHeader {
+            header_name,
+            rust_var_ident,
+            rust_var_type,
+            required,
+            ..
+        } = h;
+        let deser_fn = if *required {
+            quote! { deser_header }
+        } else {
+            quote! { deser_optional_header }
+        };
+        (
+            quote! { #rust_var_ident },
+            quote! { let #rust_var_ident: #rust_var_type = #deser_fn(req.headers(), #header_name)?; },
+        )
+    }).unzip();
+
+    let mut arg_list = Vec::new();
+    arg_list.extend(&post_body_var);
+    arg_list.extend(&query_var);
+    arg_list.extend(&query_param_vars);
+    arg_list.extend(&route_param_vars);
+    arg_list.extend(&header_vars);
+
+    let route_param_parse_stmts = route_param_parse_stmts.into_iter();
+    let route_param_vars2 = route_param_vars.iter();
+    let route_param_vars = route_param_vars.iter();
+    let arg_list = arg_list.into_iter();
+    quote! {
+        {
+            let handler = Arc::clone(&handler);
+            // We cannot move the regex captures into the async closure, thus do the parsing
+            // of route params outside of the closure and move the parsing results into it.
+            // Inside the closure, `?` the results and return the param deserialization error.
+            #(#route_param_parse_stmts);*
+            Box::pin(async move {
+                use ::humblegen_rt::service_protocol::ToErrorResponse;
+                let result: Result<hyper::Response<hyper::Body>, ErrorResponse> = async {
+                    // Invoke the interceptor
+                    let ctx = {
+                        let span = tracing::error_span!("interceptor");
+                        handler.intercept_handler_pre(&req).instrument(span).await
+                            .map_err(::humblegen_rt::service_protocol::ServiceError::from)
+                            .map_err(|e| {
+                                tracing::debug!(service_error = ?format!("{:?}", e), "interceptor rejected request");
+                                e
+                            })
+                            .map_err(|e| e.to_error_response())?
+                    };
+                    let ctx = match ctx {
+                        InterceptOutcome::Continue(ctx) => ctx,
+                        // the interceptor answered the request itself
+                        InterceptOutcome::Respond(response) => {
+                            tracing::debug!(status = response.status().as_u16(), "interceptor responded");
+                            return Ok(response);
+                        }
+                    };
+
+                    // deserialize only after we have invoked the interceptor
+                    // => interceptor can implement some DoS protection
+                    #(let #route_param_vars = #route_param_vars2?;)*
+                    #query_keys_check
+                    #query_def
+                    #(#query_param_defs)*
+                    #(#header_defs)*
+                    #post_body_def
+                    #websocket_upgrade_def
+
+                    drop(req); // free some memory
+
+                    // Invoke handler if interceptor doesn't return a ServiceError
+                    let span = tracing::error_span!("handler");
+                    Ok(#response_conversion_fn(#response_conversion_arg handler.#traitfn_ident( ctx, #(#arg_list),* ).instrument(span).await))
+                }.await;
+
+                // Invoke the post-handler interceptor on every response, including the error
+                // responses of the interceptor and of the deserialization of the request
+                let response = result.unwrap_or_else(|e| {
+                    tracing::error!(err = ?e, "request failed");
+                    e.to_hyper_response()
+                });
+                let span = tracing::error_span!("interceptor_post");
+                Ok(handler.intercept_handler_post(response).instrument(span).await)
+            })
+        }
+    }
+}
+
+/// lower the `ast::ServiceDefs` of `spec` into `struct Service`
+pub(super) fn lower_all_services(spec: &ast::Spec) -> Vec<Service> {
+    spec.iter()
+        .filter_map(|si| si.service_def())
         .map(|sdef| Service {
             trait_name: format_ident!("{}", sdef.name),
+            namespace: sdef.namespace.clone(),
+            service_name: sdef.qualified_name("."),
             trait_comment: fmt_opt_string(&sdef.doc_comment).to_string(),
             routes_factory_name: format_ident!("routes_{}", sdef.name),
             service_routes: sdef
                 .endpoints
                 .iter()
-                .map(|e| lower_service_route(&e))
+                .map(|e| lower_service_route(spec, &sdef.base_segments(), &e))
+                .collect(),
+            proxies: sdef
+                .proxies
+                .iter()
+                .map(|p| ServiceProxy {
+                    template: sdef
+                        .base_segments()
+                        .into_iter()
+                        .chain(p.prefix.iter().map(String::as_str))
+                        .chain(std::iter::once("*"))
+                        .map(|segment| format!("/{}", segment))
+                        .collect(),
+                    upstream: p.upstream.clone(),
+                })
                 .collect(),
         })
         .collect()
 }
 
 /// Helper function for lowering an `ast::ServiceEndpoint` into a `ServiceRoute`.
-fn lower_service_route(endpoint: &ast::ServiceEndpoint) -> ServiceRoute {
-    let components = endpoint
-        .route
-        .components()
-        .iter()
-        .map(|c| match c {
-            ast::ServiceRouteComponent::Literal(spec) => {
-                ServiceRouteComponent::Literal { spec: spec.clone() }
-            }
-            ast::ServiceRouteComponent::Variable(ast::FieldDefPair { name, type_ident }) => {
-                let rust_var_ident = format_ident!("{}", name);
-                let rust_var_type = generate_type_ident(type_ident);
-                let url_regex_str = r"[^/]+".to_owned();
-                ServiceRouteComponent::Param {
-                    spec_arg_name: name.clone(),
-                    url_regex_str,
-                    rust_var_ident,
-                    rust_var_type,
-                }
+///
+/// The `base` segments of the service are prepended to the route as literal components.
+fn lower_service_route(
+    spec: &ast::Spec,
+    base: &[&str],
+    endpoint: &ast::ServiceEndpoint,
+) -> ServiceRoute {
+    let base_components = base.iter().map(|segment| ServiceRouteComponent::Literal {
+        spec: (*segment).to_owned(),
+    });
+    let route_components = endpoint.route.components().iter().map(|c| match c {
+        ast::ServiceRouteComponent::Literal(spec) => {
+            ServiceRouteComponent::Literal { spec: spec.clone() }
+        }
+        ast::ServiceRouteComponent::Variable(
+            ast::FieldDefPair {
+                name, type_ident, ..
+            },
+            pattern,
+        ) => {
+            let rust_var_ident = format_ident!("{}", name);
+            let rust_var_type = generate_type_ident(type_ident);
+            // group custom patterns so that e.g. alternations stay within the param
+            let url_regex_str = match pattern {
+                ast::RouteParamPattern::Segment => pattern.to_regex().to_owned(),
+                _ => format!("(?:{})", pattern.to_regex()),
+            };
+            ServiceRouteComponent::Param {
+                spec_arg_name: name.clone(),
+                url_regex_str,
+                rust_var_ident,
+                rust_var_type,
+                multi_segment: pattern.is_multi_segment(),
             }
-        })
-        .collect();
+        }
+    });
+
+    let components = base_components.chain(route_components).collect();
 
     let post_body_type = match &endpoint.route {
         ast::ServiceRoute::Get { .. } => None,
         ast::ServiceRoute::Delete { .. } => None,
+        ast::ServiceRoute::Ws { .. } => None,
+        ast::ServiceRoute::Sse { .. } => None,
         ast::ServiceRoute::Post { body, .. } => Some(generate_type_ident(body)),
         ast::ServiceRoute::Put { body, .. } => Some(generate_type_ident(body)),
         ast::ServiceRoute::Patch { body, .. } => Some(generate_type_ident(body)),
+        ast::ServiceRoute::Options { .. } => None,
+        ast::ServiceRoute::Custom { body, .. } => body.as_ref().map(generate_type_ident),
     };
 
-    let ret_type = generate_type_ident(endpoint.route.return_type());
+    let response_kind = endpoint.route.response_kind();
+    let ret_type = match response_kind {
+        ast::ResponseKind::Json => generate_type_ident(endpoint.route.return_type()),
+        ast::ResponseKind::Paginated => {
+            let item_type = generate_type_ident(endpoint.route.return_type());
+            quote! { Page<#item_type> }
+        }
+        ast::ResponseKind::Raw => quote! { RawResponse },
+        ast::ResponseKind::Stream | ast::ResponseKind::EventStream => {
+            let item_type = generate_type_ident(endpoint.route.return_type());
+            quote! { StreamResponse<#item_type> }
+        }
+        ast::ResponseKind::WebSocket => {
+            let message_type = generate_type_ident(endpoint.route.return_type());
+            quote! { WebSocketResponse<#message_type> }
+        }
+    };
 
-    let (query_type, query_deser_fn) = endpoint
+    let headers = endpoint
+        .route
+        .headers()
+        .iter()
+        .map(|h| {
+            let (value_type, required) = match &h.type_ident {
+                ast::TypeIdent::Option(inner) | ast::TypeIdent::Nullable(inner) => {
+                    (inner.as_ref(), false)
+                }
+                ty => (ty, true),
+            };
+            ServiceRouteHeader {
+                header_name: h.name.clone(),
+                rust_var_ident: format_ident!(
+                    "{}",
+                    inflector::cases::snakecase::to_snake_case(&h.name)
+                ),
+                rust_var_type: generate_type_ident(&h.type_ident),
+                rust_value_type: generate_type_ident(value_type),
+                required,
+            }
+        })
+        .collect();
+
+    let (query_type, query_deser_fn, query_ser_fn) = endpoint
         .route
         .query()
         .as_ref()
         .map(|qt| {
-            let deser_fn = match qt {
-                ast::TypeIdent::UserDefined(_) => quote! { deser_query_serde_urlencoded },
-                _ => quote! { deser_query_primitive },
+            let (deser_fn, ser_fn) = match qt {
+                ast::TypeIdent::UserDefined(_) => (
+                    quote! { deser_query_serde_urlencoded },
+                    quote! { ser_query_serde_urlencoded },
+                ),
+                _ => (
+                    quote! { deser_query_primitive },
+                    quote! { ser_query_primitive },
+                ),
+            };
+            (Some(generate_type_ident(qt)), deser_fn, ser_fn)
+        })
+        .unwrap_or((None, quote! {}, quote! {}));
+
+    let query_params = endpoint
+        .route
+        .query_fields()
+        .iter()
+        .map(|pair| {
+            let (value_type, required) = match pair.type_ident {
+                ast::TypeIdent::Option(ref inner) | ast::TypeIdent::Nullable(ref inner) => {
+                    (inner.as_ref(), false)
+                }
+                ref ty => (ty, true),
             };
-            (Some(generate_type_ident(qt)), deser_fn)
+            ServiceRouteQueryParam {
+                param_name: pair.name.clone(),
+                rust_var_ident: format_ident!("{}", pair.name),
+                rust_var_type: generate_type_ident(&pair.type_ident),
+                rust_value_type: generate_type_ident(value_type),
+                required,
+            }
         })
-        .unwrap_or((None, quote! {}));
+        .collect();
+
+    let query_keys = match endpoint.route.query() {
+        Some(ast::TypeIdent::UserDefined(name)) => spec.iter().find_map(|si| match si {
+            ast::SpecItem::StructDef(sdef) if &sdef.name == name => Some(
+                sdef.fields
+                    .iter()
+                    .map(|field| field.wire_name(sdef.rename_all))
+                    .collect(),
+            ),
+            _ => None,
+        }),
+        Some(_) => None,
+        None => Some(
+            endpoint
+                .route
+                .query_fields()
+                .iter()
+                .map(|field| field.name.clone())
+                .collect(),
+        ),
+    };
 
     let traitfn_name_stem = &endpoint
         .route
@@ -500,11 +1170,17 @@ fn lower_service_route(endpoint: &ast::ServiceEndpoint) -> ServiceRoute {
         .iter()
         .map(|c| match c {
             ast::ServiceRouteComponent::Literal(l) => l.clone(),
-            ast::ServiceRouteComponent::Variable(ast::FieldDefPair { name, .. }) => name.clone(),
+            ast::ServiceRouteComponent::Variable(ast::FieldDefPair { name, .. }, _) => name.clone(),
         })
         .collect::<Vec<_>>()
         .join("_");
 
+    // e.g. `propfind` for `"PROPFIND"`
+    let custom_method_prefix = endpoint
+        .route
+        .http_method_as_str()
+        .to_lowercase()
+        .replace('-', "_");
     let (traitfn_name_prefix, hyper_method) = match &endpoint.route {
         ast::ServiceRoute::Get { .. } => ("get", quote!(::humblegen_rt::hyper::Method::GET)),
         ast::ServiceRoute::Delete { .. } => {
@@ -513,27 +1189,64 @@ fn lower_service_route(endpoint: &ast::ServiceEndpoint) -> ServiceRoute {
         ast::ServiceRoute::Post { .. } => ("post", quote!(::humblegen_rt::hyper::Method::POST)),
         ast::ServiceRoute::Put { .. } => ("put", quote!(::humblegen_rt::hyper::Method::PUT)),
         ast::ServiceRoute::Patch { .. } => ("patch", quote!(::humblegen_rt::hyper::Method::PATCH)),
+        ast::ServiceRoute::Options { .. } => {
+            ("options", quote!(::humblegen_rt::hyper::Method::OPTIONS))
+        }
+        ast::ServiceRoute::Custom { method, .. } => {
+            let method_bytes = proc_macro2::Literal::byte_string(method.as_bytes());
+            (
+                custom_method_prefix.as_str(),
+                quote!(::humblegen_rt::hyper::Method::from_bytes(#method_bytes)
+                    .expect("method names of the spec are valid tokens")),
+            )
+        }
+        // WebSocket handshakes are `GET` requests
+        ast::ServiceRoute::Ws { .. } => ("ws", quote!(::humblegen_rt::hyper::Method::GET)),
+        ast::ServiceRoute::Sse { .. } => ("sse", quote!(::humblegen_rt::hyper::Method::GET)),
     };
     let traitfn_ident = format_ident!(
         "{}_{}",
         traitfn_name_prefix,
         inflector::cases::snakecase::to_snake_case(&traitfn_name_stem)
     );
+    let path_fn_ident = format_ident!(
+        "{}_path",
+        inflector::cases::snakecase::to_snake_case(traitfn_name_stem)
+    );
+    let axum_method_fn = match &endpoint.route {
+        ast::ServiceRoute::Ws { .. } | ast::ServiceRoute::Sse { .. } => format_ident!("get"),
+        // rejected by `axum_router::check_axum_routes`
+        ast::ServiceRoute::Custom { .. } => format_ident!("any"),
+        route => format_ident!("{}", route.http_method_as_str().to_lowercase()),
+    };
 
     let doc_comment = {
         let doc_comment = fmt_opt_string(&endpoint.doc_comment);
         quote! { #[doc = #doc_comment] }
     };
+    let deprecated = super::generate_deprecated(&endpoint.deprecated);
 
     ServiceRoute {
         doc_comment,
+        deprecated,
         traitfn_ident,
+        path_fn_ident,
         hyper_method,
+        axum_method_fn,
         components,
         query_type,
+        default_query: endpoint.default_query,
         query_deser_fn,
+        query_ser_fn,
+        query_params,
+        query_keys,
         post_body_type,
+        headers,
         ret_type,
+        response_kind,
+        status: endpoint.route.status(),
+        error_status: endpoint.error_status,
+        max_body_size: endpoint.max_body_size,
     }
 }
 
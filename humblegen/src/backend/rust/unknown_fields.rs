@@ -0,0 +1,121 @@
+//! Selection of the structs that reject unknown fields, see `DenyUnknownFields`.
+
+use super::DenyUnknownFields;
+use crate::ast;
+use std::collections::HashSet;
+
+/// Names of the structs of `spec` that get `#[serde(deny_unknown_fields)]`.
+///
+/// serde does not support `deny_unknown_fields` in combination with `flatten`, so structs
+/// annotated using `@flatten_embeds` and the structs flattened into them are never selected.
+pub(crate) fn structs_denying_unknown_fields(
+    spec: &ast::Spec,
+    deny_unknown_fields: DenyUnknownFields,
+) -> HashSet<&str> {
+    let mut selected = HashSet::new();
+    match deny_unknown_fields {
+        DenyUnknownFields::Never => {}
+        DenyUnknownFields::RequestBodies => {
+            let bodies = spec
+                .iter()
+                .filter_map(ast::SpecItem::service_def)
+                .flat_map(|service| &service.endpoints)
+                .filter_map(|endpoint| endpoint.route.request_body());
+            let mut visited = HashSet::new();
+            for body in bodies {
+                select_type(spec, body, &mut visited, &mut selected);
+            }
+        }
+        DenyUnknownFields::All => {
+            selected.extend(
+                spec.iter()
+                    .filter_map(ast::SpecItem::struct_def)
+                    .map(|sdef| sdef.name.as_str()),
+            );
+        }
+    }
+
+    for sdef in spec.iter().filter_map(ast::SpecItem::struct_def) {
+        if sdef.flatten_embeds {
+            selected.remove(sdef.name.as_str());
+            for field in sdef.fields.iter() {
+                if let Some(embedded_from) = &field.embedded_from {
+                    selected.remove(embedded_from.as_str());
+                }
+            }
+        }
+    }
+    selected
+}
+
+/// Select the structs contained in `type_ident`, including those nested in other user defined
+/// types. `visited` holds the names of the user defined types visited so far.
+fn select_type<'a>(
+    spec: &'a ast::Spec,
+    type_ident: &ast::TypeIdent,
+    visited: &mut HashSet<&'a str>,
+    selected: &mut HashSet<&'a str>,
+) {
+    match type_ident {
+        ast::TypeIdent::BuiltIn(_) => {}
+        ast::TypeIdent::List(inner)
+        | ast::TypeIdent::Set(inner)
+        | ast::TypeIdent::Option(inner)
+        | ast::TypeIdent::Nullable(inner) => select_type(spec, inner, visited, selected),
+        ast::TypeIdent::Result(a, b)
+        | ast::TypeIdent::Map(a, b)
+        | ast::TypeIdent::OrderedMap(a, b) => {
+            select_type(spec, a, visited, selected);
+            select_type(spec, b, visited, selected);
+        }
+        ast::TypeIdent::Tuple(tdef) => {
+            for element in tdef.elements() {
+                select_type(spec, element, visited, selected);
+            }
+        }
+        ast::TypeIdent::UserDefined(name) => {
+            if visited.contains(name.as_str()) {
+                return;
+            }
+            // unknown types are reported by the compiler
+            for item in spec.iter() {
+                match item {
+                    ast::SpecItem::StructDef(sdef) if &sdef.name == name => {
+                        visited.insert(&sdef.name);
+                        selected.insert(&sdef.name);
+                        for field in sdef.fields.iter() {
+                            select_type(spec, &field.pair.type_ident, visited, selected);
+                        }
+                    }
+                    ast::SpecItem::EnumDef(edef) if &edef.name == name => {
+                        visited.insert(&edef.name);
+                        for variant in &edef.variants {
+                            match &variant.variant_type {
+                                ast::VariantType::Simple => {}
+                                ast::VariantType::Tuple(tdef) => {
+                                    for element in tdef.elements() {
+                                        select_type(spec, element, visited, selected);
+                                    }
+                                }
+                                ast::VariantType::Struct(fields) => {
+                                    for field in fields.iter() {
+                                        select_type(
+                                            spec,
+                                            &field.pair.type_ident,
+                                            visited,
+                                            selected,
+                                        );
+                                    }
+                                }
+                                ast::VariantType::Newtype(ty) => {
+                                    select_type(spec, ty, visited, selected)
+                                }
+                            }
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,297 @@
+//! Generates TypeScript type declarations for the user defined types of a humble specification
+//! file
+//!
+//! Structs become `interface`s, enums become (discriminated) unions following their
+//! representation on the wire, see `docs/humblespec/data_types_json_representation.md`.
+//! Services are not generated.
+
+use crate::{ast, backend, LibError};
+
+use std::{fs, path::Path};
+
+/// Generate the declarations of all user defined types of `spec`.
+fn generate_declarations(spec: &ast::Spec) -> String {
+    let mut out = String::from(
+        "// This file is generated by humblegen. Do not edit it, edit the humble file instead.\n",
+    );
+    for spec_item in spec.iter() {
+        let declaration = match spec_item {
+            ast::SpecItem::StructDef(sdef) => generate_struct(sdef),
+            ast::SpecItem::EnumDef(edef) => generate_enum(edef),
+            ast::SpecItem::ServiceDef(_) => continue,
+        };
+        out.push('\n');
+        out.push_str(&declaration);
+    }
+    out
+}
+
+/// Generate an `interface` for a struct.
+fn generate_struct(sdef: &ast::StructDef) -> String {
+    format!(
+        "{}export interface {} {}\n",
+        doc_comment(&sdef.doc_comment, &sdef.deprecated, ""),
+        sdef.name,
+        generate_object(None, &sdef.fields, sdef.rename_all, "")
+    )
+}
+
+/// Generate an object type with the given fields, indented by `indent`. Optional fields and
+/// fields with a default value may be missing, nullable fields have to be present but may be
+/// `null`. `tag` is an additional property in front of the fields, e.g. `kind: "Circle"`.
+fn generate_object(
+    tag: Option<&str>,
+    fields: &ast::StructFields,
+    rename_all: Option<ast::RenameRule>,
+    indent: &str,
+) -> String {
+    if fields.iter().next().is_none() {
+        return match tag {
+            Some(tag) => format!("{{ {} }}", tag),
+            None => "{}".to_owned(),
+        };
+    }
+
+    let mut out = String::from("{\n");
+    let field_indent = format!("{}    ", indent);
+    if let Some(tag) = tag {
+        out.push_str(&format!("{}{};\n", field_indent, tag));
+    }
+    for field in fields.iter() {
+        let (optional, ty) = match &field.pair.type_ident {
+            ast::TypeIdent::Option(inner) => (true, format!("{} | null", generate_type(inner))),
+            ty => (field.default.is_some(), generate_type(ty)),
+        };
+        out.push_str(&doc_comment(
+            &field.doc_comment,
+            &field.deprecated,
+            &field_indent,
+        ));
+        out.push_str(&format!(
+            "{}{}{}: {};\n",
+            field_indent,
+            property_name(&field.wire_name(rename_all)),
+            if optional { "?" } else { "" },
+            ty
+        ));
+    }
+    out.push_str(indent);
+    out.push('}');
+    out
+}
+
+/// Generate a type alias for an enum, a union of its variants.
+fn generate_enum(edef: &ast::EnumDef) -> String {
+    let variants: Vec<String> = edef
+        .variants
+        .iter()
+        .map(|variant| {
+            let ty = match edef.tagging() {
+                ast::EnumTagging::External => externally_tagged_variant(variant),
+                ast::EnumTagging::Internal { tag } => tagged_variant(variant, tag, None),
+                ast::EnumTagging::Adjacent { tag, content } => {
+                    tagged_variant(variant, tag, Some(content))
+                }
+            };
+            format!(
+                "{}    | {}\n",
+                doc_comment(&variant.doc_comment, &None, "    "),
+                ty
+            )
+        })
+        .collect();
+
+    let doc_comment = doc_comment(&edef.doc_comment, &edef.deprecated, "");
+    if variants.is_empty() {
+        format!("{}export type {} = never;\n", doc_comment, edef.name)
+    } else {
+        format!(
+            "{}export type {} =\n{};\n",
+            doc_comment,
+            edef.name,
+            variants.concat().trim_end()
+        )
+    }
+}
+
+/// The data carried by a complex variant, indented by `indent` if it is an object.
+fn variant_payload(variant_type: &ast::VariantType, indent: &str) -> String {
+    match variant_type {
+        ast::VariantType::Simple => unreachable!("variant is complex"),
+        ast::VariantType::Tuple(tdef) => generate_tuple(tdef),
+        ast::VariantType::Struct(fields) => generate_object(None, fields, None, indent),
+        ast::VariantType::Newtype(ty) => generate_type(ty),
+    }
+}
+
+/// A variant of an externally tagged enum: the name of simple variants, an object with the
+/// name as its only key for complex ones.
+fn externally_tagged_variant(variant: &ast::VariantDef) -> String {
+    match variant.variant_type {
+        ast::VariantType::Simple => string_literal(variant.wire_name()),
+        ref variant_type => format!(
+            "{{ {}: {} }}",
+            property_name(&variant.name),
+            variant_payload(variant_type, "    ")
+        ),
+    }
+}
+
+/// A variant of an internally (`content` is `None`) or adjacently tagged enum: an object
+/// discriminated by its `tag` key.
+fn tagged_variant(variant: &ast::VariantDef, tag: &str, content: Option<&str>) -> String {
+    let tag = format!(
+        "{}: {}",
+        property_name(tag),
+        string_literal(variant.wire_name())
+    );
+    match (content, &variant.variant_type) {
+        (_, ast::VariantType::Simple) => format!("{{ {} }}", tag),
+        // internally tagged enums only have simple and struct variants
+        (None, ast::VariantType::Struct(fields)) => {
+            generate_object(Some(&tag), fields, None, "    ")
+        }
+        (None, _) => unreachable!("internally tagged variants are simple or structs"),
+        (Some(content), variant_type) => format!(
+            "{{ {}; {}: {} }}",
+            tag,
+            property_name(content),
+            variant_payload(variant_type, "    ")
+        ),
+    }
+}
+
+/// Generate the TypeScript type of a humble type, as it appears outside of a struct field.
+fn generate_type(type_ident: &ast::TypeIdent) -> String {
+    match type_ident {
+        ast::TypeIdent::BuiltIn(atom) => generate_atom(*atom).to_owned(),
+        ast::TypeIdent::List(inner) | ast::TypeIdent::Set(inner) => {
+            format!("Array<{}>", generate_type(inner))
+        }
+        ast::TypeIdent::Option(inner) | ast::TypeIdent::Nullable(inner) => {
+            format!("{} | null", generate_type(inner))
+        }
+        ast::TypeIdent::Result(ok, err) => format!(
+            "{{ Ok: {} }} | {{ Err: {} }}",
+            generate_type(ok),
+            generate_type(err)
+        ),
+        // keys are always represented as strings in JSON
+        ast::TypeIdent::Map(_, value) | ast::TypeIdent::OrderedMap(_, value) => {
+            format!("Record<string, {}>", generate_type(value))
+        }
+        ast::TypeIdent::Tuple(tdef) => generate_tuple(tdef),
+        ast::TypeIdent::UserDefined(name) => name.clone(),
+    }
+}
+
+fn generate_tuple(tdef: &ast::TupleDef) -> String {
+    format!(
+        "[{}]",
+        tdef.elements()
+            .iter()
+            .map(generate_type)
+            .collect::<Vec<_>>()
+            .join(", ")
+    )
+}
+
+/// Generate the TypeScript type of a built-in type. Integers that do not fit into a JavaScript
+/// number are represented as strings.
+fn generate_atom(atom: ast::AtomType) -> &'static str {
+    match atom {
+        ast::AtomType::Empty => "null",
+        ast::AtomType::I32
+        | ast::AtomType::U32
+        | ast::AtomType::U8
+        | ast::AtomType::I64
+        | ast::AtomType::U64
+        | ast::AtomType::I16
+        | ast::AtomType::U16
+        | ast::AtomType::I8
+        | ast::AtomType::F32
+        | ast::AtomType::F64
+        | ast::AtomType::Duration => "number",
+        ast::AtomType::Bool => "boolean",
+        ast::AtomType::Str
+        | ast::AtomType::U128
+        | ast::AtomType::I128
+        | ast::AtomType::DateTime
+        | ast::AtomType::Date
+        | ast::AtomType::Uuid
+        | ast::AtomType::Url
+        | ast::AtomType::IpAddr
+        | ast::AtomType::Bytes
+        | ast::AtomType::Decimal => "string",
+        ast::AtomType::Json => "unknown",
+    }
+}
+
+/// A property name, quoted unless it is a valid identifier, e.g. `"kebab-case"`.
+fn property_name(name: &str) -> String {
+    let is_identifier = name
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_' || c == '$')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$');
+    if is_identifier {
+        name.to_owned()
+    } else {
+        string_literal(name)
+    }
+}
+
+fn string_literal(s: &str) -> String {
+    serde_json::Value::from(s).to_string()
+}
+
+/// A JSDoc comment containing the doc comment and deprecation note, if any, indented by
+/// `indent`.
+fn doc_comment(doc_comment: &Option<String>, deprecated: &Option<String>, indent: &str) -> String {
+    let mut lines: Vec<String> = doc_comment
+        .iter()
+        .flat_map(|doc_comment| doc_comment.trim_end().lines())
+        .map(ToOwned::to_owned)
+        .collect();
+    if let Some(note) = deprecated {
+        lines.push(format!("@deprecated {}", note));
+    }
+    if lines.is_empty() {
+        return String::new();
+    }
+
+    let mut out = format!("{}/**\n", indent);
+    for line in lines {
+        // a `*/` would end the comment early
+        let line = line.replace("*/", "*\\/");
+        out.push_str(&format!("{} * {}\n", indent, line).replace(" * \n", " *\n"));
+    }
+    out.push_str(&format!("{} */\n", indent));
+    out
+}
+
+/// TypeScript backend. Writes the declarations of all user defined types as a single module to
+/// the output path.
+#[derive(Default)]
+pub struct Generator {
+    default_enum_tagging: ast::EnumTagging,
+}
+
+impl Generator {
+    /// Represent enums without a `@tag(...)` annotation using `tagging` instead of external
+    /// tagging, like `rust::Generator::with_default_enum_tagging` does.
+    pub fn with_default_enum_tagging(mut self, tagging: ast::EnumTagging) -> Self {
+        self.default_enum_tagging = tagging;
+        self
+    }
+}
+
+impl crate::CodeGenerator for Generator {
+    fn generate(&self, spec: &ast::Spec, output: &Path) -> Result<(), LibError> {
+        let spec = backend::with_default_enum_tagging(spec, &self.default_enum_tagging)?;
+        fs::write(output, generate_declarations(&spec))?;
+        Ok(())
+    }
+}
@@ -9,6 +9,12 @@ pub enum CliError {
     UnknownBackend(String),
     #[error("unknown output artifact '{0}'")]
     UnknownArtifact(String),
+    #[error("unknown selection of structs '{0}', expected 'request-bodies' or 'all'")]
+    UnknownStructSelection(String),
+    #[error("unknown server runtime '{0}', expected 'hyper' or 'axum'")]
+    UnknownServerRuntime(String),
+    #[error("unknown stats format '{0}', expected 'text' or 'json'")]
+    UnknownStatsFormat(String),
     #[error(transparent)]
     LibraryError(#[from] humblegen::LibError),
 }
@@ -18,6 +24,9 @@ pub enum Backend {
     Rust,
     Elm,
     Docs,
+    OpenApi,
+    JsonSchema,
+    TypeScript,
 }
 
 impl str::FromStr for Backend {
@@ -28,6 +37,9 @@ impl str::FromStr for Backend {
             "RUST" => Ok(Backend::Rust),
             "ELM" => Ok(Backend::Elm),
             "DOCS" | "DOC" | "DOCUMENTATION" => Ok(Backend::Docs),
+            "OPENAPI" => Ok(Backend::OpenApi),
+            "JSONSCHEMA" | "JSON-SCHEMA" => Ok(Backend::JsonSchema),
+            "TYPESCRIPT" | "TS" => Ok(Backend::TypeScript),
             _ => Err(CliError::UnknownBackend(s.to_string())),
         }
     }
@@ -69,28 +81,153 @@ impl Deref for Artifact {
     }
 }
 
+/// The structs rejecting unknown fields in the generated rust code.
+pub(crate) struct DenyUnknownFields(humblegen::backend::rust::DenyUnknownFields);
+
+impl str::FromStr for DenyUnknownFields {
+    type Err = CliError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        use humblegen::backend::rust::DenyUnknownFields::*;
+        match s.to_uppercase().as_str() {
+            "REQUEST-BODIES" => Ok(DenyUnknownFields(RequestBodies)),
+            "ALL" => Ok(DenyUnknownFields(All)),
+            _ => Err(CliError::UnknownStructSelection(s.to_string())),
+        }
+    }
+}
+
+/// The runtime of the server in the generated rust code.
+pub(crate) struct ServerRuntime(humblegen::backend::rust::ServerRuntime);
+
+impl str::FromStr for ServerRuntime {
+    type Err = CliError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        use humblegen::backend::rust::ServerRuntime::*;
+        match s.to_uppercase().as_str() {
+            "HYPER" => Ok(ServerRuntime(Hyper)),
+            "AXUM" => Ok(ServerRuntime(Axum)),
+            _ => Err(CliError::UnknownServerRuntime(s.to_string())),
+        }
+    }
+}
+
+/// The format of the metrics printed by `--stats`.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub(crate) enum StatsFormat {
+    Text,
+    Json,
+}
+
+impl str::FromStr for StatsFormat {
+    type Err = CliError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_uppercase().as_str() {
+            "TEXT" => Ok(StatsFormat::Text),
+            "JSON" => Ok(StatsFormat::Json),
+            _ => Err(CliError::UnknownStatsFormat(s.to_string())),
+        }
+    }
+}
+
 /// Command-line arguments
 // TODO: turn into enum separating language backends from docs backend, docs backend does not need a gen_server and gen_client field
 #[derive(StructOpt)]
 #[structopt(about = "generate code from humble protocol spec")]
 pub(crate) struct CliArgs {
     /// language to generate code for
-    #[structopt(short = "l", long = "language")]
-    pub(crate) backend: Backend,
+    #[structopt(
+        short = "l",
+        long = "language",
+        required_unless_one = &["diff", "stats"]
+    )]
+    pub(crate) backend: Option<Backend>,
     /// generate REST endpoints for a server
     #[structopt(short = "a", long = "artifacts", default_value)]
     pub(crate) artifacts: Artifact,
     /// input path to humble file
     pub(crate) input: path::PathBuf,
-    /// input path to humble file
-    #[structopt(short = "o", long = "output")]
-    pub(crate) output: path::PathBuf,
+    /// output path; for rust, `-` writes to stdout and an existing directory receives
+    /// separate files for types and services
+    #[structopt(short = "o", long = "output", required_unless_one = &["diff", "stats"])]
+    pub(crate) output: Option<path::PathBuf>,
     /// prefix to be used in elm module declarations
     #[structopt(long, default_value = "\"Api\"")]
     pub(crate) elm_module_root: String,
+    /// maximum depth to which embeds are resolved
+    #[structopt(long)]
+    pub(crate) max_embed_depth: Option<usize>,
+    /// represent enums without a `@tag(...)` annotation using this tag (rust, elm and
+    /// typescript only), like `@tag("<tag>")`
+    #[structopt(long)]
+    pub(crate) enum_tag: Option<String>,
+    /// represent enums without a `@tag(...)` annotation using this content key in addition to
+    /// `--enum-tag`, like `@tag("<tag>", content = "<content>")`
+    #[structopt(long, requires = "enum-tag")]
+    pub(crate) enum_content: Option<String>,
+    /// reject unknown fields when deserializing structs used as request bodies
+    /// (`request-bodies`) or all structs (`all`) in the generated rust code
+    #[structopt(long)]
+    pub(crate) deny_unknown_fields: Option<DenyUnknownFields>,
+    /// serve the services of the generated rust server using the built-in server (`hyper`,
+    /// the default) or an `axum::Router` (`axum`)
+    #[structopt(long)]
+    pub(crate) server_runtime: Option<ServerRuntime>,
+    /// format the generated rust code using the built-in pretty-printer instead of rustfmt
+    #[structopt(long)]
+    pub(crate) no_rustfmt: bool,
+    /// append a `#[cfg(test)]` module to the generated rust code, testing that an example value
+    /// of each struct and enum survives a JSON round trip; derives `PartialEq` for all types
+    #[structopt(long)]
+    pub(crate) round_trip_tests: bool,
+    /// keep running and regenerate whenever the input file or one of its imports changes
+    #[structopt(long)]
+    pub(crate) watch: bool,
+    /// instead of generating code, list the changes from this older version of the input
+    /// humble file and exit with a non-zero status if any of them is breaking
+    #[structopt(long, conflicts_with = "watch")]
+    pub(crate) diff: Option<path::PathBuf>,
+    /// instead of generating code, print the number of structs, enums, services, endpoints
+    /// (by HTTP method) and fields of the input humble file
+    #[structopt(long, conflicts_with_all = &["watch", "diff"])]
+    pub(crate) stats: bool,
+    /// format of the metrics printed by `--stats`, `text` or `json`
+    #[structopt(long, default_value = "text")]
+    pub(crate) stats_format: StatsFormat,
 }
 
 impl CliArgs {
+    /// The parser options selected by the command-line arguments.
+    pub fn parser_options(&self) -> humblegen::parser::ParserOptions {
+        let mut options = humblegen::parser::ParserOptions::default();
+        if let Some(max_embed_depth) = self.max_embed_depth {
+            options.max_embed_depth = max_embed_depth;
+        }
+        options
+    }
+
+    /// The representation of enums without a `@tag(...)` annotation selected by the
+    /// command-line arguments.
+    pub fn default_enum_tagging(&self) -> humblegen::ast::EnumTagging {
+        match (&self.enum_tag, &self.enum_content) {
+            (Some(tag), Some(content)) => humblegen::ast::EnumTagging::Adjacent {
+                tag: tag.clone(),
+                content: content.clone(),
+            },
+            (Some(tag), None) => humblegen::ast::EnumTagging::Internal { tag: tag.clone() },
+            (None, _) => humblegen::ast::EnumTagging::External,
+        }
+    }
+
+    /// The output path, which is required unless `--diff` or `--stats` is given.
+    pub fn output(&self) -> &path::Path {
+        self.output
+            .as_deref()
+            .expect("`--output` is required unless `--diff` or `--stats` is given")
+    }
+
     /// Dynamcally select and instantiate the correct backend for the given
     /// command-line arguments.
     ///
@@ -98,19 +235,46 @@ impl CliArgs {
     /// requesting server endpoints for elm -- a client-side programming language --
     /// will result in an error.
     pub fn code_generator(&self) -> Result<Box<dyn humblegen::CodeGenerator>, CliError> {
-        match self.backend {
+        let backend = self
+            .backend
+            .expect("`--language` is required unless `--diff` or `--stats` is given");
+        match backend {
             Backend::Rust => Ok(Box::new(
                 humblegen::backend::rust::Generator::new(*self.artifacts)
-                    .map_err(CliError::LibraryError)?,
+                    .map_err(CliError::LibraryError)?
+                    .with_default_enum_tagging(self.default_enum_tagging())
+                    .with_deny_unknown_fields(
+                        self.deny_unknown_fields
+                            .as_ref()
+                            .map(|deny_unknown_fields| deny_unknown_fields.0)
+                            .unwrap_or_default(),
+                    )
+                    .with_server_runtime(
+                        self.server_runtime
+                            .as_ref()
+                            .map(|server_runtime| server_runtime.0)
+                            .unwrap_or_default(),
+                    )
+                    .with_rustfmt(!self.no_rustfmt)
+                    .with_round_trip_tests(self.round_trip_tests),
             )),
             Backend::Elm => Ok(Box::new(
                 humblegen::backend::elm::Generator::new(
                     *self.artifacts,
                     self.elm_module_root.clone(),
                 )
-                .map_err(CliError::LibraryError)?,
+                .map_err(CliError::LibraryError)?
+                .with_default_enum_tagging(self.default_enum_tagging()),
             )),
             Backend::Docs => Ok(Box::new(humblegen::backend::docs::Generator::default())),
+            Backend::OpenApi => Ok(Box::new(humblegen::backend::openapi::Generator::default())),
+            Backend::JsonSchema => Ok(Box::new(
+                humblegen::backend::jsonschema::Generator::default(),
+            )),
+            Backend::TypeScript => Ok(Box::new(
+                humblegen::backend::typescript::Generator::default()
+                    .with_default_enum_tagging(self.default_enum_tagging()),
+            )),
         }
     }
 }
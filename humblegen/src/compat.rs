@@ -0,0 +1,521 @@
+//! Compatibility check between two versions of a spec.
+//!
+//! [`diff`] compares the types and services of an old and a new version of a spec and
+//! classifies each change by whether it breaks peers built against the old version, e.g.
+//! clients sending messages that no longer deserialize or expecting fields that are no longer
+//! sent.
+//!
+//! # Example
+//!
+//! ```
+//! let old = humblegen::parse("struct Monster { name: str, hp: i32 }".as_bytes())?;
+//! let new = humblegen::parse("struct Monster { name: str }".as_bytes())?;
+//! let report = humblegen::compat::diff(&old, &new);
+//! assert!(report.is_breaking());
+//! assert_eq!(report.to_string(), "breaking: field `hp` of `Monster` was removed\n");
+//! # Ok::<(), humblegen::LibError>(())
+//! ```
+
+use crate::ast;
+use std::{collections::BTreeMap, fmt};
+
+/// The kind of a change between two versions of a spec.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeKind {
+    /// A struct or enum was added.
+    TypeAdded,
+    /// A struct or enum was removed.
+    TypeRemoved,
+    /// A type changed between struct and enum, or an enum changed its representation.
+    TypeChanged,
+    /// A field was added to a struct or struct variant. Fields that are `option[T]` or have a
+    /// default value may be missing from messages of old peers, other fields may not.
+    FieldAdded { optional: bool },
+    /// A field was removed from a struct or struct variant.
+    FieldRemoved,
+    /// The type of a field changed.
+    FieldTypeChanged,
+    /// A variant was added to an enum. Peers have to be prepared for new variants of enums
+    /// that were `@non_exhaustive` already.
+    VariantAdded { non_exhaustive: bool },
+    /// A variant was removed from an enum.
+    VariantRemoved,
+    /// The data carried by a variant or its representation on the wire changed.
+    VariantChanged,
+    /// A service was added.
+    ServiceAdded,
+    /// A service was removed.
+    ServiceRemoved,
+    /// An endpoint was added to a service.
+    EndpointAdded,
+    /// An endpoint was removed from a service.
+    EndpointRemoved,
+    /// The parameters, request body or response of an endpoint changed.
+    EndpointChanged,
+}
+
+impl ChangeKind {
+    /// Whether peers built against the old version of the spec may fail to communicate with
+    /// ones built against the new version.
+    pub fn is_breaking(self) -> bool {
+        match self {
+            ChangeKind::TypeAdded | ChangeKind::ServiceAdded | ChangeKind::EndpointAdded => false,
+            ChangeKind::FieldAdded { optional } => !optional,
+            ChangeKind::VariantAdded { non_exhaustive } => !non_exhaustive,
+            ChangeKind::TypeRemoved
+            | ChangeKind::TypeChanged
+            | ChangeKind::FieldRemoved
+            | ChangeKind::FieldTypeChanged
+            | ChangeKind::VariantRemoved
+            | ChangeKind::VariantChanged
+            | ChangeKind::ServiceRemoved
+            | ChangeKind::EndpointRemoved
+            | ChangeKind::EndpointChanged => true,
+        }
+    }
+}
+
+/// A change between two versions of a spec.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Change {
+    /// The kind of the change.
+    pub kind: ChangeKind,
+    /// What changed, e.g. ``field `hp` of `Monster` was removed``.
+    pub description: String,
+}
+
+impl Change {
+    /// Whether the change is breaking. See `ChangeKind::is_breaking`.
+    pub fn is_breaking(&self) -> bool {
+        self.kind.is_breaking()
+    }
+}
+
+/// Formats the change as e.g. ``breaking: field `hp` of `Monster` was removed``.
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let classification = if self.is_breaking() {
+            "breaking"
+        } else {
+            "non-breaking"
+        };
+        write!(f, "{}: {}", classification, self.description)
+    }
+}
+
+/// The changes between two versions of a spec, see [`diff`].
+#[derive(Debug, Clone, Default)]
+pub struct CompatReport {
+    /// The changes, types first, then services, each in the order of the new spec followed by
+    /// the removed ones.
+    pub changes: Vec<Change>,
+}
+
+impl CompatReport {
+    /// Whether any of the changes is breaking.
+    pub fn is_breaking(&self) -> bool {
+        self.changes.iter().any(Change::is_breaking)
+    }
+
+    /// Iterate over the breaking changes.
+    pub fn breaking_changes(&self) -> impl Iterator<Item = &Change> {
+        self.changes.iter().filter(|change| change.is_breaking())
+    }
+
+    fn push(&mut self, kind: ChangeKind, description: String) {
+        self.changes.push(Change { kind, description });
+    }
+}
+
+/// Formats the report with one change per line.
+impl fmt::Display for CompatReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for change in &self.changes {
+            writeln!(f, "{}", change)?;
+        }
+        Ok(())
+    }
+}
+
+/// Compare an old and a new version of a spec.
+///
+/// Types are matched by name, fields by their name on the wire and endpoints by their method
+/// and path, so renaming any of them shows up as a removal and an addition.
+pub fn diff(old: &ast::Spec, new: &ast::Spec) -> CompatReport {
+    let mut report = CompatReport::default();
+    diff_types(&mut report, old, new);
+    diff_services(&mut report, old, new);
+    report
+}
+
+/// A user defined type, which is compared as a whole.
+enum TypeDef<'a> {
+    Struct(&'a ast::StructDef),
+    Enum(&'a ast::EnumDef),
+}
+
+fn type_defs(spec: &ast::Spec) -> Vec<(&str, TypeDef<'_>)> {
+    spec.iter()
+        .filter_map(|item| match item {
+            ast::SpecItem::StructDef(sdef) => Some((sdef.name.as_str(), TypeDef::Struct(sdef))),
+            ast::SpecItem::EnumDef(edef) => Some((edef.name.as_str(), TypeDef::Enum(edef))),
+            ast::SpecItem::ServiceDef(_) => None,
+        })
+        .collect()
+}
+
+fn diff_types(report: &mut CompatReport, old: &ast::Spec, new: &ast::Spec) {
+    let old_types: BTreeMap<_, _> = type_defs(old).into_iter().collect();
+    let new_types = type_defs(new);
+
+    for (name, new_def) in &new_types {
+        match (old_types.get(name), new_def) {
+            (None, _) => report.push(ChangeKind::TypeAdded, format!("type `{}` was added", name)),
+            (Some(TypeDef::Struct(old_def)), TypeDef::Struct(new_def)) => diff_fields(
+                report,
+                &format!("`{}`", name),
+                (&old_def.fields, old_def.rename_all),
+                (&new_def.fields, new_def.rename_all),
+            ),
+            (Some(TypeDef::Enum(old_def)), TypeDef::Enum(new_def)) => {
+                diff_enums(report, old_def, new_def)
+            }
+            (Some(_), _) => report.push(
+                ChangeKind::TypeChanged,
+                format!("type `{}` changed between struct and enum", name),
+            ),
+        }
+    }
+
+    for name in old_types.keys() {
+        if !new_types.iter().any(|(new_name, _)| new_name == name) {
+            report.push(
+                ChangeKind::TypeRemoved,
+                format!("type `{}` was removed", name),
+            );
+        }
+    }
+}
+
+/// Compare the fields of a struct or struct variant, along with the `rename_all` rule that
+/// determines their names on the wire. `owner` names the struct or variant in descriptions.
+fn diff_fields(
+    report: &mut CompatReport,
+    owner: &str,
+    (old_fields, old_rename_all): (&ast::StructFields, Option<ast::RenameRule>),
+    (new_fields, new_rename_all): (&ast::StructFields, Option<ast::RenameRule>),
+) {
+    let old_fields: BTreeMap<_, _> = old_fields
+        .iter()
+        .map(|field| (field.wire_name(old_rename_all), field))
+        .collect();
+    let new_fields: Vec<_> = new_fields
+        .iter()
+        .map(|field| (field.wire_name(new_rename_all), field))
+        .collect();
+
+    for (wire_name, new_field) in &new_fields {
+        match old_fields.get(wire_name) {
+            None => {
+                let optional = matches!(new_field.pair.type_ident, ast::TypeIdent::Option(_))
+                    || new_field.default.is_some();
+                report.push(
+                    ChangeKind::FieldAdded { optional },
+                    format!("field `{}` was added to {}", wire_name, owner),
+                );
+            }
+            Some(old_field) => {
+                let old_type = old_field.pair.type_ident.to_string();
+                let new_type = new_field.pair.type_ident.to_string();
+                if old_type != new_type {
+                    report.push(
+                        ChangeKind::FieldTypeChanged,
+                        format!(
+                            "type of field `{}` of {} changed from `{}` to `{}`",
+                            wire_name, owner, old_type, new_type
+                        ),
+                    );
+                }
+            }
+        }
+    }
+
+    for wire_name in old_fields.keys() {
+        if !new_fields.iter().any(|(new_name, _)| new_name == wire_name) {
+            report.push(
+                ChangeKind::FieldRemoved,
+                format!("field `{}` of {} was removed", wire_name, owner),
+            );
+        }
+    }
+}
+
+fn diff_enums(report: &mut CompatReport, old_def: &ast::EnumDef, new_def: &ast::EnumDef) {
+    if old_def.tagging() != new_def.tagging() {
+        report.push(
+            ChangeKind::TypeChanged,
+            format!("representation of enum `{}` changed", new_def.name),
+        );
+    }
+
+    let old_variants: BTreeMap<_, _> = old_def
+        .variants
+        .iter()
+        .map(|variant| (variant.name.as_str(), variant))
+        .collect();
+
+    for new_variant in &new_def.variants {
+        let old_variant = match old_variants.get(new_variant.name.as_str()) {
+            Some(old_variant) => old_variant,
+            None => {
+                report.push(
+                    ChangeKind::VariantAdded {
+                        non_exhaustive: old_def.non_exhaustive,
+                    },
+                    format!(
+                        "variant `{}` was added to `{}`",
+                        new_variant.name, new_def.name
+                    ),
+                );
+                continue;
+            }
+        };
+
+        let owner = format!("variant `{}` of `{}`", new_variant.name, new_def.name);
+        if old_variant.wire_name() != new_variant.wire_name() {
+            report.push(
+                ChangeKind::VariantChanged,
+                format!(
+                    "value of {} changed from \"{}\" to \"{}\"",
+                    owner,
+                    old_variant.wire_name(),
+                    new_variant.wire_name()
+                ),
+            );
+        }
+        match (&old_variant.variant_type, &new_variant.variant_type) {
+            (ast::VariantType::Struct(old_fields), ast::VariantType::Struct(new_fields)) => {
+                diff_fields(report, &owner, (old_fields, None), (new_fields, None))
+            }
+            (old_type, new_type) => {
+                let old_type = variant_type_to_string(old_type);
+                let new_type = variant_type_to_string(new_type);
+                if old_type != new_type {
+                    report.push(
+                        ChangeKind::VariantChanged,
+                        format!(
+                            "data of {} changed from `{}` to `{}`",
+                            owner, old_type, new_type
+                        ),
+                    );
+                }
+            }
+        }
+    }
+
+    for old_variant in &old_def.variants {
+        if !new_def
+            .variants
+            .iter()
+            .any(|new_variant| new_variant.name == old_variant.name)
+        {
+            report.push(
+                ChangeKind::VariantRemoved,
+                format!(
+                    "variant `{}` of `{}` was removed",
+                    old_variant.name, old_def.name
+                ),
+            );
+        }
+    }
+}
+
+/// The data of a variant as it is written in a spec, e.g. `(str, i32)` or `{ .. }`.
+fn variant_type_to_string(variant_type: &ast::VariantType) -> String {
+    match variant_type {
+        ast::VariantType::Simple => "()".to_owned(),
+        ast::VariantType::Tuple(tuple) => tuple.to_string(),
+        ast::VariantType::Struct(_) => "{ .. }".to_owned(),
+        ast::VariantType::Newtype(ty) => format!("({})", ty),
+    }
+}
+
+fn diff_services(report: &mut CompatReport, old: &ast::Spec, new: &ast::Spec) {
+    let old_services: BTreeMap<_, _> = old
+        .iter()
+        .filter_map(ast::SpecItem::service_def)
+        .map(|service| (service.qualified_name("."), service))
+        .collect();
+    let new_services: Vec<_> = new
+        .iter()
+        .filter_map(ast::SpecItem::service_def)
+        .map(|service| (service.qualified_name("."), service))
+        .collect();
+
+    for (name, new_service) in &new_services {
+        match old_services.get(name) {
+            None => report.push(
+                ChangeKind::ServiceAdded,
+                format!("service `{}` was added", name),
+            ),
+            Some(old_service) => diff_endpoints(report, name, old_service, new_service),
+        }
+    }
+
+    for name in old_services.keys() {
+        if !new_services.iter().any(|(new_name, _)| new_name == name) {
+            report.push(
+                ChangeKind::ServiceRemoved,
+                format!("service `{}` was removed", name),
+            );
+        }
+    }
+}
+
+/// The method and path of an endpoint, including the base path of its service, e.g.
+/// `GET /v1/monsters/{id}`. Identifies the endpoint across versions.
+fn endpoint_key(service: &ast::ServiceDef, route: &ast::ServiceRoute) -> String {
+    let path = route
+        .components()
+        .iter()
+        .map(|c| match c {
+            ast::ServiceRouteComponent::Literal(literal) => format!("/{}", literal),
+            ast::ServiceRouteComponent::Variable(pair, _) => format!("/{{{}}}", pair.name),
+        })
+        .collect::<String>();
+    format!(
+        "{} {}{}",
+        route.http_method_as_str(),
+        service.base.as_deref().unwrap_or(""),
+        path
+    )
+}
+
+/// The parts of an endpoint's signature that peers depend on, as they are written in a spec,
+/// along with a name for each part used in descriptions.
+fn endpoint_signature(endpoint: &ast::ServiceEndpoint) -> Vec<(&'static str, String)> {
+    let route = &endpoint.route;
+    let fields = |fields: &[ast::FieldDefPair]| {
+        fields
+            .iter()
+            .map(|field| format!("{}: {}", field.name, field.type_ident))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+
+    let params: Vec<_> = route
+        .components()
+        .iter()
+        .filter_map(|c| match c {
+            ast::ServiceRouteComponent::Variable(pair, pattern) => Some(format!(
+                "{}: {} as /{}/",
+                pair.name,
+                pair.type_ident,
+                pattern.to_regex()
+            )),
+            ast::ServiceRouteComponent::Literal(_) => None,
+        })
+        .collect();
+    let headers: Vec<_> = route
+        .headers()
+        .iter()
+        .map(|header| format!("\"{}\": {}", header.name, header.type_ident))
+        .collect();
+
+    vec![
+        ("path parameters", params.join(", ")),
+        (
+            "query",
+            route
+                .query()
+                .as_ref()
+                .map(ToString::to_string)
+                .unwrap_or_else(|| fields(route.query_fields())),
+        ),
+        ("headers", headers.join(", ")),
+        (
+            "request body",
+            route
+                .request_body()
+                .map(ToString::to_string)
+                .unwrap_or_default(),
+        ),
+        ("response", response_to_string(route)),
+        ("status", route.status().to_string()),
+        (
+            "error status",
+            endpoint
+                .error_status
+                .map(|status| status.to_string())
+                .unwrap_or_default(),
+        ),
+    ]
+}
+
+/// The response of an endpoint as it is written in a spec, e.g. `stream[Monster]`.
+fn response_to_string(route: &ast::ServiceRoute) -> String {
+    let ret = route.return_type();
+    match route.response_kind() {
+        ast::ResponseKind::Raw => "raw bytes".to_owned(),
+        ast::ResponseKind::Stream => format!("stream[{}]", ret),
+        ast::ResponseKind::Paginated => format!("@paginated {}", ret),
+        ast::ResponseKind::Json | ast::ResponseKind::WebSocket | ast::ResponseKind::EventStream => {
+            ret.to_string()
+        }
+    }
+}
+
+fn diff_endpoints(
+    report: &mut CompatReport,
+    service_name: &str,
+    old_service: &ast::ServiceDef,
+    new_service: &ast::ServiceDef,
+) {
+    let old_endpoints: BTreeMap<_, _> = old_service
+        .endpoints
+        .iter()
+        .map(|endpoint| (endpoint_key(old_service, &endpoint.route), endpoint))
+        .collect();
+    let new_endpoints: Vec<_> = new_service
+        .endpoints
+        .iter()
+        .map(|endpoint| (endpoint_key(new_service, &endpoint.route), endpoint))
+        .collect();
+
+    for (key, new_endpoint) in &new_endpoints {
+        let old_endpoint = match old_endpoints.get(key) {
+            Some(old_endpoint) => old_endpoint,
+            None => {
+                report.push(
+                    ChangeKind::EndpointAdded,
+                    format!("endpoint `{}` was added to `{}`", key, service_name),
+                );
+                continue;
+            }
+        };
+
+        for ((part, old_part), (_, new_part)) in endpoint_signature(old_endpoint)
+            .into_iter()
+            .zip(endpoint_signature(new_endpoint))
+        {
+            if old_part != new_part {
+                report.push(
+                    ChangeKind::EndpointChanged,
+                    format!(
+                        "{} of endpoint `{}` of `{}` changed from `{}` to `{}`",
+                        part, key, service_name, old_part, new_part
+                    ),
+                );
+            }
+        }
+    }
+
+    for key in old_endpoints.keys() {
+        if !new_endpoints.iter().any(|(new_key, _)| new_key == key) {
+            report.push(
+                ChangeKind::EndpointRemoved,
+                format!("endpoint `{}` of `{}` was removed", key, service_name),
+            );
+        }
+    }
+}
@@ -6,7 +6,9 @@ pub use ast::Spec;
 
 pub mod ast;
 pub mod backend;
+pub mod compat;
 pub mod parser;
+pub mod stats;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -20,10 +22,125 @@ pub enum LibError {
     OutputMustBeFolder { backend: &'static str },
     #[error("backend '{backend}' expects output folder to be empty")]
     OutputFolderNotEmpty { backend: &'static str },
+    #[error("cannot derive `{derive}` for `{type_name}`: {reason}")]
+    UnsupportedDerive {
+        type_name: String,
+        derive: &'static str,
+        reason: String,
+    },
+    #[error(
+        "`u128` and `i128` are only supported as type of fields, route parameters, query fields \
+         and headers (optionally wrapped in `option[...]`), but {location} uses one elsewhere"
+    )]
+    UnsupportedWideInteger { location: String },
+    #[error(
+        "`duration` is only supported as type of fields (optionally wrapped in `option[...]`), \
+         but {location} uses one elsewhere"
+    )]
+    UnsupportedDuration { location: String },
+    #[error("{location} is not supported by the axum runtime: {reason}")]
+    AxumUnsupportedRoute {
+        location: String,
+        reason: &'static str,
+    },
+    #[error("elm only supports maps with `String` keys, but {location} uses `{key_type}` keys")]
+    ElmUnsupportedMapKey { key_type: String, location: String },
+    #[error("elm only supports sets of `comparable` elements, but {location} uses a set of `{element_type}`")]
+    ElmUnsupportedSetElement {
+        element_type: String,
+        location: String,
+    },
+    #[error("the elm functions of several endpoints of service `{service}` are named `{name}`")]
+    ElmEndpointNameCollision { service: String, name: String },
+    #[error("embed cycle: {}", chain.join(" -> "))]
+    EmbedCycle { chain: Vec<String> },
+    #[error(
+        "embeds are nested deeper than the maximum embed depth of {max_depth}: {}",
+        chain.join(" -> ")
+    )]
+    EmbedTooDeep {
+        max_depth: usize,
+        chain: Vec<String>,
+    },
+    #[error("`{patch}` is a patch of `{patch_of}`, which is not a struct")]
+    InvalidPatch { patch: String, patch_of: String },
+    #[error("patch cycle: {}", chain.join(" -> "))]
+    PatchCycle { chain: Vec<String> },
+    #[error("enum `{enum_name}` cannot use the default enum representation: {reason}")]
+    InvalidDefaultEnumTagging { enum_name: String, reason: String },
+    #[error("the {position} of the generated rust code cannot be tokenized: {reason}")]
+    InvalidInjectedCode {
+        /// `preamble` or `suffix`.
+        position: &'static str,
+        reason: String,
+    },
+    #[error(
+        "the query type `{type_name}` cannot use `@flatten_embeds`, since flattened fields \
+         cannot be deserialized from a URL query"
+    )]
+    FlattenedQueryEmbeds { type_name: String },
+    #[error("`@default_query` requires the query type `{type_name}` to be a struct using `@derive(Default)`")]
+    DefaultQueryWithoutDefault { type_name: String },
+    #[error("type `Page` is reserved for the envelope of `@paginated` endpoints")]
+    PageTypeConflict,
+    #[error("`{name}` is defined both as a service and as a namespace")]
+    NamespaceConflict { name: String },
+    #[error("import cycle: {}", chain.iter().map(|path| path.display().to_string()).collect::<Vec<_>>().join(" -> "))]
+    ImportCycle { chain: Vec<PathBuf> },
+    #[error("type `{name}` is defined in both {} and {}", first.display(), second.display())]
+    DuplicateType {
+        name: String,
+        first: PathBuf,
+        second: PathBuf,
+    },
     #[error(transparent)]
     IoError(#[from] io::Error),
-    #[error(transparent)]
-    ParseError(#[from] pest::error::Error<parser::Rule>),
+    /// The humble file is malformed. Displays as a snippet pointing at the offending location.
+    #[error("{error}")]
+    ParseError {
+        /// The path of the humble file, if it was parsed from a file.
+        path: Option<PathBuf>,
+        /// Line of the offending location, starting at 1.
+        line: usize,
+        /// Column of the offending location, starting at 1.
+        column: usize,
+        error: Box<pest::error::Error<parser::Rule>>,
+    },
+}
+
+impl From<pest::error::Error<parser::Rule>> for LibError {
+    fn from(error: pest::error::Error<parser::Rule>) -> Self {
+        let (line, column) = match error.line_col {
+            pest::error::LineColLocation::Pos(pos) => pos,
+            pest::error::LineColLocation::Span(start, _) => start,
+        };
+        LibError::ParseError {
+            path: None,
+            line,
+            column,
+            error: Box::new(error),
+        }
+    }
+}
+
+impl LibError {
+    /// Attributes a parse error to the humble file at `path`.
+    pub(crate) fn with_path(self, path: &Path) -> Self {
+        match self {
+            LibError::ParseError {
+                line,
+                column,
+                error,
+                ..
+            } => LibError::ParseError {
+                path: Some(path.to_owned()),
+                line,
+                column,
+                error: Box::new(error.with_path(&path.display().to_string())),
+            },
+            e => e,
+        }
+    }
 }
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
@@ -59,10 +176,36 @@ pub trait CodeGenerator {
     fn generate(&self, spec: &Spec, output: &Path) -> Result<(), LibError>;
 }
 
-pub fn parse<I: io::Read>(mut src: I) -> Result<ast::Spec, LibError> {
+/// Parse a humble spec, without support for imports. See `parse_file` for parsing a file.
+pub fn parse<I: io::Read>(src: I) -> Result<ast::Spec, LibError> {
+    parse_with_options(src, &parser::ParserOptions::default())
+}
+
+/// Parse the humble file at `path`, including all files it imports (relative to the importing
+/// file). Parse errors carry the path of the offending file.
+pub fn parse_file<P: AsRef<Path>>(
+    path: P,
+    options: &parser::ParserOptions,
+) -> Result<ast::Spec, LibError> {
+    parser::parse_file(path.as_ref(), options).map(|(spec, _)| spec)
+}
+
+/// Like `parse_file`, but also returns the canonical paths of the humble file and all files
+/// it imports.
+pub fn parse_file_with_imports<P: AsRef<Path>>(
+    path: P,
+    options: &parser::ParserOptions,
+) -> Result<(ast::Spec, Vec<PathBuf>), LibError> {
+    parser::parse_file(path.as_ref(), options)
+}
+
+pub fn parse_with_options<I: io::Read>(
+    mut src: I,
+    options: &parser::ParserOptions,
+) -> Result<ast::Spec, LibError> {
     let mut input = String::new();
     src.read_to_string(&mut input).map_err(LibError::IoError)?;
-    Ok(parser::parse(&input).map_err(LibError::ParseError)?)
+    parser::parse(&input, options)
 }
 
 /// This method is intended for use form within a `build.rs` file.
@@ -70,7 +213,7 @@ pub fn parse<I: io::Read>(mut src: I) -> Result<ast::Spec, LibError> {
 /// Builds the specified humblefile using the Rust builder
 /// and writes the generated code to `$OUT_DIR/protocol.rs`.
 ///
-/// Outputs `rerun-if-changed` instructions for the given `src` path.
+/// Outputs `rerun-if-changed` instructions for the given `src` path and all files it imports.
 pub fn build<P: AsRef<Path>>(src: P) -> Result<(), LibError> {
     println!("cargo:rerun-if-changed={}", src.as_ref().display());
     let out_dir: PathBuf = std::env::var("OUT_DIR")
@@ -78,8 +221,10 @@ pub fn build<P: AsRef<Path>>(src: P) -> Result<(), LibError> {
         .into();
     let out_path = out_dir.join("protocol.rs");
 
-    let infile = std::fs::File::open(src)?;
-    let spec = parse(infile)?;
+    let (spec, paths) = parser::parse_file(src.as_ref(), &parser::ParserOptions::default())?;
+    for path in paths {
+        println!("cargo:rerun-if-changed={}", path.display());
+    }
     let generator = backend::rust::Generator::new(Artifact::ServerEndpoints)?;
     generator.generate(&spec, &out_path)?;
 
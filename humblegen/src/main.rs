@@ -1,6 +1,7 @@
 //! Humblegen code application
 
 mod cli;
+mod watch;
 
 use anyhow::{Context, Result};
 use structopt::StructOpt;
@@ -8,16 +9,37 @@ use structopt::StructOpt;
 fn main() -> Result<()> {
     let args = cli::CliArgs::from_args();
 
-    let spec_file = std::fs::File::open(&args.input).context(format!(
-        "unable to open specification file {:?}",
-        &args.input
-    ))?;
-    let spec = humblegen::parse(spec_file).context(format!(
+    if args.watch {
+        return watch::run(&args);
+    }
+
+    let spec = humblegen::parse_file(&args.input, &args.parser_options()).context(format!(
         "failed to parse specification file {:?}",
         &args.input
     ))?;
 
-    args.code_generator()?.generate(&spec, &args.output)?;
+    if let Some(old_input) = &args.diff {
+        let old_spec = humblegen::parse_file(old_input, &args.parser_options()).context(
+            format!("failed to parse specification file {:?}", old_input),
+        )?;
+        let report = humblegen::compat::diff(&old_spec, &spec);
+        print!("{}", report);
+        if report.is_breaking() {
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    if args.stats {
+        let stats = humblegen::stats::stats(&spec);
+        match args.stats_format {
+            cli::StatsFormat::Text => print!("{}", stats),
+            cli::StatsFormat::Json => println!("{:#}", stats.to_json()),
+        }
+        return Ok(());
+    }
+
+    args.code_generator()?.generate(&spec, args.output())?;
 
     Ok(())
 }
@@ -1,6 +1,7 @@
 //! The humble language parser.
 
 mod embeds;
+mod patches;
 
 use itertools::Itertools;
 use pest::Parser;
@@ -12,21 +13,265 @@ use pest_derive::Parser;
 struct HumbleParser;
 
 use crate::ast::*;
+use crate::LibError;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 
-/// Parse complete spec.
-pub(crate) fn parse(input: &str) -> Result<Spec, pest::error::Error<Rule>> {
+/// Options of the humble language parser.
+#[derive(Debug, Clone)]
+pub struct ParserOptions {
+    /// The maximum depth to which embeds are resolved (10 by default).
+    pub max_embed_depth: usize,
+}
+
+impl Default for ParserOptions {
+    fn default() -> Self {
+        Self {
+            max_embed_depth: 10,
+        }
+    }
+}
+
+/// Parse complete spec. Imports are not supported, since they are resolved relative to the
+/// importing file.
+pub(crate) fn parse(input: &str, options: &ParserOptions) -> Result<Spec, LibError> {
     let humbled = HumbleParser::parse(Rule::doc, input)?
         .next()
         .expect("grammar requires non-empty document");
 
-    let mut ast = Spec(humbled.into_inner().map(parse_spec_item).collect());
+    let mut pairs = humbled.into_inner();
+    let title = match pairs.peek() {
+        Some(pair) if pair.as_rule() == Rule::spec_title => {
+            Some(parse_spec_header(pairs.next().unwrap()))
+        }
+        _ => None,
+    };
+    let version = match pairs.peek() {
+        Some(pair) if pair.as_rule() == Rule::spec_version => {
+            Some(parse_spec_header(pairs.next().unwrap()))
+        }
+        _ => None,
+    };
+    let mut ast = Spec {
+        items: pairs
+            .map(|pair| match pair.as_rule() {
+                Rule::import_statement => Err(pest::error::Error::new_from_span(
+                    pest::error::ErrorVariant::CustomError {
+                        message: "imports are only supported when parsing a file".to_owned(),
+                    },
+                    pair.as_span(),
+                )),
+                _ => parse_spec_items(pair),
+            })
+            .collect::<Result<Vec<_>, _>>()?
+            .concat(),
+        title,
+        version,
+    };
 
     // AST transformations
-    embeds::resolve_embeds(&mut ast);
+    embeds::resolve_embeds(&mut ast, options.max_embed_depth)?;
+    patches::resolve_patches(&mut ast)?;
+    check_page_type(&ast)?;
+    check_namespaces(&ast)?;
+    check_default_queries(&ast)?;
 
     Ok(ast)
 }
 
+/// Parse the humble file at `path` along with all files it imports, merging their items
+/// into a single spec. Also returns the paths of all files read.
+pub(crate) fn parse_file(
+    path: &Path,
+    options: &ParserOptions,
+) -> Result<(Spec, Vec<PathBuf>), LibError> {
+    let mut importer = Importer::default();
+    importer.load(path)?;
+
+    let mut defined_in: HashMap<&str, &PathBuf> = HashMap::new();
+    for (path, spec_item) in &importer.items {
+        let name = match spec_item {
+            SpecItem::StructDef(sdef) => &sdef.name,
+            SpecItem::EnumDef(edef) => &edef.name,
+            SpecItem::ServiceDef(_) => continue,
+        };
+        if let Some(first) = defined_in.insert(name, path) {
+            return Err(LibError::DuplicateType {
+                name: name.clone(),
+                first: first.clone(),
+                second: path.clone(),
+            });
+        }
+    }
+
+    let mut ast = Spec {
+        items: importer
+            .items
+            .into_iter()
+            .map(|(_, spec_item)| spec_item)
+            .collect(),
+        title: importer.title,
+        version: importer.version,
+    };
+
+    // AST transformations
+    embeds::resolve_embeds(&mut ast, options.max_embed_depth)?;
+    patches::resolve_patches(&mut ast)?;
+    check_page_type(&ast)?;
+    check_namespaces(&ast)?;
+    check_default_queries(&ast)?;
+
+    Ok((ast, importer.loaded))
+}
+
+/// Reject a user-defined type named `Page` in specs with paginated endpoints, which would
+/// conflict with the generated envelope type.
+fn check_page_type(spec: &Spec) -> Result<(), LibError> {
+    let page_defined = spec.iter().any(|item| match item {
+        SpecItem::StructDef(sdef) => sdef.name == "Page",
+        SpecItem::EnumDef(edef) => edef.name == "Page",
+        SpecItem::ServiceDef(_) => false,
+    });
+    if spec.has_paginated_endpoints() && page_defined {
+        return Err(LibError::PageTypeConflict);
+    }
+    Ok(())
+}
+
+/// Reject services named like a namespace defined next to them, since both are represented by
+/// a variant of the same `Handler` enum in generated Rust code.
+fn check_namespaces(spec: &Spec) -> Result<(), LibError> {
+    let services: Vec<&ServiceDef> = spec.iter().filter_map(SpecItem::service_def).collect();
+    for service in &services {
+        let is_namespace = services.iter().any(|other| {
+            other.namespace.len() > service.namespace.len()
+                && other.namespace[..service.namespace.len()] == service.namespace[..]
+                && other.namespace[service.namespace.len()] == service.name
+        });
+        if is_namespace {
+            return Err(LibError::NamespaceConflict {
+                name: service.qualified_name("."),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Reject `@default_query` endpoints whose query type does not implement `Default`.
+fn check_default_queries(spec: &Spec) -> Result<(), LibError> {
+    let default_query_types = spec
+        .iter()
+        .filter_map(SpecItem::service_def)
+        .flat_map(|service| &service.endpoints)
+        .filter(|endpoint| endpoint.default_query)
+        .filter_map(|endpoint| match endpoint.route.query() {
+            Some(TypeIdent::UserDefined(name)) => Some(name),
+            _ => None,
+        });
+    for type_name in default_query_types {
+        let derives_default = spec.iter().any(|item| match item {
+            SpecItem::StructDef(sdef) => {
+                &sdef.name == type_name && sdef.derives.contains(&Derive::Default)
+            }
+            _ => false,
+        });
+        if !derives_default {
+            return Err(LibError::DefaultQueryWithoutDefault {
+                type_name: type_name.clone(),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Loads humble files, recursively following their imports.
+#[derive(Default)]
+struct Importer {
+    /// Canonical paths of the files currently being loaded, to detect import cycles.
+    stack: Vec<PathBuf>,
+    /// Canonical paths of all files loaded so far. Each file is loaded only once.
+    loaded: Vec<PathBuf>,
+    /// The items of all files loaded so far along with the path of their file,
+    /// items of imported files first.
+    items: Vec<(PathBuf, SpecItem)>,
+    /// The title of the file loaded first. Titles of imported files are ignored.
+    title: Option<String>,
+    /// The version of the file loaded first, like the title.
+    version: Option<String>,
+}
+
+impl Importer {
+    fn load(&mut self, path: &Path) -> Result<(), LibError> {
+        let canonical_path = path.canonicalize()?;
+        if let Some(start) = self.stack.iter().position(|p| *p == canonical_path) {
+            let mut chain = self.stack[start..].to_vec();
+            chain.push(canonical_path);
+            return Err(LibError::ImportCycle { chain });
+        }
+        if self.loaded.contains(&canonical_path) {
+            return Ok(());
+        }
+
+        let input = std::fs::read_to_string(path)?;
+        let humbled = HumbleParser::parse(Rule::doc, &input)
+            .map_err(|e| LibError::from(e).with_path(path))?
+            .next()
+            .expect("grammar requires non-empty document");
+
+        let is_root = self.loaded.is_empty() && self.stack.is_empty();
+        self.stack.push(canonical_path.clone());
+        for pair in humbled.into_inner() {
+            if pair.as_rule() == Rule::spec_title {
+                if is_root {
+                    self.title = Some(parse_spec_header(pair));
+                }
+            } else if pair.as_rule() == Rule::spec_version {
+                if is_root {
+                    self.version = Some(parse_spec_header(pair));
+                }
+            } else if pair.as_rule() == Rule::import_statement {
+                let span = pair.as_span();
+                let import = parse_string_literal(pair.into_inner().next().unwrap());
+                let import_path = path.parent().unwrap_or_else(|| Path::new("")).join(&import);
+                if !import_path.is_file() {
+                    let error = pest::error::Error::new_from_span(
+                        pest::error::ErrorVariant::CustomError {
+                            message: format!("cannot find imported file {:?}", import_path),
+                        },
+                        span,
+                    );
+                    return Err(LibError::from(error).with_path(path));
+                }
+                self.load(&import_path)?;
+            } else {
+                let spec_items =
+                    parse_spec_items(pair).map_err(|e| LibError::from(e).with_path(path))?;
+                self.items.extend(
+                    spec_items
+                        .into_iter()
+                        .map(|spec_item| (path.to_owned(), spec_item)),
+                );
+            }
+        }
+        self.stack.pop();
+        self.loaded.push(canonical_path);
+
+        Ok(())
+    }
+}
+
+/// The location of a parsed node.
+fn parse_span(span: pest::Span) -> Span {
+    let (line, col) = span.start_pos().line_col();
+    Span {
+        start: span.start(),
+        // rules ending in optional parts include the whitespace skipped looking for them
+        end: span.start() + span.as_str().trim_end().len(),
+        line,
+        col,
+    }
+}
+
 /// Parse a doc comment.
 ///
 /// Will peek at the `pairs` to see if the next item is a doc comment. If it is, remove it and
@@ -47,25 +292,116 @@ fn parse_doc_comment<'i>(pairs: &mut pest::iterators::Pairs<'i, Rule>) -> Option
 }
 
 /// Parse a struct definition.
-fn parse_struct_definition(pair: pest::iterators::Pair<Rule>) -> StructDef {
+fn parse_struct_definition(
+    pair: pest::iterators::Pair<Rule>,
+) -> Result<StructDef, pest::error::Error<Rule>> {
+    let span = parse_span(pair.as_span());
     let mut nodes = pair.into_inner();
 
     let doc_comment = parse_doc_comment(&mut nodes);
 
+    let mut rename_all = None;
+    let mut flatten_embeds = false;
+    let mut patch_of = None;
+    let mut derives = Vec::new();
+    let mut deprecated = None;
+    while let Some(annotation) = nodes
+        .peek()
+        .filter(|p| p.as_rule() == Rule::struct_annotation)
+    {
+        nodes.next().unwrap(); // consume what we peeked
+        let annotation = annotation.into_inner().next().unwrap();
+        match annotation.as_rule() {
+            Rule::struct_annotation_rename_all => {
+                rename_all = Some(parse_rename_rule(annotation.into_inner().next().unwrap())?);
+            }
+            Rule::struct_annotation_flatten_embeds => flatten_embeds = true,
+            Rule::struct_annotation_patch_of => {
+                patch_of = Some(annotation.into_inner().next().unwrap().as_str().to_owned());
+            }
+            Rule::derive_annotation => derives.extend(parse_derive_annotation(annotation)?),
+            Rule::deprecated_annotation => {
+                deprecated = Some(parse_deprecated_annotation(annotation))
+            }
+            x => panic!("unexpected token {:?}", x),
+        }
+    }
+
     let name = nodes.next().unwrap().as_span().as_str().to_string();
-    let fields = parse_struct_fields(nodes.next().unwrap());
+    let fields = parse_struct_fields(nodes.next().unwrap())?;
 
-    StructDef {
+    Ok(StructDef {
         name,
         fields,
         doc_comment,
-    }
+        rename_all,
+        derives,
+        flatten_embeds,
+        patch_of,
+        deprecated,
+        span: Some(span),
+    })
+}
+
+/// Parse the trait names of a `@derive(...)` annotation.
+fn parse_derive_annotation(
+    pair: pest::iterators::Pair<Rule>,
+) -> Result<Vec<Derive>, pest::error::Error<Rule>> {
+    pair.into_inner()
+        .map(|ident| {
+            Derive::from_name(ident.as_str()).ok_or_else(|| {
+                pest::error::Error::new_from_span(
+                    pest::error::ErrorVariant::CustomError {
+                        message: format!(
+                            "cannot derive \"{}\", expected one of {}",
+                            ident.as_str(),
+                            Derive::ALL
+                                .iter()
+                                .map(|derive| format!("\"{}\"", derive.as_str()))
+                                .join(", ")
+                        ),
+                    },
+                    ident.as_span(),
+                )
+            })
+        })
+        .collect()
+}
+
+/// Parse the note of a `@deprecated("...")` annotation.
+fn parse_deprecated_annotation(pair: pest::iterators::Pair<Rule>) -> String {
+    parse_string_literal(pair.into_inner().next().unwrap())
+}
+
+/// Parse the string literal argument of `@rename_all`.
+fn parse_rename_rule(
+    pair: pest::iterators::Pair<Rule>,
+) -> Result<RenameRule, pest::error::Error<Rule>> {
+    let span = pair.as_span();
+    let name = parse_string_literal(pair);
+    RenameRule::from_name(&name).ok_or_else(|| {
+        pest::error::Error::new_from_span(
+            pest::error::ErrorVariant::CustomError {
+                message: format!(
+                    "unknown casing \"{}\", expected one of {}",
+                    name,
+                    RenameRule::ALL
+                        .iter()
+                        .map(|rule| format!("\"{}\"", rule.as_str()))
+                        .join(", ")
+                ),
+            },
+            span,
+        )
+    })
 }
 
 /// Parse inner struct fields of struct definition.
-fn parse_struct_fields(pair: pest::iterators::Pair<Rule>) -> StructFields {
+fn parse_struct_fields(
+    pair: pest::iterators::Pair<Rule>,
+) -> Result<StructFields, pest::error::Error<Rule>> {
     let pair = pair;
-    let fields: Vec<_> = pair
+    let fields = pair
         .into_inner()
         .map(|p| {
             assert_eq!(p.as_rule(), Rule::struct_field_def);
@@ -84,73 +420,228 @@ fn parse_struct_fields(pair: pest::iterators::Pair<Rule>) -> StructFields {
                     // and that struct type names are PascalCase
                     // => a struct type name is never a valid field name
                     // ==> for embeds, use the struct type name as field name and do the fixup in spec_resolve_embeds
+                    let span = parse_span(struct_field_def.as_span());
                     let mut nodes = struct_field_def.into_inner();
                     let ty = nodes.next().unwrap();
                     assert_eq!(nodes.next(), None);
-                    FieldNode {
+                    Ok(FieldNode {
                         doc_comment: None,
+                        rename: None,
+                        embedded_from: None,
+                        default: None,
+                        bytes_encoding: BytesEncoding::default(),
+                        deprecated: None,
+                        span: Some(span),
                         pair: FieldDefPair {
                             name: ty.as_span().as_str().to_string(),
                             type_ident: parse_type_ident(ty),
+                            range: None,
                         },
-                    }
+                    })
                 }
                 x => panic!("unexpected token {:?}", x),
             }
         })
-        .collect();
-    StructFields(fields)
+        .collect::<Result<_, _>>()?;
+    Ok(StructFields(fields))
 }
 
 /// Parse enum definition.
-fn parse_enum_definition(pair: pest::iterators::Pair<Rule>) -> EnumDef {
+fn parse_enum_definition(
+    pair: pest::iterators::Pair<Rule>,
+) -> Result<EnumDef, pest::error::Error<Rule>> {
+    let span = parse_span(pair.as_span());
     let mut outer_nodes = pair.into_inner();
     let doc_comment = parse_doc_comment(&mut outer_nodes);
+
+    let mut non_exhaustive = false;
+    let mut derives = Vec::new();
+    let mut tagging = None;
+    let mut deprecated = None;
+    while let Some(annotation) = outer_nodes
+        .peek()
+        .filter(|p| p.as_rule() == Rule::enum_annotation)
+    {
+        outer_nodes.next().unwrap(); // consume what we peeked
+        let annotation = annotation.into_inner().next().unwrap();
+        match annotation.as_rule() {
+            Rule::enum_annotation_non_exhaustive => non_exhaustive = true,
+            Rule::enum_annotation_tag => tagging = Some(parse_enum_tag_annotation(annotation)),
+            Rule::derive_annotation => derives.extend(parse_derive_annotation(annotation)?),
+            Rule::deprecated_annotation => {
+                deprecated = Some(parse_deprecated_annotation(annotation))
+            }
+            x => panic!("unexpected token {:?}", x),
+        }
+    }
+
     let mut nodes = outer_nodes.next().unwrap().into_inner();
     let name = nodes.next().unwrap().as_span().as_str().to_string();
-    let variants = nodes.map(parse_enum_variant_def).collect();
+    let mut variants = Vec::new();
+    for node in nodes {
+        let span = node.as_span();
+        let variant = parse_enum_variant_def(node)?;
+        if let Some(other) = variants
+            .iter()
+            .find(|other: &&VariantDef| other.wire_name() == variant.wire_name())
+        {
+            return Err(pest::error::Error::new_from_span(
+                pest::error::ErrorVariant::CustomError {
+                    message: format!(
+                        "variant `{}` is represented as \"{}\", just like variant `{}`",
+                        variant.name,
+                        variant.wire_name(),
+                        other.name
+                    ),
+                },
+                span,
+            ));
+        }
+        variants.push(variant);
+    }
 
-    EnumDef {
+    let tagging = match tagging {
+        Some((tagging, span)) => {
+            check_enum_tagging(&tagging, &variants).map_err(|message| {
+                pest::error::Error::new_from_span(
+                    pest::error::ErrorVariant::CustomError { message },
+                    span,
+                )
+            })?;
+            Some(tagging)
+        }
+        None => None,
+    };
+
+    Ok(EnumDef {
         name,
         variants,
         doc_comment,
+        non_exhaustive,
+        derives,
+        tagging,
+        deprecated,
+        span: Some(span),
+    })
+}
+
+/// Parse a `@tag("tag")` or `@tag("tag", content = "content")` annotation.
+fn parse_enum_tag_annotation(pair: pest::iterators::Pair<Rule>) -> (EnumTagging, pest::Span) {
+    let span = pair.as_span();
+    let mut nodes = pair.into_inner();
+    let tag = parse_string_literal(nodes.next().unwrap());
+    let tagging = match nodes.next() {
+        Some(content) => EnumTagging::Adjacent {
+            tag,
+            content: parse_string_literal(content),
+        },
+        None => EnumTagging::Internal { tag },
+    };
+    (tagging, span)
+}
+
+/// Check that the variants of an enum can be represented using `tagging`, returning the reason
+/// if they cannot.
+pub(crate) fn check_enum_tagging(
+    tagging: &EnumTagging,
+    variants: &[VariantDef],
+) -> Result<(), String> {
+    match tagging {
+        EnumTagging::External => {}
+        EnumTagging::Internal { tag } => {
+            for variant in variants {
+                match &variant.variant_type {
+                    VariantType::Simple => {}
+                    VariantType::Struct(fields) => {
+                        if fields.iter().any(|field| &field.wire_name(None) == tag) {
+                            return Err(format!(
+                                "tag \"{}\" conflicts with a field of variant `{}`",
+                                tag, variant.name
+                            ));
+                        }
+                    }
+                    VariantType::Tuple(_) | VariantType::Newtype(_) => {
+                        return Err(format!(
+                            "internally tagged enums only support simple and struct variants, \
+                             but variant `{}` is not one",
+                            variant.name
+                        ));
+                    }
+                }
+            }
+        }
+        EnumTagging::Adjacent { tag, content } => {
+            if tag == content {
+                return Err(format!(
+                    "tag and content must be different, but both are \"{}\"",
+                    tag
+                ));
+            }
+        }
     }
+    Ok(())
 }
 
 /// Parse enum variant definitions.
-fn parse_enum_variant_def(pair: pest::iterators::Pair<Rule>) -> VariantDef {
+fn parse_enum_variant_def(
+    pair: pest::iterators::Pair<Rule>,
+) -> Result<VariantDef, pest::error::Error<Rule>> {
+    let span = parse_span(pair.as_span());
     let mut nodes = pair.into_inner();
     let doc_comment = parse_doc_comment(&mut nodes);
+    let mut value = None;
+    while let Some(annotation) = nodes
+        .peek()
+        .filter(|p| p.as_rule() == Rule::variant_annotation)
+    {
+        nodes.next().unwrap(); // consume what we peeked
+        let annotation = annotation.into_inner().next().unwrap();
+        match annotation.as_rule() {
+            Rule::variant_annotation_value => {
+                let span = annotation.as_span();
+                let literal = parse_string_literal(annotation.into_inner().next().unwrap());
+                value = Some((literal, span));
+            }
+            x => panic!("unexpected token {:?}", x),
+        }
+    }
     let name = nodes.next().unwrap().as_span().as_str().to_string();
 
-    if let Some(var) = nodes.next() {
-        match var.as_rule() {
-            Rule::struct_fields => VariantDef {
-                name,
-                variant_type: VariantType::Struct(parse_struct_fields(var)),
-                doc_comment,
-            },
-            Rule::tuple_def => VariantDef {
-                name,
-                variant_type: VariantType::Tuple(parse_tuple_def(var)),
-                doc_comment,
-            },
-            Rule::newtype_def => VariantDef {
-                name,
-                variant_type: VariantType::Newtype(parse_type_ident(
-                    var.into_inner().next().unwrap(),
-                )),
-                doc_comment,
-            },
+    let variant_type = match nodes.next() {
+        Some(var) => match var.as_rule() {
+            Rule::struct_fields => VariantType::Struct(parse_struct_fields(var)?),
+            Rule::tuple_def => VariantType::Tuple(parse_tuple_def(var)),
+            Rule::newtype_def => {
+                VariantType::Newtype(parse_type_ident(var.into_inner().next().unwrap()))
+            }
             _ => unreachable!(dbg!(var)),
+        },
+        None => VariantType::Simple,
+    };
+
+    let value = match value {
+        Some((_, span)) if !matches!(variant_type, VariantType::Simple) => {
+            return Err(pest::error::Error::new_from_span(
+                pest::error::ErrorVariant::CustomError {
+                    message: format!(
+                        "`@value` can only be used on simple variants, but variant `{}` is not one",
+                        name
+                    ),
+                },
+                span,
+            ));
         }
-    } else {
-        VariantDef {
-            name,
-            variant_type: VariantType::Simple,
-            doc_comment,
-        }
-    }
+        Some((value, _)) => Some(value),
+        None => None,
+    };
+
+    Ok(VariantDef {
+        name,
+        variant_type,
+        doc_comment,
+        value,
+        span: Some(span),
+    })
 }
 
 fn parse_struct_field_def_pair(pair: pest::iterators::Pair<Rule>) -> FieldDefPair {
@@ -159,120 +650,1015 @@ fn parse_struct_field_def_pair(pair: pest::iterators::Pair<Rule>) -> FieldDefPai
     let name = nodes.next().unwrap().as_span().as_str().to_string();
     let type_ident = parse_type_ident(nodes.next().unwrap());
     assert_eq!(nodes.next(), None);
-    FieldDefPair { name, type_ident }
+    FieldDefPair {
+        name,
+        type_ident,
+        range: None,
+    }
 }
 
 /// Parse field definitions in struct.
-fn parse_struct_field_def_node(pair: pest::iterators::Pair<Rule>) -> FieldNode {
-    let pair = pair;
+fn parse_struct_field_def_node(
+    pair: pest::iterators::Pair<Rule>,
+) -> Result<FieldNode, pest::error::Error<Rule>> {
+    let span = parse_span(pair.as_span());
     let mut nodes = pair.into_inner();
     let doc_comment = parse_doc_comment(&mut nodes);
-    let pair = parse_struct_field_def_pair(nodes.next().unwrap());
-    FieldNode { pair, doc_comment }
+    let mut rename = None;
+    let mut bytes_encoding = None;
+    let mut deprecated = None;
+    while let Some(annotation) = nodes
+        .peek()
+        .filter(|p| p.as_rule() == Rule::field_annotation)
+    {
+        nodes.next().unwrap(); // consume what we peeked
+        let annotation = annotation.into_inner().next().unwrap();
+        match annotation.as_rule() {
+            Rule::field_annotation_rename => {
+                rename = Some(parse_string_literal(
+                    annotation.into_inner().next().unwrap(),
+                ));
+            }
+            Rule::field_annotation_encoding => {
+                let span = annotation.as_span();
+                let encoding = parse_bytes_encoding(annotation.into_inner().next().unwrap())?;
+                bytes_encoding = Some((encoding, span));
+            }
+            Rule::deprecated_annotation => {
+                deprecated = Some(parse_deprecated_annotation(annotation))
+            }
+            x => panic!("unexpected token {:?}", x),
+        }
+    }
+    let pair_node = nodes.next().unwrap();
+    let type_name = pair_node.clone().into_inner().nth(1).unwrap().as_str();
+    let mut pair = parse_struct_field_def_pair(pair_node);
+    if let Some(range) = nodes
+        .peek()
+        .filter(|p| p.as_rule() == Rule::range_constraint)
+    {
+        nodes.next().unwrap(); // consume what we peeked
+        pair.range = Some(parse_range_constraint(range, &pair.type_ident, type_name)?);
+    }
+    let default = match nodes.next() {
+        Some(default) => {
+            let span = default.as_span();
+            let default = parse_field_default(default, &pair.type_ident, type_name)?;
+            match (pair.range, &default) {
+                (Some(range), DefaultValue::Int(i)) if !range.contains(*i) => {
+                    return Err(pest::error::Error::new_from_span(
+                        pest::error::ErrorVariant::CustomError {
+                            message: format!(
+                                "default value {} is not in the range {} of field `{}`",
+                                i, range, pair.name
+                            ),
+                        },
+                        span,
+                    ));
+                }
+                _ => Some(default),
+            }
+        }
+        None => None,
+    };
+    let bytes_encoding = match bytes_encoding {
+        Some((_, span)) if !matches!(pair.type_ident, TypeIdent::BuiltIn(AtomType::Bytes)) => {
+            return Err(pest::error::Error::new_from_span(
+                pest::error::ErrorVariant::CustomError {
+                    message: format!(
+                        "@encoding is only supported for \"bytes\" fields, \
+                         but field `{}` is of type \"{}\"",
+                        pair.name, type_name
+                    ),
+                },
+                span,
+            ));
+        }
+        Some((encoding, _)) => encoding,
+        None => BytesEncoding::default(),
+    };
+    Ok(FieldNode {
+        pair,
+        doc_comment,
+        rename,
+        embedded_from: None,
+        default,
+        bytes_encoding,
+        deprecated,
+        span: Some(span),
+    })
+}
+
+/// Parse the string literal argument of `@encoding`.
+fn parse_bytes_encoding(
+    pair: pest::iterators::Pair<Rule>,
+) -> Result<BytesEncoding, pest::error::Error<Rule>> {
+    let span = pair.as_span();
+    let name = parse_string_literal(pair);
+    BytesEncoding::from_name(&name).ok_or_else(|| {
+        pest::error::Error::new_from_span(
+            pest::error::ErrorVariant::CustomError {
+                message: format!(
+                    "unknown encoding \"{}\", expected one of {}",
+                    name,
+                    BytesEncoding::ALL
+                        .iter()
+                        .map(|encoding| format!("\"{}\"", encoding.as_str()))
+                        .join(", ")
+                ),
+            },
+            span,
+        )
+    })
+}
+
+/// The smallest and largest value of an integer type, as far as they can be written in a spec.
+fn integer_bounds(type_ident: &TypeIdent) -> Option<(i64, i64)> {
+    match type_ident {
+        TypeIdent::BuiltIn(AtomType::I8) => Some((i8::MIN.into(), i8::MAX.into())),
+        TypeIdent::BuiltIn(AtomType::I16) => Some((i16::MIN.into(), i16::MAX.into())),
+        TypeIdent::BuiltIn(AtomType::I32) => Some((i32::MIN.into(), i32::MAX.into())),
+        TypeIdent::BuiltIn(AtomType::I64) => Some((i64::MIN, i64::MAX)),
+        TypeIdent::BuiltIn(AtomType::U8) => Some((0, u8::MAX.into())),
+        TypeIdent::BuiltIn(AtomType::U16) => Some((0, u16::MAX.into())),
+        TypeIdent::BuiltIn(AtomType::U32) => Some((0, u32::MAX.into())),
+        TypeIdent::BuiltIn(AtomType::U64) => Some((0, i64::MAX)),
+        _ => None,
+    }
+}
+
+/// Parse the range constraint of a field of type `type_ident`, which is named `type_name` in the
+/// spec. Ranges are only supported for integer fields, which may be optional.
+fn parse_range_constraint(
+    pair: pest::iterators::Pair<Rule>,
+    type_ident: &TypeIdent,
+    type_name: &str,
+) -> Result<RangeConstraint, pest::error::Error<Rule>> {
+    // `Span` is not `Copy` in all supported pest versions, so the closure creates a new one
+    let error = |message: String| {
+        pest::error::Error::new_from_span(
+            pest::error::ErrorVariant::CustomError { message },
+            pair.as_span(),
+        )
+    };
+
+    let bounds = match type_ident {
+        TypeIdent::Option(inner) | TypeIdent::Nullable(inner) => integer_bounds(inner),
+        type_ident => integer_bounds(type_ident),
+    };
+    let (type_min, type_max) = bounds.ok_or_else(|| {
+        error(format!(
+            "ranges are only supported for integer fields, but the field is of type \"{}\"",
+            type_name
+        ))
+    })?;
+
+    let mut min = None;
+    let mut max = None;
+    let mut inclusive = false;
+    for node in pair.clone().into_inner() {
+        let bound = || {
+            node.as_str().trim().parse::<i64>().map_err(|_| {
+                error(format!(
+                    "{} is out of range for type \"{}\"",
+                    node.as_str().trim(),
+                    type_name
+                ))
+            })
+        };
+        match node.as_rule() {
+            Rule::range_min => min = Some(bound()?),
+            Rule::range_max => max = Some(bound()?),
+            Rule::range_inclusive => inclusive = true,
+            Rule::range_exclusive => inclusive = false,
+            x => panic!("unexpected token {:?}", x),
+        }
+    }
+    let max = match max {
+        // the upper bound of an exclusive range is not part of it
+        Some(max) if !inclusive => Some(
+            max.checked_sub(1)
+                .ok_or_else(|| error(format!("the range ..{} is empty", max)))?,
+        ),
+        Some(max) => Some(max),
+        None if inclusive => {
+            return Err(error("an inclusive range needs an upper bound".to_owned()))
+        }
+        None => None,
+    };
+    if min.is_none() && max.is_none() {
+        return Err(error("a range needs a lower or an upper bound".to_owned()));
+    }
+
+    let range = RangeConstraint { min, max };
+    let out_of_type = |bound: Option<i64>| bound.is_some_and(|b| b < type_min || b > type_max);
+    if out_of_type(range.min) || out_of_type(range.max) {
+        return Err(error(format!(
+            "the range {} exceeds the values of type \"{}\"",
+            range, type_name
+        )));
+    }
+    if let (Some(min), Some(max)) = (range.min, range.max) {
+        if min > max {
+            return Err(error(format!("the range {} is empty", range)));
+        }
+    }
+    Ok(range)
 }
 
-fn parse_service_definition(pair: pest::iterators::Pair<Rule>) -> ServiceDef {
+/// Parse the default value of a field of type `type_ident`, which is named `type_name` in the
+/// spec. Defaults are only supported for `bool`, `str` and number fields.
+fn parse_field_default(
+    pair: pest::iterators::Pair<Rule>,
+    type_ident: &TypeIdent,
+    type_name: &str,
+) -> Result<DefaultValue, pest::error::Error<Rule>> {
+    let literal = pair.into_inner().next().unwrap();
+    let integer_range = integer_bounds(type_ident);
+    let is_float = matches!(
+        type_ident,
+        TypeIdent::BuiltIn(AtomType::F32) | TypeIdent::BuiltIn(AtomType::F64)
+    );
+
+    let value = match (literal.as_rule(), type_ident) {
+        (Rule::bool_literal, TypeIdent::BuiltIn(AtomType::Bool)) => {
+            Some(DefaultValue::Bool(literal.as_str() == "true"))
+        }
+        (Rule::string_literal, TypeIdent::BuiltIn(AtomType::Str)) => {
+            Some(DefaultValue::Str(parse_string_literal(literal.clone())))
+        }
+        (Rule::int_literal, _) if integer_range.is_some() => {
+            let (min, max) = integer_range.unwrap();
+            literal
+                .as_str()
+                .parse::<i64>()
+                .ok()
+                .filter(|i| (min..=max).contains(i))
+                .map(DefaultValue::Int)
+        }
+        (Rule::int_literal, _) | (Rule::float_literal, _) if is_float => literal
+            .as_str()
+            .parse::<f64>()
+            .ok()
+            .map(DefaultValue::Float),
+        _ => None,
+    };
+    value.ok_or_else(|| {
+        pest::error::Error::new_from_span(
+            pest::error::ErrorVariant::CustomError {
+                message: format!(
+                    "{} is not a valid default value for a field of type \"{}\", \
+                     defaults are supported for \"bool\", \"str\" and number fields",
+                    literal.as_str(),
+                    type_name
+                ),
+            },
+            literal.as_span(),
+        )
+    })
+}
+
+/// Parse a string literal, returning its contents without the surrounding quotes.
+fn parse_string_literal(pair: pest::iterators::Pair<Rule>) -> String {
+    pair.into_inner().next().unwrap().as_str().to_string()
+}
+
+/// Parse the `@title` or `@version` of a spec, returning its value.
+fn parse_spec_header(pair: pest::iterators::Pair<Rule>) -> String {
+    parse_string_literal(pair.into_inner().next().unwrap())
+}
+
+/// Parse a namespace, returning the services defined in it and its nested namespaces.
+/// `namespace` holds the names of the enclosing namespaces.
+fn parse_namespace_definition(
+    pair: pest::iterators::Pair<Rule>,
+    namespace: &[String],
+) -> Result<Vec<ServiceDef>, pest::error::Error<Rule>> {
+    let mut nodes = pair.into_inner();
+    let name = nodes.next().unwrap().as_span().as_str().to_string();
+    let mut namespace = namespace.to_vec();
+    namespace.push(name);
+    let mut services = Vec::new();
+    for node in nodes {
+        match node.as_rule() {
+            Rule::service_definition => {
+                services.push(parse_service_definition(node, &namespace)?);
+            }
+            Rule::namespace_definition => {
+                services.extend(parse_namespace_definition(node, &namespace)?);
+            }
+            _ => unreachable!("namespaces contain only services and namespaces"),
+        }
+    }
+    Ok(services)
+}
+
+fn parse_service_definition(
+    pair: pest::iterators::Pair<Rule>,
+    namespace: &[String],
+) -> Result<ServiceDef, pest::error::Error<Rule>> {
+    let span = parse_span(pair.as_span());
     let mut nodes = pair.into_inner();
     let doc_comment = parse_doc_comment(&mut nodes);
     let name = nodes.next().unwrap().as_span().as_str().to_string();
-    let endpoints = nodes
+    let mut rules = nodes.next().unwrap().into_inner().peekable();
+    let base = match rules.peek() {
+        Some(pair) if pair.as_rule() == Rule::service_base => {
+            Some(parse_service_base(rules.next().unwrap())?)
+        }
+        _ => None,
+    };
+    let mut endpoints = Vec::new();
+    let mut proxies = Vec::new();
+    for rule in rules {
+        match rule.as_rule() {
+            Rule::proxy_rule => proxies.push(parse_proxy_rule(rule)?),
+            _ => endpoints.push(parse_service_rule(rule)?),
+        }
+    }
+    assert_eq!(nodes.next(), None);
+    Ok(ServiceDef {
+        doc_comment,
+        name,
+        base,
+        endpoints,
+        proxies,
+        namespace: namespace.to_vec(),
+        span: Some(span),
+    })
+}
+
+/// Parse the `base` path of a service, which consists of literal route segments like `/v1`.
+fn parse_service_base(
+    pair: pest::iterators::Pair<Rule>,
+) -> Result<String, pest::error::Error<Rule>> {
+    let literal = pair.into_inner().next().unwrap();
+    let span = literal.as_span();
+    let base = parse_string_literal(literal);
+    let is_segment = |segment: &str| {
+        !segment.is_empty()
+            && segment
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    };
+    match base.strip_prefix('/') {
+        Some(segments) if segments.split('/').all(is_segment) => Ok(base),
+        _ => Err(pest::error::Error::new_from_span(
+            pest::error::ErrorVariant::CustomError {
+                message: format!(
+                    "invalid base \"{}\", expected a path of literal segments like \"/v1\"",
+                    base
+                ),
+            },
+            span,
+        )),
+    }
+}
+
+/// Parse a `PROXY /prefix/* -> "http://upstream"` rule.
+fn parse_proxy_rule(
+    pair: pest::iterators::Pair<Rule>,
+) -> Result<ProxyDef, pest::error::Error<Rule>> {
+    let mut nodes = pair.into_inner();
+    let doc_comment = parse_doc_comment(&mut nodes);
+    let prefix = nodes
         .next()
         .unwrap()
         .into_inner()
-        .map(parse_service_rule)
+        .map(|segment| segment.as_str().to_string())
         .collect();
+    let literal = nodes.next().unwrap();
+    let span = literal.as_span();
+    let upstream = parse_string_literal(literal);
     assert_eq!(nodes.next(), None);
-    ServiceDef {
-        doc_comment,
-        name,
-        endpoints,
+    let is_valid = matches!(
+        upstream.strip_prefix("http://"),
+        Some(rest) if !rest.is_empty() && !rest.starts_with('/') && !rest.contains('?')
+    );
+    if !is_valid {
+        return Err(pest::error::Error::new_from_span(
+            pest::error::ErrorVariant::CustomError {
+                message: format!(
+                    "invalid upstream \"{}\", expected an http URL like \"http://legacy:8080\"",
+                    upstream
+                ),
+            },
+            span,
+        ));
     }
+    Ok(ProxyDef {
+        doc_comment,
+        prefix,
+        upstream: upstream.trim_end_matches('/').to_string(),
+    })
 }
 
-fn parse_service_rule(pair: pest::iterators::Pair<Rule>) -> ServiceEndpoint {
+fn parse_service_rule(
+    pair: pest::iterators::Pair<Rule>,
+) -> Result<ServiceEndpoint, pest::error::Error<Rule>> {
+    let span = parse_span(pair.as_span());
     let mut nodes = pair.into_inner();
     let doc_comment = parse_doc_comment(&mut nodes);
-    let route = parse_service_rule_def(nodes.next().unwrap());
+    let mut deprecated = None;
+    let mut paginated = false;
+    let mut default_query = false;
+    let mut error_status = None;
+    let mut max_body_size = None;
+    while let Some(annotation) = nodes
+        .peek()
+        .filter(|p| p.as_rule() == Rule::endpoint_annotation)
+    {
+        nodes.next().unwrap(); // consume what we peeked
+        let annotation = annotation.into_inner().next().unwrap();
+        match annotation.as_rule() {
+            Rule::deprecated_annotation => {
+                deprecated = Some(parse_deprecated_annotation(annotation))
+            }
+            Rule::endpoint_annotation_paginated => paginated = true,
+            Rule::endpoint_annotation_default_query => default_query = true,
+            Rule::endpoint_annotation_error_status => {
+                error_status = Some(parse_error_status_annotation(annotation)?)
+            }
+            Rule::endpoint_annotation_max_body => {
+                max_body_size = Some(parse_max_body_annotation(annotation)?)
+            }
+            _ => unreachable!("{}", dbg!(annotation)),
+        }
+    }
+    let route_pair = nodes.next().unwrap();
+    let mut route = parse_service_rule_def(route_pair.clone())?;
     assert_eq!(nodes.next(), None);
-    ServiceEndpoint { doc_comment, route }
+    if paginated {
+        paginate_route(&mut route).map_err(|message| {
+            pest::error::Error::new_from_span(
+                pest::error::ErrorVariant::CustomError { message },
+                route_pair.as_span(),
+            )
+        })?;
+    }
+    if default_query && !matches!(route.query(), Some(TypeIdent::UserDefined(_))) {
+        return Err(pest::error::Error::new_from_span(
+            pest::error::ErrorVariant::CustomError {
+                message: "`@default_query` requires a query type like `?{MonsterQuery}`".to_owned(),
+            },
+            route_pair.as_span(),
+        ));
+    }
+    if error_status.is_some()
+        && (route.response_kind() != ResponseKind::Json
+            || !matches!(route.return_type(), TypeIdent::Result(..)))
+    {
+        return Err(pest::error::Error::new_from_span(
+            pest::error::ErrorVariant::CustomError {
+                message:
+                    "`@error_status` requires a return type like `result[Monster][MonsterError]`"
+                        .to_owned(),
+            },
+            route_pair.as_span(),
+        ));
+    }
+    if max_body_size.is_some() && route.request_body().is_none() {
+        return Err(pest::error::Error::new_from_span(
+            pest::error::ErrorVariant::CustomError {
+                message: "`@max_body` requires an endpoint with a request body".to_owned(),
+            },
+            route_pair.as_span(),
+        ));
+    }
+    Ok(ServiceEndpoint {
+        doc_comment,
+        route,
+        deprecated,
+        default_query,
+        error_status,
+        max_body_size,
+        span: Some(span),
+    })
+}
+
+/// Parse the status code of `Err` responses declared using `@error_status(404)`.
+fn parse_error_status_annotation(
+    pair: pest::iterators::Pair<Rule>,
+) -> Result<u16, pest::error::Error<Rule>> {
+    let code = pair.into_inner().next().unwrap();
+    let status: u16 = code.as_str().parse().unwrap();
+    match status {
+        400..=599 => Ok(status),
+        _ => Err(pest::error::Error::new_from_span(
+            pest::error::ErrorVariant::CustomError {
+                message: format!(
+                    "the status of error responses must be in the range 400..=599, but is {}",
+                    status
+                ),
+            },
+            code.as_span(),
+        )),
+    }
+}
+
+/// Parse the maximum size of request bodies declared using `@max_body(1mb)`. The units `kb`,
+/// `mb` and `gb` are powers of 1024, a size without a unit (or `b`) is in bytes.
+fn parse_max_body_annotation(
+    pair: pest::iterators::Pair<Rule>,
+) -> Result<usize, pest::error::Error<Rule>> {
+    let size = pair.into_inner().next().unwrap();
+    let text = size.as_str();
+    let digits_end = text
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(text.len());
+    let unit: usize = match &text[digits_end..] {
+        "" | "b" => 1,
+        "kb" => 1024,
+        "mb" => 1024 * 1024,
+        "gb" => 1024 * 1024 * 1024,
+        unit => unreachable!("unknown unit {}", unit),
+    };
+    let bytes = text[..digits_end]
+        .parse::<usize>()
+        .ok()
+        .and_then(|value| value.checked_mul(unit));
+    match bytes {
+        Some(bytes) if bytes > 0 => Ok(bytes),
+        _ => Err(pest::error::Error::new_from_span(
+            pest::error::ErrorVariant::CustomError {
+                message: format!(
+                    "the maximum body size must be greater than zero and fit into a `usize`, but is {}",
+                    text
+                ),
+            },
+            size.as_span(),
+        )),
+    }
 }
 
-fn parse_service_rule_def(pair: pest::iterators::Pair<Rule>) -> ServiceRoute {
+fn parse_service_rule_def(
+    pair: pest::iterators::Pair<Rule>,
+) -> Result<ServiceRoute, pest::error::Error<Rule>> {
     let mut nodes = pair.into_inner();
-    let parser = match nodes.peek().unwrap().as_rule() {
+    let method = nodes.next().unwrap();
+    let parser = match method.as_rule() {
         Rule::http_get => parse_service_rule_get,
         Rule::http_delete => parse_service_rule_delete,
+        Rule::http_options => parse_service_rule_options,
         Rule::http_post => parse_service_rule_post,
         Rule::http_put => parse_service_rule_put,
         Rule::http_patch => parse_service_rule_patch,
+        Rule::http_ws => parse_service_rule_ws,
+        Rule::http_sse => parse_service_rule_sse,
+        Rule::http_custom => {
+            let route = parse_service_rule_custom(method, &mut nodes)?;
+            assert_eq!(nodes.next(), None);
+            return Ok(route);
+        }
         x => panic!("unexpected token {:?}", x),
     };
-    nodes.next().unwrap(); // consume what we peeked
-    let route = parser(&mut nodes);
+    let route = parser(&mut nodes)?;
     assert_eq!(nodes.next(), None);
-    route
+    Ok(route)
 }
 
-fn parse_service_rule_get(pair: &mut pest::iterators::Pairs<Rule>) -> ServiceRoute {
-    ServiceRoute::Get {
-        components: parse_http_route(pair.next().unwrap()),
-        query: parse_http_query(pair),
-        ret: parse_type_ident(pair.next().unwrap()),
-    }
+fn parse_service_rule_get(
+    pair: &mut pest::iterators::Pairs<Rule>,
+) -> Result<ServiceRoute, pest::error::Error<Rule>> {
+    let components = parse_http_route(pair.next().unwrap())?;
+    let query = parse_http_query(pair);
+    let query_fields = parse_http_query_fields(pair);
+    let headers = parse_http_headers(pair);
+    let (ret, response_kind) = parse_service_return(pair.next().unwrap());
+    let status = parse_http_status(pair)?;
+    Ok(ServiceRoute::Get {
+        components,
+        query,
+        query_fields,
+        headers,
+        ret,
+        response_kind,
+        status,
+    })
 }
 
-fn parse_service_rule_delete(pair: &mut pest::iterators::Pairs<Rule>) -> ServiceRoute {
-    ServiceRoute::Delete {
-        components: parse_http_route(pair.next().unwrap()),
-        query: parse_http_query(pair),
-        ret: parse_type_ident(pair.next().unwrap()),
-    }
+fn parse_service_rule_delete(
+    pair: &mut pest::iterators::Pairs<Rule>,
+) -> Result<ServiceRoute, pest::error::Error<Rule>> {
+    let components = parse_http_route(pair.next().unwrap())?;
+    let query = parse_http_query(pair);
+    let query_fields = parse_http_query_fields(pair);
+    let headers = parse_http_headers(pair);
+    let (ret, response_kind) = parse_service_return(pair.next().unwrap());
+    let status = parse_http_status(pair)?;
+    Ok(ServiceRoute::Delete {
+        components,
+        query,
+        query_fields,
+        headers,
+        ret,
+        response_kind,
+        status,
+    })
+}
+
+fn parse_service_rule_options(
+    pair: &mut pest::iterators::Pairs<Rule>,
+) -> Result<ServiceRoute, pest::error::Error<Rule>> {
+    let components = parse_http_route(pair.next().unwrap())?;
+    let query = parse_http_query(pair);
+    let query_fields = parse_http_query_fields(pair);
+    let headers = parse_http_headers(pair);
+    let (ret, response_kind) = parse_service_return(pair.next().unwrap());
+    let status = parse_http_status(pair)?;
+    Ok(ServiceRoute::Options {
+        components,
+        query,
+        query_fields,
+        headers,
+        ret,
+        response_kind,
+        status,
+    })
 }
 
-fn parse_service_rule_post(pair: &mut pest::iterators::Pairs<Rule>) -> ServiceRoute {
-    ServiceRoute::Post {
-        components: parse_http_route(pair.next().unwrap()),
-        query: parse_http_query(pair),
-        body: parse_type_ident(pair.next().unwrap()),
-        ret: parse_type_ident(pair.next().unwrap()),
+/// Parse an endpoint using a custom method like `"PROPFIND"`, which has a request body if two
+/// types follow the route.
+fn parse_service_rule_custom(
+    method: pest::iterators::Pair<Rule>,
+    pair: &mut pest::iterators::Pairs<Rule>,
+) -> Result<ServiceRoute, pest::error::Error<Rule>> {
+    let method_name = method.clone().into_inner().next().unwrap().as_str();
+    let message = match method_name {
+        "GET" | "POST" | "PUT" | "PATCH" | "DELETE" | "OPTIONS" => Some(format!(
+            "`{}` endpoints are declared without quotes",
+            method_name
+        )),
+        "HEAD" => Some("`HEAD` requests are answered by `GET` endpoints".to_owned()),
+        _ => None,
+    };
+    if let Some(message) = message {
+        return Err(pest::error::Error::new_from_span(
+            pest::error::ErrorVariant::CustomError { message },
+            method.as_span(),
+        ));
     }
+
+    let components = parse_http_route(pair.next().unwrap())?;
+    let query = parse_http_query(pair);
+    let query_fields = parse_http_query_fields(pair);
+    let headers = parse_http_headers(pair);
+    let first = pair.next().unwrap();
+    let (body, ret) = match pair.peek() {
+        Some(next) if next.as_rule() != Rule::http_status => {
+            (Some(parse_type_ident(first)), pair.next().unwrap())
+        }
+        _ => (None, first),
+    };
+    let (ret, response_kind) = parse_service_return(ret);
+    let status = parse_http_status(pair)?;
+    Ok(ServiceRoute::Custom {
+        method: method_name.to_owned(),
+        components,
+        query,
+        query_fields,
+        headers,
+        body,
+        ret,
+        response_kind,
+        status,
+    })
 }
 
-fn parse_service_rule_put(pair: &mut pest::iterators::Pairs<Rule>) -> ServiceRoute {
-    ServiceRoute::Put {
-        components: parse_http_route(pair.next().unwrap()),
-        query: parse_http_query(pair),
-        body: parse_type_ident(pair.next().unwrap()),
-        ret: parse_type_ident(pair.next().unwrap()),
+fn parse_service_rule_post(
+    pair: &mut pest::iterators::Pairs<Rule>,
+) -> Result<ServiceRoute, pest::error::Error<Rule>> {
+    let components = parse_http_route(pair.next().unwrap())?;
+    let query = parse_http_query(pair);
+    let query_fields = parse_http_query_fields(pair);
+    let headers = parse_http_headers(pair);
+    let body = parse_type_ident(pair.next().unwrap());
+    let (ret, response_kind) = parse_service_return(pair.next().unwrap());
+    let status = parse_http_status(pair)?;
+    Ok(ServiceRoute::Post {
+        components,
+        query,
+        query_fields,
+        headers,
+        body,
+        ret,
+        response_kind,
+        status,
+    })
+}
+
+fn parse_service_rule_put(
+    pair: &mut pest::iterators::Pairs<Rule>,
+) -> Result<ServiceRoute, pest::error::Error<Rule>> {
+    let components = parse_http_route(pair.next().unwrap())?;
+    let query = parse_http_query(pair);
+    let query_fields = parse_http_query_fields(pair);
+    let headers = parse_http_headers(pair);
+    let body = parse_type_ident(pair.next().unwrap());
+    let (ret, response_kind) = parse_service_return(pair.next().unwrap());
+    let status = parse_http_status(pair)?;
+    Ok(ServiceRoute::Put {
+        components,
+        query,
+        query_fields,
+        headers,
+        body,
+        ret,
+        response_kind,
+        status,
+    })
+}
+
+fn parse_service_rule_patch(
+    pair: &mut pest::iterators::Pairs<Rule>,
+) -> Result<ServiceRoute, pest::error::Error<Rule>> {
+    let components = parse_http_route(pair.next().unwrap())?;
+    let query = parse_http_query(pair);
+    let query_fields = parse_http_query_fields(pair);
+    let headers = parse_http_headers(pair);
+    let body = parse_type_ident(pair.next().unwrap());
+    let (ret, response_kind) = parse_service_return(pair.next().unwrap());
+    let status = parse_http_status(pair)?;
+    Ok(ServiceRoute::Patch {
+        components,
+        query,
+        query_fields,
+        headers,
+        body,
+        ret,
+        response_kind,
+        status,
+    })
+}
+
+fn parse_service_rule_ws(
+    pair: &mut pest::iterators::Pairs<Rule>,
+) -> Result<ServiceRoute, pest::error::Error<Rule>> {
+    let components = parse_http_route(pair.next().unwrap())?;
+    let query = parse_http_query(pair);
+    let query_fields = parse_http_query_fields(pair);
+    let headers = parse_http_headers(pair);
+    let ret = parse_type_ident(pair.next().unwrap());
+    Ok(ServiceRoute::Ws {
+        components,
+        query,
+        query_fields,
+        headers,
+        ret,
+    })
+}
+
+fn parse_service_rule_sse(
+    pair: &mut pest::iterators::Pairs<Rule>,
+) -> Result<ServiceRoute, pest::error::Error<Rule>> {
+    let components = parse_http_route(pair.next().unwrap())?;
+    let query = parse_http_query(pair);
+    let query_fields = parse_http_query_fields(pair);
+    let headers = parse_http_headers(pair);
+    let ret = parse_type_ident(pair.next().unwrap());
+    Ok(ServiceRoute::Sse {
+        components,
+        query,
+        query_fields,
+        headers,
+        ret,
+    })
+}
+
+/// Turn the route of a `@paginated` endpoint returning `list[T]` into one returning pages of
+/// `T`, adding the `limit` and `offset` query fields.
+fn paginate_route(route: &mut ServiceRoute) -> Result<(), String> {
+    let (query, query_fields, ret, response_kind) = match route {
+        ServiceRoute::Get {
+            query,
+            query_fields,
+            ret,
+            response_kind,
+            ..
+        }
+        | ServiceRoute::Delete {
+            query,
+            query_fields,
+            ret,
+            response_kind,
+            ..
+        }
+        | ServiceRoute::Post {
+            query,
+            query_fields,
+            ret,
+            response_kind,
+            ..
+        }
+        | ServiceRoute::Put {
+            query,
+            query_fields,
+            ret,
+            response_kind,
+            ..
+        }
+        | ServiceRoute::Patch {
+            query,
+            query_fields,
+            ret,
+            response_kind,
+            ..
+        }
+        | ServiceRoute::Options {
+            query,
+            query_fields,
+            ret,
+            response_kind,
+            ..
+        }
+        | ServiceRoute::Custom {
+            query,
+            query_fields,
+            ret,
+            response_kind,
+            ..
+        } => (query, query_fields, ret, response_kind),
+        ServiceRoute::Ws { .. } | ServiceRoute::Sse { .. } => {
+            return Err("WS and SSE endpoints cannot be paginated".to_owned())
+        }
+    };
+    let item = match (&ret, *response_kind) {
+        (TypeIdent::List(item), ResponseKind::Json) => item.as_ref().clone(),
+        _ => return Err("only endpoints returning a `list[T]` can be paginated".to_owned()),
+    };
+    if query.is_some() {
+        return Err(
+            "paginated endpoints cannot have a query type, use query fields instead".to_owned(),
+        );
     }
+    if let Some(field) = query_fields
+        .iter()
+        .find(|field| field.name == "limit" || field.name == "offset")
+    {
+        return Err(format!(
+            "the query field `{}` is added by `@paginated`",
+            field.name
+        ));
+    }
+    for name in &["limit", "offset"] {
+        query_fields.push(FieldDefPair {
+            name: (*name).to_owned(),
+            type_ident: TypeIdent::Option(Box::new(TypeIdent::BuiltIn(AtomType::U32))),
+            range: None,
+        });
+    }
+    *ret = item;
+    *response_kind = ResponseKind::Paginated;
+    Ok(())
 }
 
-fn parse_service_rule_patch(pair: &mut pest::iterators::Pairs<Rule>) -> ServiceRoute {
-    ServiceRoute::Patch {
-        components: parse_http_route(pair.next().unwrap()),
-        query: parse_http_query(pair),
-        body: parse_type_ident(pair.next().unwrap()),
-        ret: parse_type_ident(pair.next().unwrap()),
+/// Parse the return type of an endpoint, returning how it is sent.
+fn parse_service_return(pair: pest::iterators::Pair<Rule>) -> (TypeIdent, ResponseKind) {
+    match pair.as_rule() {
+        Rule::http_raw_bytes => (TypeIdent::BuiltIn(AtomType::Bytes), ResponseKind::Raw),
+        Rule::http_stream => (
+            parse_type_ident(pair.into_inner().next().unwrap()),
+            ResponseKind::Stream,
+        ),
+        Rule::type_ident => (parse_type_ident(pair), ResponseKind::Json),
+        x => panic!("unexpected token {:?}", x),
     }
 }
 
-fn parse_http_route(pair: pest::iterators::Pair<Rule>) -> Vec<ServiceRouteComponent> {
-    pair.into_inner().map(parse_http_route_segment).collect()
+/// Parse the status code of successful responses, if declared using `status 201`.
+fn parse_http_status(
+    pairs: &mut pest::iterators::Pairs<Rule>,
+) -> Result<u16, pest::error::Error<Rule>> {
+    let pair = match pairs.peek() {
+        Some(pair) if pair.as_rule() == Rule::http_status => pairs.next().unwrap(),
+        _ => return Ok(200),
+    };
+    let code = pair.clone().into_inner().next().unwrap();
+    let status: u16 = code.as_str().parse().unwrap();
+    let message = match status {
+        204 | 205 => format!(
+            "status {} cannot be used, since responses always have a body",
+            status
+        ),
+        200..=299 => return Ok(status),
+        _ => format!(
+            "the status of successful responses must be in the range 200..=299, but is {}",
+            status
+        ),
+    };
+    Err(pest::error::Error::new_from_span(
+        pest::error::ErrorVariant::CustomError { message },
+        code.as_span(),
+    ))
+}
+
+fn parse_http_route(
+    pair: pest::iterators::Pair<Rule>,
+) -> Result<Vec<ServiceRouteComponent>, pest::error::Error<Rule>> {
+    let segments: Vec<_> = pair.into_inner().collect();
+    let components = segments
+        .iter()
+        .cloned()
+        .map(parse_http_route_segment)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // the generated route regex captures each param in a group named like it
+    let param_names: Vec<&str> = components
+        .iter()
+        .filter_map(|c| match c {
+            ServiceRouteComponent::Variable(pair, _) => Some(pair.name.as_str()),
+            ServiceRouteComponent::Literal(_) => None,
+        })
+        .collect();
+    for (segment, component) in segments.iter().zip(&components) {
+        if let ServiceRouteComponent::Variable(pair, RouteParamPattern::Regex(regex)) = component {
+            let regex = regex::Regex::new(regex).expect("validated by parse_route_param_pattern");
+            if let Some(name) = regex
+                .capture_names()
+                .flatten()
+                .find(|name| param_names.contains(name))
+            {
+                return Err(pest::error::Error::new_from_span(
+                    pest::error::ErrorVariant::CustomError {
+                        message: format!(
+                            "the regex of route parameter `{}` must not have a capture group named \
+                             like the route parameter `{}`",
+                            pair.name, name
+                        ),
+                    },
+                    segment.as_span(),
+                ));
+            }
+        }
+    }
+    Ok(components)
 }
 
-fn parse_http_route_segment(pair: pest::iterators::Pair<Rule>) -> ServiceRouteComponent {
+fn parse_http_route_segment(
+    pair: pest::iterators::Pair<Rule>,
+) -> Result<ServiceRouteComponent, pest::error::Error<Rule>> {
     let mut nodes = pair.into_inner();
     let comp = nodes.next().unwrap();
-    match comp.as_rule() {
+    Ok(match comp.as_rule() {
         Rule::kebab_case_ident => {
             ServiceRouteComponent::Literal(comp.as_span().as_str().to_string())
         }
         Rule::http_route_segment_arg => {
             let mut nodes = comp.into_inner();
-            let ret =
-                ServiceRouteComponent::Variable(parse_struct_field_def_pair(nodes.next().unwrap()));
+            let pair = parse_struct_field_def_pair(nodes.next().unwrap());
+            let pattern = match nodes.next() {
+                Some(pattern) => parse_route_param_pattern(pattern)?,
+                None => RouteParamPattern::Segment,
+            };
             assert_eq!(nodes.next(), None);
-            ret
+            ServiceRouteComponent::Variable(pair, pattern)
+        }
+        x => panic!("unexpected token {:?}", x),
+    })
+}
+
+fn parse_route_param_pattern(
+    pair: pest::iterators::Pair<Rule>,
+) -> Result<RouteParamPattern, pest::error::Error<Rule>> {
+    let pattern = pair.into_inner().next().unwrap();
+    match pattern.as_rule() {
+        Rule::string_literal => {
+            let span = pattern.as_span();
+            let name = parse_string_literal(pattern);
+            RouteParamPattern::from_class_name(&name).ok_or_else(|| {
+                pest::error::Error::new_from_span(
+                    pest::error::ErrorVariant::CustomError {
+                        message: format!(
+                            "unknown route parameter class \"{}\", expected one of {}",
+                            name,
+                            RouteParamPattern::CLASSES
+                                .iter()
+                                .map(|class| format!("\"{}\"", class))
+                                .join(", ")
+                        ),
+                    },
+                    span,
+                )
+            })
+        }
+        Rule::route_param_regex => {
+            let span = pattern.as_span();
+            // `\/` only escapes the delimiter, the regex itself matches a plain `/`
+            let regex = pattern
+                .into_inner()
+                .next()
+                .unwrap()
+                .as_str()
+                .replace("\\/", "/");
+            match regex::Regex::new(&regex) {
+                Ok(_) => Ok(RouteParamPattern::Regex(regex)),
+                Err(e) => Err(pest::error::Error::new_from_span(
+                    pest::error::ErrorVariant::CustomError {
+                        message: format!("invalid route parameter regex: {}", e),
+                    },
+                    span,
+                )),
+            }
         }
         x => panic!("unexpected token {:?}", x),
     }
@@ -290,15 +1676,43 @@ fn parse_http_query(pairs: &mut pest::iterators::Pairs<Rule>) -> Option<TypeIden
     ret
 }
 
+fn parse_http_query_fields(pairs: &mut pest::iterators::Pairs<Rule>) -> Vec<FieldDefPair> {
+    match pairs.peek() {
+        Some(pair) if pair.as_rule() == Rule::http_query_fields => {
+            pairs.next().unwrap(); // consume what we peeked
+            pair.into_inner().map(parse_struct_field_def_pair).collect()
+        }
+        _ => Vec::new(),
+    }
+}
+
+fn parse_http_headers(pairs: &mut pest::iterators::Pairs<Rule>) -> Vec<HeaderBinding> {
+    let mut headers = Vec::new();
+    while let Some(header) = pairs.peek().filter(|p| p.as_rule() == Rule::http_header) {
+        pairs.next().unwrap(); // consume what we peeked
+        let mut tokens = header.into_inner();
+        let name = tokens.next().unwrap().into_inner().next().unwrap();
+        headers.push(HeaderBinding {
+            name: name.as_str().to_string(),
+            type_ident: parse_type_ident(tokens.next().unwrap()),
+        });
+        assert_eq!(tokens.next(), None);
+    }
+    headers
+}
+
 /// Parse type identifier.
 fn parse_type_ident(pair: pest::iterators::Pair<Rule>) -> TypeIdent {
     let inner = pair.into_inner().next().unwrap();
     match inner.as_rule() {
         Rule::built_in_atom => TypeIdent::BuiltIn(parse_built_in_atom(inner)),
         Rule::list_type => parse_list_type(inner),
+        Rule::set_type => parse_set_type(inner),
         Rule::option_type => parse_option_type(inner),
+        Rule::nullable_type => parse_nullable_type(inner),
         Rule::result_type => parse_result_type(inner),
         Rule::map_type => parse_map_type(inner),
+        Rule::ordered_map_type => parse_ordered_map_type(inner),
         Rule::tuple_def => TypeIdent::Tuple(parse_tuple_def(inner)),
         Rule::camel_case_ident => TypeIdent::UserDefined(inner.as_span().as_str().to_string()),
         _ => unreachable!(dbg!(inner)),
@@ -313,12 +1727,25 @@ fn parse_built_in_atom(pair: pest::iterators::Pair<Rule>) -> AtomType {
         "i32" => AtomType::I32,
         "u32" => AtomType::U32,
         "u8" => AtomType::U8,
+        "i64" => AtomType::I64,
+        "u64" => AtomType::U64,
+        "i128" => AtomType::I128,
+        "u128" => AtomType::U128,
+        "i16" => AtomType::I16,
+        "u16" => AtomType::U16,
+        "i8" => AtomType::I8,
+        "f32" => AtomType::F32,
         "f64" => AtomType::F64,
         "bool" => AtomType::Bool,
         "datetime" => AtomType::DateTime,
         "date" => AtomType::Date,
         "uuid" => AtomType::Uuid,
+        "url" => AtomType::Url,
+        "ip" => AtomType::IpAddr,
+        "duration" => AtomType::Duration,
         "bytes" => AtomType::Bytes,
+        "decimal" => AtomType::Decimal,
+        "json" => AtomType::Json,
         _ => unreachable!(dbg!(pair)),
     }
 }
@@ -330,6 +1757,13 @@ fn parse_list_type(pair: pest::iterators::Pair<Rule>) -> TypeIdent {
     TypeIdent::List(Box::new(parse_type_ident(inner)))
 }
 
+/// Parse a set type.
+fn parse_set_type(pair: pest::iterators::Pair<Rule>) -> TypeIdent {
+    let inner = pair.into_inner().next().unwrap();
+
+    TypeIdent::Set(Box::new(parse_type_ident(inner)))
+}
+
 /// Parse a optional type.
 fn parse_option_type(pair: pest::iterators::Pair<Rule>) -> TypeIdent {
     let inner = pair.into_inner().next().unwrap();
@@ -337,6 +1771,13 @@ fn parse_option_type(pair: pest::iterators::Pair<Rule>) -> TypeIdent {
     TypeIdent::Option(Box::new(parse_type_ident(inner)))
 }
 
+/// Parse a nullable type.
+fn parse_nullable_type(pair: pest::iterators::Pair<Rule>) -> TypeIdent {
+    let inner = pair.into_inner().next().unwrap();
+
+    TypeIdent::Nullable(Box::new(parse_type_ident(inner)))
+}
+
 /// Parse a result type.
 fn parse_result_type(pair: pest::iterators::Pair<Rule>) -> TypeIdent {
     let mut tokens = pair.into_inner();
@@ -361,17 +1802,44 @@ fn parse_map_type(pair: pest::iterators::Pair<Rule>) -> TypeIdent {
     )
 }
 
+/// Parse an ordered map type.
+fn parse_ordered_map_type(pair: pest::iterators::Pair<Rule>) -> TypeIdent {
+    let mut inners = pair.into_inner();
+    let key_type = inners.next().unwrap();
+    let value_type = inners.next().unwrap();
+
+    TypeIdent::OrderedMap(
+        Box::new(parse_type_ident(key_type)),
+        Box::new(parse_type_ident(value_type)),
+    )
+}
+
 /// Parse a tuple definition.
 fn parse_tuple_def(pair: pest::iterators::Pair<Rule>) -> TupleDef {
     TupleDef(pair.into_inner().map(parse_type_ident).collect())
 }
 
 /// Parse a spec item (`struct` or `enum`).
-fn parse_spec_item(pair: pest::iterators::Pair<Rule>) -> SpecItem {
-    match pair.as_rule() {
-        Rule::struct_definition => SpecItem::StructDef(parse_struct_definition(pair)),
-        Rule::enum_definition => SpecItem::EnumDef(parse_enum_definition(pair)),
-        Rule::service_definition => SpecItem::ServiceDef(parse_service_definition(pair)),
+fn parse_spec_item(
+    pair: pest::iterators::Pair<Rule>,
+) -> Result<SpecItem, pest::error::Error<Rule>> {
+    Ok(match pair.as_rule() {
+        Rule::struct_definition => SpecItem::StructDef(parse_struct_definition(pair)?),
+        Rule::enum_definition => SpecItem::EnumDef(parse_enum_definition(pair)?),
+        Rule::service_definition => SpecItem::ServiceDef(parse_service_definition(pair, &[])?),
         _ => unreachable!(dbg!(pair)),
+    })
+}
+
+/// Parse a spec item or a namespace, which is flattened into the services it contains.
+fn parse_spec_items(
+    pair: pest::iterators::Pair<Rule>,
+) -> Result<Vec<SpecItem>, pest::error::Error<Rule>> {
+    match pair.as_rule() {
+        Rule::namespace_definition => Ok(parse_namespace_definition(pair, &[])?
+            .into_iter()
+            .map(SpecItem::ServiceDef)
+            .collect()),
+        _ => Ok(vec![parse_spec_item(pair)?]),
     }
 }
@@ -32,10 +32,24 @@
 //! }
 //! ```
 //!
+//! Inlined fields remember the struct they were embedded from (`FieldNode::embedded_from`).
+//! This allows backends to represent a struct annotated with `@flatten_embeds` as
+//!
+//! ```text
+//! struct Monster {
+//!     id: i32,
+//!     #[serde(flatten)]
+//!     monster_data: MonsterData,
+//! }
+//! ```
+//!
+//! which has the same serialized representation.
+//!
 //! # Rules
 //!
-//! - `MAX_EMBED_DEPTH` limits the maximum depth to which embeds are resolved.
-//!   Exceeding that limit results in a panic.
+//! - `ParserOptions::max_embed_depth` limits the maximum depth to which embeds are resolved.
+//!   Exceeding that limit results in `LibError::EmbedTooDeep`.
+//! - Embed-loops result in `LibError::EmbedCycle`.
 //! - No need for declare-before-use.
 //!
 //! # Limitations
@@ -43,31 +57,110 @@
 //! - The transformation does not perform any collision checks.
 //!   We rely on the rust compiler for that.
 //!
-//! - Embed-loops are not explicitly checked for but, since they are equivalent
-//!   to infintely deep embeds, will result in a panic due to transgression of
-//!   the `MAX_EMBED_DEPTH` limit.
-//!
 //! # Implementation:
 //!
 //! - AST representation of an embed is a bit hacky, see `FieldDefPair::is_embed`
+//! - Embed-loops are detected by a depth-first search through the embeds of all structs
+//!   before any embed is resolved.
 //! - Fixed-point iteration that resolves embeds by one level per iteration.
 //! - AST updates are performed in two phases (collect, update) in order to paciy
 //!   the borrow checker and avoid iterator invalidation.
 
 use crate::ast::*;
+use crate::LibError;
 use std::collections::HashMap;
 use std::iter::FromIterator;
 
-const MAX_EMBED_DEPTH: usize = 10;
+pub(crate) fn resolve_embeds(spec: &mut Spec, max_depth: usize) -> Result<(), LibError> {
+    let embeds = struct_embeds(spec);
+    if let Some(chain) = find_embed_cycle(&embeds) {
+        return Err(LibError::EmbedCycle { chain });
+    }
 
-pub(crate) fn resolve_embeds(spec: &mut Spec) {
     let changed = std::cell::Cell::new(true);
-    for _ in (0..=MAX_EMBED_DEPTH).take_while(|_| changed.get()) {
+    for _ in (0..=max_depth).take_while(|_| changed.get()) {
         changed.set(spec_resolve_embeds_one_level(spec));
     }
     if changed.get() {
-        panic!("maximum embed depth is {}", MAX_EMBED_DEPTH);
+        return Err(LibError::EmbedTooDeep {
+            max_depth,
+            chain: longest_embed_chain(&embeds),
+        });
+    }
+    Ok(())
+}
+
+/// The names of the structs embedded by each struct, in definition order.
+fn struct_embeds(spec: &Spec) -> Vec<(String, Vec<String>)> {
+    spec.iter()
+        .filter_map(|spec_item| match spec_item {
+            SpecItem::StructDef(def) => Some((
+                def.name.clone(),
+                def.fields
+                    .iter()
+                    .filter(|field_node| field_node.pair.is_embed())
+                    .map(|field_node| field_node.pair.name.clone())
+                    .collect(),
+            )),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Returns the chain of struct names forming the first embed-loop found, if any.
+/// The chain starts and ends with the same struct, e.g. `["A", "B", "A"]`.
+fn find_embed_cycle(embeds: &[(String, Vec<String>)]) -> Option<Vec<String>> {
+    let mut struct_names = embeds.iter().map(|(name, _)| name.as_str());
+    let embeds: HashMap<&str, &Vec<String>> =
+        HashMap::from_iter(embeds.iter().map(|(name, e)| (name.as_str(), e)));
+
+    fn visit<'a>(
+        name: &'a str,
+        embeds: &HashMap<&str, &'a Vec<String>>,
+        chain: &mut Vec<&'a str>,
+    ) -> Option<Vec<String>> {
+        if let Some(start) = chain.iter().position(|visited| *visited == name) {
+            let mut cycle: Vec<_> = chain[start..].iter().map(|s| s.to_string()).collect();
+            cycle.push(name.to_owned());
+            return Some(cycle);
+        }
+        chain.push(name);
+        for embedded in embeds.get(name).into_iter().flat_map(|e| e.iter()) {
+            if let Some(cycle) = visit(embedded, embeds, chain) {
+                return Some(cycle);
+            }
+        }
+        chain.pop();
+        None
     }
+
+    struct_names.find_map(|name| visit(name, &embeds, &mut Vec::new()))
+}
+
+/// Returns the longest chain of struct names in which each struct embeds the next one.
+/// Must only be called if there are no embed-loops.
+fn longest_embed_chain(embeds: &[(String, Vec<String>)]) -> Vec<String> {
+    let struct_names = embeds.iter().map(|(name, _)| name.as_str());
+    let embeds: HashMap<&str, &Vec<String>> =
+        HashMap::from_iter(embeds.iter().map(|(name, e)| (name.as_str(), e)));
+
+    fn chain_from(name: &str, embeds: &HashMap<&str, &Vec<String>>) -> Vec<String> {
+        let longest_tail = embeds
+            .get(name)
+            .into_iter()
+            .flat_map(|e| e.iter())
+            .map(|embedded| chain_from(embedded, embeds))
+            .max_by_key(|chain| chain.len())
+            .unwrap_or_default();
+        std::iter::once(name.to_owned())
+            .chain(longest_tail)
+            .collect()
+    }
+
+    struct_names
+        .map(|name| chain_from(name, &embeds))
+        .max_by_key(|chain| chain.len())
+        .unwrap_or_default()
 }
 
 fn spec_resolve_embeds_one_level(spec: &mut Spec) -> bool {
@@ -116,7 +209,18 @@ fn spec_resolve_embeds_one_level(spec: &mut Spec) -> bool {
                                 field_node.pair.name
                             )
                         });
-                    (*embedded_field_nodes).clone()
+                    // fields of nested embeds are attributed to the outermost embed
+                    let embedded_from = field_node
+                        .embedded_from
+                        .clone()
+                        .unwrap_or_else(|| field_node.pair.name.clone());
+                    embedded_field_nodes
+                        .iter()
+                        .map(|embedded_field_node| FieldNode {
+                            embedded_from: Some(embedded_from.clone()),
+                            ..embedded_field_node.clone()
+                        })
+                        .collect()
                 } else {
                     vec![field_node.clone()]
                 }
@@ -0,0 +1,163 @@
+//! Implementation of humblespec patch structs as an AST transformation.
+//!
+//! A patch struct holds a partial update of another struct, e.g. for `PATCH` endpoints.
+//! Instead of maintaining it by hand, it is derived from the struct it patches.
+//!
+//! # Example
+//!
+//! ```text
+//! struct Monster {
+//!     name: str,
+//!     hp: i32,
+//!     nickname: option[str],
+//! }
+//!
+//! @patch_of(Monster)
+//! struct MonsterPatch {}
+//! ```
+//!
+//! is equivalent to:
+//!
+//! ```text
+//! struct MonsterPatch {
+//!     name: option[str],
+//!     hp: option[i32],
+//!     nickname: option[str],
+//! }
+//! ```
+//!
+//! # Rules
+//!
+//! - Every field of the patched struct becomes optional, fields that are optional already
+//!   stay as they are.
+//! - Fields declared by the patch struct itself are kept as they are and take precedence over
+//!   fields of the patched struct with the same name.
+//! - The patch struct inherits the `@rename_all` rule of the patched struct unless it has one,
+//!   so that both use the same field names on the wire.
+//! - Patch structs may patch other patch structs. Patching a type that is not a struct results
+//!   in `LibError::InvalidPatch`, patch-loops result in `LibError::PatchCycle`.
+//!
+//! # Implementation
+//!
+//! Patches are resolved after embeds, i.e. the patched struct's embeds are already inlined.
+//! This implies that patch structs themselves cannot be embedded.
+
+use crate::ast::*;
+use crate::LibError;
+use std::collections::HashMap;
+
+pub(crate) fn resolve_patches(spec: &mut Spec) -> Result<(), LibError> {
+    // patch of struct name
+    let mut pending: HashMap<String, String> = spec
+        .iter()
+        .filter_map(|spec_item| match spec_item {
+            SpecItem::StructDef(def) => def
+                .patch_of
+                .as_ref()
+                .map(|patch_of| (def.name.clone(), patch_of.clone())),
+            _ => None,
+        })
+        .collect();
+
+    for (patch, patch_of) in &pending {
+        let is_struct = spec.iter().any(|spec_item| match spec_item {
+            SpecItem::StructDef(def) => def.name == *patch_of,
+            _ => false,
+        });
+        if !is_struct {
+            return Err(LibError::InvalidPatch {
+                patch: patch.clone(),
+                patch_of: patch_of.clone(),
+            });
+        }
+    }
+
+    // resolve patches of structs that are complete, until all patches are resolved
+    while !pending.is_empty() {
+        let resolvable: Vec<(String, String)> = pending
+            .iter()
+            .filter(|(_, patch_of)| !pending.contains_key(*patch_of))
+            .map(|(patch, patch_of)| (patch.clone(), patch_of.clone()))
+            .collect();
+        if resolvable.is_empty() {
+            return Err(LibError::PatchCycle {
+                chain: patch_cycle(&pending),
+            });
+        }
+
+        for (patch, patch_of) in resolvable {
+            let (fields, rename_all) = {
+                let patched = spec
+                    .iter()
+                    .filter_map(SpecItem::struct_def)
+                    .find(|def| def.name == patch_of)
+                    .expect("patched struct exists");
+                (patched.fields.0.clone(), patched.rename_all)
+            };
+            let def = spec
+                .iter_mut()
+                .filter_map(|spec_item| match spec_item {
+                    SpecItem::StructDef(def) => Some(def),
+                    _ => None,
+                })
+                .find(|def| def.name == patch)
+                .expect("patch struct exists");
+            apply_patch(def, fields, rename_all);
+            pending.remove(&patch);
+        }
+    }
+    Ok(())
+}
+
+/// Add the fields of the patched struct to `def`, making them optional.
+fn apply_patch(def: &mut StructDef, fields: Vec<FieldNode>, rename_all: Option<RenameRule>) {
+    let patch_fields = fields
+        .into_iter()
+        .filter(|field_node| {
+            def.fields
+                .iter()
+                .all(|f| f.pair.name != field_node.pair.name)
+        })
+        .map(|field_node| {
+            let type_ident = match field_node.pair.type_ident {
+                optional @ TypeIdent::Option(_) => optional,
+                // a patch cannot tell a field to be set to `null` from a missing one
+                TypeIdent::Nullable(inner) => TypeIdent::Option(inner),
+                type_ident => TypeIdent::Option(Box::new(type_ident)),
+            };
+            FieldNode {
+                pair: FieldDefPair {
+                    type_ident,
+                    ..field_node.pair
+                },
+                // the embedded struct is not optional as a whole, so it cannot be flattened
+                embedded_from: None,
+                // a missing field leaves the patched value unchanged
+                default: None,
+                ..field_node
+            }
+        })
+        .collect::<Vec<_>>();
+    def.fields.0.extend(patch_fields);
+    def.rename_all = def.rename_all.or(rename_all);
+}
+
+/// Returns the chain of struct names forming a patch-loop among the `pending` patches,
+/// each of which patches another pending one. The chain starts and ends with the same struct.
+fn patch_cycle(pending: &HashMap<String, String>) -> Vec<String> {
+    let mut start = pending.keys().min().expect("pending patches").as_str();
+    // walk the chain until it loops, then walk the loop from its start
+    let mut visited = Vec::new();
+    while !visited.contains(&start) {
+        visited.push(start);
+        start = &pending[start];
+    }
+    let mut chain = vec![start.to_owned()];
+    let mut current = &pending[start];
+    while current != start {
+        chain.push(current.clone());
+        current = &pending[current];
+    }
+    chain.push(start.to_owned());
+    chain
+}
@@ -0,0 +1,106 @@
+//! Metrics of a spec, e.g. for documentation dashboards.
+//!
+//! # Example
+//!
+//! ```
+//! let spec = humblegen::parse(
+//!     "struct Monster { name: str, hp: i32 }
+//!      service Monsters { GET /monsters -> list[Monster], POST /monsters -> Monster -> i32 }"
+//!         .as_bytes(),
+//! )?;
+//! let stats = humblegen::stats::stats(&spec);
+//! assert_eq!(stats.structs, 1);
+//! assert_eq!(stats.fields, 2);
+//! assert_eq!(stats.endpoint_count(), 2);
+//! assert_eq!(stats.endpoints["GET"], 1);
+//! # Ok::<(), humblegen::LibError>(())
+//! ```
+
+use crate::ast::{
+    self,
+    visit::{self, Visitor},
+};
+use std::{collections::BTreeMap, fmt};
+
+/// The number of types, services, endpoints and fields of a spec.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SpecStats {
+    pub structs: usize,
+    pub enums: usize,
+    pub services: usize,
+    /// The number of endpoints by HTTP method, e.g. `GET`, or `WS` and `SSE` for WebSocket and
+    /// Server-Sent Events endpoints.
+    pub endpoints: BTreeMap<String, usize>,
+    /// The number of fields of structs and struct variants, including embedded fields.
+    pub fields: usize,
+}
+
+impl SpecStats {
+    /// The number of endpoints of all services.
+    pub fn endpoint_count(&self) -> usize {
+        self.endpoints.values().sum()
+    }
+
+    /// The metrics as a JSON object, with the endpoints as an object keyed by method.
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "structs": self.structs,
+            "enums": self.enums,
+            "services": self.services,
+            "endpoints": {
+                "total": self.endpoint_count(),
+                "by_method": self.endpoints,
+            },
+            "fields": self.fields,
+        })
+    }
+}
+
+/// One metric per line, followed by the number of endpoints of each method.
+impl fmt::Display for SpecStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "structs: {}", self.structs)?;
+        writeln!(f, "enums: {}", self.enums)?;
+        writeln!(f, "services: {}", self.services)?;
+        writeln!(f, "endpoints: {}", self.endpoint_count())?;
+        for (method, count) in &self.endpoints {
+            writeln!(f, "  {}: {}", method, count)?;
+        }
+        writeln!(f, "fields: {}", self.fields)
+    }
+}
+
+impl<'ast> Visitor<'ast> for SpecStats {
+    fn visit_struct_def(&mut self, sdef: &'ast ast::StructDef) {
+        self.structs += 1;
+        visit::walk_struct_def(self, sdef);
+    }
+
+    fn visit_enum_def(&mut self, edef: &'ast ast::EnumDef) {
+        self.enums += 1;
+        visit::walk_enum_def(self, edef);
+    }
+
+    fn visit_field_node(&mut self, _field: &'ast ast::FieldNode) {
+        self.fields += 1;
+    }
+
+    fn visit_service_def(&mut self, service: &'ast ast::ServiceDef) {
+        self.services += 1;
+        visit::walk_service_def(self, service);
+    }
+
+    fn visit_service_endpoint(&mut self, endpoint: &'ast ast::ServiceEndpoint) {
+        *self
+            .endpoints
+            .entry(endpoint.route.http_method_as_str().to_owned())
+            .or_default() += 1;
+    }
+}
+
+/// Count the types, services, endpoints and fields of `spec`.
+pub fn stats(spec: &ast::Spec) -> SpecStats {
+    let mut stats = SpecStats::default();
+    stats.visit_spec(spec);
+    stats
+}
@@ -0,0 +1,102 @@
+//! Watch mode: regenerate whenever the humble file or one of its imports changes.
+
+use crate::cli::CliArgs;
+
+use anyhow::{Context, Result};
+use notify::{DebouncedEvent, RecursiveMode, Watcher};
+use std::{
+    collections::BTreeSet,
+    path::{Path, PathBuf},
+    sync::mpsc,
+    time::Duration,
+};
+
+/// Time to wait for further changes before regenerating, so that rapid edits
+/// (or editors writing a file in several steps) only cause a single regeneration.
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Generate code, then regenerate on every change until the process is interrupted.
+pub(crate) fn run(args: &CliArgs) -> Result<()> {
+    let (tx, rx) = mpsc::channel();
+    let mut watcher = notify::watcher(tx, DEBOUNCE).context("failed to start file watcher")?;
+
+    let input = args.input.canonicalize().context(format!(
+        "failed to open specification file {:?}",
+        &args.input
+    ))?;
+    let mut spec_files: BTreeSet<PathBuf> = std::iter::once(input.clone()).collect();
+    let mut watched_dirs = BTreeSet::new();
+
+    loop {
+        match generate(args) {
+            Ok(files) => {
+                status(&format!("generated {:?}", args.output()));
+                spec_files = files.into_iter().collect();
+            }
+            // keep watching the files of the last successful run, plus the one that failed
+            // to parse, which may have been imported just now
+            Err(e) => {
+                status(&format!("failed: {:#}", e));
+                if let Some(humblegen::LibError::ParseError {
+                    path: Some(path), ..
+                }) = e.downcast_ref()
+                {
+                    spec_files.extend(path.canonicalize());
+                }
+            }
+        }
+        spec_files.insert(input.clone());
+
+        // editors often replace files instead of writing them, so watch their directories
+        let dirs: BTreeSet<PathBuf> = spec_files
+            .iter()
+            .filter_map(|file| file.parent().map(Path::to_owned))
+            .collect();
+        for dir in dirs.difference(&watched_dirs) {
+            watcher
+                .watch(dir, RecursiveMode::NonRecursive)
+                .context(format!("failed to watch {:?}", dir))?;
+        }
+        for dir in watched_dirs.difference(&dirs) {
+            // the directory may be gone already
+            let _ = watcher.unwatch(dir);
+        }
+        watched_dirs = dirs;
+
+        // wait until one of the spec files changes
+        loop {
+            let event = rx.recv().context("file watcher stopped")?;
+            if matches!(changed_path(&event), Some(path) if spec_files.contains(path)) {
+                break;
+            }
+        }
+    }
+}
+
+/// Parse the spec and generate code, returning the paths of all files of the spec.
+fn generate(args: &CliArgs) -> Result<Vec<PathBuf>> {
+    let (spec, files) =
+        humblegen::parse_file_with_imports(&args.input, &args.parser_options()).context(
+            format!("failed to parse specification file {:?}", &args.input),
+        )?;
+
+    args.code_generator()?.generate(&spec, args.output())?;
+
+    Ok(files)
+}
+
+/// The path whose contents changed, if any.
+fn changed_path(event: &DebouncedEvent) -> Option<&Path> {
+    match event {
+        DebouncedEvent::Create(path)
+        | DebouncedEvent::Write(path)
+        | DebouncedEvent::Remove(path)
+        | DebouncedEvent::Rename(_, path) => Some(path),
+        _ => None,
+    }
+}
+
+/// Print a status line prefixed with the current time.
+fn status(message: &str) {
+    eprintln!("[{}] {}", chrono::Local::now().format("%H:%M:%S"), message);
+}
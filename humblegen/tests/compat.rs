@@ -0,0 +1,99 @@
+use humblegen::compat::{self, ChangeKind};
+
+fn diff(old: &str, new: &str) -> compat::CompatReport {
+    let old = humblegen::parse(old.as_bytes()).expect("parse old humble spec");
+    let new = humblegen::parse(new.as_bytes()).expect("parse new humble spec");
+    compat::diff(&old, &new)
+}
+
+#[test]
+fn removed_field_is_breaking() {
+    let report = diff(
+        "struct Monster {\n    name: str,\n    hp: i32,\n}\n",
+        "struct Monster {\n    name: str,\n}\n",
+    );
+    assert_eq!(report.changes.len(), 1, "{}", report);
+    assert_eq!(report.changes[0].kind, ChangeKind::FieldRemoved);
+    assert!(report.is_breaking());
+    assert_eq!(
+        report.to_string(),
+        "breaking: field `hp` of `Monster` was removed\n"
+    );
+}
+
+#[test]
+fn added_optional_field_is_not_breaking() {
+    let report = diff(
+        "struct Monster {\n    name: str,\n}\n",
+        "struct Monster {\n    name: str,\n    nickname: option[str],\n    hp: i32 = 100,\n}\n",
+    );
+    let kinds: Vec<_> = report.changes.iter().map(|change| change.kind).collect();
+    assert_eq!(
+        kinds,
+        vec![
+            ChangeKind::FieldAdded { optional: true },
+            ChangeKind::FieldAdded { optional: true },
+        ]
+    );
+    assert!(!report.is_breaking(), "{}", report);
+
+    let report = diff(
+        "struct Monster {\n    name: str,\n}\n",
+        "struct Monster {\n    name: str,\n    hp: i32,\n}\n",
+    );
+    assert_eq!(
+        report
+            .breaking_changes()
+            .map(|change| change.kind)
+            .collect::<Vec<_>>(),
+        vec![ChangeKind::FieldAdded { optional: false }]
+    );
+}
+
+#[test]
+fn endpoint_changes() {
+    let report = diff(
+        "service MonsterApi {\n    GET /monsters -> list[str],\n    DELETE /monsters/{id: i32} -> (),\n}\n",
+        "service MonsterApi {\n    GET /monsters -> list[i32],\n    POST /monsters -> str -> i32,\n}\n",
+    );
+    assert_eq!(
+        report.to_string(),
+        "breaking: response of endpoint `GET /monsters` of `MonsterApi` changed from `list[str]` to `list[i32]`\n\
+         non-breaking: endpoint `POST /monsters` was added to `MonsterApi`\n\
+         breaking: endpoint `DELETE /monsters/{id}` of `MonsterApi` was removed\n"
+    );
+}
+
+#[test]
+fn diff_cli_exits_with_failure_on_breaking_changes() {
+    let dir = tempfile::tempdir().expect("create temp dir");
+    let old = dir.path().join("old.humble");
+    let new = dir.path().join("new.humble");
+    std::fs::write(&old, "@non_exhaustive\nenum Mood {\n    Happy,\n}\n").unwrap();
+
+    std::fs::write(
+        &new,
+        "@non_exhaustive\nenum Mood {\n    Happy,\n    Sad,\n}\n",
+    )
+    .unwrap();
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_humblegen"))
+        .arg("--diff")
+        .arg(&old)
+        .arg(&new)
+        .output()
+        .expect("run humblegen");
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "non-breaking: variant `Sad` was added to `Mood`\n"
+    );
+
+    std::fs::write(&new, "@non_exhaustive\nenum Mood {\n    Sad,\n}\n").unwrap();
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_humblegen"))
+        .arg("--diff")
+        .arg(&old)
+        .arg(&new)
+        .output()
+        .expect("run humblegen");
+    assert!(!output.status.success());
+}
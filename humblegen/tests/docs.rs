@@ -0,0 +1,208 @@
+use humblegen::CodeGenerator;
+
+/// Generate the HTML documentation for the given spec.
+fn generate_html(humble_spec: &str) -> String {
+    let spec_file = std::fs::File::open(humble_spec).expect("open humble spec file");
+    let spec = humblegen::parse(spec_file).expect("parse humble spec file");
+    let outdir = tempfile::tempdir().expect("create temp dir");
+    let output = outdir.path().join("docs.html");
+    humblegen::backend::docs::Generator::default()
+        .generate(&spec, &output)
+        .expect("humblegen docs backend failed");
+
+    std::fs::read_to_string(&output).expect("read generated docs")
+}
+
+#[test]
+fn endpoints_have_curl_examples() {
+    let html = generate_html("./tests/rust/client/spec.humble");
+
+    assert!(html.contains("curl -X POST &quot;${BASE_URL}/monsters&quot;"));
+    assert!(html.contains("-H &#39;Content-Type: application/json&#39;"));
+    assert!(html.contains("&quot;name&quot;: &quot;string&quot;"));
+    assert!(html.contains("curl -X GET &quot;${BASE_URL}/monsters/0&quot;"));
+    assert!(html.contains("-H &#39;X-Tenant-Id: string&#39;"));
+}
+
+#[test]
+fn types_have_rust_and_elm_code_samples() {
+    let html = generate_html("./tests/rust/client/spec.humble");
+
+    assert!(html.contains(r#"data-tab-group="Rust">Rust</a>"#));
+    assert!(html.contains(r#"data-tab-group="Elm">Elm</a>"#));
+    assert!(html.contains("pub struct Monster {"));
+    assert!(html.contains("type alias Monster ="));
+    assert!(html.contains("pub enum MonsterError {"));
+    assert!(html.contains("type MonsterError"));
+}
+
+#[test]
+fn types_have_example_json() {
+    let html = generate_html("./tests/rust/client/spec.humble");
+
+    assert!(html.contains(r#"data-tab-group="Example JSON">Example JSON</a>"#));
+    assert!(html.contains(
+        r#"<code class="language-json">{
+  &quot;id&quot;: 0,
+  &quot;name&quot;: &quot;string&quot;,
+  &quot;hp&quot;: 0
+}</code>"#
+    ));
+    assert!(html.contains(r#"<code class="language-json">&quot;TooWeak&quot;</code>"#));
+}
+
+#[test]
+fn example_json_respects_defaults_and_ranges() {
+    let html = generate_html("./tests/rust/ranges/spec.humble");
+    assert!(html.contains(
+        r#"<code class="language-json">{
+  &quot;age&quot;: 0,
+  &quot;level&quot;: 1,
+  &quot;temperature&quot;: 0,
+  &quot;score&quot;: 0,
+  &quot;rank&quot;: 1
+}</code>"#
+    ));
+
+    let html = generate_html("./tests/rust/field-defaults/spec.humble");
+    assert!(html.contains(r#"&quot;speed&quot;: -0.5,"#));
+    assert!(html.contains(r#"&quot;title&quot;: &quot;the brave&quot;,"#));
+}
+
+#[test]
+fn endpoints_show_their_max_body_size() {
+    let html = generate_html("./tests/rust/max-body-size/spec.humble");
+
+    assert!(html.contains(r#"<span class="endpoint--body-limit">at most 4 KiB</span>"#));
+    assert_eq!(html.matches("endpoint--body-limit\">").count(), 1);
+}
+
+#[test]
+fn output_directory_gets_separate_assets() {
+    let spec_file = std::fs::File::open("./tests/rust/client/spec.humble").unwrap();
+    let spec = humblegen::parse(spec_file).unwrap();
+    let outdir = tempfile::tempdir().expect("create temp dir");
+    humblegen::backend::docs::Generator::default()
+        .generate(&spec, outdir.path())
+        .expect("humblegen docs backend failed");
+
+    let html = std::fs::read_to_string(outdir.path().join("index.html")).expect("read index.html");
+    assert!(html.contains(r#"<link href="main.css" rel="stylesheet" />"#));
+    assert!(html.contains(r#"<script src="script.js"></script>"#));
+    assert!(!html.contains("<style>"));
+    assert!(outdir.path().join("main.css").is_file());
+    assert!(outdir.path().join("script.js").is_file());
+}
+
+#[test]
+fn table_of_contents_links_services_endpoints_and_types() {
+    let html = generate_html("./tests/rust/service-base/spec.humble");
+    let toc_start = html
+        .find(r#"<nav class="toc">"#)
+        .expect("table of contents");
+    let toc = &html[toc_start..toc_start + html[toc_start..].find("</nav>").unwrap()];
+
+    let spec_file = std::fs::File::open("./tests/rust/service-base/spec.humble").unwrap();
+    let spec = humblegen::parse(spec_file).unwrap();
+    for item in spec.iter() {
+        let anchor = match item {
+            humblegen::ast::SpecItem::ServiceDef(service) => format!("service-{}", service.name),
+            humblegen::ast::SpecItem::StructDef(sdef) => format!("type-{}", sdef.name),
+            humblegen::ast::SpecItem::EnumDef(edef) => format!("type-{}", edef.name),
+        };
+        assert!(toc.contains(&format!(r##"href="#{}""##, anchor)));
+        assert!(html.contains(&format!(r#"id="{}""#, anchor)));
+    }
+    assert!(toc.contains("<code>GET /v1/monsters/{id}</code>"));
+}
+
+#[test]
+fn title_is_taken_from_the_spec() {
+    let spec = humblegen::parse(
+        r#"@title "Monster API"
+        struct Monster { name: str }"#
+            .as_bytes(),
+    )
+    .unwrap();
+    let outdir = tempfile::tempdir().expect("create temp dir");
+    let output = outdir.path().join("docs.html");
+    humblegen::backend::docs::Generator::default()
+        .generate(&spec, &output)
+        .expect("humblegen docs backend failed");
+    let html = std::fs::read_to_string(&output).unwrap();
+    assert!(html.contains("<title>\nMonster API\n</title>"));
+    assert!(html.contains(r#"<h1 class="page-title">Monster API</h1>"#));
+    assert!(!html.contains(r#"<p class="page-version">"#));
+
+    let spec = humblegen::parse(
+        r#"@title "Monster API"
+        @version "1.4.0"
+        struct Monster { name: str }"#
+            .as_bytes(),
+    )
+    .unwrap();
+    humblegen::backend::docs::Generator::default()
+        .generate(&spec, &output)
+        .expect("humblegen docs backend failed");
+    let html = std::fs::read_to_string(&output).unwrap();
+    assert!(html.contains(r#"<p class="page-version">Version 1.4.0</p>"#));
+
+    // without a title, the name of the output file is used
+    let html = generate_html("./tests/rust/client/spec.humble");
+    assert!(html.contains("<title>\ndocs\n</title>"));
+}
+
+#[test]
+fn deprecated_items_have_a_badge() {
+    let html = generate_html("./tests/rust/deprecation/spec.humble");
+    let badge = r#"<div class="deprecated"><span class="deprecated--badge">Deprecated</span>"#;
+    for note in [
+        "use Creature instead",
+        "use health instead",
+        "kinds are no longer distinguished",
+        "always 1",
+        "use GET /creatures/{name} instead",
+    ] {
+        assert!(html.contains(&format!("{}{}</div>", badge, note)));
+    }
+    // the Rust sample carries the attribute as well
+    assert!(html.contains("#[deprecated(note = &quot;use Creature instead&quot;)]"));
+}
+
+#[test]
+fn summaries_end_at_a_sentence_or_word_boundary() {
+    let spec = humblegen::parse(
+        "service Monsters {\n    \
+         /// Look up a single monster by its id. Monsters that were deleted are not found, and \
+         neither are monsters of other tenants.\n    \
+         ///\n    \
+         /// The monster is returned along with its stats.\n    \
+         GET /monsters/{id: u32} -> str,\n    \
+         /// Retrieve the monsters of the current tenant that are visible to the user who sends \
+         the request, excluding\n    \
+         /// deleted ones\n    \
+         GET /monsters -> list[str],\n}\n"
+            .as_bytes(),
+    )
+    .unwrap();
+    let outdir = tempfile::tempdir().expect("create temp dir");
+    let output = outdir.path().join("docs.html");
+    humblegen::backend::docs::Generator::default()
+        .generate(&spec, &output)
+        .expect("humblegen docs backend failed");
+    let html = std::fs::read_to_string(&output).unwrap();
+
+    // the first sentence of a long first paragraph
+    assert!(html
+        .contains(r#"<span class="endpoint--summary"><p>Look up a single monster by its id.</p>"#));
+    // the leading words of a long first sentence
+    assert!(html.contains(
+        r#"<span class="endpoint--summary"><p>Retrieve the monsters of the current tenant that are visible to the user who sends the request...</p>"#
+    ));
+    // the description keeps all paragraphs
+    assert!(html.contains(
+        "<p>Look up a single monster by its id. Monsters that were deleted are not found, and \
+         neither are monsters of other tenants.</p>\n<p>The monster is returned along with its \
+         stats.</p>"
+    ));
+}
@@ -0,0 +1,661 @@
+use humblegen::CodeGenerator;
+
+/// Generate Elm code for the given spec and return the contents of the given module file.
+fn generate_module(humble_spec: &str, module_file: &str) -> String {
+    generate_module_with_artifact(humble_spec, humblegen::Artifact::TypesOnly, module_file)
+}
+
+/// Like `generate_module`, but generates the given artifact.
+fn generate_module_with_artifact(
+    humble_spec: &str,
+    artifact: humblegen::Artifact,
+    module_file: &str,
+) -> String {
+    let spec_file = std::fs::File::open(humble_spec).expect("open humble spec file");
+    let spec = humblegen::parse(spec_file).expect("parse humble spec file");
+    let codegen = humblegen::backend::elm::Generator::new(artifact, "Api".to_owned())
+        .expect("failed to init humblegen elm backend");
+    let outdir = tempfile::tempdir().expect("create temp dir");
+    codegen
+        .generate(&spec, outdir.path())
+        .expect("humblegen elm backend failed");
+    std::fs::read_to_string(outdir.path().join(module_file)).expect("read generated module")
+}
+
+#[test]
+fn renamed_field_uses_wire_name() {
+    let encoder = generate_module("./tests/rust/showcase/spec.humble", "Encode.elm");
+    assert!(encoder.contains(r#"("isVIP", E.bool obj.isVip)"#));
+
+    let decoder = generate_module("./tests/rust/showcase/spec.humble", "Decode.elm");
+    assert!(decoder.contains(r#"|> required "isVIP" D.bool"#));
+}
+
+#[test]
+fn rename_all_applies_to_wire_names() {
+    let encoder = generate_module("./tests/rust/rename/spec.humble", "Encode.elm");
+    assert!(encoder.contains(r#"("firstName", E.string obj.firstName)"#));
+    assert!(encoder.contains(r#"("zip", E.string obj.postalCode)"#));
+    assert!(encoder.contains(r#"("FIRST_NAME", E.string obj.firstName)"#));
+
+    let decoder = generate_module("./tests/rust/rename/spec.humble", "Decode.elm");
+    assert!(decoder.contains(r#"|> required "first-name" D.string"#));
+}
+
+#[test]
+fn patch_struct_fields_are_maybe() {
+    let types = generate_module("./tests/rust/patch-structs/spec.humble", "Data.elm");
+    assert!(types.contains("type alias MonsterPatch ="));
+    assert!(types.contains("{ id: Int\n    , name: Maybe String\n    , maxHp: Maybe Int\n"));
+
+    let decoder = generate_module("./tests/rust/patch-structs/spec.humble", "Decode.elm");
+    assert!(decoder.contains(r#"|> optional "maxHp" (builtinDecodeOption D.int) Nothing"#));
+}
+
+#[test]
+fn header_bindings_are_sent() {
+    let service = generate_module_with_artifact(
+        "./tests/rust/client/spec.humble",
+        humblegen::Artifact::ClientEndpoints,
+        "Service/Monsters.elm",
+    );
+    assert!(service.contains("getWhoami : String -> (Maybe Int) -> Request NoQuery String"));
+    assert!(service.contains(r#"|> withHeader "X-Tenant-Id" (header_x_tenant_id |> identity)"#));
+    assert!(service.contains(
+        r#"|> withOptionalHeader "X-Trace-Id" (Maybe.map String.fromInt header_x_trace_id)"#
+    ));
+}
+
+#[test]
+fn namespaced_services_get_nested_modules() {
+    let service = generate_module_with_artifact(
+        "./tests/rust/namespaces/spec.humble",
+        humblegen::Artifact::ClientEndpoints,
+        "Service/Admin/Audit/Log.elm",
+    );
+    assert!(service.starts_with("module Api.Service.Admin.Audit.Log exposing (..)"));
+    assert!(service.contains("getEntries : Request NoQuery (List String)"));
+
+    let service = generate_module_with_artifact(
+        "./tests/rust/namespaces/spec.humble",
+        humblegen::Artifact::ClientEndpoints,
+        "Service/Admin/Monsters.elm",
+    );
+    assert!(service.starts_with("module Api.Service.Admin.Monsters exposing (..)"));
+}
+
+#[test]
+fn raw_responses_use_raw_resolver() {
+    let service = generate_module_with_artifact(
+        "./tests/rust/client/spec.humble",
+        humblegen::Artifact::ClientEndpoints,
+        "Service/Monsters.elm",
+    );
+    assert!(service.contains("getThumbnail : Request NoQuery RawResponse"));
+    assert!(service.contains("rawResolver"));
+}
+
+#[test]
+fn error_status_uses_result_resolver() {
+    let service = generate_module_with_artifact(
+        "./tests/rust/error-status/spec.humble",
+        humblegen::Artifact::ClientEndpoints,
+        "Service/Monsters.elm",
+    );
+    assert!(service.contains(
+        "(resultResolver 404 ((AD.builtinDecodeResult AD.decodeMonsterError AD.decodeMonster)))"
+    ));
+    assert_eq!(service.matches("resultResolver").count(), 1);
+}
+
+#[test]
+fn errors_distinguish_transport_decode_and_api_errors() {
+    let builtin = generate_module_with_artifact(
+        "./tests/rust/client/spec.humble",
+        humblegen::Artifact::ClientEndpoints,
+        "ServiceBuiltIn.elm",
+    );
+    assert!(builtin.contains("type ApiError\r\n    = BadUrl String"));
+    assert!(builtin.contains("    | BadStatus Int"));
+    assert!(builtin.contains("    | ServiceError ServiceError"));
+    assert!(builtin.contains("Err Timeout"));
+    assert!(builtin.contains("Result.mapError (DecodeError << D.errorToString)"));
+    // error responses of the service protocol are decoded, falling back to the status
+    assert!(builtin.contains(r#"D.field "Service" (D.map ServiceError serviceErrorDecoder)"#));
+    assert!(builtin.contains("Result.withDefault (BadStatus metadata.statusCode)"));
+    assert!(builtin.contains("toTask : Request q t -> Task ApiError t"));
+}
+
+#[test]
+fn rest_params_keep_their_slashes() {
+    let service = generate_module_with_artifact(
+        "./tests/rust/client/spec.humble",
+        humblegen::Artifact::ClientEndpoints,
+        "Service/Monsters.elm",
+    );
+    assert!(service.contains(r#"[ [ "files" ]"#));
+    assert!(service.contains(r#", String.split "/" (component1_path |> identity)"#));
+}
+
+#[test]
+fn query_fields_are_sent() {
+    let service = generate_module_with_artifact(
+        "./tests/rust/client/spec.humble",
+        humblegen::Artifact::ClientEndpoints,
+        "Service/Monsters.elm",
+    );
+    assert!(
+        service.contains("getSearch : (Maybe String) -> Int -> Request NoQuery (List Ty.Monster)")
+    );
+    assert!(service.contains(r#"|> withOptionalQueryParam "name" (Maybe.map identity query_name)"#));
+    assert!(service.contains(r#"|> withQueryParam "min_hp" (query_min_hp |> String.fromInt)"#));
+}
+
+#[test]
+fn colliding_endpoint_names_are_numbered() {
+    let spec_dir = tempfile::tempdir().expect("create temp dir");
+    let spec_path = spec_dir.path().join("spec.humble");
+    std::fs::write(
+        &spec_path,
+        "struct MonsterQuery {\n    name: str,\n}\n\n\
+         service Monsters {\n    \
+             GET /monsters -> list[str],\n    \
+             GET /monsters?{MonsterQuery} -> list[str],\n    \
+             POST /monsters -> str -> i32,\n    \
+             \"CREATE\" /monsters -> str -> i32,\n\
+         }\n",
+    )
+    .expect("write humble spec");
+
+    let service = generate_module_with_artifact(
+        spec_path.to_str().unwrap(),
+        humblegen::Artifact::ClientEndpoints,
+        "Service/Monsters.elm",
+    );
+    assert!(service.contains("\ngetMonsters : Request NoQuery (List String)"));
+    assert!(service.contains("\ngetMonsters2 : Request Ty.MonsterQuery (List String)"));
+    assert!(service.contains("\ncreateMonsters : String -> Request NoQuery Int"));
+    assert!(service.contains("\ncreateMonsters2 : String -> Request NoQuery Int"));
+}
+
+#[test]
+fn numbered_endpoint_name_colliding_with_another_endpoint_is_an_error() {
+    let spec = humblegen::parse(
+        "service Monsters {\n    \
+             GET /monsters -> list[str],\n    \
+             GET /monsters?{str} -> list[str],\n    \
+             GET /monsters2 -> list[str],\n\
+         }\n"
+        .as_bytes(),
+    )
+    .expect("parse humble spec");
+    let codegen = humblegen::backend::elm::Generator::new(
+        humblegen::Artifact::ClientEndpoints,
+        "Api".to_owned(),
+    )
+    .expect("failed to init humblegen elm backend");
+    let outdir = tempfile::tempdir().expect("create temp dir");
+    match codegen.generate(&spec, outdir.path()) {
+        Err(humblegen::LibError::ElmEndpointNameCollision { service, name }) => {
+            assert_eq!(service, "Monsters");
+            assert_eq!(name, "getMonsters2");
+        }
+        other => panic!("expected endpoint name collision error, got {:?}", other),
+    }
+}
+
+#[test]
+fn api_version_is_a_constant() {
+    let spec_dir = tempfile::tempdir().expect("create temp dir");
+    let spec_path = spec_dir.path().join("spec.humble");
+    std::fs::write(
+        &spec_path,
+        "@version \"1.4.0\"\nstruct Monster {\n    name: str,\n}\n",
+    )
+    .expect("write humble spec");
+
+    let types = generate_module(spec_path.to_str().unwrap(), "Data.elm");
+    assert!(types.contains("apiVersion : String\napiVersion =\n    \"1.4.0\"\n"));
+}
+
+#[test]
+fn non_string_map_key_is_an_error() {
+    let spec = humblegen::parse("struct Inventory {\n    counts: map[i32][str],\n}\n".as_bytes())
+        .expect("parse humble spec");
+    let codegen =
+        humblegen::backend::elm::Generator::new(humblegen::Artifact::TypesOnly, "Api".to_owned())
+            .expect("failed to init humblegen elm backend");
+    let outdir = tempfile::tempdir().expect("create temp dir");
+    match codegen.generate(&spec, outdir.path()) {
+        Err(humblegen::LibError::ElmUnsupportedMapKey { key_type, location }) => {
+            assert_eq!(key_type, "Int");
+            assert_eq!(location, "field `counts` of `Inventory`");
+        }
+        other => panic!("expected unsupported map key error, got {:?}", other),
+    }
+}
+
+#[test]
+fn sets_use_elm_sets() {
+    let spec_dir = tempfile::tempdir().expect("create temp dir");
+    let spec_path = spec_dir.path().join("spec.humble");
+    std::fs::write(&spec_path, "struct Inventory {\n    tags: set[str],\n}\n")
+        .expect("write humble spec");
+    let spec_path = spec_path.to_str().unwrap();
+
+    let types = generate_module(spec_path, "Data.elm");
+    assert!(types.contains("{ tags: Set String"));
+
+    let decoder = generate_module(spec_path, "Decode.elm");
+    assert!(decoder.contains(r#"|> required "tags" (builtinDecodeSet D.string)"#));
+
+    let encoder = generate_module(spec_path, "Encode.elm");
+    assert!(encoder.contains(r#"("tags", builtinEncodeSet E.string obj.tags)"#));
+}
+
+#[test]
+fn non_comparable_set_element_is_an_error() {
+    let spec = humblegen::parse("struct Inventory {\n    flags: set[bool],\n}\n".as_bytes())
+        .expect("parse humble spec");
+    let codegen =
+        humblegen::backend::elm::Generator::new(humblegen::Artifact::TypesOnly, "Api".to_owned())
+            .expect("failed to init humblegen elm backend");
+    let outdir = tempfile::tempdir().expect("create temp dir");
+    match codegen.generate(&spec, outdir.path()) {
+        Err(humblegen::LibError::ElmUnsupportedSetElement {
+            element_type,
+            location,
+        }) => {
+            assert_eq!(element_type, "Bool");
+            assert_eq!(location, "field `flags` of `Inventory`");
+        }
+        other => panic!("expected unsupported set element error, got {:?}", other),
+    }
+}
+
+#[test]
+fn tagged_enums_match_serde_representation() {
+    // the Rust side of `tests/rust/tagged-enums` serializes
+    // `TooStrong { power: 9001 }` as `{"type": "TooStrong", "power": 9001}`
+    let spec = "./tests/rust/tagged-enums/spec.humble";
+    let encoder = generate_module(spec, "Encode.elm");
+    assert!(encoder.contains(
+        r#"TooStrong obj -> E.object [ ("type", E.string "TooStrong"), ("power", E.int obj.power) ]"#
+    ));
+    assert!(encoder.contains(r#"Asleep -> E.object [ ("type", E.string "Asleep") ]"#));
+    assert!(encoder.contains(
+        r#"Moved x0 x1 -> E.object [ ("kind", E.string "Moved"), ("data", E.list identity [E.int x0, E.int x1]) ]"#
+    ));
+
+    let decoder = generate_module(spec, "Decode.elm");
+    assert!(decoder.contains(r#"D.field "type" D.string"#));
+    assert!(decoder.contains(
+        r#"D.succeed RecruitError__TooStrong__Internal__ |> required "power" D.int |> D.map TooStrong"#
+    ));
+    assert!(decoder.contains(r#"D.field "kind" D.string"#));
+    assert!(decoder.contains(r#"D.field "data" (D.map Renamed D.string)"#));
+}
+
+#[test]
+fn tuple_variants_match_serde_representation() {
+    // the Rust side of `tests/rust/tuple-variants` serializes `Circle((1.5,))` as
+    // `{"Circle": [1.5]}` and `Center((3, -4))` as `{"Center": [3, -4]}`
+    let spec = "./tests/rust/tuple-variants/spec.humble";
+    let encoder = generate_module(spec, "Encode.elm");
+    assert!(
+        encoder.contains(r#"Circle x0 -> E.object [ ("Circle", E.list identity [E.float x0]) ]"#)
+    );
+    assert!(encoder.contains(
+        r#"Center obj -> E.object [ ("Center", (\(x0, x1) -> E.list identity [ E.int x0, E.int x1 ]) obj) ]"#
+    ));
+    assert!(encoder.contains(
+        r#"Label obj -> E.object [ ("Label", (\(x0) -> E.list identity [ E.string x0 ]) obj) ]"#
+    ));
+    assert!(encoder.contains(r#"("scale", (\(x0) -> E.list identity [ E.float x0 ]) obj.scale)"#));
+
+    let decoder = generate_module(spec, "Decode.elm");
+    assert!(decoder.contains(r#"D.field "Circle" (D.succeed Circle |> requiredIdx 0 D.float)"#));
+    assert!(decoder.contains(
+        r#"D.field "Center" (D.map Center (D.succeed (\x0 x1 -> (x0, x1)) |> requiredIdx 0 D.int |> requiredIdx 1 D.int))"#
+    ));
+    assert!(decoder.contains(
+        r#"D.field "Label" (D.map Label (D.succeed (\x0 -> (x0)) |> requiredIdx 0 D.string))"#
+    ));
+    assert!(decoder.contains(r#"D.field "data" (D.succeed Circle |> requiredIdx 0 D.float)"#));
+}
+
+#[test]
+fn default_enum_tagging_applies_to_untagged_enums() {
+    let spec = "enum Shape { Circle(f32), Square { side: f32 } }\n\
+                @tag(\"kind\")\n\
+                enum Event { Start }\n";
+    let spec = humblegen::parse(spec.as_bytes()).expect("parse humble spec");
+    let outdir = tempfile::tempdir().expect("create temp dir");
+    humblegen::backend::elm::Generator::new(humblegen::Artifact::TypesOnly, "Api".to_owned())
+        .expect("failed to init humblegen elm backend")
+        .with_default_enum_tagging(humblegen::ast::EnumTagging::Adjacent {
+            tag: "type".to_owned(),
+            content: "data".to_owned(),
+        })
+        .generate(&spec, outdir.path())
+        .expect("humblegen elm backend failed");
+
+    let encoder = std::fs::read_to_string(outdir.path().join("Encode.elm")).unwrap();
+    assert!(encoder.contains(
+        r#"Circle obj -> E.object [ ("type", E.string "Circle"), ("data", E.float obj) ]"#
+    ));
+    assert!(encoder.contains(r#"Start -> E.object [ ("kind", E.string "Start") ]"#));
+
+    let decoder = std::fs::read_to_string(outdir.path().join("Decode.elm")).unwrap();
+    assert!(decoder.contains(r#"D.field "type" D.string"#));
+    assert!(decoder.contains(r#"D.field "data" (D.map Circle D.float)"#));
+}
+
+#[test]
+fn wide_integers_are_strings() {
+    let spec = "./tests/rust/wide-integers/spec.humble";
+    let types = generate_module(spec, "Data.elm");
+    assert!(types.contains("{ id: String\n    , parent: Maybe String\n    , offset: String\n"));
+
+    let decoder = generate_module(spec, "Decode.elm");
+    assert!(decoder.contains(r#"|> required "id" (builtinDecodeIntString False)"#));
+    assert!(decoder.contains(r#"|> required "offset" (builtinDecodeIntString True)"#));
+
+    let encoder = generate_module(spec, "Encode.elm");
+    assert!(encoder.contains(r#"("id", E.string obj.id)"#));
+}
+
+#[test]
+fn doc_comments_list_fields_and_variants() {
+    let types = generate_module("./tests/rust/showcase/spec.humble", "Data.elm");
+    assert!(types.contains(
+        "{-| A customer.\n\nContains the complete profile of a customer.\n\n  - `name`: Full name.\n  - `id`: Customer ID.\n"
+    ));
+    assert!(types.contains("  - `favoriteColor`: Favorite color.\n"));
+    assert!(types.contains("{-| A color.\n\n  - `Red`: Pure red.\n"));
+}
+
+#[test]
+fn doc_comments_cannot_end_the_elm_comment() {
+    let dir = tempfile::tempdir().expect("create temp dir");
+    let spec = dir.path().join("spec.humble");
+    std::fs::write(
+        &spec,
+        "/// Returns {-1} or -}\nstruct Foo {\n    bar: str,\n}\n",
+    )
+    .unwrap();
+    let types = generate_module(spec.to_str().unwrap(), "Data.elm");
+    assert!(types.contains("{-| Returns { -1} or - }\n-}\ntype alias Foo ="));
+}
+
+#[test]
+fn empty_type_is_encoded_as_null() {
+    let encoder = generate_module("./tests/rust/showcase/spec.humble", "Encode.elm");
+    assert!(encoder.contains(r#"("empty", (\_ -> E.null) obj.empty)"#));
+
+    let decoder = generate_module("./tests/rust/showcase/spec.humble", "Decode.elm");
+    assert!(decoder.contains(r#"|> required "empty" (D.null ())"#));
+}
+
+#[test]
+fn urls_are_validated_strings() {
+    let types = generate_module("./tests/rust/urls/spec.humble", "Data.elm");
+    assert!(types.contains("{ href: String"));
+
+    let decoder = generate_module("./tests/rust/urls/spec.humble", "Decode.elm");
+    assert!(decoder.contains(r#"|> required "href" builtinDecodeUrl"#));
+    assert!(decoder.contains("case Url.fromString s of"));
+
+    let encoder = generate_module("./tests/rust/urls/spec.humble", "Encode.elm");
+    assert!(encoder.contains(r#"("href", E.string obj.href)"#));
+}
+
+#[test]
+fn ip_addresses_are_validated_strings() {
+    let types = generate_module("./tests/rust/ip-addresses/spec.humble", "Data.elm");
+    assert!(types.contains("{ address: String"));
+
+    let decoder = generate_module("./tests/rust/ip-addresses/spec.humble", "Decode.elm");
+    assert!(decoder.contains(r#"|> required "address" builtinDecodeIp"#));
+    assert!(decoder.contains("if builtinIsIpv4 s || builtinIsIpv6 s then"));
+}
+
+#[test]
+fn durations_are_milliseconds() {
+    let types = generate_module("./tests/rust/durations/spec.humble", "Data.elm");
+    assert!(types.contains("{ interval: Int"));
+
+    let decoder = generate_module("./tests/rust/durations/spec.humble", "Decode.elm");
+    assert!(decoder.contains(r#"|> required "interval" builtinDecodeDuration"#));
+    assert!(decoder.contains("D.fail <| \"duration cannot be negative: \""));
+
+    let encoder = generate_module("./tests/rust/durations/spec.humble", "Encode.elm");
+    assert!(encoder.contains(r#"("interval", E.int obj.interval)"#));
+}
+
+#[test]
+fn nullable_fields_are_required() {
+    let types = generate_module("./tests/rust/nullable-fields/spec.humble", "Data.elm");
+    assert!(types.contains(", nickname: Maybe String\n    , owner: Maybe String\n"));
+
+    let decoder = generate_module("./tests/rust/nullable-fields/spec.humble", "Decode.elm");
+    assert!(decoder.contains(r#"|> optional "nickname" (builtinDecodeOption D.string) Nothing"#));
+    assert!(decoder.contains(r#"|> required "owner" (builtinDecodeOption D.string)"#));
+}
+
+#[test]
+fn missing_fields_decode_to_their_default() {
+    let decoder = generate_module("./tests/rust/field-defaults/spec.humble", "Decode.elm");
+    assert!(decoder.contains(r#"|> optional "level" D.int 1"#));
+    assert!(decoder.contains(r#"|> optional "speed" D.float (-0.5)"#));
+    assert!(decoder.contains(r#"|> optional "hostile" D.bool True"#));
+    assert!(decoder.contains(r#"|> optional "title" D.string "the brave""#));
+    assert!(decoder.contains(r#"|> required "name" D.string"#));
+}
+
+#[test]
+fn struct_variants_have_constructor_functions() {
+    let types = generate_module("./tests/rust/showcase/spec.humble", "Data.elm");
+    assert!(types.contains(
+        "color_Hsv : Int -> Int -> Int -> Color\ncolor_Hsv h s v =\n    Hsv { h = h, s = s, v = v }"
+    ));
+}
+
+#[test]
+fn hex_bytes_use_hex_encoding() {
+    let encoder = generate_module("./tests/rust/bytes-encoding/spec.humble", "Encode.elm");
+    assert!(encoder.contains(r#"("checksum", BuiltinBytes.encodeHex obj.checksum)"#));
+    assert!(encoder.contains(r#"("image", BuiltinBytes.encode obj.image)"#));
+
+    let decoder = generate_module("./tests/rust/bytes-encoding/spec.humble", "Decode.elm");
+    assert!(decoder.contains(r#"|> required "checksum" BuiltinBytes.decodeHex"#));
+    assert!(decoder.contains(r#"|> required "image" BuiltinBytes.decode"#));
+}
+
+#[test]
+fn ranges_are_checked_when_decoding() {
+    let decoder = generate_module("./tests/rust/ranges/spec.humble", "Decode.elm");
+    assert!(
+        decoder.contains(r#"|> required "age" (builtinDecodeInRange (Just 0) (Just 150) D.int)"#)
+    );
+    assert!(decoder
+        .contains(r#"|> optional "level" (builtinDecodeInRange (Just 1) (Just 99) D.int) 1"#));
+    assert!(decoder.contains(
+        r#"|> required "temperature" (builtinDecodeInRange (Just (-40)) Nothing D.int)"#
+    ));
+    assert!(decoder.contains(
+        r#"|> optional "rank" (builtinDecodeOption (builtinDecodeInRange (Just 1) (Just 10) D.int)) Nothing"#
+    ));
+}
+
+#[test]
+fn deprecation_notes_are_part_of_the_docs() {
+    let types = generate_module("./tests/rust/deprecation/spec.humble", "Data.elm");
+    assert!(types.contains(
+        "{-| A monster of the old API.\n\n**Deprecated:** use Creature instead\n-}\ntype alias Monster ="
+    ));
+    assert!(types.contains("  - `hp`: Health points.\n\n    **Deprecated:** use health instead\n"));
+    assert!(types.contains("{-| **Deprecated:** kinds are no longer distinguished\n-}\ntype Kind"));
+
+    let service = generate_module_with_artifact(
+        "./tests/rust/deprecation/spec.humble",
+        humblegen::Artifact::ClientEndpoints,
+        "Service/ZooApi.elm",
+    );
+    assert!(service.contains("**Deprecated:** use GET /creatures/{name} instead\n-}"));
+}
+
+#[test]
+fn paginated_endpoints_return_pages() {
+    let types = generate_module("./tests/rust/pagination/spec.humble", "Data.elm");
+    assert!(types.contains("type alias Page a =\n    { items: List a\n"));
+    let decoder = generate_module("./tests/rust/pagination/spec.humble", "Decode.elm");
+    assert!(decoder.contains("decodePage : D.Decoder a -> D.Decoder (Page a)"));
+
+    let service = generate_module_with_artifact(
+        "./tests/rust/pagination/spec.humble",
+        humblegen::Artifact::ClientEndpoints,
+        "Service/Monsters.elm",
+    );
+    assert!(service.contains(
+        "getMonsters : (Maybe Int) -> (Maybe Int) -> Request NoQuery (Ty.Page Ty.Monster)"
+    ));
+    assert!(service.contains("(jsonResolver (AD.decodePage AD.decodeMonster))"));
+}
+
+#[test]
+fn variant_values_are_used_on_the_wire() {
+    let encoder = generate_module("./tests/rust/enum-values/spec.humble", "Encode.elm");
+    assert!(encoder.contains(r#"Blue -> E.string "BLUE_07""#));
+    assert!(encoder.contains(r#"Red -> E.string "Red""#));
+    assert!(encoder.contains(r#"Placed -> E.object [ ("type", E.string "placed") ]"#));
+
+    let decoder = generate_module("./tests/rust/enum-values/spec.humble", "Decode.elm");
+    assert!(decoder.contains(r#"if s == "BLUE_07" then D.succeed Blue else"#));
+    assert!(decoder.contains("\"placed\" ->\n                        D.succeed Placed"));
+}
+
+#[test]
+fn json_values_are_passed_through() {
+    let types = generate_module("./tests/rust/json-values/spec.humble", "Data.elm");
+    assert!(types.lines().any(|line| line == "import Json.Encode"));
+    assert!(types.contains("payload: Json.Encode.Value"));
+    assert!(types.contains("metadata: Maybe Json.Encode.Value"));
+
+    let encoder = generate_module("./tests/rust/json-values/spec.humble", "Encode.elm");
+    assert!(encoder.contains(r#"("payload", identity obj.payload)"#));
+
+    let decoder = generate_module("./tests/rust/json-values/spec.humble", "Decode.elm");
+    assert!(decoder.contains(r#"|> required "payload" D.value"#));
+    assert!(decoder.contains(r#"|> required "history" (D.list D.value)"#));
+}
+
+/// Compile the Elm code generated for the showcase, along with a module using it, with `elm make`.
+///
+/// Needs the `elm` compiler on the `PATH` and network access to fetch Elm packages, run
+/// it using `cargo test -- --ignored`.
+#[test]
+#[ignore]
+fn generated_code_compiles() {
+    let project = tempfile::tempdir().expect("create temp dir");
+    let src = project.path().join("src").join("Api");
+    std::fs::create_dir_all(&src).expect("create source dir");
+    std::fs::write(project.path().join("elm.json"), ELM_JSON).expect("write elm.json");
+
+    let spec_file = std::fs::File::open("./tests/rust/showcase/spec.humble").expect("open spec");
+    let spec = humblegen::parse(spec_file).expect("parse humble spec file");
+    humblegen::backend::elm::Generator::new(humblegen::Artifact::ClientEndpoints, "Api".to_owned())
+        .expect("failed to init humblegen elm backend")
+        .generate(&spec, &src)
+        .expect("humblegen elm backend failed");
+    std::fs::write(project.path().join("src").join("Example.elm"), EXAMPLE_ELM)
+        .expect("write example module");
+
+    let status = std::process::Command::new("elm")
+        .current_dir(project.path())
+        .args(["make", "--output=/dev/null"])
+        .args([
+            "src/Api/Data.elm",
+            "src/Api/Decode.elm",
+            "src/Api/Encode.elm",
+            "src/Api/ServiceBuiltIn.elm",
+            "src/Example.elm",
+        ])
+        .status()
+        .expect("run elm make");
+    assert!(status.success(), "generated Elm code does not compile");
+}
+
+/// A module using the generated code.
+const EXAMPLE_ELM: &str = r#"module Example exposing (cyan, describeError)
+
+import Api.Data as Data
+import Api.ServiceBuiltIn exposing (ApiError(..), ServiceError(..))
+
+
+cyan : Data.Color
+cyan =
+    Data.color_Hsv 128 255 255
+
+
+describeError : ApiError -> String
+describeError error =
+    case error of
+        BadUrl url ->
+            "bad url " ++ url
+
+        Timeout ->
+            "timeout"
+
+        NetworkError ->
+            "network error"
+
+        BadStatus status ->
+            "status " ++ String.fromInt status
+
+        DecodeError message ->
+            "invalid response: " ++ message
+
+        ServiceError Authentication ->
+            "please log in"
+
+        ServiceError Authorization ->
+            "access denied"
+
+        ServiceError (Internal message) ->
+            "internal error: " ++ message
+
+        ServiceError (Custom { code }) ->
+            "error " ++ code
+
+        RuntimeError status name ->
+            name ++ " (status " ++ String.fromInt status ++ ")"
+"#;
+
+const ELM_JSON: &str = r#"{
+    "type": "application",
+    "source-directories": ["src"],
+    "elm-version": "0.19.1",
+    "dependencies": {
+        "direct": {
+            "danfishgold/base64-bytes": "1.1.0",
+            "elm/bytes": "1.0.8",
+            "elm/core": "1.0.5",
+            "elm/http": "2.0.0",
+            "elm/json": "1.1.3",
+            "elm/time": "1.0.0",
+            "elm/url": "1.0.0",
+            "justinmimbs/date": "4.0.1",
+            "rtfeldman/elm-iso8601-date-strings": "1.1.4"
+        },
+        "indirect": {
+            "elm/file": "1.0.5",
+            "elm/parser": "1.1.0"
+        }
+    },
+    "test-dependencies": {
+        "direct": {},
+        "indirect": {}
+    }
+}
+"#;
@@ -0,0 +1,74 @@
+use humblegen::CodeGenerator;
+
+use serde_json::{json, Value};
+
+/// Generate the JSON Schema documents for the given spec, checking that each is a valid
+/// JSON Schema. Returns a validator for the document of `type_name`.
+fn generate_validator(humble_spec: &str, type_name: &str) -> jsonschema::Validator {
+    let spec_file = std::fs::File::open(humble_spec).expect("open humble spec file");
+    let spec = humblegen::parse(spec_file).expect("parse humble spec file");
+    let outdir = tempfile::tempdir().expect("create temp dir");
+    let output = outdir.path().join("schemas");
+    humblegen::backend::jsonschema::Generator::default()
+        .generate(&spec, &output)
+        .expect("humblegen jsonschema backend failed");
+
+    for entry in std::fs::read_dir(&output).unwrap() {
+        let json = std::fs::read_to_string(entry.unwrap().path()).unwrap();
+        let schema: Value = serde_json::from_str(&json).unwrap();
+        assert!(jsonschema::meta::is_valid(&schema), "{}", json);
+    }
+
+    let json = std::fs::read_to_string(output.join(format!("{}.schema.json", type_name)))
+        .expect("one document per type");
+    jsonschema::validator_for(&serde_json::from_str(&json).unwrap()).expect("valid schema")
+}
+
+#[test]
+fn structs_validate_their_json_representation() {
+    let validator = generate_validator("./tests/rust/client/spec.humble", "Monster");
+
+    let monster = json!({ "id": 1, "name": "Gorgon", "hp": 10 });
+    assert!(validator.is_valid(&monster));
+
+    let mut wrong_type = monster.clone();
+    wrong_type["hp"] = json!("ten");
+    assert!(!validator.is_valid(&wrong_type));
+
+    let mut missing_field = monster;
+    missing_field.as_object_mut().unwrap().remove("name");
+    assert!(!validator.is_valid(&missing_field));
+}
+
+#[test]
+fn nullable_fields_are_required() {
+    let validator = generate_validator("./tests/rust/nullable-fields/spec.humble", "Monster");
+
+    let monster = json!({ "name": "Gorgon", "owner": null, "level": 3, "respawn": null });
+    assert!(validator.is_valid(&monster));
+
+    // optional fields may be `null` as well
+    let mut null_nickname = monster.clone();
+    null_nickname["nickname"] = Value::Null;
+    assert!(validator.is_valid(&null_nickname));
+
+    let mut wrong_type = monster.clone();
+    wrong_type["owner"] = json!(1);
+    assert!(!validator.is_valid(&wrong_type));
+
+    // the key of nullable fields is required
+    let mut missing_owner = monster;
+    missing_owner.as_object_mut().unwrap().remove("owner");
+    assert!(!validator.is_valid(&missing_owner));
+}
+
+#[test]
+fn tagged_enums_validate_their_json_representation() {
+    let validator = generate_validator("./tests/rust/tagged-enums/spec.humble", "Event");
+
+    assert!(validator.is_valid(&json!({ "kind": "Started" })));
+    assert!(validator.is_valid(&json!({ "kind": "Moved", "data": [1, 2] })));
+    assert!(validator.is_valid(&json!({ "kind": "Attacked", "data": { "damage": 3 } })));
+    assert!(!validator.is_valid(&json!({ "kind": "Moved", "data": [1, 2, 3] })));
+    assert!(!validator.is_valid(&json!({ "kind": "Exploded" })));
+}
@@ -0,0 +1,187 @@
+use humblegen::CodeGenerator;
+
+use serde_json::Value;
+
+/// Generate the OpenAPI document for the given spec, checking that it is a valid OpenAPI 3.0
+/// document whose references all resolve.
+fn generate_document(humble_spec: &str) -> Value {
+    let spec_file = std::fs::File::open(humble_spec).expect("open humble spec file");
+    let spec = humblegen::parse(spec_file).expect("parse humble spec file");
+    let outdir = tempfile::tempdir().expect("create temp dir");
+    let output = outdir.path().join("api.json");
+    humblegen::backend::openapi::Generator::default()
+        .generate(&spec, &output)
+        .expect("humblegen openapi backend failed");
+
+    let json = std::fs::read_to_string(&output).expect("read generated document");
+    serde_json::from_str::<openapiv3::OpenAPI>(&json).expect("document adheres to OpenAPI 3.0");
+    let document: Value = serde_json::from_str(&json).unwrap();
+    assert_references_resolve(&document, &document);
+    document
+}
+
+fn assert_references_resolve(document: &Value, value: &Value) {
+    match value {
+        Value::Object(object) => {
+            if let Some(reference) = object.get("$ref") {
+                let pointer = reference
+                    .as_str()
+                    .and_then(|r| r.strip_prefix('#'))
+                    .expect("local reference");
+                assert!(
+                    document.pointer(pointer).is_some(),
+                    "unresolved reference {}",
+                    reference
+                );
+            }
+            object
+                .values()
+                .for_each(|v| assert_references_resolve(document, v));
+        }
+        Value::Array(array) => array
+            .iter()
+            .for_each(|v| assert_references_resolve(document, v)),
+        _ => {}
+    }
+}
+
+#[test]
+fn atom_types_have_formats() {
+    let document = generate_document("./tests/rust/showcase/spec.humble");
+    let customer = &document["components"]["schemas"]["Customer"]["properties"];
+    assert_eq!(customer["join_date"]["type"], "string");
+    assert_eq!(customer["join_date"]["format"], "date-time");
+    assert_eq!(customer["birthday"]["format"], "date");
+    assert_eq!(customer["unique_id"]["format"], "uuid");
+    assert_eq!(customer["profile_pic"]["format"], "byte");
+    assert_eq!(customer["credit_limit"]["type"], "string");
+    assert_eq!(customer["isVIP"]["type"], "boolean");
+    assert_eq!(customer["email"]["nullable"], true);
+    assert_eq!(customer["bets"]["additionalProperties"]["format"], "double");
+
+    let required = document["components"]["schemas"]["Customer"]["required"]
+        .as_array()
+        .unwrap();
+    assert!(required.contains(&"isVIP".into()));
+    assert!(!required.contains(&"email".into()));
+}
+
+#[test]
+fn endpoints_become_operations() {
+    let document = generate_document("./tests/rust/client/spec.humble");
+    let paths = &document["paths"];
+
+    let get_monster = &paths["/monsters/{id}"]["get"];
+    assert_eq!(get_monster["operationId"], "monsters_get_monsters_id");
+    assert_eq!(get_monster["description"], "Look up a single monster.");
+    assert_eq!(get_monster["parameters"][0]["in"], "path");
+    assert_eq!(get_monster["parameters"][0]["name"], "id");
+    assert_eq!(
+        get_monster["responses"]["200"]["content"]["application/json"]["schema"]["$ref"],
+        "#/components/schemas/Monster"
+    );
+    assert!(paths["/monsters/{id}"]["delete"].is_object());
+
+    let post_monster = &paths["/monsters"]["post"];
+    assert_eq!(
+        post_monster["requestBody"]["content"]["application/json"]["schema"]["$ref"],
+        "#/components/schemas/MonsterData"
+    );
+
+    let search = &paths["/search"]["get"]["parameters"];
+    assert_eq!(search[0]["name"], "name");
+    assert_eq!(search[0]["in"], "query");
+    assert_eq!(search[0]["required"], false);
+    assert_eq!(search[1]["name"], "min_hp");
+    assert_eq!(search[1]["required"], true);
+
+    let whoami = &paths["/whoami"]["get"]["parameters"];
+    assert_eq!(whoami[0]["name"], "X-Tenant-Id");
+    assert_eq!(whoami[0]["in"], "header");
+    assert_eq!(whoami[0]["required"], true);
+    assert_eq!(whoami[1]["required"], false);
+
+    let tag = &paths["/tags/{tag}"]["get"]["parameters"][0];
+    assert_eq!(tag["schema"]["pattern"], "^(?:[0-9]+)$");
+
+    assert!(paths["/thumbnail"]["get"]["responses"]["200"]["content"]["*/*"].is_object());
+}
+
+#[test]
+fn streamed_responses_are_ndjson() {
+    let document = generate_document("./tests/rust/streaming/spec.humble");
+    let content = &document["paths"]["/spawns"]["get"]["responses"]["200"]["content"];
+    assert_eq!(
+        content["application/x-ndjson"]["schema"]["$ref"],
+        "#/components/schemas/Monster"
+    );
+}
+
+#[test]
+fn declared_success_status_is_the_response_code() {
+    let document = generate_document("./tests/rust/status-codes/spec.humble");
+    let responses = &document["paths"]["/monsters"]["post"]["responses"];
+    assert!(responses["201"]["content"]["application/json"].is_object());
+    assert!(responses["200"].is_null());
+    assert!(responses["default"].is_object());
+}
+
+#[test]
+fn declared_error_status_is_a_response_code() {
+    let document = generate_document("./tests/rust/error-status/spec.humble");
+    let responses = &document["paths"]["/monsters/{id}"]["get"]["responses"];
+    assert!(responses["200"].is_object());
+    let schema = &responses["404"]["content"]["application/json"]["schema"];
+    assert_eq!(
+        schema["properties"]["Err"]["$ref"],
+        "#/components/schemas/MonsterError"
+    );
+    assert!(document["paths"]["/legacy/{id}"]["get"]["responses"]["404"].is_null());
+}
+
+#[test]
+fn paginated_responses_are_pages() {
+    let document = generate_document("./tests/rust/pagination/spec.humble");
+    let operation = &document["paths"]["/monsters"]["get"];
+    let names: Vec<_> = operation["parameters"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|param| param["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, vec!["limit", "offset"]);
+
+    let schema = &operation["responses"]["200"]["content"]["application/json"]["schema"];
+    assert_eq!(
+        schema["required"],
+        serde_json::json!(["items", "total", "limit", "offset"])
+    );
+    assert_eq!(
+        schema["properties"]["items"]["items"]["$ref"],
+        "#/components/schemas/Monster"
+    );
+}
+
+#[test]
+fn service_base_prefixes_paths() {
+    let document = generate_document("./tests/rust/service-base/spec.humble");
+    let paths = &document["paths"];
+    assert!(paths["/v1/monsters"]["get"].is_object());
+    assert!(paths["/v1/monsters/{id}"]["get"].is_object());
+    assert!(paths["/monsters"].is_null());
+}
+
+#[test]
+fn tagged_enums_have_a_tag_property() {
+    let document = generate_document("./tests/rust/tagged-enums/spec.humble");
+    let schemas = &document["components"]["schemas"];
+
+    let too_strong = &schemas["RecruitError"]["oneOf"][0];
+    assert_eq!(too_strong["properties"]["type"]["enum"][0], "TooStrong");
+    assert_eq!(too_strong["required"], serde_json::json!(["type", "power"]));
+
+    let moved = &schemas["Event"]["oneOf"][1];
+    assert_eq!(moved["properties"]["kind"]["enum"][0], "Moved");
+    assert_eq!(moved["properties"]["data"]["type"], "array");
+    assert_eq!(moved["required"], serde_json::json!(["kind", "data"]));
+}
@@ -0,0 +1,1043 @@
+#[test]
+fn unknown_rename_all_casing_is_a_parse_error() {
+    let spec = "@rename_all(\"PascalCase\")\nstruct Foo {\n    bar: str,\n}\n";
+    let err = humblegen::parse(spec.as_bytes()).expect_err("unknown casing must not parse");
+    let msg = err.to_string();
+    assert!(msg.contains("unknown casing \"PascalCase\""), "{}", msg);
+    assert!(msg.contains("\"camelCase\""), "{}", msg);
+}
+
+#[test]
+fn route_param_patterns() {
+    let spec = "service Files {\n    GET /files/{path: str as \"rest\"}/tags/{tag: str as /[a-z]\\/[0-9]+/} -> str,\n}\n";
+    let spec = humblegen::parse(spec.as_bytes()).expect("parse route param patterns");
+    let service = match spec.iter().next().unwrap() {
+        humblegen::ast::SpecItem::ServiceDef(service) => service,
+        other => panic!("expected service, got {:?}", other),
+    };
+    let patterns: Vec<_> = service.endpoints[0]
+        .route
+        .components()
+        .iter()
+        .filter_map(|c| match c {
+            humblegen::ast::ServiceRouteComponent::Variable(_, pattern) => Some(pattern.clone()),
+            humblegen::ast::ServiceRouteComponent::Literal(_) => None,
+        })
+        .collect();
+    assert_eq!(
+        patterns,
+        vec![
+            humblegen::ast::RouteParamPattern::Rest,
+            humblegen::ast::RouteParamPattern::Regex("[a-z]/[0-9]+".to_owned()),
+        ]
+    );
+}
+
+#[test]
+fn unknown_route_param_class_is_a_parse_error() {
+    let spec = "service Files {\n    GET /files/{path: str as \"everything\"} -> str,\n}\n";
+    let err = humblegen::parse(spec.as_bytes()).expect_err("unknown class must not parse");
+    let msg = err.to_string();
+    assert!(
+        msg.contains("unknown route parameter class \"everything\""),
+        "{}",
+        msg
+    );
+    assert!(msg.contains("\"rest\""), "{}", msg);
+}
+
+#[test]
+fn invalid_route_param_regex_is_a_parse_error() {
+    let spec = "service Files {\n    GET /tags/{tag: str as /[0-9/} -> str,\n}\n";
+    let err = humblegen::parse(spec.as_bytes()).expect_err("invalid regex must not parse");
+    let msg = err.to_string();
+    assert!(msg.contains("invalid route parameter regex"), "{}", msg);
+    assert!(msg.contains("2:28"), "{}", msg);
+
+    let spec = "service Files {\n    GET /tags/{id: str as /(?P<id>x)/} -> str,\n}\n";
+    let err = humblegen::parse(spec.as_bytes()).expect_err("capture group named like a param");
+    let msg = err.to_string();
+    assert!(
+        msg.contains("must not have a capture group named like the route parameter `id`"),
+        "{}",
+        msg
+    );
+}
+
+#[test]
+fn service_base_path() {
+    let spec = "service Monsters {\n    base \"/api-v1/monsters\",\n    GET /all -> str,\n}\n";
+    let spec = humblegen::parse(spec.as_bytes()).expect("parse service base");
+    let service = spec.iter().find_map(|item| item.service_def()).unwrap();
+    assert_eq!(service.base.as_deref(), Some("/api-v1/monsters"));
+    assert_eq!(service.base_segments(), vec!["api-v1", "monsters"]);
+
+    let spec = "service Monsters { base \"/v1\" }";
+    humblegen::parse(spec.as_bytes()).expect("parse service without endpoints");
+}
+
+#[test]
+fn websocket_endpoint() {
+    let spec = "service Events {\n    WS /events/{room: str}?since: u64 -> str,\n}\n";
+    let spec = humblegen::parse(spec.as_bytes()).expect("parse WS endpoint");
+    let service = spec.iter().find_map(|item| item.service_def()).unwrap();
+    let route = &service.endpoints[0].route;
+    assert!(route.is_websocket());
+    assert_eq!(route.http_method_as_str(), "WS");
+    assert_eq!(
+        route.response_kind(),
+        humblegen::ast::ResponseKind::WebSocket
+    );
+    assert!(route.request_body().is_none());
+    assert_eq!(route.query_fields().len(), 1);
+}
+
+#[test]
+fn event_stream_endpoint() {
+    let spec =
+        "service Notifications {\n    SSE /notifications header \"X-User\": str -> str,\n}\n";
+    let spec = humblegen::parse(spec.as_bytes()).expect("parse SSE endpoint");
+    let service = spec.iter().find_map(|item| item.service_def()).unwrap();
+    let route = &service.endpoints[0].route;
+    assert!(route.is_event_stream());
+    assert_eq!(route.http_method_as_str(), "SSE");
+    assert_eq!(
+        route.response_kind(),
+        humblegen::ast::ResponseKind::EventStream
+    );
+    assert_eq!(route.headers().len(), 1);
+}
+
+#[test]
+fn proxy_route() {
+    let spec = "service Monsters {\n    base \"/v1\",\n    GET /monsters -> str,\n    /// The old system.\n    PROXY /legacy/* -> \"http://legacy:8080/\",\n    PROXY /* -> \"http://fallback\",\n}\n";
+    let spec = humblegen::parse(spec.as_bytes()).expect("parse proxy routes");
+    let service = spec.iter().find_map(|item| item.service_def()).unwrap();
+    assert_eq!(service.endpoints.len(), 1);
+    assert_eq!(service.proxies.len(), 2);
+    let proxy = &service.proxies[0];
+    assert_eq!(proxy.doc_comment.as_deref(), Some("The old system."));
+    assert_eq!(proxy.prefix, vec!["legacy"]);
+    assert_eq!(proxy.upstream, "http://legacy:8080");
+    assert!(service.proxies[1].prefix.is_empty());
+
+    for upstream in &[
+        "legacy:8080",
+        "https://legacy",
+        "http://",
+        "http://legacy?x=1",
+    ] {
+        let spec = format!(
+            "service Monsters {{\n    PROXY /legacy/* -> \"{}\",\n}}\n",
+            upstream
+        );
+        let err = humblegen::parse(spec.as_bytes()).expect_err("invalid upstream must not parse");
+        let msg = err.to_string();
+        assert!(
+            msg.contains(&format!("invalid upstream \"{}\"", upstream)),
+            "{}",
+            msg
+        );
+    }
+}
+
+#[test]
+fn invalid_service_base_is_a_parse_error() {
+    for base in &["v1", "/v1/", "/v1//monsters", "/{id}"] {
+        let spec = format!("service Monsters {{\n    base \"{}\",\n}}\n", base);
+        let err = humblegen::parse(spec.as_bytes()).expect_err("invalid base must not parse");
+        let msg = err.to_string();
+        assert!(
+            msg.contains(&format!("invalid base \"{}\"", base)),
+            "{}",
+            msg
+        );
+    }
+}
+
+#[test]
+fn enum_tagging() {
+    let spec = "@tag(\"type\")\nenum A { X, Y { y: i32 } }\n@tag(\"t\", content = \"c\")\nenum B { X(i32, i32) }\nenum C { X }\n";
+    let spec = humblegen::parse(spec.as_bytes()).expect("parse tagged enums");
+    let taggings: Vec<_> = spec
+        .iter()
+        .filter_map(|item| item.enum_def())
+        .map(|edef| edef.tagging().clone())
+        .collect();
+    assert_eq!(
+        taggings,
+        vec![
+            humblegen::ast::EnumTagging::Internal {
+                tag: "type".to_owned()
+            },
+            humblegen::ast::EnumTagging::Adjacent {
+                tag: "t".to_owned(),
+                content: "c".to_owned()
+            },
+            humblegen::ast::EnumTagging::External,
+        ]
+    );
+}
+
+#[test]
+fn invalid_enum_tagging_is_a_parse_error() {
+    let cases = [
+        (
+            "@tag(\"type\")\nenum A { X(i32, i32) }",
+            "internally tagged enums only support simple and struct variants",
+        ),
+        (
+            "@tag(\"type\")\nenum A { X(str) }",
+            "internally tagged enums only support simple and struct variants",
+        ),
+        (
+            "@tag(\"kind\")\nenum A { X { kind: str } }",
+            "tag \"kind\" conflicts with a field of variant `X`",
+        ),
+        (
+            "@tag(\"t\", content = \"t\")\nenum A { X(str) }",
+            "tag and content must be different",
+        ),
+    ];
+    for (spec, expected) in &cases {
+        let err = humblegen::parse(spec.as_bytes()).expect_err("invalid tagging must not parse");
+        let msg = err.to_string();
+        assert!(msg.contains(expected), "{}", msg);
+    }
+}
+
+#[test]
+fn field_defaults() {
+    let spec = "struct Monster {\n    level: u8 = 1,\n    speed: f32 = -2,\n    hostile: bool = true,\n    \
+                title: str = \"the brave\",\n    name: str,\n}";
+    let spec = humblegen::parse(spec.as_bytes()).expect("parse field defaults");
+    let defaults: Vec<_> = spec
+        .iter()
+        .next()
+        .unwrap()
+        .struct_def()
+        .unwrap()
+        .fields
+        .iter()
+        .map(|field| field.default.clone())
+        .collect();
+    use humblegen::ast::DefaultValue;
+    assert_eq!(
+        defaults,
+        vec![
+            Some(DefaultValue::Int(1)),
+            Some(DefaultValue::Float(-2.0)),
+            Some(DefaultValue::Bool(true)),
+            Some(DefaultValue::Str("the brave".to_owned())),
+            None,
+        ]
+    );
+}
+
+#[test]
+fn invalid_field_default_is_a_parse_error() {
+    let cases = [
+        (
+            "struct A { a: u8 = 256 }",
+            "256 is not a valid default value for a field of type \"u8\"",
+        ),
+        (
+            "struct A { a: u32 = -1 }",
+            "-1 is not a valid default value",
+        ),
+        (
+            "struct A { a: i32 = 1.5 }",
+            "1.5 is not a valid default value",
+        ),
+        (
+            "struct A { a: str = true }",
+            "true is not a valid default value",
+        ),
+        (
+            "struct A { a: option[i32] = 1 }",
+            "field of type \"option[i32]\"",
+        ),
+        (
+            "enum A { X { a: bool = \"yes\" } }",
+            "\"yes\" is not a valid default value",
+        ),
+    ];
+    for (spec, expected) in &cases {
+        let err = humblegen::parse(spec.as_bytes()).expect_err("invalid default must not parse");
+        let msg = err.to_string();
+        assert!(msg.contains(expected), "{}", msg);
+    }
+}
+
+#[test]
+fn range_constraints() {
+    let spec =
+        "struct A {\n    a: i32 in 0..=150,\n    b: u8 in 1..100,\n    c: i16 in -40..,\n    \
+                d: option[u64] in ..=5,\n    e: i32,\n}";
+    let spec = humblegen::parse(spec.as_bytes()).expect("parse range constraints");
+    let ranges: Vec<_> = spec
+        .iter()
+        .next()
+        .unwrap()
+        .struct_def()
+        .unwrap()
+        .fields
+        .iter()
+        .map(|field| field.pair.range)
+        .collect();
+    use humblegen::ast::RangeConstraint;
+    let range = |min, max| Some(RangeConstraint { min, max });
+    assert_eq!(
+        ranges,
+        vec![
+            range(Some(0), Some(150)),
+            range(Some(1), Some(99)),
+            range(Some(-40), None),
+            range(None, Some(5)),
+            None,
+        ]
+    );
+}
+
+#[test]
+fn invalid_range_is_a_parse_error() {
+    let cases = [
+        (
+            "struct A { a: str in 0..=1 }",
+            "ranges are only supported for integer fields",
+        ),
+        (
+            "struct A { a: f32 in 0..=1 }",
+            "ranges are only supported for integer fields",
+        ),
+        (
+            "struct A { a: u8 in 0..=256 }",
+            "the range 0..=256 exceeds the values of type \"u8\"",
+        ),
+        ("struct A { a: u32 in -1.. }", "exceeds the values of type"),
+        ("struct A { a: i32 in 5..=4 }", "the range 5..=4 is empty"),
+        ("struct A { a: i32 in 5..5 }", "the range 5..=4 is empty"),
+        (
+            "struct A { a: i32 in 0..= }",
+            "an inclusive range needs an upper bound",
+        ),
+        (
+            "struct A { a: i32 in .. }",
+            "a range needs a lower or an upper bound",
+        ),
+        (
+            "struct A { a: i32 in 1..=10 = 0 }",
+            "default value 0 is not in the range 1..=10 of field `a`",
+        ),
+    ];
+    for (spec, expected) in &cases {
+        let err = humblegen::parse(spec.as_bytes()).expect_err("invalid range must not parse");
+        let msg = err.to_string();
+        assert!(msg.contains(expected), "{}", msg);
+    }
+}
+
+#[test]
+fn invalid_bytes_encoding_is_a_parse_error() {
+    let cases = [
+        (
+            "struct A { @encoding(\"base32\") a: bytes }",
+            "unknown encoding \"base32\", expected one of \"base64\", \"hex\"",
+        ),
+        (
+            "struct A { @encoding(\"hex\") a: str }",
+            "@encoding is only supported for \"bytes\" fields, but field `a` is of type \"str\"",
+        ),
+    ];
+    for (spec, expected) in &cases {
+        let err = humblegen::parse(spec.as_bytes()).expect_err("invalid encoding must not parse");
+        let msg = err.to_string();
+        assert!(msg.contains(expected), "{}", msg);
+    }
+}
+
+#[test]
+fn embed_cycle_is_an_error() {
+    let spec =
+        "struct Monster { id: i32, .. MonsterData }\nstruct MonsterData { name: str, .. Monster }";
+    match humblegen::parse(spec.as_bytes()) {
+        Err(humblegen::LibError::EmbedCycle { chain }) => {
+            assert_eq!(chain, vec!["Monster", "MonsterData", "Monster"])
+        }
+        other => panic!("expected embed cycle, got {:?}", other),
+    }
+}
+
+#[test]
+fn max_embed_depth_can_be_raised() {
+    let spec = "struct A { .. B }\nstruct B { .. C }\nstruct C { c: i32 }";
+    let options = humblegen::parser::ParserOptions { max_embed_depth: 1 };
+    match humblegen::parse_with_options(spec.as_bytes(), &options) {
+        Err(humblegen::LibError::EmbedTooDeep { max_depth, chain }) => {
+            assert_eq!(max_depth, 1);
+            assert_eq!(chain, vec!["A", "B", "C"]);
+        }
+        other => panic!("expected too deep embeds, got {:?}", other),
+    }
+    let options = humblegen::parser::ParserOptions { max_embed_depth: 2 };
+    humblegen::parse_with_options(spec.as_bytes(), &options).expect("embeds within the limit");
+}
+
+#[test]
+fn patch_struct_fields_are_optional() {
+    let spec = "@patch_of(MonsterData)\nstruct MonsterPatch { id: i32 }\n\
+                struct MonsterData { name: str, nickname: option[str], .. Stats }\n\
+                struct Stats { hp: i32 }";
+    let spec = humblegen::parse(spec.as_bytes()).expect("parse patch struct");
+    let patch = spec
+        .iter()
+        .filter_map(|item| item.struct_def())
+        .find(|def| def.name == "MonsterPatch")
+        .unwrap();
+    let fields: Vec<_> = patch
+        .fields
+        .iter()
+        .map(|field| (field.pair.name.as_str(), &field.pair.type_ident))
+        .collect();
+    assert_eq!(fields.len(), 4);
+    assert_eq!(fields[0].0, "id");
+    assert!(matches!(fields[0].1, humblegen::ast::TypeIdent::BuiltIn(_)));
+    for (name, type_ident) in &fields[1..] {
+        match type_ident {
+            humblegen::ast::TypeIdent::Option(inner) => assert!(
+                !matches!(**inner, humblegen::ast::TypeIdent::Option(_)),
+                "{} must not be nested",
+                name
+            ),
+            other => panic!("{} must be optional, got {:?}", name, other),
+        }
+    }
+    assert_eq!(
+        fields[1..]
+            .iter()
+            .map(|(name, _)| *name)
+            .collect::<Vec<_>>(),
+        vec!["name", "nickname", "hp"]
+    );
+}
+
+#[test]
+fn invalid_patches_are_errors() {
+    let spec = "@patch_of(Kind)\nstruct KindPatch {}\nenum Kind { A }";
+    match humblegen::parse(spec.as_bytes()) {
+        Err(humblegen::LibError::InvalidPatch { patch, patch_of }) => {
+            assert_eq!((patch.as_str(), patch_of.as_str()), ("KindPatch", "Kind"))
+        }
+        other => panic!("expected invalid patch, got {:?}", other),
+    }
+
+    let spec = "@patch_of(B)\nstruct A {}\n@patch_of(A)\nstruct B {}";
+    match humblegen::parse(spec.as_bytes()) {
+        Err(humblegen::LibError::PatchCycle { chain }) => assert_eq!(chain, vec!["A", "B", "A"]),
+        other => panic!("expected patch cycle, got {:?}", other),
+    }
+}
+
+#[test]
+fn parse_errors_point_at_the_offending_line() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("broken.humble");
+    std::fs::write(&path, "struct Foo {\n    bar: str,\n    baz str,\n}\n").unwrap();
+
+    let err = humblegen::parse_file(&path, &Default::default()).expect_err("must not parse");
+    match &err {
+        humblegen::LibError::ParseError {
+            path: Some(err_path),
+            line,
+            ..
+        } => {
+            assert_eq!(err_path, &path);
+            assert_eq!(*line, 3);
+        }
+        other => panic!("expected parse error, got {:?}", other),
+    }
+    let msg = err.to_string();
+    assert!(msg.contains("broken.humble:3:"), "{}", msg);
+    assert!(msg.contains("3 |     baz str,"), "{}", msg);
+}
+
+/// Write the given humble files to a temporary directory.
+fn write_spec_files(files: &[(&str, &str)]) -> tempfile::TempDir {
+    let dir = tempfile::tempdir().unwrap();
+    for (name, contents) in files {
+        let path = dir.path().join(name);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, contents).unwrap();
+    }
+    dir
+}
+
+#[test]
+fn imports_are_merged_into_the_spec() {
+    let dir = write_spec_files(&[
+        (
+            "main.humble",
+            "import \"services/monsters.humble\"\nimport \"types.humble\"\nstruct Arena { monsters: list[Monster] }\n",
+        ),
+        (
+            "services/monsters.humble",
+            "import \"../types.humble\"\nservice Monsters {\n    GET /monsters -> list[Monster],\n}\n",
+        ),
+        ("types.humble", "struct Monster { .. MonsterData }\nstruct MonsterData { name: str }\n"),
+    ]);
+
+    let spec = humblegen::parse_file(dir.path().join("main.humble"), &Default::default())
+        .expect("parse spec with imports");
+    let names: Vec<_> = spec
+        .iter()
+        .map(|spec_item| match spec_item {
+            humblegen::ast::SpecItem::StructDef(sdef) => sdef.name.as_str(),
+            humblegen::ast::SpecItem::EnumDef(edef) => edef.name.as_str(),
+            humblegen::ast::SpecItem::ServiceDef(service) => service.name.as_str(),
+        })
+        .collect();
+    // the shared file is only included once, imported items come first
+    assert_eq!(names, vec!["Monster", "MonsterData", "Monsters", "Arena"]);
+
+    // embeds across files are resolved
+    match &spec.items[0] {
+        humblegen::ast::SpecItem::StructDef(sdef) => {
+            assert_eq!(sdef.fields.0[0].pair.name, "name")
+        }
+        other => panic!("expected struct, got {:?}", other),
+    }
+}
+
+#[test]
+fn title_and_version_of_the_importing_file_are_kept() {
+    let dir = write_spec_files(&[
+        (
+            "main.humble",
+            "@title \"Monster API\"\n@version \"1.4.0\"\nimport \"types.humble\"\n",
+        ),
+        (
+            "types.humble",
+            "@title \"Types\"\n@version \"0.1.0\"\nstruct Monster { name: str }\n",
+        ),
+    ]);
+
+    let spec = humblegen::parse_file(dir.path().join("main.humble"), &Default::default())
+        .expect("parse spec with imports");
+    assert_eq!(spec.title.as_deref(), Some("Monster API"));
+    assert_eq!(spec.version.as_deref(), Some("1.4.0"));
+
+    let spec = humblegen::parse_file(dir.path().join("types.humble"), &Default::default())
+        .expect("parse spec");
+    assert_eq!(spec.title.as_deref(), Some("Types"));
+    assert_eq!(spec.version.as_deref(), Some("0.1.0"));
+}
+
+#[test]
+fn import_cycle_is_an_error() {
+    let dir = write_spec_files(&[
+        ("a.humble", "import \"b.humble\"\nstruct A { a: i32 }\n"),
+        ("b.humble", "import \"a.humble\"\nstruct B { b: i32 }\n"),
+    ]);
+    match humblegen::parse_file(dir.path().join("a.humble"), &Default::default()) {
+        Err(humblegen::LibError::ImportCycle { chain }) => {
+            let names: Vec<_> = chain.iter().map(|p| p.file_name().unwrap()).collect();
+            assert_eq!(names, vec!["a.humble", "b.humble", "a.humble"]);
+        }
+        other => panic!("expected import cycle, got {:?}", other),
+    }
+}
+
+#[test]
+fn duplicate_type_across_files_is_an_error() {
+    let dir = write_spec_files(&[
+        (
+            "a.humble",
+            "import \"b.humble\"\nstruct Monster { a: i32 }\n",
+        ),
+        ("b.humble", "struct Monster { b: i32 }\n"),
+    ]);
+    match humblegen::parse_file(dir.path().join("a.humble"), &Default::default()) {
+        Err(humblegen::LibError::DuplicateType {
+            name,
+            first,
+            second,
+        }) => {
+            assert_eq!(name, "Monster");
+            assert_eq!(first.file_name().unwrap(), "b.humble");
+            assert_eq!(second.file_name().unwrap(), "a.humble");
+        }
+        other => panic!("expected duplicate type, got {:?}", other),
+    }
+}
+
+#[test]
+fn missing_import_is_a_parse_error() {
+    let dir = write_spec_files(&[("a.humble", "struct A { a: i32 }\nimport \"b.humble\"\n")]);
+    humblegen::parse_file(dir.path().join("a.humble"), &Default::default())
+        .expect_err("imports must come first");
+
+    let dir = write_spec_files(&[("a.humble", "import \"b.humble\"\nstruct A { a: i32 }\n")]);
+    let err = humblegen::parse_file(dir.path().join("a.humble"), &Default::default())
+        .expect_err("imported file does not exist");
+    let msg = err.to_string();
+    assert!(msg.contains("cannot find imported file"), "{}", msg);
+    assert!(msg.contains("a.humble:1:"), "{}", msg);
+}
+
+#[test]
+fn deprecation_notes() {
+    let spec_file = std::fs::File::open("./tests/rust/deprecation/spec.humble").unwrap();
+    let spec = humblegen::parse(spec_file).expect("parse deprecation spec");
+    let mut items = spec.iter();
+
+    let monster = items.next().unwrap().struct_def().unwrap();
+    assert_eq!(monster.deprecated.as_deref(), Some("use Creature instead"));
+    let creature = items.next().unwrap().struct_def().unwrap();
+    assert_eq!(creature.deprecated, None);
+    let deprecated: Vec<_> = creature
+        .fields
+        .iter()
+        .map(|field| field.deprecated.as_deref())
+        .collect();
+    assert_eq!(deprecated, vec![None, Some("use health instead"), None]);
+    let kind = items.next().unwrap().enum_def().unwrap();
+    assert_eq!(
+        kind.deprecated.as_deref(),
+        Some("kinds are no longer distinguished")
+    );
+
+    let service = spec.iter().last().unwrap().service_def().unwrap();
+    let deprecated: Vec<_> = service
+        .endpoints
+        .iter()
+        .map(|endpoint| endpoint.deprecated.as_deref())
+        .collect();
+    assert_eq!(
+        deprecated,
+        vec![Some("use GET /creatures/{name} instead"), None, None]
+    );
+}
+
+#[test]
+fn success_status() {
+    let spec = "service Monsters {\n    POST /monsters -> str -> u32 status 201,\n    \
+                GET /monsters -> list[str],\n    DELETE /monsters -> raw bytes status 202,\n}";
+    let spec = humblegen::parse(spec.as_bytes()).expect("parse success status");
+    let service = spec.iter().find_map(|item| item.service_def()).unwrap();
+    let statuses: Vec<_> = service
+        .endpoints
+        .iter()
+        .map(|endpoint| endpoint.route.status())
+        .collect();
+    assert_eq!(statuses, vec![201, 200, 202]);
+}
+
+#[test]
+fn invalid_success_status_is_a_parse_error() {
+    let cases = [
+        (
+            "404",
+            "the status of successful responses must be in the range 200..=299, but is 404",
+        ),
+        (
+            "204",
+            "status 204 cannot be used, since responses always have a body",
+        ),
+    ];
+    for (status, expected) in &cases {
+        let spec = format!(
+            "service Monsters {{ GET /monsters -> str status {} }}",
+            status
+        );
+        let err = humblegen::parse(spec.as_bytes()).expect_err("invalid status must not parse");
+        let msg = err.to_string();
+        assert!(msg.contains(expected), "{}", msg);
+    }
+}
+
+#[test]
+fn error_status() {
+    let spec = "enum MonsterError { NotFound }
+service Monsters {
+    \
+                @error_status(404)
+    GET /monsters/{id: u32} -> result[str][MonsterError],
+    \
+                GET /monsters -> result[str][MonsterError],
+}";
+    let spec = humblegen::parse(spec.as_bytes()).expect("parse error status");
+    let service = spec.iter().find_map(|item| item.service_def()).unwrap();
+    let statuses: Vec<_> = service
+        .endpoints
+        .iter()
+        .map(|endpoint| endpoint.error_status)
+        .collect();
+    assert_eq!(statuses, vec![Some(404), None]);
+}
+
+#[test]
+fn invalid_error_status_is_a_parse_error() {
+    let cases = [
+        (
+            "@error_status(200) GET /monsters -> result[str][u32]",
+            "the status of error responses must be in the range 400..=599, but is 200",
+        ),
+        (
+            "@error_status(404) GET /monsters -> str",
+            "`@error_status` requires a return type like `result[Monster][MonsterError]`",
+        ),
+        (
+            "@error_status(404) GET /monsters -> stream[result[str][u32]]",
+            "`@error_status` requires a return type like `result[Monster][MonsterError]`",
+        ),
+    ];
+    for (endpoint, expected) in &cases {
+        let spec = format!("service Monsters {{ {} }}", endpoint);
+        let err =
+            humblegen::parse(spec.as_bytes()).expect_err("invalid error status must not parse");
+        let msg = err.to_string();
+        assert!(msg.contains(expected), "{}", msg);
+    }
+}
+
+#[test]
+fn max_body() {
+    let spec = "service Uploads {
+    POST /small -> str -> u32,
+    @max_body(512) POST /bytes -> str -> u32,
+    @max_body(4kb) POST /kilobytes -> str -> u32,
+    @max_body(16mb) PUT /megabytes -> str -> u32,
+    @max_body(1gb) PATCH /gigabytes -> str -> u32,
+}";
+    let spec = humblegen::parse(spec.as_bytes()).expect("parse max body");
+    let service = spec.iter().find_map(|item| item.service_def()).unwrap();
+    let sizes: Vec<_> = service
+        .endpoints
+        .iter()
+        .map(|endpoint| endpoint.max_body_size)
+        .collect();
+    assert_eq!(
+        sizes,
+        vec![
+            None,
+            Some(512),
+            Some(4 * 1024),
+            Some(16 * 1024 * 1024),
+            Some(1024 * 1024 * 1024)
+        ]
+    );
+}
+
+#[test]
+fn invalid_max_body_is_a_parse_error() {
+    let cases = [
+        (
+            "@max_body(0kb) POST /monsters -> str -> u32",
+            "the maximum body size must be greater than zero",
+        ),
+        (
+            "@max_body(1mb) GET /monsters -> str",
+            "`@max_body` requires an endpoint with a request body",
+        ),
+    ];
+    for (endpoint, expected) in &cases {
+        let spec = format!("service Monsters {{ {} }}", endpoint);
+        let err = humblegen::parse(spec.as_bytes()).expect_err("invalid max body must not parse");
+        let msg = err.to_string();
+        assert!(msg.contains(expected), "{}", msg);
+    }
+}
+
+#[test]
+fn custom_methods() {
+    let spec_file = std::fs::File::open("./tests/rust/custom-methods/spec.humble").unwrap();
+    let spec = humblegen::parse(spec_file).expect("parse custom methods spec");
+    let service = spec.iter().find_map(|item| item.service_def()).unwrap();
+    let methods: Vec<_> = service
+        .endpoints
+        .iter()
+        .map(|endpoint| {
+            (
+                endpoint.route.http_method_as_str(),
+                endpoint.route.request_body().is_some(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        methods,
+        vec![
+            ("OPTIONS", false),
+            ("PROPFIND", false),
+            ("REPORT", true),
+            ("GET", false)
+        ]
+    );
+
+    let spec = "service Files {\n    \"GET\" /files -> str,\n}\n";
+    let err = humblegen::parse(spec.as_bytes()).expect_err("quoted GET must not parse");
+    let msg = err.to_string();
+    assert!(
+        msg.contains("`GET` endpoints are declared without quotes"),
+        "{}",
+        msg
+    );
+}
+
+#[test]
+fn paginated_endpoints() {
+    let spec_file = std::fs::File::open("./tests/rust/pagination/spec.humble").unwrap();
+    let spec = humblegen::parse(spec_file).expect("parse pagination spec");
+    assert!(spec.has_paginated_endpoints());
+    let service = spec.iter().find_map(|item| item.service_def()).unwrap();
+    let search = &service.endpoints[1].route;
+    assert!(search.is_paginated());
+    match search.return_type() {
+        humblegen::ast::TypeIdent::UserDefined(name) => assert_eq!(name, "Monster"),
+        other => panic!("expected the item type, got {:?}", other),
+    }
+    let query_fields: Vec<_> = search
+        .query_fields()
+        .iter()
+        .map(|field| field.name.as_str())
+        .collect();
+    assert_eq!(query_fields, vec!["name", "limit", "offset"]);
+}
+
+#[test]
+fn invalid_pagination_is_a_parse_error() {
+    let cases = [
+        (
+            "GET /monsters -> str",
+            "only endpoints returning a `list[T]` can be paginated",
+        ),
+        (
+            "GET /monsters?{Filter} -> list[str]",
+            "paginated endpoints cannot have a query type, use query fields instead",
+        ),
+        (
+            "GET /monsters?limit: u32 -> list[str]",
+            "the query field `limit` is added by `@paginated`",
+        ),
+    ];
+    for (endpoint, expected) in &cases {
+        let spec = format!(
+            "struct Filter {{ name: str }}\nservice Monsters {{\n    @paginated\n    {},\n}}",
+            endpoint
+        );
+        let err = humblegen::parse(spec.as_bytes()).expect_err("invalid pagination must not parse");
+        let msg = err.to_string();
+        assert!(msg.contains(expected), "{}", msg);
+    }
+
+    let spec = "struct Page { n: i32 }\nservice Monsters {\n    @paginated\n    GET /monsters -> list[str],\n}";
+    match humblegen::parse(spec.as_bytes()) {
+        Err(humblegen::LibError::PageTypeConflict) => {}
+        other => panic!("expected page type conflict, got {:?}", other),
+    }
+}
+
+#[test]
+fn variant_values() {
+    let spec_file = std::fs::File::open("./tests/rust/enum-values/spec.humble").unwrap();
+    let spec = humblegen::parse(spec_file).expect("parse enum values spec");
+    let color = spec.iter().next().unwrap().enum_def().unwrap();
+    let wire_names: Vec<_> = color
+        .variants
+        .iter()
+        .map(|variant| variant.wire_name())
+        .collect();
+    assert_eq!(wire_names, vec!["Red", "BLUE_07", "green"]);
+}
+
+#[test]
+fn nullable_fields() {
+    let spec = "struct Monster { owner: nullable[str], nickname: option[str] }\n\
+                @patch_of(Monster) struct MonsterPatch {}";
+    let spec = humblegen::parse(spec.as_bytes()).expect("parse nullable fields");
+    let mut structs = spec.iter().filter_map(|item| item.struct_def());
+
+    let monster = structs.next().unwrap();
+    let types: Vec<_> = monster.fields.iter().map(|f| &f.pair.type_ident).collect();
+    assert!(matches!(types[0], humblegen::ast::TypeIdent::Nullable(_)));
+    assert!(matches!(types[1], humblegen::ast::TypeIdent::Option(_)));
+
+    // patches cannot distinguish `null` from a missing key
+    let patch = structs.next().unwrap();
+    assert!(patch
+        .fields
+        .iter()
+        .all(|f| matches!(f.pair.type_ident, humblegen::ast::TypeIdent::Option(_))));
+}
+
+#[test]
+fn invalid_variant_value_is_a_parse_error() {
+    let cases = [
+        (
+            "enum Shape { @value(\"circle\") Circle(f64) }",
+            "`@value` can only be used on simple variants, but variant `Circle` is not one",
+        ),
+        (
+            "enum Color { Red, @value(\"Red\") Blue }",
+            "variant `Blue` is represented as \"Red\", just like variant `Red`",
+        ),
+    ];
+    for (spec, expected) in &cases {
+        let err = humblegen::parse(spec.as_bytes()).expect_err("invalid value must not parse");
+        let msg = err.to_string();
+        assert!(msg.contains(expected), "{}", msg);
+    }
+}
+
+#[test]
+fn namespaces() {
+    let spec_file = std::fs::File::open("./tests/rust/namespaces/spec.humble").unwrap();
+    let spec = humblegen::parse(spec_file).expect("parse namespaces spec");
+    let services: Vec<_> = spec
+        .iter()
+        .filter_map(|item| item.service_def())
+        .map(|service| service.qualified_name("."))
+        .collect();
+    assert_eq!(
+        services,
+        vec!["Monsters", "Admin.Monsters", "Admin.Audit.Log"]
+    );
+}
+
+#[test]
+fn service_named_like_a_namespace_is_an_error() {
+    let spec = "namespace Admin {\n    service Audit {}\n    namespace Audit {\n        service Log {}\n    }\n}\n";
+    let err = humblegen::parse(spec.as_bytes()).expect_err("conflicting names must not parse");
+    assert_eq!(
+        err.to_string(),
+        "`Admin.Audit` is defined both as a service and as a namespace"
+    );
+}
+
+#[test]
+fn invalid_default_query_is_an_error() {
+    let spec = "service Monsters {\n    @default_query\n    GET /monsters?name: str -> str,\n}\n";
+    let err = humblegen::parse(spec.as_bytes()).expect_err("query fields must not parse");
+    let msg = err.to_string();
+    assert!(
+        msg.contains("`@default_query` requires a query type like `?{MonsterQuery}`"),
+        "{}",
+        msg
+    );
+
+    let spec = "struct Filter { name: option[str] }\nservice Monsters {\n    @default_query\n    GET /monsters?{Filter} -> str,\n}\n";
+    let err = humblegen::parse(spec.as_bytes()).expect_err("query without default must not parse");
+    assert_eq!(
+        err.to_string(),
+        "`@default_query` requires the query type `Filter` to be a struct using `@derive(Default)`"
+    );
+}
+
+#[test]
+fn visitor_counts_endpoints() {
+    use humblegen::ast::visit::{self, Visitor};
+
+    #[derive(Default)]
+    struct Counter {
+        services: Vec<String>,
+        endpoints: usize,
+        user_defined: usize,
+    }
+
+    impl<'ast> Visitor<'ast> for Counter {
+        fn visit_service_def(&mut self, service: &'ast humblegen::ast::ServiceDef) {
+            self.services.push(service.name.clone());
+            visit::walk_service_def(self, service);
+        }
+
+        fn visit_service_endpoint(&mut self, endpoint: &'ast humblegen::ast::ServiceEndpoint) {
+            self.endpoints += 1;
+            visit::walk_service_endpoint(self, endpoint);
+        }
+
+        fn visit_type_ident(&mut self, type_ident: &'ast humblegen::ast::TypeIdent) {
+            if let humblegen::ast::TypeIdent::UserDefined(_) = type_ident {
+                self.user_defined += 1;
+            }
+            visit::walk_type_ident(self, type_ident);
+        }
+    }
+
+    let spec_file = std::fs::File::open("./tests/rust/service/spec.humble").unwrap();
+    let spec = humblegen::parse(spec_file).expect("parse service spec");
+    let mut counter = Counter::default();
+    counter.visit_spec(&spec);
+    assert_eq!(counter.services, vec!["Godzilla", "Movies"]);
+    assert_eq!(counter.endpoints, 12);
+    assert!(counter.user_defined > 0);
+}
+
+#[test]
+fn mutable_visitor_transforms_the_spec() {
+    use humblegen::ast::visit::{self, VisitorMut};
+    use humblegen::ast::TypeIdent;
+
+    /// Renames every use of a type.
+    struct Rename(&'static str, &'static str);
+
+    impl VisitorMut for Rename {
+        fn visit_type_ident_mut(&mut self, type_ident: &mut TypeIdent) {
+            if let TypeIdent::UserDefined(name) = type_ident {
+                if name == self.0 {
+                    *name = self.1.to_owned();
+                }
+            }
+            visit::walk_type_ident_mut(self, type_ident);
+        }
+    }
+
+    let spec = "struct Team { leader: Monster, members: list[Monster] }\n\
+                service Monsters { POST /monsters -> Monster -> option[Monster] }";
+    let mut spec = humblegen::parse(spec.as_bytes()).expect("parse spec");
+    Rename("Monster", "Creature").visit_spec_mut(&mut spec);
+
+    let debug = format!("{:?}", spec);
+    assert!(!debug.contains("\"Monster\""), "{}", debug);
+    assert_eq!(debug.matches("\"Creature\"").count(), 4, "{}", debug);
+}
+
+#[test]
+fn nodes_have_source_spans() {
+    let spec = "struct Stats { hp: i32 }\n\
+                \n\
+                /// A monster.\n\
+                struct Monster {\n    name: str,\n    .. Stats\n}\n\
+                service Monsters {\n    GET /monsters -> list[Monster],\n}\n";
+    let parsed = humblegen::parse(spec.as_bytes()).expect("parse spec");
+    let monster = parsed
+        .iter()
+        .filter_map(|item| item.struct_def())
+        .find(|def| def.name == "Monster")
+        .unwrap();
+
+    // the span of a definition includes its doc comment
+    let span = monster.span.expect("parsed structs have a span");
+    let start = spec.find("/// A monster.").unwrap();
+    let end = spec.find("}\nservice").unwrap() + 1;
+    assert_eq!((span.start, span.end), (start, end));
+    assert_eq!((span.line, span.col), (3, 1));
+
+    let name = monster.fields.iter().next().unwrap().span.unwrap();
+    assert_eq!(&spec[name.start..name.end], "name: str");
+    assert_eq!((name.line, name.col), (5, 5));
+
+    // fields inlined from embedded structs point at their original definition
+    let hp = monster.fields.iter().nth(1).unwrap().span.unwrap();
+    assert_eq!(&spec[hp.start..hp.end], "hp: i32");
+
+    let service = parsed.iter().find_map(|item| item.service_def()).unwrap();
+    let endpoint = service.endpoints[0].span.unwrap();
+    assert_eq!(
+        &spec[endpoint.start..endpoint.end],
+        "GET /monsters -> list[Monster]"
+    );
+    let start = spec.find("GET").unwrap();
+    assert_eq!(
+        format!("{:?}", endpoint),
+        format!("Span({}..{} @ 9:5)", start, start + 30)
+    );
+}
@@ -9,20 +9,59 @@ struct RustTestCase {
     name: String,
     humble_spec: PathBuf,
     humble_rust_out: PathBuf,
+    /// Reference output of the Rust backend with the client artifact, if the case uses it.
+    humble_rust_client_out: Option<PathBuf>,
+    /// Options of the Rust backend, if the case does not use the defaults.
+    generator_options: Option<PathBuf>,
     main: PathBuf,
 }
 
 impl RustTestCase {
+    /// The Rust backend for `artifact`, configured using the options of the test case.
+    ///
+    /// The options are a JSON object, e.g. `{"deny_unknown_fields": "request-bodies"}`.
+    fn generator(&self, artifact: humblegen::Artifact) -> humblegen::backend::rust::Generator {
+        let mut codegen = humblegen::backend::rust::Generator::new(artifact)
+            .expect("failed to init humblegen rust backend");
+        let options: serde_json::Value = match &self.generator_options {
+            Some(path) => serde_json::from_str(
+                &std::fs::read_to_string(path).expect("read generator options"),
+            )
+            .expect("parse generator options"),
+            None => return codegen,
+        };
+        for (option, value) in options.as_object().expect("generator options object") {
+            codegen = match (option.as_str(), value.as_str()) {
+                ("deny_unknown_fields", Some("request-bodies")) => codegen
+                    .with_deny_unknown_fields(
+                        humblegen::backend::rust::DenyUnknownFields::RequestBodies,
+                    ),
+                ("deny_unknown_fields", Some("all")) => codegen
+                    .with_deny_unknown_fields(humblegen::backend::rust::DenyUnknownFields::All),
+                ("server_runtime", Some("axum")) => {
+                    codegen.with_server_runtime(humblegen::backend::rust::ServerRuntime::Axum)
+                }
+                _ => panic!("unknown generator option {}: {}", option, value),
+            };
+        }
+        codegen
+    }
+
     fn run(&self) {
         let spec_file = std::fs::File::open(&self.humble_spec).expect("open humble spec file");
         let spec = humblegen::parse(spec_file).expect("parse humble spec file");
-        let codegen =
-            humblegen::backend::rust::Generator::new(humblegen::Artifact::ServerEndpoints)
-                .expect("failed to init humblegen rust backend");
+        let codegen = self.generator(humblegen::Artifact::ServerEndpoints);
         codegen
             .generate(&spec, &self.humble_rust_out)
             .expect("humblegen rust backend failed");
 
+        if let Some(humble_rust_client_out) = &self.humble_rust_client_out {
+            let codegen = self.generator(humblegen::Artifact::ClientEndpoints);
+            codegen
+                .generate(&spec, humble_rust_client_out)
+                .expect("humblegen rust backend failed");
+        }
+
         let t = trybuild::TestCases::new();
         t.pass(&self.main);
         // cases run on drop of t
@@ -63,7 +102,20 @@ impl RustTestCase {
             "main.rs",
             "consumer of generated code (the test case)",
         );
-        let mut required_files = vec![&mut humble_spec, &mut humble_rust_out, &mut main];
+        let mut humble_rust_client_out = RequiredFile(
+            None,
+            "client.rs",
+            "reference output of Rust backend for spec.humble with the client artifact",
+        );
+        let mut generator_options =
+            RequiredFile(None, "generator.json", "options of the Rust backend");
+        let mut required_files = vec![
+            &mut humble_spec,
+            &mut humble_rust_out,
+            &mut humble_rust_client_out,
+            &mut generator_options,
+            &mut main,
+        ];
 
         for entry in entries {
             let name = entry
@@ -83,6 +135,8 @@ impl RustTestCase {
             name: name.to_string(),
             humble_spec: humble_spec.must_exist()?,
             humble_rust_out: humble_rust_out.must_exist()?,
+            humble_rust_client_out: humble_rust_client_out.0,
+            generator_options: generator_options.0,
             main: main.must_exist()?,
         })
     }
@@ -106,3 +160,137 @@ fn rust() {
         test.run();
     }
 }
+
+/// Generate code for `spec` using `codegen`, which must fail, and return the error.
+fn generate_err(spec: &str, codegen: humblegen::backend::rust::Generator) -> humblegen::LibError {
+    let spec = humblegen::parse(spec.as_bytes()).expect("parse humble spec");
+    let outdir = tempfile::tempdir().expect("create temp dir");
+    codegen
+        .generate(&spec, &outdir.path().join("spec.rs"))
+        .expect_err("humblegen rust backend must reject the spec")
+}
+
+/// The Rust backend for `artifact` with default options.
+fn generator(artifact: humblegen::Artifact) -> humblegen::backend::rust::Generator {
+    humblegen::backend::rust::Generator::new(artifact)
+        .expect("failed to init humblegen rust backend")
+}
+
+#[test]
+fn unsupported_derive_is_rejected() {
+    let spec = "@derive(PartialEq, Eq)\nstruct Reading {\n    value: f64,\n}\n";
+    let err = generate_err(spec, generator(humblegen::Artifact::TypesOnly));
+    assert_eq!(
+        err.to_string(),
+        "cannot derive `Eq` for `Reading`: field `value` contains `f64`, which does not implement `Eq`"
+    );
+}
+
+#[test]
+fn default_derive_requires_defaultable_fields() {
+    let spec =
+        "enum Mood {\n    Happy,\n}\n\n@derive(Default)\nstruct Monster {\n    mood: Mood,\n}\n";
+    let err = generate_err(spec, generator(humblegen::Artifact::TypesOnly));
+    assert_eq!(
+        err.to_string(),
+        "cannot derive `Default` for `Monster`: field `mood` contains `Mood`, which does not implement `Default`"
+    );
+}
+
+#[test]
+fn nested_wide_integer_is_rejected() {
+    let spec = "struct Snowflakes {\n    ids: list[u128],\n}\n";
+    let err = generate_err(spec, generator(humblegen::Artifact::TypesOnly));
+    assert!(
+        matches!(
+            err,
+            humblegen::LibError::UnsupportedWideInteger { ref location }
+                if location == "field `ids` of `Snowflakes`"
+        ),
+        "{}",
+        err
+    );
+}
+
+#[test]
+fn duration_outside_of_fields_is_rejected() {
+    let specs = [
+        (
+            "struct Schedule {\n    intervals: list[duration],\n}\n",
+            "field `intervals` of `Schedule`",
+        ),
+        (
+            "service Schedules {\n    GET /schedules/{interval: duration} -> str,\n}\n",
+            "endpoint `GET /schedules/{interval}` of `Schedules`",
+        ),
+    ];
+    for (spec, expected_location) in specs.iter() {
+        let err = generate_err(spec, generator(humblegen::Artifact::ServerEndpoints));
+        assert!(
+            matches!(
+                err,
+                humblegen::LibError::UnsupportedDuration { ref location }
+                    if location == expected_location
+            ),
+            "{}",
+            err
+        );
+    }
+}
+
+#[test]
+fn flattened_query_embeds_are_rejected() {
+    let spec = "struct Pagination {\n    limit: option[u32],\n}\n\n\
+                @flatten_embeds\n\
+                struct MonsterQuery {\n    name: option[str],\n    ..Pagination,\n}\n\n\
+                service Monsters {\n    GET /monsters?{MonsterQuery} -> str,\n}\n";
+    let err = generate_err(spec, generator(humblegen::Artifact::ServerEndpoints));
+    assert!(
+        matches!(
+            err,
+            humblegen::LibError::FlattenedQueryEmbeds { ref type_name } if type_name == "MonsterQuery"
+        ),
+        "{}",
+        err
+    );
+}
+
+#[test]
+fn axum_runtime_rejects_unsupported_routes() {
+    let specs = [
+        (
+            "service Events {\n    WS /events -> str,\n}\n",
+            "endpoint `WS /events` of `Events`",
+        ),
+        (
+            "service Legacy {\n    PROXY /legacy/* -> \"http://legacy:8080\",\n}\n",
+            "route `PROXY /legacy/*` of `Legacy`",
+        ),
+        (
+            "service Tags {\n    GET /tags/{tag: str as /[0-9]+/} -> str,\n}\n",
+            "endpoint `GET /tags/{tag}` of `Tags`",
+        ),
+        (
+            "service Files {\n    GET /files/{path: str as \"rest\"}/content -> str,\n}\n",
+            "endpoint `GET /files/{path}/content` of `Files`",
+        ),
+        (
+            "service Events {\n    GET /events -> list[str],\n    SSE /events -> str,\n}\n",
+            "endpoint `SSE /events` of `Events`",
+        ),
+    ];
+    for (spec, expected_location) in specs.iter() {
+        let codegen = generator(humblegen::Artifact::ServerEndpoints)
+            .with_server_runtime(humblegen::backend::rust::ServerRuntime::Axum);
+        let err = generate_err(spec, codegen);
+        assert!(
+            matches!(
+                err,
+                humblegen::LibError::AxumUnsupportedRoute { ref location, .. }
+                    if location == expected_location
+            ),
+            "{}",
+            err
+        );
+    }
+}
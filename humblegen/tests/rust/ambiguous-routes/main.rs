@@ -0,0 +1,91 @@
+include!("spec.rs");
+
+use humblegen_rt::server::BuilderError;
+
+struct AmbiguousService;
+
+#[humblegen_rt::async_trait(Sync)]
+impl Ambiguous for AmbiguousService {
+    type Context = ();
+
+    async fn get_monsters_id(&self, _ctx: Self::Context, id: u32) -> Response<Monster> {
+        Ok(Monster {
+            id,
+            name: "Gorgon".to_owned(),
+        })
+    }
+
+    async fn get_monsters_new(&self, _ctx: Self::Context) -> Response<Monster> {
+        Ok(Monster {
+            id: 0,
+            name: String::new(),
+        })
+    }
+}
+
+struct UnambiguousService;
+
+#[humblegen_rt::async_trait(Sync)]
+impl Unambiguous for UnambiguousService {
+    type Context = ();
+
+    async fn get_monsters_id(&self, _ctx: Self::Context, id: u32) -> Response<Monster> {
+        Ok(Monster {
+            id,
+            name: "Gorgon".to_owned(),
+        })
+    }
+
+    async fn get_monsters_new(&self, _ctx: Self::Context) -> Response<Monster> {
+        Ok(Monster {
+            id: 0,
+            name: String::new(),
+        })
+    }
+
+    async fn delete_monsters_id(&self, _ctx: Self::Context, _id: u32) -> Response<()> {
+        Ok(())
+    }
+
+    async fn get_monsters_id_name(&self, _ctx: Self::Context, _id: u32) -> Response<String> {
+        Ok("Gorgon".to_owned())
+    }
+
+    async fn get_files_path(&self, _ctx: Self::Context, path: String) -> Response<String> {
+        Ok(path)
+    }
+}
+
+fn main() {
+    // overlapping routes are detected when the handler is added, not per request
+    match Builder::new().try_add("/api", Handler::Ambiguous(Arc::new(AmbiguousService))) {
+        Err(e @ BuilderError::InvalidRoutes(_)) => {
+            let message = e.to_string();
+            assert!(
+                message.contains("GET ^/monsters/(?P<id>[^/]+)$"),
+                "{}",
+                message
+            );
+            assert!(message.contains("GET ^/monsters/new$"), "{}", message);
+        }
+        other => panic!("expected ambiguous routes, got {:?}", other),
+    }
+
+    Builder::new()
+        .try_add("/api", Handler::Unambiguous(Arc::new(UnambiguousService)))
+        .expect("routes do not overlap")
+        .into_service()
+        .expect("valid service configuration");
+
+    // so are services mounted at overlapping roots
+    let err = Builder::new()
+        .add("/api", Handler::Unambiguous(Arc::new(UnambiguousService)))
+        .add("/api", Handler::Unambiguous(Arc::new(UnambiguousService)))
+        .into_service()
+        .unwrap_err();
+    assert!(
+        format!("{:#}", err).contains("match the same requests"),
+        "{:#}",
+        err
+    );
+}
@@ -0,0 +1,121 @@
+include!("spec.rs");
+
+use humblegen_rt::axum::body::Body;
+use humblegen_rt::axum::http::{Request, StatusCode};
+use tower::ServiceExt as _;
+
+struct MonstersService;
+
+#[humblegen_rt::async_trait(Sync)]
+impl Monsters for MonstersService {
+    type Context = ();
+
+    async fn get_monsters_id(&self, _ctx: Self::Context, id: i32) -> Response<Monster> {
+        Ok(Monster {
+            id,
+            name: "Goblin".to_owned(),
+        })
+    }
+
+    async fn post_monsters(
+        &self,
+        _ctx: Self::Context,
+        post_body: MonsterData,
+    ) -> Response<Monster> {
+        Ok(Monster {
+            id: 7,
+            name: post_body.name,
+        })
+    }
+
+    async fn delete_monsters_id(&self, _ctx: Self::Context, _id: i32) -> Response<()> {
+        Err(ServiceError::Authorization)
+    }
+
+    async fn get_files_path(&self, _ctx: Self::Context, path: String) -> Response<String> {
+        Ok(path)
+    }
+
+    async fn get_slow(&self, _ctx: Self::Context) -> Response<String> {
+        humblegen_rt::futures::future::pending().await
+    }
+}
+
+async fn body_of(response: humblegen_rt::axum::response::Response) -> String {
+    let body = humblegen_rt::axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    String::from_utf8(body.to_vec()).unwrap()
+}
+
+#[tokio::main]
+async fn main() {
+    let router = Handler::Monsters(Arc::new(MonstersService))
+        .into_router(Arc::new(server::ServerOptions {
+            request_timeout: Some(std::time::Duration::from_millis(50)),
+            ..server::ServerOptions::default()
+        }))
+        .expect("build router");
+
+    // route params are passed to the handler, which is invoked like by the built-in server
+    let request = Request::get("/v1/monsters/42").body(Body::empty()).unwrap();
+    let response = router.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()["content-type"], "application/json");
+    let monster: Monster = serde_json::from_str(&body_of(response).await).unwrap();
+    assert_eq!(monster.id, 42);
+
+    // request bodies are streamed to the handler, declared status codes are kept
+    let request = Request::post("/v1/monsters")
+        .body(Body::from(r#"{"name":"Orc"}"#))
+        .unwrap();
+    let response = router.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let monster: Monster = serde_json::from_str(&body_of(response).await).unwrap();
+    assert_eq!(monster.name, "Orc");
+
+    // errors of the handler are sent as error responses
+    let request = Request::delete("/v1/monsters/42")
+        .body(Body::empty())
+        .unwrap();
+    let response = router.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    // invalid route params are rejected before invoking the handler
+    let request = Request::get("/v1/monsters/goblin")
+        .body(Body::empty())
+        .unwrap();
+    let response = router.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    // rest params keep their slashes
+    let request = Request::get("/v1/files/a/b/c").body(Body::empty()).unwrap();
+    let response = router.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(body_of(response).await, "\"a/b/c\"");
+
+    // the request timeout applies to the handler, whose response is replaced by an error
+    let request = Request::get("/v1/slow").body(Body::empty()).unwrap();
+    let response = router.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::GATEWAY_TIMEOUT);
+
+    // options that do not apply to the axum runtime are rejected rather than ignored
+    let err = Handler::Monsters(Arc::new(MonstersService))
+        .into_router(Arc::new(server::ServerOptions {
+            cors: Some(server::CorsConfig::default()),
+            ..server::ServerOptions::default()
+        }))
+        .expect_err("CORS is not supported by the axum runtime");
+    match err {
+        server::BuilderError::UnsupportedByAxum(option) => assert_eq!(option, "cors"),
+        other => panic!("unexpected error {:?}", other),
+    }
+
+    // the router is mounted below a prefix like any other axum router
+    let app = humblegen_rt::axum::Router::new().nest("/api", router);
+    let request = Request::get("/api/v1/monsters/1")
+        .body(Body::empty())
+        .unwrap();
+    let response = app.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+}
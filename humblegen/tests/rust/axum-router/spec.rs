@@ -0,0 +1,274 @@
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[doc = ""]
+pub struct Monster {
+    #[doc = ""]
+    pub id: i32,
+    #[doc = ""]
+    pub name: String,
+}
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[doc = ""]
+pub struct MonsterData {
+    #[doc = ""]
+    pub name: String,
+}
+#[allow(unused_imports)]
+use ::humblegen_rt::deser_helpers::{
+    check_query_keys, deser_header, deser_optional_header, deser_optional_query_param, deser_param,
+    deser_post_data_limited, deser_query_param, deser_query_primitive,
+    deser_query_serde_urlencoded, deser_query_strict,
+};
+#[allow(unused_imports)]
+pub use ::humblegen_rt::handler::{
+    self, HandlerResponse as Response, InterceptOutcome, RawResponse, ServiceError, StreamResponse,
+};
+#[allow(unused_imports)]
+use ::humblegen_rt::regexset_map::RegexSetMap;
+#[allow(unused_imports)]
+use ::humblegen_rt::server::{
+    self, event_stream_handler_response_to_hyper_response, handler_response_to_hyper_response,
+    raw_handler_response_to_hyper_response, result_handler_response_to_hyper_response,
+    stream_handler_response_to_hyper_response, Route, Service,
+};
+#[allow(unused_imports)]
+use ::humblegen_rt::service_protocol::ErrorResponse;
+use ::humblegen_rt::tracing_futures::Instrument;
+#[allow(unused_imports)]
+use ::humblegen_rt::{hyper, tracing};
+#[allow(unused_imports)]
+use ::std::sync::Arc;
+#[doc = "Wrapper enum with one variant for each service defined in the humble spec.\nUsed to turn instantiated handler trait objects into an `axum::Router` using `Handler::into_router`."]
+#[allow(dead_code)]
+pub enum Handler<Context: Default + Sized + Send + Sync + 'static> {
+    Monsters(Arc<dyn Monsters<Context = Context> + Send + Sync>),
+}
+impl<Context: Default + Sized + Send + Sync + 'static> Handler<Context> {
+    #[doc = r" Builds an `axum::Router` serving the routes of the handler. Of the `options`,"]
+    #[doc = r" only `max_body_size`, `strict_query` and `request_timeout` apply, setting any"]
+    #[doc = r" other one fails with `BuilderError::UnsupportedByAxum`."]
+    pub fn into_router(
+        self,
+        options: Arc<server::ServerOptions>,
+    ) -> Result<::humblegen_rt::axum::Router, server::BuilderError> {
+        server::check_axum_options(&options)?;
+        match self {
+            Handler::Monsters(h) => Ok(router_Monsters(h, options)),
+        }
+    }
+}
+impl<Context: Default + Sized + Send + Sync + 'static> std::fmt::Debug for Handler<Context> {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Handler::Monsters(_) => write!(formatter, "{}", "Monsters")?,
+        }
+        Ok(())
+    }
+}
+#[doc = ""]
+#[doc = "```\n#[humblegen_rt::async_trait(Sync)]\npub trait Monsters {\n    type Context: Default + Sized + Send + Sync;\n    async fn intercept_handler_pre(\n        &self,\n        _req: &hyper::Request<hyper::Body>,\n    ) -> Result<InterceptOutcome<Self::Context>, ServiceError> {\n        Ok(InterceptOutcome::Continue(Self::Context::default()))\n    }\n    async fn intercept_handler_post(\n        &self,\n        resp: hyper::Response<hyper::Body>,\n    ) -> hyper::Response<hyper::Body> {\n        resp\n    }\n    async fn get_monsters_id(&self, ctx: Self::Context, id: i32) -> Response<Monster>;\n    async fn post_monsters(&self, ctx: Self::Context, post_body: MonsterData) -> Response<Monster>;\n    async fn delete_monsters_id(&self, ctx: Self::Context, id: i32) -> Response<()>;\n    async fn get_files_path(&self, ctx: Self::Context, path: String) -> Response<String>;\n    async fn get_slow(&self, ctx: Self::Context) -> Response<String>;\n}\n\n```"]
+#[allow(deprecated)]
+#[humblegen_rt::async_trait(Sync)]
+pub trait Monsters {
+    type Context: Default + Sized + Send + Sync;
+    async fn intercept_handler_pre(
+        &self,
+        _req: &hyper::Request<hyper::Body>,
+    ) -> Result<InterceptOutcome<Self::Context>, ServiceError> {
+        Ok(InterceptOutcome::Continue(Self::Context::default()))
+    }
+    async fn intercept_handler_post(
+        &self,
+        resp: hyper::Response<hyper::Body>,
+    ) -> hyper::Response<hyper::Body> {
+        resp
+    }
+    #[doc = "```\nasync fn get_monsters_id(&self, ctx: Self::Context, id: i32) -> Response<Monster> {}\n\n```"]
+    #[doc = ""]
+    async fn get_monsters_id(&self, ctx: Self::Context, id: i32) -> Response<Monster>;
+    #[doc = "```\nasync fn post_monsters(&self, ctx: Self::Context, post_body: MonsterData) -> Response<Monster> {}\n\n```"]
+    #[doc = ""]
+    async fn post_monsters(&self, ctx: Self::Context, post_body: MonsterData) -> Response<Monster>;
+    #[doc = "```\nasync fn delete_monsters_id(&self, ctx: Self::Context, id: i32) -> Response<()> {}\n\n```"]
+    #[doc = ""]
+    async fn delete_monsters_id(&self, ctx: Self::Context, id: i32) -> Response<()>;
+    #[doc = "```\nasync fn get_files_path(&self, ctx: Self::Context, path: String) -> Response<String> {}\n\n```"]
+    #[doc = ""]
+    async fn get_files_path(&self, ctx: Self::Context, path: String) -> Response<String>;
+    #[doc = "```\nasync fn get_slow(&self, ctx: Self::Context) -> Response<String> {}\n\n```"]
+    #[doc = ""]
+    async fn get_slow(&self, ctx: Self::Context) -> Response<String>;
+}
+#[allow(unused_variables)]
+#[allow(unused_mut)]
+#[allow(non_snake_case)]
+#[allow(deprecated)]
+fn router_Monsters<Context: Default + Sized + Send + Sync + 'static>(
+    handler: Arc<dyn Monsters<Context = Context> + Send + Sync>,
+    options: Arc<server::ServerOptions>,
+) -> ::humblegen_rt::axum::Router {
+    use ::humblegen_rt::axum::extract::{Path, Request};
+    use ::humblegen_rt::axum::routing;
+    use ::std::collections::HashMap;
+    let router = ::humblegen_rt::axum::Router::new();
+    let get_monsters_id = {
+        let handler = Arc::clone(&handler);
+        let options = Arc::clone(&options);
+        move |Path(captures): Path<HashMap<String, String>>, req: Request| {
+            let mut req = server::into_hyper_request(req);
+            let request_timeout = options.request_timeout;
+            server::into_axum_response(request_timeout, {
+                let handler = Arc::clone(&handler);
+                let id: Result<i32, ErrorResponse> = deser_param("id", &captures["id"]);
+                Box::pin(async move {
+                    use ::humblegen_rt::service_protocol::ToErrorResponse;
+                    let result : Result < hyper :: Response < hyper :: Body > , ErrorResponse > = async { let ctx = { let span = tracing :: error_span ! ("interceptor") ; handler . intercept_handler_pre (& req) . instrument (span) . await . map_err (:: humblegen_rt :: service_protocol :: ServiceError :: from) . map_err (| e | { tracing :: debug ! (service_error = ? format ! ("{:?}" , e) , "interceptor rejected request") ; e }) . map_err (| e | e . to_error_response ()) ? } ; let ctx = match ctx { InterceptOutcome :: Continue (ctx) => ctx , InterceptOutcome :: Respond (response) => { tracing :: debug ! (status = response . status () . as_u16 () , "interceptor responded") ; return Ok (response) ; } } ; let id = id ? ; if options . strict_query { check_query_keys (req . uri () . query () , & []) ? ; } drop (req) ; let span = tracing :: error_span ! ("handler") ; Ok (handler_response_to_hyper_response (200 , handler . get_monsters_id (ctx , id) . instrument (span) . await)) } . await ;
+                    let response = result.unwrap_or_else(|e| {
+                        tracing :: error ! (err = ? e , "request failed");
+                        e.to_hyper_response()
+                    });
+                    let span = tracing::error_span!("interceptor_post");
+                    Ok(handler
+                        .intercept_handler_post(response)
+                        .instrument(span)
+                        .await)
+                })
+            })
+        }
+    };
+    let router = router.route("/v1/monsters/:id", routing::get(get_monsters_id));
+    let post_monsters = {
+        let handler = Arc::clone(&handler);
+        let options = Arc::clone(&options);
+        move |req: Request| {
+            let mut req = server::into_hyper_request(req);
+            let request_timeout = options.request_timeout;
+            server::into_axum_response(request_timeout, {
+                let handler = Arc::clone(&handler);
+                Box::pin(async move {
+                    use ::humblegen_rt::service_protocol::ToErrorResponse;
+                    let result : Result < hyper :: Response < hyper :: Body > , ErrorResponse > = async { let ctx = { let span = tracing :: error_span ! ("interceptor") ; handler . intercept_handler_pre (& req) . instrument (span) . await . map_err (:: humblegen_rt :: service_protocol :: ServiceError :: from) . map_err (| e | { tracing :: debug ! (service_error = ? format ! ("{:?}" , e) , "interceptor rejected request") ; e }) . map_err (| e | e . to_error_response ()) ? } ; let ctx = match ctx { InterceptOutcome :: Continue (ctx) => ctx , InterceptOutcome :: Respond (response) => { tracing :: debug ! (status = response . status () . as_u16 () , "interceptor responded") ; return Ok (response) ; } } ; if options . strict_query { check_query_keys (req . uri () . query () , & []) ? ; } let post_body : MonsterData = deser_post_data_limited (req . body_mut () , options . max_body_size) . await ? ; drop (req) ; let span = tracing :: error_span ! ("handler") ; Ok (handler_response_to_hyper_response (201 , handler . post_monsters (ctx , post_body) . instrument (span) . await)) } . await ;
+                    let response = result.unwrap_or_else(|e| {
+                        tracing :: error ! (err = ? e , "request failed");
+                        e.to_hyper_response()
+                    });
+                    let span = tracing::error_span!("interceptor_post");
+                    Ok(handler
+                        .intercept_handler_post(response)
+                        .instrument(span)
+                        .await)
+                })
+            })
+        }
+    };
+    let router = router.route("/v1/monsters", routing::post(post_monsters));
+    let delete_monsters_id = {
+        let handler = Arc::clone(&handler);
+        let options = Arc::clone(&options);
+        move |Path(captures): Path<HashMap<String, String>>, req: Request| {
+            let mut req = server::into_hyper_request(req);
+            let request_timeout = options.request_timeout;
+            server::into_axum_response(request_timeout, {
+                let handler = Arc::clone(&handler);
+                let id: Result<i32, ErrorResponse> = deser_param("id", &captures["id"]);
+                Box::pin(async move {
+                    use ::humblegen_rt::service_protocol::ToErrorResponse;
+                    let result : Result < hyper :: Response < hyper :: Body > , ErrorResponse > = async { let ctx = { let span = tracing :: error_span ! ("interceptor") ; handler . intercept_handler_pre (& req) . instrument (span) . await . map_err (:: humblegen_rt :: service_protocol :: ServiceError :: from) . map_err (| e | { tracing :: debug ! (service_error = ? format ! ("{:?}" , e) , "interceptor rejected request") ; e }) . map_err (| e | e . to_error_response ()) ? } ; let ctx = match ctx { InterceptOutcome :: Continue (ctx) => ctx , InterceptOutcome :: Respond (response) => { tracing :: debug ! (status = response . status () . as_u16 () , "interceptor responded") ; return Ok (response) ; } } ; let id = id ? ; if options . strict_query { check_query_keys (req . uri () . query () , & []) ? ; } drop (req) ; let span = tracing :: error_span ! ("handler") ; Ok (handler_response_to_hyper_response (200 , handler . delete_monsters_id (ctx , id) . instrument (span) . await)) } . await ;
+                    let response = result.unwrap_or_else(|e| {
+                        tracing :: error ! (err = ? e , "request failed");
+                        e.to_hyper_response()
+                    });
+                    let span = tracing::error_span!("interceptor_post");
+                    Ok(handler
+                        .intercept_handler_post(response)
+                        .instrument(span)
+                        .await)
+                })
+            })
+        }
+    };
+    let router = router.route("/v1/monsters/:id", routing::delete(delete_monsters_id));
+    let get_files_path = {
+        let handler = Arc::clone(&handler);
+        let options = Arc::clone(&options);
+        move |Path(captures): Path<HashMap<String, String>>, req: Request| {
+            let mut req = server::into_hyper_request(req);
+            let request_timeout = options.request_timeout;
+            server::into_axum_response(request_timeout, {
+                let handler = Arc::clone(&handler);
+                let path: Result<String, ErrorResponse> = deser_param("path", &captures["path"]);
+                Box::pin(async move {
+                    use ::humblegen_rt::service_protocol::ToErrorResponse;
+                    let result : Result < hyper :: Response < hyper :: Body > , ErrorResponse > = async { let ctx = { let span = tracing :: error_span ! ("interceptor") ; handler . intercept_handler_pre (& req) . instrument (span) . await . map_err (:: humblegen_rt :: service_protocol :: ServiceError :: from) . map_err (| e | { tracing :: debug ! (service_error = ? format ! ("{:?}" , e) , "interceptor rejected request") ; e }) . map_err (| e | e . to_error_response ()) ? } ; let ctx = match ctx { InterceptOutcome :: Continue (ctx) => ctx , InterceptOutcome :: Respond (response) => { tracing :: debug ! (status = response . status () . as_u16 () , "interceptor responded") ; return Ok (response) ; } } ; let path = path ? ; if options . strict_query { check_query_keys (req . uri () . query () , & []) ? ; } drop (req) ; let span = tracing :: error_span ! ("handler") ; Ok (handler_response_to_hyper_response (200 , handler . get_files_path (ctx , path) . instrument (span) . await)) } . await ;
+                    let response = result.unwrap_or_else(|e| {
+                        tracing :: error ! (err = ? e , "request failed");
+                        e.to_hyper_response()
+                    });
+                    let span = tracing::error_span!("interceptor_post");
+                    Ok(handler
+                        .intercept_handler_post(response)
+                        .instrument(span)
+                        .await)
+                })
+            })
+        }
+    };
+    let router = router.route("/v1/files/*path", routing::get(get_files_path));
+    let get_slow = {
+        let handler = Arc::clone(&handler);
+        let options = Arc::clone(&options);
+        move |req: Request| {
+            let mut req = server::into_hyper_request(req);
+            let request_timeout = options.request_timeout;
+            server::into_axum_response(request_timeout, {
+                let handler = Arc::clone(&handler);
+                Box::pin(async move {
+                    use ::humblegen_rt::service_protocol::ToErrorResponse;
+                    let result : Result < hyper :: Response < hyper :: Body > , ErrorResponse > = async { let ctx = { let span = tracing :: error_span ! ("interceptor") ; handler . intercept_handler_pre (& req) . instrument (span) . await . map_err (:: humblegen_rt :: service_protocol :: ServiceError :: from) . map_err (| e | { tracing :: debug ! (service_error = ? format ! ("{:?}" , e) , "interceptor rejected request") ; e }) . map_err (| e | e . to_error_response ()) ? } ; let ctx = match ctx { InterceptOutcome :: Continue (ctx) => ctx , InterceptOutcome :: Respond (response) => { tracing :: debug ! (status = response . status () . as_u16 () , "interceptor responded") ; return Ok (response) ; } } ; if options . strict_query { check_query_keys (req . uri () . query () , & []) ? ; } drop (req) ; let span = tracing :: error_span ! ("handler") ; Ok (handler_response_to_hyper_response (200 , handler . get_slow (ctx ,) . instrument (span) . await)) } . await ;
+                    let response = result.unwrap_or_else(|e| {
+                        tracing :: error ! (err = ? e , "request failed");
+                        e.to_hyper_response()
+                    });
+                    let span = tracing::error_span!("interceptor_post");
+                    Ok(handler
+                        .intercept_handler_post(response)
+                        .instrument(span)
+                        .await)
+                })
+            })
+        }
+    };
+    let router = router.route("/v1/slow", routing::get(get_slow));
+    router
+}
+#[doc = "Paths of the routes of the `Monsters` service, relative to the root it is mounted at."]
+#[allow(dead_code)]
+pub mod monsters_paths {
+    #[allow(unused_imports)]
+    use super::*;
+    #[doc = "The path `/v1/monsters/{id}`."]
+    pub fn monsters_id_path(id: &i32) -> String {
+        ::humblegen_rt::serialization_helpers::build_path(&[
+            "v1".to_owned(),
+            "monsters".to_owned(),
+            id.to_string(),
+        ])
+    }
+    #[doc = "The path `/v1/monsters`."]
+    pub fn monsters_path() -> String {
+        ::humblegen_rt::serialization_helpers::build_path(&["v1".to_owned(), "monsters".to_owned()])
+    }
+    #[doc = "The path `/v1/files/{path}`."]
+    pub fn files_path_path(path: &String) -> String {
+        ::humblegen_rt::serialization_helpers::build_path(&{
+            let mut segments = Vec::new();
+            segments.push("v1".to_owned());
+            segments.push("files".to_owned());
+            segments.extend(path.to_string().split('/').map(str::to_owned));
+            segments
+        })
+    }
+    #[doc = "The path `/v1/slow`."]
+    pub fn slow_path() -> String {
+        ::humblegen_rt::serialization_helpers::build_path(&["v1".to_owned(), "slow".to_owned()])
+    }
+}
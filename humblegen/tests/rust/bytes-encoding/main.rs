@@ -0,0 +1,37 @@
+include!("spec.rs");
+
+fn main() {
+    let firmware = Firmware {
+        checksum: vec![0xc0, 0xff, 0xee, 0x00, 0x0a],
+        signature: vec![1, 2, 3],
+        image: vec![0xc0, 0xff, 0xee],
+    };
+
+    // hex fields use two lower case digits per byte, the others remain base64 encoded
+    let json = serde_json::to_string(&firmware).unwrap();
+    assert_eq!(
+        json,
+        r#"{"checksum":"c0ffee000a","signature":"AQID","image":"wP/u"}"#
+    );
+
+    let roundtripped: Firmware = serde_json::from_str(&json).unwrap();
+    assert_eq!(roundtripped.checksum, firmware.checksum);
+    assert_eq!(roundtripped.signature, firmware.signature);
+    assert_eq!(roundtripped.image, firmware.image);
+
+    // upper case digits are accepted as well
+    let received: Firmware =
+        serde_json::from_str(r#"{"checksum":"C0FFEE000A","signature":"","image":""}"#).unwrap();
+    assert_eq!(received.checksum, firmware.checksum);
+
+    for (invalid, expected) in &[
+        ("c0f", "odd number of hex digits"),
+        ("c0fg", "invalid hex digit `g`"),
+    ] {
+        let json = format!(r#"{{"checksum":"{}","signature":"","image":""}}"#, invalid);
+        let err = serde_json::from_str::<Firmware>(&json)
+            .err()
+            .expect("invalid hex must not deserialize");
+        assert!(err.to_string().contains(expected), "{}", err);
+    }
+}
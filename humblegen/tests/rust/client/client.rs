@@ -0,0 +1,260 @@
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[doc = ""]
+pub struct Monster {
+    #[doc = ""]
+    pub id: i32,
+    #[doc = ""]
+    pub name: String,
+    #[doc = ""]
+    pub hp: i32,
+}
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[doc = ""]
+pub struct MonsterData {
+    #[doc = ""]
+    pub name: String,
+    #[doc = ""]
+    pub hp: i32,
+}
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[doc = ""]
+pub struct MonsterQuery {
+    #[doc = ""]
+    pub name: Option<String>,
+    #[doc = ""]
+    pub min_hp: Option<i32>,
+}
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[doc = ""]
+pub enum MonsterError {
+    #[doc = ""]
+    TooWeak,
+}
+impl ::std::fmt::Display for MonsterError {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        f.write_str(match *self {
+            MonsterError::TooWeak => "TooWeak",
+        })
+    }
+}
+impl ::std::str::FromStr for MonsterError {
+    type Err = ::humblegen_rt::serialization_helpers::ParseEnumError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "TooWeak" => Ok(MonsterError::TooWeak),
+            _ => Err(::humblegen_rt::serialization_helpers::ParseEnumError {
+                enum_name: "MonsterError",
+                value: s.to_owned(),
+            }),
+        }
+    }
+}
+#[allow(unused_imports)]
+use ::humblegen_rt::client::{
+    build_url, parse_base_url, send_raw_request, send_request, send_result_request,
+    send_stream_request, ser_query_params, ser_query_primitive, ser_query_serde_urlencoded,
+};
+#[allow(unused_imports)]
+pub use ::humblegen_rt::client::{ClientError, RawResponse, StreamResponse};
+#[allow(unused_imports)]
+use ::humblegen_rt::reqwest;
+#[doc = "Client for the `Monsters` service. Requires the `client` feature of `humblegen_rt`."]
+#[doc = ""]
+#[doc = "A service for managing monsters."]
+#[derive(Debug, Clone)]
+pub struct MonstersClient {
+    base_url: reqwest::Url,
+    http_client: reqwest::Client,
+}
+#[allow(deprecated)]
+impl MonstersClient {
+    #[doc = r" Creates a client for the service mounted at `base_url`, e.g. `http://localhost:3000/api`."]
+    pub fn new(base_url: &str) -> Result<Self, ClientError> {
+        Self::with_http_client(base_url, reqwest::Client::new())
+    }
+    #[doc = r" Creates a client that sends its requests using the given `http_client`."]
+    pub fn with_http_client(
+        base_url: &str,
+        http_client: reqwest::Client,
+    ) -> Result<Self, ClientError> {
+        Ok(Self {
+            base_url: parse_base_url(base_url)?,
+            http_client,
+        })
+    }
+    #[doc = "Look up a single monster."]
+    pub async fn get_monsters_id(&self, id: &i32) -> Result<Monster, ClientError> {
+        let url = build_url(
+            &self.base_url,
+            &["monsters".to_owned(), id.to_string()],
+            None,
+        );
+        let request = self
+            .http_client
+            .request(::humblegen_rt::hyper::Method::GET, url);
+        send_request(request).await
+    }
+    #[doc = "Search for monsters."]
+    pub async fn get_monsters(
+        &self,
+        query: Option<&MonsterQuery>,
+    ) -> Result<Vec<Monster>, ClientError> {
+        let url = build_url(
+            &self.base_url,
+            &["monsters".to_owned()],
+            query.map(ser_query_serde_urlencoded).transpose()?,
+        );
+        let request = self
+            .http_client
+            .request(::humblegen_rt::hyper::Method::GET, url);
+        send_request(request).await
+    }
+    #[doc = "Create a monster."]
+    pub async fn post_monsters(
+        &self,
+        post_body: &MonsterData,
+    ) -> Result<Result<Monster, MonsterError>, ClientError> {
+        let url = build_url(&self.base_url, &["monsters".to_owned()], None);
+        let request = self
+            .http_client
+            .request(::humblegen_rt::hyper::Method::POST, url);
+        let request = request.json(post_body);
+        send_request(request).await
+    }
+    #[doc = "Delete a monster; requires authorization."]
+    pub async fn delete_monsters_id(&self, id: &i32) -> Result<(), ClientError> {
+        let url = build_url(
+            &self.base_url,
+            &["monsters".to_owned(), id.to_string()],
+            None,
+        );
+        let request = self
+            .http_client
+            .request(::humblegen_rt::hyper::Method::DELETE, url);
+        send_request(request).await
+    }
+    #[doc = "Echo the tenant and trace id headers."]
+    pub async fn get_whoami(
+        &self,
+        x_tenant_id: &String,
+        x_trace_id: Option<&u32>,
+    ) -> Result<String, ClientError> {
+        let url = build_url(&self.base_url, &["whoami".to_owned()], None);
+        let request = self
+            .http_client
+            .request(::humblegen_rt::hyper::Method::GET, url);
+        let request = request.header("X-Tenant-Id", x_tenant_id.to_string());
+        let request = match x_trace_id {
+            Some(value) => request.header("X-Trace-Id", value.to_string()),
+            None => request,
+        };
+        send_request(request).await
+    }
+    #[doc = "A PNG thumbnail, sent as is."]
+    pub async fn get_thumbnail(&self) -> Result<RawResponse, ClientError> {
+        let url = build_url(&self.base_url, &["thumbnail".to_owned()], None);
+        let request = self
+            .http_client
+            .request(::humblegen_rt::hyper::Method::GET, url);
+        send_raw_request(request).await
+    }
+    #[doc = "Echo a file path, which may contain slashes."]
+    pub async fn get_files_path_content(&self, path: &String) -> Result<String, ClientError> {
+        let url = build_url(
+            &self.base_url,
+            &{
+                let mut segments = Vec::new();
+                segments.push("files".to_owned());
+                segments.extend(path.to_string().split('/').map(str::to_owned));
+                segments.push("content".to_owned());
+                segments
+            },
+            None,
+        );
+        let request = self
+            .http_client
+            .request(::humblegen_rt::hyper::Method::GET, url);
+        send_request(request).await
+    }
+    #[doc = "Echo a numeric tag."]
+    pub async fn get_tags_tag(&self, tag: &String) -> Result<String, ClientError> {
+        let url = build_url(
+            &self.base_url,
+            &{
+                let mut segments = Vec::new();
+                segments.push("tags".to_owned());
+                segments.extend(tag.to_string().split('/').map(str::to_owned));
+                segments
+            },
+            None,
+        );
+        let request = self
+            .http_client
+            .request(::humblegen_rt::hyper::Method::GET, url);
+        send_request(request).await
+    }
+    #[doc = "Search for monsters using individual query parameters."]
+    pub async fn get_search(
+        &self,
+        name: Option<&String>,
+        min_hp: &i32,
+    ) -> Result<Vec<Monster>, ClientError> {
+        let url = build_url(
+            &self.base_url,
+            &["search".to_owned()],
+            ser_query_params(&[
+                ("name", name.map(ToString::to_string)),
+                ("min_hp", Some(min_hp.to_string())),
+            ])?,
+        );
+        let request = self
+            .http_client
+            .request(::humblegen_rt::hyper::Method::GET, url);
+        send_request(request).await
+    }
+}
+#[doc = "Paths of the routes of the `Monsters` service, relative to the root it is mounted at."]
+#[allow(dead_code)]
+pub mod monsters_paths {
+    #[allow(unused_imports)]
+    use super::*;
+    #[doc = "The path `/monsters/{id}`."]
+    pub fn monsters_id_path(id: &i32) -> String {
+        ::humblegen_rt::serialization_helpers::build_path(&["monsters".to_owned(), id.to_string()])
+    }
+    #[doc = "The path `/monsters`."]
+    pub fn monsters_path() -> String {
+        ::humblegen_rt::serialization_helpers::build_path(&["monsters".to_owned()])
+    }
+    #[doc = "The path `/whoami`."]
+    pub fn whoami_path() -> String {
+        ::humblegen_rt::serialization_helpers::build_path(&["whoami".to_owned()])
+    }
+    #[doc = "The path `/thumbnail`."]
+    pub fn thumbnail_path() -> String {
+        ::humblegen_rt::serialization_helpers::build_path(&["thumbnail".to_owned()])
+    }
+    #[doc = "The path `/files/{path}/content`."]
+    pub fn files_path_content_path(path: &String) -> String {
+        ::humblegen_rt::serialization_helpers::build_path(&{
+            let mut segments = Vec::new();
+            segments.push("files".to_owned());
+            segments.extend(path.to_string().split('/').map(str::to_owned));
+            segments.push("content".to_owned());
+            segments
+        })
+    }
+    #[doc = "The path `/tags/{tag}`."]
+    pub fn tags_tag_path(tag: &String) -> String {
+        ::humblegen_rt::serialization_helpers::build_path(&{
+            let mut segments = Vec::new();
+            segments.push("tags".to_owned());
+            segments.extend(tag.to_string().split('/').map(str::to_owned));
+            segments
+        })
+    }
+    #[doc = "The path `/search`."]
+    pub fn search_path() -> String {
+        ::humblegen_rt::serialization_helpers::build_path(&["search".to_owned()])
+    }
+}
@@ -0,0 +1,254 @@
+mod server {
+    include!("spec.rs");
+}
+mod client {
+    include!("client.rs");
+}
+
+use humblegen_rt::service_protocol::{RuntimeError, ServiceError as ProtocolServiceError};
+use std::sync::Arc;
+
+struct MonsterService;
+
+fn goblin(id: i32) -> server::Monster {
+    server::Monster {
+        id,
+        name: "Goblin".to_owned(),
+        hp: 7,
+    }
+}
+
+#[humblegen_rt::async_trait(Sync)]
+impl server::Monsters for MonsterService {
+    type Context = ();
+
+    async fn get_monsters_id(
+        &self,
+        _ctx: Self::Context,
+        id: i32,
+    ) -> server::Response<server::Monster> {
+        Ok(goblin(id))
+    }
+
+    async fn get_monsters(
+        &self,
+        _ctx: Self::Context,
+        query: Option<server::MonsterQuery>,
+    ) -> server::Response<Vec<server::Monster>> {
+        let query = query.expect("client sends query");
+        assert_eq!(query.name.as_deref(), Some("Goblin"));
+        assert_eq!(query.min_hp, Some(5));
+        Ok(vec![goblin(1), goblin(2)])
+    }
+
+    async fn post_monsters(
+        &self,
+        _ctx: Self::Context,
+        post_body: server::MonsterData,
+    ) -> server::Response<Result<server::Monster, server::MonsterError>> {
+        if post_body.hp < 5 {
+            return Ok(Err(server::MonsterError::TooWeak));
+        }
+        Ok(Ok(server::Monster {
+            id: 42,
+            name: post_body.name,
+            hp: post_body.hp,
+        }))
+    }
+
+    async fn delete_monsters_id(&self, _ctx: Self::Context, _id: i32) -> server::Response<()> {
+        Err(server::ServiceError::Authorization)
+    }
+
+    async fn get_whoami(
+        &self,
+        _ctx: Self::Context,
+        x_tenant_id: String,
+        x_trace_id: Option<u32>,
+    ) -> server::Response<String> {
+        Ok(format!("{}/{:?}", x_tenant_id, x_trace_id))
+    }
+
+    async fn get_files_path_content(
+        &self,
+        _ctx: Self::Context,
+        path: String,
+    ) -> server::Response<String> {
+        Ok(path)
+    }
+
+    async fn get_tags_tag(&self, _ctx: Self::Context, tag: String) -> server::Response<String> {
+        Ok(tag)
+    }
+
+    async fn get_search(
+        &self,
+        _ctx: Self::Context,
+        name: Option<String>,
+        min_hp: i32,
+    ) -> server::Response<Vec<server::Monster>> {
+        Ok(vec![server::Monster {
+            id: 1,
+            name: name.unwrap_or_else(|| "Anonymous".to_owned()),
+            hp: min_hp,
+        }])
+    }
+
+    async fn get_thumbnail(&self, _ctx: Self::Context) -> server::Response<server::RawResponse> {
+        Ok(server::RawResponse {
+            content_type: "image/png".to_owned(),
+            body: THUMBNAIL.to_vec(),
+        })
+    }
+}
+
+/// The PNG signature followed by bytes that are neither valid UTF-8 nor JSON.
+const THUMBNAIL: &[u8] = &[
+    0x89, b'P', b'N', b'G', 0x0d, 0x0a, 0x1a, 0x0a, 0x00, 0xff, 0xfe,
+];
+
+#[tokio::main]
+async fn main() {
+    let addr = std::net::TcpListener::bind("127.0.0.1:0")
+        .and_then(|listener| listener.local_addr())
+        .expect("find free port");
+
+    tokio::spawn(async move {
+        server::Builder::new()
+            .add("/api", server::Handler::Monsters(Arc::new(MonsterService)))
+            .listen_and_run_forever(&addr)
+            .await
+            .expect("run server")
+    });
+
+    let client = client::MonstersClient::new(&format!("http://{}/api", addr)).unwrap();
+
+    // wait for the server to come up
+    let monster = loop {
+        match client.get_monsters_id(&23).await {
+            Ok(monster) => break monster,
+            Err(client::ClientError::Transport(_)) => {
+                tokio::time::delay_for(std::time::Duration::from_millis(10)).await
+            }
+            Err(e) => panic!("unexpected error: {}", e),
+        }
+    };
+    assert_eq!(monster.id, 23);
+    assert_eq!(monster.name, "Goblin");
+
+    let query = client::MonsterQuery {
+        name: Some("Goblin".to_owned()),
+        min_hp: Some(5),
+    };
+    let monsters = client.get_monsters(Some(&query)).await.unwrap();
+    assert_eq!(monsters.len(), 2);
+
+    let created = client
+        .post_monsters(&client::MonsterData {
+            name: "Troll".to_owned(),
+            hp: 30,
+        })
+        .await
+        .unwrap();
+    assert_eq!(created.unwrap().id, 42);
+
+    let too_weak = client
+        .post_monsters(&client::MonsterData {
+            name: "Rat".to_owned(),
+            hp: 1,
+        })
+        .await
+        .unwrap();
+    assert!(matches!(too_weak, Err(client::MonsterError::TooWeak)));
+
+    match client.delete_monsters_id(&23).await {
+        Err(client::ClientError::Service(ProtocolServiceError::Authorization)) => {}
+        other => panic!("expected authorization error, got {:?}", other),
+    }
+
+    let whoami = client
+        .get_whoami(&"acme".to_owned(), Some(&7))
+        .await
+        .unwrap();
+    assert_eq!(whoami, "acme/Some(7)");
+    let whoami = client.get_whoami(&"acme".to_owned(), None).await.unwrap();
+    assert_eq!(whoami, "acme/None");
+
+    // a request without the required header is rejected by the runtime
+    let request = humblegen_rt::reqwest::Client::new().get(&format!("http://{}/api/whoami", addr));
+    match humblegen_rt::client::send_request::<String>(request).await {
+        Err(client::ClientError::Runtime(RuntimeError::HeaderMissing { header_name })) => {
+            assert_eq!(header_name, "X-Tenant-Id")
+        }
+        other => panic!("expected missing header error, got {:?}", other),
+    }
+
+    // an unparsable optional header is rejected as well
+    let request = humblegen_rt::reqwest::Client::new()
+        .get(&format!("http://{}/api/whoami", addr))
+        .header("X-Tenant-Id", "acme")
+        .header("X-Trace-Id", "not a number");
+    match humblegen_rt::client::send_request::<String>(request).await {
+        Err(client::ClientError::Runtime(RuntimeError::HeaderInvalid { header_name, .. })) => {
+            assert_eq!(header_name, "X-Trace-Id")
+        }
+        other => panic!("expected invalid header error, got {:?}", other),
+    }
+
+    // raw responses are sent unmodified with the handler's content type
+    let response = humblegen_rt::reqwest::get(&format!("http://{}/api/thumbnail", addr))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.headers()["content-type"], "image/png");
+    assert_eq!(&response.bytes().await.unwrap()[..], THUMBNAIL);
+
+    let thumbnail = client.get_thumbnail().await.unwrap();
+    assert_eq!(thumbnail.content_type, "image/png");
+    assert_eq!(thumbnail.body, THUMBNAIL);
+
+    // a "rest" param captures several path segments
+    let path = client
+        .get_files_path_content(&"docs/humblespec/rust.md".to_owned())
+        .await
+        .unwrap();
+    assert_eq!(path, "docs/humblespec/rust.md");
+
+    // a regex param only matches what the regex matches
+    assert_eq!(
+        client.get_tags_tag(&"4711".to_owned()).await.unwrap(),
+        "4711"
+    );
+    match client.get_tags_tag(&"abc".to_owned()).await {
+        Err(client::ClientError::Runtime(RuntimeError::NoRouteMountedInService { .. })) => {}
+        other => panic!("expected no route error, got {:?}", other),
+    }
+
+    // individual query params, optional ones may be absent
+    let found = client
+        .get_search(Some(&"Goblin & Co".to_owned()), &5)
+        .await
+        .unwrap();
+    assert_eq!(found[0].name, "Goblin & Co");
+    assert_eq!(found[0].hp, 5);
+    let found = client.get_search(None, &5).await.unwrap();
+    assert_eq!(found[0].name, "Anonymous");
+
+    // a missing required query param is rejected by the runtime
+    let request =
+        humblegen_rt::reqwest::Client::new().get(&format!("http://{}/api/search?name=x", addr));
+    match humblegen_rt::client::send_request::<Vec<client::Monster>>(request).await {
+        Err(client::ClientError::Runtime(RuntimeError::QueryInvalid(msg))) => {
+            assert!(msg.contains("min_hp"), "{}", msg)
+        }
+        other => panic!("expected invalid query error, got {:?}", other),
+    }
+    let request = humblegen_rt::reqwest::Client::new()
+        .get(&format!("http://{}/api/search?min_hp=many", addr));
+    match humblegen_rt::client::send_request::<Vec<client::Monster>>(request).await {
+        Err(client::ClientError::Runtime(RuntimeError::QueryInvalid(msg))) => {
+            assert!(msg.contains("min_hp"), "{}", msg)
+        }
+        other => panic!("expected invalid query error, got {:?}", other),
+    }
+}
@@ -0,0 +1,94 @@
+include!("spec.rs");
+
+use humblegen_rt::reqwest::{header, Client};
+use std::io::Read;
+
+struct EchoService;
+
+#[humblegen_rt::async_trait(Sync)]
+impl Echo for EchoService {
+    type Context = ();
+
+    async fn get_repeat(&self, _ctx: Self::Context, text: String, times: u32) -> Response<String> {
+        Ok(text.repeat(times as usize))
+    }
+}
+
+#[tokio::main]
+async fn main() {
+    let addr = std::net::TcpListener::bind("127.0.0.1:0")
+        .and_then(|listener| listener.local_addr())
+        .expect("find free port");
+
+    tokio::spawn(async move {
+        Builder::new()
+            .add("/api", Handler::Echo(std::sync::Arc::new(EchoService)))
+            .listen_and_run_forever(&addr)
+            .await
+            .expect("run server")
+    });
+
+    let client = Client::new();
+    let url = |times: u32| format!("http://{}/api/repeat?text=monster&times={}", addr, times);
+    let expected = |times: u32| serde_json::to_vec(&"monster".repeat(times as usize)).unwrap();
+
+    // wait for the server to come up; large bodies are compressed using the preferred encoding
+    let response = loop {
+        match client
+            .get(&url(1000))
+            .header(header::ACCEPT_ENCODING, "deflate;q=0.5, gzip")
+            .send()
+            .await
+        {
+            Ok(response) => break response,
+            Err(_) => tokio::time::delay_for(std::time::Duration::from_millis(10)).await,
+        }
+    };
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.headers()[header::CONTENT_ENCODING], "gzip");
+    assert_eq!(response.headers()[header::VARY], "Accept-Encoding");
+    let compressed = response.bytes().await.unwrap();
+    assert!(compressed.len() < expected(1000).len());
+    let mut body = Vec::new();
+    flate2::read::GzDecoder::new(&compressed[..])
+        .read_to_end(&mut body)
+        .unwrap();
+    assert_eq!(body, expected(1000));
+
+    // deflate is used if the client prefers it
+    let response = client
+        .get(&url(1000))
+        .header(header::ACCEPT_ENCODING, "gzip;q=0.1, deflate")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.headers()[header::CONTENT_ENCODING], "deflate");
+    let compressed = response.bytes().await.unwrap();
+    let mut body = Vec::new();
+    flate2::read::ZlibDecoder::new(&compressed[..])
+        .read_to_end(&mut body)
+        .unwrap();
+    assert_eq!(body, expected(1000));
+
+    // tiny bodies are not compressed
+    let response = client
+        .get(&url(1))
+        .header(header::ACCEPT_ENCODING, "gzip")
+        .send()
+        .await
+        .unwrap();
+    assert!(!response.headers().contains_key(header::CONTENT_ENCODING));
+    assert_eq!(&response.bytes().await.unwrap()[..], &expected(1)[..]);
+
+    // bodies are not compressed for clients that do not accept it
+    for accept_encoding in &[None, Some("identity"), Some("gzip;q=0, *;q=0")] {
+        let request = client.get(&url(1000));
+        let request = match accept_encoding {
+            Some(accept_encoding) => request.header(header::ACCEPT_ENCODING, *accept_encoding),
+            None => request,
+        };
+        let response = request.send().await.unwrap();
+        assert!(!response.headers().contains_key(header::CONTENT_ENCODING));
+        assert_eq!(&response.bytes().await.unwrap()[..], &expected(1000)[..]);
+    }
+}
@@ -0,0 +1,264 @@
+#[allow(unused_imports)]
+use ::humblegen_rt::deser_helpers::{
+    check_query_keys, deser_header, deser_optional_header, deser_optional_query_param, deser_param,
+    deser_post_data_limited, deser_query_param, deser_query_primitive,
+    deser_query_serde_urlencoded, deser_query_strict,
+};
+#[allow(unused_imports)]
+pub use ::humblegen_rt::handler::{
+    self, HandlerResponse as Response, InterceptOutcome, RawResponse, ServiceError, StreamResponse,
+};
+#[allow(unused_imports)]
+use ::humblegen_rt::regexset_map::RegexSetMap;
+#[allow(unused_imports)]
+use ::humblegen_rt::server::{
+    self, event_stream_handler_response_to_hyper_response, handler_response_to_hyper_response,
+    raw_handler_response_to_hyper_response, result_handler_response_to_hyper_response,
+    stream_handler_response_to_hyper_response, Route, Service,
+};
+#[allow(unused_imports)]
+use ::humblegen_rt::service_protocol::ErrorResponse;
+use ::humblegen_rt::tracing_futures::Instrument;
+#[allow(unused_imports)]
+use ::humblegen_rt::{hyper, tracing};
+#[allow(unused_imports)]
+use ::std::sync::Arc;
+use std::net::SocketAddr;
+#[doc = r" Builds an HTTP server that exposes services implemented by handler trait objects."]
+#[derive(Debug)]
+pub struct Builder {
+    services: Vec<Service>,
+    options: server::ServerOptions,
+}
+impl Builder {
+    pub fn new() -> Self {
+        Self {
+            services: vec![],
+            options: server::ServerOptions::default(),
+        }
+    }
+    #[doc = r" Configures the request ID header name and whether inbound request IDs are reused."]
+    pub fn request_ids(mut self, request_ids: server::RequestIdConfig) -> Self {
+        self.options.request_ids = request_ids;
+        self
+    }
+    #[doc = r" Sets the maximum size of request bodies in bytes (2 MiB by default)."]
+    #[doc = r" Requests with larger bodies are rejected with status code 413."]
+    pub fn max_body_size(mut self, max_body_size: usize) -> Self {
+        self.options.max_body_size = max_body_size;
+        self
+    }
+    #[doc = r" Enables strict query parsing (disabled by default)."]
+    #[doc = r" In strict mode, requests whose URL query contains keys that the endpoint does not"]
+    #[doc = r" declare are rejected with status code 400."]
+    pub fn strict_query(mut self, strict_query: bool) -> Self {
+        self.options.strict_query = strict_query;
+        self
+    }
+    #[doc = r" Makes paths with a trailing slash match the route of the path without it (disabled by"]
+    #[doc = r" default), e.g. `GET /monsters/` is then handled like `GET /monsters`."]
+    pub fn ignore_trailing_slash(mut self, ignore_trailing_slash: bool) -> Self {
+        self.options.ignore_trailing_slash = ignore_trailing_slash;
+        self
+    }
+    #[doc = r" Sets how long the interceptors and handler may take to produce a response (no limit by"]
+    #[doc = r" default). Slower requests are rejected with status code 504. For streamed responses,"]
+    #[doc = r" only the time until the handler returns the stream counts."]
+    pub fn request_timeout(mut self, request_timeout: ::std::time::Duration) -> Self {
+        self.options.request_timeout = Some(request_timeout);
+        self
+    }
+    #[doc = r" Sets how long the upstream server of a `PROXY` route may take to send the head of its"]
+    #[doc = r" response (30 seconds by default). Slower requests are rejected with status code 504."]
+    pub fn proxy_timeout(mut self, proxy_timeout: ::std::time::Duration) -> Self {
+        self.options.proxy_timeout = proxy_timeout;
+        self
+    }
+    #[doc = r" Enables Cross-Origin Resource Sharing (disabled by default), i.e. answers"]
+    #[doc = r" preflight requests and adds `Access-Control-Allow-*` headers to responses."]
+    pub fn cors(mut self, cors: server::CorsConfig) -> Self {
+        self.options.cors = Some(cors);
+        self
+    }
+    #[doc = r" Serves the health check endpoints `GET /healthz`, which always succeeds, and"]
+    #[doc = r" `GET /readyz`, which succeeds if `readiness` resolves to `true` and fails with"]
+    #[doc = r" status code 503 otherwise (disabled by default)."]
+    #[doc = r" They are matched before any service."]
+    pub fn with_health_checks<F, Fut>(mut self, readiness: F) -> Self
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: ::std::future::Future<Output = bool> + Send + 'static,
+    {
+        self.options.health_checks = Some(server::HealthChecks::new(readiness));
+        self
+    }
+    #[doc = r" Serves the files in directory `dir` below URL path prefix `path_prefix`, e.g."]
+    #[doc = r#" `dir/app.js` at `/static/app.js` for `path_prefix="/static"`, with a content type"#]
+    #[doc = r" guessed from the file extension. Paths of directories are answered with their"]
+    #[doc = r#" `index.html`, e.g. `dir/index.html` at `/` for `path_prefix="/"`."#]
+    #[doc = r" Files are looked up before any service is matched, `GET` requests below"]
+    #[doc = r" `path_prefix` for files that do not exist are answered with status code 404."]
+    #[doc = r" Thus, mount the services outside of `path_prefix`."]
+    #[doc = r""]
+    #[doc = r" Panics if `path_prefix` does not start with a `/`."]
+    pub fn serve_static(mut self, path_prefix: &str, dir: impl Into<::std::path::PathBuf>) -> Self {
+        self.options
+            .static_files
+            .push(server::StaticFiles::new(path_prefix, dir));
+        self
+    }
+    #[doc = r" Mounts `handler` at URL path prefix `root`."]
+    #[doc = r" This means that a `handler` implementing humble service"]
+    #[doc = r" ```"]
+    #[doc = r" service S {"]
+    #[doc = r"     GET /bar -> i32,"]
+    #[doc = r"     GET /baz -> str,"]
+    #[doc = r" }"]
+    #[doc = r" ```"]
+    #[doc = r#" and `root="/api"` will expose"#]
+    #[doc = r" * handler method `fn bar() -> i32` at `/api/bar` and"]
+    #[doc = r" * handler method `fn baz() -> String` at `/api/baz`"]
+    #[doc = r""]
+    #[doc = r" Panics if `root` is invalid, see `try_add`."]
+    pub fn add<Context: Default + Sized + Send + Sync>(
+        self,
+        root: &str,
+        handler: Handler<Context>,
+    ) -> Self {
+        self.try_add(root, handler)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+    #[doc = r" Like `add`, but returns an error instead of panicking if `root` does not start"]
+    #[doc = r" with a `/`, ends with a `/` or is not a valid regular expression, or if routes of"]
+    #[doc = r" `handler` match the same requests."]
+    pub fn try_add<Context: Default + Sized + Send + Sync>(
+        mut self,
+        root: &str,
+        handler: Handler<Context>,
+    ) -> Result<Self, server::BuilderError> {
+        let root = server::root_regex(root)?;
+        let routes: Vec<Route> = handler.into_routes();
+        let routes = RegexSetMap::new(routes).map_err(server::BuilderError::InvalidRoutes)?;
+        self.services.push(Service((root, routes)));
+        Ok(self)
+    }
+    #[doc = r" Turns the previously `add`ed handlers into a `tower::Service` answering requests,"]
+    #[doc = r" to compose it with middleware or serve it using a custom `hyper::Server`."]
+    pub fn into_service(self) -> humblegen_rt::anyhow::Result<server::HttpService> {
+        use humblegen_rt::anyhow::Context;
+        let services = RegexSetMap::new(self.services).context("invalid service configuration")?;
+        server::check_metrics_path(&services, &self.options)?;
+        Ok(server::HttpService::new(services, self.options))
+    }
+    #[doc = r" Starts an HTTP server bound to address `addr` and serves incoming requests using"]
+    #[doc = r" the previously `add`ed handlers."]
+    pub async fn listen_and_run_forever(
+        self,
+        addr: &SocketAddr,
+    ) -> humblegen_rt::anyhow::Result<()> {
+        server::listen_and_run_forever(self.into_service()?, addr).await
+    }
+    #[doc = r" Like `listen_and_run_forever`, but stops accepting connections once `shutdown`"]
+    #[doc = r" completes and returns after all in-flight requests have been answered."]
+    pub async fn listen_with_shutdown(
+        self,
+        addr: &SocketAddr,
+        shutdown: impl ::std::future::Future<Output = ()>,
+    ) -> humblegen_rt::anyhow::Result<()> {
+        server::listen_with_shutdown(self.into_service()?, addr, shutdown).await
+    }
+    ::humblegen_rt::__builder_tls_methods!();
+    ::humblegen_rt::__builder_metrics_methods!();
+}
+#[doc = "Wrapper enum with one variant for each service defined in the humble spec.\nUsed to pass instantiated handler trait objects to `Builder::add`."]
+#[allow(dead_code)]
+pub enum Handler<Context: Default + Sized + Send + Sync + 'static> {
+    Echo(Arc<dyn Echo<Context = Context> + Send + Sync>),
+}
+impl<Context: Default + Sized + Send + Sync + 'static> Handler<Context> {
+    fn into_routes(self) -> Vec<Route> {
+        match self {
+            Handler::Echo(h) => routes_Echo(h),
+        }
+    }
+}
+impl<Context: Default + Sized + Send + Sync + 'static> std::fmt::Debug for Handler<Context> {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Handler::Echo(_) => write!(formatter, "{}", "Echo")?,
+        }
+        Ok(())
+    }
+}
+#[doc = ""]
+#[doc = "```\n#[humblegen_rt::async_trait(Sync)]\npub trait Echo {\n    type Context: Default + Sized + Send + Sync;\n    async fn intercept_handler_pre(\n        &self,\n        _req: &hyper::Request<hyper::Body>,\n    ) -> Result<InterceptOutcome<Self::Context>, ServiceError> {\n        Ok(InterceptOutcome::Continue(Self::Context::default()))\n    }\n    async fn intercept_handler_post(\n        &self,\n        resp: hyper::Response<hyper::Body>,\n    ) -> hyper::Response<hyper::Body> {\n        resp\n    }\n    async fn get_repeat(&self, ctx: Self::Context, text: String, times: u32) -> Response<String>;\n}\n\n```"]
+#[allow(deprecated)]
+#[humblegen_rt::async_trait(Sync)]
+pub trait Echo {
+    type Context: Default + Sized + Send + Sync;
+    async fn intercept_handler_pre(
+        &self,
+        _req: &hyper::Request<hyper::Body>,
+    ) -> Result<InterceptOutcome<Self::Context>, ServiceError> {
+        Ok(InterceptOutcome::Continue(Self::Context::default()))
+    }
+    async fn intercept_handler_post(
+        &self,
+        resp: hyper::Response<hyper::Body>,
+    ) -> hyper::Response<hyper::Body> {
+        resp
+    }
+    #[doc = "```\nasync fn get_repeat(&self, ctx: Self::Context, text: String, times: u32) -> Response<String> {}\n\n```"]
+    #[doc = "Returns `text` repeated `times` times."]
+    async fn get_repeat(&self, ctx: Self::Context, text: String, times: u32) -> Response<String>;
+}
+#[allow(unused_variables)]
+#[allow(unused_mut)]
+#[allow(non_snake_case)]
+#[allow(clippy::trivial_regex)]
+#[allow(clippy::single_char_pattern)]
+#[allow(deprecated)]
+fn routes_Echo<Context: Default + Sized + Send + Sync + 'static>(
+    handler: Arc<dyn Echo<Context = Context> + Send + Sync>,
+) -> Vec<Route> {
+    vec![{
+        let handler = Arc::clone(&handler);
+        Route {
+            method: ::humblegen_rt::hyper::Method::GET,
+            regex: ::humblegen_rt::regex::Regex::new("^/repeat$").unwrap(),
+            service: "Echo",
+            template: "/repeat",
+            kind: server::RouteKind::Plain,
+            dispatcher: Box::new(
+                move |mut req: ::humblegen_rt::hyper::Request<::humblegen_rt::hyper::Body>,
+                      captures,
+                      options: Arc<server::ServerOptions>| {
+                    let handler = Arc::clone(&handler);
+                    Box::pin(async move {
+                        use ::humblegen_rt::service_protocol::ToErrorResponse;
+                        let result : Result < hyper :: Response < hyper :: Body > , ErrorResponse > = async { let ctx = { let span = tracing :: error_span ! ("interceptor") ; handler . intercept_handler_pre (& req) . instrument (span) . await . map_err (:: humblegen_rt :: service_protocol :: ServiceError :: from) . map_err (| e | { tracing :: debug ! (service_error = ? format ! ("{:?}" , e) , "interceptor rejected request") ; e }) . map_err (| e | e . to_error_response ()) ? } ; let ctx = match ctx { InterceptOutcome :: Continue (ctx) => ctx , InterceptOutcome :: Respond (response) => { tracing :: debug ! (status = response . status () . as_u16 () , "interceptor responded") ; return Ok (response) ; } } ; if options . strict_query { check_query_keys (req . uri () . query () , & ["text" , "times"]) ? ; } let text : String = deser_query_param (req . uri () . query () , "text") ? ; let times : u32 = deser_query_param (req . uri () . query () , "times") ? ; drop (req) ; let span = tracing :: error_span ! ("handler") ; Ok (handler_response_to_hyper_response (200 , handler . get_repeat (ctx , text , times) . instrument (span) . await)) } . await ;
+                        let response = result.unwrap_or_else(|e| {
+                            tracing :: error ! (err = ? e , "request failed");
+                            e.to_hyper_response()
+                        });
+                        let span = tracing::error_span!("interceptor_post");
+                        Ok(handler
+                            .intercept_handler_post(response)
+                            .instrument(span)
+                            .await)
+                    })
+                },
+            ),
+        }
+    }]
+}
+#[doc = "Paths of the routes of the `Echo` service, relative to the root it is mounted at."]
+#[allow(dead_code)]
+pub mod echo_paths {
+    #[allow(unused_imports)]
+    use super::*;
+    #[doc = "The path `/repeat`."]
+    pub fn repeat_path() -> String {
+        ::humblegen_rt::serialization_helpers::build_path(&["repeat".to_owned()])
+    }
+}
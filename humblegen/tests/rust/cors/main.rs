@@ -0,0 +1,127 @@
+include!("spec.rs");
+
+use humblegen_rt::hyper::Method;
+use humblegen_rt::reqwest::{header, Client, StatusCode};
+use humblegen_rt::server::{AllowedOrigins, CorsConfig};
+
+struct GreeterService;
+
+#[humblegen_rt::async_trait(Sync)]
+impl Greeter for GreeterService {
+    type Context = ();
+
+    async fn get_hello(&self, _ctx: Self::Context) -> Response<String> {
+        Ok("hello".to_owned())
+    }
+}
+
+const APP_ORIGIN: &str = "https://app.example.com";
+
+/// Start a server on a free port and return the URL of the `hello` endpoint.
+async fn start_server(cors: Option<CorsConfig>) -> String {
+    let addr = std::net::TcpListener::bind("127.0.0.1:0")
+        .and_then(|listener| listener.local_addr())
+        .expect("find free port");
+
+    tokio::spawn(async move {
+        let builder = Builder::new().add(
+            "/api",
+            Handler::Greeter(std::sync::Arc::new(GreeterService)),
+        );
+        let builder = match cors {
+            Some(cors) => builder.cors(cors),
+            None => builder,
+        };
+        builder
+            .listen_and_run_forever(&addr)
+            .await
+            .expect("run server")
+    });
+
+    // wait for the server to come up
+    let url = format!("http://{}/api/hello", addr);
+    while humblegen_rt::reqwest::get(&url).await.is_err() {
+        tokio::time::delay_for(std::time::Duration::from_millis(10)).await;
+    }
+    url
+}
+
+fn preflight(client: &Client, url: &str, origin: &str) -> humblegen_rt::reqwest::RequestBuilder {
+    client
+        .request(Method::OPTIONS, url)
+        .header(header::ORIGIN, origin)
+        .header(header::ACCESS_CONTROL_REQUEST_METHOD, "GET")
+        .header(header::ACCESS_CONTROL_REQUEST_HEADERS, "authorization")
+}
+
+#[tokio::main]
+async fn main() {
+    let client = Client::new();
+
+    let url = start_server(Some(CorsConfig {
+        allowed_origins: AllowedOrigins::List(vec![APP_ORIGIN.to_owned()]),
+        allow_credentials: true,
+        max_age: Some(std::time::Duration::from_secs(600)),
+        ..CorsConfig::default()
+    }))
+    .await;
+
+    // preflight requests of allowed origins are answered with the configuration
+    let response = preflight(&client, &url, APP_ORIGIN).send().await.unwrap();
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    let headers = response.headers();
+    assert_eq!(headers[header::ACCESS_CONTROL_ALLOW_ORIGIN], APP_ORIGIN);
+    assert_eq!(
+        headers[header::ACCESS_CONTROL_ALLOW_METHODS],
+        "GET, POST, PUT, PATCH, DELETE"
+    );
+    assert_eq!(
+        headers[header::ACCESS_CONTROL_ALLOW_HEADERS],
+        "content-type, authorization"
+    );
+    assert_eq!(headers[header::ACCESS_CONTROL_ALLOW_CREDENTIALS], "true");
+    assert_eq!(headers[header::ACCESS_CONTROL_MAX_AGE], "600");
+    assert_eq!(headers[header::VARY], "Origin");
+
+    // preflight requests of other origins are not allowed
+    let response = preflight(&client, &url, "https://evil.example.com")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    assert!(!response
+        .headers()
+        .contains_key(header::ACCESS_CONTROL_ALLOW_ORIGIN));
+
+    // actual requests get the allowed origin, and may read the request ID
+    let response = client
+        .get(&url)
+        .header(header::ORIGIN, APP_ORIGIN)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let headers = response.headers();
+    assert_eq!(headers[header::ACCESS_CONTROL_ALLOW_ORIGIN], APP_ORIGIN);
+    assert_eq!(headers[header::ACCESS_CONTROL_ALLOW_CREDENTIALS], "true");
+    assert_eq!(headers[header::ACCESS_CONTROL_EXPOSE_HEADERS], "request-id");
+    assert_eq!(response.json::<String>().await.unwrap(), "hello");
+
+    // any origin is answered with the wildcard unless credentials are allowed
+    let url = start_server(Some(CorsConfig::default())).await;
+    let response = preflight(&client, &url, APP_ORIGIN).send().await.unwrap();
+    assert_eq!(response.headers()[header::ACCESS_CONTROL_ALLOW_ORIGIN], "*");
+    assert!(response
+        .headers()
+        .get_all(header::VARY)
+        .iter()
+        .all(|vary| vary != "Origin"));
+
+    // CORS is disabled by default
+    let url = start_server(None).await;
+    let response = preflight(&client, &url, APP_ORIGIN).send().await.unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    assert!(!response
+        .headers()
+        .contains_key(header::ACCESS_CONTROL_ALLOW_ORIGIN));
+}
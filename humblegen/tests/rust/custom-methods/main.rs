@@ -0,0 +1,94 @@
+include!("spec.rs");
+
+use humblegen_rt::hyper::service::Service as _;
+use humblegen_rt::hyper::{Body, Method, Request, StatusCode};
+use humblegen_rt::server::HttpService;
+
+struct FilesService;
+
+const FILES: &[&str] = &["a.txt", "b.txt", "c.md"];
+
+#[humblegen_rt::async_trait(Sync)]
+impl Files for FilesService {
+    type Context = ();
+
+    async fn options_files(&self, _ctx: Self::Context) -> Response<Vec<String>> {
+        Ok(vec!["GET".to_owned(), "PROPFIND".to_owned(), "REPORT".to_owned()])
+    }
+
+    async fn propfind_files(
+        &self,
+        _ctx: Self::Context,
+        query: Option<FileQuery>,
+    ) -> Response<Vec<String>> {
+        let prefix = query.map(|q| q.prefix).unwrap_or_default();
+        Ok(FILES
+            .iter()
+            .filter(|f| f.starts_with(&prefix))
+            .map(|f| format!("{}: 12 bytes", f))
+            .collect())
+    }
+
+    async fn report_files(&self, _ctx: Self::Context, post_body: String) -> Response<u32> {
+        Ok(FILES.iter().filter(|f| f.ends_with(&post_body)).count() as u32)
+    }
+
+    async fn get_files(&self, _ctx: Self::Context) -> Response<Vec<String>> {
+        Ok(FILES.iter().map(|f| (*f).to_owned()).collect())
+    }
+}
+
+async fn send(
+    service: &mut HttpService,
+    method: Method,
+    path: &str,
+    body: &'static str,
+) -> (StatusCode, String) {
+    let request = Request::builder()
+        .method(method)
+        .uri(path)
+        .body(Body::from(body))
+        .unwrap();
+    let response = service.call(request).await.unwrap();
+    let status = response.status();
+    let body = humblegen_rt::hyper::body::to_bytes(response.into_body())
+        .await
+        .unwrap();
+    (status, String::from_utf8(body.to_vec()).unwrap())
+}
+
+fn method(name: &str) -> Method {
+    Method::from_bytes(name.as_bytes()).unwrap()
+}
+
+#[tokio::main]
+async fn main() {
+    let mut service = Builder::new()
+        .add("/api", Handler::Files(Arc::new(FilesService)))
+        .into_service()
+        .expect("valid service configuration");
+
+    assert_eq!(
+        send(&mut service, Method::OPTIONS, "/api/files", "").await,
+        (
+            StatusCode::OK,
+            r#"["GET","PROPFIND","REPORT"]"#.to_owned()
+        )
+    );
+    assert_eq!(
+        send(&mut service, method("PROPFIND"), "/api/files?prefix=a", "").await,
+        (StatusCode::OK, r#"["a.txt: 12 bytes"]"#.to_owned())
+    );
+    assert_eq!(
+        send(&mut service, method("REPORT"), "/api/files", r#"".txt""#).await,
+        (StatusCode::OK, "2".to_owned())
+    );
+    assert_eq!(
+        send(&mut service, Method::GET, "/api/files", "").await.1,
+        r#"["a.txt","b.txt","c.md"]"#
+    );
+
+    // the routes only match their own method
+    let (status, _) = send(&mut service, method("MKCOL"), "/api/files", "").await;
+    assert_ne!(status, StatusCode::OK);
+}
@@ -0,0 +1,42 @@
+include!("spec.rs");
+
+use humblegen_rt::hyper::service::Service as _;
+use humblegen_rt::hyper::{Body, Request, StatusCode};
+
+struct MonstersService;
+
+#[humblegen_rt::async_trait(Sync)]
+impl Monsters for MonstersService {
+    type Context = ();
+
+    // the query is passed as a whole instead of as an `Option`
+    async fn get_monsters(&self, _ctx: Self::Context, query: MonsterQuery) -> Response<String> {
+        Ok(format!("{:?} {:?}", query.name, query.max_hp))
+    }
+}
+
+#[tokio::main]
+async fn main() {
+    let mut service = Builder::new()
+        .add("/api", Handler::Monsters(Arc::new(MonstersService)))
+        .into_service()
+        .expect("valid service configuration");
+    let mut get = |uri: &str| {
+        let request = Request::get(uri).body(Body::empty()).unwrap();
+        service.call(request)
+    };
+    async fn body(response: humblegen_rt::hyper::Response<Body>) -> String {
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = humblegen_rt::hyper::body::to_bytes(response.into_body())
+            .await
+            .unwrap();
+        serde_json::from_slice(&body).unwrap()
+    }
+
+    // without a query string, the handler gets the default query
+    let response = get("/api/monsters").await.unwrap();
+    assert_eq!(body(response).await, "None None");
+
+    let response = get("/api/monsters?name=Goblin").await.unwrap();
+    assert_eq!(body(response).await, "Some(\"Goblin\") None");
+}
@@ -0,0 +1,59 @@
+include!("spec.rs");
+
+use humblegen_rt::hyper::service::Service as _;
+use humblegen_rt::hyper::{Body, Request, StatusCode};
+
+struct MonstersService;
+
+#[humblegen_rt::async_trait(Sync)]
+impl Monsters for MonstersService {
+    type Context = ();
+
+    async fn post_monsters(
+        &self,
+        _ctx: Self::Context,
+        post_body: MonsterData,
+    ) -> Response<Monster> {
+        Ok(Monster {
+            id: 1,
+            name: post_body.name,
+        })
+    }
+}
+
+#[tokio::main]
+async fn main() {
+    let mut service = Builder::new()
+        .add("/api", Handler::Monsters(Arc::new(MonstersService)))
+        .into_service()
+        .expect("valid service configuration");
+    let mut post = |body: serde_json::Value| {
+        let request = Request::post("/api/monsters")
+            .body(Body::from(body.to_string()))
+            .unwrap();
+        service.call(request)
+    };
+
+    let response = post(serde_json::json!({ "name": "Goblin", "home": { "city": "Cave" } }))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // a typo'd key is rejected instead of being ignored, also in nested structs
+    let response =
+        post(serde_json::json!({ "nmae": "Goblin", "name": "Goblin", "home": { "city": "Cave" } }))
+            .await
+            .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let response =
+        post(serde_json::json!({ "name": "Goblin", "home": { "city": "Cave", "zip": "1" } }))
+            .await
+            .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    // structs that are not part of a request body still ignore unknown fields
+    let monster: Monster =
+        serde_json::from_value(serde_json::json!({ "id": 1, "name": "Goblin", "level": 3 }))
+            .unwrap();
+    assert_eq!(monster.name, "Goblin");
+}
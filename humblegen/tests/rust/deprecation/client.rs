@@ -0,0 +1,239 @@
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[doc = "A monster of the old API."]
+#[deprecated(note = "use Creature instead")]
+#[allow(deprecated)]
+pub struct Monster {
+    #[doc = ""]
+    pub name: String,
+}
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[doc = ""]
+#[allow(deprecated)]
+pub struct Creature {
+    #[doc = ""]
+    pub name: String,
+    #[doc = "Health points."]
+    #[deprecated(note = "use health instead")]
+    pub hp: i32,
+    #[doc = ""]
+    #[serde(default = "Creature::default_health")]
+    #[serde(deserialize_with = "Creature::deserialize_health")]
+    pub health: u16,
+}
+#[allow(deprecated)]
+impl Creature {
+    fn default_health() -> u16 {
+        100
+    }
+    fn deserialize_health<'de, D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<u16, D::Error> {
+        ::humblegen_rt::serialization_helpers::deser_in_range(
+            deserializer,
+            "health",
+            Some(0),
+            Some(1000),
+        )
+    }
+}
+#[allow(deprecated)]
+impl Creature {
+    #[doc = r" Check that all fields are within the ranges declared in the spec."]
+    pub fn validate(&self) -> Result<(), ::humblegen_rt::serialization_helpers::OutOfRange> {
+        ::humblegen_rt::serialization_helpers::check_range(
+            "health",
+            self.health,
+            Some(0),
+            Some(1000),
+        )?;
+        Ok(())
+    }
+}
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[doc = ""]
+#[deprecated(note = "kinds are no longer distinguished")]
+#[allow(deprecated)]
+pub enum Kind {
+    #[doc = ""]
+    Beast,
+    #[doc = ""]
+    Ghost,
+}
+#[allow(deprecated)]
+impl ::std::fmt::Display for Kind {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        f.write_str(match *self {
+            Kind::Beast => "Beast",
+            Kind::Ghost => "Ghost",
+        })
+    }
+}
+#[allow(deprecated)]
+impl ::std::str::FromStr for Kind {
+    type Err = ::humblegen_rt::serialization_helpers::ParseEnumError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Beast" => Ok(Kind::Beast),
+            "Ghost" => Ok(Kind::Ghost),
+            _ => Err(::humblegen_rt::serialization_helpers::ParseEnumError {
+                enum_name: "Kind",
+                value: s.to_owned(),
+            }),
+        }
+    }
+}
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[doc = ""]
+#[allow(deprecated)]
+pub enum Event {
+    #[doc = ""]
+    Spawned {
+        #[doc = ""]
+        creature: Creature,
+        #[doc = ""]
+        #[deprecated(note = "always 1")]
+        #[serde(default = "Event::default_spawned_count")]
+        #[serde(deserialize_with = "Event::deserialize_spawned_count")]
+        count: u8,
+    },
+    #[doc = ""]
+    Migrated(Monster),
+    #[doc = ""]
+    Classified(Kind),
+}
+#[allow(deprecated)]
+impl Event {
+    fn default_spawned_count() -> u8 {
+        1
+    }
+    fn deserialize_spawned_count<'de, D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<u8, D::Error> {
+        ::humblegen_rt::serialization_helpers::deser_in_range(
+            deserializer,
+            "count",
+            Some(1),
+            Some(1),
+        )
+    }
+}
+#[allow(deprecated)]
+impl Event {
+    #[doc = r" Check that all fields are within the ranges declared in the spec."]
+    pub fn validate(&self) -> Result<(), ::humblegen_rt::serialization_helpers::OutOfRange> {
+        match *self {
+            Event::Spawned { count, .. } => {
+                ::humblegen_rt::serialization_helpers::check_range(
+                    "count",
+                    count,
+                    Some(1),
+                    Some(1),
+                )?;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+}
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[doc = ""]
+#[allow(deprecated)]
+pub struct Zoo {
+    #[doc = ""]
+    pub monsters: Vec<Monster>,
+    #[doc = ""]
+    pub creatures: Vec<Creature>,
+    #[doc = ""]
+    pub kinds: Option<Kind>,
+}
+#[allow(unused_imports)]
+use ::humblegen_rt::client::{
+    build_url, parse_base_url, send_raw_request, send_request, send_result_request,
+    send_stream_request, ser_query_params, ser_query_primitive, ser_query_serde_urlencoded,
+};
+#[allow(unused_imports)]
+pub use ::humblegen_rt::client::{ClientError, RawResponse, StreamResponse};
+#[allow(unused_imports)]
+use ::humblegen_rt::reqwest;
+#[doc = "Client for the `ZooApi` service. Requires the `client` feature of `humblegen_rt`."]
+#[doc = ""]
+#[doc = ""]
+#[derive(Debug, Clone)]
+pub struct ZooApiClient {
+    base_url: reqwest::Url,
+    http_client: reqwest::Client,
+}
+#[allow(deprecated)]
+impl ZooApiClient {
+    #[doc = r" Creates a client for the service mounted at `base_url`, e.g. `http://localhost:3000/api`."]
+    pub fn new(base_url: &str) -> Result<Self, ClientError> {
+        Self::with_http_client(base_url, reqwest::Client::new())
+    }
+    #[doc = r" Creates a client that sends its requests using the given `http_client`."]
+    pub fn with_http_client(
+        base_url: &str,
+        http_client: reqwest::Client,
+    ) -> Result<Self, ClientError> {
+        Ok(Self {
+            base_url: parse_base_url(base_url)?,
+            http_client,
+        })
+    }
+    #[doc = "Fetch a monster."]
+    #[deprecated(note = "use GET /creatures/{name} instead")]
+    pub async fn get_monsters_name(&self, name: &String) -> Result<Monster, ClientError> {
+        let url = build_url(
+            &self.base_url,
+            &["monsters".to_owned(), name.to_string()],
+            None,
+        );
+        let request = self
+            .http_client
+            .request(::humblegen_rt::hyper::Method::GET, url);
+        send_request(request).await
+    }
+    #[doc = ""]
+    pub async fn get_creatures_name(&self, name: &String) -> Result<Creature, ClientError> {
+        let url = build_url(
+            &self.base_url,
+            &["creatures".to_owned(), name.to_string()],
+            None,
+        );
+        let request = self
+            .http_client
+            .request(::humblegen_rt::hyper::Method::GET, url);
+        send_request(request).await
+    }
+    #[doc = ""]
+    pub async fn get_zoo(&self) -> Result<Zoo, ClientError> {
+        let url = build_url(&self.base_url, &["zoo".to_owned()], None);
+        let request = self
+            .http_client
+            .request(::humblegen_rt::hyper::Method::GET, url);
+        send_request(request).await
+    }
+}
+#[doc = "Paths of the routes of the `ZooApi` service, relative to the root it is mounted at."]
+#[allow(dead_code)]
+pub mod zoo_api_paths {
+    #[allow(unused_imports)]
+    use super::*;
+    #[doc = "The path `/monsters/{name}`."]
+    pub fn monsters_name_path(name: &String) -> String {
+        ::humblegen_rt::serialization_helpers::build_path(&[
+            "monsters".to_owned(),
+            name.to_string(),
+        ])
+    }
+    #[doc = "The path `/creatures/{name}`."]
+    pub fn creatures_name_path(name: &String) -> String {
+        ::humblegen_rt::serialization_helpers::build_path(&[
+            "creatures".to_owned(),
+            name.to_string(),
+        ])
+    }
+    #[doc = "The path `/zoo`."]
+    pub fn zoo_path() -> String {
+        ::humblegen_rt::serialization_helpers::build_path(&["zoo".to_owned()])
+    }
+}
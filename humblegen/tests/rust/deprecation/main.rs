@@ -0,0 +1,66 @@
+// the generated code must not warn about the deprecated items it defines and uses itself
+#![deny(deprecated)]
+
+mod server {
+    include!("spec.rs");
+}
+mod client {
+    include!("client.rs");
+}
+
+struct ZooService;
+
+#[allow(deprecated)]
+#[humblegen_rt::async_trait(Sync)]
+impl server::ZooApi for ZooService {
+    type Context = ();
+
+    async fn get_monsters_name(&self, _ctx: (), name: String) -> server::Response<server::Monster> {
+        Ok(server::Monster { name })
+    }
+
+    async fn get_creatures_name(
+        &self,
+        _ctx: (),
+        name: String,
+    ) -> server::Response<server::Creature> {
+        Ok(server::Creature {
+            name,
+            hp: 10,
+            health: 10,
+        })
+    }
+
+    async fn get_zoo(&self, _ctx: ()) -> server::Response<server::Zoo> {
+        Ok(server::Zoo {
+            monsters: vec![],
+            creatures: vec![],
+            kinds: Some(server::Kind::Ghost),
+        })
+    }
+}
+
+#[allow(deprecated)]
+fn main() {
+    // deprecated items still work as before
+    let monster: server::Monster = serde_json::from_str(r#"{"name":"Goblin"}"#).unwrap();
+    assert_eq!(monster.name, "Goblin");
+    let creature: server::Creature = serde_json::from_str(r#"{"name":"Slime","hp":3}"#).unwrap();
+    assert_eq!(creature.hp, 3);
+    assert_eq!(creature.health, 100);
+    assert!(matches!(
+        "Beast".parse::<server::Kind>(),
+        Ok(server::Kind::Beast)
+    ));
+    match serde_json::from_str(r#"{"Spawned":{"creature":{"name":"Slime","hp":3}}}"#).unwrap() {
+        server::Event::Spawned { count, .. } => assert_eq!(count, 1),
+        _ => panic!("expected a spawn"),
+    }
+
+    let _ = server::Builder::new().add(
+        "/api",
+        server::Handler::ZooApi(std::sync::Arc::new(ZooService)),
+    );
+    let client = client::ZooApiClient::new("http://localhost:3000/api").unwrap();
+    let _ = client.get_monsters_name(&"Goblin".to_owned());
+}
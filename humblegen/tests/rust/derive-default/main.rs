@@ -0,0 +1,9 @@
+include!("spec.rs");
+
+fn main() {
+    let data = MonsterData::default();
+    assert_eq!(data.name, "");
+    assert_eq!(data.hp, 0);
+    assert!(data.tags.is_empty());
+    assert_eq!(data.nickname, None);
+}
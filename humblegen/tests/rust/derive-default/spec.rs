@@ -0,0 +1,12 @@
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize, Default)]
+#[doc = ""]
+pub struct MonsterData {
+    #[doc = "The monster's name"]
+    pub name: String,
+    #[doc = ""]
+    pub hp: i32,
+    #[doc = ""]
+    pub tags: Vec<String>,
+    #[doc = ""]
+    pub nickname: Option<String>,
+}
@@ -0,0 +1,21 @@
+include!("spec.rs");
+
+use std::collections::HashMap;
+
+fn main() {
+    let key = CacheKey {
+        tenant: "acme".to_owned(),
+        kind: Kind::Image {
+            width: 640,
+            height: 480,
+        },
+        version: Some(2),
+    };
+
+    let mut cache = HashMap::new();
+    cache.insert(key.clone(), "cached");
+    assert_eq!(cache.get(&key), Some(&"cached"));
+
+    assert!(Kind::Document < Kind::Image { width: 0, height: 0 });
+    assert!(Measurement { value: 1.0 } < Measurement { value: 2.0 });
+}
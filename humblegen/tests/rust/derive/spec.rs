@@ -0,0 +1,31 @@
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize, PartialEq, Eq, Hash)]
+#[doc = ""]
+pub struct CacheKey {
+    #[doc = ""]
+    pub tenant: String,
+    #[doc = ""]
+    pub kind: Kind,
+    #[doc = ""]
+    pub version: Option<u32>,
+}
+#[derive(
+    Debug, Clone, serde :: Deserialize, serde :: Serialize, PartialEq, Eq, Hash, PartialOrd, Ord,
+)]
+#[doc = ""]
+pub enum Kind {
+    #[doc = ""]
+    Document,
+    #[doc = ""]
+    Image {
+        #[doc = ""]
+        width: u32,
+        #[doc = ""]
+        height: u32,
+    },
+}
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize, PartialEq, PartialOrd)]
+#[doc = ""]
+pub struct Measurement {
+    #[doc = ""]
+    pub value: f64,
+}
@@ -0,0 +1,64 @@
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[doc = "How often a monster respawns."]
+pub struct Schedule {
+    #[doc = ""]
+    #[serde(with = "::humblegen_rt::serialization_helpers::duration_millis")]
+    pub interval: ::std::time::Duration,
+    #[doc = ""]
+    #[serde(with = "::humblegen_rt::serialization_helpers::optional_duration_millis")]
+    #[serde(default)]
+    pub timeout: Option<::std::time::Duration>,
+}
+#[allow(unused_imports)]
+use ::humblegen_rt::client::{
+    build_url, parse_base_url, send_raw_request, send_request, send_result_request,
+    send_stream_request, ser_query_params, ser_query_primitive, ser_query_serde_urlencoded,
+};
+#[allow(unused_imports)]
+pub use ::humblegen_rt::client::{ClientError, RawResponse, StreamResponse};
+#[allow(unused_imports)]
+use ::humblegen_rt::reqwest;
+#[doc = "Client for the `Schedules` service. Requires the `client` feature of `humblegen_rt`."]
+#[doc = ""]
+#[doc = ""]
+#[derive(Debug, Clone)]
+pub struct SchedulesClient {
+    base_url: reqwest::Url,
+    http_client: reqwest::Client,
+}
+#[allow(deprecated)]
+impl SchedulesClient {
+    #[doc = r" Creates a client for the service mounted at `base_url`, e.g. `http://localhost:3000/api`."]
+    pub fn new(base_url: &str) -> Result<Self, ClientError> {
+        Self::with_http_client(base_url, reqwest::Client::new())
+    }
+    #[doc = r" Creates a client that sends its requests using the given `http_client`."]
+    pub fn with_http_client(
+        base_url: &str,
+        http_client: reqwest::Client,
+    ) -> Result<Self, ClientError> {
+        Ok(Self {
+            base_url: parse_base_url(base_url)?,
+            http_client,
+        })
+    }
+    #[doc = ""]
+    pub async fn post_schedules(&self, post_body: &Schedule) -> Result<Schedule, ClientError> {
+        let url = build_url(&self.base_url, &["schedules".to_owned()], None);
+        let request = self
+            .http_client
+            .request(::humblegen_rt::hyper::Method::POST, url);
+        let request = request.json(post_body);
+        send_request(request).await
+    }
+}
+#[doc = "Paths of the routes of the `Schedules` service, relative to the root it is mounted at."]
+#[allow(dead_code)]
+pub mod schedules_paths {
+    #[allow(unused_imports)]
+    use super::*;
+    #[doc = "The path `/schedules`."]
+    pub fn schedules_path() -> String {
+        ::humblegen_rt::serialization_helpers::build_path(&["schedules".to_owned()])
+    }
+}
@@ -0,0 +1,92 @@
+mod server {
+    include!("spec.rs");
+}
+mod client {
+    include!("client.rs");
+}
+
+use std::time::Duration;
+
+struct SchedulesService;
+
+#[humblegen_rt::async_trait(Sync)]
+impl server::Schedules for SchedulesService {
+    type Context = ();
+
+    async fn post_schedules(
+        &self,
+        _ctx: Self::Context,
+        post_body: server::Schedule,
+    ) -> server::Response<server::Schedule> {
+        Ok(server::Schedule {
+            interval: post_body.interval * 2,
+            timeout: post_body.timeout,
+        })
+    }
+}
+
+#[tokio::main]
+async fn main() {
+    // durations are encoded as integer milliseconds
+    let schedule = server::Schedule {
+        interval: Duration::from_millis(1500),
+        timeout: None,
+    };
+    let json = serde_json::to_value(&schedule).unwrap();
+    assert_eq!(
+        json,
+        serde_json::json!({ "interval": 1500, "timeout": null })
+    );
+    let decoded: server::Schedule = serde_json::from_value(json).unwrap();
+    assert_eq!(decoded.interval, Duration::from_millis(1500));
+    assert_eq!(decoded.timeout, None);
+
+    // fractions of a millisecond are truncated, optional fields may be missing
+    let schedule = server::Schedule {
+        interval: Duration::from_micros(2_999),
+        timeout: Some(Duration::from_secs(60)),
+    };
+    let json = serde_json::to_value(&schedule).unwrap();
+    assert_eq!(json, serde_json::json!({ "interval": 2, "timeout": 60000 }));
+    let decoded: server::Schedule =
+        serde_json::from_value(serde_json::json!({ "interval": 0 })).unwrap();
+    assert_eq!(decoded.interval, Duration::from_millis(0));
+    assert_eq!(decoded.timeout, None);
+
+    // negative and fractional milliseconds are rejected
+    assert!(
+        serde_json::from_value::<server::Schedule>(serde_json::json!({ "interval": -1 })).is_err()
+    );
+    assert!(
+        serde_json::from_value::<server::Schedule>(serde_json::json!({ "interval": 1.5 })).is_err()
+    );
+
+    let addr = std::net::TcpListener::bind("127.0.0.1:0")
+        .and_then(|listener| listener.local_addr())
+        .expect("find free port");
+    tokio::spawn(async move {
+        server::Builder::new()
+            .add(
+                "/api",
+                server::Handler::Schedules(std::sync::Arc::new(SchedulesService)),
+            )
+            .listen_and_run_forever(&addr)
+            .await
+            .expect("run server")
+    });
+
+    let client = client::SchedulesClient::new(&format!("http://{}/api", addr)).unwrap();
+    let schedule = client::Schedule {
+        interval: Duration::from_millis(250),
+        timeout: Some(Duration::from_secs(3)),
+    };
+    // wait for the server to come up
+    let posted = loop {
+        match client.post_schedules(&schedule).await {
+            Ok(posted) => break posted,
+            Err(_) => tokio::time::delay_for(Duration::from_millis(10)).await,
+        }
+    };
+    assert_eq!(posted.interval, Duration::from_millis(500));
+    assert_eq!(posted.timeout, Some(Duration::from_secs(3)));
+}
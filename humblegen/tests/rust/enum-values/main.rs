@@ -0,0 +1,42 @@
+include!("spec.rs");
+
+fn main() {
+    // simple variants are serialized as their value, if they have one
+    let paint = Paint {
+        color: Color::Blue,
+        events: vec![
+            OrderEvent::Placed,
+            OrderEvent::Shipped {
+                carrier: "Paint Express".to_owned(),
+            },
+        ],
+    };
+    let json = serde_json::to_value(&paint).unwrap();
+    assert_eq!(
+        json,
+        serde_json::json!({
+            "color": "BLUE_07",
+            "events": [
+                { "type": "placed" },
+                { "type": "Shipped", "carrier": "Paint Express" },
+            ],
+        })
+    );
+    let paint: Paint = serde_json::from_value(json).unwrap();
+    assert!(matches!(paint.color, Color::Blue));
+    assert!(matches!(paint.events[0], OrderEvent::Placed));
+
+    // the variant name is no longer accepted
+    assert!(serde_json::from_str::<Color>("\"Blue\"").is_err());
+    assert!(matches!(
+        serde_json::from_str::<Color>("\"Red\"").unwrap(),
+        Color::Red
+    ));
+
+    // the string conversions agree with the serialized representation
+    assert_eq!(Color::Blue.to_string(), "BLUE_07");
+    assert_eq!(Color::Green.to_string(), "green");
+    assert_eq!(Color::Red.to_string(), "Red");
+    assert!(matches!("BLUE_07".parse::<Color>(), Ok(Color::Blue)));
+    assert!("Blue".parse::<Color>().is_err());
+}
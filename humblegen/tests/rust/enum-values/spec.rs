@@ -0,0 +1,56 @@
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[doc = "A color, as named by the paint supplier."]
+pub enum Color {
+    #[doc = ""]
+    Red,
+    #[doc = "Navy blue."]
+    #[serde(rename = "BLUE_07")]
+    Blue,
+    #[doc = ""]
+    #[serde(rename = "green")]
+    Green,
+}
+impl ::std::fmt::Display for Color {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        f.write_str(match *self {
+            Color::Red => "Red",
+            Color::Blue => "BLUE_07",
+            Color::Green => "green",
+        })
+    }
+}
+impl ::std::str::FromStr for Color {
+    type Err = ::humblegen_rt::serialization_helpers::ParseEnumError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Red" => Ok(Color::Red),
+            "BLUE_07" => Ok(Color::Blue),
+            "green" => Ok(Color::Green),
+            _ => Err(::humblegen_rt::serialization_helpers::ParseEnumError {
+                enum_name: "Color",
+                value: s.to_owned(),
+            }),
+        }
+    }
+}
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[doc = "Something that happened to a paint order."]
+#[serde(tag = "type")]
+pub enum OrderEvent {
+    #[doc = ""]
+    #[serde(rename = "placed")]
+    Placed,
+    #[doc = ""]
+    Shipped {
+        #[doc = ""]
+        carrier: String,
+    },
+}
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[doc = ""]
+pub struct Paint {
+    #[doc = ""]
+    pub color: Color,
+    #[doc = ""]
+    pub events: Vec<OrderEvent>,
+}
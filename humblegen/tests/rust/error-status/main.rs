@@ -0,0 +1,107 @@
+mod server {
+    include!("spec.rs");
+}
+mod client {
+    include!("client.rs");
+}
+
+use humblegen_rt::service_protocol::{RuntimeError, ServiceError};
+use std::sync::Arc;
+
+fn find(id: u32) -> Result<server::Monster, server::MonsterError> {
+    match id {
+        1 => Ok(server::Monster {
+            id,
+            name: "Goblin".to_owned(),
+        }),
+        _ => Err(server::MonsterError::NotFound),
+    }
+}
+
+struct MonsterService;
+
+#[humblegen_rt::async_trait(Sync)]
+impl server::Monsters for MonsterService {
+    type Context = ();
+
+    async fn get_monsters_id(
+        &self,
+        _ctx: Self::Context,
+        id: u32,
+    ) -> server::Response<Result<server::Monster, server::MonsterError>> {
+        if id == 0 {
+            return Err(server::ServiceError::Authorization);
+        }
+        Ok(find(id))
+    }
+
+    async fn get_legacy_id(
+        &self,
+        _ctx: Self::Context,
+        id: u32,
+    ) -> server::Response<Result<server::Monster, server::MonsterError>> {
+        Ok(find(id))
+    }
+}
+
+#[tokio::main]
+async fn main() {
+    let addr = std::net::TcpListener::bind("127.0.0.1:0")
+        .and_then(|listener| listener.local_addr())
+        .expect("find free port");
+
+    tokio::spawn(async move {
+        server::Builder::new()
+            .add("/api", server::Handler::Monsters(Arc::new(MonsterService)))
+            .listen_and_run_forever(&addr)
+            .await
+            .expect("run server")
+    });
+
+    let url = |path: &str| format!("http://{}/api{}", addr, path);
+    let response = loop {
+        match humblegen_rt::reqwest::get(&url("/monsters/1")).await {
+            Ok(response) => break response,
+            Err(_) => tokio::time::delay_for(std::time::Duration::from_millis(10)).await,
+        }
+    };
+    assert_eq!(response.status(), 200);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["Ok"]["name"], "Goblin");
+
+    // the error is sent with the declared status, its payload is serialized as before
+    let response = humblegen_rt::reqwest::get(&url("/monsters/2"))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 404);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body, serde_json::json!({ "Err": "NotFound" }));
+
+    // without `@error_status`, errors are sent with the status of successful responses
+    let response = humblegen_rt::reqwest::get(&url("/legacy/2")).await.unwrap();
+    assert_eq!(response.status(), 200);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body, serde_json::json!({ "Err": "NotFound" }));
+
+    // the client decodes both arms of the result
+    let client = client::MonstersClient::new(&format!("http://{}/api", addr)).unwrap();
+    match client.get_monsters_id(&1).await {
+        Ok(Ok(monster)) => assert_eq!(monster.name, "Goblin"),
+        other => panic!("expected monster, got {:?}", other),
+    }
+    match client.get_monsters_id(&2).await {
+        Ok(Err(client::MonsterError::NotFound)) => {}
+        other => panic!("expected NotFound, got {:?}", other),
+    }
+    // service and runtime errors are still error responses
+    match client.get_monsters_id(&0).await {
+        Err(client::ClientError::Service(ServiceError::Authorization)) => {}
+        other => panic!("expected authorization error, got {:?}", other),
+    }
+    // even if they have the error status
+    let client = client::MonstersClient::new(&format!("http://{}/unmounted", addr)).unwrap();
+    match client.get_monsters_id(&1).await {
+        Err(client::ClientError::Runtime(RuntimeError::NoServiceMounted)) => {}
+        other => panic!("expected ServiceNotFound, got {:?}", other),
+    }
+}
@@ -0,0 +1,33 @@
+include!("spec.rs");
+
+fn main() {
+    // missing fields get their default value
+    let monster: Monster = serde_json::from_str(r#"{"name":"Slime"}"#).unwrap();
+    assert_eq!(monster.name, "Slime");
+    assert_eq!(monster.level, 1);
+    assert_eq!(monster.speed, -0.5);
+    assert!(monster.hostile);
+    assert_eq!(monster.title, "the brave");
+    assert_eq!(monster.hp, 0);
+    assert!(!monster.tamed);
+
+    // fields that are present are used as they are
+    let json = r#"{"name":"Dragon","level":80,"speed":2.0,"hostile":false,"title":"","hp":900,"tamed":true}"#;
+    let monster: Monster = serde_json::from_str(json).unwrap();
+    assert_eq!(monster.level, 80);
+    assert!(!monster.hostile);
+    assert_eq!(monster.title, "");
+    assert_eq!(serde_json::to_string(&monster).unwrap(), json);
+
+    // fields without a default are still required
+    assert!(serde_json::from_str::<Monster>(r#"{"level":80}"#).is_err());
+
+    // fields of enum variants can have defaults, too
+    match serde_json::from_str(r#"{"Attack":{"target":"Slime"}}"#).unwrap() {
+        Command::Attack { target, power } => {
+            assert_eq!(target, "Slime");
+            assert_eq!(power, 10);
+        }
+        Command::Flee => panic!("expected an attack"),
+    }
+}
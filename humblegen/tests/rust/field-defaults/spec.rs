@@ -0,0 +1,57 @@
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[doc = ""]
+pub struct Monster {
+    #[doc = ""]
+    pub name: String,
+    #[doc = ""]
+    #[serde(default = "Monster::default_level")]
+    pub level: i32,
+    #[doc = ""]
+    #[serde(default = "Monster::default_speed")]
+    pub speed: f32,
+    #[doc = ""]
+    #[serde(default = "Monster::default_hostile")]
+    pub hostile: bool,
+    #[doc = ""]
+    #[serde(default = "Monster::default_title")]
+    pub title: String,
+    #[doc = ""]
+    #[serde(default)]
+    pub hp: u64,
+    #[doc = ""]
+    #[serde(default)]
+    pub tamed: bool,
+}
+impl Monster {
+    fn default_level() -> i32 {
+        1
+    }
+    fn default_speed() -> f32 {
+        -0.5
+    }
+    fn default_hostile() -> bool {
+        true
+    }
+    fn default_title() -> String {
+        "the brave".to_owned()
+    }
+}
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[doc = ""]
+pub enum Command {
+    #[doc = ""]
+    Attack {
+        #[doc = ""]
+        target: String,
+        #[doc = ""]
+        #[serde(default = "Command::default_attack_power")]
+        power: u8,
+    },
+    #[doc = ""]
+    Flee,
+}
+impl Command {
+    fn default_attack_power() -> u8 {
+        10
+    }
+}
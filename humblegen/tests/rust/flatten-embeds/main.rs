@@ -0,0 +1,47 @@
+include!("spec.rs");
+
+fn main() {
+    let data = MonsterData {
+        name: "Goblin".to_owned(),
+        hp: 7,
+        created: "2020-05-01".to_owned(),
+    };
+
+    let monster = Monster {
+        id: 23,
+        name: data.name.clone(),
+        hp: data.hp,
+        created: data.created.clone(),
+        tags: vec!["green".to_owned()],
+    };
+    let flat_monster = FlatMonster {
+        id: 23,
+        monster_data: data.clone(),
+        tags: vec!["green".to_owned()],
+    };
+    let json = serde_json::to_string(&monster).unwrap();
+    assert_eq!(json, serde_json::to_string(&flat_monster).unwrap());
+
+    let flat_monster: FlatMonster = serde_json::from_str(&json).unwrap();
+    assert_eq!(flat_monster.monster_data.name, "Goblin");
+    assert_eq!(flat_monster.tags, vec!["green".to_owned()]);
+
+    // embeds of structs that flatten embeds themselves
+    let rare = Rare {
+        rarity: 3,
+        id: monster.id,
+        name: monster.name.clone(),
+        hp: monster.hp,
+        created: monster.created.clone(),
+        tags: monster.tags.clone(),
+    };
+    let flat_rare = FlatRare {
+        rarity: 3,
+        flat_monster,
+    };
+    let json = serde_json::to_string(&rare).unwrap();
+    assert_eq!(json, serde_json::to_string(&flat_rare).unwrap());
+
+    let flat_rare: FlatRare = serde_json::from_str(&json).unwrap();
+    assert_eq!(flat_rare.flat_monster.monster_data.created, "2020-05-01");
+}
@@ -0,0 +1,64 @@
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[doc = ""]
+pub struct Timestamps {
+    #[doc = ""]
+    pub created: String,
+}
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[doc = ""]
+pub struct MonsterData {
+    #[doc = ""]
+    pub name: String,
+    #[doc = ""]
+    pub hp: i32,
+    #[doc = ""]
+    pub created: String,
+}
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[doc = ""]
+pub struct Monster {
+    #[doc = ""]
+    pub id: i32,
+    #[doc = ""]
+    pub name: String,
+    #[doc = ""]
+    pub hp: i32,
+    #[doc = ""]
+    pub created: String,
+    #[doc = ""]
+    pub tags: Vec<String>,
+}
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[doc = ""]
+pub struct FlatMonster {
+    #[doc = ""]
+    pub id: i32,
+    #[serde(flatten)]
+    pub monster_data: MonsterData,
+    #[doc = ""]
+    pub tags: Vec<String>,
+}
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[doc = ""]
+pub struct Rare {
+    #[doc = ""]
+    pub rarity: u8,
+    #[doc = ""]
+    pub id: i32,
+    #[doc = ""]
+    pub name: String,
+    #[doc = ""]
+    pub hp: i32,
+    #[doc = ""]
+    pub created: String,
+    #[doc = ""]
+    pub tags: Vec<String>,
+}
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[doc = ""]
+pub struct FlatRare {
+    #[doc = ""]
+    pub rarity: u8,
+    #[serde(flatten)]
+    pub flat_monster: FlatMonster,
+}
@@ -0,0 +1,50 @@
+include!("spec.rs");
+
+struct PingerService;
+
+#[humblegen_rt::async_trait(Sync)]
+impl Pinger for PingerService {
+    type Context = ();
+
+    async fn get_ping(&self, _ctx: Self::Context) -> Response<String> {
+        Ok("pong".to_owned())
+    }
+}
+
+#[tokio::main]
+async fn main() {
+    let addr = std::net::TcpListener::bind("127.0.0.1:0")
+        .and_then(|listener| listener.local_addr())
+        .expect("find free port");
+
+    let (shutdown_tx, shutdown_rx) = tokio::sync::oneshot::channel::<()>();
+    let server = tokio::spawn(async move {
+        Builder::new()
+            .add("/api", Handler::Pinger(Arc::new(PingerService)))
+            .listen_with_shutdown(&addr, async {
+                shutdown_rx.await.ok();
+            })
+            .await
+    });
+
+    // wait for the server to come up, then serve one request
+    let url = format!("http://{}/api/ping", addr);
+    let pong = loop {
+        match humblegen_rt::reqwest::get(&url).await {
+            Ok(response) => break response.json::<String>().await.expect("decode response"),
+            Err(_) => tokio::time::delay_for(std::time::Duration::from_millis(10)).await,
+        }
+    };
+    assert_eq!(pong, "pong");
+
+    shutdown_tx.send(()).expect("server is still running");
+    server
+        .await
+        .expect("server task panicked")
+        .expect("server terminates cleanly");
+
+    assert!(
+        humblegen_rt::reqwest::get(&url).await.is_err(),
+        "server no longer accepts connections"
+    );
+}
@@ -0,0 +1,73 @@
+include!("spec.rs");
+
+use humblegen_rt::hyper::header::CONTENT_LENGTH;
+use humblegen_rt::hyper::service::Service as _;
+use humblegen_rt::hyper::{Body, Method, Request, StatusCode};
+use humblegen_rt::server::HttpService;
+
+struct StatusService;
+
+#[humblegen_rt::async_trait(Sync)]
+impl Status for StatusService {
+    type Context = ();
+
+    async fn get_version(&self, _ctx: Self::Context) -> Response<String> {
+        Ok("1.2.3".to_owned())
+    }
+
+    async fn post_restart(&self, _ctx: Self::Context, _post_body: ()) -> Response<String> {
+        Ok("restarting".to_owned())
+    }
+}
+
+async fn send(
+    service: &mut HttpService,
+    method: Method,
+    path: &str,
+) -> humblegen_rt::hyper::Response<Body> {
+    let request = Request::builder()
+        .method(method)
+        .uri(path)
+        .body(Body::empty())
+        .unwrap();
+    service.call(request).await.unwrap()
+}
+
+async fn body(response: humblegen_rt::hyper::Response<Body>) -> Vec<u8> {
+    humblegen_rt::hyper::body::to_bytes(response.into_body())
+        .await
+        .unwrap()
+        .to_vec()
+}
+
+#[tokio::main]
+async fn main() {
+    let mut service = Builder::new()
+        .add("/api", Handler::Status(Arc::new(StatusService)))
+        .with_health_checks(|| async { true })
+        .into_service()
+        .expect("valid service configuration");
+
+    let get = body(send(&mut service, Method::GET, "/api/version").await).await;
+    assert_eq!(get, b"\"1.2.3\"");
+
+    // `HEAD` is answered like `GET`, without the body
+    let head = send(&mut service, Method::HEAD, "/api/version").await;
+    assert_eq!(head.status(), StatusCode::OK);
+    assert_eq!(
+        head.headers()[CONTENT_LENGTH],
+        get.len().to_string().as_str()
+    );
+    assert_eq!(head.headers()["content-type"], "application/json");
+    assert!(body(head).await.is_empty());
+
+    // ... but only for `GET` endpoints
+    let head = send(&mut service, Method::HEAD, "/api/restart").await;
+    assert_eq!(head.status(), StatusCode::NOT_FOUND);
+    assert!(body(head).await.is_empty());
+
+    // health checks accept `HEAD` as well
+    let head = send(&mut service, Method::HEAD, "/healthz").await;
+    assert_eq!(head.status(), StatusCode::OK);
+    assert!(body(head).await.is_empty());
+}
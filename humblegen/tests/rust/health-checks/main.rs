@@ -0,0 +1,63 @@
+include!("spec.rs");
+
+use humblegen_rt::reqwest::StatusCode;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+struct GreeterService;
+
+#[humblegen_rt::async_trait(Sync)]
+impl Greeter for GreeterService {
+    type Context = ();
+
+    async fn get_hello(&self, _ctx: Self::Context) -> Response<String> {
+        Ok("hello".to_owned())
+    }
+}
+
+async fn get_status(url: &str) -> StatusCode {
+    humblegen_rt::reqwest::get(url).await.unwrap().status()
+}
+
+#[tokio::main]
+async fn main() {
+    let addr = std::net::TcpListener::bind("127.0.0.1:0")
+        .and_then(|listener| listener.local_addr())
+        .expect("find free port");
+
+    let ready = Arc::new(AtomicBool::new(false));
+    let readiness = Arc::clone(&ready);
+    tokio::spawn(async move {
+        Builder::new()
+            .add("/api", Handler::Greeter(Arc::new(GreeterService)))
+            .with_health_checks(move || {
+                let ready = readiness.load(Ordering::SeqCst);
+                async move { ready }
+            })
+            .listen_and_run_forever(&addr)
+            .await
+            .expect("run server")
+    });
+
+    // the server is alive as soon as it accepts connections
+    let healthz = format!("http://{}/healthz", addr);
+    let response = loop {
+        match humblegen_rt::reqwest::get(&healthz).await {
+            Ok(response) => break response,
+            Err(_) => tokio::time::delay_for(std::time::Duration::from_millis(10)).await,
+        }
+    };
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // but only ready once the readiness closure says so
+    let readyz = format!("http://{}/readyz", addr);
+    assert_eq!(get_status(&readyz).await, StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(get_status(&healthz).await, StatusCode::OK);
+    ready.store(true, Ordering::SeqCst);
+    assert_eq!(get_status(&readyz).await, StatusCode::OK);
+
+    // other requests are still dispatched to the services
+    assert_eq!(
+        get_status(&format!("http://{}/api/hello", addr)).await,
+        StatusCode::OK
+    );
+}
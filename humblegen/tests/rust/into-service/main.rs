@@ -0,0 +1,77 @@
+include!("spec.rs");
+
+use humblegen_rt::futures::future::poll_fn;
+use humblegen_rt::hyper::service::Service as _;
+use humblegen_rt::hyper::{Body, Request, StatusCode};
+use humblegen_rt::server::BuilderError;
+
+struct MonstersService;
+
+#[humblegen_rt::async_trait(Sync)]
+impl Monsters for MonstersService {
+    type Context = ();
+
+    async fn get_monsters(&self, _ctx: Self::Context) -> Response<Vec<Monster>> {
+        Ok(vec![])
+    }
+
+    async fn get_monsters_id(&self, _ctx: Self::Context, id: u32) -> Response<Monster> {
+        Ok(Monster {
+            id,
+            name: "Gorgon".to_owned(),
+        })
+    }
+}
+
+#[tokio::main]
+async fn main() {
+    let mut service = Builder::new()
+        .add("/api", Handler::Monsters(Arc::new(MonstersService)))
+        .into_service()
+        .expect("valid service configuration");
+
+    // drive a request through the service without binding a socket
+    poll_fn(|cx| service.poll_ready(cx)).await.unwrap();
+    let request = Request::get("/api/v1/monsters/7")
+        .body(Body::empty())
+        .unwrap();
+    let response = service.call(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = humblegen_rt::hyper::body::to_bytes(response.into_body())
+        .await
+        .unwrap();
+    let monster: Monster = serde_json::from_slice(&body).unwrap();
+    assert_eq!(monster.id, 7);
+    assert_eq!(monster.name, "Gorgon");
+
+    // clones answer requests as well, e.g. one per connection
+    let mut clone = service.clone();
+    let request = Request::get("/api/unknown").body(Body::empty()).unwrap();
+    let response = clone.call(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    // invalid roots are reported instead of panicking
+    let handler = || Handler::Monsters(Arc::new(MonstersService));
+    match Builder::new().try_add("bad", handler()) {
+        Err(BuilderError::RootWithoutLeadingSlash(root)) => assert_eq!(root, "bad"),
+        other => panic!("expected an error, got {:?}", other),
+    }
+    match Builder::new().try_add("/api/", handler()) {
+        Err(e @ BuilderError::RootWithTrailingSlash(_)) => {
+            assert_eq!(
+                e.to_string(),
+                "root must not end with \"/\", but is \"/api/\""
+            )
+        }
+        other => panic!("expected an error, got {:?}", other),
+    }
+    assert!(matches!(
+        Builder::new().try_add("/api(", handler()),
+        Err(BuilderError::InvalidRoot(..))
+    ));
+    Builder::new()
+        .try_add("/api", handler())
+        .expect("valid root")
+        .into_service()
+        .expect("valid service configuration");
+}
@@ -0,0 +1,39 @@
+include!("spec.rs");
+
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+fn main() {
+    let host = Host {
+        address: IpAddr::V6(Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 1)),
+        gateway: Some(IpAddr::V4(Ipv4Addr::new(192, 0, 2, 1))),
+        dns: vec!["::ffff:192.0.2.53".parse().unwrap()],
+    };
+
+    // addresses are serialized in their text representation
+    let json = serde_json::to_string(&host).unwrap();
+    assert_eq!(
+        json,
+        r#"{"address":"2001:db8::1","gateway":"192.0.2.1","dns":["::ffff:192.0.2.53"]}"#
+    );
+
+    let roundtripped: Host = serde_json::from_str(&json).unwrap();
+    assert_eq!(roundtripped.address, host.address);
+    assert_eq!(roundtripped.gateway, host.gateway);
+    assert_eq!(roundtripped.dns, host.dns);
+
+    // IPv6 addresses are accepted in any valid notation
+    let received: Host = serde_json::from_str(
+        r#"{"address":"2001:0DB8:0000:0000:0000:0000:0000:0001","gateway":null,"dns":[]}"#,
+    )
+    .unwrap();
+    assert_eq!(received.address, host.address);
+
+    // invalid addresses are rejected
+    for invalid in &["192.0.2.256", "2001:db8:::1", "example.com"] {
+        let json = format!(r#"{{"address":"{}","gateway":null,"dns":[]}}"#, invalid);
+        let err = serde_json::from_str::<Host>(&json)
+            .err()
+            .expect("invalid address must not deserialize");
+        assert!(err.is_data(), "{}", err);
+    }
+}
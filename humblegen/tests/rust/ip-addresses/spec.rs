@@ -0,0 +1,10 @@
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[doc = ""]
+pub struct Host {
+    #[doc = ""]
+    pub address: ::std::net::IpAddr,
+    #[doc = ""]
+    pub gateway: Option<::std::net::IpAddr>,
+    #[doc = ""]
+    pub dns: Vec<::std::net::IpAddr>,
+}
@@ -0,0 +1,43 @@
+include!("spec.rs");
+
+fn main() {
+    let payload = serde_json::json!({
+        "monster": {
+            "name": "Dragon",
+            "stats": { "hp": 300, "speed": 1.5, "flying": true },
+            "loot": [["gold", 100], { "item": "scale" }, null],
+        },
+        "tags": [],
+    });
+    let json = serde_json::json!({
+        "name": "spawned",
+        "payload": payload,
+        "metadata": null,
+        "history": [1, "two", [3], { "four": 4 }],
+    });
+
+    // JSON values are passed through untouched
+    let event: Event = serde_json::from_value(json.clone()).unwrap();
+    assert_eq!(event.payload, payload);
+    assert_eq!(event.payload["monster"]["stats"]["hp"], 300);
+    assert_eq!(event.metadata, None);
+    assert_eq!(event.history.len(), 4);
+    assert_eq!(serde_json::to_value(&event).unwrap(), json);
+
+    // the generated type is the re-exported `serde_json::Value`
+    let value: humblegen_rt::serde_json::Value = event.payload;
+    assert!(value.is_object());
+
+    let patch = Patch::Merge {
+        changes: serde_json::json!({ "monster": { "stats": { "hp": null } } }),
+    };
+    let json = serde_json::to_value(&patch).unwrap();
+    assert_eq!(
+        json,
+        serde_json::json!({ "Merge": { "changes": { "monster": { "stats": { "hp": null } } } } })
+    );
+    match serde_json::from_value(json).unwrap() {
+        Patch::Merge { changes } => assert!(changes["monster"]["stats"]["hp"].is_null()),
+        Patch::Replace(_) => panic!("wrong variant"),
+    }
+}
@@ -0,0 +1,23 @@
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[doc = "An event carrying data defined by the client that sent it."]
+pub struct Event {
+    #[doc = ""]
+    pub name: String,
+    #[doc = ""]
+    pub payload: ::humblegen_rt::serde_json::Value,
+    #[doc = ""]
+    pub metadata: Option<::humblegen_rt::serde_json::Value>,
+    #[doc = ""]
+    pub history: Vec<::humblegen_rt::serde_json::Value>,
+}
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[doc = ""]
+pub enum Patch {
+    #[doc = ""]
+    Replace(::humblegen_rt::serde_json::Value),
+    #[doc = ""]
+    Merge {
+        #[doc = ""]
+        changes: ::humblegen_rt::serde_json::Value,
+    },
+}
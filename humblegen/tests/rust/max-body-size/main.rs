@@ -0,0 +1,121 @@
+include!("spec.rs");
+
+use humblegen_rt::service_protocol::{ErrorResponseKind, RuntimeError};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+struct UploadService;
+
+#[humblegen_rt::async_trait(Sync)]
+impl Upload for UploadService {
+    type Context = ();
+
+    async fn post_upload(&self, _ctx: Self::Context, post_body: String) -> Response<u64> {
+        Ok(post_body.len() as u64)
+    }
+
+    async fn post_archive(&self, _ctx: Self::Context, post_body: String) -> Response<u64> {
+        Ok(post_body.len() as u64)
+    }
+}
+
+const MAX_BODY_SIZE: usize = 1024;
+/// The limit of `POST /archive`, declared using `@max_body(4kb)`.
+const ARCHIVE_MAX_BODY_SIZE: usize = 4 * 1024;
+
+/// A JSON string literal that is exactly `size` bytes long.
+fn json_string_body(size: usize) -> String {
+    serde_json::to_string(&"x".repeat(size - 2)).unwrap()
+}
+
+#[tokio::main]
+async fn main() {
+    let addr = std::net::TcpListener::bind("127.0.0.1:0")
+        .and_then(|listener| listener.local_addr())
+        .expect("find free port");
+
+    tokio::spawn(async move {
+        Builder::new()
+            .add("/api", Handler::Upload(std::sync::Arc::new(UploadService)))
+            .max_body_size(MAX_BODY_SIZE)
+            .listen_and_run_forever(&addr)
+            .await
+            .expect("run server")
+    });
+
+    let client = humblegen_rt::reqwest::Client::new();
+    let url = format!("http://{}/api/upload", addr);
+
+    // wait for the server to come up; a body of exactly the maximum size is accepted
+    let response = loop {
+        match client
+            .post(&url)
+            .body(json_string_body(MAX_BODY_SIZE))
+            .send()
+            .await
+        {
+            Ok(response) => break response,
+            Err(_) => tokio::time::delay_for(std::time::Duration::from_millis(10)).await,
+        }
+    };
+    assert_eq!(response.status(), 200);
+    assert_eq!(
+        response.json::<u64>().await.unwrap(),
+        MAX_BODY_SIZE as u64 - 2
+    );
+
+    // a body one byte over the limit is rejected
+    let response = client
+        .post(&url)
+        .body(json_string_body(MAX_BODY_SIZE + 1))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 413);
+    match response.json::<ErrorResponse>().await.unwrap().kind {
+        ErrorResponseKind::Runtime(RuntimeError::PayloadTooLarge { max_body_size }) => {
+            assert_eq!(max_body_size, MAX_BODY_SIZE)
+        }
+        other => panic!("expected PayloadTooLarge, got {:?}", other),
+    }
+
+    // the limit of the endpoint overrides the one of the server
+    let archive_url = format!("http://{}/api/archive", addr);
+    let response = client
+        .post(&archive_url)
+        .body(json_string_body(ARCHIVE_MAX_BODY_SIZE))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+    let response = client
+        .post(&archive_url)
+        .body(json_string_body(ARCHIVE_MAX_BODY_SIZE + 1))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 413);
+    match response.json::<ErrorResponse>().await.unwrap().kind {
+        ErrorResponseKind::Runtime(RuntimeError::PayloadTooLarge { max_body_size }) => {
+            assert_eq!(max_body_size, ARCHIVE_MAX_BODY_SIZE)
+        }
+        other => panic!("expected PayloadTooLarge, got {:?}", other),
+    }
+
+    // a chunked body without `Content-Length` is rejected once it exceeds the limit
+    let mut stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+    let chunk = "x".repeat(MAX_BODY_SIZE);
+    let request = format!(
+        "POST /api/upload HTTP/1.1\r\nHost: {}\r\nTransfer-Encoding: chunked\r\nConnection: close\r\n\r\n{:x}\r\n\"{}\r\n",
+        addr,
+        chunk.len() + 1,
+        chunk
+    );
+    stream.write_all(request.as_bytes()).await.unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await.unwrap();
+    assert!(
+        response.starts_with("HTTP/1.1 413"),
+        "unexpected response {:?}",
+        response
+    );
+}
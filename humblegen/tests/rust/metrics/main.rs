@@ -0,0 +1,82 @@
+include!("spec.rs");
+
+struct GreeterService;
+
+#[humblegen_rt::async_trait(Sync)]
+impl Greeter for GreeterService {
+    type Context = ();
+
+    async fn get_hello_name(&self, _ctx: Self::Context, name: String) -> Response<String> {
+        Ok(format!("hello {}", name))
+    }
+}
+
+/// The value of the request counter of the `hello` route, if it was recorded yet.
+async fn hello_requests(metrics_url: &str) -> Option<u64> {
+    let response = humblegen_rt::reqwest::get(metrics_url).await.unwrap();
+    assert_eq!(response.status(), 200);
+    assert!(response.headers()["Content-Type"]
+        .to_str()
+        .unwrap()
+        .starts_with("text/plain"));
+    let metrics = response.text().await.unwrap();
+    assert!(metrics.contains("# TYPE humblegen_http_request_duration_seconds histogram"));
+    metrics
+        .lines()
+        .filter(|line| line.starts_with("humblegen_http_requests_total{"))
+        .filter(|line| line.contains(r#"route="^/hello/(?P<name>[^/]+)$""#))
+        .filter(|line| line.contains(r#"service="/api""#) && line.contains(r#"status="200""#))
+        .map(|line| line.rsplit(' ').next().unwrap().parse().unwrap())
+        .next()
+}
+
+#[tokio::main]
+async fn main() {
+    let addr = std::net::TcpListener::bind("127.0.0.1:0")
+        .and_then(|listener| listener.local_addr())
+        .expect("find free port");
+
+    // the metrics must not shadow a route
+    let err = Builder::new()
+        .add(
+            "/api",
+            Handler::Greeter(std::sync::Arc::new(GreeterService)),
+        )
+        .serve_metrics("/api/hello/metrics")
+        .into_service()
+        .expect_err("metrics path matched by a route");
+    match err.downcast_ref::<humblegen_rt::server::BuilderError>() {
+        Some(humblegen_rt::server::BuilderError::MetricsPathConflict { path, root }) => {
+            assert_eq!(path, "/api/hello/metrics");
+            assert_eq!(root, "/api");
+        }
+        other => panic!("unexpected error {:?}", other),
+    }
+
+    tokio::spawn(async move {
+        Builder::new()
+            .add(
+                "/api",
+                Handler::Greeter(std::sync::Arc::new(GreeterService)),
+            )
+            .serve_metrics("/metrics")
+            .listen_and_run_forever(&addr)
+            .await
+            .expect("run server")
+    });
+
+    let metrics_url = format!("http://{}/metrics", addr);
+    while humblegen_rt::reqwest::get(&metrics_url).await.is_err() {
+        tokio::time::delay_for(std::time::Duration::from_millis(10)).await;
+    }
+    assert_eq!(hello_requests(&metrics_url).await, None);
+
+    // requests are counted by route, not by path
+    for name in &["world", "dragon"] {
+        let response = humblegen_rt::reqwest::get(&format!("http://{}/api/hello/{}", addr, name))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 200);
+    }
+    assert_eq!(hello_requests(&metrics_url).await, Some(2));
+}
@@ -0,0 +1,115 @@
+mod server {
+    include!("spec.rs");
+}
+mod client {
+    include!("client.rs");
+}
+
+use std::sync::Arc;
+
+struct PublicMonsters;
+
+#[humblegen_rt::async_trait(Sync)]
+impl server::Monsters for PublicMonsters {
+    type Context = ();
+
+    async fn get_monsters(&self, _ctx: Self::Context) -> server::Response<Vec<server::Monster>> {
+        Ok(vec![server::Monster {
+            id: 1,
+            name: "Goblin".to_owned(),
+        }])
+    }
+}
+
+struct AdminMonsters;
+
+#[humblegen_rt::async_trait(Sync)]
+impl server::admin::Monsters for AdminMonsters {
+    type Context = ();
+
+    async fn post_monsters(
+        &self,
+        _ctx: Self::Context,
+        post_body: server::Monster,
+    ) -> server::Response<server::Monster> {
+        Ok(post_body)
+    }
+
+    async fn delete_monsters_id(&self, _ctx: Self::Context, _id: u32) -> server::Response<()> {
+        Ok(())
+    }
+}
+
+struct AuditLog;
+
+#[humblegen_rt::async_trait(Sync)]
+impl server::admin::audit::Log for AuditLog {
+    type Context = ();
+
+    async fn get_entries(&self, _ctx: Self::Context) -> server::Response<Vec<String>> {
+        Ok(vec!["deleted monster 1".to_owned()])
+    }
+}
+
+#[tokio::main]
+async fn main() {
+    let addr = std::net::TcpListener::bind("127.0.0.1:0")
+        .and_then(|listener| listener.local_addr())
+        .expect("find free port");
+
+    let admin_handler =
+        server::Handler::Admin(server::admin::Handler::Monsters(Arc::new(AdminMonsters)));
+    assert_eq!(format!("{:?}", admin_handler), "Admin.Monsters");
+    let audit_handler = server::Handler::Admin(server::admin::Handler::Audit(
+        server::admin::audit::Handler::Log(Arc::new(AuditLog)),
+    ));
+    assert_eq!(format!("{:?}", audit_handler), "Admin.Audit.Log");
+
+    tokio::spawn(async move {
+        server::Builder::new()
+            .add("/api", server::Handler::Monsters(Arc::new(PublicMonsters)))
+            .add("/admin", admin_handler)
+            .add("/audit", audit_handler)
+            .listen_and_run_forever(&addr)
+            .await
+            .expect("run server")
+    });
+
+    let public_client = client::MonstersClient::new(&format!("http://{}/api", addr)).unwrap();
+    let monsters = loop {
+        match public_client.get_monsters().await {
+            Ok(monsters) => break monsters,
+            Err(_) => tokio::time::delay_for(std::time::Duration::from_millis(10)).await,
+        }
+    };
+    assert_eq!(monsters.len(), 1);
+
+    let admin_client =
+        client::admin::MonstersClient::new(&format!("http://{}/admin", addr)).unwrap();
+    let monster = server::Monster {
+        id: 2,
+        name: "Orc".to_owned(),
+    };
+    let created = admin_client
+        .post_monsters(&client::Monster {
+            id: monster.id,
+            name: monster.name.clone(),
+        })
+        .await
+        .unwrap();
+    assert_eq!(created.id, 2);
+    admin_client.delete_monsters_id(&2).await.unwrap();
+
+    let audit_client =
+        client::admin::audit::LogClient::new(&format!("http://{}/audit", addr)).unwrap();
+    assert_eq!(
+        audit_client.get_entries().await.unwrap(),
+        vec!["deleted monster 1".to_owned()]
+    );
+
+    // the public service is not mounted below the admin prefix
+    let response = humblegen_rt::reqwest::get(&format!("http://{}/admin/monsters", addr))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 404);
+}
@@ -0,0 +1,19 @@
+include!("spec.rs");
+
+fn describe(err: &MonsterError) -> String {
+    // the wildcard arm is only required outside of the crate defining the enum
+    #[allow(unreachable_patterns)]
+    match err {
+        MonsterError::TooWeak => "too weak".to_owned(),
+        MonsterError::TooStrong { max_strength } => format!("stronger than {}", max_strength),
+        _ => "unknown".to_owned(),
+    }
+}
+
+fn main() {
+    assert_eq!(describe(&MonsterError::TooWeak), "too weak");
+    assert_eq!(
+        describe(&MonsterError::TooStrong { max_strength: 9 }),
+        "stronger than 9"
+    );
+}
@@ -0,0 +1,12 @@
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[doc = "Errors returned by the monster service."]
+#[non_exhaustive]
+pub enum MonsterError {
+    #[doc = ""]
+    TooWeak,
+    #[doc = ""]
+    TooStrong {
+        #[doc = ""]
+        max_strength: i32,
+    },
+}
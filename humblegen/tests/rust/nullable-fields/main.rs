@@ -0,0 +1,132 @@
+include!("spec.rs");
+
+use humblegen_rt::hyper::service::Service as _;
+use humblegen_rt::hyper::{Body, Request, StatusCode};
+use std::time::Duration;
+
+struct MonstersService;
+
+#[humblegen_rt::async_trait(Sync)]
+impl Monsters for MonstersService {
+    type Context = ();
+
+    async fn get_monsters(
+        &self,
+        _ctx: Self::Context,
+        owner: Option<String>,
+    ) -> Response<Vec<Monster>> {
+        Ok(vec![Monster {
+            name: "Goblin".to_owned(),
+            nickname: None,
+            owner,
+            level: None,
+            respawn: None,
+        }])
+    }
+
+    async fn post_monsters(&self, _ctx: Self::Context, post_body: Monster) -> Response<Monster> {
+        Ok(post_body)
+    }
+}
+
+#[tokio::main]
+async fn main() {
+    // both optional and nullable fields are serialized as `null`
+    let monster = Monster {
+        name: "Goblin".to_owned(),
+        nickname: None,
+        owner: None,
+        level: Some(3),
+        respawn: None,
+    };
+    let json = serde_json::to_value(&monster).unwrap();
+    assert_eq!(
+        json,
+        serde_json::json!({
+            "name": "Goblin",
+            "nickname": null,
+            "owner": null,
+            "level": 3,
+            "respawn": null,
+        })
+    );
+
+    // the key of optional fields may be missing, the one of nullable fields may be `null`
+    let decoded: Monster = serde_json::from_value(serde_json::json!({
+        "name": "Goblin",
+        "owner": null,
+        "level": null,
+        "respawn": null,
+    }))
+    .unwrap();
+    assert_eq!(decoded.nickname, None);
+    assert_eq!(decoded.owner, None);
+    assert_eq!(decoded.level, None);
+    assert_eq!(decoded.respawn, None);
+
+    let decoded: Monster = serde_json::from_value(serde_json::json!({
+        "name": "Goblin",
+        "nickname": "Gobbo",
+        "owner": "Gru",
+        "level": 99,
+        "respawn": 1500,
+    }))
+    .unwrap();
+    assert_eq!(decoded.nickname.as_deref(), Some("Gobbo"));
+    assert_eq!(decoded.owner.as_deref(), Some("Gru"));
+    assert_eq!(decoded.level, Some(99));
+    assert_eq!(decoded.respawn, Some(Duration::from_millis(1500)));
+
+    // the keys of nullable fields are required
+    let complete = serde_json::json!({
+        "name": "Goblin",
+        "owner": null,
+        "level": null,
+        "respawn": null,
+    });
+    for key in &["owner", "level", "respawn"] {
+        let mut missing = complete.clone();
+        missing.as_object_mut().unwrap().remove(*key);
+        let error = serde_json::from_value::<Monster>(missing).unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .contains(&format!("missing field `{}`", key)),
+            "{}",
+            error
+        );
+    }
+
+    // ranges still apply to nullable fields
+    let mut out_of_range = complete;
+    out_of_range["level"] = serde_json::json!(100);
+    assert!(serde_json::from_value::<Monster>(out_of_range).is_err());
+
+    // nullable query fields may be omitted like optional ones
+    let mut service = Builder::new()
+        .add("/api", Handler::Monsters(Arc::new(MonstersService)))
+        .into_service()
+        .expect("valid service configuration");
+    for (uri, owner) in &[
+        ("/api/monsters", serde_json::Value::Null),
+        ("/api/monsters?owner=Gru", serde_json::json!("Gru")),
+    ] {
+        let request = Request::get(*uri).body(Body::empty()).unwrap();
+        let response = service.call(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = humblegen_rt::hyper::body::to_bytes(response.into_body())
+            .await
+            .unwrap();
+        let monsters: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(&monsters[0]["owner"], owner);
+    }
+
+    // a post body without the key of a nullable field is rejected
+    let request = Request::post("/api/monsters")
+        .body(Body::from(
+            r#"{"name": "Goblin", "level": null, "respawn": null}"#,
+        ))
+        .unwrap();
+    let response = service.call(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
@@ -0,0 +1,26 @@
+include!("spec.rs");
+
+fn main() {
+    let mut scoreboard = Scoreboard {
+        scores: Default::default(),
+        rounds: Default::default(),
+    };
+    for (name, score) in &[("zoe", 3), ("adam", 7), ("mia", 5)] {
+        scoreboard.scores.insert((*name).to_owned(), *score);
+    }
+    scoreboard.rounds.insert(10, vec!["zoe".to_owned()]);
+    scoreboard
+        .rounds
+        .insert(2, vec!["adam".to_owned(), "mia".to_owned()]);
+
+    // keys are serialized in ascending order, regardless of insertion order
+    let json = serde_json::to_string(&scoreboard).unwrap();
+    assert_eq!(
+        json,
+        r#"{"scores":{"adam":7,"mia":5,"zoe":3},"rounds":{"2":["adam","mia"],"10":["zoe"]}}"#
+    );
+
+    let roundtripped: Scoreboard = serde_json::from_str(&json).unwrap();
+    assert_eq!(roundtripped.scores, scoreboard.scores);
+    assert_eq!(roundtripped.rounds, scoreboard.rounds);
+}
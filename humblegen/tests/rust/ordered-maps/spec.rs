@@ -0,0 +1,8 @@
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[doc = ""]
+pub struct Scoreboard {
+    #[doc = ""]
+    pub scores: ::std::collections::BTreeMap<String, i32>,
+    #[doc = ""]
+    pub rounds: ::std::collections::BTreeMap<u32, Vec<String>>,
+}
@@ -0,0 +1,106 @@
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[doc = ""]
+pub struct Monster {
+    #[doc = ""]
+    pub name: String,
+}
+#[doc = r" A page of the items returned by a paginated endpoint."]
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+pub struct Page<T> {
+    #[doc = r" The items on this page."]
+    pub items: Vec<T>,
+    #[doc = r" The number of items on all pages."]
+    pub total: u64,
+    #[doc = r" The maximum number of items on a page."]
+    pub limit: u32,
+    #[doc = r" The number of items on preceding pages."]
+    pub offset: u32,
+}
+#[allow(unused_imports)]
+use ::humblegen_rt::client::{
+    build_url, parse_base_url, send_raw_request, send_request, send_result_request,
+    send_stream_request, ser_query_params, ser_query_primitive, ser_query_serde_urlencoded,
+};
+#[allow(unused_imports)]
+pub use ::humblegen_rt::client::{ClientError, RawResponse, StreamResponse};
+#[allow(unused_imports)]
+use ::humblegen_rt::reqwest;
+#[doc = "Client for the `Monsters` service. Requires the `client` feature of `humblegen_rt`."]
+#[doc = ""]
+#[doc = ""]
+#[derive(Debug, Clone)]
+pub struct MonstersClient {
+    base_url: reqwest::Url,
+    http_client: reqwest::Client,
+}
+#[allow(deprecated)]
+impl MonstersClient {
+    #[doc = r" Creates a client for the service mounted at `base_url`, e.g. `http://localhost:3000/api`."]
+    pub fn new(base_url: &str) -> Result<Self, ClientError> {
+        Self::with_http_client(base_url, reqwest::Client::new())
+    }
+    #[doc = r" Creates a client that sends its requests using the given `http_client`."]
+    pub fn with_http_client(
+        base_url: &str,
+        http_client: reqwest::Client,
+    ) -> Result<Self, ClientError> {
+        Ok(Self {
+            base_url: parse_base_url(base_url)?,
+            http_client,
+        })
+    }
+    #[doc = "List all monsters, a page at a time."]
+    pub async fn get_monsters(
+        &self,
+        limit: Option<&u32>,
+        offset: Option<&u32>,
+    ) -> Result<Page<Monster>, ClientError> {
+        let url = build_url(
+            &self.base_url,
+            &["monsters".to_owned()],
+            ser_query_params(&[
+                ("limit", limit.map(ToString::to_string)),
+                ("offset", offset.map(ToString::to_string)),
+            ])?,
+        );
+        let request = self
+            .http_client
+            .request(::humblegen_rt::hyper::Method::GET, url);
+        send_request(request).await
+    }
+    #[doc = "Search monsters by name."]
+    pub async fn get_search(
+        &self,
+        name: Option<&String>,
+        limit: Option<&u32>,
+        offset: Option<&u32>,
+    ) -> Result<Page<Monster>, ClientError> {
+        let url = build_url(
+            &self.base_url,
+            &["search".to_owned()],
+            ser_query_params(&[
+                ("name", name.map(ToString::to_string)),
+                ("limit", limit.map(ToString::to_string)),
+                ("offset", offset.map(ToString::to_string)),
+            ])?,
+        );
+        let request = self
+            .http_client
+            .request(::humblegen_rt::hyper::Method::GET, url);
+        send_request(request).await
+    }
+}
+#[doc = "Paths of the routes of the `Monsters` service, relative to the root it is mounted at."]
+#[allow(dead_code)]
+pub mod monsters_paths {
+    #[allow(unused_imports)]
+    use super::*;
+    #[doc = "The path `/monsters`."]
+    pub fn monsters_path() -> String {
+        ::humblegen_rt::serialization_helpers::build_path(&["monsters".to_owned()])
+    }
+    #[doc = "The path `/search`."]
+    pub fn search_path() -> String {
+        ::humblegen_rt::serialization_helpers::build_path(&["search".to_owned()])
+    }
+}
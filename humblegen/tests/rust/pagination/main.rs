@@ -0,0 +1,137 @@
+mod server {
+    include!("spec.rs");
+}
+mod client {
+    include!("client.rs");
+}
+
+const NAMES: &[&str] = &["Goblin", "Orc", "Troll", "Gremlin", "Ogre"];
+
+/// Returns the requested page of the monsters whose name contains `name`.
+fn page(
+    name: Option<&str>,
+    limit: Option<u32>,
+    offset: Option<u32>,
+) -> server::Page<server::Monster> {
+    let matching: Vec<_> = NAMES
+        .iter()
+        .filter(|n| name.map_or(true, |name| n.contains(name)))
+        .collect();
+    let limit = limit.unwrap_or(2);
+    let offset = offset.unwrap_or(0);
+    server::Page {
+        items: matching
+            .iter()
+            .skip(offset as usize)
+            .take(limit as usize)
+            .map(|n| server::Monster {
+                name: n.to_string(),
+            })
+            .collect(),
+        total: matching.len() as u64,
+        limit,
+        offset,
+    }
+}
+
+struct MonstersService;
+
+#[humblegen_rt::async_trait(Sync)]
+impl server::Monsters for MonstersService {
+    type Context = ();
+
+    async fn get_monsters(
+        &self,
+        _ctx: Self::Context,
+        limit: Option<u32>,
+        offset: Option<u32>,
+    ) -> server::Response<server::Page<server::Monster>> {
+        Ok(page(None, limit, offset))
+    }
+
+    async fn get_search(
+        &self,
+        _ctx: Self::Context,
+        name: Option<String>,
+        limit: Option<u32>,
+        offset: Option<u32>,
+    ) -> server::Response<server::Page<server::Monster>> {
+        Ok(page(name.as_deref(), limit, offset))
+    }
+}
+
+#[tokio::main]
+async fn main() {
+    let addr = std::net::TcpListener::bind("127.0.0.1:0")
+        .and_then(|listener| listener.local_addr())
+        .expect("find free port");
+
+    tokio::spawn(async move {
+        server::Builder::new()
+            .add(
+                "/api",
+                server::Handler::Monsters(std::sync::Arc::new(MonstersService)),
+            )
+            .listen_and_run_forever(&addr)
+            .await
+            .expect("run server")
+    });
+
+    let http = humblegen_rt::reqwest::Client::new();
+    let url = |path: &str| format!("http://{}{}", addr, path);
+
+    // wait for the server to come up
+    let response = loop {
+        match http.get(&url("/api/monsters")).send().await {
+            Ok(response) => break response,
+            Err(_) => tokio::time::delay_for(std::time::Duration::from_millis(10)).await,
+        }
+    };
+    assert_eq!(response.status(), 200);
+
+    // the envelope carries the items alongside the paging information
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(
+        body,
+        serde_json::json!({
+            "items": [{ "name": "Goblin" }, { "name": "Orc" }],
+            "total": 5,
+            "limit": 2,
+            "offset": 0,
+        })
+    );
+
+    let body: serde_json::Value = http
+        .get(&url("/api/monsters?limit=3&offset=4"))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(
+        body,
+        serde_json::json!({
+            "items": [{ "name": "Ogre" }],
+            "total": 5,
+            "limit": 3,
+            "offset": 4,
+        })
+    );
+
+    // the client passes the paging parameters after the declared query fields
+    let client = client::MonstersClient::new(&url("/api")).unwrap();
+    let page = client
+        .get_search(Some(&"r".to_owned()), Some(&1), Some(&1))
+        .await
+        .unwrap();
+    assert_eq!(page.total, 4);
+    assert_eq!(page.limit, 1);
+    assert_eq!(page.offset, 1);
+    let names: Vec<_> = page.items.into_iter().map(|m| m.name).collect();
+    assert_eq!(names, vec!["Troll"]);
+
+    let page = client.get_monsters(None, Some(&4)).await.unwrap();
+    assert_eq!(page.total, 5);
+    assert_eq!(page.items.len(), 1);
+}
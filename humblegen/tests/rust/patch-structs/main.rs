@@ -0,0 +1,26 @@
+include!("spec.rs");
+
+fn main() {
+    // all fields of the patched struct became optional, except for the ones declared explicitly
+    let patch = MonsterPatch {
+        id: 1,
+        name: None,
+        max_hp: Some(20),
+        nickname: None,
+    };
+    let MonsterPatch {
+        id: _,
+        name: _,
+        max_hp: _,
+        nickname: _,
+    } = patch.clone();
+
+    // the patch uses the same field names on the wire as the patched struct
+    let json = serde_json::to_value(&patch).unwrap();
+    assert_eq!(json["maxHp"], 20);
+    let patch: MonsterPatch =
+        serde_json::from_str(r#"{"id": 2, "name": "Goblin", "maxHp": null, "nickname": null}"#)
+            .unwrap();
+    assert_eq!(patch.name.as_deref(), Some("Goblin"));
+    assert_eq!(patch.max_hp, None);
+}
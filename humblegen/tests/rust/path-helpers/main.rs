@@ -0,0 +1,84 @@
+include!("spec.rs");
+
+use humblegen_rt::hyper::service::Service as _;
+use humblegen_rt::hyper::{Body, Request, StatusCode};
+
+struct MonstersService;
+
+#[humblegen_rt::async_trait(Sync)]
+impl Monsters for MonstersService {
+    type Context = ();
+
+    async fn get_monsters_id(&self, _ctx: Self::Context, id: i32) -> Response<Monster> {
+        Ok(Monster {
+            id,
+            name: "Goblin".to_owned(),
+        })
+    }
+
+    async fn delete_monsters_id(&self, _ctx: Self::Context, _id: i32) -> Response<()> {
+        Ok(())
+    }
+
+    async fn get_monsters_by_name_name(
+        &self,
+        _ctx: Self::Context,
+        name: String,
+    ) -> Response<Monster> {
+        Ok(Monster { id: 1, name })
+    }
+
+    async fn get_files_path_content(&self, _ctx: Self::Context, path: String) -> Response<String> {
+        Ok(path)
+    }
+}
+
+#[tokio::main]
+async fn main() {
+    // paths include the base of the service
+    assert_eq!(monsters_paths::monsters_id_path(&42), "/v1/monsters/42");
+    // params are escaped, except for the slashes of rest params
+    assert_eq!(
+        monsters_paths::monsters_by_name_name_path(&"Big Bad/Wolf?".to_owned()),
+        "/v1/monsters/by-name/Big%20Bad%2FWolf%3F"
+    );
+    assert_eq!(
+        monsters_paths::files_path_content_path(&"a/b c".to_owned()),
+        "/v1/files/a/b%20c/content"
+    );
+
+    // the paths are routed to the endpoints they were built for
+    let mut service = Builder::new()
+        .add("/api", Handler::Monsters(Arc::new(MonstersService)))
+        .into_service()
+        .expect("valid service configuration");
+    let mut get = |path: String| {
+        let request = Request::get(format!("/api{}", path))
+            .body(Body::empty())
+            .unwrap();
+        service.call(request)
+    };
+    async fn body_of(response: humblegen_rt::hyper::Response<Body>) -> String {
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = humblegen_rt::hyper::body::to_bytes(response.into_body())
+            .await
+            .unwrap();
+        String::from_utf8(body.to_vec()).unwrap()
+    }
+
+    let response = get(monsters_paths::monsters_id_path(&42)).await.unwrap();
+    let monster: Monster = serde_json::from_str(&body_of(response).await).unwrap();
+    assert_eq!(monster.id, 42);
+
+    let name = "Goblin".to_owned();
+    let response = get(monsters_paths::monsters_by_name_name_path(&name))
+        .await
+        .unwrap();
+    let monster: Monster = serde_json::from_str(&body_of(response).await).unwrap();
+    assert_eq!(monster.name, name);
+
+    let response = get(monsters_paths::files_path_content_path(&"a/b".to_owned()))
+        .await
+        .unwrap();
+    assert_eq!(body_of(response).await, "\"a/b\"");
+}
@@ -0,0 +1,76 @@
+include!("spec.rs");
+
+use humblegen_rt::hyper::service::Service as _;
+use humblegen_rt::hyper::{Body, Request, StatusCode};
+use humblegen_rt::service_protocol::{ErrorResponseKind, RuntimeError};
+
+struct MonstersService;
+
+#[humblegen_rt::async_trait(Sync)]
+impl Monsters for MonstersService {
+    type Context = ();
+
+    async fn post_monsters(&self, _ctx: Self::Context, post_body: MonsterData) -> Response<String> {
+        Ok(post_body.name)
+    }
+}
+
+#[tokio::main]
+async fn main() {
+    let mut service = Builder::new()
+        .add("/api", Handler::Monsters(Arc::new(MonstersService)))
+        .into_service()
+        .expect("valid service configuration");
+    let mut post = |body: &str| {
+        let request = Request::post("/api/monsters")
+            .body(Body::from(body.to_owned()))
+            .unwrap();
+        service.call(request)
+    };
+    async fn error_of(response: humblegen_rt::hyper::Response<Body>) -> RuntimeError {
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let body = humblegen_rt::hyper::body::to_bytes(response.into_body())
+            .await
+            .unwrap();
+        match serde_json::from_slice::<ErrorResponse>(&body).unwrap().kind {
+            ErrorResponseKind::Runtime(error) => error,
+            other => panic!("expected runtime error, got {:?}", other),
+        }
+    }
+
+    let response = post(r#"{ "name": "Goblin", "homes": [{ "city": "Cave" }] }"#)
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // the path of a field with a type error is reported
+    let response = post(r#"{ "name": "Goblin", "homes": [{ "city": "Cave" }, { "city": 7 }] }"#)
+        .await
+        .unwrap();
+    match error_of(response).await {
+        RuntimeError::PostBodyInvalid { message, fields } => {
+            assert!(message.contains("line 1"), "{}", message);
+            assert_eq!(fields.len(), 1);
+            assert_eq!(fields[0].field, "homes[1].city");
+            assert_eq!(
+                fields[0].message,
+                "invalid type: integer `7`, expected a string"
+            );
+        }
+        other => panic!("expected PostBodyInvalid, got {:?}", other),
+    }
+
+    // syntax errors concern the body as a whole
+    let response = post(r#"{ "name": "Goblin", "#).await.unwrap();
+    match error_of(response).await {
+        RuntimeError::PostBodyInvalid { fields, .. } => assert!(fields.is_empty()),
+        other => panic!("expected PostBodyInvalid, got {:?}", other),
+    }
+    let response = post(r#"{ "name": "Goblin", "homes": [] } trailing"#)
+        .await
+        .unwrap();
+    match error_of(response).await {
+        RuntimeError::PostBodyInvalid { fields, .. } => assert!(fields.is_empty()),
+        other => panic!("expected PostBodyInvalid, got {:?}", other),
+    }
+}
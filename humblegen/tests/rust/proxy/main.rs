@@ -0,0 +1,144 @@
+include!("spec.rs");
+
+use humblegen_rt::hyper::service::Service as _;
+use humblegen_rt::hyper::{Body, Request, StatusCode};
+use humblegen_rt::service_protocol::{ErrorResponseKind, RuntimeError};
+use std::convert::Infallible;
+use std::time::Duration;
+
+struct MonstersService;
+
+#[humblegen_rt::async_trait(Sync)]
+impl Monsters for MonstersService {
+    type Context = ();
+
+    async fn get_monsters(&self, _ctx: Self::Context) -> Response<String> {
+        Ok("Goblin".to_owned())
+    }
+}
+
+struct OverlappingService;
+
+#[humblegen_rt::async_trait(Sync)]
+impl Overlapping for OverlappingService {
+    type Context = ();
+
+    async fn get_legacy_monsters(&self, _ctx: Self::Context) -> Response<String> {
+        Ok("Goblin".to_owned())
+    }
+}
+
+/// Echoes the request line, the `X-Monster` header and the body of the request.
+async fn upstream(req: Request<Body>) -> Result<humblegen_rt::hyper::Response<Body>, Infallible> {
+    if req.uri().path() == "/old/slow" {
+        tokio::time::delay_for(Duration::from_secs(10)).await;
+    }
+    let (parts, body) = req.into_parts();
+    let body = humblegen_rt::hyper::body::to_bytes(body).await.unwrap();
+    let echo = format!(
+        "{} {} {:?} {:?} {}",
+        parts.method,
+        parts.uri,
+        parts.headers.get("x-monster"),
+        parts.headers.get("connection"),
+        String::from_utf8(body.to_vec()).unwrap()
+    );
+    Ok(humblegen_rt::hyper::Response::builder()
+        .status(StatusCode::IM_A_TEAPOT)
+        .header("content-type", "text/plain")
+        .header("x-upstream", "legacy")
+        .body(Body::from(echo))
+        .unwrap())
+}
+
+async fn body_of(response: humblegen_rt::hyper::Response<Body>) -> String {
+    let body = humblegen_rt::hyper::body::to_bytes(response.into_body())
+        .await
+        .unwrap();
+    String::from_utf8(body.to_vec()).unwrap()
+}
+
+async fn runtime_error_of(response: humblegen_rt::hyper::Response<Body>) -> RuntimeError {
+    let body = body_of(response).await;
+    match serde_json::from_str::<ErrorResponse>(&body).unwrap().kind {
+        ErrorResponseKind::Runtime(error) => error,
+        other => panic!("expected runtime error, got {:?}", other),
+    }
+}
+
+#[tokio::main]
+async fn main() {
+    let addr = "127.0.0.1:48088".parse().unwrap();
+    let make_service = humblegen_rt::hyper::service::make_service_fn(|_conn| async {
+        Ok::<_, Infallible>(humblegen_rt::hyper::service::service_fn(upstream))
+    });
+    tokio::spawn(humblegen_rt::hyper::Server::bind(&addr).serve(make_service));
+
+    let mut service = Builder::new()
+        .add("/api", Handler::Monsters(Arc::new(MonstersService)))
+        .proxy_timeout(Duration::from_millis(200))
+        .into_service()
+        .expect("valid service configuration");
+
+    // endpoints are handled by the handler
+    let request = Request::get("/api/v1/monsters")
+        .body(Body::empty())
+        .unwrap();
+    let response = service.call(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(body_of(response).await, "\"Goblin\"");
+
+    // method, path below the prefix, query, headers and body are forwarded
+    let request = Request::post("/api/v1/legacy/monsters/7?name=Goblin")
+        .header("x-monster", "7")
+        .header("connection", "close")
+        .body(Body::from("hello"))
+        .unwrap();
+    let response = service.call(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::IM_A_TEAPOT);
+    assert_eq!(response.headers()["x-upstream"], "legacy");
+    assert_eq!(response.headers()["content-type"], "text/plain");
+    assert_eq!(
+        body_of(response).await,
+        "POST /old/monsters/7?name=Goblin Some(\"7\") None hello"
+    );
+
+    // the prefix itself is forwarded as well
+    let request = Request::delete("/api/v1/legacy")
+        .body(Body::empty())
+        .unwrap();
+    let response = service.call(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::IM_A_TEAPOT);
+    assert_eq!(body_of(response).await, "DELETE /old None None ");
+
+    // unreachable upstream servers are reported as bad gateway
+    let request = Request::get("/api/v1/gone/monsters")
+        .body(Body::empty())
+        .unwrap();
+    let response = service.call(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_GATEWAY);
+    match runtime_error_of(response).await {
+        RuntimeError::UpstreamUnavailable { upstream, .. } => {
+            assert_eq!(upstream, "http://127.0.0.1:1")
+        }
+        other => panic!("expected UpstreamUnavailable, got {:?}", other),
+    }
+
+    // slow upstream servers are reported as gateway timeout
+    let request = Request::get("/api/v1/legacy/slow")
+        .body(Body::empty())
+        .unwrap();
+    let response = service.call(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::GATEWAY_TIMEOUT);
+    match runtime_error_of(response).await {
+        RuntimeError::UpstreamTimeout { timeout_ms, .. } => assert_eq!(timeout_ms, 200),
+        other => panic!("expected UpstreamTimeout, got {:?}", other),
+    }
+
+    // proxies that share their paths with endpoints are rejected
+    let result = Builder::new().try_add("/api", Handler::Overlapping(Arc::new(OverlappingService)));
+    assert!(matches!(
+        result,
+        Err(server::BuilderError::InvalidRoutes(_))
+    ));
+}
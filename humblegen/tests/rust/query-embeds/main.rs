@@ -0,0 +1,67 @@
+include!("spec.rs");
+
+use humblegen_rt::hyper::service::Service as _;
+use humblegen_rt::hyper::{Body, Request, StatusCode};
+
+struct MonstersService;
+
+#[humblegen_rt::async_trait(Sync)]
+impl Monsters for MonstersService {
+    type Context = ();
+
+    async fn get_monsters(
+        &self,
+        _ctx: Self::Context,
+        query: Option<MonsterQuery>,
+    ) -> Response<String> {
+        // the fields of `Pagination` are inlined into the query
+        let MonsterQuery {
+            name,
+            limit,
+            offset,
+        } = query.expect("query");
+        Ok(format!("{:?} {:?} {:?}", name, limit, offset))
+    }
+
+    async fn get_items(&self, _ctx: Self::Context, query: Option<ItemQuery>) -> Response<String> {
+        let query = query.expect("query");
+        Ok(format!("{:?} {:?}", query.limit, query.offset))
+    }
+}
+
+#[tokio::main]
+async fn main() {
+    let mut service = Builder::new()
+        .strict_query(true)
+        .add("/api", Handler::Monsters(Arc::new(MonstersService)))
+        .into_service()
+        .expect("valid service configuration");
+    let mut get = |uri: &str| {
+        let request = Request::get(uri).body(Body::empty()).unwrap();
+        service.call(request)
+    };
+    async fn body(response: humblegen_rt::hyper::Response<Body>) -> String {
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = humblegen_rt::hyper::body::to_bytes(response.into_body())
+            .await
+            .unwrap();
+        serde_json::from_slice(&body).unwrap()
+    }
+
+    let response = get("/api/monsters?limit=10&offset=20&name=foo").await.unwrap();
+    assert_eq!(body(response).await, "Some(\"foo\") Some(10) Some(20)");
+
+    let response = get("/api/monsters?name=foo").await.unwrap();
+    assert_eq!(body(response).await, "Some(\"foo\") None None");
+
+    let response = get("/api/items?offset=5").await.unwrap();
+    assert_eq!(body(response).await, "None Some(5)");
+
+    // embedded fields are known to strict query parsing, others are not
+    let response = get("/api/items?limit=10&name=foo").await.unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    // embedded fields are still typed
+    let response = get("/api/monsters?limit=ten").await.unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
@@ -0,0 +1,96 @@
+include!("spec.rs");
+
+fn main() {
+    // values within the range, including its bounds, are accepted
+    let json = r#"{"age":0,"level":99,"temperature":-40,"score":1000000,"rank":null}"#;
+    let player: Player = serde_json::from_str(json).unwrap();
+    assert_eq!(player.age, 0);
+    assert_eq!(player.level, 99);
+    assert_eq!(player.temperature, -40);
+    assert_eq!(player.rank, None);
+    assert_eq!(serde_json::to_string(&player).unwrap(), json);
+    assert!(player.validate().is_ok());
+
+    let player: Player =
+        serde_json::from_str(r#"{"age":150,"temperature":9000,"score":0,"rank":10}"#).unwrap();
+    assert_eq!(player.age, 150);
+    assert_eq!(player.level, 1);
+    assert_eq!(player.rank, Some(10));
+
+    // optional fields with a range may be missing
+    let player: Player = serde_json::from_str(r#"{"age":1,"score":0,"temperature":0}"#).unwrap();
+    assert_eq!(player.rank, None);
+
+    // values outside of the range are rejected
+    for (json, expected) in &[
+        (
+            r#"{"age":151,"score":0,"temperature":0}"#,
+            "`age` must be in the range 0..=150, but is 151",
+        ),
+        (
+            r#"{"age":-1,"score":0,"temperature":0}"#,
+            "`age` must be in the range 0..=150, but is -1",
+        ),
+        (
+            r#"{"age":1,"level":100,"score":0,"temperature":0}"#,
+            "`level` must be in the range 1..=99, but is 100",
+        ),
+        (
+            r#"{"age":1,"level":0,"score":0,"temperature":0}"#,
+            "`level` must be in the range 1..=99, but is 0",
+        ),
+        (
+            r#"{"age":1,"score":0,"temperature":-41}"#,
+            "`temperature` must be in the range -40.., but is -41",
+        ),
+        (
+            r#"{"age":1,"score":1000001,"temperature":0}"#,
+            "`score` must be in the range ..=1000000, but is 1000001",
+        ),
+        (
+            r#"{"age":1,"score":0,"temperature":0,"rank":0}"#,
+            "`rank` must be in the range 1..=10, but is 0",
+        ),
+    ] {
+        let err = serde_json::from_str::<Player>(json)
+            .unwrap_err()
+            .to_string();
+        assert!(err.starts_with(expected), "{}", err);
+    }
+
+    // values constructed in code are checked by `validate`
+    let mut player = Player {
+        age: 200,
+        level: 1,
+        temperature: 0,
+        score: 0,
+        rank: None,
+    };
+    let err = player.validate().unwrap_err();
+    assert_eq!(err.field, "age");
+    assert_eq!(err.value, 200);
+    assert_eq!(
+        err.to_string(),
+        "`age` must be in the range 0..=150, but is 200"
+    );
+    player.age = 20;
+    player.rank = Some(11);
+    assert_eq!(player.validate().unwrap_err().field, "rank");
+
+    // fields of flattened embeds are checked by the embedded struct
+    assert!(serde_json::from_str::<Hero>(r#"{"name":"Conan","strength":21}"#).is_err());
+    let hero: Hero = serde_json::from_str(r#"{"name":"Conan","strength":20}"#).unwrap();
+    assert!(hero.validate().is_ok());
+    let hero = Hero {
+        name: "Conan".to_owned(),
+        stats: Stats { strength: 0 },
+    };
+    assert_eq!(hero.validate().unwrap_err().field, "strength");
+
+    // as well as fields of enum variants
+    assert!(serde_json::from_str::<Move>(r#"{"Walk":{"steps":11}}"#).is_err());
+    let walk: Move = serde_json::from_str(r#"{"Walk":{"steps":10}}"#).unwrap();
+    assert!(walk.validate().is_ok());
+    assert!(Move::Walk { steps: 0 }.validate().is_err());
+    assert!(Move::Wait.validate().is_ok());
+}
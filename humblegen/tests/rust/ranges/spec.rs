@@ -0,0 +1,184 @@
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[doc = "Player of a game, with constraints on its stats."]
+pub struct Player {
+    #[doc = ""]
+    #[serde(deserialize_with = "Player::deserialize_age")]
+    pub age: i32,
+    #[doc = ""]
+    #[serde(default = "Player::default_level")]
+    #[serde(deserialize_with = "Player::deserialize_level")]
+    pub level: u8,
+    #[doc = ""]
+    #[serde(deserialize_with = "Player::deserialize_temperature")]
+    pub temperature: i16,
+    #[doc = ""]
+    #[serde(deserialize_with = "Player::deserialize_score")]
+    pub score: u64,
+    #[doc = ""]
+    #[serde(deserialize_with = "Player::deserialize_rank")]
+    #[serde(default)]
+    pub rank: Option<u16>,
+}
+impl Player {
+    fn deserialize_age<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<i32, D::Error> {
+        ::humblegen_rt::serialization_helpers::deser_in_range(
+            deserializer,
+            "age",
+            Some(0),
+            Some(150),
+        )
+    }
+    fn default_level() -> u8 {
+        1
+    }
+    fn deserialize_level<'de, D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<u8, D::Error> {
+        ::humblegen_rt::serialization_helpers::deser_in_range(
+            deserializer,
+            "level",
+            Some(1),
+            Some(99),
+        )
+    }
+    fn deserialize_temperature<'de, D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<i16, D::Error> {
+        ::humblegen_rt::serialization_helpers::deser_in_range(
+            deserializer,
+            "temperature",
+            Some(-40),
+            None,
+        )
+    }
+    fn deserialize_score<'de, D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<u64, D::Error> {
+        ::humblegen_rt::serialization_helpers::deser_in_range(
+            deserializer,
+            "score",
+            None,
+            Some(1000000),
+        )
+    }
+    fn deserialize_rank<'de, D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<u16>, D::Error> {
+        ::humblegen_rt::serialization_helpers::deser_optional_in_range(
+            deserializer,
+            "rank",
+            Some(1),
+            Some(10),
+        )
+    }
+}
+impl Player {
+    #[doc = r" Check that all fields are within the ranges declared in the spec."]
+    pub fn validate(&self) -> Result<(), ::humblegen_rt::serialization_helpers::OutOfRange> {
+        ::humblegen_rt::serialization_helpers::check_range("age", self.age, Some(0), Some(150))?;
+        ::humblegen_rt::serialization_helpers::check_range("level", self.level, Some(1), Some(99))?;
+        ::humblegen_rt::serialization_helpers::check_range(
+            "temperature",
+            self.temperature,
+            Some(-40),
+            None,
+        )?;
+        ::humblegen_rt::serialization_helpers::check_range(
+            "score",
+            self.score,
+            None,
+            Some(1000000),
+        )?;
+        if let Some(value) = self.rank {
+            ::humblegen_rt::serialization_helpers::check_range("rank", value, Some(1), Some(10))?;
+        }
+        Ok(())
+    }
+}
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[doc = ""]
+pub struct Stats {
+    #[doc = ""]
+    #[serde(deserialize_with = "Stats::deserialize_strength")]
+    pub strength: u8,
+}
+impl Stats {
+    fn deserialize_strength<'de, D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<u8, D::Error> {
+        ::humblegen_rt::serialization_helpers::deser_in_range(
+            deserializer,
+            "strength",
+            Some(1),
+            Some(20),
+        )
+    }
+}
+impl Stats {
+    #[doc = r" Check that all fields are within the ranges declared in the spec."]
+    pub fn validate(&self) -> Result<(), ::humblegen_rt::serialization_helpers::OutOfRange> {
+        ::humblegen_rt::serialization_helpers::check_range(
+            "strength",
+            self.strength,
+            Some(1),
+            Some(20),
+        )?;
+        Ok(())
+    }
+}
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[doc = ""]
+pub struct Hero {
+    #[doc = ""]
+    pub name: String,
+    #[serde(flatten)]
+    pub stats: Stats,
+}
+impl Hero {
+    #[doc = r" Check that all fields are within the ranges declared in the spec."]
+    pub fn validate(&self) -> Result<(), ::humblegen_rt::serialization_helpers::OutOfRange> {
+        self.stats.validate()?;
+        Ok(())
+    }
+}
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[doc = ""]
+pub enum Move {
+    #[doc = ""]
+    Walk {
+        #[doc = ""]
+        #[serde(deserialize_with = "Move::deserialize_walk_steps")]
+        steps: u32,
+    },
+    #[doc = ""]
+    Wait,
+}
+impl Move {
+    fn deserialize_walk_steps<'de, D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<u32, D::Error> {
+        ::humblegen_rt::serialization_helpers::deser_in_range(
+            deserializer,
+            "steps",
+            Some(1),
+            Some(10),
+        )
+    }
+}
+impl Move {
+    #[doc = r" Check that all fields are within the ranges declared in the spec."]
+    pub fn validate(&self) -> Result<(), ::humblegen_rt::serialization_helpers::OutOfRange> {
+        match *self {
+            Move::Walk { steps, .. } => {
+                ::humblegen_rt::serialization_helpers::check_range(
+                    "steps",
+                    steps,
+                    Some(1),
+                    Some(10),
+                )?;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+}
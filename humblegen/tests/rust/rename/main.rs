@@ -0,0 +1,31 @@
+include!("spec.rs");
+
+fn main() {
+    let camel = CamelCased {
+        first_name: "Ada".to_owned(),
+        postal_code: "12345".to_owned(),
+    };
+    assert_eq!(
+        serde_json::to_string(&camel).unwrap(),
+        r#"{"firstName":"Ada","zip":"12345"}"#
+    );
+
+    let snake = SnakeCased {
+        first_name: "Ada".to_owned(),
+    };
+    assert_eq!(
+        serde_json::to_string(&snake).unwrap(),
+        r#"{"first_name":"Ada"}"#
+    );
+
+    let screaming = ScreamingSnakeCased {
+        first_name: "Ada".to_owned(),
+    };
+    assert_eq!(
+        serde_json::to_string(&screaming).unwrap(),
+        r#"{"FIRST_NAME":"Ada"}"#
+    );
+
+    let kebab: KebabCased = serde_json::from_str(r#"{"first-name":"Ada"}"#).unwrap();
+    assert_eq!(kebab.first_name, "Ada");
+}
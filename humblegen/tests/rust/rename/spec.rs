@@ -0,0 +1,31 @@
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[doc = "Keys are camelCase on the wire."]
+#[serde(rename_all = "camelCase")]
+pub struct CamelCased {
+    #[doc = ""]
+    pub first_name: String,
+    #[doc = ""]
+    #[serde(rename = "zip")]
+    pub postal_code: String,
+}
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[doc = ""]
+#[serde(rename_all = "snake_case")]
+pub struct SnakeCased {
+    #[doc = ""]
+    pub first_name: String,
+}
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[doc = ""]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub struct ScreamingSnakeCased {
+    #[doc = ""]
+    pub first_name: String,
+}
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[doc = ""]
+#[serde(rename_all = "kebab-case")]
+pub struct KebabCased {
+    #[doc = ""]
+    pub first_name: String,
+}
@@ -0,0 +1,64 @@
+include!("spec.rs");
+
+struct PingerService;
+
+#[humblegen_rt::async_trait(Sync)]
+impl Pinger for PingerService {
+    type Context = ();
+
+    async fn get_ping(&self, _ctx: Self::Context) -> Response<String> {
+        Ok("pong".to_owned())
+    }
+}
+
+#[tokio::main]
+async fn main() {
+    let addr = std::net::TcpListener::bind("127.0.0.1:0")
+        .and_then(|listener| listener.local_addr())
+        .expect("find free port");
+
+    tokio::spawn(async move {
+        Builder::new()
+            .add("/api", Handler::Pinger(std::sync::Arc::new(PingerService)))
+            .request_ids(server::RequestIdConfig {
+                header_name: hyper::header::HeaderName::from_static("x-request-id"),
+                strategy: server::RequestIdStrategy::ReuseInbound,
+            })
+            .listen_and_run_forever(&addr)
+            .await
+            .expect("run server")
+    });
+
+    let client = humblegen_rt::reqwest::Client::new();
+    let url = format!("http://{}/api/ping", addr);
+
+    // wait for the server to come up; a request without request ID gets a generated one
+    let response = loop {
+        match client.get(&url).send().await {
+            Ok(response) => break response,
+            Err(_) => tokio::time::delay_for(std::time::Duration::from_millis(10)).await,
+        }
+    };
+    let generated = response.headers()["X-Request-Id"].to_str().unwrap();
+    assert_eq!(generated.len(), 30);
+    assert!(response.headers().get("Request-ID").is_none());
+
+    // an inbound request ID is echoed back unchanged
+    let response = client
+        .get(&url)
+        .header("X-Request-Id", "upstream-4711.Abc")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.headers()["X-Request-Id"], "upstream-4711.Abc");
+
+    // an oversized inbound request ID is replaced
+    let response = client
+        .get(&url)
+        .header("X-Request-Id", "x".repeat(1000))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.headers()["X-Request-Id"].len(), 30);
+}
@@ -0,0 +1,59 @@
+include!("spec.rs");
+
+use humblegen_rt::service_protocol::{ErrorResponseKind, RuntimeError};
+use std::time::Duration;
+
+struct ClockService;
+
+#[humblegen_rt::async_trait(Sync)]
+impl Clock for ClockService {
+    type Context = ();
+
+    async fn get_fast(&self, _ctx: Self::Context) -> Response<String> {
+        Ok("tick".to_owned())
+    }
+
+    async fn get_slow(&self, _ctx: Self::Context) -> Response<String> {
+        tokio::time::delay_for(Duration::from_secs(10)).await;
+        Ok("tock".to_owned())
+    }
+}
+
+#[tokio::main]
+async fn main() {
+    let addr = std::net::TcpListener::bind("127.0.0.1:0")
+        .and_then(|listener| listener.local_addr())
+        .expect("find free port");
+
+    tokio::spawn(async move {
+        Builder::new()
+            .add("/api", Handler::Clock(std::sync::Arc::new(ClockService)))
+            .request_timeout(Duration::from_millis(100))
+            .listen_and_run_forever(&addr)
+            .await
+            .expect("run server")
+    });
+
+    let url = format!("http://{}/api/fast", addr);
+    let response = loop {
+        match humblegen_rt::reqwest::get(&url).await {
+            Ok(response) => break response,
+            Err(_) => tokio::time::delay_for(Duration::from_millis(10)).await,
+        }
+    };
+    assert_eq!(response.status(), 200);
+
+    // the slow handler is cancelled once the timeout expires
+    let started = std::time::Instant::now();
+    let response = humblegen_rt::reqwest::get(&format!("http://{}/api/slow", addr))
+        .await
+        .unwrap();
+    assert!(started.elapsed() < Duration::from_secs(5));
+    assert_eq!(response.status(), 504);
+    match response.json::<ErrorResponse>().await.unwrap().kind {
+        ErrorResponseKind::Runtime(RuntimeError::HandlerTimeout { timeout_ms }) => {
+            assert_eq!(timeout_ms, 100)
+        }
+        other => panic!("expected HandlerTimeout, got {:?}", other),
+    }
+}
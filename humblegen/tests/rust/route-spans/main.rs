@@ -0,0 +1,90 @@
+include!("spec.rs");
+
+use humblegen_rt::hyper::service::Service as _;
+use humblegen_rt::hyper::{Body, Request, StatusCode};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use tracing::span::{Attributes, Id, Record};
+
+struct MonstersService;
+
+#[humblegen_rt::async_trait(Sync)]
+impl Monsters for MonstersService {
+    type Context = ();
+
+    async fn get_monsters_id(&self, _ctx: Self::Context, id: u32) -> Response<u32> {
+        Ok(id)
+    }
+}
+
+/// Collects the fields of all spans, e.g. the matched route.
+#[derive(Default)]
+struct Recorder {
+    next_id: AtomicU64,
+    recorded: Mutex<Vec<(String, String)>>,
+}
+
+struct Visitor<'a>(&'a mut Vec<(String, String)>);
+
+impl tracing::field::Visit for Visitor<'_> {
+    fn record_str(&mut self, field: &tracing::field::Field, value: &str) {
+        self.0.push((field.name().to_owned(), value.to_owned()));
+    }
+
+    fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn std::fmt::Debug) {
+        self.0
+            .push((field.name().to_owned(), format!("{:?}", value)));
+    }
+}
+
+impl tracing::Subscriber for &'static Recorder {
+    fn enabled(&self, _metadata: &tracing::Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, span: &Attributes<'_>) -> Id {
+        span.record(&mut Visitor(&mut self.recorded.lock().unwrap()));
+        Id::from_u64(self.next_id.fetch_add(1, Ordering::SeqCst) + 1)
+    }
+
+    fn record(&self, _span: &Id, values: &Record<'_>) {
+        values.record(&mut Visitor(&mut self.recorded.lock().unwrap()));
+    }
+
+    fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+
+    fn event(&self, _event: &tracing::Event<'_>) {}
+
+    fn enter(&self, _span: &Id) {}
+
+    fn exit(&self, _span: &Id) {}
+}
+
+#[tokio::main]
+async fn main() {
+    let recorder: &'static Recorder = Box::leak(Box::new(Recorder::default()));
+    tracing::subscriber::set_global_default(recorder).unwrap();
+
+    let mut service = Builder::new()
+        .add("/api", Handler::Monsters(Arc::new(MonstersService)))
+        .into_service()
+        .expect("valid service configuration");
+    let request = Request::get("/api/v1/monsters/7")
+        .body(Body::empty())
+        .unwrap();
+    let response = service.call(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // the span of the handler names the service and the route, not the path or the route regex
+    let recorded = recorder.recorded.lock().unwrap();
+    assert!(
+        recorded.contains(&("service".to_owned(), "Monsters".to_owned())),
+        "{:?}",
+        recorded
+    );
+    assert!(
+        recorded.contains(&("route".to_owned(), "/v1/monsters/{id}".to_owned())),
+        "{:?}",
+        recorded
+    );
+}
@@ -0,0 +1,96 @@
+mod server {
+    include!("spec.rs");
+}
+
+use humblegen_rt::futures::{self, StreamExt};
+use std::sync::Arc;
+
+fn notification(message: &str) -> server::Notification {
+    server::Notification {
+        message: message.to_owned(),
+    }
+}
+
+struct NotificationService;
+
+#[humblegen_rt::async_trait(Sync)]
+impl server::Notifications for NotificationService {
+    type Context = ();
+
+    async fn get_notifications(
+        &self,
+        _ctx: Self::Context,
+    ) -> server::Response<Vec<server::Notification>> {
+        Ok(vec![notification("Welcome")])
+    }
+
+    async fn sse_notifications(
+        &self,
+        _ctx: Self::Context,
+    ) -> server::Response<server::StreamResponse<server::Notification>> {
+        // keep the event stream open after the notifications
+        Ok(futures::stream::iter(vec![
+            notification("A dragon was sighted"),
+            notification("The dragon left"),
+        ])
+        .chain(futures::stream::pending())
+        .boxed())
+    }
+}
+
+#[tokio::main]
+async fn main() {
+    let addr = std::net::TcpListener::bind("127.0.0.1:0")
+        .and_then(|listener| listener.local_addr())
+        .expect("find free port");
+
+    tokio::spawn(async move {
+        server::Builder::new()
+            .add(
+                "/api",
+                server::Handler::Notifications(Arc::new(NotificationService)),
+            )
+            .listen_and_run_forever(&addr)
+            .await
+            .expect("run server")
+    });
+
+    // plain requests to the same path are dispatched to the `GET` endpoint
+    let url = format!("http://{}/api/notifications", addr);
+    let response = loop {
+        match humblegen_rt::reqwest::get(&url).await {
+            Ok(response) => break response,
+            Err(_) => tokio::time::delay_for(std::time::Duration::from_millis(10)).await,
+        }
+    };
+    assert_eq!(response.status(), 200);
+    let notifications: Vec<server::Notification> = response.json().await.unwrap();
+    assert_eq!(notifications.len(), 1);
+
+    // requests accepting an event stream are dispatched to the `SSE` endpoint
+    let mut response = humblegen_rt::reqwest::Client::new()
+        .get(&url)
+        .header("Accept", "text/event-stream")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.headers()["Content-Type"], "text/event-stream");
+
+    // read until two events are complete, the stream itself does not end
+    let mut received = String::new();
+    while received.matches("\n\n").count() < 2 {
+        let chunk = response.chunk().await.unwrap().expect("event stream ended");
+        received.push_str(std::str::from_utf8(&chunk).unwrap());
+    }
+    let events: Vec<&str> = received.split_terminator("\n\n").collect();
+    assert_eq!(
+        events,
+        vec![
+            r#"id: 1
+data: {"message":"A dragon was sighted"}"#,
+            r#"id: 2
+data: {"message":"The dragon left"}"#,
+        ]
+    );
+}
@@ -20,7 +20,7 @@ impl BlogApi for S {
     async fn intercept_handler_pre(
         &self,
         req: &hyper::Request<hyper::Body>,
-    ) -> Result<Self::Context, ServiceError> {
+    ) -> Result<InterceptOutcome<Self::Context>, ServiceError> {
         if req
             .headers()
             .get(hyper::header::AUTHORIZATION)
@@ -30,10 +30,10 @@ impl BlogApi for S {
             == "Custom AUTHZ_TOKEN"
         {
             // .. find user id database ...
-            Ok(AuthzScope {
+            Ok(InterceptOutcome::Continue(AuthzScope {
                 user_id: "alice".to_owned(),
                 posting_allowed: true,
-            })
+            }))
         } else {
             Err(ServiceError::Authorization)
         }
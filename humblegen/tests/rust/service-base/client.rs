@@ -0,0 +1,84 @@
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[doc = ""]
+pub struct Monster {
+    #[doc = ""]
+    pub id: u32,
+    #[doc = ""]
+    pub name: String,
+}
+#[allow(unused_imports)]
+use ::humblegen_rt::client::{
+    build_url, parse_base_url, send_raw_request, send_request, send_result_request,
+    send_stream_request, ser_query_params, ser_query_primitive, ser_query_serde_urlencoded,
+};
+#[allow(unused_imports)]
+pub use ::humblegen_rt::client::{ClientError, RawResponse, StreamResponse};
+#[allow(unused_imports)]
+use ::humblegen_rt::reqwest;
+#[doc = "Client for the `Monsters` service. Requires the `client` feature of `humblegen_rt`."]
+#[doc = ""]
+#[doc = "Version 1 of the monster API."]
+#[derive(Debug, Clone)]
+pub struct MonstersClient {
+    base_url: reqwest::Url,
+    http_client: reqwest::Client,
+}
+#[allow(deprecated)]
+impl MonstersClient {
+    #[doc = r" Creates a client for the service mounted at `base_url`, e.g. `http://localhost:3000/api`."]
+    pub fn new(base_url: &str) -> Result<Self, ClientError> {
+        Self::with_http_client(base_url, reqwest::Client::new())
+    }
+    #[doc = r" Creates a client that sends its requests using the given `http_client`."]
+    pub fn with_http_client(
+        base_url: &str,
+        http_client: reqwest::Client,
+    ) -> Result<Self, ClientError> {
+        Ok(Self {
+            base_url: parse_base_url(base_url)?,
+            http_client,
+        })
+    }
+    #[doc = "Retrieve all monsters."]
+    pub async fn get_monsters(&self) -> Result<Vec<Monster>, ClientError> {
+        let url = build_url(
+            &self.base_url,
+            &["v1".to_owned(), "monsters".to_owned()],
+            None,
+        );
+        let request = self
+            .http_client
+            .request(::humblegen_rt::hyper::Method::GET, url);
+        send_request(request).await
+    }
+    #[doc = "Retrieve a single monster."]
+    pub async fn get_monsters_id(&self, id: &u32) -> Result<Monster, ClientError> {
+        let url = build_url(
+            &self.base_url,
+            &["v1".to_owned(), "monsters".to_owned(), id.to_string()],
+            None,
+        );
+        let request = self
+            .http_client
+            .request(::humblegen_rt::hyper::Method::GET, url);
+        send_request(request).await
+    }
+}
+#[doc = "Paths of the routes of the `Monsters` service, relative to the root it is mounted at."]
+#[allow(dead_code)]
+pub mod monsters_paths {
+    #[allow(unused_imports)]
+    use super::*;
+    #[doc = "The path `/v1/monsters`."]
+    pub fn monsters_path() -> String {
+        ::humblegen_rt::serialization_helpers::build_path(&["v1".to_owned(), "monsters".to_owned()])
+    }
+    #[doc = "The path `/v1/monsters/{id}`."]
+    pub fn monsters_id_path(id: &u32) -> String {
+        ::humblegen_rt::serialization_helpers::build_path(&[
+            "v1".to_owned(),
+            "monsters".to_owned(),
+            id.to_string(),
+        ])
+    }
+}
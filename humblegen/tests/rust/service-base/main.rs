@@ -0,0 +1,84 @@
+mod server {
+    include!("spec.rs");
+}
+mod client {
+    include!("client.rs");
+}
+
+use humblegen_rt::service_protocol::{ErrorResponseKind, RuntimeError};
+use std::sync::Arc;
+
+fn goblin(id: u32) -> server::Monster {
+    server::Monster {
+        id,
+        name: "Goblin".to_owned(),
+    }
+}
+
+struct MonsterService;
+
+#[humblegen_rt::async_trait(Sync)]
+impl server::Monsters for MonsterService {
+    type Context = ();
+
+    async fn get_monsters(&self, _ctx: Self::Context) -> server::Response<Vec<server::Monster>> {
+        Ok(vec![goblin(1), goblin(2)])
+    }
+
+    async fn get_monsters_id(
+        &self,
+        _ctx: Self::Context,
+        id: u32,
+    ) -> server::Response<server::Monster> {
+        Ok(goblin(id))
+    }
+}
+
+#[tokio::main]
+async fn main() {
+    let addr = std::net::TcpListener::bind("127.0.0.1:0")
+        .and_then(|listener| listener.local_addr())
+        .expect("find free port");
+
+    tokio::spawn(async move {
+        server::Builder::new()
+            .add("/api", server::Handler::Monsters(Arc::new(MonsterService)))
+            .listen_and_run_forever(&addr)
+            .await
+            .expect("run server")
+    });
+
+    // the base path is inserted between the mount prefix and the route
+    let url = format!("http://{}/api/v1/monsters", addr);
+    let response = loop {
+        match humblegen_rt::reqwest::get(&url).await {
+            Ok(response) => break response,
+            Err(_) => tokio::time::delay_for(std::time::Duration::from_millis(10)).await,
+        }
+    };
+    assert_eq!(response.status(), 200);
+    let monsters: Vec<server::Monster> = response.json().await.unwrap();
+    assert_eq!(monsters.len(), 2);
+
+    // routes are not matched without the base path
+    let response = humblegen_rt::reqwest::get(&format!("http://{}/api/monsters", addr))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 404);
+    match response
+        .json::<humblegen_rt::service_protocol::ErrorResponse>()
+        .await
+        .unwrap()
+        .kind
+    {
+        ErrorResponseKind::Runtime(RuntimeError::NoRouteMountedInService { service }) => {
+            assert_eq!(service, "/api")
+        }
+        other => panic!("expected NoRouteMountedInService, got {:?}", other),
+    }
+
+    // the client prepends the base path, too
+    let client = client::MonstersClient::new(&format!("http://{}/api", addr)).unwrap();
+    assert_eq!(client.get_monsters_id(&7).await.unwrap().id, 7);
+    assert_eq!(client.get_monsters().await.unwrap().len(), 2);
+}
@@ -0,0 +1,104 @@
+include!("spec.rs");
+
+struct PingerService;
+
+/// The name of the caller, taken from the `X-Caller` header.
+#[derive(Default)]
+struct Caller(String);
+
+#[humblegen_rt::async_trait(Sync)]
+impl Pinger for PingerService {
+    type Context = Caller;
+
+    async fn intercept_handler_pre(
+        &self,
+        req: &hyper::Request<hyper::Body>,
+    ) -> Result<InterceptOutcome<Self::Context>, ServiceError> {
+        match req.headers().get("X-Caller") {
+            Some(caller) => Ok(InterceptOutcome::Continue(Caller(
+                caller.to_str().unwrap().to_owned(),
+            ))),
+            None => Err(ServiceError::Authentication),
+        }
+    }
+
+    async fn intercept_handler_post(
+        &self,
+        mut resp: hyper::Response<hyper::Body>,
+    ) -> hyper::Response<hyper::Body> {
+        resp.headers_mut().insert(
+            hyper::header::CACHE_CONTROL,
+            hyper::header::HeaderValue::from_static("no-store"),
+        );
+        resp
+    }
+
+    async fn get_ping(&self, ctx: Self::Context) -> Response<String> {
+        assert_eq!(ctx.0, "alice");
+        Ok("pong".to_owned())
+    }
+
+    async fn get_forbidden(&self, _ctx: Self::Context) -> Response<String> {
+        Err(ServiceError::Authorization)
+    }
+
+    async fn get_echo_n(&self, _ctx: Self::Context, n: u32) -> Response<u32> {
+        Ok(n)
+    }
+}
+
+#[tokio::main]
+async fn main() {
+    let addr = std::net::TcpListener::bind("127.0.0.1:0")
+        .and_then(|listener| listener.local_addr())
+        .expect("find free port");
+
+    tokio::spawn(async move {
+        Builder::new()
+            .add("/api", Handler::Pinger(std::sync::Arc::new(PingerService)))
+            .listen_and_run_forever(&addr)
+            .await
+            .expect("run server")
+    });
+
+    let client = humblegen_rt::reqwest::Client::new();
+    let get = |path: &str| {
+        client
+            .get(&format!("http://{}/api{}", addr, path))
+            .header("X-Caller", "alice")
+            .send()
+    };
+
+    // wait for the server to come up
+    let response = loop {
+        match get("/ping").await {
+            Ok(response) => break response,
+            Err(_) => tokio::time::delay_for(std::time::Duration::from_millis(10)).await,
+        }
+    };
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.headers()["Cache-Control"], "no-store");
+    assert_eq!(response.json::<String>().await.unwrap(), "pong");
+
+    // the hook also sees error responses returned by the handler
+    let response = get("/forbidden").await.unwrap();
+    assert_eq!(response.status(), 403);
+    assert_eq!(response.headers()["Cache-Control"], "no-store");
+
+    // and requests that cannot be deserialized
+    let response = get("/echo/not-a-number").await.unwrap();
+    assert_eq!(response.status(), 400);
+    assert_eq!(response.headers()["Cache-Control"], "no-store");
+
+    // and requests rejected by the pre interceptor
+    let response = humblegen_rt::reqwest::get(&format!("http://{}/api/ping", addr))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 401);
+    assert_eq!(response.headers()["Cache-Control"], "no-store");
+
+    // but not responses to requests that never reached a service
+    let response = get("/unknown").await.unwrap();
+    assert_eq!(response.status(), 404);
+    assert!(response.headers().get("Cache-Control").is_none());
+}
@@ -0,0 +1,84 @@
+include!("spec.rs");
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+#[derive(Default)]
+struct BestiaryService {
+    handler_calls: AtomicUsize,
+}
+
+#[humblegen_rt::async_trait(Sync)]
+impl Bestiary for BestiaryService {
+    type Context = ();
+
+    async fn intercept_handler_pre(
+        &self,
+        req: &hyper::Request<hyper::Body>,
+    ) -> Result<InterceptOutcome<Self::Context>, ServiceError> {
+        if req.uri().path() == "/api/beasts" {
+            let response = hyper::Response::builder()
+                .status(hyper::StatusCode::FOUND)
+                .header(hyper::header::LOCATION, "/api/monsters")
+                .body(hyper::Body::empty())
+                .unwrap();
+            return Ok(InterceptOutcome::Respond(response));
+        }
+        Ok(InterceptOutcome::Continue(()))
+    }
+
+    async fn get_beasts(&self, _ctx: Self::Context) -> Response<Vec<String>> {
+        self.handler_calls.fetch_add(1, Ordering::SeqCst);
+        Ok(vec![])
+    }
+
+    async fn get_monsters(&self, _ctx: Self::Context) -> Response<Vec<String>> {
+        self.handler_calls.fetch_add(1, Ordering::SeqCst);
+        Ok(vec!["Dragon".to_owned()])
+    }
+}
+
+#[tokio::main]
+async fn main() {
+    let addr = std::net::TcpListener::bind("127.0.0.1:0")
+        .and_then(|listener| listener.local_addr())
+        .expect("find free port");
+    let service = std::sync::Arc::new(BestiaryService::default());
+
+    let handler = Handler::Bestiary(service.clone());
+    tokio::spawn(async move {
+        Builder::new()
+            .add("/api", handler)
+            .listen_and_run_forever(&addr)
+            .await
+            .expect("run server")
+    });
+
+    let client = humblegen_rt::reqwest::Client::builder()
+        .redirect(humblegen_rt::reqwest::redirect::Policy::none())
+        .build()
+        .unwrap();
+
+    // wait for the server to come up
+    let url = format!("http://{}/api/beasts", addr);
+    let response = loop {
+        match client.get(&url).send().await {
+            Ok(response) => break response,
+            Err(_) => tokio::time::delay_for(std::time::Duration::from_millis(10)).await,
+        }
+    };
+
+    // the interceptor answers without invoking the handler
+    assert_eq!(response.status(), 302);
+    assert_eq!(response.headers()["Location"], "/api/monsters");
+    assert_eq!(service.handler_calls.load(Ordering::SeqCst), 0);
+
+    // other requests reach the handler
+    let response = client
+        .get(&format!("http://{}/api/monsters", addr))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.json::<Vec<String>>().await.unwrap(), vec!["Dragon"]);
+    assert_eq!(service.handler_calls.load(Ordering::SeqCst), 1);
+}
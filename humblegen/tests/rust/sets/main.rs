@@ -0,0 +1,33 @@
+include!("spec.rs");
+
+fn main() {
+    let inventory = Inventory {
+        tags: vec!["rare", "cursed", "rare"]
+            .into_iter()
+            .map(ToOwned::to_owned)
+            .collect(),
+        slots: vec![3, 1, 2, 1].into_iter().collect(),
+        kinds: vec![Kind::Potion, Kind::Weapon].into_iter().collect(),
+        blocked: None,
+    };
+
+    // elements are serialized once and in ascending order
+    let json = serde_json::to_string(&inventory).unwrap();
+    assert_eq!(
+        json,
+        r#"{"tags":["cursed","rare"],"slots":[1,2,3],"kinds":["Weapon","Potion"],"blocked":null}"#
+    );
+
+    // duplicates in received arrays are dropped
+    let received: Inventory = serde_json::from_str(
+        r#"{"tags":["rare","cursed","rare"],"slots":[2,3,1,1],"kinds":["Potion","Weapon","Potion"],"blocked":[7,7]}"#,
+    )
+    .unwrap();
+    assert_eq!(received.tags, inventory.tags);
+    assert_eq!(received.slots, inventory.slots);
+    assert_eq!(received.kinds, inventory.kinds);
+    assert_eq!(received.blocked.unwrap().len(), 1);
+
+    let roundtripped: Inventory = serde_json::from_str(&json).unwrap();
+    assert_eq!(serde_json::to_string(&roundtripped).unwrap(), json);
+}
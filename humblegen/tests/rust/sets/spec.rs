@@ -0,0 +1,47 @@
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[doc = ""]
+pub struct Inventory {
+    #[doc = ""]
+    pub tags: ::std::collections::BTreeSet<String>,
+    #[doc = ""]
+    pub slots: ::std::collections::BTreeSet<i32>,
+    #[doc = ""]
+    pub kinds: ::std::collections::BTreeSet<Kind>,
+    #[doc = ""]
+    pub blocked: Option<::std::collections::BTreeSet<u8>>,
+}
+#[derive(
+    Debug, Clone, serde :: Deserialize, serde :: Serialize, PartialEq, Eq, PartialOrd, Ord,
+)]
+#[doc = ""]
+pub enum Kind {
+    #[doc = ""]
+    Weapon,
+    #[doc = ""]
+    Armor,
+    #[doc = ""]
+    Potion,
+}
+impl ::std::fmt::Display for Kind {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        f.write_str(match *self {
+            Kind::Weapon => "Weapon",
+            Kind::Armor => "Armor",
+            Kind::Potion => "Potion",
+        })
+    }
+}
+impl ::std::str::FromStr for Kind {
+    type Err = ::humblegen_rt::serialization_helpers::ParseEnumError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Weapon" => Ok(Kind::Weapon),
+            "Armor" => Ok(Kind::Armor),
+            "Potion" => Ok(Kind::Potion),
+            _ => Err(::humblegen_rt::serialization_helpers::ParseEnumError {
+                enum_name: "Kind",
+                value: s.to_owned(),
+            }),
+        }
+    }
+}
@@ -7,7 +7,15 @@ fn main() {
     let customer = Customer {
         name: "somename".to_owned(),
         id: -23,
+        legacy_id: u64::MAX - 1,
+        balance: i64::MIN + 1,
+        login_count: u16::MAX,
+        satisfaction_delta: -1234,
+        utc_offset: -12,
         net_worth: 0.123456,
+        average_rating: 4.2,
+        credit_limit: ::humblegen_rt::rust_decimal::Decimal::from_str("1234567.89")
+            .expect("parse decimal"),
         join_date: ::humblegen_rt::chrono::prelude::Utc::now(),
         birthday: ::humblegen_rt::chrono::prelude::Utc::now()
             .naive_utc()
@@ -32,11 +40,20 @@ fn main() {
 
     let serialized = serde_json::to_string(&customer).expect("serialize customer");
     println!("serialized:\n{}", serialized);
+    assert!(serialized.contains(r#""credit_limit":"1234567.89""#));
+    assert!(serialized.contains(r#""isVIP":true"#));
     let deserialized: Customer = serde_json::from_str(&serialized).expect("deserialize customer");
 
     assert_eq!(customer.name, deserialized.name);
     assert_eq!(customer.id, deserialized.id);
+    assert_eq!(customer.legacy_id, deserialized.legacy_id);
+    assert_eq!(customer.balance, deserialized.balance);
+    assert_eq!(customer.login_count, deserialized.login_count);
+    assert_eq!(customer.satisfaction_delta, deserialized.satisfaction_delta);
+    assert_eq!(customer.utc_offset, deserialized.utc_offset);
     assert_eq!(customer.net_worth, deserialized.net_worth);
+    assert_eq!(customer.average_rating, deserialized.average_rating);
+    assert_eq!(customer.credit_limit, deserialized.credit_limit);
     assert_eq!(customer.is_vip, deserialized.is_vip);
     assert_eq!(customer.aliases, deserialized.aliases);
     assert_eq!(customer.coords, deserialized.coords);
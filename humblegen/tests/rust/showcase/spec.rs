@@ -5,13 +5,28 @@ pub struct Customer {
     pub name: String,
     #[doc = "Customer ID."]
     pub id: i32,
+    #[doc = "Customer number in the legacy system."]
+    pub legacy_id: u64,
+    #[doc = "Account balance in cents."]
+    pub balance: i64,
+    #[doc = "Number of logins."]
+    pub login_count: u16,
+    #[doc = "Satisfaction delta since the last survey."]
+    pub satisfaction_delta: i16,
+    #[doc = "Timezone offset in hours."]
+    pub utc_offset: i8,
     #[doc = "The customer's net worth in dollars."]
     pub net_worth: f64,
+    #[doc = "Average rating given by the customer."]
+    pub average_rating: f32,
+    #[doc = "Maximum credit in dollars."]
+    pub credit_limit: ::humblegen_rt::rust_decimal::Decimal,
     #[doc = "Time the customer joined the site."]
     pub join_date: ::humblegen_rt::chrono::DateTime<::humblegen_rt::chrono::prelude::Utc>,
     #[doc = "Date of birth."]
     pub birthday: ::humblegen_rt::chrono::NaiveDate,
     #[doc = "Is the customer a VIP?"]
+    #[serde(rename = "isVIP")]
     pub is_vip: bool,
     #[doc = "Favorite color."]
     pub favorite_color: Color,
@@ -0,0 +1,44 @@
+include!("spec.rs");
+
+fn main() {
+    // simple enums are displayed as, and parsed from, their variant names
+    let blue = Color::Blue.to_string();
+    assert_eq!(blue, "Blue");
+    assert!(matches!(blue.parse::<Color>(), Ok(Color::Blue)));
+    assert!(matches!("Red".parse::<Color>(), Ok(Color::Red)));
+
+    // parsing is case-sensitive
+    let err = "blue".parse::<Color>().unwrap_err();
+    assert_eq!(err.enum_name, "Color");
+    assert_eq!(err.value, "blue");
+    assert_eq!(err.to_string(), "unknown variant `blue` of enum `Color`");
+
+    let config = Config {
+        color: Color::Green,
+        shape: Shape::Circle(1.0),
+    };
+    assert_eq!(config.color.to_string(), "Green");
+    assert!(matches!(config.shape, Shape::Circle(r) if r == 1.0));
+
+    // enums with data carrying variants have no string conversions
+    assert!(!DisplayProbe::<Shape>(std::marker::PhantomData).has_display());
+    assert!(DisplayProbe::<Color>(std::marker::PhantomData).has_display());
+}
+
+/// Tells whether `T` implements `Display`: the inherent method takes precedence over the trait
+/// method, but only applies if `T: Display`.
+struct DisplayProbe<T>(std::marker::PhantomData<T>);
+
+impl<T: std::fmt::Display> DisplayProbe<T> {
+    fn has_display(&self) -> bool {
+        true
+    }
+}
+
+trait NoDisplay {
+    fn has_display(&self) -> bool {
+        false
+    }
+}
+
+impl<T> NoDisplay for DisplayProbe<T> {}
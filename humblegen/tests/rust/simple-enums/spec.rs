@@ -0,0 +1,49 @@
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[doc = "A color."]
+pub enum Color {
+    #[doc = "Pure red."]
+    Red,
+    #[doc = "Pure blue."]
+    Blue,
+    #[doc = "Pure green."]
+    Green,
+}
+impl ::std::fmt::Display for Color {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        f.write_str(match *self {
+            Color::Red => "Red",
+            Color::Blue => "Blue",
+            Color::Green => "Green",
+        })
+    }
+}
+impl ::std::str::FromStr for Color {
+    type Err = ::humblegen_rt::serialization_helpers::ParseEnumError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Red" => Ok(Color::Red),
+            "Blue" => Ok(Color::Blue),
+            "Green" => Ok(Color::Green),
+            _ => Err(::humblegen_rt::serialization_helpers::ParseEnumError {
+                enum_name: "Color",
+                value: s.to_owned(),
+            }),
+        }
+    }
+}
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[doc = "A shape, which has no string conversions since some of its variants carry data."]
+pub enum Shape {
+    #[doc = ""]
+    Point,
+    #[doc = ""]
+    Circle(f64),
+}
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[doc = ""]
+pub struct Config {
+    #[doc = ""]
+    pub color: Color,
+    #[doc = ""]
+    pub shape: Shape,
+}
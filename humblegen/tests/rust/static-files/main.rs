@@ -0,0 +1,85 @@
+include!("spec.rs");
+
+use humblegen_rt::hyper::header::CONTENT_TYPE;
+use humblegen_rt::hyper::service::Service as _;
+use humblegen_rt::hyper::{Body, Method, Request, StatusCode};
+use humblegen_rt::server::HttpService;
+
+struct StatusService;
+
+#[humblegen_rt::async_trait(Sync)]
+impl Status for StatusService {
+    type Context = ();
+
+    async fn get_version(&self, _ctx: Self::Context) -> Response<String> {
+        Ok("1.2.3".to_owned())
+    }
+}
+
+async fn get(service: &mut HttpService, path: &str) -> humblegen_rt::hyper::Response<Body> {
+    let request = Request::builder()
+        .method(Method::GET)
+        .uri(path)
+        .body(Body::empty())
+        .unwrap();
+    service.call(request).await.unwrap()
+}
+
+async fn body(response: humblegen_rt::hyper::Response<Body>) -> Vec<u8> {
+    humblegen_rt::hyper::body::to_bytes(response.into_body())
+        .await
+        .unwrap()
+        .to_vec()
+}
+
+#[tokio::main]
+async fn main() {
+    let root = tempfile::tempdir().expect("create temp dir");
+    let public = root.path().join("public");
+    std::fs::create_dir_all(public.join("js")).unwrap();
+    std::fs::write(public.join("index.html"), "<html></html>").unwrap();
+    std::fs::write(public.join("js").join("main.js"), "Elm.Main.init()").unwrap();
+    std::fs::write(root.path().join("secret.txt"), "secret").unwrap();
+
+    let mut service = Builder::new()
+        .add("/api", Handler::Status(Arc::new(StatusService)))
+        .add("/app/api", Handler::Status(Arc::new(StatusService)))
+        .serve_static("/app", &public)
+        .into_service()
+        .expect("valid service configuration");
+
+    // directories are answered with their `index.html`
+    for path in &["/app", "/app/"] {
+        let response = get(&mut service, path).await;
+        assert_eq!(response.status(), StatusCode::OK, "{}", path);
+        assert_eq!(response.headers()[CONTENT_TYPE], "text/html");
+        assert_eq!(body(response).await, b"<html></html>");
+    }
+
+    let response = get(&mut service, "/app/js/main.js").await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()[CONTENT_TYPE], "text/javascript");
+    assert_eq!(body(response).await, b"Elm.Main.init()");
+
+    // requests outside of the prefix are dispatched to the services
+    let response = get(&mut service, "/api/version").await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(body(response).await, b"\"1.2.3\"");
+
+    // missing files below the prefix are not found, even if a service matches them
+    for path in &["/app/js/missing.js", "/app/api/version"] {
+        let response = get(&mut service, path).await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND, "{}", path);
+        assert!(body(response).await.is_empty(), "{}", path);
+    }
+
+    // files outside of the directory are not served
+    for path in &[
+        "/app/../secret.txt",
+        "/app/js/../../secret.txt",
+        "/app/..%2Fsecret.txt",
+    ] {
+        let response = get(&mut service, path).await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND, "{}", path);
+    }
+}
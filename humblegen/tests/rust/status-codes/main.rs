@@ -0,0 +1,238 @@
+include!("spec.rs");
+
+use humblegen_rt::service_protocol::{ErrorResponseKind, RuntimeError};
+
+struct ErrorsService;
+
+#[humblegen_rt::async_trait(Sync)]
+impl Errors for ErrorsService {
+    type Context = ();
+
+    async fn get_authentication(&self, _ctx: Self::Context) -> Response<String> {
+        Err(ServiceError::Authentication)
+    }
+
+    async fn get_authorization(&self, _ctx: Self::Context) -> Response<String> {
+        Err(ServiceError::Authorization)
+    }
+
+    async fn get_internal(&self, _ctx: Self::Context) -> Response<String> {
+        Err(ServiceError::Internal(Box::new(std::io::Error::new(
+            std::io::ErrorKind::Other,
+            "database down",
+        ))))
+    }
+
+    async fn get_ratelimited(&self, _ctx: Self::Context) -> Response<String> {
+        Err(ServiceError::Custom {
+            status: 429,
+            code: "rate_limited".to_owned(),
+            message: "try again in a minute".to_owned(),
+        })
+    }
+
+    async fn get_teapot(&self, _ctx: Self::Context) -> Response<String> {
+        // not an error status code
+        Err(ServiceError::Custom {
+            status: 200,
+            code: "teapot".to_owned(),
+            message: "I'm a teapot".to_owned(),
+        })
+    }
+
+    async fn get_items_id(
+        &self,
+        _ctx: Self::Context,
+        _query: Option<Filter>,
+        id: u32,
+    ) -> Response<String> {
+        Ok(id.to_string())
+    }
+
+    async fn post_items(&self, _ctx: Self::Context, post_body: u32) -> Response<String> {
+        Ok(post_body.to_string())
+    }
+
+    async fn get_header(&self, _ctx: Self::Context, x_count: u32) -> Response<String> {
+        Ok(x_count.to_string())
+    }
+
+    async fn post_monsters(&self, _ctx: Self::Context, post_body: String) -> Response<u32> {
+        if post_body.is_empty() {
+            return Err(ServiceError::Authorization);
+        }
+        Ok(42)
+    }
+
+    async fn put_thumbnail(
+        &self,
+        _ctx: Self::Context,
+        _post_body: String,
+    ) -> Response<RawResponse> {
+        Ok(RawResponse {
+            content_type: "image/png".to_owned(),
+            body: vec![0x89, b'P', b'N', b'G'],
+        })
+    }
+}
+
+/// Sends `request` and returns the status code and the kind of the error response.
+async fn error_of(request: humblegen_rt::reqwest::RequestBuilder) -> (u16, ErrorResponseKind) {
+    let response = request.send().await.unwrap();
+    let status = response.status().as_u16();
+    let error: ErrorResponse = response.json().await.unwrap();
+    assert_eq!(error.code, status, "error response repeats the status code");
+    (status, error.kind)
+}
+
+#[tokio::main]
+async fn main() {
+    let addr = std::net::TcpListener::bind("127.0.0.1:0")
+        .and_then(|listener| listener.local_addr())
+        .expect("find free port");
+
+    tokio::spawn(async move {
+        Builder::new()
+            .add("/api", Handler::Errors(std::sync::Arc::new(ErrorsService)))
+            .listen_and_run_forever(&addr)
+            .await
+            .expect("run server")
+    });
+
+    let client = humblegen_rt::reqwest::Client::new();
+    let url = |path: &str| format!("http://{}{}", addr, path);
+
+    // wait for the server to come up
+    let response = loop {
+        match client.get(&url("/api/items/1?limit=2")).send().await {
+            Ok(response) => break response,
+            Err(_) => tokio::time::delay_for(std::time::Duration::from_millis(10)).await,
+        }
+    };
+    assert_eq!(response.status(), 200);
+
+    // declared success status codes
+    let response = client
+        .post(&url("/api/monsters"))
+        .body("\"Goblin\"")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 201);
+    assert_eq!(response.json::<u32>().await.unwrap(), 42);
+    let response = client
+        .put(&url("/api/thumbnail"))
+        .body("\"Goblin\"")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 202);
+    assert_eq!(response.headers()["content-type"], "image/png");
+    // errors keep their own status code
+    match error_of(client.post(&url("/api/monsters")).body("\"\"")).await {
+        (
+            403,
+            ErrorResponseKind::Service(humblegen_rt::service_protocol::ServiceError::Authorization),
+        ) => {}
+        other => panic!("unexpected {:?}", other),
+    }
+
+    // service errors
+    match error_of(client.get(&url("/api/authentication"))).await {
+        (
+            401,
+            ErrorResponseKind::Service(
+                humblegen_rt::service_protocol::ServiceError::Authentication,
+            ),
+        ) => {}
+        other => panic!("unexpected {:?}", other),
+    }
+    match error_of(client.get(&url("/api/authorization"))).await {
+        (
+            403,
+            ErrorResponseKind::Service(humblegen_rt::service_protocol::ServiceError::Authorization),
+        ) => {}
+        other => panic!("unexpected {:?}", other),
+    }
+    match error_of(client.get(&url("/api/internal"))).await {
+        (
+            500,
+            ErrorResponseKind::Service(humblegen_rt::service_protocol::ServiceError::Internal(msg)),
+        ) => {
+            assert_eq!(msg, "database down")
+        }
+        other => panic!("unexpected {:?}", other),
+    }
+
+    // custom service errors carry their own status code
+    let response = client.get(&url("/api/ratelimited")).send().await.unwrap();
+    assert_eq!(response.status(), 429);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(
+        body,
+        serde_json::json!({
+            "code": 429,
+            "kind": {
+                "Service": {
+                    "Custom": {
+                        "status": 429,
+                        "code": "rate_limited",
+                        "message": "try again in a minute",
+                    }
+                }
+            }
+        })
+    );
+    match error_of(client.get(&url("/api/teapot"))).await {
+        (
+            500,
+            ErrorResponseKind::Service(humblegen_rt::service_protocol::ServiceError::Custom {
+                status,
+                code,
+                ..
+            }),
+        ) => {
+            assert_eq!(status, 200);
+            assert_eq!(code, "teapot");
+        }
+        other => panic!("unexpected {:?}", other),
+    }
+
+    // routing errors
+    match error_of(client.get(&url("/other/items/1"))).await {
+        (404, ErrorResponseKind::Runtime(RuntimeError::NoServiceMounted)) => {}
+        other => panic!("unexpected {:?}", other),
+    }
+    match error_of(client.get(&url("/api/unknown"))).await {
+        (404, ErrorResponseKind::Runtime(RuntimeError::NoRouteMountedInService { service })) => {
+            assert_eq!(service, "/api")
+        }
+        other => panic!("unexpected {:?}", other),
+    }
+
+    // deserialization errors
+    match error_of(client.get(&url("/api/items/abc"))).await {
+        (400, ErrorResponseKind::Runtime(RuntimeError::RouteParamInvalid { param_name, .. })) => {
+            assert_eq!(param_name, "id")
+        }
+        other => panic!("unexpected {:?}", other),
+    }
+    match error_of(client.get(&url("/api/items/1?limit=many"))).await {
+        (400, ErrorResponseKind::Runtime(RuntimeError::QueryInvalid(_))) => {}
+        other => panic!("unexpected {:?}", other),
+    }
+    match error_of(client.post(&url("/api/items")).body("\"not a number\"")).await {
+        (400, ErrorResponseKind::Runtime(RuntimeError::PostBodyInvalid { fields, .. })) => {
+            assert!(fields.is_empty(), "{:?}", fields)
+        }
+        other => panic!("unexpected {:?}", other),
+    }
+    match error_of(client.get(&url("/api/header"))).await {
+        (400, ErrorResponseKind::Runtime(RuntimeError::HeaderMissing { .. })) => {}
+        other => panic!("unexpected {:?}", other),
+    }
+    match error_of(client.get(&url("/api/header")).header("X-Count", "many")).await {
+        (400, ErrorResponseKind::Runtime(RuntimeError::HeaderInvalid { .. })) => {}
+        other => panic!("unexpected {:?}", other),
+    }
+}
@@ -0,0 +1,129 @@
+mod server {
+    include!("spec.rs");
+}
+mod client {
+    include!("client.rs");
+}
+
+use humblegen_rt::futures::{channel::mpsc, StreamExt};
+use humblegen_rt::service_protocol::ServiceError as ProtocolServiceError;
+use std::sync::{Arc, Mutex};
+
+fn monster(id: i32) -> server::Monster {
+    server::Monster {
+        id,
+        name: format!("Monster {}", id),
+    }
+}
+
+struct MonsterService {
+    spawns: Mutex<Option<mpsc::UnboundedReceiver<server::Monster>>>,
+}
+
+#[humblegen_rt::async_trait(Sync)]
+impl server::Monsters for MonsterService {
+    type Context = ();
+
+    async fn get_monsters(
+        &self,
+        _ctx: Self::Context,
+        count: u32,
+    ) -> server::Response<server::StreamResponse<server::Monster>> {
+        if count > 1000 {
+            return Err(server::ServiceError::Custom {
+                status: 400,
+                code: "too_many".to_owned(),
+                message: "at most 1000 monsters can be streamed".to_owned(),
+            });
+        }
+        Ok(humblegen_rt::futures::stream::iter((0..count as i32).map(monster)).boxed())
+    }
+
+    async fn get_spawns(
+        &self,
+        _ctx: Self::Context,
+    ) -> server::Response<server::StreamResponse<server::Monster>> {
+        let spawns = self
+            .spawns
+            .lock()
+            .unwrap()
+            .take()
+            .expect("spawns are streamed once");
+        Ok(spawns.boxed())
+    }
+}
+
+#[tokio::main]
+async fn main() {
+    let addr = std::net::TcpListener::bind("127.0.0.1:0")
+        .and_then(|listener| listener.local_addr())
+        .expect("find free port");
+
+    let (spawn, spawns) = mpsc::unbounded();
+    let service = MonsterService {
+        spawns: Mutex::new(Some(spawns)),
+    };
+    tokio::spawn(async move {
+        server::Builder::new()
+            .add("/api", server::Handler::Monsters(Arc::new(service)))
+            .listen_and_run_forever(&addr)
+            .await
+            .expect("run server")
+    });
+
+    let base_url = format!("http://{}/api", addr);
+    let client = client::MonstersClient::new(&base_url).unwrap();
+
+    // wait for the server to come up
+    let monsters = loop {
+        match client.get_monsters(&3).await {
+            Ok(monsters) => break monsters,
+            Err(client::ClientError::Transport(_)) => {
+                tokio::time::delay_for(std::time::Duration::from_millis(10)).await
+            }
+            Err(e) => panic!("unexpected error {:?}", e),
+        }
+    };
+    let monsters: Vec<client::Monster> = monsters.map(Result::unwrap).collect().await;
+    assert_eq!(
+        monsters
+            .iter()
+            .map(|m| (m.id, m.name.as_str()))
+            .collect::<Vec<_>>(),
+        vec![(0, "Monster 0"), (1, "Monster 1"), (2, "Monster 2")]
+    );
+
+    // the response is newline-delimited JSON
+    let response = humblegen_rt::reqwest::get(&format!("{}/monsters?count=2", base_url))
+        .await
+        .unwrap();
+    assert_eq!(response.headers()["content-type"], "application/x-ndjson");
+    assert_eq!(
+        response.text().await.unwrap(),
+        "{\"id\":0,\"name\":\"Monster 0\"}\n{\"id\":1,\"name\":\"Monster 1\"}\n"
+    );
+
+    // an empty stream has no items
+    let monsters = client.get_monsters(&0).await.unwrap();
+    assert_eq!(monsters.count().await, 0);
+
+    // errors returned before streaming are sent as error responses
+    match client.get_monsters(&1001).await {
+        Err(client::ClientError::Service(ProtocolServiceError::Custom {
+            status, code, ..
+        })) => {
+            assert_eq!(status, 400);
+            assert_eq!(code, "too_many");
+        }
+        other => panic!("expected a custom error, got {:?}", other.map(|_| ())),
+    }
+
+    // items are received while the handler produces them
+    let mut spawns = client.get_spawns().await.unwrap();
+    for id in 0..3 {
+        spawn.unbounded_send(monster(id)).unwrap();
+        assert_eq!(spawns.next().await.unwrap().unwrap().id, id);
+    }
+    drop(spawn);
+    assert!(spawns.next().await.is_none());
+}
@@ -0,0 +1,81 @@
+include!("spec.rs");
+
+use humblegen_rt::service_protocol::{ErrorResponseKind, RuntimeError};
+
+struct SearchService;
+
+#[humblegen_rt::async_trait(Sync)]
+impl Search for SearchService {
+    type Context = ();
+
+    async fn get_search(
+        &self,
+        _ctx: Self::Context,
+        query: Option<SearchQuery>,
+    ) -> Response<String> {
+        Ok(query.map(|q| q.name).unwrap_or_default())
+    }
+
+    async fn get_greet(&self, _ctx: Self::Context, name: String) -> Response<String> {
+        Ok(name)
+    }
+}
+
+/// Start a server on a free port and return its address.
+fn start_server(strict_query: bool) -> std::net::SocketAddr {
+    let addr = std::net::TcpListener::bind("127.0.0.1:0")
+        .and_then(|listener| listener.local_addr())
+        .expect("find free port");
+
+    tokio::spawn(async move {
+        Builder::new()
+            .add("/api", Handler::Search(std::sync::Arc::new(SearchService)))
+            .strict_query(strict_query)
+            .listen_and_run_forever(&addr)
+            .await
+            .expect("run server")
+    });
+    addr
+}
+
+/// GET `path_and_query` from the server at `addr`, waiting for the server to come up.
+async fn get(addr: std::net::SocketAddr, path_and_query: &str) -> humblegen_rt::reqwest::Response {
+    let url = format!("http://{}/api{}", addr, path_and_query);
+    loop {
+        match humblegen_rt::reqwest::get(&url).await {
+            Ok(response) => break response,
+            Err(_) => tokio::time::delay_for(std::time::Duration::from_millis(10)).await,
+        }
+    }
+}
+
+async fn assert_accepted(addr: std::net::SocketAddr, path_and_query: &str) {
+    let response = get(addr, path_and_query).await;
+    assert_eq!(response.status(), 200, "{} rejected", path_and_query);
+    assert_eq!(response.json::<String>().await.unwrap(), "foo");
+}
+
+async fn assert_unknown_key(addr: std::net::SocketAddr, path_and_query: &str) {
+    let response = get(addr, path_and_query).await;
+    assert_eq!(response.status(), 400, "{} accepted", path_and_query);
+    match response.json::<ErrorResponse>().await.unwrap().kind {
+        ErrorResponseKind::Runtime(RuntimeError::QueryInvalid(message)) => {
+            assert_eq!(message, "unknown query parameter `bogus`")
+        }
+        other => panic!("expected QueryInvalid, got {:?}", other),
+    }
+}
+
+#[tokio::main]
+async fn main() {
+    let lenient = start_server(false);
+    assert_accepted(lenient, "/search?name=foo&bogus=1").await;
+    assert_accepted(lenient, "/greet?name=foo&bogus=1").await;
+
+    let strict = start_server(true);
+    assert_accepted(strict, "/search?name=foo").await;
+    assert_accepted(strict, "/search?name=foo&limit=3").await;
+    assert_accepted(strict, "/greet?name=foo").await;
+    assert_unknown_key(strict, "/search?name=foo&bogus=1").await;
+    assert_unknown_key(strict, "/greet?name=foo&bogus=1").await;
+}
@@ -0,0 +1,41 @@
+include!("spec.rs");
+
+use serde_json::json;
+
+fn main() {
+    // internally tagged: struct variant fields are siblings of the tag
+    let too_strong = RecruitError::TooStrong { power: 9001 };
+    let value = serde_json::to_value(&too_strong).unwrap();
+    assert_eq!(value, json!({ "type": "TooStrong", "power": 9001 }));
+    assert_eq!(
+        serde_json::to_value(&RecruitError::Asleep).unwrap(),
+        json!({ "type": "Asleep" })
+    );
+    assert!(matches!(
+        serde_json::from_value(value).unwrap(),
+        RecruitError::TooStrong { power: 9001 }
+    ));
+
+    // adjacently tagged: the payload is the value of the content field
+    let cases = vec![
+        (Event::Started, json!({ "kind": "Started" })),
+        (
+            Event::Moved(1, -2),
+            json!({ "kind": "Moved", "data": [1, -2] }),
+        ),
+        (
+            Event::Renamed("Bob".to_owned()),
+            json!({ "kind": "Renamed", "data": "Bob" }),
+        ),
+        (
+            Event::Attacked { damage: 7 },
+            json!({ "kind": "Attacked", "data": { "damage": 7 } }),
+        ),
+    ];
+    for (event, expected) in cases {
+        let value = serde_json::to_value(&event).unwrap();
+        assert_eq!(value, expected);
+        let decoded: Event = serde_json::from_value(value).unwrap();
+        assert_eq!(format!("{:?}", decoded), format!("{:?}", event));
+    }
+}
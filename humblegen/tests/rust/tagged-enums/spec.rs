@@ -0,0 +1,28 @@
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[doc = "Why a monster could not be recruited, tagged internally."]
+#[serde(tag = "type")]
+pub enum RecruitError {
+    #[doc = "The monster is too strong to be recruited."]
+    TooStrong {
+        #[doc = ""]
+        power: i32,
+    },
+    #[doc = "The monster is asleep."]
+    Asleep,
+}
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[doc = "An event, tagged adjacently."]
+#[serde(tag = "kind", content = "data")]
+pub enum Event {
+    #[doc = ""]
+    Started,
+    #[doc = ""]
+    Moved(i32, i32),
+    #[doc = ""]
+    Renamed(String),
+    #[doc = ""]
+    Attacked {
+        #[doc = ""]
+        damage: u32,
+    },
+}
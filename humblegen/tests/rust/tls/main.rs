@@ -0,0 +1,69 @@
+include!("spec.rs");
+
+struct PingerService;
+
+#[humblegen_rt::async_trait(Sync)]
+impl Pinger for PingerService {
+    type Context = ();
+
+    async fn get_ping(&self, _ctx: Self::Context) -> Response<String> {
+        Ok("pong".to_owned())
+    }
+}
+
+/// Self-signed certificate for `localhost`, generated using
+/// `openssl req -x509 -newkey rsa:2048 -nodes -keyout key.pem -out cert.pem -days 36500 -subj "/CN=localhost" -addext "subjectAltName=DNS:localhost" -addext "basicConstraints=critical,CA:FALSE"`
+const CERT_PEM: &str = include_str!("cert.pem");
+const KEY_PEM: &str = include_str!("key.pem");
+
+#[tokio::main]
+async fn main() {
+    let addr = std::net::TcpListener::bind("127.0.0.1:0")
+        .and_then(|listener| listener.local_addr())
+        .expect("find free port");
+
+    let pem_dir = std::env::temp_dir().join(format!("humblegen-tls-test-{}", addr.port()));
+    std::fs::create_dir_all(&pem_dir).unwrap();
+    std::fs::write(pem_dir.join("cert.pem"), CERT_PEM).unwrap();
+    std::fs::write(pem_dir.join("key.pem"), KEY_PEM).unwrap();
+    let tls_config = humblegen_rt::server::TlsConfig::from_pem_files(
+        pem_dir.join("cert.pem"),
+        pem_dir.join("key.pem"),
+    );
+
+    tokio::spawn(async move {
+        Builder::new()
+            .add("/api", Handler::Pinger(std::sync::Arc::new(PingerService)))
+            .listen_and_run_forever_tls(&addr, tls_config)
+            .await
+            .expect("run server")
+    });
+
+    let client = humblegen_rt::reqwest::Client::builder()
+        .add_root_certificate(
+            humblegen_rt::reqwest::Certificate::from_pem(CERT_PEM.as_bytes()).unwrap(),
+        )
+        .build()
+        .unwrap();
+    let url = format!("https://localhost:{}/api/ping", addr.port());
+
+    // wait for the server to come up
+    let response = loop {
+        match client.get(&url).send().await {
+            Ok(response) => break response,
+            Err(e) if e.is_connect() => {
+                tokio::time::delay_for(std::time::Duration::from_millis(10)).await
+            }
+            Err(e) => panic!("unexpected error: {}", e),
+        }
+    };
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.json::<String>().await.unwrap(), "pong");
+
+    // plain HTTP is not served
+    let plain =
+        humblegen_rt::reqwest::get(&format!("http://localhost:{}/api/ping", addr.port())).await;
+    assert!(plain.is_err(), "expected an error, got {:?}", plain);
+
+    std::fs::remove_dir_all(&pem_dir).unwrap();
+}
@@ -0,0 +1,84 @@
+include!("spec.rs");
+
+use humblegen_rt::hyper::service::Service as _;
+use humblegen_rt::hyper::{Body, Request, StatusCode};
+use humblegen_rt::server::HttpService;
+
+struct MonstersService;
+
+#[humblegen_rt::async_trait(Sync)]
+impl Monsters for MonstersService {
+    type Context = ();
+
+    async fn get_monsters(&self, _ctx: Self::Context) -> Response<String> {
+        Ok("all".to_owned())
+    }
+
+    async fn get_monsters_id(&self, _ctx: Self::Context, id: u32) -> Response<u32> {
+        Ok(id)
+    }
+
+    async fn get_files_path(&self, _ctx: Self::Context, path: String) -> Response<String> {
+        Ok(path)
+    }
+}
+
+fn service(builder: Builder) -> HttpService {
+    builder
+        .add("/api", Handler::Monsters(Arc::new(MonstersService)))
+        .into_service()
+        .expect("valid service configuration")
+}
+
+/// Sends a `GET` request for `path` and returns the status and body of the response.
+async fn get(service: &mut HttpService, path: &str) -> (StatusCode, String) {
+    let request = Request::get(path).body(Body::empty()).unwrap();
+    let response = service.call(request).await.unwrap();
+    let status = response.status();
+    let body = humblegen_rt::hyper::body::to_bytes(response.into_body())
+        .await
+        .unwrap();
+    (status, String::from_utf8(body.to_vec()).unwrap())
+}
+
+#[tokio::main]
+async fn main() {
+    // paths must match exactly by default
+    let mut strict = service(Builder::new());
+    assert_eq!(
+        get(&mut strict, "/api/monsters").await,
+        (StatusCode::OK, "\"all\"".to_owned())
+    );
+    assert_eq!(
+        get(&mut strict, "/api/monsters/").await.0,
+        StatusCode::NOT_FOUND
+    );
+    assert_eq!(
+        get(&mut strict, "/api/monsters/7/").await.0,
+        StatusCode::NOT_FOUND
+    );
+
+    let mut tolerant = service(Builder::new().ignore_trailing_slash(true));
+    assert_eq!(
+        get(&mut tolerant, "/api/monsters").await,
+        (StatusCode::OK, "\"all\"".to_owned())
+    );
+    assert_eq!(
+        get(&mut tolerant, "/api/monsters/").await,
+        (StatusCode::OK, "\"all\"".to_owned())
+    );
+    assert_eq!(
+        get(&mut tolerant, "/api/monsters/7/").await,
+        (StatusCode::OK, "7".to_owned())
+    );
+    // only a single trailing slash is ignored
+    assert_eq!(
+        get(&mut tolerant, "/api/monsters//").await.0,
+        StatusCode::NOT_FOUND
+    );
+    // paths that match as they are keep their trailing slash
+    assert_eq!(
+        get(&mut tolerant, "/api/files/docs/").await,
+        (StatusCode::OK, "\"docs/\"".to_owned())
+    );
+}
@@ -0,0 +1,54 @@
+include!("spec.rs");
+
+use serde_json::json;
+
+fn main() {
+    // single-element tuple variants are arrays, like the content of other tuple variants;
+    // newtype variants wrapping a tuple are represented like the tuple
+    let cases = vec![
+        (Shape::Point, json!("Point")),
+        (Shape::Circle((1.5,)), json!({ "Circle": [1.5] })),
+        (Shape::Rect(1.0, 2.0), json!({ "Rect": [1.0, 2.0] })),
+        (Shape::Center((3, -4)), json!({ "Center": [3, -4] })),
+        (
+            Shape::Label(("home".to_owned(),)),
+            json!({ "Label": ["home"] }),
+        ),
+    ];
+    for (shape, expected) in cases {
+        let value = serde_json::to_value(&shape).unwrap();
+        assert_eq!(value, expected);
+        let decoded: Shape = serde_json::from_value(value).unwrap();
+        assert_eq!(format!("{:?}", decoded), format!("{:?}", shape));
+    }
+
+    let cases = vec![
+        (
+            TaggedShape::Circle((1.5,)),
+            json!({ "kind": "Circle", "data": [1.5] }),
+        ),
+        (
+            TaggedShape::Center((3, -4)),
+            json!({ "kind": "Center", "data": [3, -4] }),
+        ),
+    ];
+    for (shape, expected) in cases {
+        let value = serde_json::to_value(&shape).unwrap();
+        assert_eq!(value, expected);
+        let decoded: TaggedShape = serde_json::from_value(value).unwrap();
+        assert_eq!(format!("{:?}", decoded), format!("{:?}", shape));
+    }
+
+    // single-element tuple fields are arrays as well
+    let drawing = Drawing {
+        shapes: vec![Shape::Circle((2.0,))],
+        scale: (0.5,),
+    };
+    let value = serde_json::to_value(&drawing).unwrap();
+    assert_eq!(
+        value,
+        json!({ "shapes": [{ "Circle": [2.0] }], "scale": [0.5] })
+    );
+    let decoded: Drawing = serde_json::from_value(value).unwrap();
+    assert_eq!(format!("{:?}", decoded), format!("{:?}", drawing));
+}
@@ -0,0 +1,31 @@
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[doc = "A shape, with tuple variants of one and several elements and newtype variants wrapping\ntuples."]
+pub enum Shape {
+    #[doc = ""]
+    Point,
+    #[doc = ""]
+    Circle((f64,)),
+    #[doc = ""]
+    Rect(f64, f64),
+    #[doc = ""]
+    Center((i32, i32)),
+    #[doc = ""]
+    Label((String,)),
+}
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[doc = "The same variants, tagged adjacently."]
+#[serde(tag = "kind", content = "data")]
+pub enum TaggedShape {
+    #[doc = ""]
+    Circle((f64,)),
+    #[doc = ""]
+    Center((i32, i32)),
+}
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[doc = ""]
+pub struct Drawing {
+    #[doc = ""]
+    pub shapes: Vec<Shape>,
+    #[doc = ""]
+    pub scale: (f64,),
+}
@@ -0,0 +1,40 @@
+include!("spec.rs");
+
+fn main() {
+    let link = Link {
+        href: "https://example.com/monsters?page=2".parse().unwrap(),
+        mirrors: vec!["ftp://mirror.example.com/monsters".parse().unwrap()],
+        icon: None,
+    };
+
+    // URLs are serialized as strings
+    let json = serde_json::to_string(&link).unwrap();
+    assert_eq!(
+        json,
+        r#"{"href":"https://example.com/monsters?page=2","mirrors":["ftp://mirror.example.com/monsters"],"icon":null}"#
+    );
+
+    let roundtripped: Link = serde_json::from_str(&json).unwrap();
+    assert_eq!(roundtripped.href, link.href);
+    assert_eq!(roundtripped.mirrors, link.mirrors);
+    assert_eq!(roundtripped.icon, None);
+
+    // invalid URLs are rejected
+    let err = serde_json::from_str::<Link>(r#"{"href":"not a url","mirrors":[],"icon":null}"#)
+        .err()
+        .expect("invalid URL must not deserialize");
+    assert!(err.is_data());
+    assert!(
+        err.to_string()
+            .starts_with("relative URL without a base: \"not a url\""),
+        "{}",
+        err
+    );
+
+    let err = serde_json::from_str::<Link>(
+        r#"{"href":"https://example.com","mirrors":[],"icon":"http://[::1"}"#,
+    )
+    .err()
+    .expect("invalid URL must not deserialize");
+    assert!(err.to_string().contains("http://[::1"), "{}", err);
+}
@@ -0,0 +1,10 @@
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[doc = ""]
+pub struct Link {
+    #[doc = ""]
+    pub href: ::humblegen_rt::url::Url,
+    #[doc = ""]
+    pub mirrors: Vec<::humblegen_rt::url::Url>,
+    #[doc = ""]
+    pub icon: Option<::humblegen_rt::url::Url>,
+}
@@ -0,0 +1,158 @@
+include!("spec.rs");
+
+use humblegen_rt::service_protocol::{ErrorResponseKind, RuntimeError};
+use humblegen_rt::uuid::Uuid;
+
+const OWNER: &str = "67e55044-10b1-426f-9247-bb680e5fe0c8";
+const FRIEND: &str = "db05098d-ecca-478c-8447-cb0a822f9a56";
+
+struct MonstersService;
+
+#[humblegen_rt::async_trait(Sync)]
+impl Monsters for MonstersService {
+    type Context = ();
+
+    async fn get_monsters_id(&self, _ctx: Self::Context, id: Uuid) -> Response<Uuid> {
+        Ok(id)
+    }
+
+    async fn get_search(
+        &self,
+        _ctx: Self::Context,
+        owner: Uuid,
+        friend: Option<Uuid>,
+    ) -> Response<String> {
+        Ok(format!("{} {:?}", owner, friend.map(|f| f.to_string())))
+    }
+
+    async fn get_filter(
+        &self,
+        _ctx: Self::Context,
+        query: Option<MonsterFilter>,
+    ) -> Response<String> {
+        Ok(format!(
+            "{:?}",
+            query.and_then(|q| q.owner).map(|o| o.to_string())
+        ))
+    }
+
+    async fn get_header(&self, _ctx: Self::Context, x_owner: Uuid) -> Response<Uuid> {
+        Ok(x_owner)
+    }
+}
+
+/// Sends `request` and returns the error message of a 400 response.
+async fn invalid(request: humblegen_rt::reqwest::RequestBuilder) -> RuntimeError {
+    let response = request.send().await.unwrap();
+    assert_eq!(response.status(), 400);
+    match response.json::<ErrorResponse>().await.unwrap().kind {
+        ErrorResponseKind::Runtime(e) => e,
+        other => panic!("unexpected {:?}", other),
+    }
+}
+
+#[tokio::main]
+async fn main() {
+    let addr = std::net::TcpListener::bind("127.0.0.1:0")
+        .and_then(|listener| listener.local_addr())
+        .expect("find free port");
+
+    tokio::spawn(async move {
+        Builder::new()
+            .add(
+                "/api",
+                Handler::Monsters(std::sync::Arc::new(MonstersService)),
+            )
+            .listen_and_run_forever(&addr)
+            .await
+            .expect("run server")
+    });
+
+    let client = humblegen_rt::reqwest::Client::new();
+    let url = |path: &str| format!("http://{}{}", addr, path);
+
+    // wait for the server to come up
+    let response = loop {
+        match client
+            .get(&url(&format!("/api/monsters/{}", OWNER)))
+            .send()
+            .await
+        {
+            Ok(response) => break response,
+            Err(_) => tokio::time::delay_for(std::time::Duration::from_millis(10)).await,
+        }
+    };
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.json::<Uuid>().await.unwrap().to_string(), OWNER);
+
+    // the simple format without hyphens is accepted as well
+    let simple = OWNER.replace('-', "");
+    let response = client
+        .get(&url(&format!("/api/monsters/{}", simple)))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.json::<Uuid>().await.unwrap().to_string(), OWNER);
+
+    let response = client
+        .get(&url(&format!(
+            "/api/search?owner={}&friend={}",
+            OWNER, FRIEND
+        )))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(
+        response.json::<String>().await.unwrap(),
+        format!("{} Some(\"{}\")", OWNER, FRIEND)
+    );
+    let response = client
+        .get(&url(&format!("/api/filter?owner={}", OWNER)))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(
+        response.json::<String>().await.unwrap(),
+        format!("Some(\"{}\")", OWNER)
+    );
+    let response = client
+        .get(&url("/api/header"))
+        .header("X-Owner", OWNER)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.json::<Uuid>().await.unwrap().to_string(), OWNER);
+
+    // invalid UUIDs are rejected, naming the expected format
+    match invalid(client.get(&url("/api/monsters/42"))).await {
+        RuntimeError::RouteParamInvalid {
+            param_name,
+            parse_error,
+        } => {
+            assert_eq!(param_name, "id");
+            assert!(parse_error.starts_with("invalid UUID ("), "{}", parse_error);
+        }
+        other => panic!("unexpected {:?}", other),
+    }
+    match invalid(client.get(&url(&format!("/api/search?owner={}&friend=x", OWNER)))).await {
+        RuntimeError::QueryInvalid(message) => {
+            assert!(
+                message.starts_with("query parameter `friend`: invalid UUID ("),
+                "{}",
+                message
+            );
+        }
+        other => panic!("unexpected {:?}", other),
+    }
+    // struct queries are deserialized by serde, whose UUID errors are fine as they are
+    match invalid(client.get(&url("/api/filter?owner=x"))).await {
+        RuntimeError::QueryInvalid(message) => assert!(message.contains("UUID"), "{}", message),
+        other => panic!("unexpected {:?}", other),
+    }
+    match invalid(client.get(&url("/api/header")).header("X-Owner", "x")).await {
+        RuntimeError::HeaderInvalid { parse_error, .. } => {
+            assert!(parse_error.starts_with("invalid UUID ("), "{}", parse_error);
+        }
+        other => panic!("unexpected {:?}", other),
+    }
+}
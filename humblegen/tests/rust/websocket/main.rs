@@ -0,0 +1,80 @@
+mod server {
+    include!("spec.rs");
+}
+
+use humblegen_rt::futures::{self, StreamExt};
+use humblegen_rt::tokio_tungstenite::{connect_async, tungstenite::Message};
+use std::sync::Arc;
+
+fn dragon_sighted() -> server::Event {
+    server::Event {
+        id: 1,
+        message: "A dragon was sighted".to_owned(),
+    }
+}
+
+struct EventService;
+
+#[humblegen_rt::async_trait(Sync)]
+impl server::Events for EventService {
+    type Context = ();
+
+    async fn get_events(&self, _ctx: Self::Context) -> server::Response<Vec<server::Event>> {
+        Ok(vec![dragon_sighted()])
+    }
+
+    async fn ws_events(
+        &self,
+        _ctx: Self::Context,
+    ) -> server::Response<server::WebSocketResponse<server::Event>> {
+        Ok(server::WebSocketResponse::new(futures::stream::iter(vec![
+            dragon_sighted(),
+        ])))
+    }
+}
+
+#[tokio::main]
+async fn main() {
+    let addr = std::net::TcpListener::bind("127.0.0.1:0")
+        .and_then(|listener| listener.local_addr())
+        .expect("find free port");
+
+    tokio::spawn(async move {
+        server::Builder::new()
+            .add("/api", server::Handler::Events(Arc::new(EventService)))
+            .listen_and_run_forever(&addr)
+            .await
+            .expect("run server")
+    });
+
+    // plain requests to the same path are dispatched to the `GET` endpoint
+    let url = format!("http://{}/api/events", addr);
+    let response = loop {
+        match humblegen_rt::reqwest::get(&url).await {
+            Ok(response) => break response,
+            Err(_) => tokio::time::delay_for(std::time::Duration::from_millis(10)).await,
+        }
+    };
+    assert_eq!(response.status(), 200);
+    let events: Vec<server::Event> = response.json().await.unwrap();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].message, dragon_sighted().message);
+
+    // upgrade requests are dispatched to the `WS` endpoint, which sends JSON text messages
+    let (mut socket, response) = connect_async(format!("ws://{}/api/events", addr))
+        .await
+        .expect("connect WebSocket");
+    assert_eq!(response.status(), 101);
+    let event: server::Event = match socket.next().await {
+        Some(Ok(Message::Text(text))) => serde_json::from_str(&text).unwrap(),
+        other => panic!("expected a text message, got {:?}", other),
+    };
+    assert_eq!(event.id, 1);
+    assert_eq!(event.message, dragon_sighted().message);
+
+    // the server closes the connection once the handler's stream ends
+    match socket.next().await {
+        Some(Ok(Message::Close(_))) | None => {}
+        other => panic!("expected the WebSocket to be closed, got {:?}", other),
+    }
+}
@@ -0,0 +1,90 @@
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[doc = "An identifier too large for a JSON number."]
+pub struct Snowflake {
+    #[doc = ""]
+    #[serde(with = "::humblegen_rt::serialization_helpers::int_string")]
+    pub id: u128,
+    #[doc = ""]
+    #[serde(with = "::humblegen_rt::serialization_helpers::optional_int_string")]
+    #[serde(default)]
+    pub parent: Option<u128>,
+    #[doc = ""]
+    #[serde(with = "::humblegen_rt::serialization_helpers::int_string")]
+    pub offset: i128,
+}
+#[allow(unused_imports)]
+use ::humblegen_rt::client::{
+    build_url, parse_base_url, send_raw_request, send_request, send_result_request,
+    send_stream_request, ser_query_params, ser_query_primitive, ser_query_serde_urlencoded,
+};
+#[allow(unused_imports)]
+pub use ::humblegen_rt::client::{ClientError, RawResponse, StreamResponse};
+#[allow(unused_imports)]
+use ::humblegen_rt::reqwest;
+#[doc = "Client for the `Snowflakes` service. Requires the `client` feature of `humblegen_rt`."]
+#[doc = ""]
+#[doc = ""]
+#[derive(Debug, Clone)]
+pub struct SnowflakesClient {
+    base_url: reqwest::Url,
+    http_client: reqwest::Client,
+}
+#[allow(deprecated)]
+impl SnowflakesClient {
+    #[doc = r" Creates a client for the service mounted at `base_url`, e.g. `http://localhost:3000/api`."]
+    pub fn new(base_url: &str) -> Result<Self, ClientError> {
+        Self::with_http_client(base_url, reqwest::Client::new())
+    }
+    #[doc = r" Creates a client that sends its requests using the given `http_client`."]
+    pub fn with_http_client(
+        base_url: &str,
+        http_client: reqwest::Client,
+    ) -> Result<Self, ClientError> {
+        Ok(Self {
+            base_url: parse_base_url(base_url)?,
+            http_client,
+        })
+    }
+    #[doc = ""]
+    pub async fn get_snowflakes_id(
+        &self,
+        offset: &i128,
+        id: &u128,
+    ) -> Result<Snowflake, ClientError> {
+        let url = build_url(
+            &self.base_url,
+            &["snowflakes".to_owned(), id.to_string()],
+            ser_query_params(&[("offset", Some(offset.to_string()))])?,
+        );
+        let request = self
+            .http_client
+            .request(::humblegen_rt::hyper::Method::GET, url);
+        send_request(request).await
+    }
+    #[doc = ""]
+    pub async fn post_snowflakes(&self, post_body: &Snowflake) -> Result<Snowflake, ClientError> {
+        let url = build_url(&self.base_url, &["snowflakes".to_owned()], None);
+        let request = self
+            .http_client
+            .request(::humblegen_rt::hyper::Method::POST, url);
+        let request = request.json(post_body);
+        send_request(request).await
+    }
+}
+#[doc = "Paths of the routes of the `Snowflakes` service, relative to the root it is mounted at."]
+#[allow(dead_code)]
+pub mod snowflakes_paths {
+    #[allow(unused_imports)]
+    use super::*;
+    #[doc = "The path `/snowflakes/{id}`."]
+    pub fn snowflakes_id_path(id: &u128) -> String {
+        ::humblegen_rt::serialization_helpers::build_path(&[
+            "snowflakes".to_owned(),
+            id.to_string(),
+        ])
+    }
+    #[doc = "The path `/snowflakes`."]
+    pub fn snowflakes_path() -> String {
+        ::humblegen_rt::serialization_helpers::build_path(&["snowflakes".to_owned()])
+    }
+}
@@ -0,0 +1,113 @@
+mod server {
+    include!("spec.rs");
+}
+mod client {
+    include!("client.rs");
+}
+
+struct SnowflakesService;
+
+#[humblegen_rt::async_trait(Sync)]
+impl server::Snowflakes for SnowflakesService {
+    type Context = ();
+
+    async fn get_snowflakes_id(
+        &self,
+        _ctx: Self::Context,
+        offset: i128,
+        id: u128,
+    ) -> server::Response<server::Snowflake> {
+        Ok(server::Snowflake {
+            id,
+            parent: id.checked_sub(1),
+            offset,
+        })
+    }
+
+    async fn post_snowflakes(
+        &self,
+        _ctx: Self::Context,
+        post_body: server::Snowflake,
+    ) -> server::Response<server::Snowflake> {
+        Ok(post_body)
+    }
+}
+
+#[tokio::main]
+async fn main() {
+    // 128-bit integers are encoded as decimal strings
+    let snowflake = server::Snowflake {
+        id: u128::MAX,
+        parent: None,
+        offset: i128::MIN,
+    };
+    let json = serde_json::to_value(&snowflake).unwrap();
+    assert_eq!(
+        json,
+        serde_json::json!({
+            "id": "340282366920938463463374607431768211455",
+            "parent": null,
+            "offset": "-170141183460469231731687303715884105728",
+        })
+    );
+    let decoded: server::Snowflake = serde_json::from_value(json).unwrap();
+    assert_eq!(decoded.id, u128::MAX);
+    assert_eq!(decoded.offset, i128::MIN);
+
+    // optional fields may be missing, values out of range are rejected
+    let decoded: server::Snowflake =
+        serde_json::from_value(serde_json::json!({ "id": "1", "offset": "-1" })).unwrap();
+    assert_eq!(decoded.parent, None);
+    let err = serde_json::from_value::<server::Snowflake>(serde_json::json!({
+        "id": "340282366920938463463374607431768211456",
+        "offset": "0",
+    }))
+    .unwrap_err();
+    assert!(
+        err.to_string().contains("invalid integer string"),
+        "{}",
+        err
+    );
+    assert!(serde_json::from_value::<server::Snowflake>(
+        serde_json::json!({ "id": 1, "offset": "0" })
+    )
+    .is_err());
+
+    let addr = std::net::TcpListener::bind("127.0.0.1:0")
+        .and_then(|listener| listener.local_addr())
+        .expect("find free port");
+    tokio::spawn(async move {
+        server::Builder::new()
+            .add(
+                "/api",
+                server::Handler::Snowflakes(std::sync::Arc::new(SnowflakesService)),
+            )
+            .listen_and_run_forever(&addr)
+            .await
+            .expect("run server")
+    });
+
+    let client = client::SnowflakesClient::new(&format!("http://{}/api", addr)).unwrap();
+    // wait for the server to come up
+    let fetched = loop {
+        match client.get_snowflakes_id(&i128::MIN, &u128::MAX).await {
+            Ok(fetched) => break fetched,
+            Err(_) => tokio::time::delay_for(std::time::Duration::from_millis(10)).await,
+        }
+    };
+    assert_eq!(fetched.id, u128::MAX);
+    assert_eq!(fetched.parent, Some(u128::MAX - 1));
+    assert_eq!(fetched.offset, i128::MIN);
+
+    let posted = client
+        .post_snowflakes(&client::Snowflake {
+            id: u128::MAX - 7,
+            parent: Some(u128::MAX),
+            offset: i128::MAX,
+        })
+        .await
+        .unwrap();
+    assert_eq!(posted.id, u128::MAX - 7);
+    assert_eq!(posted.parent, Some(u128::MAX));
+    assert_eq!(posted.offset, i128::MAX);
+}
@@ -0,0 +1,349 @@
+use humblegen::CodeGenerator;
+
+const SPEC: &str = "./tests/rust/client/spec.humble";
+
+fn generate(artifact: humblegen::Artifact, output: &std::path::Path) {
+    let spec_file = std::fs::File::open(SPEC).expect("open humble spec file");
+    let spec = humblegen::parse(spec_file).expect("parse humble spec file");
+    humblegen::backend::rust::Generator::new(artifact)
+        .expect("failed to init humblegen rust backend")
+        .generate(&spec, output)
+        .expect("humblegen rust backend failed");
+}
+
+#[test]
+fn dash_writes_to_stdout() {
+    let outdir = tempfile::tempdir().expect("create temp dir");
+    let file_output = outdir.path().join("spec.rs");
+    generate(humblegen::Artifact::ServerEndpoints, &file_output);
+
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_humblegen"))
+        .args(["-l", "rust", "-a", "server", "-o", "-", SPEC])
+        .output()
+        .expect("run humblegen");
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        std::fs::read_to_string(&file_output).unwrap()
+    );
+    assert!(!std::path::Path::new("-").exists());
+}
+
+#[test]
+fn directory_output_is_split_into_modules() {
+    for (artifact, services_module) in &[
+        (humblegen::Artifact::ServerEndpoints, "server"),
+        (humblegen::Artifact::ClientEndpoints, "client"),
+    ] {
+        let outdir = tempfile::tempdir().expect("create temp dir");
+        generate(*artifact, outdir.path());
+
+        let read = |name: &str| std::fs::read_to_string(outdir.path().join(name)).unwrap();
+        let module = read("mod.rs");
+        assert!(module.contains("pub mod types;"));
+        assert!(module.contains(&format!("pub mod {};", services_module)));
+        assert!(module.contains(&format!("pub use {}::*;", services_module)));
+
+        assert!(read("types.rs").contains("pub struct Monster {"));
+        let services = read(&format!("{}.rs", services_module));
+        assert!(services.contains("use super::types::*;"));
+        assert!(!services.contains("pub struct Monster {"));
+    }
+}
+
+#[test]
+fn directory_output_compiles() {
+    let outdir = tempfile::tempdir().expect("create temp dir");
+    generate(humblegen::Artifact::ServerEndpoints, outdir.path());
+    let main = outdir.path().join("main.rs");
+    std::fs::write(
+        &main,
+        format!(
+            "#[path = {:?}]\nmod protocol;\n\nfn main() {{\n    let _ = protocol::Builder::new();\n    let _ = protocol::types::MonsterError::TooWeak;\n}}\n",
+            outdir.path().join("mod.rs")
+        ),
+    )
+    .unwrap();
+
+    let t = trybuild::TestCases::new();
+    t.pass(&main);
+}
+
+#[test]
+fn default_enum_tagging_applies_to_untagged_enums() {
+    let spec = "enum Shape { Circle(f32), Square { side: f32 } }\n\
+                enum Color { Red, Green }\n\
+                @tag(\"kind\")\n\
+                enum Event { Start, Stop { reason: str } }\n";
+    let spec = humblegen::parse(spec.as_bytes()).expect("parse humble spec");
+    let outdir = tempfile::tempdir().expect("create temp dir");
+    let output = outdir.path().join("spec.rs");
+    humblegen::backend::rust::Generator::new(humblegen::Artifact::TypesOnly)
+        .expect("failed to init humblegen rust backend")
+        .with_default_enum_tagging(humblegen::ast::EnumTagging::Adjacent {
+            tag: "type".to_owned(),
+            content: "data".to_owned(),
+        })
+        .generate(&spec, &output)
+        .expect("humblegen rust backend failed");
+    let code = std::fs::read_to_string(&output).unwrap();
+
+    // the attributes between the end of the previous item and the enum
+    let attributes = |name: &str| {
+        let end = code.find(&format!("pub enum {} {{", name)).unwrap();
+        let start = code[..end].rfind('}').unwrap_or(0);
+        code[start..end].to_owned()
+    };
+    for name in &["Shape", "Color"] {
+        assert!(
+            attributes(name).contains(r#"#[serde(tag = "type", content = "data")]"#),
+            "{}",
+            attributes(name)
+        );
+    }
+    // an explicit `@tag(...)` takes precedence
+    assert!(attributes("Event").contains(r#"#[serde(tag = "kind")]"#));
+    assert!(!attributes("Event").contains("content"));
+}
+
+#[test]
+fn default_enum_tagging_must_fit_untagged_enums() {
+    let spec = "enum Shape { Circle(f32), Square { side: f32 } }\n";
+    let spec = humblegen::parse(spec.as_bytes()).expect("parse humble spec");
+    let outdir = tempfile::tempdir().expect("create temp dir");
+    let err = humblegen::backend::rust::Generator::new(humblegen::Artifact::TypesOnly)
+        .expect("failed to init humblegen rust backend")
+        .with_default_enum_tagging(humblegen::ast::EnumTagging::Internal {
+            tag: "type".to_owned(),
+        })
+        .generate(&spec, &outdir.path().join("spec.rs"))
+        .expect_err("tuple variants cannot be tagged internally");
+    assert!(
+        matches!(
+            err,
+            humblegen::LibError::InvalidDefaultEnumTagging { ref enum_name, .. } if enum_name == "Shape"
+        ),
+        "{}",
+        err
+    );
+}
+
+#[test]
+fn doc_comments_keep_their_paragraphs() {
+    let spec = "/// A monster.\n///\n/// Monsters roam the dungeon.\nstruct Monster {\n    \
+                /// The name.\n    ///\n    /// Unique per dungeon.\n    name: str,\n}\n";
+    let spec = humblegen::parse(spec.as_bytes()).expect("parse humble spec");
+    let outdir = tempfile::tempdir().expect("create temp dir");
+    let output = outdir.path().join("spec.rs");
+    humblegen::backend::rust::Generator::new(humblegen::Artifact::TypesOnly)
+        .expect("failed to init humblegen rust backend")
+        .generate(&spec, &output)
+        .expect("humblegen rust backend failed");
+    let code = std::fs::read_to_string(&output).unwrap();
+    assert!(code.contains(r#"#[doc = "A monster.\n\nMonsters roam the dungeon."]"#));
+    assert!(code.contains(r#"#[doc = "The name.\n\nUnique per dungeon."]"#));
+}
+
+#[test]
+fn api_version_is_a_constant() {
+    let spec = humblegen::parse("@version \"1.4.0\"\nstruct Monster { name: str }\n".as_bytes())
+        .expect("parse humble spec");
+    let outdir = tempfile::tempdir().expect("create temp dir");
+    let output = outdir.path().join("spec.rs");
+    humblegen::backend::rust::Generator::new(humblegen::Artifact::TypesOnly)
+        .expect("failed to init humblegen rust backend")
+        .generate(&spec, &output)
+        .expect("humblegen rust backend failed");
+    let code = std::fs::read_to_string(&output).unwrap();
+    assert!(
+        code.contains(r#"pub const API_VERSION: &str = "1.4.0";"#),
+        "{}",
+        code
+    );
+
+    // without a version, no constant is generated
+    let spec = humblegen::parse("struct Monster { name: str }\n".as_bytes()).expect("parse spec");
+    humblegen::backend::rust::Generator::new(humblegen::Artifact::TypesOnly)
+        .expect("failed to init humblegen rust backend")
+        .generate(&spec, &output)
+        .expect("humblegen rust backend failed");
+    let code = std::fs::read_to_string(&output).unwrap();
+    assert!(!code.contains("API_VERSION"));
+}
+
+#[test]
+fn output_without_rustfmt_is_pretty_printed() {
+    let spec_file = std::fs::File::open(SPEC).expect("open humble spec file");
+    let spec = humblegen::parse(spec_file).expect("parse humble spec file");
+    let outdir = tempfile::tempdir().expect("create temp dir");
+    let output = outdir.path().join("spec.rs");
+    humblegen::backend::rust::Generator::new(humblegen::Artifact::ServerEndpoints)
+        .expect("failed to init humblegen rust backend")
+        .with_rustfmt(false)
+        .generate(&spec, &output)
+        .expect("humblegen rust backend failed");
+    let code = std::fs::read_to_string(&output).unwrap();
+
+    assert!(code.lines().count() > 100, "{}", code);
+    assert!(
+        code.contains("pub struct Monster {\n    #[doc = "),
+        "{}",
+        code
+    );
+    assert!(code.contains("\n        "), "{}", code);
+
+    // only whitespace differs from the unformatted code
+    let unformatted =
+        humblegen::backend::rust::render_spec(&spec, Default::default(), Default::default())
+            .to_string();
+    let strip = |s: &str| s.split_whitespace().collect::<String>();
+    assert_eq!(strip(&code), strip(&unformatted));
+
+    let main = outdir.path().join("main.rs");
+    std::fs::write(
+        &main,
+        format!(
+            "#[path = {:?}]\nmod protocol;\n\nfn main() {{\n    let _ = protocol::Builder::new();\n}}\n",
+            output
+        ),
+    )
+    .unwrap();
+    let t = trybuild::TestCases::new();
+    t.pass(&main);
+}
+
+#[test]
+fn preamble_and_suffix_are_inserted_verbatim() {
+    let spec = humblegen::parse("struct Monster { name: str }\n".as_bytes()).expect("parse spec");
+    let outdir = tempfile::tempdir().expect("create temp dir");
+    let output = outdir.path().join("spec.rs");
+    let preamble = "#![allow(clippy::all)]\n// generated from monsters.humble\nuse std::fmt;\n";
+    humblegen::backend::rust::Generator::new(humblegen::Artifact::TypesOnly)
+        .expect("failed to init humblegen rust backend")
+        .with_preamble(preamble)
+        .with_suffix("pub type Monsters = Vec<Monster>;")
+        .generate(&spec, &output)
+        .expect("humblegen rust backend failed");
+    let code = std::fs::read_to_string(&output).unwrap();
+    assert!(code.starts_with(preamble), "{}", code);
+    assert!(code.contains("pub struct Monster {"), "{}", code);
+    assert!(
+        code.ends_with("}\n\npub type Monsters = Vec<Monster>;\n"),
+        "{}",
+        code
+    );
+
+    let err = humblegen::backend::rust::Generator::new(humblegen::Artifact::TypesOnly)
+        .expect("failed to init humblegen rust backend")
+        .with_preamble("use std::{fmt;")
+        .generate(&spec, &output)
+        .expect_err("unbalanced preamble");
+    assert!(
+        matches!(
+            err,
+            humblegen::LibError::InvalidInjectedCode {
+                position: "preamble",
+                ..
+            }
+        ),
+        "{}",
+        err
+    );
+}
+
+#[test]
+fn round_trip_tests_compile_and_pass() {
+    let spec = "struct Monster {\n\
+                    name: str,\n\
+                    hp: u16 in 10..=100,\n\
+                    level: option[i32] in ..=-1,\n\
+                    born: datetime,\n\
+                    id: uuid,\n\
+                    home: url,\n\
+                    cooldown: duration,\n\
+                    picture: bytes,\n\
+                    gold: decimal,\n\
+                    notes: json,\n\
+                    speed: f32,\n\
+                    tags: set[str],\n\
+                    loot: map[str][u64],\n\
+                    wealth: u128,\n\
+                    position: (i32, i32),\n\
+                    nothing: option[()],\n\
+                    lair: Lair,\n\
+                    minions: list[Monster],\n\
+                }\n\
+                @flatten_embeds\n\
+                struct Lair { .. Location, depth: i8 }\n\
+                struct Location { x: f64, y: f64 }\n\
+                enum Attack { Bite(u8,), Breath(Element, u32), Claw { damage: i64 } }\n\
+                @tag(\"kind\")\n\
+                enum Element { Fire { heat: u32 }, Ice { cold: nullable[i16] } }\n\
+                enum Tree { Node(list[Tree]) }\n";
+    let spec = humblegen::parse(spec.as_bytes()).expect("parse humble spec");
+
+    let project = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("round-trip-tests");
+    std::fs::create_dir_all(project.join("src")).expect("create test project");
+    std::fs::write(
+        project.join("Cargo.toml"),
+        format!(
+            "[package]\nname = \"round-trip-tests\"\nversion = \"0.0.0\"\nedition = \"2018\"\n\n\
+             [dependencies]\nhumblegen-rt = {{ path = {:?} }}\n\
+             serde = {{ version = \"1.0.110\", features = [\"derive\"] }}\n\n\
+             # not part of the humblegen workspace\n[workspace]\n",
+            concat!(env!("CARGO_MANIFEST_DIR"), "/../humblegen-rt")
+        ),
+    )
+    .unwrap();
+    // use the versions of the dependencies the workspace was built with
+    std::fs::copy(
+        concat!(env!("CARGO_MANIFEST_DIR"), "/../Cargo.lock"),
+        project.join("Cargo.lock"),
+    )
+    .expect("copy Cargo.lock");
+
+    let generator = || {
+        humblegen::backend::rust::Generator::new(humblegen::Artifact::TypesOnly)
+            .expect("failed to init humblegen rust backend")
+    };
+    let lib = project.join("src").join("lib.rs");
+    generator()
+        .generate(&spec, &lib)
+        .expect("humblegen rust backend failed");
+    assert!(!std::fs::read_to_string(&lib)
+        .unwrap()
+        .contains("round_trip_tests"));
+    generator()
+        .with_round_trip_tests(true)
+        .generate(&spec, &lib)
+        .expect("humblegen rust backend failed");
+
+    let cargo = std::env::var_os("CARGO").unwrap_or_else(|| "cargo".into());
+    let output = std::process::Command::new(cargo)
+        .arg("test")
+        .current_dir(&project)
+        .env("CARGO_TARGET_DIR", project.join("target"))
+        .output()
+        .expect("run cargo test");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "{}\n{}",
+        stdout,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    for test in &[
+        "monster_round_trips",
+        "lair_round_trips",
+        "location_round_trips",
+        "attack_round_trips",
+        "element_round_trips",
+        "tree_round_trips",
+    ] {
+        assert!(
+            stdout.contains(&format!("test round_trip_tests::{} ... ok", test)),
+            "{}",
+            stdout
+        );
+    }
+}
@@ -0,0 +1,83 @@
+const SPEC: &str = "struct Monster {
+    id: i32,
+    .. MonsterData,
+}
+
+struct MonsterData {
+    name: str,
+    hp: i32,
+}
+
+enum MonsterError {
+    TooWeak,
+    TooStrong { max_strength: i32 },
+}
+
+service Monsters {
+    GET /monsters -> list[Monster],
+    GET /monsters/{id: i32} -> result[Monster][MonsterError],
+    POST /monsters -> MonsterData -> Monster,
+    DELETE /monsters/{id: i32} -> (),
+    WS /monsters/events -> Monster,
+}
+
+service Health {
+    GET /health -> str,
+}
+";
+
+#[test]
+fn stats_count_types_endpoints_and_fields() {
+    let spec = humblegen::parse(SPEC.as_bytes()).expect("parse spec");
+    let stats = humblegen::stats::stats(&spec);
+
+    assert_eq!(stats.structs, 2);
+    assert_eq!(stats.enums, 1);
+    assert_eq!(stats.services, 2);
+    assert_eq!(stats.endpoint_count(), 6);
+    let endpoints: Vec<_> = stats
+        .endpoints
+        .iter()
+        .map(|(method, count)| (method.as_str(), *count))
+        .collect();
+    assert_eq!(
+        endpoints,
+        vec![("DELETE", 1), ("GET", 3), ("POST", 1), ("WS", 1)]
+    );
+    // the embedded fields of `Monster` and the field of the struct variant count as well
+    assert_eq!(stats.fields, 6);
+
+    assert_eq!(
+        stats.to_string(),
+        "structs: 2\nenums: 1\nservices: 2\nendpoints: 6\n  DELETE: 1\n  GET: 3\n  POST: 1\n  \
+         WS: 1\nfields: 6\n"
+    );
+}
+
+#[test]
+fn stats_cli_prints_json() {
+    let dir = tempfile::tempdir().expect("create temp dir");
+    let input = dir.path().join("spec.humble");
+    std::fs::write(&input, SPEC).unwrap();
+
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_humblegen"))
+        .args(["--stats", "--stats-format", "json"])
+        .arg(&input)
+        .output()
+        .expect("run humblegen");
+    assert!(output.status.success());
+    let stats: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(
+        stats,
+        serde_json::json!({
+            "structs": 2,
+            "enums": 1,
+            "services": 2,
+            "endpoints": {
+                "total": 6,
+                "by_method": { "DELETE": 1, "GET": 3, "POST": 1, "WS": 1 },
+            },
+            "fields": 6,
+        })
+    );
+}
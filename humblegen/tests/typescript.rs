@@ -0,0 +1,110 @@
+use humblegen::CodeGenerator;
+
+/// Generate the TypeScript declarations for the given spec.
+fn generate(spec: &str, generator: humblegen::backend::typescript::Generator) -> String {
+    let spec = humblegen::parse(spec.as_bytes()).expect("parse humble spec");
+    let outdir = tempfile::tempdir().expect("create temp dir");
+    let output = outdir.path().join("protocol.ts");
+    generator
+        .generate(&spec, &output)
+        .expect("humblegen typescript backend failed");
+    std::fs::read_to_string(output).unwrap()
+}
+
+#[test]
+fn structs_become_interfaces() {
+    let ts = generate(
+        "/// A monster.\n\
+         @rename_all(\"kebab-case\")\n\
+         struct Monster {\n\
+             id: uuid,\n\
+             born: datetime,\n\
+             nickname: option[str],\n\
+             owner: nullable[str],\n\
+             hp: i32 = 100,\n\
+             loot: map[str][list[u8]],\n\
+             position: (f32, f32),\n\
+             fight: result[str][MonsterError],\n\
+         }\n\
+         enum MonsterError { TooWeak }\n\
+         service Monsters { GET /monsters -> list[Monster] }\n",
+        Default::default(),
+    );
+
+    assert!(
+        ts.contains(
+            "/**\n * A monster.\n */\nexport interface Monster {\n    \
+             id: string;\n    \
+             born: string;\n    \
+             nickname?: string | null;\n    \
+             owner: string | null;\n    \
+             hp?: number;\n    \
+             loot: Record<string, Array<number>>;\n    \
+             position: [number, number];\n    \
+             fight: { Ok: string } | { Err: MonsterError };\n\
+             }\n"
+        ),
+        "{}",
+        ts
+    );
+    assert!(!ts.contains("Monsters"), "{}", ts);
+}
+
+#[test]
+fn enums_become_discriminated_unions() {
+    let ts = generate(
+        "enum Shape {\n    Point,\n    Circle(f64),\n    Rect(f64, f64),\n    Poly { sides: u8 },\n}\n\
+         @tag(\"type\")\n\
+         enum RecruitError {\n    TooStrong { power: i32 },\n    Asleep,\n}\n\
+         @tag(\"kind\", content = \"data\")\n\
+         enum Event {\n    Started,\n    Moved(i32, i32),\n}\n",
+        Default::default(),
+    );
+
+    assert!(
+        ts.contains(
+            "export type Shape =\n    \
+             | \"Point\"\n    \
+             | { Circle: number }\n    \
+             | { Rect: [number, number] }\n    \
+             | { Poly: {\n        sides: number;\n    } };\n"
+        ),
+        "{}",
+        ts
+    );
+    assert!(
+        ts.contains(
+            "export type RecruitError =\n    \
+             | {\n        type: \"TooStrong\";\n        power: number;\n    }\n    \
+             | { type: \"Asleep\" };\n"
+        ),
+        "{}",
+        ts
+    );
+    assert!(
+        ts.contains(
+            "export type Event =\n    \
+             | { kind: \"Started\" }\n    \
+             | { kind: \"Moved\"; data: [number, number] };\n"
+        ),
+        "{}",
+        ts
+    );
+}
+
+#[test]
+fn default_enum_tagging_applies_to_untagged_enums() {
+    let ts = generate(
+        "enum Color {\n    Red,\n    Green,\n}\n",
+        humblegen::backend::typescript::Generator::default().with_default_enum_tagging(
+            humblegen::ast::EnumTagging::Internal {
+                tag: "color".to_owned(),
+            },
+        ),
+    );
+    assert!(
+        ts.contains("export type Color =\n    | { color: \"Red\" }\n    | { color: \"Green\" };\n"),
+        "{}",
+        ts
+    );
+}